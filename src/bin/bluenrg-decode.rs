@@ -0,0 +1,545 @@
+//! Prints decoded BlueNRG HCI commands and events.
+//!
+//! ```text
+//! bluenrg-decode [HEX]...
+//! bluenrg-decode --file CAPTURE
+//! bluenrg-decode --crash [HEX]...
+//! ```
+//!
+//! Each `HEX` argument is one packet, starting with its H4 packet indicator: `01` for commands,
+//! `02` for ACL data, and `04` for events. Bytes may be separated by spaces, colons, commas, or
+//! dashes, and may have a `0x` prefix. Each group of digits must contain whole bytes, so `abc def`
+//! is rejected rather than read as `ab cd ef`. With no arguments, packets are read from standard
+//! input, one per line; empty lines and lines starting with `#` are skipped.
+//!
+//! `CAPTURE` may be a btsnoop file (with the H1 or H4 data link type) or a raw H4 stream.
+//!
+//! Vendor-specific commands are named from the opcode table and decoded with
+//! [`VendorCommand::new`]. Events are decoded as HCI events with [`BlueNRGEvent`] as the vendor
+//! event type, so vendor events and the return parameters of vendor commands are decoded as well.
+//! Attribute values and other payloads of vendor events are printed in full on the following
+//! lines, since the decoded event only shows their first 16 bytes.
+//!
+//! With `--crash`, each `HEX` argument (or line of standard input) is a crash record saved by
+//! [`bluenrg::crash::Monitor`]. The stacked registers are printed with an analysis of PC, LR, and
+//! xPSR, along with the debug data, ready to be attached to a report.
+
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+
+use bluenrg::decode::{opcode_name, VendorCommand};
+use bluenrg::event::{BlueNRGEvent, GapProcedure};
+use std::io::{self, BufRead};
+use std::{env, fmt, fs, process};
+
+const COMMAND_PACKET: u8 = 0x01;
+const ACL_PACKET: u8 = 0x02;
+const EVENT_PACKET: u8 = 0x04;
+
+const VENDOR_OGF: u16 = 0x3F;
+
+const BTSNOOP_MAGIC: &[u8] = b"btsnoop\0";
+const BTSNOOP_HEADER_LEN: usize = 16;
+const BTSNOOP_RECORD_HEADER_LEN: usize = 24;
+const BTSNOOP_H1: u32 = 1001;
+const BTSNOOP_H4: u32 = 1002;
+
+const USAGE: &str = "\
+usage: bluenrg-decode [HEX]...
+       bluenrg-decode --file CAPTURE
+       bluenrg-decode --crash [HEX]...
+
+Each HEX argument is one packet, starting with its H4 packet indicator (01 for
+commands, 02 for ACL data, 04 for events). With no arguments, packets are read
+from standard input, one per line. CAPTURE may be a btsnoop file or a raw H4
+stream. With --crash, each HEX argument or line is a saved crash record.
+";
+
+#[derive(Debug)]
+enum Error {
+    Usage,
+    Io(io::Error),
+    BadHex(String),
+    OddHexToken(String),
+    Truncated(usize),
+    UnknownPacketType(u8),
+    UnsupportedDataLink(u32),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Error::Usage => f.write_str(USAGE.trim_end()),
+            Error::Io(e) => write!(f, "{}", e),
+            Error::BadHex(s) => write!(f, "not a hex packet: {:?}", s),
+            Error::OddHexToken(token) => {
+                write!(f, "odd number of hex digits in {:?}", token)
+            }
+            Error::Truncated(offset) => write!(f, "capture is truncated at offset {}", offset),
+            Error::UnknownPacketType(t) => write!(f, "unknown packet type {:#04x}", t),
+            Error::UnsupportedDataLink(link) => {
+                write!(f, "unsupported btsnoop data link type {}", link)
+            }
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(e: io::Error) -> Self {
+        Error::Io(e)
+    }
+}
+
+fn main() {
+    let args: Vec<String> = env::args().skip(1).collect();
+    if let Err(e) = run(&args) {
+        eprintln!("bluenrg-decode: {}", e);
+        process::exit(1);
+    }
+}
+
+fn run(args: &[String]) -> Result<(), Error> {
+    match args.first().map(String::as_str) {
+        Some("-h") | Some("--help") => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Some("-f") | Some("--file") => {
+            if args.len() != 2 {
+                return Err(Error::Usage);
+            }
+            decode_capture(&fs::read(&args[1])?)
+        }
+        Some("--crash") => decode_hex(&args[1..], print_crash_record),
+        _ => decode_hex(args, print_packet),
+    }
+}
+
+// Prints each hex argument, or each line of standard input if there are no arguments.
+fn decode_hex(args: &[String], print: fn(&[u8])) -> Result<(), Error> {
+    if !args.is_empty() {
+        for arg in args {
+            print(&parse_hex(arg)?);
+        }
+        return Ok(());
+    }
+
+    let stdin = io::stdin();
+    for line in stdin.lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match parse_hex(line) {
+            Ok(bytes) => print(&bytes),
+            Err(e) => println!("error: {}", e),
+        }
+    }
+    Ok(())
+}
+
+fn parse_hex(s: &str) -> Result<Vec<u8>, Error> {
+    let mut bytes = Vec::with_capacity(s.len() / 2);
+    for token in s.split(|c: char| c.is_whitespace() || c == ':' || c == ',' || c == '-') {
+        let digits = token
+            .strip_prefix("0x")
+            .or_else(|| token.strip_prefix("0X"))
+            .unwrap_or(token);
+        // Each token must be a whole number of bytes; otherwise every byte after it would shift.
+        if digits.len() % 2 != 0 {
+            return Err(Error::OddHexToken(token.to_string()));
+        }
+        for byte in digits.as_bytes().chunks(2) {
+            let byte = std::str::from_utf8(byte)
+                .ok()
+                .and_then(|byte| u8::from_str_radix(byte, 16).ok())
+                .ok_or_else(|| Error::BadHex(s.to_string()))?;
+            bytes.push(byte);
+        }
+    }
+
+    if bytes.is_empty() {
+        return Err(Error::BadHex(s.to_string()));
+    }
+    Ok(bytes)
+}
+
+fn decode_capture(data: &[u8]) -> Result<(), Error> {
+    if data.starts_with(BTSNOOP_MAGIC) {
+        decode_btsnoop(data)
+    } else {
+        decode_h4_stream(data)
+    }
+}
+
+fn decode_btsnoop(data: &[u8]) -> Result<(), Error> {
+    if data.len() < BTSNOOP_HEADER_LEN {
+        return Err(Error::Truncated(data.len()));
+    }
+    let data_link = be_u32(&data[12..]);
+    if data_link != BTSNOOP_H1 && data_link != BTSNOOP_H4 {
+        return Err(Error::UnsupportedDataLink(data_link));
+    }
+
+    let mut first_timestamp = None;
+    let mut offset = BTSNOOP_HEADER_LEN;
+    while offset < data.len() {
+        if data.len() - offset < BTSNOOP_RECORD_HEADER_LEN {
+            return Err(Error::Truncated(offset));
+        }
+        let header = &data[offset..offset + BTSNOOP_RECORD_HEADER_LEN];
+        let included_len = be_u32(&header[4..]) as usize;
+        let flags = be_u32(&header[8..]);
+        let timestamp = be_u64(&header[16..]);
+
+        let start = offset + BTSNOOP_RECORD_HEADER_LEN;
+        let end = start
+            .checked_add(included_len)
+            .filter(|&end| end <= data.len())
+            .ok_or(Error::Truncated(offset))?;
+        let record = &data[start..end];
+
+        // Timestamps are in microseconds; print them relative to the first record.
+        let elapsed = timestamp.wrapping_sub(*first_timestamp.get_or_insert(timestamp));
+        let direction = if flags & 0b01 == 0 {
+            "sent"
+        } else {
+            "received"
+        };
+        print!(
+            "[{:4}.{:06}] {:8} ",
+            elapsed / 1_000_000,
+            elapsed % 1_000_000,
+            direction
+        );
+
+        if data_link == BTSNOOP_H4 {
+            print_packet(record);
+        } else {
+            // H1 records have no packet indicator; the flags say whether the record is a command
+            // or an event.
+            let packet_type = match flags & 0b11 {
+                0b10 => COMMAND_PACKET,
+                0b11 => EVENT_PACKET,
+                _ => ACL_PACKET,
+            };
+            print_typed_packet(packet_type, record);
+        }
+
+        offset = end;
+    }
+
+    Ok(())
+}
+
+fn decode_h4_stream(data: &[u8]) -> Result<(), Error> {
+    let mut offset = 0;
+    while offset < data.len() {
+        let len = h4_packet_len(&data[offset..]).ok_or(Error::Truncated(offset))??;
+        print_packet(&data[offset..offset + len]);
+        offset += len;
+    }
+
+    Ok(())
+}
+
+// Returns the full length of the H4 packet at the start of `data`, including the packet indicator,
+// or `None` if `data` does not contain the whole packet.
+fn h4_packet_len(data: &[u8]) -> Option<Result<usize, Error>> {
+    let len = match data[0] {
+        COMMAND_PACKET => 4 + *data.get(3)? as usize,
+        ACL_PACKET => 5 + le_u16(data.get(3..5)?) as usize,
+        EVENT_PACKET => 3 + *data.get(2)? as usize,
+        other => return Some(Err(Error::UnknownPacketType(other))),
+    };
+    if len > data.len() {
+        return None;
+    }
+
+    Some(Ok(len))
+}
+
+fn print_packet(packet: &[u8]) {
+    match packet.split_first() {
+        Some((&packet_type, body)) => print_typed_packet(packet_type, body),
+        None => println!("empty packet"),
+    }
+}
+
+fn print_typed_packet(packet_type: u8, body: &[u8]) {
+    match packet_type {
+        COMMAND_PACKET => print_command(body),
+        ACL_PACKET => print_acl_data(body),
+        EVENT_PACKET => print_event(body),
+        other => println!("unknown packet type {:#04x}: {}", other, Hex(body)),
+    }
+}
+
+fn print_command(body: &[u8]) {
+    if body.len() < 3 {
+        println!("CMD truncated command: {}", Hex(body));
+        return;
+    }
+
+    let opcode = hci::Opcode(le_u16(body));
+    let params = &body[3..];
+    if params.len() != body[2] as usize {
+        println!(
+            "CMD {:#06x} parameter length {} does not match header length {}: {}",
+            opcode.0,
+            params.len(),
+            body[2],
+            Hex(params)
+        );
+        return;
+    }
+
+    if opcode.ogf() != VENDOR_OGF {
+        println!(
+            "CMD {:#06x} (OGF {:#04x}, OCF {:#05x}): {}",
+            opcode.0,
+            opcode.ogf(),
+            opcode.ocf(),
+            Hex(params)
+        );
+        return;
+    }
+
+    let name = opcode_name(opcode).unwrap_or("unknown vendor command");
+    match VendorCommand::new(opcode, params) {
+        Ok(command) => println!("CMD {:#06x} {}: {:?}", opcode.0, name, command),
+        Err(e) => println!(
+            "CMD {:#06x} {}: error {:?}: {}",
+            opcode.0,
+            name,
+            e,
+            Hex(params)
+        ),
+    }
+}
+
+fn print_acl_data(body: &[u8]) {
+    if body.len() < 4 {
+        println!("ACL truncated data: {}", Hex(body));
+        return;
+    }
+
+    println!(
+        "ACL handle {:#06x}, {} bytes: {}",
+        le_u16(body) & 0x0FFF,
+        le_u16(&body[2..]),
+        Hex(&body[4..])
+    );
+}
+
+fn print_event(body: &[u8]) {
+    match hci::event::Event::<BlueNRGEvent>::new(hci::event::Packet(body)) {
+        Ok(event) => {
+            println!("EVT {:?}", event);
+            if let hci::event::Event::Vendor(event) = &event {
+                print_event_payloads(event);
+            }
+        }
+        Err(e) => println!("EVT error {:?}: {}", e, Hex(body)),
+    }
+}
+
+// The Debug output of vendor events shows only the first 16 bytes of their values and data, so the
+// payloads are printed again in full.
+fn print_event_payloads(event: &BlueNRGEvent) {
+    match event {
+        BlueNRGEvent::GapProcedureComplete(complete) => {
+            if let GapProcedure::NameDiscovery(len, name) = &complete.procedure {
+                print_payload("name", &name.0[..*len]);
+            }
+        }
+        BlueNRGEvent::GattAttributeModified(modified) => print_payload("data", modified.data()),
+        BlueNRGEvent::AttReadByTypeResponse(response) => {
+            for pair in response.handle_value_pair_iter() {
+                print_payload(&format!("handle {:#06x} value", pair.handle.0), pair.value);
+            }
+        }
+        BlueNRGEvent::AttReadResponse(response)
+        | BlueNRGEvent::AttReadBlobResponse(response)
+        | BlueNRGEvent::AttReadMultipleResponse(response) => {
+            print_payload("value", response.value())
+        }
+        BlueNRGEvent::AttReadByGroupTypeResponse(response) => {
+            for data in response.attribute_data_iter() {
+                print_payload(
+                    &format!("handle {:#06x} value", data.attribute_handle.0),
+                    data.value,
+                );
+            }
+        }
+        BlueNRGEvent::AttPrepareWriteResponse(response) => print_payload("value", response.value()),
+        BlueNRGEvent::GattIndication(value)
+        | BlueNRGEvent::GattNotification(value)
+        | BlueNRGEvent::GattDiscoverOrReadCharacteristicByUuidResponse(value)
+        | BlueNRGEvent::AttWritePermitRequest(value) => print_payload("value", value.value()),
+        BlueNRGEvent::AttReadMultiplePermitRequest(request) => {
+            let handles: Vec<String> = request
+                .handles()
+                .iter()
+                .map(|handle| format!("{:#06x}", handle.0))
+                .collect();
+            println!("  handles ({}): {}", handles.len(), handles.join(" "));
+        }
+        #[cfg(feature = "ms")]
+        BlueNRGEvent::AttPrepareWritePermitRequest(request) => {
+            print_payload("value", request.value())
+        }
+        BlueNRGEvent::Unknown { data, .. } => print_payload("data", data.as_slice()),
+        _ => (),
+    }
+}
+
+fn print_payload(name: &str, bytes: &[u8]) {
+    println!("  {} ({} bytes): {}", name, bytes.len(), Hex(bytes));
+}
+
+#[cfg(feature = "ms")]
+fn print_crash_record(bytes: &[u8]) {
+    use bluenrg::crash::{ExceptionReturn, Record, Region};
+
+    let record = match Record::from_bytes(bytes) {
+        Ok(record) => record,
+        Err(e) => {
+            println!("CRASH error {:?}: {}", e, Hex(bytes));
+            return;
+        }
+    };
+    let registers = &record.registers;
+    println!(
+        "CRASH #{} {:?} after {} reset",
+        record.sequence,
+        record.crash_reason,
+        record
+            .reset_reason
+            .map_or_else(|| "unknown".to_string(), |reason| format!("{:?}", reason))
+    );
+    println!(
+        "  r0  {:#010x}  r1  {:#010x}  r2  {:#010x}  r3 {:#010x}",
+        registers.r0, registers.r1, registers.r2, registers.r3
+    );
+    println!(
+        "  r12 {:#010x}  sp  {:#010x} ({:?})",
+        registers.r12,
+        registers.sp,
+        Region::of(registers.sp)
+    );
+
+    println!(
+        "  pc  {:#010x} ({:?})",
+        registers.pc,
+        Region::of(registers.pc)
+    );
+    if Region::of(registers.pc) != Region::Code {
+        println!("      warning: PC is outside of code; the core may have jumped to a bad address");
+    }
+
+    match registers.exception_return() {
+        Some(ret) => println!(
+            "  lr  {:#010x} (EXC_RETURN: {})",
+            registers.lr,
+            match ret {
+                ExceptionReturn::Handler => "return to handler mode, main stack",
+                ExceptionReturn::ThreadMainStack => "return to thread mode, main stack",
+                ExceptionReturn::ThreadProcessStack => "return to thread mode, process stack",
+            }
+        ),
+        None => println!(
+            "  lr  {:#010x} (caller near {:#010x}, {:?})",
+            registers.lr,
+            registers.lr & !1,
+            Region::of(registers.lr)
+        ),
+    }
+
+    let flags: String = [(31, 'N'), (30, 'Z'), (29, 'C'), (28, 'V')]
+        .iter()
+        .map(|&(bit, flag)| {
+            if registers.xpsr & (1 << bit) != 0 {
+                flag
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    println!(
+        "  xpsr {:#010x} (flags {}, {}, {})",
+        registers.xpsr,
+        flags,
+        exception_name(registers.exception_number()),
+        if registers.is_thumb() {
+            "Thumb"
+        } else {
+            "Thumb bit clear"
+        }
+    );
+    if !registers.is_thumb() {
+        println!(
+            "      warning: the core left Thumb state, usually by branching to an even address"
+        );
+    }
+
+    println!(
+        "  debug data ({} bytes): {}",
+        record.debug_data.len(),
+        Hex(record.debug_data)
+    );
+}
+
+#[cfg(not(feature = "ms"))]
+fn print_crash_record(bytes: &[u8]) {
+    println!("CRASH crash reports require the BlueNRG-MS: {}", Hex(bytes));
+}
+
+#[cfg(feature = "ms")]
+fn exception_name(number: u16) -> String {
+    match number {
+        0 => "thread mode".to_string(),
+        1 => "Reset".to_string(),
+        2 => "NMI".to_string(),
+        3 => "HardFault".to_string(),
+        4 => "MemManage".to_string(),
+        5 => "BusFault".to_string(),
+        6 => "UsageFault".to_string(),
+        11 => "SVCall".to_string(),
+        12 => "DebugMonitor".to_string(),
+        14 => "PendSV".to_string(),
+        15 => "SysTick".to_string(),
+        n if n >= 16 => format!("IRQ {}", n - 16),
+        n => format!("reserved exception {}", n),
+    }
+}
+
+fn le_u16(bytes: &[u8]) -> u16 {
+    u16::from_le_bytes([bytes[0], bytes[1]])
+}
+
+fn be_u32(bytes: &[u8]) -> u32 {
+    u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]])
+}
+
+fn be_u64(bytes: &[u8]) -> u64 {
+    let mut buf = [0; 8];
+    buf.copy_from_slice(&bytes[..8]);
+    u64::from_be_bytes(buf)
+}
+
+struct Hex<'a>(&'a [u8]);
+
+impl fmt::Display for Hex<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for (i, byte) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" ")?;
+            }
+            write!(f, "{:02x}", byte)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,3017 @@
+//! Decoding of serialized vendor-specific commands.
+//!
+//! The command traits in [`gap`](crate::gap), [`gatt`](crate::gatt), [`hal`](crate::hal),
+//! [`l2cap`](crate::l2cap), and [`updater`](crate::updater) serialize their parameters before
+//! writing them to the controller.
+//! [`VendorCommand::new`] reverses that process: given the opcode and the parameter bytes of a
+//! command, it returns the command and its parameters. This is useful for analyzing traces of HCI
+//! traffic, for emulating a controller, and for testing.
+//!
+//! Decoding checks that each serialized value can be represented by the corresponding type, but
+//! does not repeat the other validation that the command methods perform before sending a command.
+//!
+//! With the `ms` feature, commands are decoded in the BlueNRG-MS format, whichever
+//! [variant](crate::Variant) is selected.
+
+extern crate bluetooth_hci as hci;
+extern crate byteorder;
+
+use byteorder::{ByteOrder, LittleEndian};
+use core::convert::TryFrom;
+use core::time::Duration;
+use hci::host::{AdvertisingFilterPolicy, AdvertisingType, OwnAddressType, PeerAddrType, ScanType};
+use hci::types::{
+    AdvertisingIntervalError, ConnectionInterval, ConnectionIntervalError,
+    ExpectedConnectionLength, ExpectedConnectionLengthError, ScanWindow, ScanWindowError,
+};
+use hci::{BdAddr, BdAddrType, ConnectionHandle};
+
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
+use crate::gap;
+use crate::gatt;
+use crate::hal;
+use crate::l2cap;
+
+/// Vendor-specific commands, with their parameters.
+///
+/// Most variants contain the same parameter type that the corresponding command method takes.
+/// Parameters that contain slices of addresses or handles cannot borrow from the serialized
+/// command, so those commands are decoded into separate types that own the lists (for example,
+/// [`AutoConnectionEstablishment`]).
+#[derive(Debug)]
+pub enum VendorCommand<'a> {
+    /// [HAL Get Firmware Revision](crate::hal::Commands::get_firmware_revision)
+    HalGetFirmwareRevision,
+
+    /// [HAL Write Config Data](crate::hal::Commands::write_config_data)
+    HalWriteConfigData(hal::ConfigData),
+
+    /// [HAL Read Config Data](crate::hal::Commands::read_config_data)
+    HalReadConfigData(hal::ConfigParameter),
+
+    /// [HAL Set Tx Power Level](crate::hal::Commands::set_tx_power_level)
+    HalSetTxPowerLevel(hal::PowerLevel),
+
+    /// [HAL Device Standby](crate::hal::Commands::device_standby)
+    HalDeviceStandby,
+
+    /// [HAL Get Tx Test Packet Count](crate::hal::Commands::get_tx_test_packet_count)
+    HalGetTxTestPacketCount,
+
+    /// [HAL Start Tone](crate::hal::Commands::start_tone). Includes the channel.
+    HalStartTone(u8),
+
+    /// [HAL Stop Tone](crate::hal::Commands::stop_tone)
+    HalStopTone,
+
+    /// [HAL Get Link Status](crate::hal::Commands::get_link_status)
+    HalGetLinkStatus,
+
+    /// [HAL Get Anchor Period](crate::hal::Commands::get_anchor_period)
+    HalGetAnchorPeriod,
+
+    /// [HAL Set Radio Activity Mask](crate::hal::Commands::set_radio_activity_mask)
+    #[cfg(feature = "bluenrg-1-2")]
+    HalSetRadioActivityMask(hal::RadioActivityFlags),
+
+    /// [HAL Set Event Mask](crate::hal::Commands::set_hal_event_mask)
+    #[cfg(feature = "bluenrg-1-2")]
+    HalSetEventMask(hal::EventFlags),
+
+    /// [HAL Stack Reset](crate::hal::Commands::stack_reset)
+    #[cfg(feature = "bluenrg-1-2")]
+    HalStackReset,
+
+    /// [GAP Set Nondiscoverable](crate::gap::Commands::set_nondiscoverable)
+    GapSetNonDiscoverable,
+
+    /// [GAP Set Limited Discoverable](crate::gap::Commands::set_limited_discoverable)
+    GapSetLimitedDiscoverable(gap::DiscoverableParameters<'a, 'a>),
+
+    /// [GAP Set Discoverable](crate::gap::Commands::set_discoverable)
+    GapSetDiscoverable(gap::DiscoverableParameters<'a, 'a>),
+
+    /// [GAP Set Direct Connectable](crate::gap::Commands::set_direct_connectable). With the BlueNRG
+    /// variant, which sends neither, the advertising type is high duty cycle directed advertising
+    /// and the advertising interval is empty.
+    GapSetDirectConnectable(gap::DirectConnectableParameters),
+
+    /// [GAP Set IO Capability](crate::gap::Commands::set_io_capability)
+    GapSetIoCapability(gap::IoCapability),
+
+    /// [GAP Set Authentication Requirement](crate::gap::Commands::set_authentication_requirement)
+    GapSetAuthenticationRequirement(gap::AuthenticationRequirements),
+
+    /// [GAP Set Authorization Requirement](crate::gap::Commands::set_authorization_requirement)
+    GapSetAuthorizationRequirement {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// True if authorization is required.
+        authorization_required: bool,
+    },
+
+    /// [GAP Pass Key Response](crate::gap::Commands::pass_key_response)
+    GapPassKeyResponse {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Pass key for the pairing process.
+        pin: u32,
+    },
+
+    /// [GAP Authorization Response](crate::gap::Commands::authorization_response)
+    GapAuthorizationResponse {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Whether the connection is authorized.
+        authorization: gap::Authorization,
+    },
+
+    /// [GAP Init](crate::gap::Commands::init)
+    #[cfg(not(feature = "ms"))]
+    GapInit {
+        /// Role of the device.
+        role: gap::Role,
+    },
+
+    /// [GAP Init](crate::gap::Commands::init)
+    #[cfg(feature = "ms")]
+    GapInit {
+        /// Role of the device.
+        role: gap::Role,
+
+        /// True if privacy is enabled. Always false for the BlueNRG variant, which does not send
+        /// it.
+        privacy_enabled: bool,
+
+        /// Length of the device name characteristic. Always 0 for the BlueNRG variant, which does
+        /// not send it.
+        dev_name_characteristic_len: u8,
+    },
+
+    /// [GAP Set Nonconnectable](crate::gap::Commands::set_nonconnectable)
+    #[cfg(not(feature = "ms"))]
+    GapSetNonConnectable {
+        /// Advertising method.
+        advertising_type: AdvertisingType,
+    },
+
+    /// [GAP Set Nonconnectable](crate::gap::Commands::set_nonconnectable)
+    #[cfg(feature = "ms")]
+    GapSetNonConnectable {
+        /// Advertising method.
+        advertising_type: AdvertisingType,
+
+        /// Type of address used in the advertising packets. Always
+        /// [`Public`](gap::AddressType::Public) for the BlueNRG variant, which does not send it.
+        address_type: gap::AddressType,
+    },
+
+    /// [GAP Set Undirected Connectable](crate::gap::Commands::set_undirected_connectable)
+    GapSetUndirectedConnectable {
+        /// Filter policy for the device.
+        filter_policy: AdvertisingFilterPolicy,
+
+        /// Type of address used in the advertising packets.
+        address_type: gap::AddressType,
+    },
+
+    /// [GAP Peripheral Security Request](crate::gap::Commands::peripheral_security_request)
+    GapPeripheralSecurityRequest(gap::SecurityRequestParameters),
+
+    /// [GAP Update Advertising Data](crate::gap::Commands::update_advertising_data). Includes the
+    /// advertising data.
+    GapUpdateAdvertisingData(&'a [u8]),
+
+    /// [GAP Delete AD Type](crate::gap::Commands::delete_ad_type)
+    GapDeleteAdType(gap::AdvertisingDataType),
+
+    /// [GAP Get Security Level](crate::gap::Commands::get_security_level)
+    GapGetSecurityLevel,
+
+    /// [GAP Set Event Mask](crate::gap::Commands::set_event_mask)
+    GapSetEventMask(gap::EventFlags),
+
+    /// [GAP Configure White List](crate::gap::Commands::configure_white_list)
+    GapConfigureWhiteList,
+
+    /// [GAP Terminate](crate::gap::Commands::terminate)
+    GapTerminate {
+        /// Handle of the connection to terminate.
+        conn_handle: ConnectionHandle,
+
+        /// Reason for terminating the connection.
+        reason: hci::Status<crate::event::Status>,
+    },
+
+    /// [GAP Clear Security Database](crate::gap::Commands::clear_security_database)
+    GapClearSecurityDatabase,
+
+    /// [GAP Allow Rebond](crate::gap::Commands::allow_rebond)
+    #[cfg(not(feature = "ms"))]
+    GapAllowRebond,
+
+    /// [GAP Allow Rebond](crate::gap::Commands::allow_rebond). Includes the connection handle,
+    /// which is always 0 for the BlueNRG variant because it does not send one.
+    #[cfg(feature = "ms")]
+    GapAllowRebond(ConnectionHandle),
+
+    /// [GAP Start Limited Discovery
+    /// Procedure](crate::gap::Commands::start_limited_discovery_procedure)
+    GapStartLimitedDiscoveryProcedure(gap::DiscoveryProcedureParameters),
+
+    /// [GAP Start General Discovery
+    /// Procedure](crate::gap::Commands::start_general_discovery_procedure)
+    GapStartGeneralDiscoveryProcedure(gap::DiscoveryProcedureParameters),
+
+    /// [GAP Start Name Discovery Procedure](crate::gap::Commands::start_name_discovery_procedure)
+    GapStartNameDiscoveryProcedure(gap::NameDiscoveryProcedureParameters),
+
+    /// [GAP Start Auto Connection
+    /// Establishment](crate::gap::Commands::start_auto_connection_establishment)
+    GapStartAutoConnectionEstablishment(AutoConnectionEstablishment),
+
+    /// [GAP Start General Connection
+    /// Establishment](crate::gap::Commands::start_general_connection_establishment)
+    GapStartGeneralConnectionEstablishment(gap::GeneralConnectionEstablishmentParameters),
+
+    /// [GAP Start Selective Connection
+    /// Establishment](crate::gap::Commands::start_selective_connection_establishment)
+    GapStartSelectiveConnectionEstablishment(SelectiveConnectionEstablishment),
+
+    /// [GAP Create Connection](crate::gap::Commands::create_connection)
+    GapCreateConnection(gap::ConnectionParameters),
+
+    /// [GAP Terminate Procedure](crate::gap::Commands::terminate_procedure)
+    GapTerminateProcedure(gap::Procedure),
+
+    /// [GAP Start Connection Update](crate::gap::Commands::start_connection_update)
+    GapStartConnectionUpdate(gap::ConnectionUpdateParameters),
+
+    /// [GAP Send Pairing Request](crate::gap::Commands::send_pairing_request)
+    GapSendPairingRequest(gap::PairingRequest),
+
+    /// [GAP Resolve Private Address](crate::gap::Commands::resolve_private_address). Includes the
+    /// address to resolve.
+    GapResolvePrivateAddress(BdAddr),
+
+    /// [GAP Set Broadcast Mode](crate::gap::Commands::set_broadcast_mode)
+    #[cfg(feature = "ms")]
+    GapSetBroadcastMode(BroadcastMode<'a>),
+
+    /// [GAP Start Observation Procedure](crate::gap::Commands::start_observation_procedure)
+    #[cfg(feature = "ms")]
+    GapStartObservationProcedure(gap::ObservationProcedureParameters),
+
+    /// [GAP Get Bonded Devices](crate::gap::Commands::get_bonded_devices)
+    GapGetBondedDevices,
+
+    /// [GAP Is Device Bonded](crate::gap::Commands::is_device_bonded). Includes the peer address.
+    GapIsDeviceBonded(PeerAddrType),
+
+    /// [GAP Numeric Comparison Value
+    /// Confirm](crate::gap::Commands::numeric_comparison_value_confirm)
+    #[cfg(feature = "bluenrg-1-2")]
+    GapNumericComparisonValueConfirm {
+        /// Connection handle on which pairing is in progress.
+        conn_handle: ConnectionHandle,
+        /// Whether the user confirmed the value.
+        confirm: bool,
+    },
+
+    /// [GAP Passkey Input](crate::gap::Commands::passkey_input)
+    #[cfg(feature = "bluenrg-1-2")]
+    GapPasskeyInput {
+        /// Connection handle on which pairing is in progress.
+        conn_handle: ConnectionHandle,
+        /// Progress of pass key entry.
+        input: gap::KeypressNotification,
+    },
+
+    /// [GAP Remove Bonded Device](crate::gap::Commands::remove_bonded_device). Includes the peer
+    /// address.
+    #[cfg(feature = "bluenrg-1-2")]
+    GapRemoveBondedDevice(PeerAddrType),
+
+    /// [GAP Set LE Event Mask](crate::gap::Commands::set_le_event_mask)
+    #[cfg(feature = "bluenrg-1-2")]
+    GapSetLeEventMask(hci::host::LeEventFlags),
+
+    /// [GATT Init](crate::gatt::Commands::init)
+    GattInit,
+
+    /// [GATT Add Service](crate::gatt::Commands::add_service)
+    GattAddService(gatt::AddServiceParameters),
+
+    /// [GATT Include Service](crate::gatt::Commands::include_service)
+    GattIncludeService(gatt::IncludeServiceParameters),
+
+    /// [GATT Add Characteristic](crate::gatt::Commands::add_characteristic)
+    GattAddCharacteristic(gatt::AddCharacteristicParameters),
+
+    /// [GATT Add Characteristic
+    /// Descriptor](crate::gatt::Commands::add_characteristic_descriptor)
+    GattAddCharacteristicDescriptor(gatt::AddDescriptorParameters<'a>),
+
+    /// [GATT Update Characteristic Value](crate::gatt::Commands::update_characteristic_value)
+    GattUpdateCharacteristicValue(gatt::UpdateCharacteristicValueParameters<'a>),
+
+    /// [GATT Delete Characteristic](crate::gatt::Commands::delete_characteristic)
+    GattDeleteCharacteristic {
+        /// Handle of the service that contains the characteristic.
+        service: gatt::ServiceHandle,
+
+        /// Handle of the characteristic to delete.
+        characteristic: gatt::CharacteristicHandle,
+    },
+
+    /// [GATT Delete Service](crate::gatt::Commands::delete_service). Includes the service handle.
+    GattDeleteService(gatt::ServiceHandle),
+
+    /// [GATT Delete Included Service](crate::gatt::Commands::delete_included_service)
+    GattDeleteIncludedService(gatt::DeleteIncludedServiceParameters),
+
+    /// [GATT Set Event Mask](crate::gatt::Commands::set_event_mask)
+    GattSetEventMask(gatt::Event),
+
+    /// [GATT Exchange Configuration](crate::gatt::Commands::exchange_configuration). Includes the
+    /// connection handle.
+    GattExchangeConfiguration(ConnectionHandle),
+
+    /// [GATT Find Information Request](crate::gatt::Commands::find_information_request)
+    GattFindInformationRequest {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Range of attributes to find.
+        attribute_range: gatt::Range<gatt::CharacteristicHandle>,
+    },
+
+    /// [GATT Find By Type Value Request](crate::gatt::Commands::find_by_type_value_request)
+    GattFindByTypeValueRequest(gatt::FindByTypeValueParameters<'a>),
+
+    /// [GATT Read By Type Request](crate::gatt::Commands::read_by_type_request)
+    GattReadByTypeRequest(gatt::ReadByTypeParameters),
+
+    /// [GATT Read By Group Type Request](crate::gatt::Commands::read_by_group_type_request)
+    GattReadByGroupTypeRequest(gatt::ReadByTypeParameters),
+
+    /// [GATT Prepare Write Request](crate::gatt::Commands::prepare_write_request)
+    GattPrepareWriteRequest(gatt::WriteRequest<'a>),
+
+    /// [GATT Execute Write Request](crate::gatt::Commands::execute_write_request). Includes the
+    /// connection handle.
+    GattExecuteWriteRequest(ConnectionHandle),
+
+    /// [GATT Cancel Write Request](crate::gatt::Commands::cancel_write_request). Includes the
+    /// connection handle.
+    GattCancelWriteRequest(ConnectionHandle),
+
+    /// [GATT Discover All Primary Services](crate::gatt::Commands::discover_all_primary_services).
+    /// Includes the connection handle.
+    GattDiscoverAllPrimaryServices(ConnectionHandle),
+
+    /// [GATT Discover Primary Services by
+    /// UUID](crate::gatt::Commands::discover_primary_services_by_uuid)
+    GattDiscoverPrimaryServicesByUuid {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// UUID of the services to discover.
+        uuid: gatt::Uuid,
+    },
+
+    /// [GATT Find Included Services](crate::gatt::Commands::find_included_services)
+    GattFindIncludedServices {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Range of services to search.
+        service_handle_range: gatt::Range<gatt::ServiceHandle>,
+    },
+
+    /// [GATT Discover All Characteristics of
+    /// Service](crate::gatt::Commands::discover_all_characteristics_of_service)
+    GattDiscoverAllCharacteristicsOfService {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Range of attributes to search.
+        attribute_handle_range: gatt::Range<gatt::CharacteristicHandle>,
+    },
+
+    /// [GATT Discover Characteristics by
+    /// UUID](crate::gatt::Commands::discover_characteristics_by_uuid)
+    GattDiscoverCharacteristicsByUuid {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Range of attributes to search.
+        attribute_handle_range: gatt::Range<gatt::CharacteristicHandle>,
+
+        /// UUID of the characteristics to discover.
+        uuid: gatt::Uuid,
+    },
+
+    /// [GATT Discover All Characteristic
+    /// Descriptors](crate::gatt::Commands::discover_all_characteristic_descriptors)
+    GattDiscoverAllCharacteristicDescriptors {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Range of characteristics to search.
+        characteristic_handle_range: gatt::Range<gatt::CharacteristicHandle>,
+    },
+
+    /// [GATT Read Characteristic Value](crate::gatt::Commands::read_characteristic_value)
+    GattReadCharacteristicValue {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Handle of the characteristic to read.
+        characteristic_handle: gatt::CharacteristicHandle,
+    },
+
+    /// [GATT Read Characteristic Using
+    /// UUID](crate::gatt::Commands::read_characteristic_using_uuid)
+    GattReadCharacteristicUsingUuid {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Range of characteristics to search.
+        characteristic_handle_range: gatt::Range<gatt::CharacteristicHandle>,
+
+        /// UUID of the characteristic to read.
+        uuid: gatt::Uuid,
+    },
+
+    /// [GATT Read Long Characteristic
+    /// Value](crate::gatt::Commands::read_long_characteristic_value)
+    GattReadLongCharacteristicValue(gatt::LongCharacteristicReadParameters),
+
+    /// [GATT Read Multiple Characteristic
+    /// Values](crate::gatt::Commands::read_multiple_characteristic_values)
+    GattReadMultipleCharacteristicValues(MultipleCharacteristicRead),
+
+    /// [GATT Write Characteristic Value](crate::gatt::Commands::write_characteristic_value)
+    GattWriteCharacteristicValue(gatt::CharacteristicValue<'a>),
+
+    /// [GATT Write Long Characteristic
+    /// Value](crate::gatt::Commands::write_long_characteristic_value)
+    GattWriteLongCharacteristicValue(gatt::LongCharacteristicValue<'a>),
+
+    /// [GATT Write Characteristic Value
+    /// Reliably](crate::gatt::Commands::write_characteristic_value_reliably)
+    GattWriteCharacteristicValueReliably(gatt::LongCharacteristicValue<'a>),
+
+    /// [GATT Write Long Characteristic
+    /// Descriptor](crate::gatt::Commands::write_long_characteristic_descriptor)
+    GattWriteLongCharacteristicDescriptor(gatt::LongCharacteristicValue<'a>),
+
+    /// [GATT Read Long Characteristic
+    /// Descriptor](crate::gatt::Commands::read_long_characteristic_descriptor)
+    GattReadLongCharacteristicDescriptor(gatt::LongCharacteristicReadParameters),
+
+    /// [GATT Write Characteristic
+    /// Descriptor](crate::gatt::Commands::write_characteristic_descriptor)
+    GattWriteCharacteristicDescriptor(gatt::CharacteristicValue<'a>),
+
+    /// [GATT Read Characteristic
+    /// Descriptor](crate::gatt::Commands::read_characteristic_descriptor)
+    GattReadCharacteristicDescriptor {
+        /// Handle of the connection.
+        conn_handle: ConnectionHandle,
+
+        /// Handle of the descriptor to read.
+        characteristic_handle: gatt::CharacteristicHandle,
+    },
+
+    /// [GATT Write Without Response](crate::gatt::Commands::write_without_response)
+    GattWriteWithoutResponse(gatt::CharacteristicValue<'a>),
+
+    /// [GATT Signed Write Without
+    /// Response](crate::gatt::Commands::signed_write_without_response)
+    GattSignedWriteWithoutResponse(gatt::CharacteristicValue<'a>),
+
+    /// [GATT Confirm Indication](crate::gatt::Commands::confirm_indication). Includes the
+    /// connection handle.
+    GattConfirmIndication(ConnectionHandle),
+
+    /// [GATT Write Response](crate::gatt::Commands::write_response)
+    GattWriteResponse(gatt::WriteResponseParameters<'a>),
+
+    /// [GATT Allow Read](crate::gatt::Commands::allow_read). Includes the connection handle.
+    GattAllowRead(ConnectionHandle),
+
+    /// [GATT Set Security Permission](crate::gatt::Commands::set_security_permission)
+    GattSetSecurityPermission(gatt::SecurityPermissionParameters),
+
+    /// [GATT Set Descriptor Value](crate::gatt::Commands::set_descriptor_value)
+    GattSetDescriptorValue(gatt::DescriptorValueParameters<'a>),
+
+    /// [GATT Read Handle Value](crate::gatt::Commands::read_handle_value). Includes the handle of
+    /// the attribute.
+    GattReadHandleValue(gatt::CharacteristicHandle),
+
+    /// [GATT Read Handle Value Offset](crate::gatt::Commands::read_handle_value_offset)
+    #[cfg(feature = "ms")]
+    GattReadHandleValueOffset {
+        /// Handle of the attribute to read.
+        handle: gatt::CharacteristicHandle,
+
+        /// Offset from which the value is read.
+        offset: usize,
+    },
+
+    /// [GATT Update Long Characteristic
+    /// Value](crate::gatt::Commands::update_long_characteristic_value)
+    #[cfg(feature = "ms")]
+    GattUpdateLongCharacteristicValue(gatt::UpdateLongCharacteristicValueParameters<'a>),
+
+    /// [L2CAP Connection Parameter Update
+    /// Request](crate::l2cap::Commands::connection_parameter_update_request)
+    L2CapConnectionParameterUpdateRequest(l2cap::ConnectionParameterUpdateRequest),
+
+    /// [L2CAP Connection Parameter Update
+    /// Response](crate::l2cap::Commands::connection_parameter_update_response)
+    L2CapConnectionParameterUpdateResponse(l2cap::ConnectionParameterUpdateResponse),
+
+    /// [Updater Start](crate::updater::Commands::updater_start)
+    UpdaterStart,
+
+    /// [Updater Reboot](crate::updater::Commands::updater_reboot)
+    UpdaterReboot,
+
+    /// [Updater Get Version](crate::updater::Commands::get_updater_version)
+    UpdaterGetVersion,
+
+    /// [Updater Get Buffer Size](crate::updater::Commands::get_updater_buffer_size)
+    UpdaterGetBufferSize,
+
+    /// [Updater Erase BLUE Flag](crate::updater::Commands::erase_blue_flag)
+    UpdaterEraseBlueFlag,
+
+    /// [Updater Reset BLUE Flag](crate::updater::Commands::reset_blue_flag)
+    UpdaterResetBlueFlag,
+
+    /// [Updater Erase Sector](crate::updater::Commands::erase_sector). Includes the address of the
+    /// sector.
+    UpdaterEraseSector(u32),
+
+    /// [Updater Program Data Block](crate::updater::Commands::program_data_block)
+    UpdaterProgramDataBlock {
+        /// Flash address of the first byte of data.
+        address: u32,
+
+        /// Data to program.
+        data: &'a [u8],
+    },
+
+    /// [Updater Read Data Block](crate::updater::Commands::read_data_block)
+    UpdaterReadDataBlock {
+        /// Flash address of the first byte to read.
+        address: u32,
+
+        /// Number of bytes to read.
+        len: usize,
+    },
+
+    /// [Updater Calculate CRC](crate::updater::Commands::calculate_crc)
+    UpdaterCalculateCrc {
+        /// Address of the first sector.
+        address: u32,
+
+        /// Number of sectors.
+        sectors: u8,
+    },
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for VendorCommand<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            VendorCommand::HalGetFirmwareRevision => defmt::write!(f, "HalGetFirmwareRevision"),
+            VendorCommand::HalWriteConfigData(params) => {
+                defmt::write!(f, "HalWriteConfigData({})", params)
+            }
+            VendorCommand::HalReadConfigData(params) => {
+                defmt::write!(f, "HalReadConfigData({})", params)
+            }
+            VendorCommand::HalSetTxPowerLevel(params) => {
+                defmt::write!(f, "HalSetTxPowerLevel({})", params)
+            }
+            VendorCommand::HalDeviceStandby => defmt::write!(f, "HalDeviceStandby"),
+            VendorCommand::HalGetTxTestPacketCount => defmt::write!(f, "HalGetTxTestPacketCount"),
+            VendorCommand::HalStartTone(value) => defmt::write!(f, "HalStartTone({})", value),
+            VendorCommand::HalStopTone => defmt::write!(f, "HalStopTone"),
+            VendorCommand::HalGetLinkStatus => defmt::write!(f, "HalGetLinkStatus"),
+            VendorCommand::HalGetAnchorPeriod => defmt::write!(f, "HalGetAnchorPeriod"),
+            #[cfg(feature = "bluenrg-1-2")]
+            VendorCommand::HalSetRadioActivityMask(params) => {
+                defmt::write!(f, "HalSetRadioActivityMask({})", params)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            VendorCommand::HalSetEventMask(params) => {
+                defmt::write!(f, "HalSetEventMask({})", params)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            VendorCommand::HalStackReset => defmt::write!(f, "HalStackReset"),
+            VendorCommand::GapSetNonDiscoverable => defmt::write!(f, "GapSetNonDiscoverable"),
+            VendorCommand::GapSetLimitedDiscoverable(params) => {
+                defmt::write!(f, "GapSetLimitedDiscoverable({})", params)
+            }
+            VendorCommand::GapSetDiscoverable(params) => {
+                defmt::write!(f, "GapSetDiscoverable({})", params)
+            }
+            VendorCommand::GapSetDirectConnectable(params) => {
+                defmt::write!(f, "GapSetDirectConnectable({})", params)
+            }
+            VendorCommand::GapSetIoCapability(params) => {
+                defmt::write!(f, "GapSetIoCapability({})", params)
+            }
+            VendorCommand::GapSetAuthenticationRequirement(params) => {
+                defmt::write!(f, "GapSetAuthenticationRequirement({})", params)
+            }
+            VendorCommand::GapSetAuthorizationRequirement {
+                conn_handle,
+                authorization_required,
+            } => {
+                defmt::write!(
+                    f,
+                    "GapSetAuthorizationRequirement {{ conn_handle: {}, authorization_required: ",
+                    Remote(conn_handle)
+                );
+                defmt::write!(f, "{} }}", authorization_required)
+            }
+            VendorCommand::GapPassKeyResponse { conn_handle, pin } => defmt::write!(
+                f,
+                "GapPassKeyResponse {{ conn_handle: {}, pin: {} }}",
+                Remote(conn_handle),
+                pin
+            ),
+            VendorCommand::GapAuthorizationResponse {
+                conn_handle,
+                authorization,
+            } => defmt::write!(
+                f,
+                "GapAuthorizationResponse {{ conn_handle: {}, authorization: {} }}",
+                Remote(conn_handle),
+                authorization
+            ),
+            #[cfg(not(feature = "ms"))]
+            VendorCommand::GapInit { role } => defmt::write!(f, "GapInit {{ role: {} }}", role),
+            #[cfg(feature = "ms")]
+            VendorCommand::GapInit {
+                role,
+                privacy_enabled,
+                dev_name_characteristic_len,
+            } => defmt::write!(
+                f,
+                "GapInit {{ role: {}, privacy_enabled: {}, dev_name_characteristic_len: {} }}",
+                role,
+                privacy_enabled,
+                dev_name_characteristic_len
+            ),
+            #[cfg(not(feature = "ms"))]
+            VendorCommand::GapSetNonConnectable { advertising_type } => defmt::write!(
+                f,
+                "GapSetNonConnectable {{ advertising_type: {} }}",
+                defmt::Debug2Format(advertising_type)
+            ),
+            #[cfg(feature = "ms")]
+            VendorCommand::GapSetNonConnectable {
+                advertising_type,
+                address_type,
+            } => defmt::write!(
+                f,
+                "GapSetNonConnectable {{ advertising_type: {}, address_type: {} }}",
+                defmt::Debug2Format(advertising_type),
+                address_type
+            ),
+            VendorCommand::GapSetUndirectedConnectable {
+                filter_policy,
+                address_type,
+            } => defmt::write!(
+                f,
+                "GapSetUndirectedConnectable {{ filter_policy: {}, address_type: {} }}",
+                defmt::Debug2Format(filter_policy),
+                address_type
+            ),
+            VendorCommand::GapPeripheralSecurityRequest(params) => {
+                defmt::write!(f, "GapPeripheralSecurityRequest({})", params)
+            }
+            VendorCommand::GapUpdateAdvertisingData(value) => {
+                defmt::write!(f, "GapUpdateAdvertisingData({})", value)
+            }
+            VendorCommand::GapDeleteAdType(params) => {
+                defmt::write!(f, "GapDeleteAdType({})", params)
+            }
+            VendorCommand::GapGetSecurityLevel => defmt::write!(f, "GapGetSecurityLevel"),
+            VendorCommand::GapSetEventMask(params) => {
+                defmt::write!(f, "GapSetEventMask({})", params)
+            }
+            VendorCommand::GapConfigureWhiteList => defmt::write!(f, "GapConfigureWhiteList"),
+            VendorCommand::GapTerminate {
+                conn_handle,
+                reason,
+            } => defmt::write!(
+                f,
+                "GapTerminate {{ conn_handle: {}, reason: {} }}",
+                Remote(conn_handle),
+                Remote(reason)
+            ),
+            VendorCommand::GapClearSecurityDatabase => defmt::write!(f, "GapClearSecurityDatabase"),
+            #[cfg(not(feature = "ms"))]
+            VendorCommand::GapAllowRebond => defmt::write!(f, "GapAllowRebond"),
+            #[cfg(feature = "ms")]
+            VendorCommand::GapAllowRebond(conn_handle) => {
+                defmt::write!(f, "GapAllowRebond({})", Remote(conn_handle))
+            }
+            VendorCommand::GapStartLimitedDiscoveryProcedure(params) => {
+                defmt::write!(f, "GapStartLimitedDiscoveryProcedure({})", params)
+            }
+            VendorCommand::GapStartGeneralDiscoveryProcedure(params) => {
+                defmt::write!(f, "GapStartGeneralDiscoveryProcedure({})", params)
+            }
+            VendorCommand::GapStartNameDiscoveryProcedure(params) => {
+                defmt::write!(f, "GapStartNameDiscoveryProcedure({})", params)
+            }
+            VendorCommand::GapStartAutoConnectionEstablishment(params) => {
+                defmt::write!(f, "GapStartAutoConnectionEstablishment({})", params)
+            }
+            VendorCommand::GapStartGeneralConnectionEstablishment(params) => {
+                defmt::write!(f, "GapStartGeneralConnectionEstablishment({})", params)
+            }
+            VendorCommand::GapStartSelectiveConnectionEstablishment(
+                selective_connection_establishment,
+            ) => defmt::write!(
+                f,
+                "GapStartSelectiveConnectionEstablishment({})",
+                selective_connection_establishment
+            ),
+            VendorCommand::GapCreateConnection(params) => {
+                defmt::write!(f, "GapCreateConnection({})", params)
+            }
+            VendorCommand::GapTerminateProcedure(params) => {
+                defmt::write!(f, "GapTerminateProcedure({})", params)
+            }
+            VendorCommand::GapStartConnectionUpdate(params) => {
+                defmt::write!(f, "GapStartConnectionUpdate({})", params)
+            }
+            VendorCommand::GapSendPairingRequest(params) => {
+                defmt::write!(f, "GapSendPairingRequest({})", params)
+            }
+            VendorCommand::GapResolvePrivateAddress(addr) => {
+                defmt::write!(f, "GapResolvePrivateAddress({})", Remote(addr))
+            }
+            #[cfg(feature = "ms")]
+            VendorCommand::GapSetBroadcastMode(params) => {
+                defmt::write!(f, "GapSetBroadcastMode({})", params)
+            }
+            #[cfg(feature = "ms")]
+            VendorCommand::GapStartObservationProcedure(params) => {
+                defmt::write!(f, "GapStartObservationProcedure({})", params)
+            }
+            VendorCommand::GapGetBondedDevices => defmt::write!(f, "GapGetBondedDevices"),
+            VendorCommand::GapIsDeviceBonded(params) => {
+                defmt::write!(f, "GapIsDeviceBonded({})", defmt::Debug2Format(params))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            VendorCommand::GapNumericComparisonValueConfirm {
+                conn_handle,
+                confirm,
+            } => defmt::write!(
+                f,
+                "GapNumericComparisonValueConfirm {{ conn_handle: {}, confirm: {} }}",
+                Remote(conn_handle),
+                confirm
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            VendorCommand::GapPasskeyInput { conn_handle, input } => defmt::write!(
+                f,
+                "GapPasskeyInput {{ conn_handle: {}, input: {} }}",
+                Remote(conn_handle),
+                input
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            VendorCommand::GapRemoveBondedDevice(params) => {
+                defmt::write!(f, "GapRemoveBondedDevice({})", defmt::Debug2Format(params))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            VendorCommand::GapSetLeEventMask(params) => {
+                defmt::write!(f, "GapSetLeEventMask({})", defmt::Debug2Format(params))
+            }
+            VendorCommand::GattInit => defmt::write!(f, "GattInit"),
+            VendorCommand::GattAddService(params) => defmt::write!(f, "GattAddService({})", params),
+            VendorCommand::GattIncludeService(params) => {
+                defmt::write!(f, "GattIncludeService({})", params)
+            }
+            VendorCommand::GattAddCharacteristic(params) => {
+                defmt::write!(f, "GattAddCharacteristic({})", params)
+            }
+            VendorCommand::GattAddCharacteristicDescriptor(params) => {
+                defmt::write!(f, "GattAddCharacteristicDescriptor({})", params)
+            }
+            VendorCommand::GattUpdateCharacteristicValue(params) => {
+                defmt::write!(f, "GattUpdateCharacteristicValue({})", params)
+            }
+            VendorCommand::GattDeleteCharacteristic {
+                service,
+                characteristic,
+            } => defmt::write!(
+                f,
+                "GattDeleteCharacteristic {{ service: {}, characteristic: {} }}",
+                service,
+                characteristic
+            ),
+            VendorCommand::GattDeleteService(params) => {
+                defmt::write!(f, "GattDeleteService({})", params)
+            }
+            VendorCommand::GattDeleteIncludedService(params) => {
+                defmt::write!(f, "GattDeleteIncludedService({})", params)
+            }
+            VendorCommand::GattSetEventMask(event) => {
+                defmt::write!(f, "GattSetEventMask({})", event)
+            }
+            VendorCommand::GattExchangeConfiguration(conn_handle) => {
+                defmt::write!(f, "GattExchangeConfiguration({})", Remote(conn_handle))
+            }
+            VendorCommand::GattFindInformationRequest {
+                conn_handle,
+                attribute_range,
+            } => defmt::write!(
+                f,
+                "GattFindInformationRequest {{ conn_handle: {}, attribute_range: {} }}",
+                Remote(conn_handle),
+                attribute_range
+            ),
+            VendorCommand::GattFindByTypeValueRequest(params) => {
+                defmt::write!(f, "GattFindByTypeValueRequest({})", params)
+            }
+            VendorCommand::GattReadByTypeRequest(params) => {
+                defmt::write!(f, "GattReadByTypeRequest({})", params)
+            }
+            VendorCommand::GattReadByGroupTypeRequest(params) => {
+                defmt::write!(f, "GattReadByGroupTypeRequest({})", params)
+            }
+            VendorCommand::GattPrepareWriteRequest(params) => {
+                defmt::write!(f, "GattPrepareWriteRequest({})", params)
+            }
+            VendorCommand::GattExecuteWriteRequest(conn_handle) => {
+                defmt::write!(f, "GattExecuteWriteRequest({})", Remote(conn_handle))
+            }
+            VendorCommand::GattCancelWriteRequest(conn_handle) => {
+                defmt::write!(f, "GattCancelWriteRequest({})", Remote(conn_handle))
+            }
+            VendorCommand::GattDiscoverAllPrimaryServices(conn_handle) => {
+                defmt::write!(f, "GattDiscoverAllPrimaryServices({})", Remote(conn_handle))
+            }
+            VendorCommand::GattDiscoverPrimaryServicesByUuid { conn_handle, uuid } => {
+                defmt::write!(
+                    f,
+                    "GattDiscoverPrimaryServicesByUuid {{ conn_handle: {}, uuid: {} }}",
+                    Remote(conn_handle),
+                    uuid
+                )
+            }
+            VendorCommand::GattFindIncludedServices {
+                conn_handle,
+                service_handle_range,
+            } => defmt::write!(
+                f,
+                "GattFindIncludedServices {{ conn_handle: {}, service_handle_range: {} }}",
+                Remote(conn_handle),
+                service_handle_range
+            ),
+            VendorCommand::GattDiscoverAllCharacteristicsOfService {
+                conn_handle,
+                attribute_handle_range,
+            } => {
+                defmt::write!(
+                    f,
+                    "GattDiscoverAllCharacteristicsOfService {{ conn_handle: {}, ",
+                    Remote(conn_handle)
+                );
+                defmt::write!(f, "attribute_handle_range: {} }}", attribute_handle_range)
+            }
+            VendorCommand::GattDiscoverCharacteristicsByUuid {
+                conn_handle,
+                attribute_handle_range,
+                uuid,
+            } => {
+                defmt::write!(
+                    f,
+                    "GattDiscoverCharacteristicsByUuid {{ conn_handle: {}, ",
+                    Remote(conn_handle)
+                );
+                defmt::write!(
+                    f,
+                    "attribute_handle_range: {}, uuid: {} }}",
+                    attribute_handle_range,
+                    uuid
+                )
+            }
+            VendorCommand::GattDiscoverAllCharacteristicDescriptors {
+                conn_handle,
+                characteristic_handle_range,
+            } => {
+                defmt::write!(
+                    f,
+                    "GattDiscoverAllCharacteristicDescriptors {{ conn_handle: {}, ",
+                    Remote(conn_handle)
+                );
+                defmt::write!(
+                    f,
+                    "characteristic_handle_range: {} }}",
+                    characteristic_handle_range
+                )
+            }
+            VendorCommand::GattReadCharacteristicValue {
+                conn_handle,
+                characteristic_handle,
+            } => defmt::write!(
+                f,
+                "GattReadCharacteristicValue {{ conn_handle: {}, characteristic_handle: {} }}",
+                Remote(conn_handle),
+                characteristic_handle
+            ),
+            VendorCommand::GattReadCharacteristicUsingUuid {
+                conn_handle,
+                characteristic_handle_range,
+                uuid,
+            } => {
+                defmt::write!(
+                    f,
+                    "GattReadCharacteristicUsingUuid {{ conn_handle: {}, ",
+                    Remote(conn_handle)
+                );
+                defmt::write!(
+                    f,
+                    "characteristic_handle_range: {}, uuid: {} }}",
+                    characteristic_handle_range,
+                    uuid
+                )
+            }
+            VendorCommand::GattReadLongCharacteristicValue(params) => {
+                defmt::write!(f, "GattReadLongCharacteristicValue({})", params)
+            }
+            VendorCommand::GattReadMultipleCharacteristicValues(params) => {
+                defmt::write!(f, "GattReadMultipleCharacteristicValues({})", params)
+            }
+            VendorCommand::GattWriteCharacteristicValue(params) => {
+                defmt::write!(f, "GattWriteCharacteristicValue({})", params)
+            }
+            VendorCommand::GattWriteLongCharacteristicValue(params) => {
+                defmt::write!(f, "GattWriteLongCharacteristicValue({})", params)
+            }
+            VendorCommand::GattWriteCharacteristicValueReliably(params) => {
+                defmt::write!(f, "GattWriteCharacteristicValueReliably({})", params)
+            }
+            VendorCommand::GattWriteLongCharacteristicDescriptor(params) => {
+                defmt::write!(f, "GattWriteLongCharacteristicDescriptor({})", params)
+            }
+            VendorCommand::GattReadLongCharacteristicDescriptor(params) => {
+                defmt::write!(f, "GattReadLongCharacteristicDescriptor({})", params)
+            }
+            VendorCommand::GattWriteCharacteristicDescriptor(params) => {
+                defmt::write!(f, "GattWriteCharacteristicDescriptor({})", params)
+            }
+            VendorCommand::GattReadCharacteristicDescriptor {
+                conn_handle,
+                characteristic_handle,
+            } => {
+                defmt::write!(
+                    f,
+                    "GattReadCharacteristicDescriptor {{ conn_handle: {}, characteristic_handle: ",
+                    Remote(conn_handle)
+                );
+                defmt::write!(f, "{} }}", characteristic_handle)
+            }
+            VendorCommand::GattWriteWithoutResponse(params) => {
+                defmt::write!(f, "GattWriteWithoutResponse({})", params)
+            }
+            VendorCommand::GattSignedWriteWithoutResponse(params) => {
+                defmt::write!(f, "GattSignedWriteWithoutResponse({})", params)
+            }
+            VendorCommand::GattConfirmIndication(conn_handle) => {
+                defmt::write!(f, "GattConfirmIndication({})", Remote(conn_handle))
+            }
+            VendorCommand::GattWriteResponse(params) => {
+                defmt::write!(f, "GattWriteResponse({})", params)
+            }
+            VendorCommand::GattAllowRead(conn_handle) => {
+                defmt::write!(f, "GattAllowRead({})", Remote(conn_handle))
+            }
+            VendorCommand::GattSetSecurityPermission(params) => {
+                defmt::write!(f, "GattSetSecurityPermission({})", params)
+            }
+            VendorCommand::GattSetDescriptorValue(params) => {
+                defmt::write!(f, "GattSetDescriptorValue({})", params)
+            }
+            VendorCommand::GattReadHandleValue(params) => {
+                defmt::write!(f, "GattReadHandleValue({})", params)
+            }
+            #[cfg(feature = "ms")]
+            VendorCommand::GattReadHandleValueOffset { handle, offset } => defmt::write!(
+                f,
+                "GattReadHandleValueOffset {{ handle: {}, offset: {} }}",
+                handle,
+                offset
+            ),
+            #[cfg(feature = "ms")]
+            VendorCommand::GattUpdateLongCharacteristicValue(params) => {
+                defmt::write!(f, "GattUpdateLongCharacteristicValue({})", params)
+            }
+            VendorCommand::L2CapConnectionParameterUpdateRequest(
+                connection_parameter_update_request,
+            ) => defmt::write!(
+                f,
+                "L2CapConnectionParameterUpdateRequest({})",
+                connection_parameter_update_request
+            ),
+            VendorCommand::L2CapConnectionParameterUpdateResponse(
+                connection_parameter_update_response,
+            ) => defmt::write!(
+                f,
+                "L2CapConnectionParameterUpdateResponse({})",
+                connection_parameter_update_response
+            ),
+            VendorCommand::UpdaterStart => defmt::write!(f, "UpdaterStart"),
+            VendorCommand::UpdaterReboot => defmt::write!(f, "UpdaterReboot"),
+            VendorCommand::UpdaterGetVersion => defmt::write!(f, "UpdaterGetVersion"),
+            VendorCommand::UpdaterGetBufferSize => defmt::write!(f, "UpdaterGetBufferSize"),
+            VendorCommand::UpdaterEraseBlueFlag => defmt::write!(f, "UpdaterEraseBlueFlag"),
+            VendorCommand::UpdaterResetBlueFlag => defmt::write!(f, "UpdaterResetBlueFlag"),
+            VendorCommand::UpdaterEraseSector(value) => {
+                defmt::write!(f, "UpdaterEraseSector({})", value)
+            }
+            VendorCommand::UpdaterProgramDataBlock { address, data } => defmt::write!(
+                f,
+                "UpdaterProgramDataBlock {{ address: {}, data: {} }}",
+                address,
+                data
+            ),
+            VendorCommand::UpdaterReadDataBlock { address, len } => defmt::write!(
+                f,
+                "UpdaterReadDataBlock {{ address: {}, len: {} }}",
+                address,
+                len
+            ),
+            VendorCommand::UpdaterCalculateCrc { address, sectors } => defmt::write!(
+                f,
+                "UpdaterCalculateCrc {{ address: {}, sectors: {} }}",
+                address,
+                sectors
+            ),
+        }
+    }
+}
+
+impl<'a> VendorCommand<'a> {
+    /// Decodes the vendor-specific command with the given opcode from its serialized parameters.
+    ///
+    /// The returned command borrows any variable-length data (like characteristic values) from
+    /// `params`. Commands whose layout differs between the BlueNRG and BlueNRG-MS are decoded with
+    /// the layout of the [selected variant](crate::variant); fields the selected variant does not
+    /// send are given the defaults documented on each command.
+    ///
+    /// # Errors
+    ///
+    /// - [`UnknownOpcode`](Error::UnknownOpcode) if the opcode is not a known vendor-specific
+    ///   command for the selected variant.
+    /// - [`BadLength`](Error::BadLength) if the length of the parameters does not match the
+    ///   command.
+    /// - Other errors if any of the parameters are invalid for their type.
+    pub fn new(opcode: hci::Opcode, params: &'a [u8]) -> Result<VendorCommand<'a>, Error> {
+        const MAX_PARAMETER_LEN: usize = 255;
+        if params.len() > MAX_PARAMETER_LEN {
+            return Err(Error::BadLength(params.len(), MAX_PARAMETER_LEN));
+        }
+
+        match opcode {
+            crate::opcode::HAL_GET_FIRMWARE_REVISION => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::HalGetFirmwareRevision)
+            }
+            crate::opcode::HAL_WRITE_CONFIG_DATA => {
+                Ok(VendorCommand::HalWriteConfigData(to_config_data(params)?))
+            }
+            crate::opcode::HAL_READ_CONFIG_DATA => {
+                require_len(params, 1)?;
+                Ok(VendorCommand::HalReadConfigData(to_config_parameter(
+                    params[0],
+                )?))
+            }
+            crate::opcode::HAL_SET_TX_POWER_LEVEL => {
+                require_len(params, 2)?;
+                Ok(VendorCommand::HalSetTxPowerLevel(to_power_level(
+                    LittleEndian::read_u16(params),
+                )?))
+            }
+            crate::opcode::HAL_DEVICE_STANDBY => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::HalDeviceStandby)
+            }
+            crate::opcode::HAL_TX_TEST_PACKET_COUNT => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::HalGetTxTestPacketCount)
+            }
+            crate::opcode::HAL_START_TONE => {
+                require_len(params, 1)?;
+                Ok(VendorCommand::HalStartTone(params[0]))
+            }
+            crate::opcode::HAL_STOP_TONE => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::HalStopTone)
+            }
+            crate::opcode::HAL_GET_LINK_STATUS => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::HalGetLinkStatus)
+            }
+            crate::opcode::HAL_GET_ANCHOR_PERIOD => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::HalGetAnchorPeriod)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::HAL_SET_RADIO_ACTIVITY_MASK => {
+                require_len(params, 2)?;
+                let bits = LittleEndian::read_u16(params);
+                Ok(VendorCommand::HalSetRadioActivityMask(
+                    hal::RadioActivityFlags::from_bits(bits)
+                        .ok_or(Error::BadRadioActivityMask(bits))?,
+                ))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::HAL_SET_EVENT_MASK => {
+                require_len(params, 4)?;
+                let bits = LittleEndian::read_u32(params);
+                Ok(VendorCommand::HalSetEventMask(
+                    hal::EventFlags::from_bits(bits).ok_or(Error::BadHalEventMask(bits))?,
+                ))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::HAL_STACK_RESET => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::HalStackReset)
+            }
+            crate::opcode::GAP_SET_NONDISCOVERABLE => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::GapSetNonDiscoverable)
+            }
+            crate::opcode::GAP_SET_LIMITED_DISCOVERABLE => Ok(
+                VendorCommand::GapSetLimitedDiscoverable(to_discoverable_parameters(params)?),
+            ),
+            crate::opcode::GAP_SET_DISCOVERABLE => Ok(VendorCommand::GapSetDiscoverable(
+                to_discoverable_parameters(params)?,
+            )),
+            crate::opcode::GAP_SET_DIRECT_CONNECTABLE => Ok(
+                VendorCommand::GapSetDirectConnectable(to_direct_connectable_parameters(params)?),
+            ),
+            crate::opcode::GAP_SET_IO_CAPABILITY => {
+                require_len(params, 1)?;
+                Ok(VendorCommand::GapSetIoCapability(to_io_capability(
+                    params[0],
+                )?))
+            }
+            crate::opcode::GAP_SET_AUTHENTICATION_REQUIREMENT => {
+                Ok(VendorCommand::GapSetAuthenticationRequirement(
+                    to_authentication_requirements(params)?,
+                ))
+            }
+            crate::opcode::GAP_SET_AUTHORIZATION_REQUIREMENT => {
+                require_len(params, 3)?;
+                Ok(VendorCommand::GapSetAuthorizationRequirement {
+                    conn_handle: to_conn_handle(params),
+                    authorization_required: to_bool(params[2])?,
+                })
+            }
+            crate::opcode::GAP_PASS_KEY_RESPONSE => {
+                require_len(params, 6)?;
+                Ok(VendorCommand::GapPassKeyResponse {
+                    conn_handle: to_conn_handle(params),
+                    pin: LittleEndian::read_u32(&params[2..]),
+                })
+            }
+            crate::opcode::GAP_AUTHORIZATION_RESPONSE => {
+                require_len(params, 3)?;
+                Ok(VendorCommand::GapAuthorizationResponse {
+                    conn_handle: to_conn_handle(params),
+                    authorization: to_authorization(params[2])?,
+                })
+            }
+            crate::opcode::GAP_INIT => to_gap_init(params),
+            crate::opcode::GAP_SET_NONCONNECTABLE => to_gap_set_nonconnectable(params),
+            crate::opcode::GAP_SET_UNDIRECTED_CONNECTABLE => {
+                require_len(params, 2)?;
+                Ok(VendorCommand::GapSetUndirectedConnectable {
+                    filter_policy: to_advertising_filter_policy(params[0])?,
+                    address_type: to_address_type(params[1])?,
+                })
+            }
+            crate::opcode::GAP_PERIPHERAL_SECURITY_REQUEST => {
+                require_len(params, 4)?;
+                Ok(VendorCommand::GapPeripheralSecurityRequest(
+                    gap::SecurityRequestParameters {
+                        conn_handle: to_conn_handle(params),
+                        bonding: to_bool(params[2])?,
+                        mitm_protection: to_bool(params[3])?,
+                    },
+                ))
+            }
+            crate::opcode::GAP_UPDATE_ADVERTISING_DATA => Ok(
+                VendorCommand::GapUpdateAdvertisingData(to_length_prefixed(params, 0)?),
+            ),
+            crate::opcode::GAP_DELETE_AD_TYPE => {
+                require_len(params, 1)?;
+                Ok(VendorCommand::GapDeleteAdType(to_advertising_data_type(
+                    params[0],
+                )?))
+            }
+            crate::opcode::GAP_GET_SECURITY_LEVEL => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::GapGetSecurityLevel)
+            }
+            crate::opcode::GAP_SET_EVENT_MASK => {
+                require_len(params, 2)?;
+                let bits = LittleEndian::read_u16(params);
+                Ok(VendorCommand::GapSetEventMask(
+                    gap::EventFlags::from_bits(bits).ok_or(Error::BadGapEventFlags(bits))?,
+                ))
+            }
+            crate::opcode::GAP_CONFIGURE_WHITE_LIST => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::GapConfigureWhiteList)
+            }
+            crate::opcode::GAP_TERMINATE => {
+                require_len(params, 3)?;
+                Ok(VendorCommand::GapTerminate {
+                    conn_handle: to_conn_handle(params),
+                    reason: to_status(params[2])?,
+                })
+            }
+            crate::opcode::GAP_CLEAR_SECURITY_DATABASE => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::GapClearSecurityDatabase)
+            }
+            #[cfg(not(feature = "ms"))]
+            crate::opcode::GAP_ALLOW_REBOND => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::GapAllowRebond)
+            }
+            #[cfg(feature = "ms")]
+            crate::opcode::GAP_ALLOW_REBOND => {
+                if crate::is_ms() {
+                    require_len(params, 2)?;
+                    Ok(VendorCommand::GapAllowRebond(to_conn_handle(params)))
+                } else {
+                    require_len(params, 0)?;
+                    Ok(VendorCommand::GapAllowRebond(ConnectionHandle(0)))
+                }
+            }
+            crate::opcode::GAP_START_LIMITED_DISCOVERY_PROCEDURE => {
+                Ok(VendorCommand::GapStartLimitedDiscoveryProcedure(
+                    to_discovery_procedure_parameters(params)?,
+                ))
+            }
+            crate::opcode::GAP_START_GENERAL_DISCOVERY_PROCEDURE => {
+                Ok(VendorCommand::GapStartGeneralDiscoveryProcedure(
+                    to_discovery_procedure_parameters(params)?,
+                ))
+            }
+            crate::opcode::GAP_START_NAME_DISCOVERY_PROCEDURE => Ok(
+                VendorCommand::GapStartNameDiscoveryProcedure(to_connection_parameters(params)?),
+            ),
+            crate::opcode::GAP_START_AUTO_CONNECTION_ESTABLISHMENT => {
+                Ok(VendorCommand::GapStartAutoConnectionEstablishment(
+                    to_auto_connection_establishment(params)?,
+                ))
+            }
+            crate::opcode::GAP_START_GENERAL_CONNECTION_ESTABLISHMENT => {
+                Ok(VendorCommand::GapStartGeneralConnectionEstablishment(
+                    to_general_connection_establishment_parameters(params)?,
+                ))
+            }
+            crate::opcode::GAP_START_SELECTIVE_CONNECTION_ESTABLISHMENT => {
+                Ok(VendorCommand::GapStartSelectiveConnectionEstablishment(
+                    to_selective_connection_establishment(params)?,
+                ))
+            }
+            crate::opcode::GAP_CREATE_CONNECTION => Ok(VendorCommand::GapCreateConnection(
+                to_connection_parameters(params)?,
+            )),
+            crate::opcode::GAP_TERMINATE_PROCEDURE => {
+                require_len(params, 1)?;
+                Ok(VendorCommand::GapTerminateProcedure(
+                    gap::Procedure::from_bits_truncate(params[0]),
+                ))
+            }
+            crate::opcode::GAP_START_CONNECTION_UPDATE => {
+                require_len(params, 14)?;
+                Ok(VendorCommand::GapStartConnectionUpdate(
+                    gap::ConnectionUpdateParameters {
+                        conn_handle: to_conn_handle(params),
+                        conn_interval: to_conn_interval(&params[2..10])?,
+                        expected_connection_length: to_expected_connection_length(&params[10..14])?,
+                    },
+                ))
+            }
+            crate::opcode::GAP_SEND_PAIRING_REQUEST => {
+                require_len(params, 3)?;
+                Ok(VendorCommand::GapSendPairingRequest(to_pairing_request(
+                    params,
+                )?))
+            }
+            crate::opcode::GAP_RESOLVE_PRIVATE_ADDRESS => {
+                require_len(params, 6)?;
+                Ok(VendorCommand::GapResolvePrivateAddress(to_bd_addr(params)))
+            }
+            #[cfg(feature = "ms")]
+            crate::opcode::GAP_SET_BROADCAST_MODE if !crate::is_ms() => {
+                Err(Error::UnknownOpcode(opcode))
+            }
+            #[cfg(feature = "ms")]
+            crate::opcode::GAP_SET_BROADCAST_MODE => Ok(VendorCommand::GapSetBroadcastMode(
+                to_broadcast_mode(params)?,
+            )),
+            #[cfg(feature = "ms")]
+            crate::opcode::GAP_START_OBSERVATION_PROCEDURE if !crate::is_ms() => {
+                Err(Error::UnknownOpcode(opcode))
+            }
+            #[cfg(feature = "ms")]
+            crate::opcode::GAP_START_OBSERVATION_PROCEDURE => {
+                require_len(params, 7)?;
+                Ok(VendorCommand::GapStartObservationProcedure(
+                    gap::ObservationProcedureParameters {
+                        scan_window: to_scan_window(&params[0..4])?,
+                        scan_type: to_scan_type(params[4])?,
+                        own_address_type: to_address_type(params[5])?,
+                        filter_duplicates: to_bool(params[6])?,
+                    },
+                ))
+            }
+            crate::opcode::GAP_GET_BONDED_DEVICES => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::GapGetBondedDevices)
+            }
+            crate::opcode::GAP_IS_DEVICE_BONDED => {
+                require_len(params, 7)?;
+                Ok(VendorCommand::GapIsDeviceBonded(to_peer_addr(params)?))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::GAP_NUMERIC_COMPARISON_VALUE_CONFIRM => {
+                require_len(params, 3)?;
+                Ok(VendorCommand::GapNumericComparisonValueConfirm {
+                    conn_handle: to_conn_handle(params),
+                    confirm: to_bool(params[2])?,
+                })
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::GAP_PASSKEY_INPUT => {
+                require_len(params, 3)?;
+                Ok(VendorCommand::GapPasskeyInput {
+                    conn_handle: to_conn_handle(params),
+                    input: to_keypress_notification(params[2])?,
+                })
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::GAP_REMOVE_BONDED_DEVICE => {
+                require_len(params, 7)?;
+                Ok(VendorCommand::GapRemoveBondedDevice(to_peer_addr(params)?))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::GAP_SET_LE_EVENT_MASK => {
+                require_len(params, 8)?;
+                let bits = LittleEndian::read_u64(params);
+                Ok(VendorCommand::GapSetLeEventMask(
+                    hci::host::LeEventFlags::from_bits(bits).ok_or(Error::BadLeEventMask(bits))?,
+                ))
+            }
+            crate::opcode::GATT_INIT => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::GattInit)
+            }
+            crate::opcode::GATT_ADD_SERVICE => Ok(VendorCommand::GattAddService(
+                to_add_service_parameters(params)?,
+            )),
+            crate::opcode::GATT_INCLUDE_SERVICE => Ok(VendorCommand::GattIncludeService(
+                to_include_service_parameters(params)?,
+            )),
+            crate::opcode::GATT_ADD_CHARACTERISTIC => Ok(VendorCommand::GattAddCharacteristic(
+                to_add_characteristic_parameters(params)?,
+            )),
+            crate::opcode::GATT_ADD_CHARACTERISTIC_DESCRIPTOR => {
+                Ok(VendorCommand::GattAddCharacteristicDescriptor(
+                    to_add_descriptor_parameters(params)?,
+                ))
+            }
+            crate::opcode::GATT_UPDATE_CHARACTERISTIC_VALUE => {
+                require_len_at_least(params, 6)?;
+                Ok(VendorCommand::GattUpdateCharacteristicValue(
+                    gatt::UpdateCharacteristicValueParameters {
+                        service_handle: to_service_handle(&params[0..]),
+                        characteristic_handle: to_characteristic_handle(&params[2..]),
+                        offset: params[4] as usize,
+                        value: to_length_prefixed(params, 5)?,
+                    },
+                ))
+            }
+            crate::opcode::GATT_DELETE_CHARACTERISTIC => {
+                require_len(params, 4)?;
+                Ok(VendorCommand::GattDeleteCharacteristic {
+                    service: to_service_handle(&params[0..]),
+                    characteristic: to_characteristic_handle(&params[2..]),
+                })
+            }
+            crate::opcode::GATT_DELETE_SERVICE => {
+                require_len(params, 2)?;
+                Ok(VendorCommand::GattDeleteService(to_service_handle(params)))
+            }
+            crate::opcode::GATT_DELETE_INCLUDED_SERVICE => {
+                require_len(params, 4)?;
+                Ok(VendorCommand::GattDeleteIncludedService(
+                    gatt::DeleteIncludedServiceParameters {
+                        service: to_service_handle(&params[0..]),
+                        included_service: to_service_handle(&params[2..]),
+                    },
+                ))
+            }
+            crate::opcode::GATT_SET_EVENT_MASK => {
+                require_len(params, 4)?;
+                let bits = LittleEndian::read_u32(params);
+                Ok(VendorCommand::GattSetEventMask(
+                    gatt::Event::from_bits(bits).ok_or(Error::BadGattEventMask(bits))?,
+                ))
+            }
+            crate::opcode::GATT_EXCHANGE_CONFIGURATION => {
+                require_len(params, 2)?;
+                Ok(VendorCommand::GattExchangeConfiguration(to_conn_handle(
+                    params,
+                )))
+            }
+            crate::opcode::GATT_FIND_INFORMATION_REQUEST => {
+                require_len(params, 6)?;
+                Ok(VendorCommand::GattFindInformationRequest {
+                    conn_handle: to_conn_handle(params),
+                    attribute_range: to_characteristic_range(&params[2..])?,
+                })
+            }
+            crate::opcode::GATT_FIND_BY_TYPE_VALUE_REQUEST => {
+                require_len_at_least(params, 9)?;
+                Ok(VendorCommand::GattFindByTypeValueRequest(
+                    gatt::FindByTypeValueParameters {
+                        conn_handle: to_conn_handle(params),
+                        attribute_handle_range: to_characteristic_range(&params[2..])?,
+                        uuid: gatt::Uuid::Uuid16(LittleEndian::read_u16(&params[6..])),
+                        value: to_length_prefixed(params, 8)?,
+                    },
+                ))
+            }
+            crate::opcode::GATT_READ_BY_TYPE_REQUEST => Ok(VendorCommand::GattReadByTypeRequest(
+                to_read_by_type_parameters(params)?,
+            )),
+            crate::opcode::GATT_READ_BY_GROUP_TYPE_REQUEST => Ok(
+                VendorCommand::GattReadByGroupTypeRequest(to_read_by_type_parameters(params)?),
+            ),
+            crate::opcode::GATT_PREPARE_WRITE_REQUEST => {
+                require_len_at_least(params, 7)?;
+                Ok(VendorCommand::GattPrepareWriteRequest(gatt::WriteRequest {
+                    conn_handle: to_conn_handle(params),
+                    attribute_handle: to_characteristic_handle(&params[2..]),
+                    offset: LittleEndian::read_u16(&params[4..]) as usize,
+                    value: to_length_prefixed(params, 6)?,
+                }))
+            }
+            crate::opcode::GATT_EXECUTE_WRITE_REQUEST => {
+                require_len(params, 3)?;
+                let conn_handle = to_conn_handle(params);
+                if to_bool(params[2])? {
+                    Ok(VendorCommand::GattExecuteWriteRequest(conn_handle))
+                } else {
+                    Ok(VendorCommand::GattCancelWriteRequest(conn_handle))
+                }
+            }
+            crate::opcode::GATT_DISCOVER_ALL_PRIMARY_SERVICES => {
+                require_len(params, 2)?;
+                Ok(VendorCommand::GattDiscoverAllPrimaryServices(
+                    to_conn_handle(params),
+                ))
+            }
+            crate::opcode::GATT_DISCOVER_PRIMARY_SERVICES_BY_UUID => {
+                require_len_at_least(params, 2)?;
+                Ok(VendorCommand::GattDiscoverPrimaryServicesByUuid {
+                    conn_handle: to_conn_handle(params),
+                    uuid: to_trailing_uuid(params, 2)?,
+                })
+            }
+            crate::opcode::GATT_FIND_INCLUDED_SERVICES => {
+                require_len(params, 6)?;
+                Ok(VendorCommand::GattFindIncludedServices {
+                    conn_handle: to_conn_handle(params),
+                    service_handle_range: to_service_range(&params[2..])?,
+                })
+            }
+            crate::opcode::GATT_DISCOVER_ALL_CHARACTERISTICS_OF_SERVICE => {
+                require_len(params, 6)?;
+                Ok(VendorCommand::GattDiscoverAllCharacteristicsOfService {
+                    conn_handle: to_conn_handle(params),
+                    attribute_handle_range: to_characteristic_range(&params[2..])?,
+                })
+            }
+            crate::opcode::GATT_DISCOVER_CHARACTERISTICS_BY_UUID => {
+                require_len_at_least(params, 6)?;
+                Ok(VendorCommand::GattDiscoverCharacteristicsByUuid {
+                    conn_handle: to_conn_handle(params),
+                    attribute_handle_range: to_characteristic_range(&params[2..])?,
+                    uuid: to_trailing_uuid(params, 6)?,
+                })
+            }
+            crate::opcode::GATT_DISCOVER_ALL_CHARACTERISTIC_DESCRIPTORS => {
+                require_len(params, 6)?;
+                Ok(VendorCommand::GattDiscoverAllCharacteristicDescriptors {
+                    conn_handle: to_conn_handle(params),
+                    characteristic_handle_range: to_characteristic_range(&params[2..])?,
+                })
+            }
+            crate::opcode::GATT_READ_CHARACTERISTIC_VALUE => {
+                require_len(params, 4)?;
+                Ok(VendorCommand::GattReadCharacteristicValue {
+                    conn_handle: to_conn_handle(params),
+                    characteristic_handle: to_characteristic_handle(&params[2..]),
+                })
+            }
+            crate::opcode::GATT_READ_CHARACTERISTIC_BY_UUID => {
+                require_len_at_least(params, 6)?;
+                Ok(VendorCommand::GattReadCharacteristicUsingUuid {
+                    conn_handle: to_conn_handle(params),
+                    characteristic_handle_range: to_characteristic_range(&params[2..])?,
+                    uuid: to_trailing_uuid(params, 6)?,
+                })
+            }
+            crate::opcode::GATT_READ_LONG_CHARACTERISTIC_VALUE => {
+                Ok(VendorCommand::GattReadLongCharacteristicValue(
+                    to_long_characteristic_read_parameters(params)?,
+                ))
+            }
+            crate::opcode::GATT_READ_MULTIPLE_CHARACTERISTIC_VALUES => {
+                Ok(VendorCommand::GattReadMultipleCharacteristicValues(
+                    to_multiple_characteristic_read(params)?,
+                ))
+            }
+            crate::opcode::GATT_WRITE_CHARACTERISTIC_VALUE => Ok(
+                VendorCommand::GattWriteCharacteristicValue(to_characteristic_value(params)?),
+            ),
+            crate::opcode::GATT_WRITE_LONG_CHARACTERISTIC_VALUE => {
+                Ok(VendorCommand::GattWriteLongCharacteristicValue(
+                    to_long_characteristic_value(params)?,
+                ))
+            }
+            crate::opcode::GATT_WRITE_CHARACTERISTIC_VALUE_RELIABLY => {
+                Ok(VendorCommand::GattWriteCharacteristicValueReliably(
+                    to_long_characteristic_value(params)?,
+                ))
+            }
+            crate::opcode::GATT_WRITE_LONG_CHARACTERISTIC_DESCRIPTOR => {
+                Ok(VendorCommand::GattWriteLongCharacteristicDescriptor(
+                    to_long_characteristic_value(params)?,
+                ))
+            }
+            crate::opcode::GATT_READ_LONG_CHARACTERISTIC_DESCRIPTOR => {
+                Ok(VendorCommand::GattReadLongCharacteristicDescriptor(
+                    to_long_characteristic_read_parameters(params)?,
+                ))
+            }
+            crate::opcode::GATT_WRITE_CHARACTERISTIC_DESCRIPTOR => Ok(
+                VendorCommand::GattWriteCharacteristicDescriptor(to_characteristic_value(params)?),
+            ),
+            crate::opcode::GATT_READ_CHARACTERISTIC_DESCRIPTOR => {
+                require_len(params, 4)?;
+                Ok(VendorCommand::GattReadCharacteristicDescriptor {
+                    conn_handle: to_conn_handle(params),
+                    characteristic_handle: to_characteristic_handle(&params[2..]),
+                })
+            }
+            crate::opcode::GATT_WRITE_WITHOUT_RESPONSE => Ok(
+                VendorCommand::GattWriteWithoutResponse(to_characteristic_value(params)?),
+            ),
+            crate::opcode::GATT_SIGNED_WRITE_WITHOUT_RESPONSE => Ok(
+                VendorCommand::GattSignedWriteWithoutResponse(to_characteristic_value(params)?),
+            ),
+            crate::opcode::GATT_CONFIRM_INDICATION => {
+                require_len(params, 2)?;
+                Ok(VendorCommand::GattConfirmIndication(to_conn_handle(params)))
+            }
+            crate::opcode::GATT_WRITE_RESPONSE => Ok(VendorCommand::GattWriteResponse(
+                to_write_response_parameters(params)?,
+            )),
+            crate::opcode::GATT_ALLOW_READ => {
+                require_len(params, 2)?;
+                Ok(VendorCommand::GattAllowRead(to_conn_handle(params)))
+            }
+            crate::opcode::GATT_SET_SECURITY_PERMISSION => {
+                require_len(params, 5)?;
+                Ok(VendorCommand::GattSetSecurityPermission(
+                    gatt::SecurityPermissionParameters {
+                        service_handle: to_service_handle(&params[0..]),
+                        attribute_handle: to_characteristic_handle(&params[2..]),
+                        permission: to_characteristic_permission(params[4])?,
+                    },
+                ))
+            }
+            crate::opcode::GATT_SET_DESCRIPTOR_VALUE => {
+                require_len_at_least(params, 9)?;
+                Ok(VendorCommand::GattSetDescriptorValue(
+                    gatt::DescriptorValueParameters {
+                        service_handle: to_service_handle(&params[0..]),
+                        characteristic_handle: to_characteristic_handle(&params[2..]),
+                        descriptor_handle: gatt::DescriptorHandle(LittleEndian::read_u16(
+                            &params[4..],
+                        )),
+                        offset: LittleEndian::read_u16(&params[6..]) as usize,
+                        value: to_length_prefixed(params, 8)?,
+                    },
+                ))
+            }
+            crate::opcode::GATT_READ_HANDLE_VALUE => {
+                require_len(params, 2)?;
+                Ok(VendorCommand::GattReadHandleValue(
+                    to_characteristic_handle(params),
+                ))
+            }
+            #[cfg(feature = "ms")]
+            crate::opcode::GATT_READ_HANDLE_VALUE_OFFSET if !crate::is_ms() => {
+                Err(Error::UnknownOpcode(opcode))
+            }
+            #[cfg(feature = "ms")]
+            crate::opcode::GATT_READ_HANDLE_VALUE_OFFSET => {
+                require_len(params, 3)?;
+                Ok(VendorCommand::GattReadHandleValueOffset {
+                    handle: to_characteristic_handle(params),
+                    offset: params[2] as usize,
+                })
+            }
+            #[cfg(feature = "ms")]
+            crate::opcode::GATT_UPDATE_LONG_CHARACTERISTIC_VALUE if !crate::is_ms() => {
+                Err(Error::UnknownOpcode(opcode))
+            }
+            #[cfg(feature = "ms")]
+            crate::opcode::GATT_UPDATE_LONG_CHARACTERISTIC_VALUE => {
+                require_len_at_least(params, 10)?;
+                Ok(VendorCommand::GattUpdateLongCharacteristicValue(
+                    gatt::UpdateLongCharacteristicValueParameters {
+                        service_handle: to_service_handle(&params[0..]),
+                        characteristic_handle: to_characteristic_handle(&params[2..]),
+                        update_type: gatt::UpdateType::from_bits(params[4])
+                            .ok_or(Error::BadUpdateType(params[4]))?,
+                        total_len: LittleEndian::read_u16(&params[5..]) as usize,
+                        offset: LittleEndian::read_u16(&params[7..]) as usize,
+                        value: to_length_prefixed(params, 9)?,
+                    },
+                ))
+            }
+            crate::opcode::L2CAP_CONN_PARAM_UPDATE_REQ => {
+                require_len(params, 10)?;
+                Ok(VendorCommand::L2CapConnectionParameterUpdateRequest(
+                    l2cap::ConnectionParameterUpdateRequest {
+                        conn_handle: to_conn_handle(params),
+                        conn_interval: to_conn_interval(&params[2..10])?,
+                    },
+                ))
+            }
+            crate::opcode::L2CAP_CONN_PARAM_UPDATE_RESP => {
+                require_len(params, 16)?;
+                Ok(VendorCommand::L2CapConnectionParameterUpdateResponse(
+                    l2cap::ConnectionParameterUpdateResponse {
+                        conn_handle: to_conn_handle(params),
+                        conn_interval: to_conn_interval(&params[2..10])?,
+                        expected_connection_length_range: to_expected_connection_length(
+                            &params[10..14],
+                        )?,
+                        identifier: params[14],
+                        accepted: to_bool(params[15])?,
+                    },
+                ))
+            }
+            crate::opcode::UPDATER_START => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::UpdaterStart)
+            }
+            crate::opcode::UPDATER_REBOOT => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::UpdaterReboot)
+            }
+            crate::opcode::UPDATER_GET_VERSION => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::UpdaterGetVersion)
+            }
+            crate::opcode::UPDATER_GET_BUFFER_SIZE => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::UpdaterGetBufferSize)
+            }
+            crate::opcode::UPDATER_ERASE_BLUE_FLAG => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::UpdaterEraseBlueFlag)
+            }
+            crate::opcode::UPDATER_RESET_BLUE_FLAG => {
+                require_len(params, 0)?;
+                Ok(VendorCommand::UpdaterResetBlueFlag)
+            }
+            crate::opcode::UPDATER_ERASE_SECTOR => {
+                require_len(params, 4)?;
+                Ok(VendorCommand::UpdaterEraseSector(LittleEndian::read_u32(
+                    params,
+                )))
+            }
+            crate::opcode::UPDATER_PROGRAM_DATA_BLOCK => {
+                require_len_at_least(params, 6)?;
+                let data_len = LittleEndian::read_u16(&params[4..]) as usize;
+                require_len(params, 6 + data_len)?;
+                Ok(VendorCommand::UpdaterProgramDataBlock {
+                    address: LittleEndian::read_u32(params),
+                    data: &params[6..],
+                })
+            }
+            crate::opcode::UPDATER_READ_DATA_BLOCK => {
+                require_len(params, 6)?;
+                Ok(VendorCommand::UpdaterReadDataBlock {
+                    address: LittleEndian::read_u32(params),
+                    len: LittleEndian::read_u16(&params[4..]) as usize,
+                })
+            }
+            crate::opcode::UPDATER_CALC_CRC => {
+                require_len(params, 5)?;
+                Ok(VendorCommand::UpdaterCalculateCrc {
+                    address: LittleEndian::read_u32(params),
+                    sectors: params[4],
+                })
+            }
+            other => Err(Error::UnknownOpcode(other)),
+        }
+    }
+}
+
+/// Returns the name of the vendor-specific command with the given opcode (for example,
+/// `"GAP_SET_DISCOVERABLE"`), or `None` if the opcode is not a known vendor-specific command.
+pub fn opcode_name(opcode: hci::Opcode) -> Option<&'static str> {
+    crate::opcode::name(opcode)
+}
+
+/// Potential errors from decoding a vendor-specific command.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The opcode is not a known vendor-specific command. Includes the opcode.
+    UnknownOpcode(hci::Opcode),
+
+    /// The parameters are not the right length for the command. Includes the actual and expected
+    /// lengths.
+    BadLength(usize, usize),
+
+    /// A boolean parameter was neither 0 nor 1. Includes the invalid value.
+    BadBooleanValue(u8),
+
+    /// The [configuration parameter](crate::hal::ConfigParameter) was not recognized. Includes the
+    /// invalid value.
+    BadConfigParameter(u8),
+
+    /// The [power level](crate::hal::PowerLevel) was not recognized. Includes the invalid value.
+    BadPowerLevel(u16),
+
+    /// The [advertising type](crate::gap::AdvertisingType) was not recognized. Includes the invalid
+    /// value.
+    BadAdvertisingType(u8),
+
+    /// The [advertising filter policy](crate::gap::AdvertisingFilterPolicy) was not recognized.
+    /// Includes the invalid value.
+    BadAdvertisingFilterPolicy(u8),
+
+    /// The [own address type](crate::gap::OwnAddressType) was not recognized. Includes the invalid
+    /// value.
+    BadOwnAddressType(u8),
+
+    /// The [address type](crate::gap::AddressType) was not recognized. Includes the invalid value.
+    BadAddressType(u8),
+
+    /// The type of a [BD_ADDR](hci::BdAddrType) was not recognized. Includes the invalid value.
+    BadBdAddrType(u8),
+
+    /// The type of a [peer address](hci::host::PeerAddrType) was not recognized. Includes the
+    /// invalid value.
+    BadPeerAddrType(u8),
+
+    /// The [scan type](hci::host::ScanType) was not recognized. Includes the invalid value.
+    BadScanType(u8),
+
+    /// The local name of the [discoverable parameters](crate::gap::DiscoverableParameters) was not
+    /// a shortened or complete local name. Includes the AD type of the name.
+    BadLocalNameType(u8),
+
+    /// The [I/O capability](crate::gap::IoCapability) was not recognized. Includes the invalid
+    /// value.
+    BadIoCapability(u8),
+
+    /// The [authorization](crate::gap::Authorization) was not recognized. Includes the invalid
+    /// value.
+    BadAuthorization(u8),
+
+    /// The [advertising data type](crate::gap::AdvertisingDataType) was not recognized. Includes
+    /// the invalid value.
+    BadAdvertisingDataType(u8),
+
+    /// The [GAP role](crate::gap::Role) included unknown flags. Includes the invalid value.
+    BadGapRole(u8),
+
+    /// The [GAP event mask](crate::gap::EventFlags) included unknown flags. Includes the invalid
+    /// value.
+    BadGapEventFlags(u16),
+
+    /// The [pairing request](crate::gap::PairingRequest) flags included unknown flags. Includes
+    /// the invalid value.
+    BadPairingRequestFlags(u8),
+
+    /// The [termination reason](crate::gap::Commands::terminate) was not a recognized status.
+    /// Includes the invalid value.
+    BadStatus(u8),
+
+    /// The [GATT event mask](crate::gatt::Event) included unknown flags. Includes the invalid
+    /// value.
+    BadGattEventMask(u32),
+
+    /// The [HAL radio activity mask](crate::hal::RadioActivityFlags) included unknown flags.
+    /// Includes the invalid value.
+    #[cfg(feature = "bluenrg-1-2")]
+    BadRadioActivityMask(u16),
+
+    /// The [HAL event mask](crate::hal::EventFlags) included unknown flags. Includes the invalid
+    /// value.
+    #[cfg(feature = "bluenrg-1-2")]
+    BadHalEventMask(u32),
+
+    /// The [LE event mask](hci::host::LeEventFlags) included unknown flags. Includes the invalid
+    /// value.
+    #[cfg(feature = "bluenrg-1-2")]
+    BadLeEventMask(u64),
+
+    /// The [keypress notification](crate::gap::KeypressNotification) was not recognized. Includes
+    /// the invalid value.
+    #[cfg(feature = "bluenrg-1-2")]
+    BadKeypressNotification(u8),
+
+    /// The [secure connections support](crate::gap::SecureConnectionsSupport) was not recognized.
+    /// Includes the invalid value.
+    #[cfg(feature = "bluenrg-1-2")]
+    BadSecureConnectionsSupport(u8),
+
+    /// The [identity address type](crate::gap::IdentityAddressType) was not recognized. Includes
+    /// the invalid value.
+    #[cfg(feature = "bluenrg-1-2")]
+    BadIdentityAddressType(u8),
+
+    /// The [UUID](crate::gatt::Uuid) type was not recognized. Includes the invalid value.
+    BadUuidType(u8),
+
+    /// The [service type](crate::gatt::ServiceType) was not recognized. Includes the invalid
+    /// value.
+    BadServiceType(u8),
+
+    /// The [characteristic permissions](crate::gatt::CharacteristicPermission) included unknown
+    /// flags. Includes the invalid value.
+    BadCharacteristicPermission(u8),
+
+    /// The [characteristic events](crate::gatt::CharacteristicEvent) included unknown flags.
+    /// Includes the invalid value.
+    BadCharacteristicEvent(u8),
+
+    /// The [descriptor permissions](crate::gatt::DescriptorPermission) included unknown flags.
+    /// Includes the invalid value.
+    BadDescriptorPermission(u8),
+
+    /// The [access permissions](crate::gatt::AccessPermission) included unknown flags. Includes
+    /// the invalid value.
+    BadAccessPermission(u8),
+
+    /// The [update type](crate::gatt::UpdateType) included unknown flags. Includes the invalid
+    /// value.
+    #[cfg(feature = "ms")]
+    BadUpdateType(u8),
+
+    /// The [encryption key size](crate::gatt::EncryptionKeySize) was out of range. Includes the
+    /// invalid value.
+    BadEncryptionKeySize(u8),
+
+    /// The end of a [range](crate::gatt::Range) was before its beginning. Includes the beginning
+    /// and end.
+    InvertedRange(u16, u16),
+
+    /// The scan window was invalid.
+    BadScanWindow(ScanWindowError),
+
+    /// The connection interval was invalid.
+    BadConnectionInterval(ConnectionIntervalError),
+
+    /// The expected connection length was invalid.
+    BadExpectedConnectionLength(ExpectedConnectionLengthError),
+
+    /// The advertising interval was invalid.
+    BadAdvertisingInterval(AdvertisingIntervalError),
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::UnknownOpcode(opcode) => defmt::write!(f, "UnknownOpcode({})", Remote(opcode)),
+            Error::BadLength(actual, expected) => {
+                defmt::write!(f, "BadLength({}, {})", actual, expected)
+            }
+            Error::BadBooleanValue(value) => defmt::write!(f, "BadBooleanValue({})", value),
+            Error::BadConfigParameter(value) => defmt::write!(f, "BadConfigParameter({})", value),
+            Error::BadPowerLevel(value) => defmt::write!(f, "BadPowerLevel({})", value),
+            Error::BadAdvertisingType(value) => defmt::write!(f, "BadAdvertisingType({})", value),
+            Error::BadAdvertisingFilterPolicy(value) => {
+                defmt::write!(f, "BadAdvertisingFilterPolicy({})", value)
+            }
+            Error::BadOwnAddressType(value) => defmt::write!(f, "BadOwnAddressType({})", value),
+            Error::BadAddressType(value) => defmt::write!(f, "BadAddressType({})", value),
+            Error::BadBdAddrType(value) => defmt::write!(f, "BadBdAddrType({})", value),
+            Error::BadPeerAddrType(value) => defmt::write!(f, "BadPeerAddrType({})", value),
+            Error::BadScanType(value) => defmt::write!(f, "BadScanType({})", value),
+            Error::BadLocalNameType(value) => defmt::write!(f, "BadLocalNameType({})", value),
+            Error::BadIoCapability(value) => defmt::write!(f, "BadIoCapability({})", value),
+            Error::BadAuthorization(value) => defmt::write!(f, "BadAuthorization({})", value),
+            Error::BadAdvertisingDataType(value) => {
+                defmt::write!(f, "BadAdvertisingDataType({})", value)
+            }
+            Error::BadGapRole(value) => defmt::write!(f, "BadGapRole({})", value),
+            Error::BadGapEventFlags(value) => defmt::write!(f, "BadGapEventFlags({})", value),
+            Error::BadPairingRequestFlags(value) => {
+                defmt::write!(f, "BadPairingRequestFlags({})", value)
+            }
+            Error::BadStatus(value) => defmt::write!(f, "BadStatus({})", value),
+            Error::BadGattEventMask(value) => defmt::write!(f, "BadGattEventMask({})", value),
+            #[cfg(feature = "bluenrg-1-2")]
+            Error::BadRadioActivityMask(value) => {
+                defmt::write!(f, "BadRadioActivityMask({})", value)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            Error::BadHalEventMask(value) => defmt::write!(f, "BadHalEventMask({})", value),
+            #[cfg(feature = "bluenrg-1-2")]
+            Error::BadLeEventMask(value) => defmt::write!(f, "BadLeEventMask({})", value),
+            #[cfg(feature = "bluenrg-1-2")]
+            Error::BadKeypressNotification(value) => {
+                defmt::write!(f, "BadKeypressNotification({})", value)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            Error::BadSecureConnectionsSupport(value) => {
+                defmt::write!(f, "BadSecureConnectionsSupport({})", value)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            Error::BadIdentityAddressType(value) => {
+                defmt::write!(f, "BadIdentityAddressType({})", value)
+            }
+            Error::BadUuidType(value) => defmt::write!(f, "BadUuidType({})", value),
+            Error::BadServiceType(value) => defmt::write!(f, "BadServiceType({})", value),
+            Error::BadCharacteristicPermission(value) => {
+                defmt::write!(f, "BadCharacteristicPermission({})", value)
+            }
+            Error::BadCharacteristicEvent(value) => {
+                defmt::write!(f, "BadCharacteristicEvent({})", value)
+            }
+            Error::BadDescriptorPermission(value) => {
+                defmt::write!(f, "BadDescriptorPermission({})", value)
+            }
+            Error::BadAccessPermission(value) => defmt::write!(f, "BadAccessPermission({})", value),
+            #[cfg(feature = "ms")]
+            Error::BadUpdateType(value) => defmt::write!(f, "BadUpdateType({})", value),
+            Error::BadEncryptionKeySize(value) => {
+                defmt::write!(f, "BadEncryptionKeySize({})", value)
+            }
+            Error::InvertedRange(from, to) => defmt::write!(f, "InvertedRange({}, {})", from, to),
+            Error::BadScanWindow(value) => {
+                defmt::write!(f, "BadScanWindow({})", defmt::Debug2Format(value))
+            }
+            Error::BadConnectionInterval(value) => {
+                defmt::write!(f, "BadConnectionInterval({})", defmt::Debug2Format(value))
+            }
+            Error::BadExpectedConnectionLength(value) => defmt::write!(
+                f,
+                "BadExpectedConnectionLength({})",
+                defmt::Debug2Format(value)
+            ),
+            Error::BadAdvertisingInterval(value) => {
+                defmt::write!(f, "BadAdvertisingInterval({})", defmt::Debug2Format(value))
+            }
+        }
+    }
+}
+
+/// Addresses decoded from a white list.
+#[derive(Copy, Clone)]
+pub struct WhiteList {
+    count: usize,
+    addresses: [PeerAddrType; MAX_WHITE_LIST_LENGTH],
+}
+
+// A 255-byte command can hold at most 35 7-byte addresses after its other parameters.
+const MAX_WHITE_LIST_LENGTH: usize = 35;
+
+impl WhiteList {
+    /// Returns the addresses in the white list.
+    pub fn as_slice(&self) -> &[PeerAddrType] {
+        &self.addresses[..self.count]
+    }
+}
+
+impl core::fmt::Debug for WhiteList {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{:?}", self.as_slice())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for WhiteList {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{}", defmt::Debug2Format(&self.as_slice()))
+    }
+}
+
+/// Decoded parameters of the [GAP Start Auto Connection
+/// Establishment](crate::gap::Commands::start_auto_connection_establishment) command.
+#[derive(Debug)]
+pub struct AutoConnectionEstablishment {
+    /// Scanning window for connection establishment.
+    pub scan_window: ScanWindow,
+
+    /// Address type of this device.
+    pub own_address_type: OwnAddressType,
+
+    /// Connection interval parameters.
+    pub conn_interval: ConnectionInterval,
+
+    /// Expected connection length
+    pub expected_connection_length: ExpectedConnectionLength,
+
+    /// Reconnection address used as our address during the procedure. The BlueNRG-MS format does
+    /// not include it, so it is `None` with the `ms` feature.
+    pub reconnection_address: Option<BdAddr>,
+
+    /// Addresses to white-list for automatic connection.
+    pub white_list: WhiteList,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for AutoConnectionEstablishment {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "AutoConnectionEstablishment {{ scan_window: {}, own_address_type: ",
+            defmt::Debug2Format(&self.scan_window)
+        );
+        defmt::write!(
+            f,
+            "{}, conn_interval: {}, expected_connection_length: {}, reconnection_address: ",
+            defmt::Debug2Format(&self.own_address_type),
+            defmt::Debug2Format(&self.conn_interval),
+            defmt::Debug2Format(&self.expected_connection_length)
+        );
+        defmt::write!(
+            f,
+            "{}, white_list: {} }}",
+            Remote(&self.reconnection_address),
+            self.white_list
+        )
+    }
+}
+
+impl AutoConnectionEstablishment {
+    /// Returns the parameters for the
+    /// [`start_auto_connection_establishment`](crate::gap::Commands::start_auto_connection_establishment)
+    /// command.
+    pub fn parameters(&self) -> gap::AutoConnectionEstablishmentParameters<'_> {
+        gap::AutoConnectionEstablishmentParameters {
+            scan_window: self.scan_window.clone(),
+            own_address_type: self.own_address_type,
+            conn_interval: self.conn_interval,
+            expected_connection_length: self.expected_connection_length.clone(),
+            reconnection_address: self.reconnection_address,
+            white_list: self.white_list.as_slice(),
+        }
+    }
+}
+
+/// Decoded parameters of the [GAP Start Selective Connection
+/// Establishment](crate::gap::Commands::start_selective_connection_establishment) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct SelectiveConnectionEstablishment {
+    /// Type of scanning
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    pub scan_type: ScanType,
+
+    /// Scanning window for connection establishment.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    pub scan_window: ScanWindow,
+
+    /// Address type of this device.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    pub own_address_type: OwnAddressType,
+
+    /// If true, only report unique devices.
+    pub filter_duplicates: bool,
+
+    /// Addresses to white-list for automatic connection.
+    pub white_list: WhiteList,
+}
+
+impl SelectiveConnectionEstablishment {
+    /// Returns the parameters for the
+    /// [`start_selective_connection_establishment`](crate::gap::Commands::start_selective_connection_establishment)
+    /// command.
+    pub fn parameters(&self) -> gap::SelectiveConnectionEstablishmentParameters<'_> {
+        gap::SelectiveConnectionEstablishmentParameters {
+            scan_type: self.scan_type,
+            scan_window: self.scan_window.clone(),
+            own_address_type: self.own_address_type,
+            filter_duplicates: self.filter_duplicates,
+            white_list: self.white_list.as_slice(),
+        }
+    }
+}
+
+/// Decoded parameters of the [GAP Set Broadcast Mode](crate::gap::Commands::set_broadcast_mode)
+/// command.
+#[cfg(feature = "ms")]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BroadcastMode<'a> {
+    /// Advertising type and interval.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    pub advertising_interval: hci::types::AdvertisingInterval,
+
+    /// Type of this device's address.
+    pub own_address_type: gap::AddressType,
+
+    /// Advertising data used by the device when advertising.
+    pub advertising_data: &'a [u8],
+
+    /// Addresses to add to the white list.
+    pub white_list: WhiteList,
+}
+
+#[cfg(feature = "ms")]
+impl<'a> BroadcastMode<'a> {
+    /// Returns the parameters for the [`set_broadcast_mode`](crate::gap::Commands::set_broadcast_mode)
+    /// command.
+    pub fn parameters(&self) -> gap::BroadcastModeParameters<'a, '_> {
+        gap::BroadcastModeParameters {
+            advertising_interval: self.advertising_interval.clone(),
+            own_address_type: self.own_address_type,
+            advertising_data: self.advertising_data,
+            white_list: self.white_list.as_slice(),
+        }
+    }
+}
+
+/// Decoded parameters of the [GATT Read Multiple Characteristic
+/// Values](crate::gatt::Commands::read_multiple_characteristic_values) command.
+#[derive(Copy, Clone)]
+pub struct MultipleCharacteristicRead {
+    /// Connection handle for which the command is given.
+    pub conn_handle: ConnectionHandle,
+
+    handle_count: usize,
+    handles: [gatt::CharacteristicHandle; MAX_HANDLE_COUNT],
+}
+
+// A 255-byte command can hold at most 126 handles after the connection handle and count.
+const MAX_HANDLE_COUNT: usize = 126;
+
+impl MultipleCharacteristicRead {
+    /// Returns the handles for which the attribute value has to be read.
+    pub fn handles(&self) -> &[gatt::CharacteristicHandle] {
+        &self.handles[..self.handle_count]
+    }
+
+    /// Returns the parameters for the
+    /// [`read_multiple_characteristic_values`](crate::gatt::Commands::read_multiple_characteristic_values)
+    /// command.
+    pub fn parameters(&self) -> gatt::MultipleCharacteristicReadParameters<'_> {
+        gatt::MultipleCharacteristicReadParameters {
+            conn_handle: self.conn_handle,
+            handles: self.handles(),
+        }
+    }
+}
+
+impl core::fmt::Debug for MultipleCharacteristicRead {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(
+            f,
+            "{{conn_handle: {:?}, handles: {:?}}}",
+            self.conn_handle,
+            self.handles()
+        )
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for MultipleCharacteristicRead {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{conn_handle: {=u16:#x}, handles: {}}}",
+            self.conn_handle.0,
+            self.handles()
+        )
+    }
+}
+
+fn require_len(bytes: &[u8], len: usize) -> Result<(), Error> {
+    if bytes.len() != len {
+        return Err(Error::BadLength(bytes.len(), len));
+    }
+
+    Ok(())
+}
+
+fn require_len_at_least(bytes: &[u8], len: usize) -> Result<(), Error> {
+    if bytes.len() < len {
+        return Err(Error::BadLength(bytes.len(), len));
+    }
+
+    Ok(())
+}
+
+// Returns the data that follows the length byte at bytes[len_index], which must extend to the end
+// of the buffer.
+fn to_length_prefixed(bytes: &[u8], len_index: usize) -> Result<&[u8], Error> {
+    require_len_at_least(bytes, len_index + 1)?;
+    let data_len = bytes[len_index] as usize;
+    require_len(bytes, len_index + 1 + data_len)?;
+
+    Ok(&bytes[len_index + 1..])
+}
+
+fn to_bool(value: u8) -> Result<bool, Error> {
+    match value {
+        0 => Ok(false),
+        1 => Ok(true),
+        _ => Err(Error::BadBooleanValue(value)),
+    }
+}
+
+fn to_conn_handle(bytes: &[u8]) -> ConnectionHandle {
+    ConnectionHandle(LittleEndian::read_u16(bytes))
+}
+
+fn to_service_handle(bytes: &[u8]) -> gatt::ServiceHandle {
+    gatt::ServiceHandle(LittleEndian::read_u16(bytes))
+}
+
+fn to_characteristic_handle(bytes: &[u8]) -> gatt::CharacteristicHandle {
+    gatt::CharacteristicHandle(LittleEndian::read_u16(bytes))
+}
+
+fn to_bd_addr(bytes: &[u8]) -> BdAddr {
+    let mut addr = BdAddr([0; 6]);
+    addr.0.copy_from_slice(&bytes[0..6]);
+
+    addr
+}
+
+fn to_optional_bd_addr(bytes: &[u8]) -> Result<Option<BdAddr>, Error> {
+    if to_bool(bytes[0])? {
+        Ok(Some(to_bd_addr(&bytes[1..7])))
+    } else {
+        Ok(None)
+    }
+}
+
+fn to_peer_addr(bytes: &[u8]) -> Result<PeerAddrType, Error> {
+    let addr = to_bd_addr(&bytes[1..7]);
+    match bytes[0] {
+        0 => Ok(PeerAddrType::PublicDeviceAddress(addr)),
+        1 => Ok(PeerAddrType::RandomDeviceAddress(addr)),
+        other => Err(Error::BadPeerAddrType(other)),
+    }
+}
+
+fn to_white_list(bytes: &[u8]) -> Result<WhiteList, Error> {
+    const ADDR_LEN: usize = 7;
+
+    require_len_at_least(bytes, 1)?;
+    let count = bytes[0] as usize;
+    require_len(bytes, 1 + ADDR_LEN * count)?;
+
+    let mut white_list = WhiteList {
+        count,
+        addresses: [PeerAddrType::PublicDeviceAddress(BdAddr([0; 6])); MAX_WHITE_LIST_LENGTH],
+    };
+    for (addr, chunk) in white_list
+        .addresses
+        .iter_mut()
+        .zip(bytes[1..].chunks(ADDR_LEN))
+    {
+        *addr = to_peer_addr(chunk)?;
+    }
+
+    Ok(white_list)
+}
+
+fn to_status(value: u8) -> Result<hci::Status<crate::event::Status>, Error> {
+    hci::Status::try_from(value).map_err(|_| Error::BadStatus(value))
+}
+
+// Durations of advertising intervals, scan windows, and connection lengths are all sent as
+// multiples of 0.625 ms.
+fn to_duration(value: u16) -> Duration {
+    Duration::from_micros(625 * value as u64)
+}
+
+fn to_scan_window(bytes: &[u8]) -> Result<ScanWindow, Error> {
+    ScanWindow::start_every(to_duration(LittleEndian::read_u16(&bytes[0..])))
+        .and_then(|builder| builder.open_for(to_duration(LittleEndian::read_u16(&bytes[2..]))))
+        .map_err(Error::BadScanWindow)
+}
+
+fn to_conn_interval(bytes: &[u8]) -> Result<ConnectionInterval, Error> {
+    ConnectionInterval::from_bytes(bytes).map_err(Error::BadConnectionInterval)
+}
+
+fn to_expected_connection_length(bytes: &[u8]) -> Result<ExpectedConnectionLength, Error> {
+    ExpectedConnectionLength::new(
+        to_duration(LittleEndian::read_u16(&bytes[0..])),
+        to_duration(LittleEndian::read_u16(&bytes[2..])),
+    )
+    .map_err(Error::BadExpectedConnectionLength)
+}
+
+fn to_config_parameter(value: u8) -> Result<hal::ConfigParameter, Error> {
+    match value {
+        0 => Ok(hal::ConfigParameter::PublicAddress),
+        6 => Ok(hal::ConfigParameter::Diversifier),
+        8 => Ok(hal::ConfigParameter::EncryptionRoot),
+        24 => Ok(hal::ConfigParameter::IdentityRoot),
+        40 => Ok(hal::ConfigParameter::LinkLayerOnly),
+        41 => Ok(hal::ConfigParameter::Role),
+        _ => Err(Error::BadConfigParameter(value)),
+    }
+}
+
+fn to_config_data(bytes: &[u8]) -> Result<hal::ConfigData, Error> {
+    require_len_at_least(bytes, 2)?;
+    if bytes.len() > hal::ConfigData::MAX_LENGTH {
+        return Err(Error::BadLength(bytes.len(), hal::ConfigData::MAX_LENGTH));
+    }
+
+    Ok(hal::ConfigData::from_raw(
+        bytes[0],
+        to_length_prefixed(bytes, 1)?,
+    ))
+}
+
+fn to_power_level(value: u16) -> Result<hal::PowerLevel, Error> {
+    match value {
+        0x000 => Ok(hal::PowerLevel::DbmNeg18),
+        0x001 => Ok(hal::PowerLevel::DbmNeg15),
+        0x100 => Ok(hal::PowerLevel::DbmNeg14_7),
+        0x101 => Ok(hal::PowerLevel::DbmNeg11_7),
+        0x200 => Ok(hal::PowerLevel::DbmNeg11_4),
+        0x201 => Ok(hal::PowerLevel::DbmNeg8_4),
+        0x300 => Ok(hal::PowerLevel::DbmNeg8_1),
+        0x301 => Ok(hal::PowerLevel::DbmNeg5_1),
+        0x400 => Ok(hal::PowerLevel::DbmNeg4_9),
+        0x401 => Ok(hal::PowerLevel::DbmNeg2_1),
+        0x500 => Ok(hal::PowerLevel::DbmNeg1_6),
+        0x501 => Ok(hal::PowerLevel::Dbm1_4),
+        0x600 => Ok(hal::PowerLevel::Dbm1_7),
+        0x601 => Ok(hal::PowerLevel::Dbm4_7),
+        0x700 => Ok(hal::PowerLevel::Dbm5_0),
+        0x701 => Ok(hal::PowerLevel::Dbm8_0),
+        _ => Err(Error::BadPowerLevel(value)),
+    }
+}
+
+fn to_advertising_type(value: u8) -> Result<AdvertisingType, Error> {
+    match value {
+        0x00 => Ok(AdvertisingType::ConnectableUndirected),
+        0x01 => Ok(AdvertisingType::ConnectableDirectedHighDutyCycle),
+        0x02 => Ok(AdvertisingType::ScannableUndirected),
+        0x03 => Ok(AdvertisingType::NonConnectableUndirected),
+        0x04 => Ok(AdvertisingType::ConnectableDirectedLowDutyCycle),
+        _ => Err(Error::BadAdvertisingType(value)),
+    }
+}
+
+fn to_advertising_filter_policy(value: u8) -> Result<AdvertisingFilterPolicy, Error> {
+    match value {
+        0x00 => Ok(AdvertisingFilterPolicy::AllowConnectionAndScan),
+        0x01 => Ok(AdvertisingFilterPolicy::AllowConnectionWhiteListScan),
+        0x02 => Ok(AdvertisingFilterPolicy::WhiteListConnectionAllowScan),
+        0x03 => Ok(AdvertisingFilterPolicy::WhiteListConnectionAndScan),
+        _ => Err(Error::BadAdvertisingFilterPolicy(value)),
+    }
+}
+
+fn to_own_address_type(value: u8) -> Result<OwnAddressType, Error> {
+    match value {
+        0x00 => Ok(OwnAddressType::Public),
+        0x01 => Ok(OwnAddressType::Random),
+        _ => Err(Error::BadOwnAddressType(value)),
+    }
+}
+
+fn to_address_type(value: u8) -> Result<gap::AddressType, Error> {
+    match value {
+        0x00 => Ok(gap::AddressType::Public),
+        0x01 => Ok(gap::AddressType::Random),
+        0x02 => Ok(gap::AddressType::ResolvablePrivate),
+        0x03 => Ok(gap::AddressType::NonResolvablePrivate),
+        _ => Err(Error::BadAddressType(value)),
+    }
+}
+
+fn to_scan_type(value: u8) -> Result<ScanType, Error> {
+    match value {
+        0x00 => Ok(ScanType::Passive),
+        0x01 => Ok(ScanType::Active),
+        _ => Err(Error::BadScanType(value)),
+    }
+}
+
+fn to_io_capability(value: u8) -> Result<gap::IoCapability, Error> {
+    match value {
+        0x00 => Ok(gap::IoCapability::Display),
+        0x01 => Ok(gap::IoCapability::DisplayConfirm),
+        0x02 => Ok(gap::IoCapability::Keyboard),
+        0x03 => Ok(gap::IoCapability::None),
+        0x04 => Ok(gap::IoCapability::KeyboardDisplay),
+        _ => Err(Error::BadIoCapability(value)),
+    }
+}
+
+fn to_authorization(value: u8) -> Result<gap::Authorization, Error> {
+    match value {
+        0x01 => Ok(gap::Authorization::Authorized),
+        0x02 => Ok(gap::Authorization::Rejected),
+        _ => Err(Error::BadAuthorization(value)),
+    }
+}
+
+fn to_advertising_data_type(value: u8) -> Result<gap::AdvertisingDataType, Error> {
+    match value {
+        0x01 => Ok(gap::AdvertisingDataType::Flags),
+        0x02 => Ok(gap::AdvertisingDataType::Uuid16),
+        0x03 => Ok(gap::AdvertisingDataType::UuidCompleteList16),
+        0x04 => Ok(gap::AdvertisingDataType::Uuid32),
+        0x05 => Ok(gap::AdvertisingDataType::UuidCompleteList32),
+        0x06 => Ok(gap::AdvertisingDataType::Uuid128),
+        0x07 => Ok(gap::AdvertisingDataType::UuidCompleteList128),
+        0x08 => Ok(gap::AdvertisingDataType::ShortenedLocalName),
+        0x09 => Ok(gap::AdvertisingDataType::CompleteLocalName),
+        0x0A => Ok(gap::AdvertisingDataType::TxPowerLevel),
+        0x10 => Ok(gap::AdvertisingDataType::SecurityManagerTkValue),
+        0x11 => Ok(gap::AdvertisingDataType::SecurityManagerOutOfBandFlags),
+        0x12 => Ok(gap::AdvertisingDataType::PeripheralConnectionInterval),
+        0x14 => Ok(gap::AdvertisingDataType::SolicitUuidList16),
+        0x15 => Ok(gap::AdvertisingDataType::SolicitUuidList32),
+        0x16 => Ok(gap::AdvertisingDataType::ServiceData),
+        0xFF => Ok(gap::AdvertisingDataType::ManufacturerSpecificData),
+        _ => Err(Error::BadAdvertisingDataType(value)),
+    }
+}
+
+fn to_gap_role(value: u8) -> Result<gap::Role, Error> {
+    gap::Role::from_bits(value).ok_or(Error::BadGapRole(value))
+}
+
+fn to_gap_init(bytes: &[u8]) -> Result<VendorCommand<'_>, Error> {
+    #[cfg(feature = "ms")]
+    if crate::is_ms() {
+        require_len(bytes, 3)?;
+        return Ok(VendorCommand::GapInit {
+            role: to_gap_role(bytes[0])?,
+            privacy_enabled: to_bool(bytes[1])?,
+            dev_name_characteristic_len: bytes[2],
+        });
+    }
+
+    require_len(bytes, 1)?;
+    Ok(VendorCommand::GapInit {
+        role: to_gap_role(bytes[0])?,
+        #[cfg(feature = "ms")]
+        privacy_enabled: false,
+        #[cfg(feature = "ms")]
+        dev_name_characteristic_len: 0,
+    })
+}
+
+fn to_gap_set_nonconnectable(bytes: &[u8]) -> Result<VendorCommand<'_>, Error> {
+    #[cfg(feature = "ms")]
+    if crate::is_ms() {
+        require_len(bytes, 2)?;
+        return Ok(VendorCommand::GapSetNonConnectable {
+            advertising_type: to_advertising_type(bytes[0])?,
+            address_type: to_address_type(bytes[1])?,
+        });
+    }
+
+    require_len(bytes, 1)?;
+    Ok(VendorCommand::GapSetNonConnectable {
+        advertising_type: to_advertising_type(bytes[0])?,
+        #[cfg(feature = "ms")]
+        address_type: gap::AddressType::Public,
+    })
+}
+
+fn to_discoverable_parameters(bytes: &[u8]) -> Result<gap::DiscoverableParameters<'_, '_>, Error> {
+    const AD_TYPE_SHORTENED_LOCAL_NAME: u8 = 0x08;
+    const AD_TYPE_COMPLETE_LOCAL_NAME: u8 = 0x09;
+    const NO_SPECIFIC_CONN_INTERVAL: u16 = 0xFFFF;
+
+    require_len_at_least(bytes, 8)?;
+    let advertising_interval = match (
+        LittleEndian::read_u16(&bytes[1..]),
+        LittleEndian::read_u16(&bytes[3..]),
+    ) {
+        (0, 0) => None,
+        (min, max) => Some((to_duration(min), to_duration(max))),
+    };
+
+    let name_len = bytes[7] as usize;
+    let advertising_data_len_index = 8 + name_len;
+    require_len_at_least(bytes, advertising_data_len_index + 1)?;
+    let local_name = if name_len == 0 {
+        None
+    } else {
+        let name = &bytes[9..advertising_data_len_index];
+        match bytes[8] {
+            AD_TYPE_SHORTENED_LOCAL_NAME => Some(gap::LocalName::Shortened(name)),
+            AD_TYPE_COMPLETE_LOCAL_NAME => Some(gap::LocalName::Complete(name)),
+            other => return Err(Error::BadLocalNameType(other)),
+        }
+    };
+
+    let advertising_data_len = bytes[advertising_data_len_index] as usize;
+    let conn_interval_index = advertising_data_len_index + 1 + advertising_data_len;
+    require_len(bytes, conn_interval_index + 4)?;
+    let to_conn_interval_value = |value| {
+        if value == NO_SPECIFIC_CONN_INTERVAL {
+            None
+        } else {
+            // T = N * 1.25 ms
+            Some(Duration::from_micros(1250 * value as u64))
+        }
+    };
+
+    Ok(gap::DiscoverableParameters {
+        advertising_type: to_advertising_type(bytes[0])?,
+        advertising_interval,
+        address_type: to_own_address_type(bytes[5])?,
+        filter_policy: to_advertising_filter_policy(bytes[6])?,
+        local_name,
+        advertising_data: &bytes[advertising_data_len_index + 1..conn_interval_index],
+        conn_interval: (
+            to_conn_interval_value(LittleEndian::read_u16(&bytes[conn_interval_index..])),
+            to_conn_interval_value(LittleEndian::read_u16(&bytes[conn_interval_index + 2..])),
+        ),
+    })
+}
+
+fn to_direct_connectable_parameters(
+    bytes: &[u8],
+) -> Result<gap::DirectConnectableParameters, Error> {
+    #[cfg(feature = "ms")]
+    if crate::is_ms() {
+        require_len(bytes, 13)?;
+        return Ok(gap::DirectConnectableParameters {
+            own_address_type: to_own_address_type(bytes[0])?,
+            advertising_type: to_advertising_type(bytes[1])?,
+            initiator_address: to_bd_addr_type(&bytes[2..9])?,
+            advertising_interval: (
+                to_duration(LittleEndian::read_u16(&bytes[9..])),
+                to_duration(LittleEndian::read_u16(&bytes[11..])),
+            ),
+        });
+    }
+
+    // The BlueNRG variant does not send the advertising type or interval; it always uses high duty
+    // cycle directed advertising, which has no interval.
+    require_len(bytes, 8)?;
+    Ok(gap::DirectConnectableParameters {
+        own_address_type: to_own_address_type(bytes[0])?,
+        #[cfg(feature = "ms")]
+        advertising_type: AdvertisingType::ConnectableDirectedHighDutyCycle,
+        initiator_address: to_bd_addr_type(&bytes[1..8])?,
+        #[cfg(feature = "ms")]
+        advertising_interval: (Duration::from_secs(0), Duration::from_secs(0)),
+    })
+}
+
+fn to_bd_addr_type(bytes: &[u8]) -> Result<BdAddrType, Error> {
+    hci::to_bd_addr_type(bytes[0], to_bd_addr(&bytes[1..7]))
+        .map_err(|_| Error::BadBdAddrType(bytes[0]))
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+fn to_authentication_requirements(bytes: &[u8]) -> Result<gap::AuthenticationRequirements, Error> {
+    require_len(bytes, 26)?;
+
+    let out_of_band_auth = if to_bool(bytes[1])? {
+        let mut data = [0; 16];
+        data.copy_from_slice(&bytes[2..18]);
+        gap::OutOfBandAuthentication::Enabled(data)
+    } else {
+        gap::OutOfBandAuthentication::Disabled
+    };
+    let fixed_pin = if to_bool(bytes[20])? {
+        gap::Pin::Requested
+    } else {
+        gap::Pin::Fixed(LittleEndian::read_u32(&bytes[21..]))
+    };
+
+    Ok(gap::AuthenticationRequirements {
+        mitm_protection_required: to_bool(bytes[0])?,
+        out_of_band_auth,
+        encryption_key_size_range: (bytes[18], bytes[19]),
+        fixed_pin,
+        bonding_required: to_bool(bytes[25])?,
+    })
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+fn to_authentication_requirements(bytes: &[u8]) -> Result<gap::AuthenticationRequirements, Error> {
+    require_len(bytes, 12)?;
+
+    let fixed_pin = if to_bool(bytes[6])? {
+        gap::Pin::Requested
+    } else {
+        gap::Pin::Fixed(LittleEndian::read_u32(&bytes[7..]))
+    };
+
+    Ok(gap::AuthenticationRequirements {
+        bonding_required: to_bool(bytes[0])?,
+        mitm_protection_required: to_bool(bytes[1])?,
+        secure_connections: match bytes[2] {
+            0x00 => gap::SecureConnectionsSupport::NotSupported,
+            0x01 => gap::SecureConnectionsSupport::Optional,
+            0x02 => gap::SecureConnectionsSupport::Mandatory,
+            other => return Err(Error::BadSecureConnectionsSupport(other)),
+        },
+        keypress_notification_support: to_bool(bytes[3])?,
+        encryption_key_size_range: (bytes[4], bytes[5]),
+        fixed_pin,
+        identity_address_type: match bytes[11] {
+            0x00 => gap::IdentityAddressType::Public,
+            0x01 => gap::IdentityAddressType::Random,
+            other => return Err(Error::BadIdentityAddressType(other)),
+        },
+    })
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+fn to_keypress_notification(value: u8) -> Result<gap::KeypressNotification, Error> {
+    match value {
+        0x00 => Ok(gap::KeypressNotification::EntryStarted),
+        0x01 => Ok(gap::KeypressNotification::DigitEntered),
+        0x02 => Ok(gap::KeypressNotification::DigitErased),
+        0x03 => Ok(gap::KeypressNotification::Cleared),
+        0x04 => Ok(gap::KeypressNotification::EntryCompleted),
+        _ => Err(Error::BadKeypressNotification(value)),
+    }
+}
+
+fn to_discovery_procedure_parameters(
+    bytes: &[u8],
+) -> Result<gap::DiscoveryProcedureParameters, Error> {
+    require_len(bytes, 6)?;
+    Ok(gap::DiscoveryProcedureParameters {
+        scan_window: to_scan_window(&bytes[0..4])?,
+        own_address_type: to_own_address_type(bytes[4])?,
+        filter_duplicates: to_bool(bytes[5])?,
+    })
+}
+
+fn to_connection_parameters(bytes: &[u8]) -> Result<gap::ConnectionParameters, Error> {
+    require_len(bytes, 24)?;
+    Ok(gap::ConnectionParameters {
+        scan_window: to_scan_window(&bytes[0..4])?,
+        peer_address: to_peer_addr(&bytes[4..11])?,
+        own_address_type: to_own_address_type(bytes[11])?,
+        conn_interval: to_conn_interval(&bytes[12..20])?,
+        expected_connection_length: to_expected_connection_length(&bytes[20..24])?,
+    })
+}
+
+fn to_auto_connection_establishment(bytes: &[u8]) -> Result<AutoConnectionEstablishment, Error> {
+    let white_list_index = if crate::is_ms() { 17 } else { 24 };
+    require_len_at_least(bytes, white_list_index + 1)?;
+
+    Ok(AutoConnectionEstablishment {
+        scan_window: to_scan_window(&bytes[0..4])?,
+        own_address_type: to_own_address_type(bytes[4])?,
+        conn_interval: to_conn_interval(&bytes[5..13])?,
+        expected_connection_length: to_expected_connection_length(&bytes[13..17])?,
+        reconnection_address: if crate::is_ms() {
+            None
+        } else {
+            to_optional_bd_addr(&bytes[17..24])?
+        },
+        white_list: to_white_list(&bytes[white_list_index..])?,
+    })
+}
+
+fn to_general_connection_establishment_parameters(
+    bytes: &[u8],
+) -> Result<gap::GeneralConnectionEstablishmentParameters, Error> {
+    // The BlueNRG-MS parameters do not include a reconnection address.
+    require_len(bytes, if crate::is_ms() { 6 } else { 13 })?;
+    Ok(gap::GeneralConnectionEstablishmentParameters {
+        scan_window: to_scan_window(&bytes[0..4])?,
+        own_address_type: to_own_address_type(bytes[4])?,
+        filter_duplicates: to_bool(bytes[5])?,
+        reconnection_address: if crate::is_ms() {
+            None
+        } else {
+            to_optional_bd_addr(&bytes[6..13])?
+        },
+    })
+}
+
+fn to_selective_connection_establishment(
+    bytes: &[u8],
+) -> Result<SelectiveConnectionEstablishment, Error> {
+    require_len_at_least(bytes, 8)?;
+    Ok(SelectiveConnectionEstablishment {
+        scan_type: to_scan_type(bytes[0])?,
+        scan_window: to_scan_window(&bytes[1..5])?,
+        own_address_type: to_own_address_type(bytes[5])?,
+        filter_duplicates: to_bool(bytes[6])?,
+        white_list: to_white_list(&bytes[7..])?,
+    })
+}
+
+fn to_pairing_request(bytes: &[u8]) -> Result<gap::PairingRequest, Error> {
+    const FORCE_REBOND: u8 = 0x01;
+    const FORCE_REENCRYPT: u8 = 0x02;
+
+    let flags = bytes[2];
+    if flags & !(FORCE_REBOND | FORCE_REENCRYPT) != 0 {
+        return Err(Error::BadPairingRequestFlags(flags));
+    }
+
+    Ok(gap::PairingRequest {
+        conn_handle: to_conn_handle(bytes),
+        force_rebond: flags & FORCE_REBOND != 0,
+        force_reencrypt: flags & FORCE_REENCRYPT != 0,
+    })
+}
+
+#[cfg(feature = "ms")]
+fn to_broadcast_mode(bytes: &[u8]) -> Result<BroadcastMode<'_>, Error> {
+    require_len_at_least(bytes, 7)?;
+    let advertising_type = to_advertising_type(bytes[4])?;
+    let builder = hci::types::AdvertisingInterval::for_type(advertising_type);
+    let advertising_interval =
+        if advertising_type == AdvertisingType::ConnectableDirectedHighDutyCycle {
+            builder.build()
+        } else {
+            builder.with_range(
+                to_duration(LittleEndian::read_u16(&bytes[0..])),
+                to_duration(LittleEndian::read_u16(&bytes[2..])),
+            )
+        }
+        .map_err(Error::BadAdvertisingInterval)?;
+
+    let white_list_index = 7 + bytes[6] as usize;
+    require_len_at_least(bytes, white_list_index)?;
+
+    Ok(BroadcastMode {
+        advertising_interval,
+        own_address_type: to_address_type(bytes[5])?,
+        advertising_data: &bytes[7..white_list_index],
+        white_list: to_white_list(&bytes[white_list_index..])?,
+    })
+}
+
+// Returns the UUID that starts at bytes[index] and extends to the end of the buffer.
+fn to_trailing_uuid(bytes: &[u8], index: usize) -> Result<gatt::Uuid, Error> {
+    let (uuid, len) = to_uuid(&bytes[index..])?;
+    require_len(bytes, index + len)?;
+
+    Ok(uuid)
+}
+
+// Returns the UUID at the beginning of the buffer and its serialized length.
+fn to_uuid(bytes: &[u8]) -> Result<(gatt::Uuid, usize), Error> {
+    const UUID_16: u8 = 0x01;
+    const UUID_128: u8 = 0x02;
+
+    require_len_at_least(bytes, 1)?;
+    match bytes[0] {
+        UUID_16 => {
+            require_len_at_least(bytes, 3)?;
+            Ok((gatt::Uuid::Uuid16(LittleEndian::read_u16(&bytes[1..])), 3))
+        }
+        UUID_128 => {
+            require_len_at_least(bytes, 17)?;
+            let mut uuid = [0; 16];
+            uuid.copy_from_slice(&bytes[1..17]);
+            Ok((gatt::Uuid::Uuid128(uuid), 17))
+        }
+        other => Err(Error::BadUuidType(other)),
+    }
+}
+
+fn to_service_range(bytes: &[u8]) -> Result<gatt::Range<gatt::ServiceHandle>, Error> {
+    let from = LittleEndian::read_u16(&bytes[0..]);
+    let to = LittleEndian::read_u16(&bytes[2..]);
+    gatt::Range::new(gatt::ServiceHandle(from), gatt::ServiceHandle(to))
+        .map_err(|_| Error::InvertedRange(from, to))
+}
+
+fn to_characteristic_range(bytes: &[u8]) -> Result<gatt::Range<gatt::CharacteristicHandle>, Error> {
+    let from = LittleEndian::read_u16(&bytes[0..]);
+    let to = LittleEndian::read_u16(&bytes[2..]);
+    gatt::Range::new(
+        gatt::CharacteristicHandle(from),
+        gatt::CharacteristicHandle(to),
+    )
+    .map_err(|_| Error::InvertedRange(from, to))
+}
+
+fn to_service_type(value: u8) -> Result<gatt::ServiceType, Error> {
+    match value {
+        0x01 => Ok(gatt::ServiceType::Primary),
+        0x02 => Ok(gatt::ServiceType::Secondary),
+        _ => Err(Error::BadServiceType(value)),
+    }
+}
+
+fn to_characteristic_permission(value: u8) -> Result<gatt::CharacteristicPermission, Error> {
+    gatt::CharacteristicPermission::from_bits(value)
+        .ok_or(Error::BadCharacteristicPermission(value))
+}
+
+fn to_characteristic_event(value: u8) -> Result<gatt::CharacteristicEvent, Error> {
+    gatt::CharacteristicEvent::from_bits(value).ok_or(Error::BadCharacteristicEvent(value))
+}
+
+fn to_encryption_key_size(value: u8) -> Result<gatt::EncryptionKeySize, Error> {
+    gatt::EncryptionKeySize::with_value(value as usize)
+        .map_err(|_| Error::BadEncryptionKeySize(value))
+}
+
+fn to_add_service_parameters(bytes: &[u8]) -> Result<gatt::AddServiceParameters, Error> {
+    let (uuid, uuid_len) = to_uuid(bytes)?;
+    require_len(bytes, uuid_len + 2)?;
+
+    Ok(gatt::AddServiceParameters {
+        uuid,
+        service_type: to_service_type(bytes[uuid_len])?,
+        max_attribute_records: bytes[uuid_len + 1] as usize,
+    })
+}
+
+fn to_include_service_parameters(bytes: &[u8]) -> Result<gatt::IncludeServiceParameters, Error> {
+    require_len_at_least(bytes, 6)?;
+    Ok(gatt::IncludeServiceParameters {
+        service_handle: to_service_handle(&bytes[0..]),
+        include_handle_range: to_service_range(&bytes[2..])?,
+        include_uuid: to_trailing_uuid(bytes, 6)?,
+    })
+}
+
+fn to_add_characteristic_parameters(
+    bytes: &[u8],
+) -> Result<gatt::AddCharacteristicParameters, Error> {
+    require_len_at_least(bytes, 2)?;
+    let (characteristic_uuid, uuid_len) = to_uuid(&bytes[2..])?;
+
+    // Firmware before version 7.2 uses a single byte for the characteristic value length, so the
+    // total length of the command tells us which version the command was built for.
+    let value_len_index = 2 + uuid_len;
+    let (fw_version_before_v72, characteristic_value_len, next) =
+        if bytes.len() == value_len_index + 6 {
+            (true, bytes[value_len_index] as usize, value_len_index + 1)
+        } else {
+            require_len(bytes, value_len_index + 7)?;
+            (
+                false,
+                LittleEndian::read_u16(&bytes[value_len_index..]) as usize,
+                value_len_index + 2,
+            )
+        };
+
+    Ok(gatt::AddCharacteristicParameters {
+        service_handle: to_service_handle(&bytes[0..]),
+        characteristic_uuid,
+        characteristic_value_len,
+        characteristic_properties: gatt::CharacteristicProperty::from_bits_truncate(bytes[next]),
+        security_permissions: to_characteristic_permission(bytes[next + 1])?,
+        gatt_event_mask: to_characteristic_event(bytes[next + 2])?,
+        encryption_key_size: to_encryption_key_size(bytes[next + 3])?,
+        is_variable: to_bool(bytes[next + 4])?,
+        fw_version_before_v72,
+    })
+}
+
+fn to_add_descriptor_parameters(bytes: &[u8]) -> Result<gatt::AddDescriptorParameters<'_>, Error> {
+    require_len_at_least(bytes, 4)?;
+    let (descriptor_uuid, uuid_len) = to_uuid(&bytes[4..])?;
+    let value_len_index = 5 + uuid_len;
+    require_len_at_least(bytes, value_len_index + 1)?;
+    let next = value_len_index + 1 + bytes[value_len_index] as usize;
+    require_len(bytes, next + 5)?;
+
+    Ok(gatt::AddDescriptorParameters {
+        service_handle: to_service_handle(&bytes[0..]),
+        characteristic_handle: to_characteristic_handle(&bytes[2..]),
+        descriptor_uuid,
+        descriptor_value_max_len: bytes[4 + uuid_len] as usize,
+        descriptor_value: &bytes[value_len_index + 1..next],
+        security_permissions: gatt::DescriptorPermission::from_bits(bytes[next])
+            .ok_or(Error::BadDescriptorPermission(bytes[next]))?,
+        access_permissions: gatt::AccessPermission::from_bits(bytes[next + 1])
+            .ok_or(Error::BadAccessPermission(bytes[next + 1]))?,
+        gatt_event_mask: to_characteristic_event(bytes[next + 2])?,
+        encryption_key_size: to_encryption_key_size(bytes[next + 3])?,
+        is_variable: to_bool(bytes[next + 4])?,
+    })
+}
+
+fn to_read_by_type_parameters(bytes: &[u8]) -> Result<gatt::ReadByTypeParameters, Error> {
+    require_len_at_least(bytes, 6)?;
+    Ok(gatt::ReadByTypeParameters {
+        conn_handle: to_conn_handle(bytes),
+        attribute_handle_range: to_characteristic_range(&bytes[2..])?,
+        uuid: to_trailing_uuid(bytes, 6)?,
+    })
+}
+
+fn to_long_characteristic_read_parameters(
+    bytes: &[u8],
+) -> Result<gatt::LongCharacteristicReadParameters, Error> {
+    require_len(bytes, 6)?;
+    Ok(gatt::LongCharacteristicReadParameters {
+        conn_handle: to_conn_handle(bytes),
+        attribute: to_characteristic_handle(&bytes[2..]),
+        offset: LittleEndian::read_u16(&bytes[4..]) as usize,
+    })
+}
+
+fn to_multiple_characteristic_read(bytes: &[u8]) -> Result<MultipleCharacteristicRead, Error> {
+    const HANDLE_LEN: usize = 2;
+
+    require_len_at_least(bytes, 3)?;
+    let handle_count = bytes[2] as usize;
+    require_len(bytes, 3 + HANDLE_LEN * handle_count)?;
+
+    let mut read = MultipleCharacteristicRead {
+        conn_handle: to_conn_handle(bytes),
+        handle_count,
+        handles: [gatt::CharacteristicHandle(0); MAX_HANDLE_COUNT],
+    };
+    for (handle, chunk) in read.handles.iter_mut().zip(bytes[3..].chunks(HANDLE_LEN)) {
+        *handle = to_characteristic_handle(chunk);
+    }
+
+    Ok(read)
+}
+
+fn to_characteristic_value(bytes: &[u8]) -> Result<gatt::CharacteristicValue<'_>, Error> {
+    require_len_at_least(bytes, 5)?;
+    Ok(gatt::CharacteristicValue {
+        conn_handle: to_conn_handle(bytes),
+        characteristic_handle: to_characteristic_handle(&bytes[2..]),
+        value: to_length_prefixed(bytes, 4)?,
+    })
+}
+
+fn to_long_characteristic_value(bytes: &[u8]) -> Result<gatt::LongCharacteristicValue<'_>, Error> {
+    require_len_at_least(bytes, 7)?;
+    Ok(gatt::LongCharacteristicValue {
+        conn_handle: to_conn_handle(bytes),
+        characteristic_handle: to_characteristic_handle(&bytes[2..]),
+        offset: LittleEndian::read_u16(&bytes[4..]) as usize,
+        value: to_length_prefixed(bytes, 6)?,
+    })
+}
+
+fn to_write_response_parameters(bytes: &[u8]) -> Result<gatt::WriteResponseParameters<'_>, Error> {
+    require_len_at_least(bytes, 7)?;
+    let status = if to_bool(bytes[4])? {
+        Err(to_status(bytes[5])?)
+    } else {
+        Ok(())
+    };
+
+    Ok(gatt::WriteResponseParameters {
+        conn_handle: to_conn_handle(bytes),
+        attribute_handle: to_characteristic_handle(&bytes[2..]),
+        status,
+        value: to_length_prefixed(bytes, 6)?,
+    })
+}
@@ -5,13 +5,19 @@ extern crate byteorder;
 extern crate embedded_hal as hal;
 extern crate nb;
 
+use crate::command::{check_buffer_len, is_valid_conn_handle};
+use crate::EncodeError;
 use byteorder::{ByteOrder, LittleEndian};
 use core::time::Duration;
 pub use hci::host::{AdvertisingFilterPolicy, AdvertisingType, OwnAddressType};
 pub use hci::types::{ConnectionInterval, ExpectedConnectionLength, ScanWindow};
 pub use hci::{BdAddr, BdAddrType};
 
-/// GAP-specific commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG).
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
+/// GAP-specific commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG)
+/// and any other [`CommandWriter`](crate::CommandWriter).
 pub trait Commands {
     /// Type of communication errors.
     type Error;
@@ -45,11 +51,18 @@ pub trait Commands {
     ///   or
     ///   [ConnectableDirectedLowDutyCycle](bluetooth_hci::host::AdvertisingType::ConnectableDirectedLowDutyCycle).
     /// - [`BadAdvertisingInterval`](Error::BadAdvertisingInterval) if
-    ///   [`advertising_interval`](DiscoverableParameters::advertising_interval) is inverted.
-    ///   That is, if the min is greater than the max.
+    ///   [`advertising_interval`](DiscoverableParameters::advertising_interval) is out of range
+    ///   (20 ms to 10.24 s) or inverted (the min is greater than the max).
     /// - [`BadConnectionInterval`](Error::BadConnectionInterval) if
     ///   [`conn_interval`](DiscoverableParameters::conn_interval) is inverted. That is, both the
     ///   min and max are provided, and the min is greater than the max.
+    /// - [`ConnectionIntervalOutOfRange`](Error::ConnectionIntervalOutOfRange) if either limit of
+    ///   [`conn_interval`](DiscoverableParameters::conn_interval) is provided and is less than 7.5
+    ///   ms or greater than 4 seconds.
+    /// - [`BadLocalNameLength`](Error::BadLocalNameLength) if
+    ///   [`local_name`](DiscoverableParameters::local_name) is longer than 29 bytes.
+    /// - [`BadAdvertisingDataLength`](Error::BadAdvertisingDataLength) if
+    ///   [`advertising_data`](DiscoverableParameters::advertising_data) is longer than 31 bytes.
     ///
     /// # Generated evenst
     ///
@@ -80,11 +93,18 @@ pub trait Commands {
     ///   or
     ///   [ConnectableDirectedLowDutyCycle](bluetooth_hci::host::AdvertisingType::ConnectableDirectedLowDutyCycle).
     /// - [`BadAdvertisingInterval`](Error::BadAdvertisingInterval) if
-    ///   [`advertising_interval`](DiscoverableParameters::advertising_interval) is inverted.
-    ///   That is, if the min is greater than the max.
+    ///   [`advertising_interval`](DiscoverableParameters::advertising_interval) is out of range
+    ///   (20 ms to 10.24 s) or inverted (the min is greater than the max).
     /// - [`BadConnectionInterval`](Error::BadConnectionInterval) if
     ///   [`conn_interval`](DiscoverableParameters::conn_interval) is inverted. That is, both the
     ///   min and max are provided, and the min is greater than the max.
+    /// - [`ConnectionIntervalOutOfRange`](Error::ConnectionIntervalOutOfRange) if either limit of
+    ///   [`conn_interval`](DiscoverableParameters::conn_interval) is provided and is less than 7.5
+    ///   ms or greater than 4 seconds.
+    /// - [`BadLocalNameLength`](Error::BadLocalNameLength) if
+    ///   [`local_name`](DiscoverableParameters::local_name) is longer than 29 bytes.
+    /// - [`BadAdvertisingDataLength`](Error::BadAdvertisingDataLength) if
+    ///   [`advertising_data`](DiscoverableParameters::advertising_data) is longer than 31 bytes.
     ///
     /// # Generated evenst
     ///
@@ -153,10 +173,11 @@ pub trait Commands {
     ///
     /// - [BadEncryptionKeySizeRange](Error::BadEncryptionKeySizeRange) if the
     ///   [`encryption_key_size_range`](AuthenticationRequirements::encryption_key_size_range) min
-    ///   is greater than the max.
+    ///   is greater than the max, or either limit is outside of 7 to 16 bytes.
     /// - [BadFixedPin](Error::BadFixedPin) if the
     ///   [`fixed_pin`](AuthenticationRequirements::fixed_pin) is [Fixed](Pin::Fixed) with a value
     ///   greater than 999999.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors.
     ///
     /// # Generated events
@@ -263,6 +284,9 @@ pub trait Commands {
     /// handles of these characteristics are returned in the [event
     /// data](crate::event::command::GapInit).
     ///
+    /// The BlueNRG [variant](crate::Variant) does not support privacy or set the length of the
+    /// device name characteristic, so it only sends the role.
+    ///
     /// # Errors
     ///
     /// Only underlying communication errors are reported.
@@ -321,6 +345,9 @@ pub trait Commands {
     /// enabled, `address_type` may be [ResolvablePrivate](AddressType::ResolvablePrivate) or
     /// [NonResolvablePrivate](AddressType::NonResolvablePrivate).
     ///
+    /// The BlueNRG [variant](crate::Variant) does not take an address type, so `address_type` is
+    /// not sent.
+    ///
     /// # Errors
     ///
     /// - [BadAdvertisingType](Error::BadAdvertisingType) if the advertising type is not one
@@ -366,7 +393,10 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater than
+    ///   0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -378,7 +408,7 @@ pub trait Commands {
     fn peripheral_security_request(
         &mut self,
         params: &SecurityRequestParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// This command can be used to update the advertising data for a particular AD type. If the AD
     /// type specified does not exist, then it is added to the advertising data. If the overall
@@ -519,6 +549,9 @@ pub trait Commands {
     /// successfully. If this command is not given on receiving the event, the bonding procedure
     /// will timeout.
     ///
+    /// The BlueNRG [variant](crate::Variant) only supports one connection, so `conn_handle` is not
+    /// sent.
+    ///
     /// # Errors
     ///
     /// Only underlying communication errors are reported.
@@ -537,7 +570,10 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadScanWindow](Error::BadScanWindow) if the [scan
+    ///   window](DiscoveryProcedureParameters::scan_window) is out of range.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -558,13 +594,16 @@ pub trait Commands {
     fn start_limited_discovery_procedure(
         &mut self,
         params: &DiscoveryProcedureParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Start the general discovery procedure. The controller is commanded to start active scanning.
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadScanWindow](Error::BadScanWindow) if the [scan
+    ///   window](DiscoveryProcedureParameters::scan_window) is out of range.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -584,7 +623,7 @@ pub trait Commands {
     fn start_general_discovery_procedure(
         &mut self,
         params: &DiscoveryProcedureParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Start the name discovery procedure.
     ///
@@ -601,7 +640,12 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadScanWindow](Error::BadScanWindow) if the [scan
+    ///   window](NameDiscoveryProcedureParameters::scan_window) is out of range.
+    /// - [ConnectionIntervalOutOfRange](Error::ConnectionIntervalOutOfRange) if the [connection
+    ///   interval](NameDiscoveryProcedureParameters::conn_interval) is sent out of range.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated Events
     ///
@@ -613,7 +657,7 @@ pub trait Commands {
     fn start_name_discovery_procedure(
         &mut self,
         params: &NameDiscoveryProcedureParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Start the auto connection establishment procedure.
     ///
@@ -632,6 +676,12 @@ pub trait Commands {
     ///   (such that the serialized command would not fit in 255 bytes), a
     ///   [WhiteListTooLong](Error::WhiteListTooLong) is returned. The list cannot have more than 33
     ///   elements.
+    /// - [BadScanWindow](Error::BadScanWindow) if the [scan
+    ///   window](AutoConnectionEstablishmentParameters::scan_window) is out of range.
+    /// - [ConnectionIntervalOutOfRange](Error::ConnectionIntervalOutOfRange) if the [connection
+    ///   interval](AutoConnectionEstablishmentParameters::conn_interval) is sent out of range.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     fn start_auto_connection_establishment<'a>(
         &mut self,
         params: &AutoConnectionEstablishmentParameters<'a>,
@@ -653,11 +703,14 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadScanWindow](Error::BadScanWindow) if the [scan
+    ///   window](GeneralConnectionEstablishmentParameters::scan_window) is out of range.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     fn start_general_connection_establishment(
         &mut self,
         params: &GeneralConnectionEstablishmentParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Start a selective connection establishment procedure.
     ///
@@ -676,6 +729,10 @@ pub trait Commands {
     ///   long (such that the serialized command would not fit in 255 bytes), a
     ///   [WhiteListTooLong](Error::WhiteListTooLong) is returned. The list cannot have more than 35
     ///   elements.
+    /// - [BadScanWindow](Error::BadScanWindow) if the [scan
+    ///   window](SelectiveConnectionEstablishmentParameters::scan_window) is out of range.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     fn start_selective_connection_establishment<'a>(
         &mut self,
         params: &SelectiveConnectionEstablishmentParameters<'a>,
@@ -696,7 +753,12 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadScanWindow](Error::BadScanWindow) if the [scan
+    ///   window](ConnectionParameters::scan_window) is out of range.
+    /// - [ConnectionIntervalOutOfRange](Error::ConnectionIntervalOutOfRange) if the
+    ///   [connection interval](ConnectionParameters::conn_interval) is sent out of range.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -707,7 +769,10 @@ pub trait Commands {
     /// command [`terminate_procedure`](Commands::terminate_procedure) with the procedure_code set
     /// to
     /// [DirectConnectionEstablishment](crate::event::GapProcedure::DirectConnectionEstablishment).
-    fn create_connection(&mut self, params: &ConnectionParameters) -> nb::Result<(), Self::Error>;
+    fn create_connection(
+        &mut self,
+        params: &ConnectionParameters,
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// The GAP procedure(s) specified is terminated.
     ///
@@ -732,7 +797,12 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater than
+    ///   0x0EFF.
+    /// - [ConnectionIntervalOutOfRange](Error::ConnectionIntervalOutOfRange) if the
+    ///   [connection interval](ConnectionUpdateParameters::conn_interval) is sent out of range.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -744,7 +814,7 @@ pub trait Commands {
     fn start_connection_update(
         &mut self,
         params: &ConnectionUpdateParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Send the SM pairing request to start a pairing process. The authentication requirements and
     /// I/O capabilities should be set before issuing this command using the
@@ -754,7 +824,10 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater than
+    ///   0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -762,7 +835,10 @@ pub trait Commands {
     /// received. If [Success](hci::Status::Success) is returned in the command status event, a
     /// [Pairing Complete](crate::event::BlueNRGEvent::GapPairingComplete) event is returned after
     /// the pairing process is completed.
-    fn send_pairing_request(&mut self, params: &PairingRequest) -> nb::Result<(), Self::Error>;
+    fn send_pairing_request(
+        &mut self,
+        params: &PairingRequest,
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// This command tries to resolve the address provided with the IRKs present in its database.
     ///
@@ -807,6 +883,9 @@ pub trait Commands {
     /// - [WhiteListTooLong](Error::WhiteListTooLong) if the length of the white list would put the
     ///   packet length over 255 bytes. The exact number of addresses that can be in the white list
     ///   can range from 35 to 31, depending on the length of the advertising data.
+    /// - [UnsupportedByVariant](Error::UnsupportedByVariant) if the BlueNRG
+    ///   [variant](crate::Variant) is selected.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors.
     ///
     /// # Generated events
@@ -827,7 +906,10 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [UnsupportedByVariant](Error::UnsupportedByVariant) if the BlueNRG
+    ///   [variant](crate::Variant) is selected.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -836,7 +918,7 @@ pub trait Commands {
     fn start_observation_procedure(
         &mut self,
         params: &ObservationProcedureParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// The command finds whether the device, whose address is specified in the command, is
     /// bonded. If the device is using a resolvable private address and it has been bonded, then the
@@ -851,18 +933,89 @@ pub trait Commands {
     /// A [command complete](crate::event::command::ReturnParameters::GapIsDeviceBonded) event is
     /// generated.
     fn is_device_bonded(&mut self, addr: hci::host::PeerAddrType) -> nb::Result<(), Self::Error>;
+
+    /// Confirms or rejects the numeric comparison value shown in a [GAP Numeric Comparison
+    /// Value](crate::event::BlueNRGEvent::GapNumericComparisonValue) event during LE Secure
+    /// Connections pairing.
+    ///
+    /// Only the BlueNRG-1 and BlueNRG-2 support this command.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [command
+    /// complete](crate::event::command::ReturnParameters::GapNumericComparisonValueConfirm) event
+    /// is generated.
+    #[cfg(feature = "bluenrg-1-2")]
+    fn numeric_comparison_value_confirm(
+        &mut self,
+        conn_handle: hci::ConnectionHandle,
+        confirm: bool,
+    ) -> nb::Result<(), Self::Error>;
+
+    /// Notifies the peer of the progress of pass key entry on this device, when keypress
+    /// notifications are [supported](AuthenticationRequirements::keypress_notification_support).
+    ///
+    /// Only the BlueNRG-1 and BlueNRG-2 support this command.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [command complete](crate::event::command::ReturnParameters::GapPasskeyInput) event is
+    /// generated.
+    #[cfg(feature = "bluenrg-1-2")]
+    fn passkey_input(
+        &mut self,
+        conn_handle: hci::ConnectionHandle,
+        input: KeypressNotification,
+    ) -> nb::Result<(), Self::Error>;
+
+    /// Removes the device with the given address from the security database.
+    ///
+    /// Only the BlueNRG-1 and BlueNRG-2 support this command.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [command complete](crate::event::command::ReturnParameters::GapRemoveBondedDevice) event
+    /// is generated.
+    #[cfg(feature = "bluenrg-1-2")]
+    fn remove_bonded_device(
+        &mut self,
+        addr: hci::host::PeerAddrType,
+    ) -> nb::Result<(), Self::Error>;
+
+    /// Selects the LE meta events that the controller forwards to the host. This is the GAP
+    /// equivalent of the HCI LE Set Event Mask command.
+    ///
+    /// Only the BlueNRG-1 and BlueNRG-2 support this command.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [command complete](crate::event::command::ReturnParameters::GapSetLeEventMask) event is
+    /// generated.
+    #[cfg(feature = "bluenrg-1-2")]
+    fn set_le_event_mask(&mut self, mask: hci::host::LeEventFlags) -> nb::Result<(), Self::Error>;
 }
 
-impl<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, SpiError, GpioError> Commands
-    for crate::ActiveBlueNRG<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, GpioError>
+impl<T> Commands for T
 where
-    SPI: hal::blocking::spi::Transfer<u8, Error = SpiError>
-        + hal::blocking::spi::Write<u8, Error = SpiError>,
-    OutputPin1: hal::digital::v2::OutputPin<Error = GpioError>,
-    OutputPin2: hal::digital::v2::OutputPin<Error = GpioError>,
-    InputPin: hal::digital::v2::InputPin<Error = GpioError>,
+    T: crate::CommandWriter,
 {
-    type Error = crate::Error<SpiError, GpioError>;
+    type Error = T::Error;
 
     fn set_nondiscoverable(&mut self) -> nb::Result<(), Self::Error> {
         self.write_command(crate::opcode::GAP_SET_NONDISCOVERABLE, &[])
@@ -880,7 +1033,7 @@ where
         crate::opcode::GAP_SET_DISCOVERABLE
     );
 
-    impl_validate_params!(
+    impl_validate_variable_length_params!(
         set_direct_connectable,
         DirectConnectableParameters,
         crate::opcode::GAP_SET_DIRECT_CONNECTABLE
@@ -954,7 +1107,8 @@ where
         bytes[1] = privacy_enabled as u8;
         bytes[2] = dev_name_characteristic_len as u8;
 
-        self.write_command(crate::opcode::GAP_INIT, &bytes)
+        let len = if crate::is_ms() { 3 } else { 1 };
+        self.write_command(crate::opcode::GAP_INIT, &bytes[..len])
     }
 
     #[cfg(not(feature = "ms"))]
@@ -993,11 +1147,10 @@ where
             }
         }
 
-        self.write_command(
-            crate::opcode::GAP_SET_NONCONNECTABLE,
-            &[advertising_type as u8, address_type as u8],
-        )
-        .map_err(rewrap_error)
+        let bytes = [advertising_type as u8, address_type as u8];
+        let len = if crate::is_ms() { 2 } else { 1 };
+        self.write_command(crate::opcode::GAP_SET_NONCONNECTABLE, &bytes[..len])
+            .map_err(rewrap_error)
     }
 
     fn set_undirected_connectable(
@@ -1022,21 +1175,20 @@ where
         .map_err(rewrap_error)
     }
 
-    impl_params!(
+    impl_validate_params!(
         peripheral_security_request,
         SecurityRequestParameters,
         crate::opcode::GAP_PERIPHERAL_SECURITY_REQUEST
     );
 
     fn update_advertising_data(&mut self, data: &[u8]) -> nb::Result<(), Error<Self::Error>> {
-        const MAX_LENGTH: usize = 31;
-        if data.len() > MAX_LENGTH {
+        if data.len() > MAX_ADVERTISING_DATA_LENGTH {
             return Err(nb::Error::Other(Error::BadAdvertisingDataLength(
                 data.len(),
             )));
         }
 
-        let mut bytes = [0; 1 + MAX_LENGTH];
+        let mut bytes = [0; 1 + MAX_ADVERTISING_DATA_LENGTH];
         bytes[0] = data.len() as u8;
         bytes[1..=data.len()].copy_from_slice(data);
 
@@ -1103,22 +1255,24 @@ where
     fn allow_rebond(&mut self, conn_handle: hci::ConnectionHandle) -> nb::Result<(), Self::Error> {
         let mut bytes = [0; 2];
         LittleEndian::write_u16(&mut bytes, conn_handle.0);
-        self.write_command(crate::opcode::GAP_ALLOW_REBOND, &bytes)
+
+        let len = if crate::is_ms() { 2 } else { 0 };
+        self.write_command(crate::opcode::GAP_ALLOW_REBOND, &bytes[..len])
     }
 
-    impl_params!(
+    impl_validate_params!(
         start_limited_discovery_procedure,
         DiscoveryProcedureParameters,
         crate::opcode::GAP_START_LIMITED_DISCOVERY_PROCEDURE
     );
 
-    impl_params!(
+    impl_validate_params!(
         start_general_discovery_procedure,
         DiscoveryProcedureParameters,
         crate::opcode::GAP_START_GENERAL_DISCOVERY_PROCEDURE
     );
 
-    impl_params!(
+    impl_validate_params!(
         start_name_discovery_procedure,
         NameDiscoveryProcedureParameters,
         crate::opcode::GAP_START_NAME_DISCOVERY_PROCEDURE
@@ -1130,7 +1284,7 @@ where
         crate::opcode::GAP_START_AUTO_CONNECTION_ESTABLISHMENT
     );
 
-    impl_params!(
+    impl_validate_variable_length_params!(
         start_general_connection_establishment,
         GeneralConnectionEstablishmentParameters,
         crate::opcode::GAP_START_GENERAL_CONNECTION_ESTABLISHMENT
@@ -1141,7 +1295,7 @@ where
         SelectiveConnectionEstablishmentParameters<'a>,
         crate::opcode::GAP_START_SELECTIVE_CONNECTION_ESTABLISHMENT
     );
-    impl_params!(
+    impl_validate_params!(
         create_connection,
         ConnectionParameters,
         crate::opcode::GAP_CREATE_CONNECTION
@@ -1156,13 +1310,13 @@ where
             .map_err(rewrap_error)
     }
 
-    impl_params!(
+    impl_validate_params!(
         start_connection_update,
         ConnectionUpdateParameters,
         crate::opcode::GAP_START_CONNECTION_UPDATE
     );
 
-    impl_params!(
+    impl_validate_params!(
         send_pairing_request,
         PairingRequest,
         crate::opcode::GAP_SEND_PAIRING_REQUEST
@@ -1177,18 +1331,41 @@ where
     }
 
     #[cfg(feature = "ms")]
-    impl_validate_variable_length_params!(
-        set_broadcast_mode,
-        BroadcastModeParameters,
-        crate::opcode::GAP_SET_BROADCAST_MODE
-    );
+    fn set_broadcast_mode(
+        &mut self,
+        params: &BroadcastModeParameters,
+    ) -> nb::Result<(), Error<Self::Error>> {
+        if !crate::is_ms() {
+            return Err(nb::Error::Other(Error::UnsupportedByVariant));
+        }
+        params.validate().map_err(nb::Error::Other)?;
+
+        let mut bytes = [0; BroadcastModeParameters::MAX_LENGTH];
+        let len = params
+            .copy_into_slice(&mut bytes)
+            .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
+
+        self.write_command(crate::opcode::GAP_SET_BROADCAST_MODE, &bytes[..len])
+            .map_err(rewrap_error)
+    }
 
     #[cfg(feature = "ms")]
-    impl_params!(
-        start_observation_procedure,
-        ObservationProcedureParameters,
-        crate::opcode::GAP_START_OBSERVATION_PROCEDURE
-    );
+    fn start_observation_procedure(
+        &mut self,
+        params: &ObservationProcedureParameters,
+    ) -> nb::Result<(), Error<Self::Error>> {
+        if !crate::is_ms() {
+            return Err(nb::Error::Other(Error::UnsupportedByVariant));
+        }
+
+        let mut bytes = [0; ObservationProcedureParameters::LENGTH];
+        params
+            .copy_into_slice(&mut bytes)
+            .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
+
+        self.write_command(crate::opcode::GAP_START_OBSERVATION_PROCEDURE, &bytes)
+            .map_err(rewrap_error)
+    }
 
     fn is_device_bonded(&mut self, addr: hci::host::PeerAddrType) -> nb::Result<(), Self::Error> {
         let mut bytes = [0; 7];
@@ -1196,6 +1373,51 @@ where
 
         self.write_command(crate::opcode::GAP_IS_DEVICE_BONDED, &bytes)
     }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn numeric_comparison_value_confirm(
+        &mut self,
+        conn_handle: hci::ConnectionHandle,
+        confirm: bool,
+    ) -> nb::Result<(), Self::Error> {
+        let mut bytes = [0; 3];
+        LittleEndian::write_u16(&mut bytes[0..2], conn_handle.0);
+        bytes[2] = confirm as u8;
+
+        self.write_command(crate::opcode::GAP_NUMERIC_COMPARISON_VALUE_CONFIRM, &bytes)
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn passkey_input(
+        &mut self,
+        conn_handle: hci::ConnectionHandle,
+        input: KeypressNotification,
+    ) -> nb::Result<(), Self::Error> {
+        let mut bytes = [0; 3];
+        LittleEndian::write_u16(&mut bytes[0..2], conn_handle.0);
+        bytes[2] = input as u8;
+
+        self.write_command(crate::opcode::GAP_PASSKEY_INPUT, &bytes)
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn remove_bonded_device(
+        &mut self,
+        addr: hci::host::PeerAddrType,
+    ) -> nb::Result<(), Self::Error> {
+        let mut bytes = [0; 7];
+        addr.copy_into_slice(&mut bytes);
+
+        self.write_command(crate::opcode::GAP_REMOVE_BONDED_DEVICE, &bytes)
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn set_le_event_mask(&mut self, mask: hci::host::LeEventFlags) -> nb::Result<(), Self::Error> {
+        let mut bytes = [0; 8];
+        LittleEndian::write_u64(&mut bytes, mask.bits());
+
+        self.write_command(crate::opcode::GAP_SET_LE_EVENT_MASK, &bytes)
+    }
 }
 
 /// Potential errors from parameter validation.
@@ -1204,6 +1426,7 @@ where
 /// enumerates the potential validation errors. Must be specialized on the types of communication
 /// errors.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum Error<E> {
     /// For the [GAP Set Limited Discoverable](Commands::set_limited_discoverable) and
     /// [GAP Set Discoverable](Commands::set_discoverable) commands, the connection
@@ -1211,19 +1434,48 @@ pub enum Error<E> {
     /// first element, max as the second.
     BadConnectionInterval(Duration, Duration),
 
+    /// For the discovery and connection establishment procedures, the [scan window](ScanWindow)
+    /// is not sent as an interval and window from 2.5 ms to 10.24 seconds, with the window no
+    /// longer than the interval. Includes the provided interval and window.
+    BadScanWindow(Duration, Duration),
+
     /// For the [GAP Set Limited Discoverable](Commands::set_limited_discoverable) and
     /// [GAP Set Broadcast Mode](Commands::set_broadcast_mode) commands, the advertising
     /// type is disallowed.  Returns the invalid advertising type.
-    BadAdvertisingType(crate::AdvertisingType),
-
-    /// For the [GAP Set Limited Discoverable](Commands::set_limited_discoverable)
-    /// command, the advertising interval is inverted (that is, the max is less than the
-    /// min). Includes the provided range.
+    BadAdvertisingType(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::advertising_type")
+        )]
+        crate::AdvertisingType,
+    ),
+
+    /// For the [GAP Set Limited Discoverable](Commands::set_limited_discoverable), [GAP Set
+    /// Discoverable](Commands::set_discoverable), and [GAP Set Direct
+    /// Connectable](Commands::set_direct_connectable) commands, the advertising interval is
+    /// inverted (that is, the max is less than the min) or one of its limits is outside of 20 ms
+    /// to 10.24 seconds. Includes the provided range.
     BadAdvertisingInterval(Duration, Duration),
 
+    /// For the [GAP Set Limited Discoverable](Commands::set_limited_discoverable) and
+    /// [GAP Set Discoverable](Commands::set_discoverable) commands, one of the provided connection
+    /// interval limits is less than 7.5 ms or greater than 4 seconds. Includes the invalid limit.
+    ///
+    /// For the commands that take a [`ConnectionInterval`], one of its limits is sent as less than
+    /// 7.5 ms or more than 4 seconds. The interval is converted to units of 1.25 ms from whole
+    /// milliseconds, so a minimum of 7.5 ms is sent as 6.25 ms; use 8 ms instead.
+    ConnectionIntervalOutOfRange(Duration),
+
+    /// For the [GAP Set Limited Discoverable](Commands::set_limited_discoverable) and
+    /// [GAP Set Discoverable](Commands::set_discoverable) commands, the local name does not fit in
+    /// a single advertising data structure. It must be 29 bytes or less. Includes the length of the
+    /// provided name.
+    BadLocalNameLength(usize),
+
     /// For the [GAP Set Authentication
     /// Requirement](Commands::set_authentication_requirement) command, the encryption
-    /// key size range is inverted (the max is less than the min). Includes the provided range.
+    /// key size range is inverted (the max is less than the min), or one of its limits is outside
+    /// of 7 to 16 bytes. Includes the provided range.
     BadEncryptionKeySizeRange(u8, u8),
 
     /// For the [GAP Set Authentication
@@ -1237,16 +1489,40 @@ pub enum Error<E> {
     /// [AllowConnectionAndScan](crate::AdvertisingFilterPolicy::AllowConnectionAndScan) and
     /// [WhiteListConnectionAndScan](crate::AdvertisingFilterPolicy::WhiteListConnectionAndScan) are
     /// allowed.
-    BadAdvertisingFilterPolicy(crate::AdvertisingFilterPolicy),
-
-    /// For the [GAP Update Advertising Data](Commands::update_advertising_data) and [GAP
-    /// Set Broadcast Mode](Commands::set_broadcast_mode) commands, the advertising data
-    /// is too long. It must be 31 bytes or less. The length of the provided data is returned.
+    BadAdvertisingFilterPolicy(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::advertising_filter_policy")
+        )]
+        crate::AdvertisingFilterPolicy,
+    ),
+
+    /// For the [GAP Update Advertising Data](Commands::update_advertising_data), [GAP Set Limited
+    /// Discoverable](Commands::set_limited_discoverable), [GAP Set
+    /// Discoverable](Commands::set_discoverable), and [GAP Set Broadcast
+    /// Mode](Commands::set_broadcast_mode) commands, the advertising data is too long. It must be
+    /// 31 bytes or less. The length of the provided data is returned.
     BadAdvertisingDataLength(usize),
 
+    /// For the [GAP Peripheral Security Request](Commands::peripheral_security_request), [GAP
+    /// Start Connection Update](Commands::start_connection_update), and [GAP Send Pairing
+    /// Request](Commands::send_pairing_request) commands, the connection handle is greater than
+    /// 0x0EFF, the largest handle allowed by the Bluetooth specification. Includes the invalid
+    /// handle.
+    BadConnectionHandle(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        hci::ConnectionHandle,
+    ),
+
     /// For the [GAP Terminate](Commands::terminate) command, the termination reason was
     /// not one of the allowed reason. The reason is returned.
-    BadTerminationReason(hci::Status<crate::event::Status>),
+    BadTerminationReason(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// For the [GAP Start Auto Connection
     /// Establishment](Commands::start_auto_connection_establishment) or [GAP Start
@@ -1264,10 +1540,64 @@ pub enum Error<E> {
     /// provided bitfield had no bits set.
     NoProcedure,
 
+    /// The command is only supported by the BlueNRG-MS, and the BlueNRG [variant](crate::Variant)
+    /// is selected.
+    UnsupportedByVariant,
+
+    /// The parameters could not be serialized into the command buffer.
+    Encode(crate::EncodeError),
+
     /// Underlying communication error.
     Comm(E),
 }
 
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for Error<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::BadConnectionInterval(min, max) => {
+                defmt::write!(f, "BadConnectionInterval({}, {})", min, max)
+            }
+            Error::BadScanWindow(interval, window) => {
+                defmt::write!(f, "BadScanWindow({}, {})", interval, window)
+            }
+            Error::BadAdvertisingType(value) => {
+                defmt::write!(f, "BadAdvertisingType({})", defmt::Debug2Format(value))
+            }
+            Error::BadAdvertisingInterval(min, max) => {
+                defmt::write!(f, "BadAdvertisingInterval({}, {})", min, max)
+            }
+            Error::ConnectionIntervalOutOfRange(value) => {
+                defmt::write!(f, "ConnectionIntervalOutOfRange({})", value)
+            }
+            Error::BadLocalNameLength(value) => defmt::write!(f, "BadLocalNameLength({})", value),
+            Error::BadEncryptionKeySizeRange(min, max) => {
+                defmt::write!(f, "BadEncryptionKeySizeRange({}, {})", min, max)
+            }
+            Error::BadFixedPin(value) => defmt::write!(f, "BadFixedPin({})", value),
+            Error::BadAdvertisingFilterPolicy(value) => defmt::write!(
+                f,
+                "BadAdvertisingFilterPolicy({})",
+                defmt::Debug2Format(value)
+            ),
+            Error::BadAdvertisingDataLength(value) => {
+                defmt::write!(f, "BadAdvertisingDataLength({})", value)
+            }
+            Error::BadConnectionHandle(conn_handle) => {
+                defmt::write!(f, "BadConnectionHandle({})", Remote(conn_handle))
+            }
+            Error::BadTerminationReason(status) => {
+                defmt::write!(f, "BadTerminationReason({})", Remote(status))
+            }
+            Error::WhiteListTooLong => defmt::write!(f, "WhiteListTooLong"),
+            Error::NoProcedure => defmt::write!(f, "NoProcedure"),
+            Error::UnsupportedByVariant => defmt::write!(f, "UnsupportedByVariant"),
+            Error::Encode(value) => defmt::write!(f, "Encode({})", value),
+            Error::Comm(e) => defmt::write!(f, "Comm({})", e),
+        }
+    }
+}
+
 fn rewrap_error<E>(e: nb::Error<E>) -> nb::Error<Error<E>> {
     match e {
         nb::Error::WouldBlock => nb::Error::WouldBlock,
@@ -1275,13 +1605,97 @@ fn rewrap_error<E>(e: nb::Error<E>) -> nb::Error<Error<E>> {
     }
 }
 
+impl<E> From<Error<E>> for crate::CommandError<E> {
+    fn from(e: Error<E>) -> Self {
+        use self::Error::*;
+
+        let validation = match e {
+            Comm(e) => return crate::CommandError::Comm(e),
+            Encode(e) => crate::ValidationError::Encode(e),
+            UnsupportedByVariant => crate::ValidationError::UnsupportedByVariant,
+            BadConnectionInterval(a, b) => crate::ValidationError::Gap(BadConnectionInterval(a, b)),
+            BadScanWindow(a, b) => crate::ValidationError::Gap(BadScanWindow(a, b)),
+            BadAdvertisingType(a) => crate::ValidationError::Gap(BadAdvertisingType(a)),
+            BadAdvertisingInterval(a, b) => {
+                crate::ValidationError::Gap(BadAdvertisingInterval(a, b))
+            }
+            ConnectionIntervalOutOfRange(a) => {
+                crate::ValidationError::Gap(ConnectionIntervalOutOfRange(a))
+            }
+            BadLocalNameLength(a) => crate::ValidationError::Gap(BadLocalNameLength(a)),
+            BadEncryptionKeySizeRange(a, b) => {
+                crate::ValidationError::Gap(BadEncryptionKeySizeRange(a, b))
+            }
+            BadFixedPin(a) => crate::ValidationError::Gap(BadFixedPin(a)),
+            BadAdvertisingFilterPolicy(a) => {
+                crate::ValidationError::Gap(BadAdvertisingFilterPolicy(a))
+            }
+            BadAdvertisingDataLength(a) => crate::ValidationError::Gap(BadAdvertisingDataLength(a)),
+            BadConnectionHandle(a) => crate::ValidationError::Gap(BadConnectionHandle(a)),
+            BadTerminationReason(a) => crate::ValidationError::Gap(BadTerminationReason(a)),
+            WhiteListTooLong => crate::ValidationError::Gap(WhiteListTooLong),
+            NoProcedure => crate::ValidationError::Gap(NoProcedure),
+        };
+        crate::CommandError::Validation(validation)
+    }
+}
+
+// Limits from the Bluetooth specification, v4.1, Vol 2, Part E, Section 7.8.5 and Vol 3, Part C,
+// Section 11.1.8, and the maximum length of advertising data.
+const MIN_ADVERTISING_INTERVAL: Duration = Duration::from_millis(20);
+const MAX_ADVERTISING_INTERVAL: Duration = Duration::from_millis(10240);
+const MIN_CONN_INTERVAL: Duration = Duration::from_micros(7500);
+const MAX_CONN_INTERVAL: Duration = Duration::from_secs(4);
+const MAX_ADVERTISING_DATA_LENGTH: usize = 31;
+
+// Checks the scan window as it is sent, in units of 0.625 ms, against the ranges of the Bluetooth
+// specification, v4.1, Vol 2, Part E, Section 7.8.10.
+fn validate_scan_window<E>(scan_window: &ScanWindow) -> Result<(), Error<E>> {
+    const MIN_VALUE: u16 = 0x0004;
+    const MAX_VALUE: u16 = 0x4000;
+
+    let mut bytes = [0; 4];
+    scan_window.copy_into_slice(&mut bytes);
+    let interval = LittleEndian::read_u16(&bytes[0..2]);
+    let window = LittleEndian::read_u16(&bytes[2..4]);
+    if !(MIN_VALUE..=MAX_VALUE).contains(&interval) || window < MIN_VALUE || window > interval {
+        return Err(Error::BadScanWindow(
+            scan_window.interval(),
+            scan_window.window(),
+        ));
+    }
+
+    Ok(())
+}
+
+// Checks the connection interval as it is sent, in units of 1.25 ms, against the range of the
+// Bluetooth specification, v4.1, Vol 2, Part E, Section 7.8.12.
+fn validate_conn_interval<E>(conn_interval: &ConnectionInterval) -> Result<(), Error<E>> {
+    const MIN_VALUE: u16 = 0x0006;
+    const MAX_VALUE: u16 = 0x0C80;
+
+    let mut bytes = [0; 8];
+    conn_interval.copy_into_slice(&mut bytes);
+    let (min, max) = conn_interval.interval();
+    if LittleEndian::read_u16(&bytes[0..2]) < MIN_VALUE {
+        return Err(Error::ConnectionIntervalOutOfRange(min));
+    }
+    if LittleEndian::read_u16(&bytes[2..4]) > MAX_VALUE {
+        return Err(Error::ConnectionIntervalOutOfRange(max));
+    }
+
+    Ok(())
+}
+
 fn to_conn_interval_value(d: Duration) -> u16 {
     // Connection interval value: T = N * 1.25 ms
     // We have T, we need to return N.
     // N = T / 1.25 ms
     //   = 4 * T / 5 ms
-    let millis = (d.as_secs() * 1000) as u32 + d.subsec_millis();
-    (4 * millis / 5) as u16
+    //
+    // Out-of-range durations wrap, the same as in release builds, instead of panicking on overflow.
+    let millis = (d.as_secs().wrapping_mul(1000) as u32).wrapping_add(d.subsec_millis());
+    (millis.wrapping_mul(4) / 5) as u16
 }
 
 fn to_connection_length_value(d: Duration) -> u16 {
@@ -1290,12 +1704,17 @@ fn to_connection_length_value(d: Duration) -> u16 {
     // N = T / 0.625 ms
     //   = T / 625 us
     // 1600 = 1_000_000 / 625
-    (1600 * d.as_secs() as u32 + (d.subsec_micros() / 625)) as u16
+    (d.as_secs() as u32)
+        .wrapping_mul(1600)
+        .wrapping_add(d.subsec_micros() / 625) as u16
 }
 
 /// Parameters for the
 /// [`set_limited_discoverable`](Commands::set_limited_discoverable) and
 /// [`set_discoverable`](Commands::set_discoverable) commands.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DiscoverableParameters<'a, 'b> {
     /// Advertising method for the device.
     ///
@@ -1303,6 +1722,11 @@ pub struct DiscoverableParameters<'a, 'b> {
     /// [ConnectableUndirected](bluetooth_hci::host::AdvertisingType::ConnectableUndirected),
     /// [ScannableUndirected](bluetooth_hci::host::AdvertisingType::ScannableUndirected), or
     /// [NonConnectableUndirected](bluetooth_hci::host::AdvertisingType::NonConnectableUndirected).
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::advertising_type")
+    )]
     pub advertising_type: AdvertisingType,
 
     /// Range of advertising for non-directed advertising.
@@ -1314,9 +1738,19 @@ pub struct DiscoverableParameters<'a, 'b> {
     pub advertising_interval: Option<(Duration, Duration)>,
 
     /// Address type for this device.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::own_address_type")
+    )]
     pub address_type: OwnAddressType,
 
     /// Filter policy for this device.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::advertising_filter_policy")
+    )]
     pub filter_policy: AdvertisingFilterPolicy,
 
     /// Name of the device.
@@ -1344,25 +1778,50 @@ impl<'a, 'b> DiscoverableParameters<'a, 'b> {
         }
 
         if let Some(interval) = self.advertising_interval {
-            if interval.0 > interval.1 {
+            if interval.0 < MIN_ADVERTISING_INTERVAL
+                || interval.1 > MAX_ADVERTISING_INTERVAL
+                || interval.0 > interval.1
+            {
                 return Err(Error::BadAdvertisingInterval(interval.0, interval.1));
             }
         }
 
+        for limit in [self.conn_interval.0, self.conn_interval.1]
+            .iter()
+            .flatten()
+        {
+            if *limit < MIN_CONN_INTERVAL || *limit > MAX_CONN_INTERVAL {
+                return Err(Error::ConnectionIntervalOutOfRange(*limit));
+            }
+        }
+
         if let (Some(min), Some(max)) = self.conn_interval {
             if min > max {
                 return Err(Error::BadConnectionInterval(min, max));
             }
         }
 
+        // The name is sent as one AD structure: a length byte, the AD type, and the name itself.
+        const MAX_LOCAL_NAME_LENGTH: usize = MAX_ADVERTISING_DATA_LENGTH - 2;
+        if let Some(LocalName::Shortened(name)) | Some(LocalName::Complete(name)) = self.local_name
+        {
+            if name.len() > MAX_LOCAL_NAME_LENGTH {
+                return Err(Error::BadLocalNameLength(name.len()));
+            }
+        }
+
+        if self.advertising_data.len() > MAX_ADVERTISING_DATA_LENGTH {
+            return Err(Error::BadAdvertisingDataLength(self.advertising_data.len()));
+        }
+
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
         const NO_SPECIFIC_CONN_INTERVAL: u16 = 0xFFFF;
 
         let len = self.required_len();
-        assert!(len <= bytes.len());
+        check_buffer_len(bytes, len)?;
 
         let no_duration = Duration::from_secs(0);
         let no_interval = (no_duration, no_duration);
@@ -1405,22 +1864,18 @@ impl<'a, 'b> DiscoverableParameters<'a, 'b> {
         let conn_interval_index = advertising_data_len_index + 1 + self.advertising_data.len();
         LittleEndian::write_u16(
             &mut bytes[conn_interval_index..],
-            if self.conn_interval.0.is_some() {
-                to_conn_interval_value(self.conn_interval.0.unwrap())
-            } else {
-                NO_SPECIFIC_CONN_INTERVAL
-            },
+            self.conn_interval
+                .0
+                .map_or(NO_SPECIFIC_CONN_INTERVAL, to_conn_interval_value),
         );
         LittleEndian::write_u16(
             &mut bytes[(conn_interval_index + 2)..],
-            if self.conn_interval.1.is_some() {
-                to_conn_interval_value(self.conn_interval.1.unwrap())
-            } else {
-                NO_SPECIFIC_CONN_INTERVAL
-            },
+            self.conn_interval
+                .1
+                .map_or(NO_SPECIFIC_CONN_INTERVAL, to_conn_interval_value),
         );
 
-        len
+        Ok(len)
     }
 
     fn required_len(&self) -> usize {
@@ -1440,6 +1895,9 @@ impl<'a, 'b> DiscoverableParameters<'a, 'b> {
 }
 
 /// Allowed types for the local name.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum LocalName<'a> {
     /// The shortened local name.
     Shortened(&'a [u8]),
@@ -1450,8 +1908,16 @@ pub enum LocalName<'a> {
 
 /// Parameters for the
 /// [`set_direct_connectable`](Commands::set_direct_connectable) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DirectConnectableParameters {
     /// Address type of this device.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::own_address_type")
+    )]
     pub own_address_type: OwnAddressType,
 
     #[cfg(feature = "ms")]
@@ -1461,9 +1927,16 @@ pub struct DirectConnectableParameters {
     /// [ConnectableDirectedHighDutyCycle](bluetooth_hci::host::AdvertisingType::ConnectableDirectedHighDutyCycle),
     /// or
     /// [ConnectableDirectedLowDutyCycle](bluetooth_hci::host::AdvertisingType::ConnectableDirectedLowDutyCycle).
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::advertising_type")
+    )]
     pub advertising_type: AdvertisingType,
 
     /// Initiator's Bluetooth address.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::BdAddrTypeDef"))]
     pub initiator_address: BdAddrType,
 
     #[cfg(feature = "ms")]
@@ -1476,25 +1949,23 @@ pub struct DirectConnectableParameters {
 
 impl DirectConnectableParameters {
     #[cfg(not(feature = "ms"))]
-    const LENGTH: usize = 8;
+    const MAX_LENGTH: usize = 8;
 
     #[cfg(feature = "ms")]
-    const LENGTH: usize = 13;
+    const MAX_LENGTH: usize = 13;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        // The BlueNRG variant does not send the advertising type or interval.
         #[cfg(feature = "ms")]
-        {
-            const MIN_DURATION: Duration = Duration::from_millis(20);
-            const MAX_DURATION: Duration = Duration::from_millis(10240);
-
+        if crate::is_ms() {
             match self.advertising_type {
                 AdvertisingType::ConnectableDirectedHighDutyCycle
                 | AdvertisingType::ConnectableDirectedLowDutyCycle => (),
                 _ => return Err(Error::BadAdvertisingType(self.advertising_type)),
             }
 
-            if self.advertising_interval.0 < MIN_DURATION
-                || self.advertising_interval.1 > MAX_DURATION
+            if self.advertising_interval.0 < MIN_ADVERTISING_INTERVAL
+                || self.advertising_interval.1 > MAX_ADVERTISING_INTERVAL
                 || self.advertising_interval.0 > self.advertising_interval.1
             {
                 return Err(Error::BadAdvertisingInterval(
@@ -1507,18 +1978,18 @@ impl DirectConnectableParameters {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert_eq!(bytes.len(), Self::LENGTH);
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        let len = if crate::is_ms() { 13 } else { 8 };
+        check_buffer_len(bytes, len)?;
 
         bytes[0] = self.own_address_type as u8;
 
-        #[cfg(not(feature = "ms"))]
-        {
+        if !crate::is_ms() {
             self.initiator_address.copy_into_slice(&mut bytes[1..8]);
         }
 
         #[cfg(feature = "ms")]
-        {
+        if crate::is_ms() {
             bytes[1] = self.advertising_type as u8;
             self.initiator_address.copy_into_slice(&mut bytes[2..9]);
             LittleEndian::write_u16(
@@ -1530,6 +2001,8 @@ impl DirectConnectableParameters {
                 to_connection_length_value(self.advertising_interval.1),
             );
         }
+
+        Ok(len)
     }
 }
 
@@ -1537,6 +2010,8 @@ impl DirectConnectableParameters {
 /// Capability](Commands::set_io_capability) command.
 #[repr(u8)]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum IoCapability {
     /// Display Only
     Display = 0x00,
@@ -1552,13 +2027,28 @@ pub enum IoCapability {
 
 /// Parameters for the [GAP Set Authentication
 /// Requirement](Commands::set_authentication_requirement) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AuthenticationRequirements {
     /// Is MITM (man-in-the-middle) protection required?
     pub mitm_protection_required: bool,
 
     /// Out-of-band authentication data.
+    ///
+    /// The BlueNRG-1 and BlueNRG-2 do not take out-of-band data with the authentication
+    /// requirements.
+    #[cfg(not(feature = "bluenrg-1-2"))]
     pub out_of_band_auth: OutOfBandAuthentication,
 
+    /// Support for LE Secure Connections pairing.
+    #[cfg(feature = "bluenrg-1-2")]
+    pub secure_connections: SecureConnectionsSupport,
+
+    /// Are keypress notifications supported during pass key entry?
+    #[cfg(feature = "bluenrg-1-2")]
+    pub keypress_notification_support: bool,
+
     /// Minimum and maximum size of the encryption key.
     pub encryption_key_size_range: (u8, u8),
 
@@ -1567,13 +2057,27 @@ pub struct AuthenticationRequirements {
 
     /// Is bonding required?
     pub bonding_required: bool,
+
+    /// Type of the identity address distributed during pairing.
+    #[cfg(feature = "bluenrg-1-2")]
+    pub identity_address_type: IdentityAddressType,
 }
 
 impl AuthenticationRequirements {
+    #[cfg(not(feature = "bluenrg-1-2"))]
     const LENGTH: usize = 26;
 
+    #[cfg(feature = "bluenrg-1-2")]
+    const LENGTH: usize = 12;
+
     fn validate<E>(&self) -> Result<(), Error<E>> {
-        if self.encryption_key_size_range.0 > self.encryption_key_size_range.1 {
+        const MIN_ENCRYPTION_KEY_SIZE: u8 = 7;
+        const MAX_ENCRYPTION_KEY_SIZE: u8 = 16;
+
+        if self.encryption_key_size_range.0 > self.encryption_key_size_range.1
+            || self.encryption_key_size_range.0 < MIN_ENCRYPTION_KEY_SIZE
+            || self.encryption_key_size_range.1 > MAX_ENCRYPTION_KEY_SIZE
+        {
             return Err(Error::BadEncryptionKeySizeRange(
                 self.encryption_key_size_range.0,
                 self.encryption_key_size_range.1,
@@ -1589,8 +2093,9 @@ impl AuthenticationRequirements {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert_eq!(bytes.len(), Self::LENGTH);
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         bytes[0] = self.mitm_protection_required as u8;
         match self.out_of_band_auth {
@@ -1618,10 +2123,92 @@ impl AuthenticationRequirements {
         }
 
         bytes[25] = self.bonding_required as u8;
+
+        Ok(())
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
+
+        bytes[0] = self.bonding_required as u8;
+        bytes[1] = self.mitm_protection_required as u8;
+        bytes[2] = self.secure_connections as u8;
+        bytes[3] = self.keypress_notification_support as u8;
+        bytes[4] = self.encryption_key_size_range.0;
+        bytes[5] = self.encryption_key_size_range.1;
+
+        match self.fixed_pin {
+            Pin::Requested => {
+                bytes[6] = 1;
+                bytes[7..11].copy_from_slice(&[0; 4]);
+            }
+            Pin::Fixed(pin) => {
+                bytes[6] = 0;
+                LittleEndian::write_u32(&mut bytes[7..11], pin);
+            }
+        }
+
+        bytes[11] = self.identity_address_type as u8;
+
+        Ok(())
     }
 }
 
-/// Options for [`out_of_band_auth`](AuthenticationRequirements::out_of_band_auth).
+/// Options for [`secure_connections`](AuthenticationRequirements::secure_connections).
+#[cfg(feature = "bluenrg-1-2")]
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum SecureConnectionsSupport {
+    /// Only legacy pairing is used.
+    NotSupported = 0x00,
+    /// LE Secure Connections pairing is used if the peer supports it; otherwise legacy pairing is
+    /// used.
+    Optional = 0x01,
+    /// Only LE Secure Connections pairing is accepted.
+    Mandatory = 0x02,
+}
+
+/// Options for [`identity_address_type`](AuthenticationRequirements::identity_address_type).
+#[cfg(feature = "bluenrg-1-2")]
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum IdentityAddressType {
+    /// The public device address.
+    Public = 0x00,
+    /// The static random device address.
+    Random = 0x01,
+}
+
+/// Pass key entry progress, sent with [GAP Passkey Input](Commands::passkey_input) and received in
+/// the [GAP Keypress Notification](crate::event::BlueNRGEvent::GapKeypressNotification) event.
+#[cfg(feature = "bluenrg-1-2")]
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum KeypressNotification {
+    /// Pass key entry started.
+    EntryStarted = 0x00,
+    /// A digit was entered.
+    DigitEntered = 0x01,
+    /// A digit was erased.
+    DigitErased = 0x02,
+    /// The pass key was cleared.
+    Cleared = 0x03,
+    /// Pass key entry completed.
+    EntryCompleted = 0x04,
+}
+
+/// Options for the `out_of_band_auth` field of [`AuthenticationRequirements`]. The BlueNRG-1 and
+/// BlueNRG-2 do not accept out-of-band data in their authentication requirements.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum OutOfBandAuthentication {
     /// Out Of Band authentication not enabled
     Disabled,
@@ -1630,6 +2217,9 @@ pub enum OutOfBandAuthentication {
 }
 
 /// Options for [`fixed_pin`](AuthenticationRequirements::fixed_pin).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Pin {
     /// Do not use fixed pin during the pairing process.  In this case, GAP will generate a [GAP
     /// Pass Key Request](crate::event::BlueNRGEvent::GapPassKeyRequest) event to the host.
@@ -1642,6 +2232,9 @@ pub enum Pin {
 
 /// Options for the [GAP Authorization Response](Commands::authorization_response).
 #[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Authorization {
     /// Accept the connection.
     Authorized = 0x01,
@@ -1651,6 +2244,7 @@ pub enum Authorization {
 
 bitflags! {
     /// Roles for a [GAP service](Commands::init).
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Role: u8 {
         /// Peripheral
         const PERIPHERAL = 0x01;
@@ -1663,10 +2257,15 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(Role, u8);
+
 /// Indicates the type of address being used in the advertising packets, for the
 /// [`set_nonconnectable`](Commands::set_nonconnectable).
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AddressType {
     /// Public device address.
     Public = 0x00,
@@ -1681,9 +2280,15 @@ pub enum AddressType {
 
 /// Parameters for the [GAP Peripheral Security
 /// Request](Commands::peripheral_security_request) parameters.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SecurityRequestParameters {
     /// Handle of the connection on which the peripheral security request will
     /// be sent (ignored in peripheral-only role).
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Is bonding required?
@@ -1693,20 +2298,46 @@ pub struct SecurityRequestParameters {
     pub mitm_protection: bool,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for SecurityRequestParameters {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "SecurityRequestParameters {{ conn_handle: {}, bonding: {}, mitm_protection: ",
+            Remote(&self.conn_handle),
+            self.bonding
+        );
+        defmt::write!(f, "{} }}", self.mitm_protection)
+    }
+}
+
 impl SecurityRequestParameters {
     const LENGTH: usize = 4;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert_eq!(bytes.len(), Self::LENGTH);
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        Ok(())
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         bytes[2] = self.bonding as u8;
         bytes[3] = self.mitm_protection as u8;
+
+        Ok(())
     }
 }
 
 /// Available types of advertising data.
 #[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AdvertisingDataType {
     /// Flags
     Flags = 0x01,
@@ -1746,6 +2377,7 @@ pub enum AdvertisingDataType {
 
 bitflags! {
     /// Event types for [GAP Set Event Mask](Commands::set_event_mask).
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct EventFlags: u16 {
         /// [Limited Discoverable](::event::BlueNRGEvent::GapLimitedDiscoverableTimeout)
         const LIMITED_DISCOVERABLE_TIMEOUT = 0x0001;
@@ -1762,14 +2394,30 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(EventFlags, u16);
+
 /// Parameters for the [GAP Limited
 /// Discovery](Commands::start_limited_discovery_procedure) and [GAP General
 /// Discovery](Commands::start_general_discovery_procedure) procedures.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DiscoveryProcedureParameters {
     /// Scanning window for the discovery procedure.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ScanWindowDef::serialize")
+    )]
     pub scan_window: ScanWindow,
 
     /// Address type of this device.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::own_address_type")
+    )]
     pub own_address_type: hci::host::OwnAddressType,
 
     /// If true, duplicate devices are filtered out.
@@ -1779,39 +2427,75 @@ pub struct DiscoveryProcedureParameters {
 impl DiscoveryProcedureParameters {
     const LENGTH: usize = 6;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert_eq!(bytes.len(), Self::LENGTH);
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        validate_scan_window(&self.scan_window)
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         self.scan_window.copy_into_slice(&mut bytes[0..4]);
         bytes[4] = self.own_address_type as u8;
         bytes[5] = self.filter_duplicates as u8;
+
+        Ok(())
     }
 }
 
 /// Parameters for the [GAP Name Discovery](Commands::start_name_discovery_procedure)
 /// procedure.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct NameDiscoveryProcedureParameters {
     /// Scanning window for the discovery procedure.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ScanWindowDef::serialize")
+    )]
     pub scan_window: ScanWindow,
 
     /// Address of the connected device
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::peer_addr_type"))]
     pub peer_address: hci::host::PeerAddrType,
 
     /// Address type of this device.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::own_address_type")
+    )]
     pub own_address_type: hci::host::OwnAddressType,
 
     /// Connection interval parameters.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ConnectionIntervalDef::serialize")
+    )]
     pub conn_interval: ConnectionInterval,
 
     /// Expected connection length
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::expected_connection_length")
+    )]
     pub expected_connection_length: ExpectedConnectionLength,
 }
 
 impl NameDiscoveryProcedureParameters {
     const LENGTH: usize = 24;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert_eq!(bytes.len(), Self::LENGTH);
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        validate_scan_window(&self.scan_window)?;
+        validate_conn_interval(&self.conn_interval)
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         self.scan_window.copy_into_slice(&mut bytes[0..4]);
         self.peer_address.copy_into_slice(&mut bytes[4..11]);
@@ -1819,50 +2503,101 @@ impl NameDiscoveryProcedureParameters {
         self.conn_interval.copy_into_slice(&mut bytes[12..20]);
         self.expected_connection_length
             .copy_into_slice(&mut bytes[20..24]);
+
+        Ok(())
     }
 }
 
 /// Parameters for the [GAP Start Auto Connection
 /// Establishment](Commands::start_auto_connection_establishment) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AutoConnectionEstablishmentParameters<'a> {
     /// Scanning window for connection establishment.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ScanWindowDef::serialize")
+    )]
     pub scan_window: ScanWindow,
 
     /// Address type of this device.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::own_address_type")
+    )]
     pub own_address_type: hci::host::OwnAddressType,
 
     /// Connection interval parameters.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ConnectionIntervalDef::serialize")
+    )]
     pub conn_interval: ConnectionInterval,
 
     /// Expected connection length
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::expected_connection_length")
+    )]
     pub expected_connection_length: ExpectedConnectionLength,
 
-    #[cfg(not(feature = "ms"))]
     /// Reconnection address is used as our address during the procedure. The address has been
-    /// previously notified to the application through the
-    /// [ReconnectionAddress](::event::Event::ReconnectionAddress) event.
+    /// previously notified to the application through the [GAP Reconnection
+    /// Address](crate::event::BlueNRGEvent::GapReconnectionAddress) event.
+    ///
+    /// Only the BlueNRG [variant](crate::Variant) sends the reconnection address; the BlueNRG-MS
+    /// ignores it.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::option_bd_addr"))]
     pub reconnection_address: Option<hci::BdAddr>,
 
     /// Addresses to white-list for automatic connection.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::peer_addr_types")
+    )]
     pub white_list: &'a [hci::host::PeerAddrType],
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AutoConnectionEstablishmentParameters<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "AutoConnectionEstablishmentParameters {{ scan_window: {}, own_address_type: ",
+            defmt::Debug2Format(&self.scan_window)
+        );
+        defmt::write!(
+            f,
+            "{}, conn_interval: {}, expected_connection_length: {}, reconnection_address: ",
+            defmt::Debug2Format(&self.own_address_type),
+            defmt::Debug2Format(&self.conn_interval),
+            defmt::Debug2Format(&self.expected_connection_length)
+        );
+        defmt::write!(
+            f,
+            "{}, white_list: {} }}",
+            Remote(&self.reconnection_address),
+            defmt::Debug2Format(&self.white_list)
+        )
+    }
+}
+
 impl<'a> AutoConnectionEstablishmentParameters<'a> {
     const MAX_LENGTH: usize = 249;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
         const MAX_WHITE_LIST_LENGTH: usize = 33;
-        if self.white_list.len() > MAX_WHITE_LIST_LENGTH - if cfg!(feature = "ms") { 0 } else { 1 }
-        {
+        if self.white_list.len() > MAX_WHITE_LIST_LENGTH - if crate::is_ms() { 0 } else { 1 } {
             return Err(Error::WhiteListTooLong);
         }
 
-        Ok(())
+        validate_scan_window(&self.scan_window)?;
+        validate_conn_interval(&self.conn_interval)
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
         let len = self.len();
-        assert!(bytes.len() >= len);
+        check_buffer_len(bytes, len)?;
 
         self.scan_window.copy_into_slice(&mut bytes[0..4]);
         bytes[4] = self.own_address_type as u8;
@@ -1870,8 +2605,8 @@ impl<'a> AutoConnectionEstablishmentParameters<'a> {
         self.expected_connection_length
             .copy_into_slice(&mut bytes[13..17]);
 
-        #[cfg(not(feature = "ms"))]
-        {
+        // The BlueNRG-MS parameters do not include a reconnection address.
+        if !crate::is_ms() {
             if let Some(addr) = self.reconnection_address {
                 bytes[17] = 1;
                 bytes[18..24].copy_from_slice(&addr.0);
@@ -1880,7 +2615,7 @@ impl<'a> AutoConnectionEstablishmentParameters<'a> {
             }
         }
 
-        let index = if cfg!(feature = "ms") { 17 } else { 24 };
+        let index = if crate::is_ms() { 17 } else { 24 };
 
         bytes[index] = self.white_list.len() as u8;
         let index = index + 1;
@@ -1888,50 +2623,82 @@ impl<'a> AutoConnectionEstablishmentParameters<'a> {
             self.white_list[i].copy_into_slice(&mut bytes[(index + 7 * i)..(index + 7 * (i + 1))]);
         }
 
-        len
+        Ok(len)
     }
 
     fn len(&self) -> usize {
-        let reconn_addr_len = if cfg!(feature = "ms") { 0 } else { 7 };
+        let reconn_addr_len = if crate::is_ms() { 0 } else { 7 };
         18 + reconn_addr_len + 7 * self.white_list.len()
     }
 }
 
 /// Parameters for the [GAP Start General Connection
 /// Establishment](Commands::start_general_connection_establishment) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GeneralConnectionEstablishmentParameters {
     /// Scanning window for connection establishment.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ScanWindowDef::serialize")
+    )]
     pub scan_window: ScanWindow,
 
     /// Address type of this device.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::own_address_type")
+    )]
     pub own_address_type: hci::host::OwnAddressType,
 
     /// If true, only report unique devices.
     pub filter_duplicates: bool,
 
-    #[cfg(not(feature = "ms"))]
     /// Reconnection address is used as our address during the procedure. The address has been
-    /// previously notified to the application through the
-    /// [ReconnectionAddress](::event::Event::ReconnectionAddress) event.
+    /// previously notified to the application through the [GAP Reconnection
+    /// Address](crate::event::BlueNRGEvent::GapReconnectionAddress) event.
+    ///
+    /// Only the BlueNRG [variant](crate::Variant) sends the reconnection address; the BlueNRG-MS
+    /// ignores it.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::option_bd_addr"))]
     pub reconnection_address: Option<hci::BdAddr>,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GeneralConnectionEstablishmentParameters {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GeneralConnectionEstablishmentParameters {{ scan_window: {}, own_address_type: ",
+            defmt::Debug2Format(&self.scan_window)
+        );
+        defmt::write!(
+            f,
+            "{}, filter_duplicates: {}, reconnection_address: {} }}",
+            defmt::Debug2Format(&self.own_address_type),
+            self.filter_duplicates,
+            Remote(&self.reconnection_address)
+        )
+    }
+}
+
 impl GeneralConnectionEstablishmentParameters {
-    #[cfg(not(feature = "ms"))]
-    const LENGTH: usize = 13;
+    const MAX_LENGTH: usize = 13;
 
-    #[cfg(feature = "ms")]
-    const LENGTH: usize = 6;
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        validate_scan_window(&self.scan_window)
+    }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert!(bytes.len() >= Self::LENGTH);
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        let len = if crate::is_ms() { 6 } else { 13 };
+        check_buffer_len(bytes, len)?;
 
         self.scan_window.copy_into_slice(&mut bytes[0..4]);
         bytes[4] = self.own_address_type as u8;
         bytes[5] = self.filter_duplicates as u8;
 
-        #[cfg(not(feature = "ms"))]
-        {
+        // The BlueNRG-MS parameters do not include a reconnection address.
+        if !crate::is_ms() {
             if let Some(addr) = self.reconnection_address {
                 bytes[6] = 1;
                 bytes[7..13].copy_from_slice(&addr.0)
@@ -1939,25 +2706,47 @@ impl GeneralConnectionEstablishmentParameters {
                 bytes[6..13].copy_from_slice(&[0; 7])
             }
         }
+
+        Ok(len)
     }
 }
 
 /// Parameters for the [GAP Start Selective Connection
 /// Establishment](Commands::start_selective_connection_establishment) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct SelectiveConnectionEstablishmentParameters<'a> {
     /// Type of scanning
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::scan_type"))]
     pub scan_type: hci::host::ScanType,
 
     /// Scanning window for connection establishment.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ScanWindowDef::serialize")
+    )]
     pub scan_window: ScanWindow,
 
     /// Address type of this device.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::own_address_type")
+    )]
     pub own_address_type: hci::host::OwnAddressType,
 
     /// If true, only report unique devices.
     pub filter_duplicates: bool,
 
     /// Addresses to white-list for automatic connection.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::peer_addr_types")
+    )]
     pub white_list: &'a [hci::host::PeerAddrType],
 }
 
@@ -1970,12 +2759,12 @@ impl<'a> SelectiveConnectionEstablishmentParameters<'a> {
             return Err(Error::WhiteListTooLong);
         }
 
-        Ok(())
+        validate_scan_window(&self.scan_window)
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
         let len = self.len();
-        assert!(bytes.len() >= len);
+        check_buffer_len(bytes, len)?;
 
         bytes[0] = self.scan_type as u8;
         self.scan_window.copy_into_slice(&mut bytes[1..5]);
@@ -1986,7 +2775,7 @@ impl<'a> SelectiveConnectionEstablishmentParameters<'a> {
             self.white_list[i].copy_into_slice(&mut bytes[(8 + 7 * i)..(8 + 7 * (i + 1))]);
         }
 
-        len
+        Ok(len)
     }
 
     fn len(&self) -> usize {
@@ -2000,6 +2789,7 @@ pub type ConnectionParameters = NameDiscoveryProcedureParameters;
 
 bitflags! {
     /// Roles for a [GAP service](Commands::init).
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Procedure: u8 {
         /// [Limited Discovery](Commands::start_limited_discovery_procedure) procedure.
         const LIMITED_DISCOVERY = 0x01;
@@ -2023,34 +2813,86 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(Procedure, u8);
+
 /// Parameters for the [`start_connection_update`](Commands::start_connection_update)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ConnectionUpdateParameters {
     /// Handle of the connection for which the update procedure has to be started.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Updated connection interval for the connection.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ConnectionIntervalDef::serialize")
+    )]
     pub conn_interval: ConnectionInterval,
 
     /// Expected length of connection event needed for this connection.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::expected_connection_length")
+    )]
     pub expected_connection_length: ExpectedConnectionLength,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for ConnectionUpdateParameters {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ConnectionUpdateParameters {{ conn_handle: {}, conn_interval: ",
+            Remote(&self.conn_handle)
+        );
+        defmt::write!(
+            f,
+            "{}, expected_connection_length: {} }}",
+            defmt::Debug2Format(&self.conn_interval),
+            defmt::Debug2Format(&self.expected_connection_length)
+        )
+    }
+}
+
 impl ConnectionUpdateParameters {
     const LENGTH: usize = 14;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        validate_conn_interval(&self.conn_interval)
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
+
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         self.conn_interval.copy_into_slice(&mut bytes[2..10]);
         self.expected_connection_length
             .copy_into_slice(&mut bytes[10..14]);
+
+        Ok(())
     }
 }
 
 /// Parameters for the [`send_pairing_request`](Commands::send_pairing_request)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct PairingRequest {
     /// Handle of the connection for which the pairing request has to be sent.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Whether pairing request has to be sent if the device is previously bonded or not. If false,
@@ -2061,24 +2903,55 @@ pub struct PairingRequest {
     pub force_reencrypt: bool,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for PairingRequest {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "PairingRequest {{ conn_handle: {}, force_rebond: {}, force_reencrypt: {} }}",
+            Remote(&self.conn_handle),
+            self.force_rebond,
+            self.force_reencrypt
+        )
+    }
+}
+
 impl PairingRequest {
     const LENGTH: usize = 3;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert!(bytes.len() >= Self::LENGTH);
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        Ok(())
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         bytes[2] = self.force_rebond as u8 | ((self.force_reencrypt as u8) << 1);
+
+        Ok(())
     }
 }
 
 #[cfg(feature = "ms")]
 /// Parameters for the [GAP Set Broadcast Mode](Commands::set_broadcast_mode) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct BroadcastModeParameters<'a, 'b> {
     /// Advertising type and interval.
     ///
     /// Only the [ScannableUndirected](hci::types::AdvertisingType::ScannableUndirected) and
     /// [NonConnectableUndirected](hci::types::AdvertisingType::NonConnectableUndirected).
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::advertising_interval")
+    )]
     pub advertising_interval: hci::types::AdvertisingInterval,
 
     /// Type of this device's address.
@@ -2100,6 +2973,11 @@ pub struct BroadcastModeParameters<'a, 'b> {
     /// 31 and 35 entries, depending on the length of
     /// [`advertising_data`](BroadcastModeParameters::advertising_data). Shorter advertising data
     /// allows more white list entries.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::peer_addr_types")
+    )]
     pub white_list: &'b [hci::host::PeerAddrType],
 }
 
@@ -2108,8 +2986,6 @@ impl<'a, 'b> BroadcastModeParameters<'a, 'b> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
-        const MAX_ADVERTISING_DATA_LENGTH: usize = 31;
-
         match self.advertising_interval.advertising_type() {
             hci::types::AdvertisingType::ScannableUndirected
             | hci::types::AdvertisingType::NonConnectableUndirected => (),
@@ -2134,8 +3010,8 @@ impl<'a, 'b> BroadcastModeParameters<'a, 'b> {
             1 + 7 * self.white_list.len() // white_list
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(self.len() <= bytes.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, self.len())?;
 
         self.advertising_interval.copy_into_slice(&mut bytes[0..5]);
         bytes[5] = self.own_address_type as u8;
@@ -2149,18 +3025,28 @@ impl<'a, 'b> BroadcastModeParameters<'a, 'b> {
             index += 7;
         }
 
-        index
+        Ok(index)
     }
 }
 
 #[cfg(feature = "ms")]
 /// Parameters for the [GAP Start Observation Procedure](Commands::start_observation_procedure)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ObservationProcedureParameters {
     /// Scanning window.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ScanWindowDef::serialize")
+    )]
     pub scan_window: hci::types::ScanWindow,
 
     /// Active or passive scanning
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::scan_type"))]
     pub scan_type: hci::host::ScanType,
 
     /// Address type of this device.
@@ -2175,12 +3061,14 @@ pub struct ObservationProcedureParameters {
 impl ObservationProcedureParameters {
     const LENGTH: usize = 7;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert!(bytes.len() >= Self::LENGTH);
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         self.scan_window.copy_into_slice(&mut bytes[0..4]);
         bytes[4] = self.scan_type as u8;
         bytes[5] = self.own_address_type as u8;
         bytes[6] = self.filter_duplicates as u8;
+
+        Ok(())
     }
 }
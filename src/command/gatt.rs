@@ -5,9 +5,15 @@ extern crate byteorder;
 extern crate embedded_hal as hal;
 extern crate nb;
 
+use crate::command::{check_buffer_len, is_valid_conn_handle};
+use crate::EncodeError;
 use byteorder::{ByteOrder, LittleEndian};
 
-/// GATT-specific commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG).
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
+/// GATT-specific commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG)
+/// and any other [`CommandWriter`](crate::CommandWriter).
 pub trait Commands {
     /// Type of communication errors.
     type Error;
@@ -44,13 +50,16 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadMaxAttributeRecords](Error::BadMaxAttributeRecords) if the [maximum number of
+    ///   attribute records](AddServiceParameters::max_attribute_records) is 0 or greater than 255.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
     /// A [Command complete](crate::event::command::ReturnParameters::GattAddService) event is
     /// generated.
-    fn add_service(&mut self, params: &AddServiceParameters) -> nb::Result<(), Self::Error>;
+    fn add_service(&mut self, params: &AddServiceParameters) -> nb::Result<(), Error<Self::Error>>;
 
     /// Include a service to another service.
     ///
@@ -58,20 +67,33 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadServiceHandle](Error::BadServiceHandle) if the
+    ///   [service handle](IncludeServiceParameters::service_handle) is 0.
+    /// - [BadIncludeHandleRange](Error::BadIncludeHandleRange) if the
+    ///   [range](IncludeServiceParameters::include_handle_range) of the included service starts at
+    ///   handle 0 or is inverted.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
     /// A [Command complete](crate::event::command::ReturnParameters::GattIncludeService) event is
     /// generated.
-    fn include_service(&mut self, params: &IncludeServiceParameters)
-        -> nb::Result<(), Self::Error>;
+    fn include_service(
+        &mut self,
+        params: &IncludeServiceParameters,
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Add a characteristic to a service.
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [CharacteristicValueTooLong](Error::CharacteristicValueTooLong) if the [maximum
+    ///   characteristic value length](AddCharacteristicParameters::characteristic_value_len) is
+    ///   greater than 512 bytes, or greater than 255 bytes if
+    ///   [`fw_version_before_v72`](AddCharacteristicParameters::fw_version_before_v72) is set.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -82,7 +104,7 @@ pub trait Commands {
     fn add_characteristic(
         &mut self,
         params: &AddCharacteristicParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Add a characteristic descriptor to a service.
     ///
@@ -94,6 +116,7 @@ pub trait Commands {
     /// - [DescriptorBufferTooLong](Error::DescriptorBufferTooLong) if the [descriptor
     ///   value maximum length](AddDescriptorParameters::descriptor_value_max_len) is so large that
     ///   the serialized structure may be more than 255 bytes. The maximum size is 227.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors.
     ///
     /// # Generated events
@@ -114,6 +137,9 @@ pub trait Commands {
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the [characteristic
     ///   value](UpdateCharacteristicValueParameters::value] is so long that the command packet
     ///   would exceed 255 bytes. The maximum allowed length is 249 bytes.
+    /// - [BadOffset](Error::BadOffset) if the [offset](UpdateCharacteristicValueParameters::offset)
+    ///   is greater than 255. The offset is sent as a single byte.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors.
     ///
     /// # Generated events
@@ -157,7 +183,8 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -166,7 +193,7 @@ pub trait Commands {
     fn delete_included_service(
         &mut self,
         params: &DeleteIncludedServiceParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Allows masking events from the GATT.
     ///
@@ -174,19 +201,20 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
     /// A [command complete](crate::event::command::ReturnParameters::GattSetEventMask) event is
     /// generated on the completion of the command.
-    fn set_event_mask(&mut self, mask: Event) -> nb::Result<(), Self::Error>;
+    fn set_event_mask(&mut self, mask: Event) -> nb::Result<(), Error<Self::Error>>;
 
     /// Allows masking events from the GATT.
     ///
     /// This function exists to prevent name conflicts with other Commands traits' set_event_mask
     /// methods.
-    fn set_gatt_event_mask(&mut self, mask: Event) -> nb::Result<(), Self::Error> {
+    fn set_gatt_event_mask(&mut self, mask: Event) -> nb::Result<(), Error<Self::Error>> {
         self.set_event_mask(mask)
     }
 
@@ -234,6 +262,11 @@ pub trait Commands {
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the [attribute
     ///   value](FindByTypeValueParameters::value) to find is too long to fit in one command packet
     ///   (255 bytes). The maximum length is 246 bytes.
+    /// - [BadAttributeType](Error::BadAttributeType) if the [UUID](FindByTypeValueParameters::uuid)
+    ///   has no 16-bit form.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors.
     ///
     /// # Generated events
@@ -252,7 +285,10 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -264,7 +300,7 @@ pub trait Commands {
     fn read_by_type_request(
         &mut self,
         params: &ReadByTypeParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Sends a Read By Group Type request.
     ///
@@ -275,7 +311,10 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -287,7 +326,7 @@ pub trait Commands {
     fn read_by_group_type_request(
         &mut self,
         params: &ReadByTypeParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Sends a Prepare Write request.
     ///
@@ -295,6 +334,11 @@ pub trait Commands {
     ///
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the attribute value is so long that the
     ///   serialized command would be longer than 255 bytes. The maximum length is 248 bytes.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [BadOffset](Error::BadOffset) if the [offset](WriteRequest::offset) is greater
+    ///   than 512, the maximum length of an attribute value.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying comminication errors
     ///
     /// # Generated events
@@ -369,7 +413,8 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -382,7 +427,7 @@ pub trait Commands {
         &mut self,
         conn_handle: hci::ConnectionHandle,
         uuid: Uuid,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Start the procedure to find all included services.
     ///
@@ -426,7 +471,8 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -441,7 +487,7 @@ pub trait Commands {
         conn_handle: hci::ConnectionHandle,
         attribute_handle_range: Range<CharacteristicHandle>,
         uuid: Uuid,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Start the procedure to discover all characteristic descriptors on the server.
     ///
@@ -485,7 +531,8 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -500,13 +547,19 @@ pub trait Commands {
         conn_handle: hci::ConnectionHandle,
         characteristic_handle_range: Range<CharacteristicHandle>,
         uuid: Uuid,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Start the procedure to read a long characteristic value.
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [BadOffset](Error::BadOffset) if the
+    ///   [offset](LongCharacteristicReadParameters::offset) is greater than 512, the maximum
+    ///   length of an attribute value.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -518,7 +571,7 @@ pub trait Commands {
     fn read_long_characteristic_value(
         &mut self,
         params: &LongCharacteristicReadParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Start a procedure to read multiple characteristic values from a server.
     ///
@@ -530,6 +583,11 @@ pub trait Commands {
     /// - [TooManyHandlesToRead](Error::TooManyHandlesToRead) if the number of handles to read would
     ///   cause the length of the serialized command to exceed 255 bytes. The maximum number of
     ///   handles is 126.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [TooFewHandlesToRead](Error::TooFewHandlesToRead) if fewer than 2
+    ///   [handles](MultipleCharacteristicReadParameters::handles) are provided.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -550,6 +608,9 @@ pub trait Commands {
     ///
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the [value](CharacteristicValue::value)
     ///   is too long to fit in one command packet. The maximum length is 250 bytes.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -569,6 +630,11 @@ pub trait Commands {
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the
     ///   [value](LongCharacteristicValue::value) is too long to fit in one command packet. The
     ///   maximum length is 248 bytes.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [BadOffset](Error::BadOffset) if the [offset](LongCharacteristicValue::offset) is greater
+    ///   than 512, the maximum length of an attribute value.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -591,6 +657,11 @@ pub trait Commands {
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the
     ///   [value](LongCharacteristicValue::value) is too long to fit in one command packet. The
     ///   maximum length is 248 bytes.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [BadOffset](Error::BadOffset) if the [offset](LongCharacteristicValue::offset) is greater
+    ///   than 512, the maximum length of an attribute value.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -613,6 +684,11 @@ pub trait Commands {
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the
     ///   [value](LongCharacteristicValue::value) is too long to fit in one command packet. The
     ///   maximum length is 248 bytes.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [BadOffset](Error::BadOffset) if the [offset](LongCharacteristicValue::offset) is greater
+    ///   than 512, the maximum length of an attribute value.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -632,7 +708,13 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [BadOffset](Error::BadOffset) if the
+    ///   [offset](LongCharacteristicReadParameters::offset) is greater than 512, the maximum
+    ///   length of an attribute value.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -644,7 +726,7 @@ pub trait Commands {
     fn read_long_characteristic_descriptor(
         &mut self,
         params: &LongCharacteristicReadParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Start the procedure to write a characteristic descriptor value.
     ///
@@ -652,6 +734,9 @@ pub trait Commands {
     ///
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the [value](CharacteristicValue::value)
     ///   is too long to fit in one command packet. The maximum length is 250 bytes.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -690,6 +775,9 @@ pub trait Commands {
     ///
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the [value](CharacteristicValue::value)
     ///   is too long to fit in one command packet. The maximum length is 250 bytes.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -708,6 +796,9 @@ pub trait Commands {
     ///
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the [value](CharacteristicValue::value)
     ///   is too long to fit in one command packet. The maximum length is 250 bytes.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -746,6 +837,9 @@ pub trait Commands {
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the [attribute
     ///   value](WriteResponseParameters::value) is so long that the serialized command cannot fit
     ///   in one packet. The maximum length is 250 bytes.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater
+    ///   than 0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -784,7 +878,8 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -793,7 +888,7 @@ pub trait Commands {
     fn set_security_permission(
         &mut self,
         params: &SecurityPermissionParameters,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// This command sets the value of a descriptor.
     ///
@@ -802,6 +897,10 @@ pub trait Commands {
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the length of the descriptor value is
     ///   so long that the serialized command would not fit in one packet. The maximum length is 246
     ///   bytes.
+    /// - [BadOffset](Error::BadOffset) if the
+    ///   [offset](DescriptorValueParameters::offset) is greater than 512, the maximum
+    ///   length of an attribute value.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -834,7 +933,9 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [UnsupportedByVariant](Error::UnsupportedByVariant) if the BlueNRG
+    ///   [variant](crate::Variant) is selected.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -845,7 +946,7 @@ pub trait Commands {
         &mut self,
         handle: CharacteristicHandle,
         offset: usize,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// Update the Attribute Value of a Characteristic belonging to a specified service.
     ///
@@ -857,6 +958,14 @@ pub trait Commands {
     ///
     /// - [ValueBufferTooLong](Error::ValueBufferTooLong) if the characteristic value is so long
     ///   that the command would not fit in one packet. The maximum length is 245 bytes.
+    /// - [BadOffset](Error::BadOffset) if the
+    ///   [offset](UpdateLongCharacteristicValueParameters::offset) is greater than 512, the maximum
+    ///   length of an attribute value.
+    /// - [CharacteristicValueTooLong](Error::CharacteristicValueTooLong) if the [total
+    ///   length](UpdateLongCharacteristicValueParameters::total_len) is greater than 512 bytes.
+    /// - [UnsupportedByVariant](Error::UnsupportedByVariant) if the BlueNRG
+    ///   [variant](crate::Variant) is selected.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors are reported.
     ///
     /// # Generated events
@@ -870,34 +979,29 @@ pub trait Commands {
     ) -> nb::Result<(), Error<Self::Error>>;
 }
 
-impl<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, SpiError, GpioError> Commands
-    for crate::ActiveBlueNRG<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, GpioError>
+impl<T> Commands for T
 where
-    SPI: hal::blocking::spi::Transfer<u8, Error = SpiError>
-        + hal::blocking::spi::Write<u8, Error = SpiError>,
-    OutputPin1: hal::digital::v2::OutputPin<Error = GpioError>,
-    OutputPin2: hal::digital::v2::OutputPin<Error = GpioError>,
-    InputPin: hal::digital::v2::InputPin<Error = GpioError>,
+    T: crate::CommandWriter,
 {
-    type Error = crate::Error<SpiError, GpioError>;
+    type Error = T::Error;
 
     fn init(&mut self) -> nb::Result<(), Self::Error> {
         self.write_command(crate::opcode::GATT_INIT, &[])
     }
 
-    impl_variable_length_params!(
+    impl_validate_variable_length_params!(
         add_service,
         AddServiceParameters,
         crate::opcode::GATT_ADD_SERVICE
     );
 
-    impl_variable_length_params!(
+    impl_validate_variable_length_params!(
         include_service,
         IncludeServiceParameters,
         crate::opcode::GATT_INCLUDE_SERVICE
     );
 
-    impl_variable_length_params!(
+    impl_validate_variable_length_params!(
         add_characteristic,
         AddCharacteristicParameters,
         crate::opcode::GATT_ADD_CHARACTERISTIC
@@ -971,13 +1075,13 @@ where
         crate::opcode::GATT_FIND_BY_TYPE_VALUE_REQUEST
     );
 
-    impl_variable_length_params!(
+    impl_validate_variable_length_params!(
         read_by_type_request,
         ReadByTypeParameters,
         crate::opcode::GATT_READ_BY_TYPE_REQUEST
     );
 
-    impl_variable_length_params!(
+    impl_validate_variable_length_params!(
         read_by_group_type_request,
         ReadByTypeParameters,
         crate::opcode::GATT_READ_BY_GROUP_TYPE_REQUEST
@@ -1025,15 +1129,18 @@ where
         &mut self,
         conn_handle: hci::ConnectionHandle,
         uuid: Uuid,
-    ) -> nb::Result<(), Self::Error> {
+    ) -> nb::Result<(), Error<Self::Error>> {
         let mut bytes = [0; 19];
         LittleEndian::write_u16(&mut bytes, conn_handle.0);
-        let end = 2 + uuid.copy_into_slice(&mut bytes[2..]);
+        let end = 2 + uuid
+            .copy_into_slice(&mut bytes[2..])
+            .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
 
         self.write_command(
             crate::opcode::GATT_DISCOVER_PRIMARY_SERVICES_BY_UUID,
             &bytes[..end],
         )
+        .map_err(rewrap_error)
     }
 
     fn find_included_services(
@@ -1070,17 +1177,20 @@ where
         conn_handle: hci::ConnectionHandle,
         attribute_handle_range: Range<CharacteristicHandle>,
         uuid: Uuid,
-    ) -> nb::Result<(), Self::Error> {
+    ) -> nb::Result<(), Error<Self::Error>> {
         let mut bytes = [0; 23];
         LittleEndian::write_u16(&mut bytes[0..2], conn_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], attribute_handle_range.from.0);
         LittleEndian::write_u16(&mut bytes[4..6], attribute_handle_range.to.0);
-        let uuid_len = uuid.copy_into_slice(&mut bytes[6..]);
+        let uuid_len = uuid
+            .copy_into_slice(&mut bytes[6..])
+            .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
 
         self.write_command(
             crate::opcode::GATT_DISCOVER_CHARACTERISTICS_BY_UUID,
             &bytes[..6 + uuid_len],
         )
+        .map_err(rewrap_error)
     }
 
     fn discover_all_characteristic_descriptors(
@@ -1116,20 +1226,23 @@ where
         conn_handle: hci::ConnectionHandle,
         characteristic_handle_range: Range<CharacteristicHandle>,
         uuid: Uuid,
-    ) -> nb::Result<(), Self::Error> {
+    ) -> nb::Result<(), Error<Self::Error>> {
         let mut bytes = [0; 23];
         LittleEndian::write_u16(&mut bytes[0..2], conn_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], characteristic_handle_range.from.0);
         LittleEndian::write_u16(&mut bytes[4..6], characteristic_handle_range.to.0);
-        let uuid_len = uuid.copy_into_slice(&mut bytes[6..]);
+        let uuid_len = uuid
+            .copy_into_slice(&mut bytes[6..])
+            .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
 
         self.write_command(
             crate::opcode::GATT_READ_CHARACTERISTIC_BY_UUID,
             &bytes[..6 + uuid_len],
         )
+        .map_err(rewrap_error)
     }
 
-    impl_params!(
+    impl_validate_params!(
         read_long_characteristic_value,
         LongCharacteristicReadParameters,
         crate::opcode::GATT_READ_LONG_CHARACTERISTIC_VALUE
@@ -1165,7 +1278,7 @@ where
         crate::opcode::GATT_WRITE_LONG_CHARACTERISTIC_DESCRIPTOR
     );
 
-    impl_params!(
+    impl_validate_params!(
         read_long_characteristic_descriptor,
         LongCharacteristicReadParameters,
         crate::opcode::GATT_READ_LONG_CHARACTERISTIC_DESCRIPTOR
@@ -1248,20 +1361,40 @@ where
         &mut self,
         handle: CharacteristicHandle,
         offset: usize,
-    ) -> nb::Result<(), Self::Error> {
+    ) -> nb::Result<(), Error<Self::Error>> {
+        if !crate::is_ms() {
+            return Err(nb::Error::Other(Error::UnsupportedByVariant));
+        }
+
         let mut bytes = [0; 3];
         LittleEndian::write_u16(&mut bytes, handle.0);
         bytes[2] = offset as u8;
 
         self.write_command(crate::opcode::GATT_READ_HANDLE_VALUE_OFFSET, &bytes)
+            .map_err(rewrap_error)
     }
 
     #[cfg(feature = "ms")]
-    impl_validate_variable_length_params!(
-        update_long_characteristic_value<'a>,
-        UpdateLongCharacteristicValueParameters<'a>,
-        crate::opcode::GATT_UPDATE_LONG_CHARACTERISTIC_VALUE
-    );
+    fn update_long_characteristic_value<'a>(
+        &mut self,
+        params: &UpdateLongCharacteristicValueParameters<'a>,
+    ) -> nb::Result<(), Error<Self::Error>> {
+        if !crate::is_ms() {
+            return Err(nb::Error::Other(Error::UnsupportedByVariant));
+        }
+        params.validate().map_err(nb::Error::Other)?;
+
+        let mut bytes = [0; UpdateLongCharacteristicValueParameters::MAX_LENGTH];
+        let len = params
+            .copy_into_slice(&mut bytes)
+            .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
+
+        self.write_command(
+            crate::opcode::GATT_UPDATE_LONG_CHARACTERISTIC_VALUE,
+            &bytes[..len],
+        )
+        .map_err(rewrap_error)
+    }
 }
 
 /// Potential errors from parameter validation.
@@ -1270,6 +1403,7 @@ where
 /// enumerates the potential validation errors. Must be specialized on the types of communication
 /// errors.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error<E> {
     /// For the [Add Characteristic Descriptor](Commands::add_characteristic_descriptor) command:
     /// the [descriptor value](AddDescriptorParameters::descriptor_value) is longer than the
@@ -1291,10 +1425,96 @@ pub enum Error<E> {
     /// the serialized command to be more than 255 bytes. The maximum length is 126 handles.
     TooManyHandlesToRead,
 
+    /// For the [Read Multiple Characteristic Values](Commands::read_multiple_characteristic_values)
+    /// command: fewer than 2 [handles](MultipleCharacteristicReadParameters::handles) were
+    /// provided. The ATT Read Multiple Request requires at least 2 handles.
+    TooFewHandlesToRead,
+
+    /// For commands addressed to a remote device: the connection handle is greater than 0x0EFF, the
+    /// largest handle allowed by the Bluetooth specification. Includes the invalid handle.
+    BadConnectionHandle(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        hci::ConnectionHandle,
+    ),
+
+    /// For the [Include Service](Commands::include_service) command: the
+    /// [service handle](IncludeServiceParameters::service_handle) is 0, which is not a valid
+    /// attribute handle.
+    BadServiceHandle(ServiceHandle),
+
+    /// For the [Include Service](Commands::include_service) command: the
+    /// [range](IncludeServiceParameters::include_handle_range) of the included service starts at
+    /// handle 0, which is not a valid attribute handle, or is inverted. Includes the invalid range.
+    BadIncludeHandleRange(Range<ServiceHandle>),
+
+    /// For the [Add Service](Commands::add_service) command: the [maximum number of attribute
+    /// records](AddServiceParameters::max_attribute_records) must be between 1 and 255. Includes
+    /// the invalid value.
+    BadMaxAttributeRecords(usize),
+
+    /// For the [Add Characteristic](Commands::add_characteristic) and [Update Long Characteristic
+    /// Value](Commands::update_long_characteristic_value) commands: the characteristic value length
+    /// is greater than the maximum attribute length (512 bytes), or greater than 255 bytes for
+    /// firmware that encodes the length in one byte. Includes the invalid length.
+    CharacteristicValueTooLong(usize),
+
+    /// The offset into an attribute value is greater than the maximum attribute length (512 bytes),
+    /// or does not fit in the command field. Includes the invalid offset.
+    BadOffset(usize),
+
+    /// For the [Find by Type Value Request](Commands::find_by_type_value_request) command: the
+    /// [UUID](FindByTypeValueParameters::uuid) has no 16-bit form. Includes the invalid UUID.
+    BadAttributeType(Uuid),
+
+    /// The command is only supported by the BlueNRG-MS, and the BlueNRG [variant](crate::Variant)
+    /// is selected.
+    UnsupportedByVariant,
+
+    /// The parameters could not be serialized into the command buffer.
+    Encode(crate::EncodeError),
+
     /// Underlying communication error.
     Comm(E),
 }
 
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for Error<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::DescriptorTooLong => defmt::write!(f, "DescriptorTooLong"),
+            Error::DescriptorBufferTooLong => defmt::write!(f, "DescriptorBufferTooLong"),
+            Error::ValueBufferTooLong => defmt::write!(f, "ValueBufferTooLong"),
+            Error::TooManyHandlesToRead => defmt::write!(f, "TooManyHandlesToRead"),
+            Error::TooFewHandlesToRead => defmt::write!(f, "TooFewHandlesToRead"),
+            Error::BadConnectionHandle(conn_handle) => {
+                defmt::write!(f, "BadConnectionHandle({})", Remote(conn_handle))
+            }
+            Error::BadServiceHandle(value) => defmt::write!(f, "BadServiceHandle({})", value),
+            Error::BadIncludeHandleRange(range) => {
+                defmt::write!(f, "BadIncludeHandleRange({})", range)
+            }
+            Error::BadMaxAttributeRecords(value) => {
+                defmt::write!(f, "BadMaxAttributeRecords({})", value)
+            }
+            Error::CharacteristicValueTooLong(value) => {
+                defmt::write!(f, "CharacteristicValueTooLong({})", value)
+            }
+            Error::BadOffset(value) => defmt::write!(f, "BadOffset({})", value),
+            Error::BadAttributeType(uuid) => defmt::write!(f, "BadAttributeType({})", uuid),
+            Error::UnsupportedByVariant => defmt::write!(f, "UnsupportedByVariant"),
+            Error::Encode(value) => defmt::write!(f, "Encode({})", value),
+            Error::Comm(e) => defmt::write!(f, "Comm({})", e),
+        }
+    }
+}
+
+/// Maximum length of an attribute value (Bluetooth Core Specification v4.1, Vol 3, Part F, Section
+/// 3.2.9).
+const MAX_ATTRIBUTE_LEN: usize = 512;
+
 fn rewrap_error<E>(e: nb::Error<E>) -> nb::Error<Error<E>> {
     match e {
         nb::Error::WouldBlock => nb::Error::WouldBlock,
@@ -1302,8 +1522,37 @@ fn rewrap_error<E>(e: nb::Error<E>) -> nb::Error<Error<E>> {
     }
 }
 
+impl<E> From<Error<E>> for crate::CommandError<E> {
+    fn from(e: Error<E>) -> Self {
+        use self::Error::*;
+
+        let validation = match e {
+            Comm(e) => return crate::CommandError::Comm(e),
+            Encode(e) => crate::ValidationError::Encode(e),
+            UnsupportedByVariant => crate::ValidationError::UnsupportedByVariant,
+            DescriptorTooLong => crate::ValidationError::Gatt(DescriptorTooLong),
+            DescriptorBufferTooLong => crate::ValidationError::Gatt(DescriptorBufferTooLong),
+            ValueBufferTooLong => crate::ValidationError::Gatt(ValueBufferTooLong),
+            TooManyHandlesToRead => crate::ValidationError::Gatt(TooManyHandlesToRead),
+            TooFewHandlesToRead => crate::ValidationError::Gatt(TooFewHandlesToRead),
+            BadConnectionHandle(a) => crate::ValidationError::Gatt(BadConnectionHandle(a)),
+            BadServiceHandle(a) => crate::ValidationError::Gatt(BadServiceHandle(a)),
+            BadIncludeHandleRange(a) => crate::ValidationError::Gatt(BadIncludeHandleRange(a)),
+            BadMaxAttributeRecords(a) => crate::ValidationError::Gatt(BadMaxAttributeRecords(a)),
+            CharacteristicValueTooLong(a) => {
+                crate::ValidationError::Gatt(CharacteristicValueTooLong(a))
+            }
+            BadOffset(a) => crate::ValidationError::Gatt(BadOffset(a)),
+            BadAttributeType(a) => crate::ValidationError::Gatt(BadAttributeType(a)),
+        };
+        crate::CommandError::Validation(validation)
+    }
+}
+
 /// Parameters for the [GATT Add Service](Commands::add_service) command.
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddServiceParameters {
     /// UUID of the service
     pub uuid: Uuid,
@@ -1320,52 +1569,32 @@ pub struct AddServiceParameters {
 impl AddServiceParameters {
     const MAX_LENGTH: usize = 19;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= Self::MAX_LENGTH);
-
-        let next = self.uuid.copy_into_slice(bytes);
-        bytes[next] = self.service_type as u8;
-        bytes[next + 1] = self.max_attribute_records as u8;
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        const MAX_ATTRIBUTE_RECORDS: usize = 255;
+        if self.max_attribute_records == 0 || self.max_attribute_records > MAX_ATTRIBUTE_RECORDS {
+            return Err(Error::BadMaxAttributeRecords(self.max_attribute_records));
+        }
 
-        next + 2
+        Ok(())
     }
-}
-
-/// Types of UUID
-#[derive(Copy, Clone, Debug, PartialEq)]
-pub enum Uuid {
-    /// 16-bit UUID
-    Uuid16(u16),
-
-    /// 128-bit UUID
-    Uuid128([u8; 16]),
-}
-
-impl Uuid {
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        match *self {
-            Uuid::Uuid16(uuid) => {
-                assert!(bytes.len() >= 3);
-
-                bytes[0] = 0x01;
-                LittleEndian::write_u16(&mut bytes[1..3], uuid);
 
-                3
-            }
-            Uuid::Uuid128(uuid) => {
-                assert!(bytes.len() >= 17);
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, Self::MAX_LENGTH)?;
 
-                bytes[0] = 0x02;
-                bytes[1..17].copy_from_slice(&uuid);
+        let next = self.uuid.copy_into_slice(bytes)?;
+        bytes[next] = self.service_type as u8;
+        bytes[next + 1] = self.max_attribute_records as u8;
 
-                17
-            }
-        }
+        Ok(next + 2)
     }
 }
 
+pub use crate::uuid::Uuid;
+
 /// Types of GATT services
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum ServiceType {
     /// Primary service
@@ -1375,6 +1604,9 @@ pub enum ServiceType {
 }
 
 /// Parameters for the [GATT Include Service](Commands::include_service) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct IncludeServiceParameters {
     /// Handle of the service to which another service has to be included
     pub service_handle: ServiceHandle,
@@ -1389,24 +1621,43 @@ pub struct IncludeServiceParameters {
 impl IncludeServiceParameters {
     const MAX_LENGTH: usize = 23;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= Self::MAX_LENGTH);
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        if self.service_handle.0 == 0 {
+            return Err(Error::BadServiceHandle(self.service_handle));
+        }
+
+        // The range may have been deserialized without going through Range::new.
+        let range = &self.include_handle_range;
+        if range.from.0 == 0 || range.from > range.to {
+            return Err(Error::BadIncludeHandleRange(*range));
+        }
+
+        Ok(())
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, Self::MAX_LENGTH)?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.service_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.include_handle_range.from.0);
         LittleEndian::write_u16(&mut bytes[4..6], self.include_handle_range.to.0);
-        let uuid_len = self.include_uuid.copy_into_slice(&mut bytes[6..]);
+        let uuid_len = self.include_uuid.copy_into_slice(&mut bytes[6..])?;
 
-        6 + uuid_len
+        Ok(6 + uuid_len)
     }
 }
 
 /// Handle for GATT Services.
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ServiceHandle(pub u16);
 
 /// Two ordered points that represent a range. The points may be identical to represent a range with
 /// only one value.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Range<T> {
     from: T,
     to: T,
@@ -1427,8 +1678,22 @@ impl<T: PartialOrd> Range<T> {
     }
 }
 
+impl<T: Copy> Range<T> {
+    /// Returns the beginning of the range.
+    pub fn from(&self) -> T {
+        self.from
+    }
+
+    /// Returns the end of the range.
+    pub fn to(&self) -> T {
+        self.to
+    }
+}
+
 /// Potential errors that can occer when creating a [Range].
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum RangeError {
     /// The beginning of the range came after the end.
     Inverted,
@@ -1436,6 +1701,8 @@ pub enum RangeError {
 
 /// Parameters for the [GATT Add Characteristic](Commands::add_characteristic) command.
 #[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AddCharacteristicParameters {
     /// Handle of the service to which the characteristic has to be added
     pub service_handle: ServiceHandle,
@@ -1472,11 +1739,28 @@ pub struct AddCharacteristicParameters {
 impl AddCharacteristicParameters {
     const MAX_LENGTH: usize = 26;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= Self::MAX_LENGTH);
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        let max_len = if self.fw_version_before_v72 {
+            255
+        } else {
+            MAX_ATTRIBUTE_LEN
+        };
+        if self.characteristic_value_len > max_len {
+            return Err(Error::CharacteristicValueTooLong(
+                self.characteristic_value_len,
+            ));
+        }
+
+        Ok(())
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, Self::MAX_LENGTH)?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.service_handle.0);
-        let uuid_len = self.characteristic_uuid.copy_into_slice(&mut bytes[2..19]);
+        let uuid_len = self
+            .characteristic_uuid
+            .copy_into_slice(&mut bytes[2..19])?;
         let mut next = 2 + uuid_len;
         if self.fw_version_before_v72 {
             bytes[next] = self.characteristic_value_len as u8;
@@ -1495,7 +1779,7 @@ impl AddCharacteristicParameters {
         bytes[next + 3] = self.encryption_key_size.0;
         bytes[next + 4] = self.is_variable as u8;
 
-        next + 5
+        Ok(next + 5)
     }
 }
 
@@ -1503,6 +1787,7 @@ bitflags! {
     /// Available [properties](AddCharacteristicParameters::characteristic_properties) for
     /// characteristics. Defined in Volume 3, Part G, Section 3.3.3.1 of Bluetooth Specification
     /// 4.1.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct CharacteristicProperty: u8 {
         /// If set, permits broadcasts of the Characteristic Value using Server Characteristic
         /// Configuration Descriptor. If set, the Server Characteristic Configuration Descriptor
@@ -1545,9 +1830,13 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(CharacteristicProperty, u8);
+
 bitflags! {
     /// [Permissions](AddCharacteristicParameter::security_permissions) available for
     /// characteristics.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct CharacteristicPermission: u8 {
         /// Need authentication to read.
         const AUTHENTICATED_READ = 0x01;
@@ -1569,8 +1858,12 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(CharacteristicPermission, u8);
+
 bitflags! {
     /// Which events may be generated when a characteristic is accessed.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct CharacteristicEvent: u8 {
         /// The application will be notified when a client writes to this attribute.
         const ATTRIBUTE_WRITE = 0x01;
@@ -1585,8 +1878,13 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(CharacteristicEvent, u8);
+
 /// Encryption key size, in bytes.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct EncryptionKeySize(u8);
 
 impl EncryptionKeySize {
@@ -1619,6 +1917,8 @@ impl EncryptionKeySize {
 
 /// Errors that can occur when creating an [`EncryptionKeySize`].
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum EncryptionKeySizeError {
     /// The provided size was less than the minimum allowed size.
     TooShort,
@@ -1628,10 +1928,15 @@ pub enum EncryptionKeySizeError {
 
 /// Handle for GATT characteristics.
 #[derive(Copy, Clone, Debug, PartialEq, PartialOrd)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct CharacteristicHandle(pub u16);
 
 /// Parameters for the [GATT Add Characteristic Descriptor](Commands::add_characteristic_descriptor)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AddDescriptorParameters<'a> {
     /// Handle of the service to which characteristic belongs.
     pub service_handle: ServiceHandle,
@@ -1683,13 +1988,13 @@ impl<'a> AddDescriptorParameters<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
         // The buffer should be big enough to hold this descriptor, assuming a 128-bit UUID.
-        assert!(bytes.len() >= 28 + self.descriptor_value.len());
+        check_buffer_len(bytes, 28 + self.descriptor_value.len())?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.service_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.characteristic_handle.0);
-        let uuid_len = self.descriptor_uuid.copy_into_slice(&mut bytes[4..]);
+        let uuid_len = self.descriptor_uuid.copy_into_slice(&mut bytes[4..])?;
         bytes[4 + uuid_len] = self.descriptor_value_max_len as u8;
         bytes[5 + uuid_len] = self.descriptor_value.len() as u8;
         bytes[6 + uuid_len..6 + uuid_len + self.descriptor_value.len()]
@@ -1701,7 +2006,7 @@ impl<'a> AddDescriptorParameters<'a> {
         bytes[3 + next] = self.encryption_key_size.0;
         bytes[4 + next] = self.is_variable as u8;
 
-        5 + next
+        Ok(5 + next)
     }
 }
 
@@ -1730,6 +2035,7 @@ impl From<KnownDescriptor> for Uuid {
 
 bitflags! {
     /// Permissions available for characteristic descriptors.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct DescriptorPermission: u8 {
         /// Authentication required.
         const AUTHENTICATED = 0x01;
@@ -1742,8 +2048,12 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(DescriptorPermission, u8);
+
 bitflags! {
     /// Types of access for characteristic descriptors
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct AccessPermission: u8 {
         /// Readable
         const READ = 0x01;
@@ -1756,12 +2066,20 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(AccessPermission, u8);
+
 /// Handle for GATT characteristic descriptors.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DescriptorHandle(pub u16);
 
 /// Parameters for the [Update Characteristic Value](Commands::update_characteristic_value)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct UpdateCharacteristicValueParameters<'a> {
     /// Handle of the service to which characteristic belongs.
     pub service_handle: ServiceHandle,
@@ -1786,6 +2104,11 @@ impl<'a> UpdateCharacteristicValueParameters<'a> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        const MAX_OFFSET: usize = 255;
+        if self.offset > MAX_OFFSET {
+            return Err(Error::BadOffset(self.offset));
+        }
+
         const MAX_VALUE_LEN: usize = 249;
         if self.value.len() > MAX_VALUE_LEN {
             return Err(Error::ValueBufferTooLong);
@@ -1794,8 +2117,8 @@ impl<'a> UpdateCharacteristicValueParameters<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= 6 + self.value.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, 6 + self.value.len())?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.service_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.characteristic_handle.0);
@@ -1803,11 +2126,14 @@ impl<'a> UpdateCharacteristicValueParameters<'a> {
         bytes[5] = self.value.len() as u8;
         bytes[6..6 + self.value.len()].copy_from_slice(self.value);
 
-        6 + self.value.len()
+        Ok(6 + self.value.len())
     }
 }
 
 /// Parameters for the [GATT Delete Included Service](Commands::delete_included_service) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct DeleteIncludedServiceParameters {
     /// Handle of the service to which Include definition belongs
     pub service: ServiceHandle,
@@ -1819,17 +2145,20 @@ pub struct DeleteIncludedServiceParameters {
 impl DeleteIncludedServiceParameters {
     const LENGTH: usize = 4;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert!(bytes.len() >= Self::LENGTH);
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.service.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.included_service.0);
+
+        Ok(())
     }
 }
 
 bitflags! {
     /// Flags for individual events that can be masked by the [GATT Set Event
     /// Mask](Commands::set_event_mask) command.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct Event: u32 {
         /// [GATT Attribute Modified](crate::event::BlueNRGEvent::GattAttributeModified).
         const ATTRIBUTE_MODIFIED = 0x0000_0001;
@@ -1873,27 +2202,38 @@ bitflags! {
     }
 }
 
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(Event, u32);
+
 impl Event {
     const LENGTH: usize = 4;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert!(bytes.len() >= Self::LENGTH);
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         LittleEndian::write_u32(bytes, self.bits());
+
+        Ok(())
     }
 }
 
 /// Parameters for the [GATT Find by Type Value Request](Commands::find_by_type_value_request)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct FindByTypeValueParameters<'a> {
     /// Connection handle for which the command is given.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Range of attributes to be discovered on the server.
     pub attribute_handle_range: Range<CharacteristicHandle>,
 
-    /// UUID to find.
-    pub uuid: Uuid16,
+    /// UUID to find. The command carries only 16-bit UUIDs, so the UUID must have a 16-bit form.
+    pub uuid: Uuid,
 
     /// Attribute value to find.
     ///
@@ -1903,10 +2243,36 @@ pub struct FindByTypeValueParameters<'a> {
     pub value: &'a [u8],
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for FindByTypeValueParameters<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "FindByTypeValueParameters {{ conn_handle: {}, attribute_handle_range: ",
+            Remote(&self.conn_handle)
+        );
+        defmt::write!(
+            f,
+            "{}, uuid: {}, value: {} }}",
+            self.attribute_handle_range,
+            self.uuid,
+            self.value
+        )
+    }
+}
+
 impl<'a> FindByTypeValueParameters<'a> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        if self.uuid.to_u16().is_none() {
+            return Err(Error::BadAttributeType(self.uuid));
+        }
+
         if 9 + self.value.len() > Self::MAX_LENGTH {
             return Err(Error::ValueBufferTooLong);
         }
@@ -1914,26 +2280,43 @@ impl<'a> FindByTypeValueParameters<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= 9 + self.value.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, 9 + self.value.len())?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.attribute_handle_range.from.0);
         LittleEndian::write_u16(&mut bytes[4..6], self.attribute_handle_range.to.0);
-        LittleEndian::write_u16(&mut bytes[6..8], self.uuid.0);
+        LittleEndian::write_u16(&mut bytes[6..8], self.uuid.to_u16().unwrap_or(0));
         bytes[8] = self.value.len() as u8;
         bytes[9..9 + self.value.len()].copy_from_slice(&self.value);
 
-        9 + self.value.len()
+        Ok(9 + self.value.len())
     }
 }
 
-/// 16-bit UUID
+/// 16-bit UUID. Converts to, and compares with, [`Uuid`].
+#[deprecated(note = "use `Uuid::Uuid16`")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Uuid16(pub u16);
 
+#[cfg(feature = "defmt")]
+#[allow(deprecated)]
+impl defmt::Format for Uuid16 {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Uuid16({})", self.0)
+    }
+}
+
 /// Parameters for the [Read by Group Type Request](Commands::read_by_group_type_request) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ReadByTypeParameters {
     /// Connection handle for which the command is given.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Range of values to be read on the server.
@@ -1943,22 +2326,51 @@ pub struct ReadByTypeParameters {
     pub uuid: Uuid,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for ReadByTypeParameters {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ReadByTypeParameters {{ conn_handle: {}, attribute_handle_range: {}, uuid: ",
+            Remote(&self.conn_handle),
+            self.attribute_handle_range
+        );
+        defmt::write!(f, "{} }}", self.uuid)
+    }
+}
+
 impl ReadByTypeParameters {
     const MAX_LENGTH: usize = 23;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= Self::MAX_LENGTH);
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        Ok(())
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, Self::MAX_LENGTH)?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.attribute_handle_range.from.0);
         LittleEndian::write_u16(&mut bytes[4..6], self.attribute_handle_range.to.0);
-        6 + self.uuid.copy_into_slice(&mut bytes[6..])
+        let uuid_len = self.uuid.copy_into_slice(&mut bytes[6..])?;
+
+        Ok(6 + uuid_len)
     }
 }
 
 /// Parameters for the [Prepare Write Request](Commands::prepare_write_request) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct WriteRequest<'a> {
     /// Connection handle for which the command is given.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Handle of the attribute whose value has to be written
@@ -1971,10 +2383,32 @@ pub struct WriteRequest<'a> {
     pub value: &'a [u8],
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for WriteRequest<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "WriteRequest {{ conn_handle: {}, attribute_handle: {}, offset: {}, value: ",
+            Remote(&self.conn_handle),
+            self.attribute_handle,
+            self.offset
+        );
+        defmt::write!(f, "{} }}", self.value)
+    }
+}
+
 impl<'a> WriteRequest<'a> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        if self.offset > MAX_ATTRIBUTE_LEN {
+            return Err(Error::BadOffset(self.offset));
+        }
+
         if 9 + self.value.len() > 255 {
             return Err(Error::ValueBufferTooLong);
         }
@@ -1982,8 +2416,8 @@ impl<'a> WriteRequest<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= 9 + self.value.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, 9 + self.value.len())?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.attribute_handle.0);
@@ -1991,14 +2425,20 @@ impl<'a> WriteRequest<'a> {
         bytes[6] = self.value.len() as u8;
         bytes[7..7 + self.value.len()].copy_from_slice(&self.value);
 
-        7 + self.value.len()
+        Ok(7 + self.value.len())
     }
 }
 
 /// Parameters for the [Read long characteristic value](Commands::read_long_characteristic_value)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct LongCharacteristicReadParameters {
     /// Connection handle for which the command is given.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Handle of the characteristic to be read
@@ -2008,20 +2448,55 @@ pub struct LongCharacteristicReadParameters {
     pub offset: usize,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for LongCharacteristicReadParameters {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "LongCharacteristicReadParameters {{ conn_handle: {}, attribute: {}, offset: ",
+            Remote(&self.conn_handle),
+            self.attribute
+        );
+        defmt::write!(f, "{} }}", self.offset)
+    }
+}
+
 impl LongCharacteristicReadParameters {
     const LENGTH: usize = 6;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        if self.offset > MAX_ATTRIBUTE_LEN {
+            return Err(Error::BadOffset(self.offset));
+        }
+
+        Ok(())
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
+
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.attribute.0);
         LittleEndian::write_u16(&mut bytes[4..6], self.offset as u16);
+
+        Ok(())
     }
 }
 
 /// Parameters for the [Read Multiple Characteristic
 /// Values](Commands::read_multiple_characteristic_values) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct MultipleCharacteristicReadParameters<'a> {
     /// Connection handle for which the command is given.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// The handles for which the attribute value has to be read.
@@ -2030,10 +2505,31 @@ pub struct MultipleCharacteristicReadParameters<'a> {
     pub handles: &'a [CharacteristicHandle],
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for MultipleCharacteristicReadParameters<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "MultipleCharacteristicReadParameters {{ conn_handle: {}, handles: {} }}",
+            Remote(&self.conn_handle),
+            self.handles
+        )
+    }
+}
+
 impl<'a> MultipleCharacteristicReadParameters<'a> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        const MIN_HANDLE_COUNT: usize = 2;
+        if self.handles.len() < MIN_HANDLE_COUNT {
+            return Err(Error::TooFewHandlesToRead);
+        }
+
         const MAX_HANDLE_COUNT: usize = 126;
         if self.handles.len() > MAX_HANDLE_COUNT {
             return Err(Error::TooManyHandlesToRead);
@@ -2042,8 +2538,8 @@ impl<'a> MultipleCharacteristicReadParameters<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= 3 + 2 * self.handles.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, 3 + 2 * self.handles.len())?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         bytes[2] = self.handles.len() as u8;
@@ -2053,13 +2549,19 @@ impl<'a> MultipleCharacteristicReadParameters<'a> {
             next += 2
         }
 
-        next
+        Ok(next)
     }
 }
 
 /// Parameters for the [Write Characteristic Value](Commands::write_characteristic_value) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct CharacteristicValue<'a> {
     /// Connection handle for which the command is given.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Handle of the characteristic to be written.
@@ -2069,10 +2571,27 @@ pub struct CharacteristicValue<'a> {
     pub value: &'a [u8],
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for CharacteristicValue<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "CharacteristicValue {{ conn_handle: {}, characteristic_handle: {}, value: ",
+            Remote(&self.conn_handle),
+            self.characteristic_handle
+        );
+        defmt::write!(f, "{} }}", self.value)
+    }
+}
+
 impl<'a> CharacteristicValue<'a> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
         if self.len() > Self::MAX_LENGTH {
             return Err(Error::ValueBufferTooLong);
         }
@@ -2080,15 +2599,15 @@ impl<'a> CharacteristicValue<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= self.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, self.len())?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.characteristic_handle.0);
         bytes[4] = self.value.len() as u8;
         bytes[5..self.len()].copy_from_slice(self.value);
 
-        self.len()
+        Ok(self.len())
     }
 
     fn len(&self) -> usize {
@@ -2098,8 +2617,14 @@ impl<'a> CharacteristicValue<'a> {
 
 /// Parameters for the [Write Long Characteristic Value](Commands::write_long_characteristic_value)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LongCharacteristicValue<'a> {
     /// Connection handle for which the command is given.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Handle of the characteristic to be written.
@@ -2112,10 +2637,36 @@ pub struct LongCharacteristicValue<'a> {
     pub value: &'a [u8],
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for LongCharacteristicValue<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "LongCharacteristicValue {{ conn_handle: {}, characteristic_handle: ",
+            Remote(&self.conn_handle)
+        );
+        defmt::write!(
+            f,
+            "{}, offset: {}, value: {} }}",
+            self.characteristic_handle,
+            self.offset,
+            self.value
+        )
+    }
+}
+
 impl<'a> LongCharacteristicValue<'a> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        if self.offset > MAX_ATTRIBUTE_LEN {
+            return Err(Error::BadOffset(self.offset));
+        }
+
         if self.len() > Self::MAX_LENGTH {
             return Err(Error::ValueBufferTooLong);
         }
@@ -2123,8 +2674,8 @@ impl<'a> LongCharacteristicValue<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= self.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, self.len())?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.characteristic_handle.0);
@@ -2132,7 +2683,7 @@ impl<'a> LongCharacteristicValue<'a> {
         bytes[6] = self.value.len() as u8;
         bytes[7..self.len()].copy_from_slice(self.value);
 
-        self.len()
+        Ok(self.len())
     }
 
     fn len(&self) -> usize {
@@ -2141,8 +2692,14 @@ impl<'a> LongCharacteristicValue<'a> {
 }
 
 /// Parameters for the [Write Response](Commands::write_response) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct WriteResponseParameters<'a> {
     /// Connection handle for which the command is given
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Handle of the attribute that was passed in the [Write Permit
@@ -2150,6 +2707,7 @@ pub struct WriteResponseParameters<'a> {
     pub attribute_handle: CharacteristicHandle,
 
     /// Is the command rejected, and if so, why?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status_result"))]
     pub status: Result<(), hci::Status<crate::event::Status>>,
 
     /// Value as passed in the [Write Permit
@@ -2157,10 +2715,27 @@ pub struct WriteResponseParameters<'a> {
     pub value: &'a [u8],
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for WriteResponseParameters<'_> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "WriteResponseParameters {{ conn_handle: {}, attribute_handle: {}, status: ",
+            Remote(&self.conn_handle),
+            self.attribute_handle
+        );
+        defmt::write!(f, "{}, value: {} }}", Remote(&self.status), self.value)
+    }
+}
+
 impl<'a> WriteResponseParameters<'a> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
         if self.len() > Self::MAX_LENGTH {
             return Err(Error::ValueBufferTooLong);
         }
@@ -2168,8 +2743,8 @@ impl<'a> WriteResponseParameters<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= self.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, self.len())?;
         LittleEndian::write_u16(&mut bytes[0..2], self.conn_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.attribute_handle.0);
         match self.status {
@@ -2185,7 +2760,7 @@ impl<'a> WriteResponseParameters<'a> {
         bytes[6] = self.value.len() as u8;
         bytes[7..self.len()].copy_from_slice(self.value);
 
-        self.len()
+        Ok(self.len())
     }
 
     fn len(&self) -> usize {
@@ -2194,6 +2769,9 @@ impl<'a> WriteResponseParameters<'a> {
 }
 
 /// Parameters for the [Set Security Permission](Commands::set_security_permission) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct SecurityPermissionParameters {
     /// Handle of the service which contains the attribute whose security permission has to be
     /// modified.
@@ -2209,16 +2787,21 @@ pub struct SecurityPermissionParameters {
 impl SecurityPermissionParameters {
     const LENGTH: usize = 5;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert!(bytes.len() >= Self::LENGTH);
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.service_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.attribute_handle.0);
         bytes[4] = self.permission.bits();
+
+        Ok(())
     }
 }
 
 /// Parameters for the [Set Descriptor Value](Commands::set_descriptor_value) command.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct DescriptorValueParameters<'a> {
     /// Handle of the service which contains the descriptor.
     pub service_handle: ServiceHandle,
@@ -2240,6 +2823,10 @@ impl<'a> DescriptorValueParameters<'a> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        if self.offset > MAX_ATTRIBUTE_LEN {
+            return Err(Error::BadOffset(self.offset));
+        }
+
         if self.len() > Self::MAX_LENGTH {
             return Err(Error::ValueBufferTooLong);
         }
@@ -2247,8 +2834,8 @@ impl<'a> DescriptorValueParameters<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= self.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, self.len())?;
         LittleEndian::write_u16(&mut bytes[0..2], self.service_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.characteristic_handle.0);
         LittleEndian::write_u16(&mut bytes[4..6], self.descriptor_handle.0);
@@ -2256,7 +2843,7 @@ impl<'a> DescriptorValueParameters<'a> {
         bytes[8] = self.value.len() as u8;
         bytes[9..self.len()].copy_from_slice(self.value);
 
-        self.len()
+        Ok(self.len())
     }
 
     fn len(&self) -> usize {
@@ -2267,6 +2854,9 @@ impl<'a> DescriptorValueParameters<'a> {
 /// Parameters for the [Update Long Characteristic
 /// Value](Commands::update_long_characteristic_value) command.
 #[cfg(feature = "ms")]
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct UpdateLongCharacteristicValueParameters<'a> {
     /// Handle of the service to which characteristic belongs.
     pub service_handle: ServiceHandle,
@@ -2296,6 +2886,14 @@ impl<'a> UpdateLongCharacteristicValueParameters<'a> {
     const MAX_LENGTH: usize = 255;
 
     fn validate<E>(&self) -> Result<(), Error<E>> {
+        if self.offset > MAX_ATTRIBUTE_LEN {
+            return Err(Error::BadOffset(self.offset));
+        }
+
+        if self.total_len > MAX_ATTRIBUTE_LEN {
+            return Err(Error::CharacteristicValueTooLong(self.total_len));
+        }
+
         if self.len() > Self::MAX_LENGTH {
             return Err(Error::ValueBufferTooLong);
         }
@@ -2303,8 +2901,8 @@ impl<'a> UpdateLongCharacteristicValueParameters<'a> {
         Ok(())
     }
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
-        assert!(bytes.len() >= self.len());
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        check_buffer_len(bytes, self.len())?;
 
         LittleEndian::write_u16(&mut bytes[0..2], self.service_handle.0);
         LittleEndian::write_u16(&mut bytes[2..4], self.characteristic_handle.0);
@@ -2314,7 +2912,7 @@ impl<'a> UpdateLongCharacteristicValueParameters<'a> {
         bytes[9] = self.value.len() as u8;
         bytes[10..self.len()].copy_from_slice(self.value);
 
-        self.len()
+        Ok(self.len())
     }
 
     fn len(&self) -> usize {
@@ -2326,6 +2924,7 @@ impl<'a> UpdateLongCharacteristicValueParameters<'a> {
 bitflags! {
     /// Flags for types of updates that the controller should signal when a characteristic value is
     /// [updated](Commands::update_long_characteristic_value).
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct UpdateType: u8 {
         /// A notification can be sent if enabled in the client characteristic configuration
         /// descriptor.
@@ -2335,3 +2934,6 @@ bitflags! {
         const INDICATION = 0x02;
     }
 }
+
+#[cfg(all(feature = "ms", feature = "serde"))]
+impl_serde_bitflags!(UpdateType, u8);
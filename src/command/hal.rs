@@ -7,7 +7,8 @@ extern crate nb;
 
 use byteorder::{ByteOrder, LittleEndian};
 
-/// Vendor-specific HCI commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG).
+/// Vendor-specific HCI commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG)
+/// and any other [`CommandWriter`](crate::CommandWriter).
 pub trait Commands {
     /// Type of communication errors.
     type Error;
@@ -29,13 +30,17 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadConfigData](Error::BadConfigData) if the configuration data does not start and end on
+    ///   the boundaries of the fields in the configuration data structure. Data created by the
+    ///   [`ConfigData`] builders is always valid.
+    /// - [Encode](Error::Encode) if the configuration data cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
     /// The controller will generate a [command
     /// complete](crate::event::command::ReturnParameters::HalWriteConfigData) event.
-    fn write_config_data(&mut self, config: &ConfigData) -> nb::Result<(), Self::Error>;
+    fn write_config_data(&mut self, config: &ConfigData) -> nb::Result<(), Error<Self::Error>>;
 
     /// This command requests the value in the low level configure data structure.
     ///
@@ -174,24 +179,67 @@ pub trait Commands {
     /// The controller will generate a [command
     /// complete](crate::event::command::ReturnParameters::HalGetAnchorPeriod) event.
     fn get_anchor_period(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Selects the radio activities that generate a [HAL End of Radio
+    /// Activity](crate::event::BlueNRGEvent::HalEndOfRadioActivity) event. Events are generated
+    /// when the radio leaves one of the selected states.
+    ///
+    /// Only the BlueNRG-1 and BlueNRG-2 support this command.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// The controller will generate a [command
+    /// complete](crate::event::command::ReturnParameters::HalSetRadioActivityMask) event.
+    #[cfg(feature = "bluenrg-1-2")]
+    fn set_radio_activity_mask(&mut self, mask: RadioActivityFlags) -> nb::Result<(), Self::Error>;
+
+    /// Enables or disables the HAL events. All HAL events are disabled by default.
+    ///
+    /// Only the BlueNRG-1 and BlueNRG-2 support this command.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// The controller will generate a [command
+    /// complete](crate::event::command::ReturnParameters::HalSetEventMask) event.
+    #[cfg(feature = "bluenrg-1-2")]
+    fn set_hal_event_mask(&mut self, mask: EventFlags) -> nb::Result<(), Self::Error>;
+
+    /// Resets the Bluetooth stack (host and link layer) without resetting the chip. All
+    /// connections are terminated, and the stack must be initialized again.
+    ///
+    /// Only the BlueNRG-1 and BlueNRG-2 support this command.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// The controller will generate a [command
+    /// complete](crate::event::command::ReturnParameters::HalStackReset) event.
+    #[cfg(feature = "bluenrg-1-2")]
+    fn stack_reset(&mut self) -> nb::Result<(), Self::Error>;
 }
 
-impl<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, SpiError, GpioError> Commands
-    for crate::ActiveBlueNRG<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, GpioError>
+impl<T> Commands for T
 where
-    SPI: hal::blocking::spi::Transfer<u8, Error = SpiError>
-        + hal::blocking::spi::Write<u8, Error = SpiError>,
-    OutputPin1: hal::digital::v2::OutputPin<Error = GpioError>,
-    OutputPin2: hal::digital::v2::OutputPin<Error = GpioError>,
-    InputPin: hal::digital::v2::InputPin<Error = GpioError>,
+    T: crate::CommandWriter,
 {
-    type Error = crate::Error<SpiError, GpioError>;
+    type Error = T::Error;
 
     fn get_firmware_revision(&mut self) -> nb::Result<(), Self::Error> {
         self.write_command(crate::opcode::HAL_GET_FIRMWARE_REVISION, &[])
     }
 
-    impl_variable_length_params!(
+    impl_validate_variable_length_params!(
         write_config_data,
         ConfigData,
         crate::opcode::HAL_WRITE_CONFIG_DATA
@@ -237,6 +285,27 @@ where
     fn get_anchor_period(&mut self) -> nb::Result<(), Self::Error> {
         self.write_command(crate::opcode::HAL_GET_ANCHOR_PERIOD, &[])
     }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn set_radio_activity_mask(&mut self, mask: RadioActivityFlags) -> nb::Result<(), Self::Error> {
+        let mut bytes = [0; 2];
+        LittleEndian::write_u16(&mut bytes, mask.bits());
+
+        self.write_command(crate::opcode::HAL_SET_RADIO_ACTIVITY_MASK, &bytes)
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn set_hal_event_mask(&mut self, mask: EventFlags) -> nb::Result<(), Self::Error> {
+        let mut bytes = [0; 4];
+        LittleEndian::write_u32(&mut bytes, mask.bits());
+
+        self.write_command(crate::opcode::HAL_SET_EVENT_MASK, &bytes)
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn stack_reset(&mut self) -> nb::Result<(), Self::Error> {
+        self.write_command(crate::opcode::HAL_STACK_RESET, &[])
+    }
 }
 
 /// Potential errors from parameter validation.
@@ -245,15 +314,37 @@ where
 /// enumerates the potential validation errors. Must be specialized on the types of communication
 /// errors.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error<E> {
     /// For the [Start Tone](Commands::start_tone) command, the channel was greater than the maximum
     /// allowed channel (39). The invalid channel is returned.
     InvalidChannel(u8),
 
+    /// For the [Write Config Data](Commands::write_config_data) command, the data does not cover a
+    /// contiguous set of whole fields of the configuration data structure. Includes the offset and
+    /// length of the data.
+    BadConfigData(usize, usize),
+
+    /// The parameters could not be serialized into the command buffer.
+    Encode(crate::EncodeError),
+
     /// Underlying communication error.
     Comm(E),
 }
 
+impl Error<crate::NoComm> {
+    // Converts a validation error to an error with any communication error type.
+    pub(crate) fn widen<E>(self) -> Error<E> {
+        match self {
+            Error::InvalidChannel(channel) => Error::InvalidChannel(channel),
+            Error::BadConfigData(offset, len) => Error::BadConfigData(offset, len),
+            Error::Encode(e) => Error::Encode(e),
+            Error::Comm(never) => match never {},
+        }
+    }
+}
+
 fn rewrap_error<E>(e: nb::Error<E>) -> nb::Error<Error<E>> {
     match e {
         nb::Error::WouldBlock => nb::Error::WouldBlock,
@@ -261,7 +352,23 @@ fn rewrap_error<E>(e: nb::Error<E>) -> nb::Error<Error<E>> {
     }
 }
 
+impl<E> From<Error<E>> for crate::CommandError<E> {
+    fn from(e: Error<E>) -> Self {
+        use self::Error::*;
+
+        let validation = match e {
+            Comm(e) => return crate::CommandError::Comm(e),
+            Encode(e) => crate::ValidationError::Encode(e),
+            InvalidChannel(a) => crate::ValidationError::Hal(InvalidChannel(a)),
+            BadConfigData(a, b) => crate::ValidationError::Hal(BadConfigData(a, b)),
+        };
+        crate::CommandError::Validation(validation)
+    }
+}
+
 /// Low-level configuration parameters for the controller.
+#[derive(Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub struct ConfigData {
     offset: u8,
     length: u8,
@@ -272,22 +379,62 @@ impl ConfigData {
     /// Maximum length needed to serialize the data.
     pub const MAX_LENGTH: usize = 0x2E;
 
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        // Offsets of the fields in the configuration data structure, and the end of the structure.
+        const FIELD_BOUNDARIES: [usize; 7] = [0, 6, 8, 24, 40, 41, 42];
+
+        let start = self.offset as usize;
+        let end = start + self.length as usize;
+        if self.length == 0
+            || !FIELD_BOUNDARIES.contains(&start)
+            || !FIELD_BOUNDARIES.contains(&end)
+        {
+            return Err(Error::BadConfigData(start, self.length as usize));
+        }
+
+        Ok(())
+    }
+
     /// Serializes the data into the given buffer.
     ///
     /// Returns the number of valid bytes in the buffer.
     ///
-    /// # Panics
+    /// # Errors
     ///
-    /// The buffer must be large enough to support the serialized data (at least
-    /// [`MAX_LENGTH`](ConfigData::MAX_LENGTH) bytes).
-    pub fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+    /// Returns [`BufferTooSmall`](crate::EncodeError::BufferTooSmall) if the buffer cannot hold the
+    /// serialized data. A buffer of [`MAX_LENGTH`](ConfigData::MAX_LENGTH) bytes is always large
+    /// enough.
+    pub fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, crate::EncodeError> {
+        let value = self.value();
+        crate::command::check_buffer_len(bytes, 2 + value.len())?;
+
         bytes[0] = self.offset;
         bytes[1] = self.length;
+        bytes[2..2 + value.len()].copy_from_slice(value);
 
-        let len = self.length as usize;
-        bytes[2..2 + len].copy_from_slice(&self.value_buf[..len]);
+        Ok(2 + value.len())
+    }
+
+    /// Returns the offset of the first byte of [`value`](ConfigData::value) within the
+    /// configuration data structure.
+    pub fn offset(&self) -> usize {
+        self.offset as usize
+    }
+
+    /// Returns the serialized value of the configuration data.
+    pub fn value(&self) -> &[u8] {
+        &self.value_buf[..self.length as usize]
+    }
+
+    pub(crate) fn from_raw(offset: u8, value: &[u8]) -> ConfigData {
+        let mut data = Self {
+            offset,
+            length: value.len() as u8,
+            value_buf: [0; Self::MAX_LENGTH],
+        };
+        data.value_buf[..value.len()].copy_from_slice(value);
 
-        2 + len
+        data
     }
 
     /// Builder for [ConfigData].
@@ -391,6 +538,21 @@ impl ConfigData {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for ConfigData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("ConfigData", 2)?;
+        state.serialize_field("offset", &self.offset)?;
+        state.serialize_field("value", self.value())?;
+        state.end()
+    }
+}
+
 /// Builder for [`ConfigData`].
 pub struct ConfigDataDiversifierBuilder {
     data: ConfigData,
@@ -516,6 +678,9 @@ impl ConfigDataCompleteBuilder {
 
 /// Roles that the server can adopt.
 #[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Role {
     /// Peripheral and primary device.
     /// - Only one connection.
@@ -539,9 +704,23 @@ pub enum Role {
     SimultaneousAdvertisingScanning = 4,
 }
 
+impl Role {
+    /// Returns the maximum number of simultaneous connections in this role.
+    pub fn max_connections(&self) -> usize {
+        match self {
+            Role::Peripheral6Kb | Role::Peripheral12Kb => 1,
+            Role::Primary12Kb => 8,
+            Role::SimultaneousAdvertisingScanning => 4,
+        }
+    }
+}
+
 /// Configuration parameters that are readable by the
 /// [`read_config_data`](Commands::read_config_data) command.
 #[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ConfigParameter {
     /// Bluetooth public address.
     PublicAddress = 0,
@@ -570,6 +749,9 @@ pub enum ConfigParameter {
 /// PA level. This enum combines the two parameters. The high byte is the PA level; the low byte is
 /// the enable high power flag.
 #[repr(u16)]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PowerLevel {
     /// PA level 0, low power.
     DbmNeg18 = 0x000,
@@ -604,3 +786,140 @@ pub enum PowerLevel {
     /// PA level 7, high power.
     Dbm8_0 = 0x701,
 }
+
+impl PowerLevel {
+    /// Every power level, from the lowest output power to the highest.
+    pub const ALL: [PowerLevel; 16] = [
+        PowerLevel::DbmNeg18,
+        PowerLevel::DbmNeg15,
+        PowerLevel::DbmNeg14_7,
+        PowerLevel::DbmNeg11_7,
+        PowerLevel::DbmNeg11_4,
+        PowerLevel::DbmNeg8_4,
+        PowerLevel::DbmNeg8_1,
+        PowerLevel::DbmNeg5_1,
+        PowerLevel::DbmNeg4_9,
+        PowerLevel::DbmNeg2_1,
+        PowerLevel::DbmNeg1_6,
+        PowerLevel::Dbm1_4,
+        PowerLevel::Dbm1_7,
+        PowerLevel::Dbm4_7,
+        PowerLevel::Dbm5_0,
+        PowerLevel::Dbm8_0,
+    ];
+
+    /// Returns the output power of the level, in dBm.
+    pub fn dbm(&self) -> f32 {
+        f32::from(self.tenths_dbm()) / 10.0
+    }
+
+    /// Returns the output power of the level, rounded to the nearest dBm. This is the value of the
+    /// [`TxPowerLevel`](crate::gap::AdvertisingDataType::TxPowerLevel) advertising data type.
+    pub fn rounded_dbm(&self) -> i8 {
+        let tenths = self.tenths_dbm();
+        let half = if tenths < 0 { -5 } else { 5 };
+
+        ((tenths + half) / 10) as i8
+    }
+
+    /// Returns the PA (power amplifier) level, from 0 to 7.
+    pub fn pa_level(&self) -> u8 {
+        (*self as u16 >> 8) as u8
+    }
+
+    /// Returns true if the level uses the high power mode of the PA.
+    pub fn is_high_power(&self) -> bool {
+        *self as u16 & 1 != 0
+    }
+
+    /// Returns the level with the output power closest to `dbm`. Ties go to the lower level.
+    pub fn nearest(dbm: f32) -> PowerLevel {
+        let distance = |level: &PowerLevel| {
+            let diff = level.dbm() - dbm;
+            if diff < 0.0 {
+                -diff
+            } else {
+                diff
+            }
+        };
+
+        let mut nearest = PowerLevel::ALL[0];
+        for level in PowerLevel::ALL.iter().skip(1) {
+            if distance(level) < distance(&nearest) {
+                nearest = *level;
+            }
+        }
+
+        nearest
+    }
+
+    /// Returns the lowest level with an output power from `min` to `max` dBm (inclusive), or
+    /// `None` if no level is in that range.
+    pub fn lowest_within(min: f32, max: f32) -> Option<PowerLevel> {
+        PowerLevel::ALL
+            .iter()
+            .find(|level| level.dbm() >= min && level.dbm() <= max)
+            .copied()
+    }
+
+    fn tenths_dbm(&self) -> i16 {
+        match self {
+            PowerLevel::DbmNeg18 => -180,
+            PowerLevel::DbmNeg15 => -150,
+            PowerLevel::DbmNeg14_7 => -147,
+            PowerLevel::DbmNeg11_7 => -117,
+            PowerLevel::DbmNeg11_4 => -114,
+            PowerLevel::DbmNeg8_4 => -84,
+            PowerLevel::DbmNeg8_1 => -81,
+            PowerLevel::DbmNeg5_1 => -51,
+            PowerLevel::DbmNeg4_9 => -49,
+            PowerLevel::DbmNeg2_1 => -21,
+            PowerLevel::DbmNeg1_6 => -16,
+            PowerLevel::Dbm1_4 => 14,
+            PowerLevel::Dbm1_7 => 17,
+            PowerLevel::Dbm4_7 => 47,
+            PowerLevel::Dbm5_0 => 50,
+            PowerLevel::Dbm8_0 => 80,
+        }
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+bitflags! {
+    /// Radio activities for [HAL Set Radio Activity Mask](Commands::set_radio_activity_mask).
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct RadioActivityFlags: u16 {
+        /// The radio is idle.
+        const IDLE = 0x0001;
+        /// Advertising.
+        const ADVERTISING = 0x0002;
+        /// Connection event in the peripheral role.
+        const PERIPHERAL_CONNECTION = 0x0004;
+        /// Scanning.
+        const SCANNING = 0x0008;
+        /// Connection request.
+        const CONNECTION_REQUEST = 0x0010;
+        /// Connection event in the central role.
+        const CENTRAL_CONNECTION = 0x0020;
+        /// Transmitter test mode.
+        const TX_TEST = 0x0040;
+        /// Receiver test mode.
+        const RX_TEST = 0x0080;
+    }
+}
+
+#[cfg(all(feature = "bluenrg-1-2", feature = "serde"))]
+impl_serde_bitflags!(RadioActivityFlags, u16);
+
+#[cfg(feature = "bluenrg-1-2")]
+bitflags! {
+    /// Event types for [HAL Set Event Mask](Commands::set_hal_event_mask).
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct EventFlags: u32 {
+        /// [Scan Request Report](crate::event::BlueNRGEvent::HalScanRequestReport)
+        const SCAN_REQUEST_REPORT = 0x0000_0001;
+    }
+}
+
+#[cfg(all(feature = "bluenrg-1-2", feature = "serde"))]
+impl_serde_bitflags!(EventFlags, u32);
@@ -5,10 +5,16 @@ extern crate byteorder;
 extern crate embedded_hal as hal;
 extern crate nb;
 
+use crate::command::{check_buffer_len, is_valid_conn_handle};
+use crate::EncodeError;
 use byteorder::{ByteOrder, LittleEndian};
 use hci::types::{ConnectionInterval, ExpectedConnectionLength};
 
-/// L2Cap-specific commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG).
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
+/// L2Cap-specific commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG)
+/// and any other [`CommandWriter`](crate::CommandWriter).
 pub trait Commands {
     /// Type of communication errors.
     type Error;
@@ -18,6 +24,9 @@ pub trait Commands {
     ///
     /// # Errors
     ///
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater than
+    ///   0x0EFF.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
     /// - Underlying communication errors.
     ///
     /// # Generated events
@@ -29,7 +38,7 @@ pub trait Commands {
     fn connection_parameter_update_request(
         &mut self,
         params: &ConnectionParameterUpdateRequest,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 
     /// This command should be sent in response to the
     /// [`L2CapConnectionUpdateResponse`](crate::event::BlueNRGEvent::L2CapConnectionUpdateResponse)
@@ -38,7 +47,13 @@ pub trait Commands {
     ///
     /// # Errors
     ///
-    /// Only underlying communication errors are reported.
+    /// - [BadConnectionHandle](Error::BadConnectionHandle) if the connection handle is greater than
+    ///   0x0EFF.
+    /// - [BadIdentifier](Error::BadIdentifier) if the
+    ///   [identifier](ConnectionParameterUpdateResponse::identifier) is 0, which is not a valid
+    ///   L2CAP signaling identifier.
+    /// - [Encode](Error::Encode) if the parameters cannot be serialized.
+    /// - Underlying communication errors.
     ///
     /// # Generated events
     ///
@@ -48,71 +63,174 @@ pub trait Commands {
     fn connection_parameter_update_response(
         &mut self,
         params: &ConnectionParameterUpdateResponse,
-    ) -> nb::Result<(), Self::Error>;
+    ) -> nb::Result<(), Error<Self::Error>>;
 }
 
-impl<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, SpiError, GpioError> Commands
-    for crate::ActiveBlueNRG<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, GpioError>
+impl<T> Commands for T
 where
-    SPI: hal::blocking::spi::Transfer<u8, Error = SpiError>
-        + hal::blocking::spi::Write<u8, Error = SpiError>,
-    OutputPin1: hal::digital::v2::OutputPin<Error = GpioError>,
-    OutputPin2: hal::digital::v2::OutputPin<Error = GpioError>,
-    InputPin: hal::digital::v2::InputPin<Error = GpioError>,
+    T: crate::CommandWriter,
 {
-    type Error = crate::Error<SpiError, GpioError>;
+    type Error = T::Error;
 
-    impl_params!(
+    impl_validate_params!(
         connection_parameter_update_request,
         ConnectionParameterUpdateRequest,
         crate::opcode::L2CAP_CONN_PARAM_UPDATE_REQ
     );
 
-    impl_params!(
+    impl_validate_params!(
         connection_parameter_update_response,
         ConnectionParameterUpdateResponse,
         crate::opcode::L2CAP_CONN_PARAM_UPDATE_RESP
     );
 }
 
+/// Potential errors from L2CAP commands. Must be specialized on the types of communication errors.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error<E> {
+    /// The connection handle is greater than 0x0EFF, the largest handle allowed by the Bluetooth
+    /// specification. Includes the invalid handle.
+    BadConnectionHandle(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        hci::ConnectionHandle,
+    ),
+
+    /// For the [connection parameter update
+    /// response](Commands::connection_parameter_update_response), the identifier is 0. The
+    /// Bluetooth specification reserves 0 as an invalid identifier for L2CAP signaling commands
+    /// (Vol 3, Part A, Section 4).
+    BadIdentifier(u8),
+
+    /// The parameters could not be serialized into the command buffer.
+    Encode(crate::EncodeError),
+
+    /// Underlying communication error.
+    Comm(E),
+}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for Error<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::BadConnectionHandle(conn_handle) => {
+                defmt::write!(f, "BadConnectionHandle({})", Remote(conn_handle))
+            }
+            Error::BadIdentifier(value) => defmt::write!(f, "BadIdentifier({})", value),
+            Error::Encode(value) => defmt::write!(f, "Encode({})", value),
+            Error::Comm(e) => defmt::write!(f, "Comm({})", e),
+        }
+    }
+}
+
+fn rewrap_error<E>(e: nb::Error<E>) -> nb::Error<Error<E>> {
+    match e {
+        nb::Error::WouldBlock => nb::Error::WouldBlock,
+        nb::Error::Other(c) => nb::Error::Other(Error::Comm(c)),
+    }
+}
+
+impl<E> From<Error<E>> for crate::CommandError<E> {
+    fn from(e: Error<E>) -> Self {
+        use self::Error::*;
+
+        let validation = match e {
+            Comm(e) => return crate::CommandError::Comm(e),
+            Encode(e) => crate::ValidationError::Encode(e),
+            BadConnectionHandle(a) => crate::ValidationError::L2Cap(BadConnectionHandle(a)),
+            BadIdentifier(a) => crate::ValidationError::L2Cap(BadIdentifier(a)),
+        };
+        crate::CommandError::Validation(validation)
+    }
+}
+
 /// Parameters for the
 /// [`connection_parameter_update_request`](Commands::connection_parameter_update_request)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ConnectionParameterUpdateRequest {
     /// Connection handle of the link which the connection parameter update request has to be sent.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// Defines the range of the connection interval.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ConnectionIntervalDef::serialize")
+    )]
     pub conn_interval: ConnectionInterval,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for ConnectionParameterUpdateRequest {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ConnectionParameterUpdateRequest {{ conn_handle: {}, conn_interval: {} }}",
+            Remote(&self.conn_handle),
+            defmt::Debug2Format(&self.conn_interval)
+        )
+    }
+}
+
 impl ConnectionParameterUpdateRequest {
     const LENGTH: usize = 10;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert_eq!(bytes.len(), Self::LENGTH);
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        Ok(())
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         LittleEndian::write_u16(&mut bytes[0..], self.conn_handle.0);
         self.conn_interval.copy_into_slice(&mut bytes[2..10]);
+
+        Ok(())
     }
 }
 
 /// Parameters for the
 /// [`connection_parameter_update_response`](Commands::connection_parameter_update_response)
 /// command.
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct ConnectionParameterUpdateResponse {
     /// [Connection handle](crate::event::L2CapConnectionUpdateRequest::conn_handle) received in the
     /// [`L2CapConnectionUpdateRequest`](crate::event::BlueNRGEvent::L2CapConnectionUpdateRequest)
     /// event.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 
     /// [Connection interval](crate::event::L2CapConnectionUpdateRequest::conn_interval) received in
     /// the
     /// [`L2CapConnectionUpdateRequest`](crate::event::BlueNRGEvent::L2CapConnectionUpdateRequest)
     /// event.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ConnectionIntervalDef::serialize")
+    )]
     pub conn_interval: ConnectionInterval,
 
     /// Expected length of connection event needed for this connection.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::expected_connection_length")
+    )]
     pub expected_connection_length_range: ExpectedConnectionLength,
 
     /// [Identifier](crate::event::L2CapConnectionUpdateRequest::identifier) received in the
@@ -125,11 +243,42 @@ pub struct ConnectionParameterUpdateResponse {
     pub accepted: bool,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for ConnectionParameterUpdateResponse {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ConnectionParameterUpdateResponse {{ conn_handle: {}, conn_interval: ",
+            Remote(&self.conn_handle)
+        );
+        defmt::write!(
+            f,
+            "{}, expected_connection_length_range: {}, identifier: {}, accepted: {} }}",
+            defmt::Debug2Format(&self.conn_interval),
+            defmt::Debug2Format(&self.expected_connection_length_range),
+            self.identifier,
+            self.accepted
+        )
+    }
+}
+
 impl ConnectionParameterUpdateResponse {
     const LENGTH: usize = 16;
 
-    fn copy_into_slice(&self, bytes: &mut [u8]) {
-        assert_eq!(bytes.len(), Self::LENGTH);
+    fn validate<E>(&self) -> Result<(), Error<E>> {
+        if !is_valid_conn_handle(self.conn_handle) {
+            return Err(Error::BadConnectionHandle(self.conn_handle));
+        }
+
+        if self.identifier == 0 {
+            return Err(Error::BadIdentifier(self.identifier));
+        }
+
+        Ok(())
+    }
+
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<(), EncodeError> {
+        check_buffer_len(bytes, Self::LENGTH)?;
 
         LittleEndian::write_u16(&mut bytes[0..], self.conn_handle.0);
         self.conn_interval.copy_into_slice(&mut bytes[2..10]);
@@ -137,5 +286,7 @@ impl ConnectionParameterUpdateResponse {
             .copy_into_slice(&mut bytes[10..14]);
         bytes[14] = self.identifier;
         bytes[15] = self.accepted as u8;
+
+        Ok(())
     }
 }
@@ -1,21 +1,25 @@
 macro_rules! impl_params {
     ($method:ident, $param_type:ident, $opcode:path) => {
-        fn $method(&mut self, params: &$param_type) -> nb::Result<(), Self::Error> {
+        fn $method(&mut self, params: &$param_type) -> nb::Result<(), Error<Self::Error>> {
             let mut bytes = [0; $param_type::LENGTH];
-            params.copy_into_slice(&mut bytes);
+            params
+                .copy_into_slice(&mut bytes)
+                .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
 
-            self.write_command($opcode, &bytes)
+            self.write_command($opcode, &bytes).map_err(rewrap_error)
         }
     };
 }
 
 macro_rules! impl_value_params {
     ($method:ident, $param_type:ident, $opcode:path) => {
-        fn $method(&mut self, params: $param_type) -> nb::Result<(), Self::Error> {
+        fn $method(&mut self, params: $param_type) -> nb::Result<(), Error<Self::Error>> {
             let mut bytes = [0; $param_type::LENGTH];
-            params.copy_into_slice(&mut bytes);
+            params
+                .copy_into_slice(&mut bytes)
+                .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
 
-            self.write_command($opcode, &bytes)
+            self.write_command($opcode, &bytes).map_err(rewrap_error)
         }
     };
 }
@@ -26,31 +30,24 @@ macro_rules! impl_validate_params {
             params.validate().map_err(nb::Error::Other)?;
 
             let mut bytes = [0; $param_type::LENGTH];
-            params.copy_into_slice(&mut bytes);
+            params
+                .copy_into_slice(&mut bytes)
+                .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
 
             self.write_command($opcode, &bytes).map_err(rewrap_error)
         }
     };
 }
 
-macro_rules! impl_variable_length_params {
-    ($method:ident, $param_type:ident, $opcode:path) => {
-        fn $method(&mut self, params: &$param_type) -> nb::Result<(), Self::Error> {
-            let mut bytes = [0; $param_type::MAX_LENGTH];
-            let len = params.copy_into_slice(&mut bytes);
-
-            self.write_command($opcode, &bytes[..len])
-        }
-    };
-}
-
 macro_rules! impl_validate_variable_length_params {
     ($method:ident, $param_type:ident, $opcode:path) => {
         fn $method(&mut self, params: &$param_type) -> nb::Result<(), Error<Self::Error>> {
             params.validate().map_err(nb::Error::Other)?;
 
             let mut bytes = [0; $param_type::MAX_LENGTH];
-            let len = params.copy_into_slice(&mut bytes);
+            let len = params
+                .copy_into_slice(&mut bytes)
+                .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
 
             self.write_command($opcode, &bytes[..len])
                 .map_err(rewrap_error)
@@ -64,7 +61,9 @@ macro_rules! impl_validate_variable_length_params {
             params.validate().map_err(nb::Error::Other)?;
 
             let mut bytes = [0; $param_type::MAX_LENGTH];
-            let len = params.copy_into_slice(&mut bytes);
+            let len = params
+                .copy_into_slice(&mut bytes)
+                .map_err(|e| nb::Error::Other(Error::Encode(e)))?;
 
             self.write_command($opcode, &bytes[..len])
                 .map_err(rewrap_error)
@@ -72,7 +71,176 @@ macro_rules! impl_validate_variable_length_params {
     };
 }
 
+pub mod decode;
 pub mod gap;
 pub mod gatt;
 pub mod hal;
 pub mod l2cap;
+pub mod raw;
+pub mod updater;
+
+/// Errors that may occur while serializing command parameters into a buffer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum EncodeError {
+    /// The buffer is too small to hold the serialized parameters. Includes the number of bytes
+    /// required and the length of the provided buffer.
+    BufferTooSmall {
+        /// Number of bytes needed to serialize the parameters.
+        required: usize,
+
+        /// Length of the provided buffer.
+        available: usize,
+    },
+}
+
+/// Errors from any vendor-specific command, whichever command group it belongs to.
+///
+/// Commands that validate their parameters report a command group error ([`gap::Error`],
+/// [`gatt::Error`], [`hal::Error`], [`l2cap::Error`], [`raw::Error`], or [`updater::Error`]); the
+/// others report the communication error of the controller directly. All of them convert into this
+/// type with `From`, so code that mixes commands from several groups can use a single error type.
+/// For the [`nb::Result`] returned by each command, use [`CommandError::rewrap`]:
+///
+/// ```
+/// # extern crate nb;
+/// use bluenrg::gap::Commands as GapCommands;
+/// use bluenrg::gatt::Commands as GattCommands;
+/// use bluenrg::CommandError;
+///
+/// fn start_gatt<C, E>(controller: &mut C) -> nb::Result<(), CommandError<E>>
+/// where
+///     C: GapCommands<Error = E> + GattCommands<Error = E>,
+/// {
+///     controller.set_nondiscoverable().map_err(CommandError::rewrap)?;
+///     GattCommands::init(controller).map_err(CommandError::rewrap)?;
+///     controller
+///         .update_advertising_data(&[0x02, 0x01, 0x06])
+///         .map_err(CommandError::rewrap)?;
+///     Ok(())
+/// }
+/// ```
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum CommandError<E> {
+    /// The command parameters were rejected before anything was sent to the controller.
+    Validation(ValidationError),
+
+    /// The controller reported that the command failed. Includes the status it returned.
+    Status(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Underlying communication error.
+    Comm(E),
+}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for CommandError<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            CommandError::Validation(value) => defmt::write!(f, "Validation({})", value),
+            CommandError::Status(status) => {
+                defmt::write!(f, "Status({})", crate::defmt_remote::Remote(status))
+            }
+            CommandError::Comm(e) => defmt::write!(f, "Comm({})", e),
+        }
+    }
+}
+
+impl<E> CommandError<E> {
+    /// Returns `Ok` if the controller reported success for a command, or
+    /// [`Status`](CommandError::Status) otherwise.
+    pub fn check_status(status: hci::Status<crate::event::Status>) -> Result<(), CommandError<E>> {
+        match status {
+            hci::Status::Success => Ok(()),
+            other => Err(CommandError::Status(other)),
+        }
+    }
+
+    /// Converts the result of any command into a `CommandError`, keeping
+    /// [`WouldBlock`](nb::Error::WouldBlock) as it is.
+    pub fn rewrap<T>(e: nb::Error<T>) -> nb::Error<CommandError<E>>
+    where
+        T: Into<CommandError<E>>,
+    {
+        match e {
+            nb::Error::WouldBlock => nb::Error::WouldBlock,
+            nb::Error::Other(e) => nb::Error::Other(e.into()),
+        }
+    }
+}
+
+impl<E> From<E> for CommandError<E> {
+    fn from(e: E) -> Self {
+        CommandError::Comm(e)
+    }
+}
+
+/// Validation errors of every command group, for [`CommandError::Validation`].
+///
+/// The command group errors are specialized on [`NoComm`], so they can only hold the validation
+/// variants; communication errors are reported in [`CommandError::Comm`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum ValidationError {
+    /// The parameters of a [GAP](gap) command are invalid.
+    Gap(gap::Error<NoComm>),
+
+    /// The parameters of a [GATT](gatt) command are invalid.
+    Gatt(gatt::Error<NoComm>),
+
+    /// The parameters of a [HAL](hal) command are invalid.
+    Hal(hal::Error<NoComm>),
+
+    /// The parameters of an [L2CAP](l2cap) command are invalid.
+    L2Cap(l2cap::Error<NoComm>),
+
+    /// The opcode or parameters of a [raw](raw) command are invalid.
+    Raw(raw::Error<NoComm>),
+
+    /// The parameters of an [updater](updater) command are invalid.
+    Updater(updater::Error<NoComm>),
+
+    /// The parameters could not be serialized into the command buffer.
+    Encode(EncodeError),
+
+    /// The command is only supported by the BlueNRG-MS, and the BlueNRG [variant](crate::Variant)
+    /// is selected.
+    UnsupportedByVariant,
+}
+
+/// Communication error type with no values, for command group errors that can only hold
+/// validation errors. See [`ValidationError`].
+///
+/// [Nonblocking procedures](crate#nonblocking-procedures) also use it for the failures they
+/// record while handling events, which cannot include communication errors. The failures are
+/// converted to the error type of the controller when `poll` reports them.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum NoComm {}
+
+/// Returns an error if `bytes` cannot hold `required` bytes. Encoders call this before writing
+/// anything, so the writes that follow stay in bounds.
+pub(crate) fn check_buffer_len(bytes: &[u8], required: usize) -> Result<(), EncodeError> {
+    if bytes.len() < required {
+        return Err(EncodeError::BufferTooSmall {
+            required,
+            available: bytes.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Returns true if the connection handle is in the range allowed by the Bluetooth specification
+/// (0x0000 to 0x0EFF; Vol 2, Part E, Section 5.4.2).
+pub(crate) fn is_valid_conn_handle(conn_handle: hci::ConnectionHandle) -> bool {
+    const MAX_CONN_HANDLE: u16 = 0x0EFF;
+
+    conn_handle.0 <= MAX_CONN_HANDLE
+}
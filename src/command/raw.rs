@@ -0,0 +1,101 @@
+//! Escape hatch for vendor-specific commands that this crate does not (yet) know about.
+//!
+//! Newer controller firmware may implement commands that are not in the other command modules.
+//! These commands can be sent with [`Commands::write_vendor_command`], and their return parameters
+//! are reported as [`Unknown`](crate::event::command::ReturnParameters::Unknown).
+
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+/// Raw vendor-specific commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG) and any other
+/// [`CommandWriter`](crate::CommandWriter).
+pub trait Commands {
+    /// Type of communication errors.
+    type Error;
+
+    /// Sends the vendor-specific command with the given OCF (Opcode Command Field) and already
+    /// serialized parameters. The OGF (Opcode Group Field) is always the vendor-specific OGF
+    /// (0x3F).
+    ///
+    /// No validation is done on the parameters, aside from their length.
+    ///
+    /// # Errors
+    ///
+    /// - [`BadOcf`](Error::BadOcf) if the OCF does not fit in 10 bits.
+    /// - [`ParametersTooLong`](Error::ParametersTooLong) if there are more than 255 bytes of
+    ///   parameters.
+    /// - Underlying communication errors.
+    ///
+    /// # Generated events
+    ///
+    /// Depends on the command. If the controller responds with a Command Complete event for an
+    /// opcode this crate does not recognize, its return parameters are reported as
+    /// [`Unknown`](crate::event::command::ReturnParameters::Unknown).
+    fn write_vendor_command(
+        &mut self,
+        ocf: u16,
+        params: &[u8],
+    ) -> nb::Result<(), Error<Self::Error>>;
+}
+
+impl<T> Commands for T
+where
+    T: crate::CommandWriter,
+{
+    type Error = T::Error;
+
+    fn write_vendor_command(
+        &mut self,
+        ocf: u16,
+        params: &[u8],
+    ) -> nb::Result<(), Error<Self::Error>> {
+        const MAX_OCF: u16 = 0x03FF;
+        const MAX_PARAMETER_LEN: usize = 255;
+
+        if ocf > MAX_OCF {
+            return Err(nb::Error::Other(Error::BadOcf(ocf)));
+        }
+        if params.len() > MAX_PARAMETER_LEN {
+            return Err(nb::Error::Other(Error::ParametersTooLong(params.len())));
+        }
+
+        self.write_command(crate::opcode::vendor(ocf), params)
+            .map_err(rewrap_error)
+    }
+}
+
+/// Potential errors from raw vendor-specific commands.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error<E> {
+    /// The OCF does not fit in the 10 bits allowed by the opcode. Includes the invalid OCF.
+    BadOcf(u16),
+
+    /// The parameters are longer than the 255 bytes that fit in one command. Includes the length
+    /// of the parameters.
+    ParametersTooLong(usize),
+
+    /// Underlying communication error.
+    Comm(E),
+}
+
+fn rewrap_error<E>(e: nb::Error<E>) -> nb::Error<Error<E>> {
+    match e {
+        nb::Error::WouldBlock => nb::Error::WouldBlock,
+        nb::Error::Other(c) => nb::Error::Other(Error::Comm(c)),
+    }
+}
+
+impl<E> From<Error<E>> for crate::CommandError<E> {
+    fn from(e: Error<E>) -> Self {
+        use self::Error::*;
+
+        let validation = match e {
+            Comm(e) => return crate::CommandError::Comm(e),
+            BadOcf(a) => crate::ValidationError::Raw(BadOcf(a)),
+            ParametersTooLong(a) => crate::ValidationError::Raw(ParametersTooLong(a)),
+        };
+        crate::CommandError::Validation(validation)
+    }
+}
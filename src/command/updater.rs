@@ -0,0 +1,660 @@
+//! Updater commands, for reprogramming the controller firmware.
+//!
+//! The updater is a small program in the first sector of the controller flash. The controller runs
+//! it instead of the Bluetooth stack after the [`updater_start`](Commands::updater_start) command,
+//! when the BLUE flag that marks the stack as valid is erased, or when the IRQ pin is held high
+//! during reset. Either way, the controller reports a [HAL
+//! Initialized](crate::event::BlueNRGEvent::HalInitialized) event with one of the updater [reset
+//! reasons](crate::event::ResetReason::Updater). While the updater runs, only the commands in this
+//! module are available.
+//!
+//! [`Flasher`] uses these commands to program a firmware image from ST into the controller.
+
+extern crate bluetooth_hci as hci;
+extern crate byteorder;
+extern crate nb;
+
+use crate::event::command::ReturnParameters;
+use byteorder::{ByteOrder, LittleEndian};
+use core::cmp::min;
+
+/// Updater commands for the [`ActiveBlueNRG`](crate::ActiveBlueNRG) and any other
+/// [`CommandWriter`](crate::CommandWriter).
+pub trait Commands {
+    /// Type of communication errors.
+    type Error;
+
+    /// Restarts the controller in updater mode.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterStart) event is
+    /// generated, followed by a [HAL Initialized](crate::event::BlueNRGEvent::HalInitialized) event
+    /// with the [`Updater`](crate::event::ResetReason::Updater) reset reason once the updater is
+    /// running.
+    fn updater_start(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Restarts the controller. If the BLUE flag is valid, the controller runs the Bluetooth stack;
+    /// otherwise, it stays in updater mode.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterReboot) event is
+    /// generated, followed by a [HAL Initialized](crate::event::BlueNRGEvent::HalInitialized) event.
+    fn updater_reboot(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Requests the version of the updater.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterGetVersion) event is
+    /// generated.
+    fn get_updater_version(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Requests the size of the updater buffer, which limits the length of the data that can be
+    /// [programmed](Commands::program_data_block) with each command.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterGetBufferSize) event is
+    /// generated.
+    fn get_updater_buffer_size(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Erases the BLUE flag, which marks the firmware as invalid. Until the flag is
+    /// [reset](Commands::reset_blue_flag), the controller restarts in updater mode.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterEraseBlueFlag) event is
+    /// generated.
+    fn erase_blue_flag(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Restores the BLUE flag, which marks the firmware as valid. This should only be done once
+    /// the complete firmware has been programmed and verified.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterResetBlueFlag) event is
+    /// generated.
+    fn reset_blue_flag(&mut self) -> nb::Result<(), Self::Error>;
+
+    /// Erases the flash sector that starts at the given address.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterEraseSector) event is
+    /// generated.
+    fn erase_sector(&mut self, address: u32) -> nb::Result<(), Self::Error>;
+
+    /// Programs the data into flash, starting at the given address. The flash must have been
+    /// [erased](Commands::erase_sector) first.
+    ///
+    /// # Errors
+    ///
+    /// - [`DataTooLong`](Error::DataTooLong) if the data is longer than [`MAX_DATA_BLOCK_LEN`].
+    /// - Underlying communication errors.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterProgramDataBlock) event
+    /// is generated.
+    fn program_data_block(
+        &mut self,
+        address: u32,
+        data: &[u8],
+    ) -> nb::Result<(), Error<Self::Error>>;
+
+    /// Reads `len` bytes of flash, starting at the given address.
+    ///
+    /// # Errors
+    ///
+    /// - [`DataTooLong`](Error::DataTooLong) if `len` is greater than [`MAX_DATA_BLOCK_LEN`].
+    /// - Underlying communication errors.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterReadDataBlock) event is
+    /// generated.
+    fn read_data_block(&mut self, address: u32, len: usize) -> nb::Result<(), Error<Self::Error>>;
+
+    /// Calculates the CRC of `sectors` flash sectors, starting with the sector at the given address.
+    /// The CRC can be compared with the [CRC](crc) of the data that should be in flash.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    ///
+    /// # Generated events
+    ///
+    /// A [Command Complete](crate::event::command::ReturnParameters::UpdaterCalculateCrc) event is
+    /// generated.
+    fn calculate_crc(&mut self, address: u32, sectors: u8) -> nb::Result<(), Self::Error>;
+}
+
+impl<T> Commands for T
+where
+    T: crate::CommandWriter,
+{
+    type Error = T::Error;
+
+    fn updater_start(&mut self) -> nb::Result<(), Self::Error> {
+        self.write_command(crate::opcode::UPDATER_START, &[])
+    }
+
+    fn updater_reboot(&mut self) -> nb::Result<(), Self::Error> {
+        self.write_command(crate::opcode::UPDATER_REBOOT, &[])
+    }
+
+    fn get_updater_version(&mut self) -> nb::Result<(), Self::Error> {
+        self.write_command(crate::opcode::UPDATER_GET_VERSION, &[])
+    }
+
+    fn get_updater_buffer_size(&mut self) -> nb::Result<(), Self::Error> {
+        self.write_command(crate::opcode::UPDATER_GET_BUFFER_SIZE, &[])
+    }
+
+    fn erase_blue_flag(&mut self) -> nb::Result<(), Self::Error> {
+        self.write_command(crate::opcode::UPDATER_ERASE_BLUE_FLAG, &[])
+    }
+
+    fn reset_blue_flag(&mut self) -> nb::Result<(), Self::Error> {
+        self.write_command(crate::opcode::UPDATER_RESET_BLUE_FLAG, &[])
+    }
+
+    fn erase_sector(&mut self, address: u32) -> nb::Result<(), Self::Error> {
+        let mut bytes = [0; 4];
+        LittleEndian::write_u32(&mut bytes, address);
+
+        self.write_command(crate::opcode::UPDATER_ERASE_SECTOR, &bytes)
+    }
+
+    fn program_data_block(
+        &mut self,
+        address: u32,
+        data: &[u8],
+    ) -> nb::Result<(), Error<Self::Error>> {
+        if data.len() > MAX_DATA_BLOCK_LEN {
+            return Err(nb::Error::Other(Error::DataTooLong(data.len())));
+        }
+
+        let mut bytes = [0; 6 + MAX_DATA_BLOCK_LEN];
+        LittleEndian::write_u32(&mut bytes[0..4], address);
+        LittleEndian::write_u16(&mut bytes[4..6], data.len() as u16);
+        bytes[6..6 + data.len()].copy_from_slice(data);
+
+        self.write_command(
+            crate::opcode::UPDATER_PROGRAM_DATA_BLOCK,
+            &bytes[..6 + data.len()],
+        )
+        .map_err(rewrap_error)
+    }
+
+    fn read_data_block(&mut self, address: u32, len: usize) -> nb::Result<(), Error<Self::Error>> {
+        if len > MAX_DATA_BLOCK_LEN {
+            return Err(nb::Error::Other(Error::DataTooLong(len)));
+        }
+
+        let mut bytes = [0; 6];
+        LittleEndian::write_u32(&mut bytes[0..4], address);
+        LittleEndian::write_u16(&mut bytes[4..6], len as u16);
+
+        self.write_command(crate::opcode::UPDATER_READ_DATA_BLOCK, &bytes)
+            .map_err(rewrap_error)
+    }
+
+    fn calculate_crc(&mut self, address: u32, sectors: u8) -> nb::Result<(), Self::Error> {
+        let mut bytes = [0; 5];
+        LittleEndian::write_u32(&mut bytes[0..4], address);
+        bytes[4] = sectors;
+
+        self.write_command(crate::opcode::UPDATER_CALC_CRC, &bytes)
+    }
+}
+
+/// Maximum length of the data in a single [program](Commands::program_data_block) or
+/// [read](Commands::read_data_block) command. The 255 bytes of command parameters also hold the
+/// 4-byte address and the 2-byte length.
+pub const MAX_DATA_BLOCK_LEN: usize = 249;
+
+/// Potential errors from updater commands. Must be specialized on the types of communication
+/// errors.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error<E> {
+    /// The data block is longer than [`MAX_DATA_BLOCK_LEN`]. Includes the length of the data.
+    DataTooLong(usize),
+
+    /// Underlying communication error.
+    Comm(E),
+}
+
+pub(crate) fn rewrap_error<E>(e: nb::Error<E>) -> nb::Error<Error<E>> {
+    match e {
+        nb::Error::WouldBlock => nb::Error::WouldBlock,
+        nb::Error::Other(c) => nb::Error::Other(Error::Comm(c)),
+    }
+}
+
+impl<E> From<Error<E>> for crate::CommandError<E> {
+    fn from(e: Error<E>) -> Self {
+        use self::Error::*;
+
+        let validation = match e {
+            Comm(e) => return crate::CommandError::Comm(e),
+            DataTooLong(a) => crate::ValidationError::Updater(DataTooLong(a)),
+        };
+        crate::CommandError::Validation(validation)
+    }
+}
+
+/// Address of the start of the controller flash.
+pub const BASE_ADDRESS: u32 = 0x1001_0000;
+
+/// Size of a flash sector, which is the unit for [erasing](Commands::erase_sector) flash and
+/// [calculating its CRC](Commands::calculate_crc).
+pub const SECTOR_SIZE: usize = 2 * 1024;
+
+/// Offset of the Bluetooth stack in the flash and in firmware images. The first sector holds the
+/// updater itself, which is never reprogrammed.
+pub const FIRMWARE_OFFSET: usize = SECTOR_SIZE;
+
+/// Size of the flash that holds the updater and the Bluetooth stack. Firmware images are never
+/// larger. The sector that follows holds the device configuration (IFR), which the flasher leaves
+/// alone.
+pub const MAX_IMAGE_LEN: usize = 64 * 1024;
+
+const CRC_POLY: u32 = 0x04C1_1DB7;
+
+/// Returns the CRC that the updater [calculates](Commands::calculate_crc) for flash that holds
+/// `data`. The CRC is calculated over 32-bit little-endian words; if the length of `data` is not a
+/// multiple of 4, the last word is padded with erased (0xFF) bytes.
+pub fn crc(data: &[u8]) -> u32 {
+    crc_bytes(data.iter().cloned())
+}
+
+// Returns the CRC of a sector that holds `data`, with the rest of the sector erased.
+pub(crate) fn sector_crc(data: &[u8]) -> u32 {
+    crc_bytes(
+        data.iter()
+            .cloned()
+            .chain(core::iter::repeat(0xFF))
+            .take(SECTOR_SIZE),
+    )
+}
+
+fn crc_bytes<I>(bytes: I) -> u32
+where
+    I: Iterator<Item = u8>,
+{
+    let mut crc = 0;
+    let mut word = [0xFF; 4];
+    let mut word_len = 0;
+    for byte in bytes {
+        word[word_len] = byte;
+        word_len += 1;
+        if word_len == word.len() {
+            crc = crc_word(crc, LittleEndian::read_u32(&word));
+            word = [0xFF; 4];
+            word_len = 0;
+        }
+    }
+    if word_len > 0 {
+        crc = crc_word(crc, LittleEndian::read_u32(&word));
+    }
+
+    crc
+}
+
+fn crc_word(crc: u32, word: u32) -> u32 {
+    let mut crc = crc ^ word;
+    for _ in 0..32 {
+        crc = if crc & 0x8000_0000 == 0 {
+            crc << 1
+        } else {
+            (crc << 1) ^ CRC_POLY
+        };
+    }
+
+    crc
+}
+
+/// Error returned by [`Flasher::new`] if the image cannot be a firmware image. The image must be
+/// longer than [`FIRMWARE_OFFSET`], no longer than [`MAX_IMAGE_LEN`], and a whole number of 32-bit
+/// words. Includes the length of the image.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct InvalidImageLength(pub usize);
+
+/// Potential errors while [flashing](Flasher) a firmware image, or while reading or writing the
+/// [IFR](crate::ifr).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FlashError<E> {
+    /// The updater buffer is too small to program even a single word. Includes the reported
+    /// buffer size.
+    BufferTooSmall(usize),
+
+    /// The controller reported that a command failed. Includes the opcode of the command and the
+    /// status it returned.
+    CommandFailed(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::OpcodeDef"))] hci::Opcode,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// The CRC of a sector did not match the image after it was programmed. Includes the address
+    /// of the sector.
+    VerifyFailed(u32),
+
+    /// The controller returned less data than was requested. Includes the address of the data.
+    ShortRead(u32),
+
+    /// A command could not be sent.
+    Command(Error<E>),
+}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for FlashError<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            FlashError::BufferTooSmall(size) => defmt::write!(f, "BufferTooSmall({})", size),
+            FlashError::CommandFailed(opcode, status) => {
+                defmt::write!(
+                    f,
+                    "CommandFailed({}, {})",
+                    crate::defmt_remote::Remote(opcode),
+                    crate::defmt_remote::Remote(status)
+                )
+            }
+            FlashError::VerifyFailed(address) => {
+                defmt::write!(f, "VerifyFailed({=u32:#x})", address)
+            }
+            FlashError::ShortRead(address) => defmt::write!(f, "ShortRead({=u32:#x})", address),
+            FlashError::Command(e) => defmt::write!(f, "Command({})", e),
+        }
+    }
+}
+
+impl<E> FlashError<E> {
+    pub(crate) fn widen(failure: FlashError<crate::NoComm>) -> FlashError<E> {
+        match failure {
+            FlashError::BufferTooSmall(size) => FlashError::BufferTooSmall(size),
+            FlashError::CommandFailed(opcode, status) => FlashError::CommandFailed(opcode, status),
+            FlashError::VerifyFailed(address) => FlashError::VerifyFailed(address),
+            FlashError::ShortRead(address) => FlashError::ShortRead(address),
+            FlashError::Command(Error::DataTooLong(len)) => {
+                FlashError::Command(Error::DataTooLong(len))
+            }
+            FlashError::Command(Error::Comm(never)) => match never {},
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Step {
+    GetBufferSize,
+    EraseBlueFlag,
+    CheckSector,
+    EraseSector,
+    Program,
+    VerifySector,
+    ResetBlueFlag,
+    Done,
+}
+
+const FIRST_SECTOR: usize = FIRMWARE_OFFSET / SECTOR_SIZE;
+const SECTOR_COUNT: usize = MAX_IMAGE_LEN / SECTOR_SIZE;
+
+/// Programs a firmware image into the controller flash, and verifies it.
+///
+/// The controller must be in updater mode. The flasher erases the BLUE flag, then, for each
+/// sector after the updater, compares the CRC of the flash with the image. Sectors that already
+/// match are skipped; the others are erased, programmed, and verified. Flash beyond the end of the
+/// image is left erased. Finally, the BLUE flag is reset, so that the new firmware runs after the
+/// controller is [rebooted](Commands::updater_reboot).
+///
+/// The flasher sends one command at a time; see
+/// [nonblocking procedures](crate#nonblocking-procedures) for how to drive it.
+/// [`progress`](Flasher::progress) reports how many sectors are done.
+///
+/// # Resuming
+///
+/// If flashing is interrupted, for example by a reset or a power failure, the BLUE flag is still
+/// erased, so the controller restarts in updater mode (with the
+/// [`UpdaterBadFlag`](crate::event::ResetReason::UpdaterBadFlag) reset reason). Flash the same image
+/// again with a new `Flasher`: the sectors that were completed before the interruption already
+/// match the image, so flashing resumes where it stopped.
+pub struct Flasher<'img> {
+    image: &'img [u8],
+    step: Step,
+    block_len: usize,
+    sector: usize,
+    offset: usize,
+    awaiting: bool,
+    failure: Option<FlashError<crate::NoComm>>,
+}
+
+impl<'img> Flasher<'img> {
+    /// Returns a flasher for the given firmware image. The image starts at [`BASE_ADDRESS`], so it
+    /// includes the updater sector, which is not programmed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`InvalidImageLength`] if the image cannot be a firmware image.
+    pub fn new(image: &'img [u8]) -> Result<Flasher<'img>, InvalidImageLength> {
+        if image.len() <= FIRMWARE_OFFSET || image.len() > MAX_IMAGE_LEN || image.len() & 3 != 0 {
+            return Err(InvalidImageLength(image.len()));
+        }
+
+        Ok(Flasher {
+            image,
+            step: Step::GetBufferSize,
+            block_len: 0,
+            sector: FIRST_SECTOR,
+            offset: 0,
+            awaiting: false,
+            failure: None,
+        })
+    }
+
+    /// Returns the number of sectors that have been verified, and the total number of sectors.
+    pub fn progress(&self) -> (usize, usize) {
+        (self.sector - FIRST_SECTOR, SECTOR_COUNT - FIRST_SECTOR)
+    }
+
+    /// Returns true if the image has been programmed and verified, and the BLUE flag reset.
+    pub fn is_done(&self) -> bool {
+        self.step == Step::Done
+    }
+
+    /// Sends the next command to the controller, unless the flasher is waiting for the Command
+    /// Complete event of the previous one.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] while flashing is in progress.
+    /// - Returns a [`FlashError`] if flashing failed. Flashing can be retried with a new `Flasher`.
+    pub fn poll<C>(&mut self, controller: &mut C) -> nb::Result<(), FlashError<C::Error>>
+    where
+        C: Commands,
+    {
+        if let Some(failure) = self.failure {
+            return Err(nb::Error::Other(FlashError::widen(failure)));
+        }
+        if self.step == Step::Done {
+            return Ok(());
+        }
+        if self.awaiting {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let address = self.sector_address();
+        let result = match self.step {
+            Step::GetBufferSize => controller.get_updater_buffer_size().map_err(rewrap_error),
+            Step::EraseBlueFlag => controller.erase_blue_flag().map_err(rewrap_error),
+            Step::CheckSector | Step::VerifySector => {
+                controller.calculate_crc(address, 1).map_err(rewrap_error)
+            }
+            Step::EraseSector => controller.erase_sector(address).map_err(rewrap_error),
+            Step::Program => {
+                let end = min(self.offset + self.block_len, self.programmed_end());
+                controller.program_data_block(
+                    BASE_ADDRESS + self.offset as u32,
+                    &self.image[self.offset..end],
+                )
+            }
+            Step::ResetBlueFlag => controller.reset_blue_flag().map_err(rewrap_error),
+            Step::Done => Ok(()),
+        };
+        match result {
+            Ok(()) => {
+                self.awaiting = true;
+                Err(nb::Error::WouldBlock)
+            }
+            Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+            Err(nb::Error::Other(e)) => Err(nb::Error::Other(FlashError::Command(e))),
+        }
+    }
+
+    /// Advances flashing with the return parameters of the command that was sent last. Events
+    /// that do not complete that command are ignored.
+    pub fn handle_event(&mut self, event: &hci::Event<crate::event::BlueNRGEvent>) {
+        if !self.awaiting {
+            return;
+        }
+        if let hci::Event::CommandComplete(ref event) = *event {
+            if let hci::event::command::ReturnParameters::Vendor(ref params) = event.return_params {
+                self.handle_return_parameters(params);
+            }
+        }
+    }
+
+    fn handle_return_parameters(&mut self, params: &ReturnParameters) {
+        let (opcode, status) = match (self.step, params) {
+            (Step::GetBufferSize, ReturnParameters::UpdaterGetBufferSize(params)) => {
+                (crate::opcode::UPDATER_GET_BUFFER_SIZE, params.status)
+            }
+            (Step::EraseBlueFlag, ReturnParameters::UpdaterEraseBlueFlag(status)) => {
+                (crate::opcode::UPDATER_ERASE_BLUE_FLAG, *status)
+            }
+            (Step::CheckSector, ReturnParameters::UpdaterCalculateCrc(params))
+            | (Step::VerifySector, ReturnParameters::UpdaterCalculateCrc(params)) => {
+                (crate::opcode::UPDATER_CALC_CRC, params.status)
+            }
+            (Step::EraseSector, ReturnParameters::UpdaterEraseSector(status)) => {
+                (crate::opcode::UPDATER_ERASE_SECTOR, *status)
+            }
+            (Step::Program, ReturnParameters::UpdaterProgramDataBlock(status)) => {
+                (crate::opcode::UPDATER_PROGRAM_DATA_BLOCK, *status)
+            }
+            (Step::ResetBlueFlag, ReturnParameters::UpdaterResetBlueFlag(status)) => {
+                (crate::opcode::UPDATER_RESET_BLUE_FLAG, *status)
+            }
+            _ => return,
+        };
+
+        self.awaiting = false;
+        if status != hci::Status::Success {
+            self.failure = Some(FlashError::CommandFailed(opcode, status));
+            return;
+        }
+
+        match (self.step, params) {
+            (Step::GetBufferSize, ReturnParameters::UpdaterGetBufferSize(params)) => {
+                // Program whole words, so the last block of the image is never split.
+                self.block_len = min(params.buffer_size, MAX_DATA_BLOCK_LEN) & !3;
+                if self.block_len == 0 {
+                    self.failure = Some(FlashError::BufferTooSmall(params.buffer_size));
+                } else {
+                    self.step = Step::EraseBlueFlag;
+                }
+            }
+            (Step::EraseBlueFlag, _) => self.step = Step::CheckSector,
+            (Step::CheckSector, ReturnParameters::UpdaterCalculateCrc(params)) => {
+                if params.crc == self.sector_crc() {
+                    self.next_sector();
+                } else {
+                    self.step = Step::EraseSector;
+                }
+            }
+            (Step::EraseSector, _) => {
+                self.offset = self.sector * SECTOR_SIZE;
+                self.step = if self.offset < self.programmed_end() {
+                    Step::Program
+                } else {
+                    Step::VerifySector
+                };
+            }
+            (Step::Program, _) => {
+                self.offset = min(self.offset + self.block_len, self.programmed_end());
+                if self.offset == self.programmed_end() {
+                    self.step = Step::VerifySector;
+                }
+            }
+            (Step::VerifySector, ReturnParameters::UpdaterCalculateCrc(params)) => {
+                if params.crc == self.sector_crc() {
+                    self.next_sector();
+                } else {
+                    self.failure = Some(FlashError::VerifyFailed(self.sector_address()));
+                }
+            }
+            (Step::ResetBlueFlag, _) => self.step = Step::Done,
+            _ => (),
+        }
+    }
+
+    fn sector_address(&self) -> u32 {
+        BASE_ADDRESS + (self.sector * SECTOR_SIZE) as u32
+    }
+
+    // Returns the offset of the end of the image data in the current sector.
+    fn programmed_end(&self) -> usize {
+        min((self.sector + 1) * SECTOR_SIZE, self.image.len())
+    }
+
+    // Returns the CRC the current sector has once it holds its part of the image, with the rest of
+    // the sector erased.
+    fn sector_crc(&self) -> u32 {
+        let start = min(self.sector * SECTOR_SIZE, self.image.len());
+        sector_crc(&self.image[start..self.programmed_end()])
+    }
+
+    fn next_sector(&mut self) {
+        self.sector += 1;
+        self.step = if self.sector == SECTOR_COUNT {
+            Step::ResetBlueFlag
+        } else {
+            Step::CheckSector
+        };
+    }
+}
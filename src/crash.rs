@@ -0,0 +1,466 @@
+//! Capture and persistence of controller crash reports.
+//!
+//! After an NMI or hard fault, the controller resets, reports [`ResetReason::Crash`] in the
+//! [`HalInitialized`](BlueNRGEvent::HalInitialized) event, and follows it with a
+//! [`CrashReport`](BlueNRGEvent::CrashReport) event. [`Monitor`] saves each crash report as a
+//! [`Record`] in application [`Storage`], and keeps [counts](CrashCounts) of the abnormal resets
+//! across resets of the host.
+//!
+//! Records use a compact, versioned binary format, so they can be stored in flash and decoded
+//! later, for example with the `bluenrg-decode --crash` tool.
+
+extern crate bluetooth_hci as hci;
+
+use crate::event::{BlueNRGEvent, CrashReason, FaultData, ResetReason};
+use byteorder::{ByteOrder, LittleEndian};
+use core::convert::TryFrom;
+
+/// Version of the [record](Record) and [counts](CrashCounts) formats.
+pub const FORMAT_VERSION: u8 = 1;
+
+// Version, crash reason, reset reason, sequence number, 9 registers, and the debug data length.
+const RECORD_HEADER_LEN: usize = 1 + 1 + 1 + 4 + 9 * 4 + 1;
+
+/// Maximum length of a serialized [`Record`].
+pub const MAX_RECORD_LEN: usize = RECORD_HEADER_LEN + 255;
+
+/// Length of serialized [`CrashCounts`].
+pub const COUNTS_LEN: usize = 1 + 4 * 4;
+
+/// Potential errors from serializing or deserializing crash data.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum FormatError {
+    /// The buffer is too small for the serialized data. Includes the required length.
+    BufferTooSmall(usize),
+
+    /// The data ended early. Includes the length of the data.
+    Truncated(usize),
+
+    /// The data was written with an unsupported format version. Includes the version.
+    BadVersion(u8),
+
+    /// The crash reason was not recognized. Includes the invalid value.
+    BadCrashReason(u8),
+
+    /// The reset reason was not recognized. Includes the invalid value.
+    BadResetReason(u8),
+}
+
+/// Potential errors from the crash [`Monitor`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error<E> {
+    /// The stored counts could not be deserialized.
+    Format(FormatError),
+
+    /// Underlying storage error.
+    Storage(E),
+}
+
+/// Application storage for crash data, typically a reserved area of flash.
+pub trait Storage {
+    /// Type of errors from the storage.
+    type Error;
+
+    /// Reads the counts saved by [`save_counts`](Storage::save_counts) into `counts`. Returns
+    /// false if no counts have been saved, for example because the storage is erased.
+    fn load_counts(&mut self, counts: &mut [u8; COUNTS_LEN]) -> Result<bool, Self::Error>;
+
+    /// Saves the counts, replacing any previously saved counts.
+    fn save_counts(&mut self, counts: &[u8; COUNTS_LEN]) -> Result<(), Self::Error>;
+
+    /// Saves a new crash record. Each record is at most [`MAX_RECORD_LEN`] bytes. How many records
+    /// are kept is up to the storage.
+    fn save_record(&mut self, record: &[u8]) -> Result<(), Self::Error>;
+}
+
+/// Numbers of abnormal resets of the controller.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CrashCounts {
+    /// Resets caused by a [crash](ResetReason::Crash).
+    pub crashes: u32,
+
+    /// Resets caused by the [watchdog](ResetReason::Watchdog).
+    pub watchdog_resets: u32,
+
+    /// Resets caused by a [lockup](ResetReason::Lockup).
+    pub lockups: u32,
+
+    /// Crash reports that were saved.
+    pub reports: u32,
+}
+
+impl CrashCounts {
+    /// Returns the total number of abnormal resets.
+    pub fn resets(&self) -> u32 {
+        self.crashes
+            .wrapping_add(self.watchdog_resets)
+            .wrapping_add(self.lockups)
+    }
+
+    /// Serializes the counts.
+    pub fn to_bytes(&self) -> [u8; COUNTS_LEN] {
+        let mut bytes = [0; COUNTS_LEN];
+        bytes[0] = FORMAT_VERSION;
+        LittleEndian::write_u32(&mut bytes[1..], self.crashes);
+        LittleEndian::write_u32(&mut bytes[5..], self.watchdog_resets);
+        LittleEndian::write_u32(&mut bytes[9..], self.lockups);
+        LittleEndian::write_u32(&mut bytes[13..], self.reports);
+
+        bytes
+    }
+
+    /// Deserializes counts written by [`to_bytes`](CrashCounts::to_bytes).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BadVersion`](FormatError::BadVersion) if the counts were written with a different
+    /// format version.
+    pub fn from_bytes(bytes: &[u8; COUNTS_LEN]) -> Result<CrashCounts, FormatError> {
+        if bytes[0] != FORMAT_VERSION {
+            return Err(FormatError::BadVersion(bytes[0]));
+        }
+
+        Ok(CrashCounts {
+            crashes: LittleEndian::read_u32(&bytes[1..]),
+            watchdog_resets: LittleEndian::read_u32(&bytes[5..]),
+            lockups: LittleEndian::read_u32(&bytes[9..]),
+            reports: LittleEndian::read_u32(&bytes[13..]),
+        })
+    }
+}
+
+/// Registers stacked by the controller when it faulted.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Registers {
+    /// SP register
+    pub sp: u32,
+    /// R0 register
+    pub r0: u32,
+    /// R1 register
+    pub r1: u32,
+    /// R2 register
+    pub r2: u32,
+    /// R3 register
+    pub r3: u32,
+    /// R12 register
+    pub r12: u32,
+    /// LR register
+    pub lr: u32,
+    /// PC register
+    pub pc: u32,
+    /// xPSR register
+    pub xpsr: u32,
+}
+
+/// Regions of the Cortex-M memory map.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Region {
+    /// Code (flash and ROM), from 0x0000_0000.
+    Code,
+    /// SRAM, from 0x2000_0000.
+    Sram,
+    /// Peripherals, from 0x4000_0000.
+    Peripheral,
+    /// External RAM and devices, from 0x6000_0000.
+    External,
+    /// System control space and vendor-specific memory, from 0xE000_0000.
+    System,
+}
+
+impl Region {
+    /// Returns the region of the given address.
+    pub fn of(address: u32) -> Region {
+        match address {
+            0x0000_0000..=0x1FFF_FFFF => Region::Code,
+            0x2000_0000..=0x3FFF_FFFF => Region::Sram,
+            0x4000_0000..=0x5FFF_FFFF => Region::Peripheral,
+            0x6000_0000..=0xDFFF_FFFF => Region::External,
+            _ => Region::System,
+        }
+    }
+}
+
+/// Meanings of an `EXC_RETURN` value in the LR register.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ExceptionReturn {
+    /// Return to handler mode, using the main stack.
+    Handler,
+    /// Return to thread mode, using the main stack.
+    ThreadMainStack,
+    /// Return to thread mode, using the process stack.
+    ThreadProcessStack,
+}
+
+impl Registers {
+    fn from_fault(fault: &FaultData) -> Registers {
+        Registers {
+            sp: fault.sp,
+            r0: fault.r0,
+            r1: fault.r1,
+            r2: fault.r2,
+            r3: fault.r3,
+            r12: fault.r12,
+            lr: fault.lr,
+            pc: fault.pc,
+            xpsr: fault.xpsr,
+        }
+    }
+
+    fn to_array(self) -> [u32; 9] {
+        [
+            self.sp, self.r0, self.r1, self.r2, self.r3, self.r12, self.lr, self.pc, self.xpsr,
+        ]
+    }
+
+    /// Returns the number of the exception that was active when the controller faulted, from the
+    /// IPSR bits of xPSR. 0 is thread mode, 2 is NMI, 3 is HardFault, and 16 and up are
+    /// interrupts.
+    pub fn exception_number(&self) -> u16 {
+        (self.xpsr & 0x1FF) as u16
+    }
+
+    /// Returns true if the Thumb bit of xPSR is set. Cortex-M cores only execute Thumb code, so a
+    /// clear bit means the core faulted after branching to an even address.
+    pub fn is_thumb(&self) -> bool {
+        self.xpsr & (1 << 24) != 0
+    }
+
+    /// Returns the meaning of the LR register if it holds an `EXC_RETURN` value, which means the
+    /// fault happened at the very start of an exception handler.
+    pub fn exception_return(&self) -> Option<ExceptionReturn> {
+        match self.lr {
+            0xFFFF_FFF1 => Some(ExceptionReturn::Handler),
+            0xFFFF_FFF9 => Some(ExceptionReturn::ThreadMainStack),
+            0xFFFF_FFFD => Some(ExceptionReturn::ThreadProcessStack),
+            _ => None,
+        }
+    }
+}
+
+/// A crash report, as saved by the [`Monitor`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Record<'a> {
+    /// Reason for the fault.
+    pub crash_reason: CrashReason,
+
+    /// Reason for the reset that preceded the crash report, if it was reported.
+    pub reset_reason: Option<ResetReason>,
+
+    /// Number of abnormal resets, including this one, when the crash report was received. Matches
+    /// [`CrashCounts::resets`].
+    pub sequence: u32,
+
+    /// Registers stacked by the fault.
+    pub registers: Registers,
+
+    /// Additional debug data from the controller.
+    pub debug_data: &'a [u8],
+}
+
+impl<'a> Record<'a> {
+    /// Returns a record of the given fault data.
+    pub fn new(fault: &'a FaultData, reset_reason: Option<ResetReason>, sequence: u32) -> Self {
+        Record {
+            crash_reason: fault.reason,
+            reset_reason,
+            sequence,
+            registers: Registers::from_fault(fault),
+            debug_data: fault.debug_data(),
+        }
+    }
+
+    /// Returns the serialized length of the record.
+    pub fn len(&self) -> usize {
+        RECORD_HEADER_LEN + self.debug_data.len()
+    }
+
+    /// Returns true if the record has no debug data.
+    pub fn is_empty(&self) -> bool {
+        self.debug_data.is_empty()
+    }
+
+    /// Serializes the record into `bytes`, and returns the number of bytes used.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BufferTooSmall`](FormatError::BufferTooSmall) if `bytes` is shorter than
+    /// [`len`](Record::len).
+    pub fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, FormatError> {
+        if bytes.len() < self.len() {
+            return Err(FormatError::BufferTooSmall(self.len()));
+        }
+
+        bytes[0] = FORMAT_VERSION;
+        bytes[1] = self.crash_reason.into();
+        bytes[2] = self.reset_reason.map_or(0, u8::from);
+        LittleEndian::write_u32(&mut bytes[3..], self.sequence);
+        for (i, register) in self.registers.to_array().iter().enumerate() {
+            LittleEndian::write_u32(&mut bytes[7 + 4 * i..], *register);
+        }
+        bytes[RECORD_HEADER_LEN - 1] = self.debug_data.len() as u8;
+        bytes[RECORD_HEADER_LEN..self.len()].copy_from_slice(self.debug_data);
+
+        Ok(self.len())
+    }
+
+    /// Deserializes a record written by [`copy_into_slice`](Record::copy_into_slice). Bytes after
+    /// the record are ignored.
+    ///
+    /// # Errors
+    ///
+    /// - [`Truncated`](FormatError::Truncated) if `bytes` is shorter than the record.
+    /// - [`BadVersion`](FormatError::BadVersion) if the record was written with a different format
+    ///   version.
+    /// - [`BadCrashReason`](FormatError::BadCrashReason) or
+    ///   [`BadResetReason`](FormatError::BadResetReason) if a reason is not recognized.
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Record<'a>, FormatError> {
+        if let Some(&version) = bytes.first() {
+            if version != FORMAT_VERSION {
+                return Err(FormatError::BadVersion(version));
+            }
+        }
+        if bytes.len() < RECORD_HEADER_LEN {
+            return Err(FormatError::Truncated(bytes.len()));
+        }
+        let len = RECORD_HEADER_LEN + bytes[RECORD_HEADER_LEN - 1] as usize;
+        if bytes.len() < len {
+            return Err(FormatError::Truncated(bytes.len()));
+        }
+
+        let crash_reason =
+            CrashReason::try_from(bytes[1]).map_err(|_| FormatError::BadCrashReason(bytes[1]))?;
+        let reset_reason = match bytes[2] {
+            0 => None,
+            value => {
+                Some(ResetReason::try_from(value).map_err(|_| FormatError::BadResetReason(value))?)
+            }
+        };
+        let register = |i: usize| LittleEndian::read_u32(&bytes[7 + 4 * i..]);
+
+        Ok(Record {
+            crash_reason,
+            reset_reason,
+            sequence: LittleEndian::read_u32(&bytes[3..]),
+            registers: Registers {
+                sp: register(0),
+                r0: register(1),
+                r1: register(2),
+                r2: register(3),
+                r3: register(4),
+                r12: register(5),
+                lr: register(6),
+                pc: register(7),
+                xpsr: register(8),
+            },
+            debug_data: &bytes[RECORD_HEADER_LEN..len],
+        })
+    }
+}
+
+/// Saves crash reports and counts abnormal resets.
+///
+/// Pass every event from the controller to [`handle_event`](Monitor::handle_event). The counts are
+/// saved each time they change, so they survive resets of the host.
+pub struct Monitor<S> {
+    storage: S,
+    counts: CrashCounts,
+    last_reset: Option<ResetReason>,
+}
+
+impl<S> Monitor<S>
+where
+    S: Storage,
+{
+    /// Returns a monitor that uses the given storage, starting from the counts saved in it.
+    ///
+    /// # Errors
+    ///
+    /// - [`Format`](Error::Format) if the saved counts cannot be deserialized.
+    /// - [`Storage`](Error::Storage) if the storage cannot be read.
+    pub fn new(mut storage: S) -> Result<Monitor<S>, Error<S::Error>> {
+        let mut bytes = [0; COUNTS_LEN];
+        let counts = if storage.load_counts(&mut bytes).map_err(Error::Storage)? {
+            CrashCounts::from_bytes(&bytes).map_err(Error::Format)?
+        } else {
+            CrashCounts::default()
+        };
+
+        Ok(Monitor {
+            storage,
+            counts,
+            last_reset: None,
+        })
+    }
+
+    /// Returns the current counts.
+    pub fn counts(&self) -> CrashCounts {
+        self.counts
+    }
+
+    /// Returns the storage.
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Returns the storage, for example to read back the saved records.
+    pub fn storage_mut(&mut self) -> &mut S {
+        &mut self.storage
+    }
+
+    /// Counts abnormal resets reported by [`HalInitialized`](BlueNRGEvent::HalInitialized) events,
+    /// and saves the fault data of [`CrashReport`](BlueNRGEvent::CrashReport) events. Other events
+    /// are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`Storage`](Error::Storage) if the counts or the record cannot be saved.
+    pub fn handle_event(
+        &mut self,
+        event: &hci::Event<BlueNRGEvent>,
+    ) -> Result<(), Error<S::Error>> {
+        match *event {
+            hci::Event::Vendor(BlueNRGEvent::HalInitialized(reason)) => {
+                self.last_reset = Some(reason);
+                let count = match reason {
+                    ResetReason::Crash => &mut self.counts.crashes,
+                    ResetReason::Watchdog => &mut self.counts.watchdog_resets,
+                    ResetReason::Lockup => &mut self.counts.lockups,
+                    _ => return Ok(()),
+                };
+                *count = count.wrapping_add(1);
+                self.save_counts()
+            }
+            hci::Event::Vendor(BlueNRGEvent::CrashReport(ref fault)) => {
+                let record = Record::new(fault, self.last_reset.take(), self.counts.resets());
+                let mut bytes = [0; MAX_RECORD_LEN];
+                let len = record.copy_into_slice(&mut bytes).map_err(Error::Format)?;
+                self.storage
+                    .save_record(&bytes[..len])
+                    .map_err(Error::Storage)?;
+                self.counts.reports = self.counts.reports.wrapping_add(1);
+                self.save_counts()
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn save_counts(&mut self) -> Result<(), Error<S::Error>> {
+        self.storage
+            .save_counts(&self.counts.to_bytes())
+            .map_err(Error::Storage)
+    }
+}
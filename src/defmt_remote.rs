@@ -0,0 +1,65 @@
+//! defmt support for the types this crate uses from the Bluetooth HCI crate.
+//!
+//! The HCI crate does not implement `defmt::Format`, and the orphan rule keeps this crate from
+//! implementing it there, so the types that hold handles, addresses, opcodes, or status codes
+//! implement `Format` by hand and wrap those fields in [`Remote`]. Handles, addresses, opcodes, and
+//! status codes are logged as the values the controller uses for them, like the serde definitions
+//! in `serde_remote`, instead of going through `core::fmt`.
+
+/// Formats a borrowed value of one of the HCI types.
+pub struct Remote<'a, T>(pub &'a T);
+
+impl defmt::Format for Remote<'_, hci::ConnectionHandle> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "ConnectionHandle({=u16:#x})", (self.0).0)
+    }
+}
+
+impl defmt::Format for Remote<'_, hci::BdAddr> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "BdAddr({=[u8]:#x})", &(self.0).0[..])
+    }
+}
+
+impl defmt::Format for Remote<'_, Option<hci::BdAddr>> {
+    fn format(&self, f: defmt::Formatter) {
+        match self.0 {
+            Some(addr) => defmt::write!(f, "Some({})", Remote(addr)),
+            None => defmt::write!(f, "None"),
+        }
+    }
+}
+
+impl defmt::Format for Remote<'_, hci::Opcode> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Opcode({=u16:#x})", (self.0).0)
+    }
+}
+
+impl<V> defmt::Format for Remote<'_, hci::Status<V>>
+where
+    V: Copy + Into<u8> + defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        match self.0 {
+            hci::Status::Success => defmt::write!(f, "Success"),
+            hci::Status::Vendor(status) => defmt::write!(f, "Vendor({})", status),
+            status => {
+                let code: u8 = (*status).into();
+                defmt::write!(f, "Status({=u8:#x})", code)
+            }
+        }
+    }
+}
+
+impl<V> defmt::Format for Remote<'_, Result<(), hci::Status<V>>>
+where
+    V: Copy + Into<u8> + defmt::Format,
+{
+    fn format(&self, f: defmt::Formatter) {
+        match self.0 {
+            Ok(()) => defmt::write!(f, "Ok(())"),
+            Err(status) => defmt::write!(f, "Err({})", Remote(status)),
+        }
+    }
+}
@@ -11,11 +11,15 @@ use core::convert::{TryFrom, TryInto};
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 use core::time::Duration;
 
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
 /// Vendor-specific commands that may generate the [Command
 /// Complete](hci::event::command::ReturnParameters::Vendor) event. If the commands have defined
 /// return parameters, they are included in the enum.
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum ReturnParameters {
     /// Parameters returned by the [HAL Get Firmware
     /// Revision](crate::hal::Commands::get_firmware_revision) command.
@@ -23,7 +27,10 @@ pub enum ReturnParameters {
 
     /// Status returned by the [HAL Write Config Data](crate::hal::Commands::write_config_data)
     /// command.
-    HalWriteConfigData(hci::Status<crate::event::Status>),
+    HalWriteConfigData(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [HAL Read Config Data](crate::hal::Commands::read_config_data)
     /// command.
@@ -31,20 +38,32 @@ pub enum ReturnParameters {
 
     /// Status returned by the [HAL Set Tx Power Level](crate::hal::Commands::set_tx_power_level)
     /// command.
-    HalSetTxPowerLevel(hci::Status<crate::event::Status>),
+    HalSetTxPowerLevel(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [HAL Device Standby](crate::hal::Commands::device_standby) command.
-    HalDeviceStandby(hci::Status<crate::event::Status>),
+    HalDeviceStandby(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [HAL Get Tx Test Packet
     /// Count](crate::hal::Commands::get_tx_test_packet_count) command.
     HalGetTxTestPacketCount(HalTxTestPacketCount),
 
     /// Status returned by the [HAL Start Tone](crate::hal::Commands::start_tone) command.
-    HalStartTone(hci::Status<crate::event::Status>),
+    HalStartTone(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [HAL Stop Tone](crate::hal::Commands::stop_tone) command.
-    HalStopTone(hci::Status<crate::event::Status>),
+    HalStopTone(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [HAL Get Link Status](crate::hal::Commands::get_link_status) command.
     HalGetLinkStatus(HalLinkStatus),
@@ -53,56 +72,114 @@ pub enum ReturnParameters {
     /// command.
     HalGetAnchorPeriod(HalAnchorPeriod),
 
+    /// Status returned by the [HAL Set Radio Activity
+    /// Mask](crate::hal::Commands::set_radio_activity_mask) command.
+    #[cfg(feature = "bluenrg-1-2")]
+    HalSetRadioActivityMask(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [HAL Set Event Mask](crate::hal::Commands::set_hal_event_mask) command.
+    #[cfg(feature = "bluenrg-1-2")]
+    HalSetEventMask(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [HAL Stack Reset](crate::hal::Commands::stack_reset) command.
+    #[cfg(feature = "bluenrg-1-2")]
+    HalStackReset(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
     /// Status returned by the [GAP Set Non-Discoverable](crate::gap::Commands::set_nondiscoverable)
     /// command.
-    GapSetNonDiscoverable(hci::Status<crate::event::Status>),
+    GapSetNonDiscoverable(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [GAP Set Discoverable](crate::gap::Commands::set_discoverable)
     /// command.
-    GapSetDiscoverable(hci::Status<crate::event::Status>),
+    GapSetDiscoverable(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [GAP Set Direct
     /// Connectable](crate::gap::Commands::set_direct_connectable) command.
-    GapSetDirectConnectable(hci::Status<crate::event::Status>),
+    GapSetDirectConnectable(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [GAP Set IO Capability](crate::gap::Commands::set_io_capability)
     /// command.
-    GapSetIoCapability(hci::Status<crate::event::Status>),
+    GapSetIoCapability(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [GAP Set Authentication
     /// Requirement](crate::gap::Commands::set_authentication_requirement) command.
-    GapSetAuthenticationRequirement(hci::Status<crate::event::Status>),
+    GapSetAuthenticationRequirement(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [GAP Set Authorization
     /// Requirement](crate::gap::Commands::set_authorization_requirement) command.
-    GapSetAuthorizationRequirement(hci::Status<crate::event::Status>),
+    GapSetAuthorizationRequirement(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [GAP Pass Key Response](crate::gap::Commands::pass_key_response)
     /// command.
-    GapPassKeyResponse(hci::Status<crate::event::Status>),
+    GapPassKeyResponse(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [GAP Authorization
     /// Response](crate::gap::Commands::authorization_response) command.
-    GapAuthorizationResponse(hci::Status<crate::event::Status>),
+    GapAuthorizationResponse(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Init](crate::gap::Commands::init) command.
     GapInit(GapInit),
 
     /// Parameters returned by the [GAP Set
     /// Non-Connectable](crate::gap::Commands::set_nonconnectable) command.
-    GapSetNonConnectable(hci::Status<crate::event::Status>),
+    GapSetNonConnectable(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Set
     /// Undirected Connectable](crate::gap::Commands::set_undirected_connectable) command.
-    GapSetUndirectedConnectable(hci::Status<crate::event::Status>),
+    GapSetUndirectedConnectable(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Update Advertising
     /// Data](crate::gap::Commands::update_advertising_data) command.
-    GapUpdateAdvertisingData(hci::Status<crate::event::Status>),
+    GapUpdateAdvertisingData(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Delete AD Type](crate::gap::Commands::delete_ad_type)
     /// command.
-    GapDeleteAdType(hci::Status<crate::event::Status>),
+    GapDeleteAdType(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Get Security
     /// Level](crate::gap::Commands::get_security_level) command.
@@ -110,27 +187,45 @@ pub enum ReturnParameters {
 
     /// Parameters returned by the [GAP Set Event Mask](crate::gap::Commands::set_event_mask)
     /// command.
-    GapSetEventMask(hci::Status<crate::event::Status>),
+    GapSetEventMask(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Configure
     /// White List](crate::gap::Commands::configure_white_list) command.
-    GapConfigureWhiteList(hci::Status<crate::event::Status>),
+    GapConfigureWhiteList(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Clear Security
     /// Database](crate::gap::Commands::clear_security_database) command.
-    GapClearSecurityDatabase(hci::Status<crate::event::Status>),
+    GapClearSecurityDatabase(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Allow Rebond](crate::gap::Commands::allow_rebond) command.
-    GapAllowRebond(hci::Status<crate::event::Status>),
+    GapAllowRebond(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Terminate
     /// Procedure](crate::gap::Commands::terminate_procedure) command.
-    GapTerminateProcedure(hci::Status<crate::event::Status>),
+    GapTerminateProcedure(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     #[cfg(not(feature = "ms"))]
     /// Parameters returned by the [GAP Resolve Private
     /// Address](crate::gap::Commands::resolve_private_address) command.
-    GapResolvePrivateAddress(hci::Status<crate::event::Status>),
+    GapResolvePrivateAddress(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     #[cfg(feature = "ms")]
     /// Parameters returned by the [GAP Resolve Private
@@ -144,19 +239,61 @@ pub enum ReturnParameters {
     #[cfg(feature = "ms")]
     /// Parameters returned by the [GAP Set Broadcast
     /// Mode](crate::gap::Commands::set_broadcast_mode) command.
-    GapSetBroadcastMode(hci::Status<crate::event::Status>),
+    GapSetBroadcastMode(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     #[cfg(feature = "ms")]
     /// Parameters returned by the [GAP Start Observation
     /// Procedure](crate::gap::Commands::start_observation_procedure) command.
-    GapStartObservationProcedure(hci::Status<crate::event::Status>),
+    GapStartObservationProcedure(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GAP Is Device Bonded](crate::gap::Commands::is_device_bonded)
     /// command.
-    GapIsDeviceBonded(hci::Status<crate::event::Status>),
+    GapIsDeviceBonded(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [GAP Numeric Comparison Value
+    /// Confirm](crate::gap::Commands::numeric_comparison_value_confirm) command.
+    #[cfg(feature = "bluenrg-1-2")]
+    GapNumericComparisonValueConfirm(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [GAP Passkey Input](crate::gap::Commands::passkey_input) command.
+    #[cfg(feature = "bluenrg-1-2")]
+    GapPasskeyInput(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [GAP Remove Bonded
+    /// Device](crate::gap::Commands::remove_bonded_device) command.
+    #[cfg(feature = "bluenrg-1-2")]
+    GapRemoveBondedDevice(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [GAP Set LE Event Mask](crate::gap::Commands::set_le_event_mask) command.
+    #[cfg(feature = "bluenrg-1-2")]
+    GapSetLeEventMask(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Init](crate::gatt::Commands::init) command.
-    GattInit(hci::Status<crate::event::Status>),
+    GattInit(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Add Service](crate::gatt::Commands::add_service) command.
     GattAddService(GattService),
@@ -175,50 +312,86 @@ pub enum ReturnParameters {
 
     /// Parameters returned by the [GATT Update Characteristic
     /// Value](crate::gatt::Commands::update_characteristic_value) command.
-    GattUpdateCharacteristicValue(hci::Status<crate::event::Status>),
+    GattUpdateCharacteristicValue(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Delete
     /// Characteristic](crate::gatt::Commands::delete_characteristic) command.
-    GattDeleteCharacteristic(hci::Status<crate::event::Status>),
+    GattDeleteCharacteristic(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Delete Service](crate::gatt::Commands::delete_service)
     /// command.
-    GattDeleteService(hci::Status<crate::event::Status>),
+    GattDeleteService(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Delete Included
     /// Service](crate::gatt::Commands::delete_included_service) command.
-    GattDeleteIncludedService(hci::Status<crate::event::Status>),
+    GattDeleteIncludedService(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Set Event Mask](crate::gatt::Commands::set_event_mask)
     /// command.
-    GattSetEventMask(hci::Status<crate::event::Status>),
+    GattSetEventMask(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Write Without
     /// Response](crate::gatt::Commands::write_without_response) command.
-    GattWriteWithoutResponse(hci::Status<crate::event::Status>),
+    GattWriteWithoutResponse(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Signed Write Without
     /// Response](crate::gatt::Commands::signed_write_without_response) command.
-    GattSignedWriteWithoutResponse(hci::Status<crate::event::Status>),
+    GattSignedWriteWithoutResponse(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Confirm
     /// Indication](crate::gatt::Commands::confirm_indication) command.
-    GattConfirmIndication(hci::Status<crate::event::Status>),
+    GattConfirmIndication(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Write Response](crate::gatt::Commands::write_response)
     /// command.
-    GattWriteResponse(hci::Status<crate::event::Status>),
+    GattWriteResponse(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Allow Read](crate::gatt::Commands::allow_read) command.
-    GattAllowRead(hci::Status<crate::event::Status>),
+    GattAllowRead(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Set Security
     /// Permission](crate::gatt::Commands::set_security_permission) command.
-    GattSetSecurityPermission(hci::Status<crate::event::Status>),
+    GattSetSecurityPermission(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Set Descriptor
     /// Value](crate::gatt::Commands::set_descriptor_value) command.
-    GattSetDescriptorValue(hci::Status<crate::event::Status>),
+    GattSetDescriptorValue(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Parameters returned by the [GATT Read Handle
     /// Value](crate::gatt::Commands::read_handle_value) command.
@@ -232,11 +405,328 @@ pub enum ReturnParameters {
     /// Parameters returned by the [GATT Update Long Characteristic
     /// Value](crate::gatt::Commands::update_long_characteristic_value) command.
     #[cfg(feature = "ms")]
-    GattUpdateLongCharacteristicValue(hci::Status<crate::event::Status>),
+    GattUpdateLongCharacteristicValue(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
 
     /// Status returned by the [L2CAP Connection Parameter Update
     /// Response](crate::l2cap::Commands::connection_parameter_update_response) command.
-    L2CapConnectionParameterUpdateResponse(hci::Status<crate::event::Status>),
+    L2CapConnectionParameterUpdateResponse(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [Updater Start](crate::updater::Commands::updater_start) command.
+    UpdaterStart(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [Updater Reboot](crate::updater::Commands::updater_reboot) command.
+    UpdaterReboot(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Parameters returned by the [Updater Get
+    /// Version](crate::updater::Commands::get_updater_version) command.
+    UpdaterGetVersion(UpdaterVersion),
+
+    /// Parameters returned by the [Updater Get Buffer
+    /// Size](crate::updater::Commands::get_updater_buffer_size) command.
+    UpdaterGetBufferSize(UpdaterBufferSize),
+
+    /// Status returned by the [Updater Erase BLUE
+    /// Flag](crate::updater::Commands::erase_blue_flag) command.
+    UpdaterEraseBlueFlag(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [Updater Reset BLUE
+    /// Flag](crate::updater::Commands::reset_blue_flag) command.
+    UpdaterResetBlueFlag(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [Updater Erase Sector](crate::updater::Commands::erase_sector)
+    /// command.
+    UpdaterEraseSector(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Status returned by the [Updater Program Data
+    /// Block](crate::updater::Commands::program_data_block) command.
+    UpdaterProgramDataBlock(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Parameters returned by the [Updater Read Data
+    /// Block](crate::updater::Commands::read_data_block) command.
+    UpdaterReadDataBlock(UpdaterData),
+
+    /// Parameters returned by the [Updater Calculate CRC](crate::updater::Commands::calculate_crc)
+    /// command.
+    UpdaterCalculateCrc(UpdaterCrc),
+
+    /// Return parameters for a vendor-specific command that this crate does not recognize, like
+    /// those sent with [`write_vendor_command`](crate::raw::Commands::write_vendor_command).
+    Unknown {
+        /// Opcode of the command that completed.
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::OpcodeDef"))]
+        opcode: hci::Opcode,
+
+        /// Return parameters of the command, not including the opcode.
+        bytes: RawReturnParameters,
+    },
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for ReturnParameters {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            ReturnParameters::HalGetFirmwareRevision(params) => {
+                defmt::write!(f, "HalGetFirmwareRevision({})", params)
+            }
+            ReturnParameters::HalWriteConfigData(status) => {
+                defmt::write!(f, "HalWriteConfigData({})", Remote(status))
+            }
+            ReturnParameters::HalReadConfigData(params) => {
+                defmt::write!(f, "HalReadConfigData({})", params)
+            }
+            ReturnParameters::HalSetTxPowerLevel(status) => {
+                defmt::write!(f, "HalSetTxPowerLevel({})", Remote(status))
+            }
+            ReturnParameters::HalDeviceStandby(status) => {
+                defmt::write!(f, "HalDeviceStandby({})", Remote(status))
+            }
+            ReturnParameters::HalGetTxTestPacketCount(params) => {
+                defmt::write!(f, "HalGetTxTestPacketCount({})", params)
+            }
+            ReturnParameters::HalStartTone(status) => {
+                defmt::write!(f, "HalStartTone({})", Remote(status))
+            }
+            ReturnParameters::HalStopTone(status) => {
+                defmt::write!(f, "HalStopTone({})", Remote(status))
+            }
+            ReturnParameters::HalGetLinkStatus(params) => {
+                defmt::write!(f, "HalGetLinkStatus({})", params)
+            }
+            ReturnParameters::HalGetAnchorPeriod(params) => {
+                defmt::write!(f, "HalGetAnchorPeriod({})", params)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::HalSetRadioActivityMask(status) => {
+                defmt::write!(f, "HalSetRadioActivityMask({})", Remote(status))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::HalSetEventMask(status) => {
+                defmt::write!(f, "HalSetEventMask({})", Remote(status))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::HalStackReset(status) => {
+                defmt::write!(f, "HalStackReset({})", Remote(status))
+            }
+            ReturnParameters::GapSetNonDiscoverable(status) => {
+                defmt::write!(f, "GapSetNonDiscoverable({})", Remote(status))
+            }
+            ReturnParameters::GapSetDiscoverable(status) => {
+                defmt::write!(f, "GapSetDiscoverable({})", Remote(status))
+            }
+            ReturnParameters::GapSetDirectConnectable(status) => {
+                defmt::write!(f, "GapSetDirectConnectable({})", Remote(status))
+            }
+            ReturnParameters::GapSetIoCapability(status) => {
+                defmt::write!(f, "GapSetIoCapability({})", Remote(status))
+            }
+            ReturnParameters::GapSetAuthenticationRequirement(status) => {
+                defmt::write!(f, "GapSetAuthenticationRequirement({})", Remote(status))
+            }
+            ReturnParameters::GapSetAuthorizationRequirement(status) => {
+                defmt::write!(f, "GapSetAuthorizationRequirement({})", Remote(status))
+            }
+            ReturnParameters::GapPassKeyResponse(status) => {
+                defmt::write!(f, "GapPassKeyResponse({})", Remote(status))
+            }
+            ReturnParameters::GapAuthorizationResponse(status) => {
+                defmt::write!(f, "GapAuthorizationResponse({})", Remote(status))
+            }
+            ReturnParameters::GapInit(params) => defmt::write!(f, "GapInit({})", params),
+            ReturnParameters::GapSetNonConnectable(status) => {
+                defmt::write!(f, "GapSetNonConnectable({})", Remote(status))
+            }
+            ReturnParameters::GapSetUndirectedConnectable(status) => {
+                defmt::write!(f, "GapSetUndirectedConnectable({})", Remote(status))
+            }
+            ReturnParameters::GapUpdateAdvertisingData(status) => {
+                defmt::write!(f, "GapUpdateAdvertisingData({})", Remote(status))
+            }
+            ReturnParameters::GapDeleteAdType(status) => {
+                defmt::write!(f, "GapDeleteAdType({})", Remote(status))
+            }
+            ReturnParameters::GapGetSecurityLevel(params) => {
+                defmt::write!(f, "GapGetSecurityLevel({})", params)
+            }
+            ReturnParameters::GapSetEventMask(status) => {
+                defmt::write!(f, "GapSetEventMask({})", Remote(status))
+            }
+            ReturnParameters::GapConfigureWhiteList(status) => {
+                defmt::write!(f, "GapConfigureWhiteList({})", Remote(status))
+            }
+            ReturnParameters::GapClearSecurityDatabase(status) => {
+                defmt::write!(f, "GapClearSecurityDatabase({})", Remote(status))
+            }
+            ReturnParameters::GapAllowRebond(status) => {
+                defmt::write!(f, "GapAllowRebond({})", Remote(status))
+            }
+            ReturnParameters::GapTerminateProcedure(status) => {
+                defmt::write!(f, "GapTerminateProcedure({})", Remote(status))
+            }
+            #[cfg(not(feature = "ms"))]
+            ReturnParameters::GapResolvePrivateAddress(status) => {
+                defmt::write!(f, "GapResolvePrivateAddress({})", Remote(status))
+            }
+            #[cfg(feature = "ms")]
+            ReturnParameters::GapResolvePrivateAddress(params) => {
+                defmt::write!(f, "GapResolvePrivateAddress({})", params)
+            }
+            ReturnParameters::GapGetBondedDevices(params) => {
+                defmt::write!(f, "GapGetBondedDevices({})", params)
+            }
+            #[cfg(feature = "ms")]
+            ReturnParameters::GapSetBroadcastMode(status) => {
+                defmt::write!(f, "GapSetBroadcastMode({})", Remote(status))
+            }
+            #[cfg(feature = "ms")]
+            ReturnParameters::GapStartObservationProcedure(status) => {
+                defmt::write!(f, "GapStartObservationProcedure({})", Remote(status))
+            }
+            ReturnParameters::GapIsDeviceBonded(status) => {
+                defmt::write!(f, "GapIsDeviceBonded({})", Remote(status))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::GapNumericComparisonValueConfirm(status) => {
+                defmt::write!(f, "GapNumericComparisonValueConfirm({})", Remote(status))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::GapPasskeyInput(status) => {
+                defmt::write!(f, "GapPasskeyInput({})", Remote(status))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::GapRemoveBondedDevice(status) => {
+                defmt::write!(f, "GapRemoveBondedDevice({})", Remote(status))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::GapSetLeEventMask(status) => {
+                defmt::write!(f, "GapSetLeEventMask({})", Remote(status))
+            }
+            ReturnParameters::GattInit(status) => defmt::write!(f, "GattInit({})", Remote(status)),
+            ReturnParameters::GattAddService(params) => {
+                defmt::write!(f, "GattAddService({})", params)
+            }
+            ReturnParameters::GattIncludeService(params) => {
+                defmt::write!(f, "GattIncludeService({})", params)
+            }
+            ReturnParameters::GattAddCharacteristic(params) => {
+                defmt::write!(f, "GattAddCharacteristic({})", params)
+            }
+            ReturnParameters::GattAddCharacteristicDescriptor(params) => {
+                defmt::write!(f, "GattAddCharacteristicDescriptor({})", params)
+            }
+            ReturnParameters::GattUpdateCharacteristicValue(status) => {
+                defmt::write!(f, "GattUpdateCharacteristicValue({})", Remote(status))
+            }
+            ReturnParameters::GattDeleteCharacteristic(status) => {
+                defmt::write!(f, "GattDeleteCharacteristic({})", Remote(status))
+            }
+            ReturnParameters::GattDeleteService(status) => {
+                defmt::write!(f, "GattDeleteService({})", Remote(status))
+            }
+            ReturnParameters::GattDeleteIncludedService(status) => {
+                defmt::write!(f, "GattDeleteIncludedService({})", Remote(status))
+            }
+            ReturnParameters::GattSetEventMask(status) => {
+                defmt::write!(f, "GattSetEventMask({})", Remote(status))
+            }
+            ReturnParameters::GattWriteWithoutResponse(status) => {
+                defmt::write!(f, "GattWriteWithoutResponse({})", Remote(status))
+            }
+            ReturnParameters::GattSignedWriteWithoutResponse(status) => {
+                defmt::write!(f, "GattSignedWriteWithoutResponse({})", Remote(status))
+            }
+            ReturnParameters::GattConfirmIndication(status) => {
+                defmt::write!(f, "GattConfirmIndication({})", Remote(status))
+            }
+            ReturnParameters::GattWriteResponse(status) => {
+                defmt::write!(f, "GattWriteResponse({})", Remote(status))
+            }
+            ReturnParameters::GattAllowRead(status) => {
+                defmt::write!(f, "GattAllowRead({})", Remote(status))
+            }
+            ReturnParameters::GattSetSecurityPermission(status) => {
+                defmt::write!(f, "GattSetSecurityPermission({})", Remote(status))
+            }
+            ReturnParameters::GattSetDescriptorValue(status) => {
+                defmt::write!(f, "GattSetDescriptorValue({})", Remote(status))
+            }
+            ReturnParameters::GattReadHandleValue(params) => {
+                defmt::write!(f, "GattReadHandleValue({})", params)
+            }
+            #[cfg(feature = "ms")]
+            ReturnParameters::GattReadHandleValueOffset(params) => {
+                defmt::write!(f, "GattReadHandleValueOffset({})", params)
+            }
+            #[cfg(feature = "ms")]
+            ReturnParameters::GattUpdateLongCharacteristicValue(status) => {
+                defmt::write!(f, "GattUpdateLongCharacteristicValue({})", Remote(status))
+            }
+            ReturnParameters::L2CapConnectionParameterUpdateResponse(status) => defmt::write!(
+                f,
+                "L2CapConnectionParameterUpdateResponse({})",
+                Remote(status)
+            ),
+            ReturnParameters::UpdaterStart(status) => {
+                defmt::write!(f, "UpdaterStart({})", Remote(status))
+            }
+            ReturnParameters::UpdaterReboot(status) => {
+                defmt::write!(f, "UpdaterReboot({})", Remote(status))
+            }
+            ReturnParameters::UpdaterGetVersion(params) => {
+                defmt::write!(f, "UpdaterGetVersion({})", params)
+            }
+            ReturnParameters::UpdaterGetBufferSize(params) => {
+                defmt::write!(f, "UpdaterGetBufferSize({})", params)
+            }
+            ReturnParameters::UpdaterEraseBlueFlag(status) => {
+                defmt::write!(f, "UpdaterEraseBlueFlag({})", Remote(status))
+            }
+            ReturnParameters::UpdaterResetBlueFlag(status) => {
+                defmt::write!(f, "UpdaterResetBlueFlag({})", Remote(status))
+            }
+            ReturnParameters::UpdaterEraseSector(status) => {
+                defmt::write!(f, "UpdaterEraseSector({})", Remote(status))
+            }
+            ReturnParameters::UpdaterProgramDataBlock(status) => {
+                defmt::write!(f, "UpdaterProgramDataBlock({})", Remote(status))
+            }
+            ReturnParameters::UpdaterReadDataBlock(params) => {
+                defmt::write!(f, "UpdaterReadDataBlock({})", params)
+            }
+            ReturnParameters::UpdaterCalculateCrc(params) => {
+                defmt::write!(f, "UpdaterCalculateCrc({})", params)
+            }
+            ReturnParameters::Unknown { opcode, bytes } => defmt::write!(
+                f,
+                "Unknown {{ opcode: {}, bytes: {} }}",
+                Remote(opcode),
+                bytes
+            ),
+        }
+    }
 }
 
 impl hci::event::VendorReturnParameters for ReturnParameters {
@@ -278,6 +768,18 @@ impl hci::event::VendorReturnParameters for ReturnParameters {
             crate::opcode::HAL_GET_ANCHOR_PERIOD => Ok(ReturnParameters::HalGetAnchorPeriod(
                 to_hal_anchor_period(&bytes[3..])?,
             )),
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::HAL_SET_RADIO_ACTIVITY_MASK => Ok(
+                ReturnParameters::HalSetRadioActivityMask(to_status(&bytes[3..])?),
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::HAL_SET_EVENT_MASK => {
+                Ok(ReturnParameters::HalSetEventMask(to_status(&bytes[3..])?))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::HAL_STACK_RESET => {
+                Ok(ReturnParameters::HalStackReset(to_status(&bytes[3..])?))
+            }
             crate::opcode::GAP_SET_NONDISCOVERABLE => Ok(ReturnParameters::GapSetNonDiscoverable(
                 to_status(&bytes[3..])?,
             )),
@@ -361,9 +863,7 @@ impl hci::event::VendorReturnParameters for ReturnParameters {
 
                 #[cfg(not(feature = "ms"))]
                 {
-                    Err(hci::event::Error::UnknownOpcode(
-                        crate::opcode::GAP_SET_BROADCAST_MODE,
-                    ))
+                    to_unknown(crate::opcode::GAP_SET_BROADCAST_MODE, &bytes[3..])
                 }
             }
             crate::opcode::GAP_START_OBSERVATION_PROCEDURE => {
@@ -376,14 +876,28 @@ impl hci::event::VendorReturnParameters for ReturnParameters {
 
                 #[cfg(not(feature = "ms"))]
                 {
-                    Err(hci::event::Error::UnknownOpcode(
-                        crate::opcode::GAP_START_OBSERVATION_PROCEDURE,
-                    ))
+                    to_unknown(crate::opcode::GAP_START_OBSERVATION_PROCEDURE, &bytes[3..])
                 }
             }
             crate::opcode::GAP_IS_DEVICE_BONDED => {
                 Ok(ReturnParameters::GapIsDeviceBonded(to_status(&bytes[3..])?))
             }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::GAP_NUMERIC_COMPARISON_VALUE_CONFIRM => Ok(
+                ReturnParameters::GapNumericComparisonValueConfirm(to_status(&bytes[3..])?),
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::GAP_PASSKEY_INPUT => {
+                Ok(ReturnParameters::GapPasskeyInput(to_status(&bytes[3..])?))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::GAP_REMOVE_BONDED_DEVICE => Ok(ReturnParameters::GapRemoveBondedDevice(
+                to_status(&bytes[3..])?,
+            )),
+            #[cfg(feature = "bluenrg-1-2")]
+            crate::opcode::GAP_SET_LE_EVENT_MASK => {
+                Ok(ReturnParameters::GapSetLeEventMask(to_status(&bytes[3..])?))
+            }
             crate::opcode::GATT_INIT => Ok(ReturnParameters::GattInit(to_status(&bytes[3..])?)),
             crate::opcode::GATT_ADD_SERVICE => Ok(ReturnParameters::GattAddService(
                 to_gatt_service(&bytes[3..])?,
@@ -448,9 +962,7 @@ impl hci::event::VendorReturnParameters for ReturnParameters {
 
                 #[cfg(not(feature = "ms"))]
                 {
-                    Err(hci::event::Error::UnknownOpcode(
-                        crate::opcode::GATT_READ_HANDLE_VALUE_OFFSET,
-                    ))
+                    to_unknown(crate::opcode::GATT_READ_HANDLE_VALUE_OFFSET, &bytes[3..])
                 }
             }
             crate::opcode::GATT_UPDATE_LONG_CHARACTERISTIC_VALUE => {
@@ -463,19 +975,385 @@ impl hci::event::VendorReturnParameters for ReturnParameters {
 
                 #[cfg(not(feature = "ms"))]
                 {
-                    Err(hci::event::Error::UnknownOpcode(
+                    to_unknown(
                         crate::opcode::GATT_UPDATE_LONG_CHARACTERISTIC_VALUE,
-                    ))
+                        &bytes[3..],
+                    )
                 }
             }
             crate::opcode::L2CAP_CONN_PARAM_UPDATE_RESP => Ok(
                 ReturnParameters::L2CapConnectionParameterUpdateResponse(to_status(&bytes[3..])?),
             ),
-            other => Err(hci::event::Error::UnknownOpcode(other)),
+            crate::opcode::UPDATER_START => {
+                Ok(ReturnParameters::UpdaterStart(to_status(&bytes[3..])?))
+            }
+            crate::opcode::UPDATER_REBOOT => {
+                Ok(ReturnParameters::UpdaterReboot(to_status(&bytes[3..])?))
+            }
+            crate::opcode::UPDATER_GET_VERSION => Ok(ReturnParameters::UpdaterGetVersion(
+                to_updater_version(&bytes[3..])?,
+            )),
+            crate::opcode::UPDATER_GET_BUFFER_SIZE => Ok(ReturnParameters::UpdaterGetBufferSize(
+                to_updater_buffer_size(&bytes[3..])?,
+            )),
+            crate::opcode::UPDATER_ERASE_BLUE_FLAG => Ok(ReturnParameters::UpdaterEraseBlueFlag(
+                to_status(&bytes[3..])?,
+            )),
+            crate::opcode::UPDATER_RESET_BLUE_FLAG => Ok(ReturnParameters::UpdaterResetBlueFlag(
+                to_status(&bytes[3..])?,
+            )),
+            crate::opcode::UPDATER_ERASE_SECTOR => Ok(ReturnParameters::UpdaterEraseSector(
+                to_status(&bytes[3..])?,
+            )),
+            crate::opcode::UPDATER_PROGRAM_DATA_BLOCK => Ok(
+                ReturnParameters::UpdaterProgramDataBlock(to_status(&bytes[3..])?),
+            ),
+            crate::opcode::UPDATER_READ_DATA_BLOCK => Ok(ReturnParameters::UpdaterReadDataBlock(
+                to_updater_data(&bytes[3..])?,
+            )),
+            crate::opcode::UPDATER_CALC_CRC => Ok(ReturnParameters::UpdaterCalculateCrc(
+                to_updater_crc(&bytes[3..])?,
+            )),
+            other => to_unknown(other, &bytes[3..]),
         }
     }
 }
 
+impl ReturnParameters {
+    /// Serializes the return parameters into the given buffer: the 2-byte opcode of the command
+    /// followed by its return parameters. This is the format that
+    /// [`new`](hci::event::VendorReturnParameters::new) parses, less the leading
+    /// Num_HCI_Command_Packets byte, which belongs to the Command Complete event. Returns the number
+    /// of bytes written.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BufferTooSmall`](crate::EncodeError::BufferTooSmall) if the buffer cannot hold the
+    /// return parameters. They are never longer than 254 bytes.
+    pub fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, crate::EncodeError> {
+        // Serialize into a buffer that fits any return parameters, then copy out only if the
+        // caller's buffer has room.
+        let mut buffer = [0; 254];
+        let len = self.copy_into_return_buffer(&mut buffer);
+        crate::command::check_buffer_len(bytes, len)?;
+        bytes[..len].copy_from_slice(&buffer[..len]);
+
+        Ok(len)
+    }
+
+    fn copy_into_return_buffer(&self, bytes: &mut [u8; 254]) -> usize {
+        let params = &mut bytes[2..];
+        let (opcode, len) = match *self {
+            ReturnParameters::HalGetFirmwareRevision(ref params_struct) => (
+                crate::opcode::HAL_GET_FIRMWARE_REVISION,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::HalWriteConfigData(status) => (
+                crate::opcode::HAL_WRITE_CONFIG_DATA,
+                write_status(params, status),
+            ),
+            ReturnParameters::HalReadConfigData(ref params_struct) => (
+                crate::opcode::HAL_READ_CONFIG_DATA,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::HalSetTxPowerLevel(status) => (
+                crate::opcode::HAL_SET_TX_POWER_LEVEL,
+                write_status(params, status),
+            ),
+            ReturnParameters::HalDeviceStandby(status) => (
+                crate::opcode::HAL_DEVICE_STANDBY,
+                write_status(params, status),
+            ),
+            ReturnParameters::HalGetTxTestPacketCount(ref params_struct) => (
+                crate::opcode::HAL_TX_TEST_PACKET_COUNT,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::HalStartTone(status) => {
+                (crate::opcode::HAL_START_TONE, write_status(params, status))
+            }
+            ReturnParameters::HalStopTone(status) => {
+                (crate::opcode::HAL_STOP_TONE, write_status(params, status))
+            }
+            ReturnParameters::HalGetLinkStatus(ref params_struct) => (
+                crate::opcode::HAL_GET_LINK_STATUS,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::HalGetAnchorPeriod(ref params_struct) => (
+                crate::opcode::HAL_GET_ANCHOR_PERIOD,
+                params_struct.copy_into_slice(params),
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::HalSetRadioActivityMask(status) => (
+                crate::opcode::HAL_SET_RADIO_ACTIVITY_MASK,
+                write_status(params, status),
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::HalSetEventMask(status) => (
+                crate::opcode::HAL_SET_EVENT_MASK,
+                write_status(params, status),
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::HalStackReset(status) => {
+                (crate::opcode::HAL_STACK_RESET, write_status(params, status))
+            }
+            ReturnParameters::GapSetNonDiscoverable(status) => (
+                crate::opcode::GAP_SET_NONDISCOVERABLE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapSetDiscoverable(status) => (
+                crate::opcode::GAP_SET_DISCOVERABLE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapSetDirectConnectable(status) => (
+                crate::opcode::GAP_SET_DIRECT_CONNECTABLE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapSetIoCapability(status) => (
+                crate::opcode::GAP_SET_IO_CAPABILITY,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapSetAuthenticationRequirement(status) => (
+                crate::opcode::GAP_SET_AUTHENTICATION_REQUIREMENT,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapSetAuthorizationRequirement(status) => (
+                crate::opcode::GAP_SET_AUTHORIZATION_REQUIREMENT,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapPassKeyResponse(status) => (
+                crate::opcode::GAP_PASS_KEY_RESPONSE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapAuthorizationResponse(status) => (
+                crate::opcode::GAP_AUTHORIZATION_RESPONSE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapInit(ref params_struct) => (
+                crate::opcode::GAP_INIT,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::GapSetNonConnectable(status) => (
+                crate::opcode::GAP_SET_NONCONNECTABLE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapSetUndirectedConnectable(status) => (
+                crate::opcode::GAP_SET_UNDIRECTED_CONNECTABLE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapUpdateAdvertisingData(status) => (
+                crate::opcode::GAP_UPDATE_ADVERTISING_DATA,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapDeleteAdType(status) => (
+                crate::opcode::GAP_DELETE_AD_TYPE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapGetSecurityLevel(ref params_struct) => (
+                crate::opcode::GAP_GET_SECURITY_LEVEL,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::GapSetEventMask(status) => (
+                crate::opcode::GAP_SET_EVENT_MASK,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapConfigureWhiteList(status) => (
+                crate::opcode::GAP_CONFIGURE_WHITE_LIST,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapClearSecurityDatabase(status) => (
+                crate::opcode::GAP_CLEAR_SECURITY_DATABASE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapAllowRebond(status) => (
+                crate::opcode::GAP_ALLOW_REBOND,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapTerminateProcedure(status) => (
+                crate::opcode::GAP_TERMINATE_PROCEDURE,
+                write_status(params, status),
+            ),
+            #[cfg(feature = "ms")]
+            ReturnParameters::GapResolvePrivateAddress(ref params_struct) => (
+                crate::opcode::GAP_RESOLVE_PRIVATE_ADDRESS,
+                params_struct.copy_into_slice(params),
+            ),
+            #[cfg(not(feature = "ms"))]
+            ReturnParameters::GapResolvePrivateAddress(status) => (
+                crate::opcode::GAP_RESOLVE_PRIVATE_ADDRESS,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapGetBondedDevices(ref params_struct) => (
+                crate::opcode::GAP_GET_BONDED_DEVICES,
+                params_struct.copy_into_slice(params),
+            ),
+            #[cfg(feature = "ms")]
+            ReturnParameters::GapSetBroadcastMode(status) => (
+                crate::opcode::GAP_SET_BROADCAST_MODE,
+                write_status(params, status),
+            ),
+            #[cfg(feature = "ms")]
+            ReturnParameters::GapStartObservationProcedure(status) => (
+                crate::opcode::GAP_START_OBSERVATION_PROCEDURE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GapIsDeviceBonded(status) => (
+                crate::opcode::GAP_IS_DEVICE_BONDED,
+                write_status(params, status),
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::GapNumericComparisonValueConfirm(status) => (
+                crate::opcode::GAP_NUMERIC_COMPARISON_VALUE_CONFIRM,
+                write_status(params, status),
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::GapPasskeyInput(status) => (
+                crate::opcode::GAP_PASSKEY_INPUT,
+                write_status(params, status),
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::GapRemoveBondedDevice(status) => (
+                crate::opcode::GAP_REMOVE_BONDED_DEVICE,
+                write_status(params, status),
+            ),
+            #[cfg(feature = "bluenrg-1-2")]
+            ReturnParameters::GapSetLeEventMask(status) => (
+                crate::opcode::GAP_SET_LE_EVENT_MASK,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattInit(status) => {
+                (crate::opcode::GATT_INIT, write_status(params, status))
+            }
+            ReturnParameters::GattAddService(ref params_struct) => (
+                crate::opcode::GATT_ADD_SERVICE,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::GattIncludeService(ref params_struct) => (
+                crate::opcode::GATT_INCLUDE_SERVICE,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::GattAddCharacteristic(ref params_struct) => (
+                crate::opcode::GATT_ADD_CHARACTERISTIC,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::GattAddCharacteristicDescriptor(ref params_struct) => (
+                crate::opcode::GATT_ADD_CHARACTERISTIC_DESCRIPTOR,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::GattUpdateCharacteristicValue(status) => (
+                crate::opcode::GATT_UPDATE_CHARACTERISTIC_VALUE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattDeleteCharacteristic(status) => (
+                crate::opcode::GATT_DELETE_CHARACTERISTIC,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattDeleteService(status) => (
+                crate::opcode::GATT_DELETE_SERVICE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattDeleteIncludedService(status) => (
+                crate::opcode::GATT_DELETE_INCLUDED_SERVICE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattSetEventMask(status) => (
+                crate::opcode::GATT_SET_EVENT_MASK,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattWriteWithoutResponse(status) => (
+                crate::opcode::GATT_WRITE_WITHOUT_RESPONSE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattSignedWriteWithoutResponse(status) => (
+                crate::opcode::GATT_SIGNED_WRITE_WITHOUT_RESPONSE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattConfirmIndication(status) => (
+                crate::opcode::GATT_CONFIRM_INDICATION,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattWriteResponse(status) => (
+                crate::opcode::GATT_WRITE_RESPONSE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattAllowRead(status) => {
+                (crate::opcode::GATT_ALLOW_READ, write_status(params, status))
+            }
+            ReturnParameters::GattSetSecurityPermission(status) => (
+                crate::opcode::GATT_SET_SECURITY_PERMISSION,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattSetDescriptorValue(status) => (
+                crate::opcode::GATT_SET_DESCRIPTOR_VALUE,
+                write_status(params, status),
+            ),
+            ReturnParameters::GattReadHandleValue(ref params_struct) => (
+                crate::opcode::GATT_READ_HANDLE_VALUE,
+                params_struct.copy_into_slice(params),
+            ),
+            #[cfg(feature = "ms")]
+            ReturnParameters::GattReadHandleValueOffset(ref params_struct) => (
+                crate::opcode::GATT_READ_HANDLE_VALUE_OFFSET,
+                params_struct.copy_into_slice(params),
+            ),
+            #[cfg(feature = "ms")]
+            ReturnParameters::GattUpdateLongCharacteristicValue(status) => (
+                crate::opcode::GATT_UPDATE_LONG_CHARACTERISTIC_VALUE,
+                write_status(params, status),
+            ),
+            ReturnParameters::L2CapConnectionParameterUpdateResponse(status) => (
+                crate::opcode::L2CAP_CONN_PARAM_UPDATE_RESP,
+                write_status(params, status),
+            ),
+            ReturnParameters::UpdaterStart(status) => {
+                (crate::opcode::UPDATER_START, write_status(params, status))
+            }
+            ReturnParameters::UpdaterReboot(status) => {
+                (crate::opcode::UPDATER_REBOOT, write_status(params, status))
+            }
+            ReturnParameters::UpdaterGetVersion(ref params_struct) => (
+                crate::opcode::UPDATER_GET_VERSION,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::UpdaterGetBufferSize(ref params_struct) => (
+                crate::opcode::UPDATER_GET_BUFFER_SIZE,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::UpdaterEraseBlueFlag(status) => (
+                crate::opcode::UPDATER_ERASE_BLUE_FLAG,
+                write_status(params, status),
+            ),
+            ReturnParameters::UpdaterResetBlueFlag(status) => (
+                crate::opcode::UPDATER_RESET_BLUE_FLAG,
+                write_status(params, status),
+            ),
+            ReturnParameters::UpdaterEraseSector(status) => (
+                crate::opcode::UPDATER_ERASE_SECTOR,
+                write_status(params, status),
+            ),
+            ReturnParameters::UpdaterProgramDataBlock(status) => (
+                crate::opcode::UPDATER_PROGRAM_DATA_BLOCK,
+                write_status(params, status),
+            ),
+            ReturnParameters::UpdaterReadDataBlock(ref params_struct) => (
+                crate::opcode::UPDATER_READ_DATA_BLOCK,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::UpdaterCalculateCrc(ref params_struct) => (
+                crate::opcode::UPDATER_CALC_CRC,
+                params_struct.copy_into_slice(params),
+            ),
+            ReturnParameters::Unknown { opcode, ref bytes } => {
+                let bytes = bytes.as_slice();
+                params[..bytes.len()].copy_from_slice(bytes);
+                (opcode, bytes.len())
+            }
+        };
+        LittleEndian::write_u16(&mut bytes[0..2], opcode.0);
+
+        2 + len
+    }
+}
+
 fn check_len_at_least(
     buffer: &[u8],
     len: usize,
@@ -494,17 +1372,99 @@ fn to_status(
     bytes[0].try_into().map_err(hci::event::rewrap_bad_status)
 }
 
+fn write_status(bytes: &mut [u8], status: hci::Status<crate::event::Status>) -> usize {
+    bytes[0] = status.into();
+    1
+}
+
+/// Unparsed return parameters of an [unknown](ReturnParameters::Unknown) command.
+#[derive(Copy, Clone)]
+pub struct RawReturnParameters {
+    len: usize,
+    buf: [u8; MAX_RAW_RETURN_PARAMETERS_LEN],
+}
+
+// Max packet size (255 bytes) less the number of command packets (1 byte) and opcode (2 bytes).
+const MAX_RAW_RETURN_PARAMETERS_LEN: usize = super::payload_limit(252);
+
+impl RawReturnParameters {
+    /// Returns the valid return parameter bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl Debug for RawReturnParameters {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?}", self.as_slice())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for RawReturnParameters {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=[u8]:#x}", self.as_slice())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RawReturnParameters {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(self.as_slice(), serializer)
+    }
+}
+
+fn to_unknown(
+    opcode: hci::Opcode,
+    bytes: &[u8],
+) -> Result<ReturnParameters, hci::event::Error<super::BlueNRGError>> {
+    if bytes.len() > MAX_RAW_RETURN_PARAMETERS_LEN {
+        return Err(hci::event::Error::BadLength(
+            bytes.len(),
+            MAX_RAW_RETURN_PARAMETERS_LEN,
+        ));
+    }
+
+    let mut buf = [0; MAX_RAW_RETURN_PARAMETERS_LEN];
+    buf[..bytes.len()].copy_from_slice(bytes);
+
+    Ok(ReturnParameters::Unknown {
+        opcode,
+        bytes: RawReturnParameters {
+            len: bytes.len(),
+            buf,
+        },
+    })
+}
+
 /// Parameters returned by the [HAL Get Firmware
 /// Revision](crate::hal::Commands::get_firmware_revision) command.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HalFirmwareRevision {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// The firmware revision number.
     pub revision: u16,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for HalFirmwareRevision {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "HalFirmwareRevision {{ status: {}, revision: {} }}",
+            Remote(&self.status),
+            self.revision
+        )
+    }
+}
+
 fn to_hal_firmware_revision(
     bytes: &[u8],
 ) -> Result<HalFirmwareRevision, hci::event::Error<super::BlueNRGError>> {
@@ -516,11 +1476,22 @@ fn to_hal_firmware_revision(
     })
 }
 
+impl HalFirmwareRevision {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        LittleEndian::write_u16(&mut bytes[1..3], self.revision);
+
+        3
+    }
+}
+
 /// Parameters returned by the [HAL Read Config Data](crate::hal::Commands::read_config_data)
 /// command.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HalConfigData {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// Requested value.
@@ -534,13 +1505,28 @@ pub struct HalConfigData {
     pub value: HalConfigParameter,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for HalConfigData {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "HalConfigData {{ status: {}, value: {} }}",
+            Remote(&self.status),
+            self.value
+        )
+    }
+}
+
 /// Potential values that can be fetched by [HAL Read Config
 /// Data](crate::hal::Commands::read_config_data).
 #[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HalConfigParameter {
     /// Bluetooth public address. Corresponds to
     /// [PublicAddress](crate::hal::ConfigParameter::PublicAddress).
-    PublicAddress(hci::BdAddr),
+    PublicAddress(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::BdAddrDef"))] hci::BdAddr,
+    ),
 
     /// Diversifier used to derive CSRK (connection signature resolving key).  Corresponds to
     /// [Diversifier](crate::hal::ConfigParameter::Diversifier).
@@ -549,7 +1535,13 @@ pub enum HalConfigParameter {
     /// A requested encryption key. Corresponds to either
     /// [EncryptionRoot](crate::hal::ConfigParameter::EncryptionRoot) or
     /// [IdentityRoot](crate::hal::ConfigParameter::IdentityRoot).
-    EncryptionKey(hci::host::EncryptionKey),
+    EncryptionKey(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::EncryptionKeyDef")
+        )]
+        hci::host::EncryptionKey,
+    ),
 
     /// A single-byte value. Corresponds to either
     /// [LinkLayerOnly](crate::hal::ConfigParameter::LinkLayerOnly) or
@@ -557,6 +1549,22 @@ pub enum HalConfigParameter {
     Byte(u8),
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for HalConfigParameter {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            HalConfigParameter::PublicAddress(addr) => {
+                defmt::write!(f, "PublicAddress({})", Remote(addr))
+            }
+            HalConfigParameter::Diversifier(value) => defmt::write!(f, "Diversifier({})", value),
+            HalConfigParameter::EncryptionKey(value) => {
+                defmt::write!(f, "EncryptionKey({})", defmt::Debug2Format(value))
+            }
+            HalConfigParameter::Byte(value) => defmt::write!(f, "Byte({})", value),
+        }
+    }
+}
+
 fn to_hal_config_data(
     bytes: &[u8],
 ) -> Result<HalConfigData, hci::event::Error<super::BlueNRGError>> {
@@ -595,17 +1603,57 @@ fn to_hal_config_parameter(
     }
 }
 
+impl HalConfigData {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        let len = match self.value {
+            HalConfigParameter::PublicAddress(addr) => {
+                bytes[1..7].copy_from_slice(&addr.0);
+                6
+            }
+            HalConfigParameter::Diversifier(value) => {
+                LittleEndian::write_u16(&mut bytes[1..3], value);
+                2
+            }
+            HalConfigParameter::EncryptionKey(ref key) => {
+                bytes[1..17].copy_from_slice(&key.0);
+                16
+            }
+            HalConfigParameter::Byte(value) => {
+                bytes[1] = value;
+                1
+            }
+        };
+
+        1 + len
+    }
+}
+
 /// Parameters returned by the [HAL Get Tx Test Packet
 /// Count](crate::hal::Commands::get_tx_test_packet_count) command.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HalTxTestPacketCount {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// Number of packets sent during the last Direct TX test.
     pub packet_count: u32,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for HalTxTestPacketCount {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "HalTxTestPacketCount {{ status: {}, packet_count: {} }}",
+            Remote(&self.status),
+            self.packet_count
+        )
+    }
+}
+
 fn to_hal_tx_test_packet_count(
     bytes: &[u8],
 ) -> Result<HalTxTestPacketCount, hci::event::Error<super::BlueNRGError>> {
@@ -616,28 +1664,70 @@ fn to_hal_tx_test_packet_count(
     })
 }
 
+impl HalTxTestPacketCount {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        LittleEndian::write_u32(&mut bytes[1..5], self.packet_count);
+
+        5
+    }
+}
+
 /// Parameters returned by the [HAL Get Link Status](crate::hal::Commands::get_link_status) command.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HalLinkStatus {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// State of the client connections.
     pub clients: [ClientStatus; 8],
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for HalLinkStatus {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "HalLinkStatus {{ status: {}, clients: {} }}",
+            Remote(&self.status),
+            self.clients
+        )
+    }
+}
+
 /// State of a client connection.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct ClientStatus {
     /// Link state for the client.
     pub state: LinkState,
 
     /// Connection handle for the client
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: hci::ConnectionHandle,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for ClientStatus {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "ClientStatus {{ state: {}, conn_handle: {} }}",
+            self.state,
+            Remote(&self.conn_handle)
+        )
+    }
+}
+
 /// Potential states for a connection.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum LinkState {
     /// Idle
     Idle,
@@ -657,6 +1747,16 @@ pub enum LinkState {
     RxTest,
 }
 
+impl LinkState {
+    /// Returns true if the link is connected, in either role.
+    pub fn is_connected(&self) -> bool {
+        matches!(
+            self,
+            LinkState::ConnectedAsPeripheral | LinkState::ConnectedAsPrimary
+        )
+    }
+}
+
 impl TryFrom<u8> for LinkState {
     type Error = super::BlueNRGError;
 
@@ -675,6 +1775,21 @@ impl TryFrom<u8> for LinkState {
     }
 }
 
+impl From<LinkState> for u8 {
+    fn from(state: LinkState) -> u8 {
+        match state {
+            LinkState::Idle => 0,
+            LinkState::Advertising => 1,
+            LinkState::ConnectedAsPeripheral => 2,
+            LinkState::Scanning => 3,
+            LinkState::Reserved => 4,
+            LinkState::ConnectedAsPrimary => 5,
+            LinkState::TxTest => 6,
+            LinkState::RxTest => 7,
+        }
+    }
+}
+
 fn to_hal_link_status(
     bytes: &[u8],
 ) -> Result<HalLinkStatus, hci::event::Error<super::BlueNRGError>> {
@@ -700,11 +1815,25 @@ fn to_hal_link_status(
     Ok(status)
 }
 
+impl HalLinkStatus {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        for (i, client) in self.clients.iter().enumerate() {
+            bytes[1 + i] = client.state.into();
+            LittleEndian::write_u16(&mut bytes[9 + 2 * i..11 + 2 * i], client.conn_handle.0);
+        }
+
+        25
+    }
+}
+
 /// Parameters returned by the [HAL Get Anchor Period](crate::hal::Commands::get_anchor_period)
 /// command.
 #[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HalAnchorPeriod {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// Duration between the beginnings of sniff anchor points.
@@ -714,6 +1843,19 @@ pub struct HalAnchorPeriod {
     pub max_slot: Duration,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for HalAnchorPeriod {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "HalAnchorPeriod {{ status: {}, anchor_interval: {}, max_slot: {} }}",
+            Remote(&self.status),
+            self.anchor_interval,
+            self.max_slot
+        )
+    }
+}
+
 fn to_hal_anchor_period(
     bytes: &[u8],
 ) -> Result<HalAnchorPeriod, hci::event::Error<super::BlueNRGError>> {
@@ -728,14 +1870,29 @@ fn to_hal_anchor_period(
     })
 }
 
+impl HalAnchorPeriod {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        LittleEndian::write_u32(
+            &mut bytes[1..5],
+            (self.anchor_interval.as_micros() / 625) as u32,
+        );
+        LittleEndian::write_u32(&mut bytes[5..9], (self.max_slot.as_micros() / 625) as u32);
+
+        9
+    }
+}
+
 /// Parameters returned by the [GAP Init](crate::gap::Commands::init) command.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GapInit {
     /// Did the command fail, and if so, how?
     ///
     /// Should be one of:
     /// - [Success](hci::Status::Success)
     /// - [InvalidParameters](hci::Status::InvalidParameters)
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// Handle for the GAP service
@@ -748,6 +1905,24 @@ pub struct GapInit {
     pub appearance_handle: crate::gatt::CharacteristicHandle,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GapInit {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GapInit {{ status: {}, service_handle: {}, dev_name_handle: ",
+            Remote(&self.status),
+            self.service_handle
+        );
+        defmt::write!(
+            f,
+            "{}, appearance_handle: {} }}",
+            self.dev_name_handle,
+            self.appearance_handle
+        )
+    }
+}
+
 fn to_gap_init(bytes: &[u8]) -> Result<GapInit, hci::event::Error<super::BlueNRGError>> {
     require_len!(bytes, 7);
 
@@ -759,11 +1934,24 @@ fn to_gap_init(bytes: &[u8]) -> Result<GapInit, hci::event::Error<super::BlueNRG
     })
 }
 
+impl GapInit {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        LittleEndian::write_u16(&mut bytes[1..3], self.service_handle.0);
+        LittleEndian::write_u16(&mut bytes[3..5], self.dev_name_handle.0);
+        LittleEndian::write_u16(&mut bytes[5..7], self.appearance_handle.0);
+
+        7
+    }
+}
+
 /// Parameters returned by the [GAP Get Security Level](crate::gap::Commands::get_security_level)
 /// command.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GapSecurityLevel {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// Is MITM (man-in-the-middle) protection required?
@@ -779,8 +1967,29 @@ pub struct GapSecurityLevel {
     pub pass_key_required: PassKeyRequirement,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GapSecurityLevel {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GapSecurityLevel {{ status: {}, mitm_protection_required: ",
+            Remote(&self.status)
+        );
+        defmt::write!(
+            f,
+            "{}, bonding_required: {}, out_of_band_data_present: {}, pass_key_required: ",
+            self.mitm_protection_required,
+            self.bonding_required,
+            self.out_of_band_data_present
+        );
+        defmt::write!(f, "{} }}", self.pass_key_required)
+    }
+}
+
 /// Options for pass key generation.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum PassKeyRequirement {
     /// A pass key is not required.
     NotRequired,
@@ -803,6 +2012,16 @@ impl TryFrom<u8> for PassKeyRequirement {
     }
 }
 
+impl From<PassKeyRequirement> for u8 {
+    fn from(requirement: PassKeyRequirement) -> u8 {
+        match requirement {
+            PassKeyRequirement::NotRequired => 0x00,
+            PassKeyRequirement::FixedPin => 0x01,
+            PassKeyRequirement::Generated => 0x02,
+        }
+    }
+}
+
 fn to_boolean(value: u8) -> Result<bool, super::BlueNRGError> {
     match value {
         0 => Ok(false),
@@ -825,25 +2044,53 @@ fn to_gap_security_level(
     })
 }
 
+impl GapSecurityLevel {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        bytes[1] = self.mitm_protection_required as u8;
+        bytes[2] = self.bonding_required as u8;
+        bytes[3] = self.out_of_band_data_present as u8;
+        bytes[4] = self.pass_key_required.into();
+
+        5
+    }
+}
+
 #[cfg(feature = "ms")]
 /// Parameters returned by the [GAP Resolve Private
 /// Address](crate::gap::Commands::resolve_private_address) command.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GapResolvePrivateAddress {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// If the address was successfully resolved, the peer address is returned.  This value is
-    /// `None` if the address could not be resolved.
+    /// `None` if the address could not be resolved, or if the controller is the BlueNRG
+    /// [variant](crate::Variant), which only returns the status.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::option_bd_addr"))]
     pub bd_addr: Option<hci::BdAddr>,
 }
 
+#[cfg(all(feature = "ms", feature = "defmt"))]
+impl defmt::Format for GapResolvePrivateAddress {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GapResolvePrivateAddress {{ status: {}, bd_addr: {} }}",
+            Remote(&self.status),
+            Remote(&self.bd_addr)
+        )
+    }
+}
+
 #[cfg(feature = "ms")]
 fn to_gap_resolve_private_address(
     bytes: &[u8],
 ) -> Result<GapResolvePrivateAddress, hci::event::Error<super::BlueNRGError>> {
     let status = to_status(&bytes)?;
-    if status == hci::Status::Success {
+    if status == hci::Status::Success && crate::is_ms() {
         require_len!(bytes, 7);
 
         let mut addr = [0; 6];
@@ -861,6 +2108,19 @@ fn to_gap_resolve_private_address(
     }
 }
 
+#[cfg(feature = "ms")]
+impl GapResolvePrivateAddress {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        if let Some(addr) = self.bd_addr {
+            bytes[1..7].copy_from_slice(&addr.0);
+            7
+        } else {
+            1
+        }
+    }
+}
+
 /// Parameters returned by the [GAP Get Bonded Devices](crate::gap::Commands::get_bonded_devices)
 /// command.
 #[derive(Copy, Clone)]
@@ -874,7 +2134,7 @@ pub struct GapBondedDevices {
 }
 
 // Max packet size (255 bytes) less non-address data (4 bytes) divided by peer address size (7):
-const MAX_ADDRESSES: usize = 35;
+const MAX_ADDRESSES: usize = super::payload_limit(35 * 7) / 7;
 
 impl GapBondedDevices {
     /// Return an iterator over the bonded device addresses.
@@ -893,6 +2153,27 @@ impl Debug for GapBondedDevices {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GapBondedDevices {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{}", defmt::Debug2Format(&self.bonded_addresses()))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for GapBondedDevices {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(
+            self.bonded_addresses()
+                .iter()
+                .map(crate::serde_remote::BdAddrTypeRef),
+        )
+    }
+}
+
 fn to_gap_bonded_devices(
     bytes: &[u8],
 ) -> Result<GapBondedDevices, hci::event::Error<super::BlueNRGError>> {
@@ -909,6 +2190,12 @@ fn to_gap_bonded_devices(
                     super::BlueNRGError::PartialBondedDeviceAddress,
                 ));
             }
+            if address_count > MAX_ADDRESSES {
+                return Err(hci::event::Error::BadLength(
+                    bytes.len(),
+                    HEADER_LEN + ADDR_LEN * MAX_ADDRESSES,
+                ));
+            }
 
             let mut address_buffer = [hci::BdAddrType::Public(hci::BdAddr([0; 6])); MAX_ADDRESSES];
             for (i, byte) in address_buffer.iter_mut().enumerate().take(address_count) {
@@ -934,11 +2221,29 @@ fn to_gap_bonded_devices(
     }
 }
 
+impl GapBondedDevices {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        if self.status != hci::Status::Success {
+            return 1;
+        }
+
+        bytes[1] = self.address_count as u8;
+        for (i, addr) in self.bonded_addresses().iter().enumerate() {
+            addr.copy_into_slice(&mut bytes[2 + 7 * i..9 + 7 * i]);
+        }
+
+        2 + 7 * self.address_count
+    }
+}
+
 /// Parameters returned by the [GATT Add Service](crate::gatt::Commands::add_service) and [GATT
 /// Include Service](crate::gatt::Commands::include_service) commands.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GattService {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// Handle of the Service
@@ -950,6 +2255,18 @@ pub struct GattService {
     pub service_handle: crate::gatt::ServiceHandle,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GattService {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GattService {{ status: {}, service_handle: {} }}",
+            Remote(&self.status),
+            self.service_handle
+        )
+    }
+}
+
 fn to_gatt_service(bytes: &[u8]) -> Result<GattService, hci::event::Error<super::BlueNRGError>> {
     require_len!(bytes, 3);
 
@@ -959,17 +2276,40 @@ fn to_gatt_service(bytes: &[u8]) -> Result<GattService, hci::event::Error<super:
     })
 }
 
+impl GattService {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        LittleEndian::write_u16(&mut bytes[1..3], self.service_handle.0);
+
+        3
+    }
+}
+
 /// Parameters returned by the [GATT Add Characteristic](crate::gatt::Commands::add_characteristic)
 /// command.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GattCharacteristic {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// Handle of the characteristic.
     pub characteristic_handle: crate::gatt::CharacteristicHandle,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GattCharacteristic {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GattCharacteristic {{ status: {}, characteristic_handle: {} }}",
+            Remote(&self.status),
+            self.characteristic_handle
+        )
+    }
+}
+
 fn to_gatt_characteristic(
     bytes: &[u8],
 ) -> Result<GattCharacteristic, hci::event::Error<super::BlueNRGError>> {
@@ -983,17 +2323,40 @@ fn to_gatt_characteristic(
     })
 }
 
+impl GattCharacteristic {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        LittleEndian::write_u16(&mut bytes[1..3], self.characteristic_handle.0);
+
+        3
+    }
+}
+
 /// Parameters returned by the [GATT Add Characteristic
 /// Descriptor](crate::gatt::Commands::add_characteristic_descriptor) command.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GattCharacteristicDescriptor {
     /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
     pub status: hci::Status<crate::event::Status>,
 
     /// Handle of the characteristic.
     pub descriptor_handle: crate::gatt::DescriptorHandle,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GattCharacteristicDescriptor {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GattCharacteristicDescriptor {{ status: {}, descriptor_handle: {} }}",
+            Remote(&self.status),
+            self.descriptor_handle
+        )
+    }
+}
+
 fn to_gatt_characteristic_descriptor(
     bytes: &[u8],
 ) -> Result<GattCharacteristicDescriptor, hci::event::Error<super::BlueNRGError>> {
@@ -1005,6 +2368,15 @@ fn to_gatt_characteristic_descriptor(
     })
 }
 
+impl GattCharacteristicDescriptor {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        LittleEndian::write_u16(&mut bytes[1..3], self.descriptor_handle.0);
+
+        3
+    }
+}
+
 /// Parameters returned by the [GATT Read Handle Value](crate::gatt::Commands::read_handle_value)
 /// command.
 #[derive(Copy, Clone)]
@@ -1027,11 +2399,39 @@ impl Debug for GattHandleValue {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GattHandleValue {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{status: {}; value: {=[u8]:#x}}}",
+            defmt::Debug2Format(&self.status),
+            self.value()
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for GattHandleValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let status: u8 = self.status.into();
+        let mut state = serializer.serialize_struct("GattHandleValue", 2)?;
+        state.serialize_field("status", &status)?;
+        state.serialize_field("value", self.value())?;
+        state.end()
+    }
+}
+
 impl GattHandleValue {
     // Maximum length of the handle value. The spec says the length can be 2 bytes (up to 65535),
     // but the communication layer is limited to 255 bytes in a packet. There are 6 bytes reserved
     // for data other than the value, so the maximum length of the value buffer is 249 bytes.
-    const MAX_VALUE_BUF: usize = 249;
+    const MAX_VALUE_BUF: usize = super::payload_limit(249);
 
     /// Return the handle value. Only valid bytes are returned.
     pub fn value(&self) -> &[u8] {
@@ -1047,6 +2447,12 @@ fn to_gatt_handle_value(
     let status = to_status(bytes)?;
     let value_len = LittleEndian::read_u16(&bytes[1..3]) as usize;
     require_len!(bytes, 3 + value_len);
+    if value_len > GattHandleValue::MAX_VALUE_BUF {
+        return Err(hci::event::Error::BadLength(
+            value_len,
+            GattHandleValue::MAX_VALUE_BUF,
+        ));
+    }
 
     let mut handle_value = GattHandleValue {
         status,
@@ -1057,3 +2463,239 @@ fn to_gatt_handle_value(
 
     Ok(handle_value)
 }
+
+impl GattHandleValue {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        LittleEndian::write_u16(&mut bytes[1..3], self.value_len as u16);
+        bytes[3..3 + self.value_len].copy_from_slice(self.value());
+
+        3 + self.value_len
+    }
+}
+
+/// Parameters returned by the [Updater Get Version](crate::updater::Commands::get_updater_version)
+/// command.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdaterVersion {
+    /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+    pub status: hci::Status<crate::event::Status>,
+
+    /// Version of the updater.
+    pub version: u8,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for UpdaterVersion {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "UpdaterVersion {{ status: {}, version: {} }}",
+            Remote(&self.status),
+            self.version
+        )
+    }
+}
+
+fn to_updater_version(
+    bytes: &[u8],
+) -> Result<UpdaterVersion, hci::event::Error<super::BlueNRGError>> {
+    require_len!(bytes, 2);
+
+    Ok(UpdaterVersion {
+        status: to_status(bytes)?,
+        version: bytes[1],
+    })
+}
+
+impl UpdaterVersion {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        bytes[1] = self.version;
+
+        2
+    }
+}
+
+/// Parameters returned by the [Updater Get Buffer
+/// Size](crate::updater::Commands::get_updater_buffer_size) command.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdaterBufferSize {
+    /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+    pub status: hci::Status<crate::event::Status>,
+
+    /// Size of the updater buffer, in bytes. This is the maximum length of the data that can be
+    /// [programmed](crate::updater::Commands::program_data_block) with a single command.
+    pub buffer_size: usize,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for UpdaterBufferSize {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "UpdaterBufferSize {{ status: {}, buffer_size: {} }}",
+            Remote(&self.status),
+            self.buffer_size
+        )
+    }
+}
+
+fn to_updater_buffer_size(
+    bytes: &[u8],
+) -> Result<UpdaterBufferSize, hci::event::Error<super::BlueNRGError>> {
+    require_len!(bytes, 2);
+
+    Ok(UpdaterBufferSize {
+        status: to_status(bytes)?,
+        buffer_size: bytes[1] as usize,
+    })
+}
+
+impl UpdaterBufferSize {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        bytes[1] = self.buffer_size as u8;
+
+        2
+    }
+}
+
+/// Parameters returned by the [Updater Read Data
+/// Block](crate::updater::Commands::read_data_block) command.
+#[derive(Copy, Clone)]
+pub struct UpdaterData {
+    /// Did the command fail, and if so, how?
+    pub status: hci::Status<crate::event::Status>,
+
+    data_buf: [u8; UpdaterData::MAX_DATA_BUF],
+    data_len: usize,
+}
+
+impl Debug for UpdaterData {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{{")?;
+        write!(f, "status: {:?}; data: {{", self.status)?;
+        for byte in self.data().iter() {
+            write!(f, "{:?}, ", byte)?;
+        }
+        write!(f, "}}}}")
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for UpdaterData {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{status: {}; data: {=[u8]:#x}}}",
+            defmt::Debug2Format(&self.status),
+            self.data()
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for UpdaterData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let status: u8 = self.status.into();
+        let mut state = serializer.serialize_struct("UpdaterData", 2)?;
+        state.serialize_field("status", &status)?;
+        state.serialize_field("data", self.data())?;
+        state.end()
+    }
+}
+
+impl UpdaterData {
+    // Maximum length of the data. The read command limits it to the 249 bytes that fit in a
+    // program command.
+    const MAX_DATA_BUF: usize = super::payload_limit(crate::updater::MAX_DATA_BLOCK_LEN);
+
+    /// Returns the data that was read from flash. Only valid bytes are returned.
+    pub fn data(&self) -> &[u8] {
+        &self.data_buf[..self.data_len]
+    }
+}
+
+fn to_updater_data(bytes: &[u8]) -> Result<UpdaterData, hci::event::Error<super::BlueNRGError>> {
+    require_len_at_least!(bytes, 1);
+
+    let status = to_status(bytes)?;
+    let data_len = bytes.len() - 1;
+    if data_len > UpdaterData::MAX_DATA_BUF {
+        return Err(hci::event::Error::BadLength(
+            data_len,
+            UpdaterData::MAX_DATA_BUF,
+        ));
+    }
+
+    let mut data = UpdaterData {
+        status,
+        data_buf: [0; UpdaterData::MAX_DATA_BUF],
+        data_len,
+    };
+    data.data_buf[..data_len].copy_from_slice(&bytes[1..]);
+
+    Ok(data)
+}
+
+impl UpdaterData {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        bytes[1..1 + self.data_len].copy_from_slice(self.data());
+
+        1 + self.data_len
+    }
+}
+
+/// Parameters returned by the [Updater Calculate CRC](crate::updater::Commands::calculate_crc)
+/// command.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct UpdaterCrc {
+    /// Did the command fail, and if so, how?
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+    pub status: hci::Status<crate::event::Status>,
+
+    /// CRC of the flash sectors. See [`crc`](crate::updater::crc).
+    pub crc: u32,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for UpdaterCrc {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "UpdaterCrc {{ status: {}, crc: {} }}",
+            Remote(&self.status),
+            self.crc
+        )
+    }
+}
+
+fn to_updater_crc(bytes: &[u8]) -> Result<UpdaterCrc, hci::event::Error<super::BlueNRGError>> {
+    require_len!(bytes, 5);
+
+    Ok(UpdaterCrc {
+        status: to_status(bytes)?,
+        crc: LittleEndian::read_u32(&bytes[1..5]),
+    })
+}
+
+impl UpdaterCrc {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_status(bytes, self.status);
+        LittleEndian::write_u32(&mut bytes[1..5], self.crc);
+
+        5
+    }
+}
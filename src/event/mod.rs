@@ -2,24 +2,107 @@
 //!
 //! The BlueNRG implementation defines several additional events that are packaged as
 //! vendor-specific events by the Bluetooth HCI. This module defines those events and functions to
-//! deserialize buffers into them.
+//! deserialize buffers into them and serialize them back into buffers.
 extern crate bluetooth_hci as hci;
 
 pub mod command;
 
+use crate::uuid::Uuid;
 use byteorder::{ByteOrder, LittleEndian};
 use core::cmp::PartialEq;
 use core::convert::{TryFrom, TryInto};
 use core::fmt::{Debug, Formatter, Result as FmtResult};
 use core::mem;
+use core::sync::atomic::{AtomicBool, Ordering};
 use core::time::Duration;
 
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
 pub use hci::types::{ConnectionInterval, ConnectionIntervalError};
 pub use hci::{BdAddr, BdAddrType, ConnectionHandle};
 
+/// Maximum number of bytes of variable-length data (attribute values, names, debug data, unparsed
+/// parameters) that a single event can carry.
+///
+/// Every event is as large as its largest variant, so this bounds the size of [`BlueNRGEvent`] and
+/// [`ReturnParameters`](command::ReturnParameters). By default, events can hold anything the
+/// controller can fit in an HCI packet. Devices that never exchange large payloads (for example,
+/// those that keep the default ATT MTU) can enable one of the `event-payload-32`,
+/// `event-payload-64`, or `event-payload-128` features to shrink these two vendor types. If more
+/// than one is enabled, the largest limit applies. Events whose payload does not fit return
+/// [`BadLength`](hci::event::Error::BadLength) instead.
+///
+/// The features do not shrink `hci::Event<BlueNRGEvent>`: the standard events defined by the
+/// `bluetooth-hci` crate keep their own fixed-size buffers, so the wrapping event stays as large
+/// as its largest standard variant. Only code that stores [`BlueNRGEvent`] or
+/// [`ReturnParameters`](command::ReturnParameters) values directly saves memory.
+pub const MAX_PAYLOAD_LEN: usize = if cfg!(feature = "event-payload-128") {
+    128
+} else if cfg!(feature = "event-payload-64") {
+    64
+} else if cfg!(feature = "event-payload-32") {
+    32
+} else {
+    255
+};
+
+// Returns the size of a buffer that would hold `len` bytes if the payload were not limited.
+pub(crate) const fn payload_limit(len: usize) -> usize {
+    if len < MAX_PAYLOAD_LEN {
+        len
+    } else {
+        MAX_PAYLOAD_LEN
+    }
+}
+
+static LENIENT_ATT_PARSING: AtomicBool = AtomicBool::new(false);
+
+/// Selects whether ATT responses that end in the middle of a record are rejected or truncated.
+///
+/// By default, the [ATT Find Information](BlueNRGEvent::AttFindInformationResponse), [ATT Find by
+/// Type Value](BlueNRGEvent::AttFindByTypeValueResponse), [ATT Read by
+/// Type](BlueNRGEvent::AttReadByTypeResponse), and [ATT Read by Group
+/// Type](BlueNRGEvent::AttReadByGroupTypeResponse) responses fail to deserialize if the packet ends
+/// with a partial record (for example, with
+/// [`AttReadByTypeResponsePartial`](BlueNRGError::AttReadByTypeResponsePartial)), and the whole
+/// event is lost. With lenient parsing, these events keep their complete records, the partial record
+/// is dropped, and the event reports that it was truncated (for example,
+/// [`AttReadByTypeResponse::is_truncated`]).
+///
+/// Like the [variant](crate::variant), the setting is global, because events are deserialized
+/// without any access to the controller.
+pub fn set_lenient_att_parsing(lenient: bool) {
+    LENIENT_ATT_PARSING.store(lenient, Ordering::Relaxed);
+}
+
+/// Returns true if [lenient parsing](set_lenient_att_parsing) of ATT responses is enabled.
+pub fn lenient_att_parsing() -> bool {
+    LENIENT_ATT_PARSING.load(Ordering::Relaxed)
+}
+
+// Returns the length of the complete records of `record_len` bytes at the start of `len` bytes of
+// data, and whether they are followed by a partial record. A partial record is the `partial` error
+// unless lenient parsing is enabled.
+fn complete_records_len(
+    len: usize,
+    record_len: usize,
+    partial: BlueNRGError,
+) -> Result<(usize, bool), BlueNRGError> {
+    let complete_len = len - len % record_len;
+    if complete_len == len {
+        Ok((len, false))
+    } else if lenient_att_parsing() {
+        Ok((complete_len, true))
+    } else {
+        Err(partial)
+    }
+}
+
 /// Vendor-specific events for the BlueNRG-MS controllers.
 #[allow(clippy::large_enum_variant)]
 #[derive(Clone, Copy, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum BlueNRGEvent {
     /// When the BlueNRG-MS firmware is started normally, it gives this event to the user to
     /// indicate the system has started.
@@ -37,6 +120,18 @@ pub enum BlueNRGEvent {
     #[cfg(feature = "ms")]
     CrashReport(FaultData),
 
+    /// This event is generated when the radio leaves one of the states selected with [HAL Set
+    /// Radio Activity Mask](crate::hal::Commands::set_radio_activity_mask). It reports the next
+    /// radio state and when it starts, so the application can schedule its own activity around
+    /// the radio.
+    #[cfg(feature = "bluenrg-1-2")]
+    HalEndOfRadioActivity(HalEndOfRadioActivity),
+
+    /// This event is generated when the device receives a scan request while advertising, if it is
+    /// enabled with [HAL Set Event Mask](crate::hal::Commands::set_hal_event_mask).
+    #[cfg(feature = "bluenrg-1-2")]
+    HalScanRequestReport(HalScanRequestReport),
+
     /// This event is generated by the controller when the limited discoverable mode ends due to
     /// timeout (180 seconds).
     GapLimitedDiscoverableTimeout,
@@ -50,13 +145,25 @@ pub enum BlueNRGEvent {
     /// This event is generated by the Security manager to the application when a pass key is
     /// required for pairing.  When this event is received, the application has to respond with the
     /// `gap_pass_key_response` command.
-    GapPassKeyRequest(ConnectionHandle),
+    GapPassKeyRequest(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        ConnectionHandle,
+    ),
 
     /// This event is generated by the Security manager to the application when the application has
     /// set that authorization is required for reading/writing of attributes. This event will be
     /// generated as soon as the pairing is complete. When this event is received,
     /// `gap_authorization_response` command should be used by the application.
-    GapAuthorizationRequest(ConnectionHandle),
+    GapAuthorizationRequest(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        ConnectionHandle,
+    ),
 
     /// This event is generated when the peripheral security request is successfully sent to the
     /// central device.
@@ -83,8 +190,16 @@ pub enum BlueNRGEvent {
     /// This event is sent only by a privacy enabled peripheral. The event is sent to the upper
     /// layers when the peripheral is unsuccessful in resolving the resolvable address of the peer
     /// device after connecting to it.
+    ///
+    /// Only the BlueNRG-MS [variant](crate::Variant) sends this event.
     #[cfg(feature = "ms")]
-    GapAddressNotResolved(ConnectionHandle),
+    GapAddressNotResolved(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        ConnectionHandle,
+    ),
 
     /// This event is generated when the reconnection address is generated during the general
     /// connection establishment procedure. The same address is set to the peer device also as a
@@ -92,8 +207,21 @@ pub enum BlueNRGEvent {
     /// reconnection address the next time while connecting to the bonded peripheral, the
     /// application needs to set its own address as well as the peer address to which it wants to
     /// connect to this reconnection address.
-    #[cfg(not(feature = "ms"))]
-    GapReconnectionAddress(BdAddr),
+    ///
+    /// Only the BlueNRG [variant](crate::Variant) sends this event.
+    GapReconnectionAddress(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::BdAddrDef"))] BdAddr,
+    ),
+
+    /// This event is generated during LE Secure Connections pairing with numeric comparison. The
+    /// application shows the value to the user, and confirms or rejects it with [GAP Numeric
+    /// Comparison Value Confirm](crate::gap::Commands::numeric_comparison_value_confirm).
+    #[cfg(feature = "bluenrg-1-2")]
+    GapNumericComparisonValue(GapNumericComparisonValue),
+
+    /// This event is generated when the peer sends a keypress notification during pass key entry.
+    #[cfg(feature = "bluenrg-1-2")]
+    GapKeypressNotification(GapKeypressNotification),
 
     /// This event is generated when the central device responds to the L2CAP connection update
     /// request packet. For more info see
@@ -103,7 +231,13 @@ pub enum BlueNRGEvent {
 
     /// This event is generated when the central device does not respond to the connection update
     /// request within 30 seconds.
-    L2CapProcedureTimeout(ConnectionHandle),
+    L2CapProcedureTimeout(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        ConnectionHandle,
+    ),
 
     /// The event is given by the L2CAP layer when a connection update request is received from the
     /// peripheral. The application has to respond by calling
@@ -121,7 +255,13 @@ pub enum BlueNRGEvent {
 
     /// This event is generated when a ATT client procedure completes either with error or
     /// successfully.
-    GattProcedureTimeout(ConnectionHandle),
+    GattProcedureTimeout(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        ConnectionHandle,
+    ),
 
     /// This event is generated in response to an Exchange MTU request.
     AttExchangeMtuResponse(AttExchangeMtuResponse),
@@ -159,7 +299,13 @@ pub enum BlueNRGEvent {
 
     /// This event is generated in response to an Execute Write Request. See the Bluetooth Core v4.1
     /// spec, Vol 3, Part F, section 3.4.6.3 and 3.4.6.4
-    AttExecuteWriteResponse(ConnectionHandle),
+    AttExecuteWriteResponse(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        ConnectionHandle,
+    ),
 
     /// This event is generated when an indication is received from the server.
     GattIndication(AttributeValue),
@@ -229,7 +375,13 @@ pub enum BlueNRGEvent {
 
     /// This event is raised on the server when the client confirms the reception of an indication.
     #[cfg(feature = "ms")]
-    GattServerConfirmation(ConnectionHandle),
+    GattServerConfirmation(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        ConnectionHandle,
+    ),
 
     /// This event is given to the application when a prepare write request is received by the
     /// server from the client. This event will be given to the application only if the event bit
@@ -242,10 +394,163 @@ pub enum BlueNRGEvent {
     /// application.
     #[cfg(feature = "ms")]
     AttPrepareWritePermitRequest(AttPrepareWritePermitRequest),
+
+    /// An event that this crate does not recognize, like events added by newer controller firmware
+    /// or events that only exist for another controller variant. The event parameters are passed
+    /// through unparsed so the application may still handle the event.
+    Unknown {
+        /// Vendor-specific event code (ECODE).
+        code: u16,
+
+        /// Event parameters, not including the event code.
+        data: RawEventData,
+    },
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for BlueNRGEvent {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            BlueNRGEvent::HalInitialized(event) => defmt::write!(f, "HalInitialized({})", event),
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::EventsLost(event) => defmt::write!(f, "EventsLost({})", event),
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::CrashReport(event) => defmt::write!(f, "CrashReport({})", event),
+            #[cfg(feature = "bluenrg-1-2")]
+            BlueNRGEvent::HalEndOfRadioActivity(event) => {
+                defmt::write!(f, "HalEndOfRadioActivity({})", event)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            BlueNRGEvent::HalScanRequestReport(event) => {
+                defmt::write!(f, "HalScanRequestReport({})", event)
+            }
+            BlueNRGEvent::GapLimitedDiscoverableTimeout => {
+                defmt::write!(f, "GapLimitedDiscoverableTimeout")
+            }
+            BlueNRGEvent::GapPairingComplete(event) => {
+                defmt::write!(f, "GapPairingComplete({})", event)
+            }
+            BlueNRGEvent::GapPassKeyRequest(conn_handle) => {
+                defmt::write!(f, "GapPassKeyRequest({})", Remote(conn_handle))
+            }
+            BlueNRGEvent::GapAuthorizationRequest(conn_handle) => {
+                defmt::write!(f, "GapAuthorizationRequest({})", Remote(conn_handle))
+            }
+            BlueNRGEvent::GapPeripheralSecurityInitiated => {
+                defmt::write!(f, "GapPeripheralSecurityInitiated")
+            }
+            BlueNRGEvent::GapBondLost => defmt::write!(f, "GapBondLost"),
+            BlueNRGEvent::GapDeviceFound(event) => defmt::write!(f, "GapDeviceFound({})", event),
+            BlueNRGEvent::GapProcedureComplete(event) => {
+                defmt::write!(f, "GapProcedureComplete({})", event)
+            }
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::GapAddressNotResolved(conn_handle) => {
+                defmt::write!(f, "GapAddressNotResolved({})", Remote(conn_handle))
+            }
+            BlueNRGEvent::GapReconnectionAddress(addr) => {
+                defmt::write!(f, "GapReconnectionAddress({})", Remote(addr))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            BlueNRGEvent::GapNumericComparisonValue(event) => {
+                defmt::write!(f, "GapNumericComparisonValue({})", event)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            BlueNRGEvent::GapKeypressNotification(event) => {
+                defmt::write!(f, "GapKeypressNotification({})", event)
+            }
+            BlueNRGEvent::L2CapConnectionUpdateResponse(event) => {
+                defmt::write!(f, "L2CapConnectionUpdateResponse({})", event)
+            }
+            BlueNRGEvent::L2CapProcedureTimeout(conn_handle) => {
+                defmt::write!(f, "L2CapProcedureTimeout({})", Remote(conn_handle))
+            }
+            BlueNRGEvent::L2CapConnectionUpdateRequest(event) => {
+                defmt::write!(f, "L2CapConnectionUpdateRequest({})", event)
+            }
+            BlueNRGEvent::GattAttributeModified(event) => {
+                defmt::write!(f, "GattAttributeModified({})", event)
+            }
+            BlueNRGEvent::GattProcedureTimeout(conn_handle) => {
+                defmt::write!(f, "GattProcedureTimeout({})", Remote(conn_handle))
+            }
+            BlueNRGEvent::AttExchangeMtuResponse(event) => {
+                defmt::write!(f, "AttExchangeMtuResponse({})", event)
+            }
+            BlueNRGEvent::AttFindInformationResponse(event) => {
+                defmt::write!(f, "AttFindInformationResponse({})", event)
+            }
+            BlueNRGEvent::AttFindByTypeValueResponse(event) => {
+                defmt::write!(f, "AttFindByTypeValueResponse({})", event)
+            }
+            BlueNRGEvent::AttReadByTypeResponse(event) => {
+                defmt::write!(f, "AttReadByTypeResponse({})", event)
+            }
+            BlueNRGEvent::AttReadResponse(event) => defmt::write!(f, "AttReadResponse({})", event),
+            BlueNRGEvent::AttReadBlobResponse(event) => {
+                defmt::write!(f, "AttReadBlobResponse({})", event)
+            }
+            BlueNRGEvent::AttReadMultipleResponse(event) => {
+                defmt::write!(f, "AttReadMultipleResponse({})", event)
+            }
+            BlueNRGEvent::AttReadByGroupTypeResponse(event) => {
+                defmt::write!(f, "AttReadByGroupTypeResponse({})", event)
+            }
+            BlueNRGEvent::AttPrepareWriteResponse(event) => {
+                defmt::write!(f, "AttPrepareWriteResponse({})", event)
+            }
+            BlueNRGEvent::AttExecuteWriteResponse(conn_handle) => {
+                defmt::write!(f, "AttExecuteWriteResponse({})", Remote(conn_handle))
+            }
+            BlueNRGEvent::GattIndication(event) => defmt::write!(f, "GattIndication({})", event),
+            BlueNRGEvent::GattNotification(event) => {
+                defmt::write!(f, "GattNotification({})", event)
+            }
+            BlueNRGEvent::GattProcedureComplete(event) => {
+                defmt::write!(f, "GattProcedureComplete({})", event)
+            }
+            BlueNRGEvent::AttErrorResponse(event) => {
+                defmt::write!(f, "AttErrorResponse({})", event)
+            }
+            BlueNRGEvent::GattDiscoverOrReadCharacteristicByUuidResponse(event) => {
+                defmt::write!(
+                    f,
+                    "GattDiscoverOrReadCharacteristicByUuidResponse({})",
+                    event
+                )
+            }
+            BlueNRGEvent::AttWritePermitRequest(event) => {
+                defmt::write!(f, "AttWritePermitRequest({})", event)
+            }
+            BlueNRGEvent::AttReadPermitRequest(event) => {
+                defmt::write!(f, "AttReadPermitRequest({})", event)
+            }
+            BlueNRGEvent::AttReadMultiplePermitRequest(event) => {
+                defmt::write!(f, "AttReadMultiplePermitRequest({})", event)
+            }
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::GattTxPoolAvailable(event) => {
+                defmt::write!(f, "GattTxPoolAvailable({})", event)
+            }
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::GattServerConfirmation(conn_handle) => {
+                defmt::write!(f, "GattServerConfirmation({})", Remote(conn_handle))
+            }
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::AttPrepareWritePermitRequest(event) => {
+                defmt::write!(f, "AttPrepareWritePermitRequest({})", event)
+            }
+            BlueNRGEvent::Unknown { code, data } => {
+                defmt::write!(f, "Unknown {{ code: {}, data: {} }}", code, data)
+            }
+        }
+    }
 }
 
 /// Enumeration of vendor-specific status codes.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum Status {
     /// The command cannot be executed due to the current state of the device.
@@ -373,10 +678,9 @@ impl Into<u8> for Status {
 
 /// Enumeration of potential errors when sending commands or deserializing events.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub enum BlueNRGError {
-    /// The event is not recoginized. Includes the unknown opcode.
-    UnknownEvent(u16),
-
     /// For the [HalInitialized](BlueNRGEvent::HalInitialized) event: the reset reason was not
     /// recognized. Includes the unrecognized byte.
     UnknownResetReason(u8),
@@ -434,7 +738,14 @@ pub enum BlueNRGError {
 
     /// For the [L2CAP Connection Update Request](BlueNRGEvent::L2CapConnectionUpdateRequest) event:
     /// The provided connection interval is invalid. Includes the underlying error.
-    BadConnectionInterval(ConnectionIntervalError),
+    BadConnectionInterval(
+        #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionIntervalErrorDef")
+        )]
+        ConnectionIntervalError,
+    ),
 
     /// For the [L2CAP Connection Update Request](BlueNRGEvent::L2CapConnectionUpdateRequest) event:
     /// The provided interval is invalid. Potential errors:
@@ -541,6 +852,21 @@ pub enum BlueNRGError {
     /// [event](command::ReturnParameters::GapGetBondedDevices): one of the address type bytes was
     /// invalid. Includes the invalid byte.
     BadBdAddrType(u8),
+
+    /// For the [HAL End of Radio Activity](BlueNRGEvent::HalEndOfRadioActivity) event: one of the
+    /// radio states was not recognized. Includes the unrecognized byte.
+    #[cfg(feature = "bluenrg-1-2")]
+    BadRadioState(u8),
+
+    /// For the [HAL Scan Request Report](BlueNRGEvent::HalScanRequestReport) event: the type of the
+    /// peer address was not recognized. Includes the unrecognized byte.
+    #[cfg(feature = "bluenrg-1-2")]
+    BadScanRequestAddrType(u8),
+
+    /// For the [GAP Keypress Notification](BlueNRGEvent::GapKeypressNotification) event: the
+    /// notification type was not recognized. Includes the unrecognized byte.
+    #[cfg(feature = "bluenrg-1-2")]
+    BadKeypressNotification(u8),
 }
 
 macro_rules! require_len {
@@ -559,6 +885,75 @@ macro_rules! require_len_at_least {
     };
 }
 
+// Returns BadLength if `len` bytes of variable-length data do not fit in a buffer that holds `max`
+// bytes. This only happens when the payload length is limited (see MAX_PAYLOAD_LEN).
+macro_rules! require_payload_fits {
+    ($len:expr, $max:expr) => {
+        if $len > $max {
+            return Err(hci::event::Error::BadLength($len, $max));
+        }
+    };
+}
+
+/// Unparsed parameters of an [unknown](BlueNRGEvent::Unknown) event.
+#[derive(Copy, Clone)]
+pub struct RawEventData {
+    len: usize,
+    buf: [u8; MAX_RAW_EVENT_DATA_LEN],
+}
+
+// Max packet size (255 bytes) less the event code (2 bytes).
+const MAX_RAW_EVENT_DATA_LEN: usize = payload_limit(253);
+
+impl RawEventData {
+    /// Returns the valid event parameter bytes.
+    pub fn as_slice(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+}
+
+impl Debug for RawEventData {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        write!(f, "{:?}", first_16(self.as_slice()))
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for RawEventData {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=[u8]:#x}", self.as_slice())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for RawEventData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(self.as_slice(), serializer)
+    }
+}
+
+fn to_unknown_event(
+    code: u16,
+    buffer: &[u8],
+) -> Result<BlueNRGEvent, hci::event::Error<BlueNRGError>> {
+    let data = &buffer[2..];
+    require_payload_fits!(data.len(), MAX_RAW_EVENT_DATA_LEN);
+
+    let mut buf = [0; MAX_RAW_EVENT_DATA_LEN];
+    buf[..data.len()].copy_from_slice(data);
+
+    Ok(BlueNRGEvent::Unknown {
+        code,
+        data: RawEventData {
+            len: data.len(),
+            buf,
+        },
+    })
+}
+
 fn first_16<T>(buffer: &[T]) -> &[T] {
     if buffer.len() < 16 {
         &buffer
@@ -586,9 +981,7 @@ impl hci::event::VendorEvent for BlueNRGEvent {
 
                 #[cfg(not(feature = "ms"))]
                 {
-                    Err(hci::event::Error::Vendor(BlueNRGError::UnknownEvent(
-                        event_code,
-                    )))
+                    to_unknown_event(event_code, buffer)
                 }
             }
             0x0003 => {
@@ -599,11 +992,17 @@ impl hci::event::VendorEvent for BlueNRGEvent {
 
                 #[cfg(not(feature = "ms"))]
                 {
-                    Err(hci::event::Error::Vendor(BlueNRGError::UnknownEvent(
-                        event_code,
-                    )))
+                    to_unknown_event(event_code, buffer)
                 }
             }
+            #[cfg(feature = "bluenrg-1-2")]
+            0x0004 => Ok(BlueNRGEvent::HalEndOfRadioActivity(
+                to_hal_end_of_radio_activity(buffer)?,
+            )),
+            #[cfg(feature = "bluenrg-1-2")]
+            0x0005 => Ok(BlueNRGEvent::HalScanRequestReport(
+                to_hal_scan_request_report(buffer)?,
+            )),
             0x0400 => Ok(BlueNRGEvent::GapLimitedDiscoverableTimeout),
             0x0401 => Ok(BlueNRGEvent::GapPairingComplete(to_gap_pairing_complete(
                 buffer,
@@ -621,16 +1020,23 @@ impl hci::event::VendorEvent for BlueNRGEvent {
             0x0408 => {
                 #[cfg(feature = "ms")]
                 {
-                    Ok(BlueNRGEvent::GapAddressNotResolved(to_conn_handle(buffer)?))
+                    if crate::is_ms() {
+                        return Ok(BlueNRGEvent::GapAddressNotResolved(to_conn_handle(buffer)?));
+                    }
                 }
 
-                #[cfg(not(feature = "ms"))]
-                {
-                    Ok(BlueNRGEvent::GapReconnectionAddress(
-                        to_gap_reconnection_address(buffer)?,
-                    ))
-                }
+                Ok(BlueNRGEvent::GapReconnectionAddress(
+                    to_gap_reconnection_address(buffer)?,
+                ))
             }
+            #[cfg(feature = "bluenrg-1-2")]
+            0x0409 => Ok(BlueNRGEvent::GapNumericComparisonValue(
+                to_gap_numeric_comparison_value(buffer)?,
+            )),
+            #[cfg(feature = "bluenrg-1-2")]
+            0x040A => Ok(BlueNRGEvent::GapKeypressNotification(
+                to_gap_keypress_notification(buffer)?,
+            )),
             0x0800 => Ok(BlueNRGEvent::L2CapConnectionUpdateResponse(
                 to_l2cap_connection_update_response(buffer)?,
             )),
@@ -704,9 +1110,7 @@ impl hci::event::VendorEvent for BlueNRGEvent {
 
                 #[cfg(not(feature = "ms"))]
                 {
-                    Err(hci::event::Error::Vendor(BlueNRGError::UnknownEvent(
-                        event_code,
-                    )))
+                    to_unknown_event(event_code, buffer)
                 }
             }
             0x0C17 => {
@@ -719,9 +1123,7 @@ impl hci::event::VendorEvent for BlueNRGEvent {
 
                 #[cfg(not(feature = "ms"))]
                 {
-                    Err(hci::event::Error::Vendor(BlueNRGError::UnknownEvent(
-                        event_code,
-                    )))
+                    to_unknown_event(event_code, buffer)
                 }
             }
             0x0C18 => {
@@ -734,25 +1136,197 @@ impl hci::event::VendorEvent for BlueNRGEvent {
 
                 #[cfg(not(feature = "ms"))]
                 {
-                    Err(hci::event::Error::Vendor(BlueNRGError::UnknownEvent(
-                        event_code,
-                    )))
+                    to_unknown_event(event_code, buffer)
                 }
             }
-            _ => Err(hci::event::Error::Vendor(BlueNRGError::UnknownEvent(
-                event_code,
-            ))),
+            _ => to_unknown_event(event_code, buffer),
         }
     }
 }
 
+impl BlueNRGEvent {
+    /// Serializes the event into the given buffer in the same format that
+    /// [`new`](hci::event::VendorEvent::new) parses: the 2-byte event code followed by the event
+    /// parameters. Returns the number of bytes written.
+    ///
+    /// Parsing the written bytes returns an identical event, so this can be used to build test
+    /// doubles or emulate a controller.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BufferTooSmall`](crate::EncodeError::BufferTooSmall) if the buffer cannot hold the
+    /// event. An event is never longer than 255 bytes.
+    pub fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, crate::EncodeError> {
+        // Serialize into a buffer that fits any event, then copy out only if the caller's buffer
+        // has room.
+        let mut buffer = [0; 255];
+        let len = self.copy_into_event_buffer(&mut buffer);
+        crate::command::check_buffer_len(bytes, len)?;
+        bytes[..len].copy_from_slice(&buffer[..len]);
+
+        Ok(len)
+    }
+
+    fn copy_into_event_buffer(&self, bytes: &mut [u8; 255]) -> usize {
+        let params = &mut bytes[2..];
+        let (code, len) = match *self {
+            BlueNRGEvent::HalInitialized(reason) => {
+                params[0] = reason.into();
+                (0x0001, 1)
+            }
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::EventsLost(flags) => {
+                LittleEndian::write_u64(&mut params[0..8], flags.bits());
+                (0x0002, 8)
+            }
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::CrashReport(ref fault_data) => {
+                (0x0003, fault_data.copy_into_slice(params))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            BlueNRGEvent::HalEndOfRadioActivity(ref event) => {
+                (0x0004, event.copy_into_slice(params))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            BlueNRGEvent::HalScanRequestReport(ref event) => {
+                (0x0005, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::GapLimitedDiscoverableTimeout => (0x0400, 0),
+            BlueNRGEvent::GapPairingComplete(ref event) => (0x0401, event.copy_into_slice(params)),
+            BlueNRGEvent::GapPassKeyRequest(conn_handle) => {
+                (0x0402, write_conn_handle(params, conn_handle))
+            }
+            BlueNRGEvent::GapAuthorizationRequest(conn_handle) => {
+                (0x0403, write_conn_handle(params, conn_handle))
+            }
+            BlueNRGEvent::GapPeripheralSecurityInitiated => (0x0404, 0),
+            BlueNRGEvent::GapBondLost => (0x0405, 0),
+            BlueNRGEvent::GapDeviceFound(ref event) => (0x0406, event.copy_into_slice(params)),
+            BlueNRGEvent::GapProcedureComplete(ref event) => {
+                (0x0407, event.copy_into_slice(params))
+            }
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::GapAddressNotResolved(conn_handle) => {
+                (0x0408, write_conn_handle(params, conn_handle))
+            }
+            BlueNRGEvent::GapReconnectionAddress(addr) => {
+                params[0..6].copy_from_slice(&addr.0);
+                (0x0408, 6)
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            BlueNRGEvent::GapNumericComparisonValue(ref event) => {
+                (0x0409, event.copy_into_slice(params))
+            }
+            #[cfg(feature = "bluenrg-1-2")]
+            BlueNRGEvent::GapKeypressNotification(ref event) => {
+                (0x040A, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::L2CapConnectionUpdateResponse(ref event) => {
+                (0x0800, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::L2CapProcedureTimeout(conn_handle) => {
+                write_conn_handle(params, conn_handle);
+                params[2] = 0; // L2CAP event data length
+                (0x0801, 3)
+            }
+            BlueNRGEvent::L2CapConnectionUpdateRequest(ref event) => {
+                (0x0802, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::GattAttributeModified(ref event) => {
+                (0x0C01, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::GattProcedureTimeout(conn_handle) => {
+                (0x0C02, write_conn_handle(params, conn_handle))
+            }
+            BlueNRGEvent::AttExchangeMtuResponse(ref event) => {
+                (0x0C03, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::AttFindInformationResponse(ref event) => {
+                (0x0C04, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::AttFindByTypeValueResponse(ref event) => {
+                (0x0C05, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::AttReadByTypeResponse(ref event) => {
+                (0x0C06, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::AttReadResponse(ref event) => (0x0C07, event.copy_into_slice(params)),
+            BlueNRGEvent::AttReadBlobResponse(ref event) => (0x0C08, event.copy_into_slice(params)),
+            BlueNRGEvent::AttReadMultipleResponse(ref event) => {
+                (0x0C09, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::AttReadByGroupTypeResponse(ref event) => {
+                (0x0C0A, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::AttPrepareWriteResponse(ref event) => {
+                (0x0C0C, event.copy_into_slice(params))
+            }
+            #[cfg(not(feature = "bluenrg-1-2"))]
+            BlueNRGEvent::AttExecuteWriteResponse(conn_handle) => {
+                write_conn_handle(params, conn_handle);
+                params[2] = 0; // event data length
+                (0x0C0D, 3)
+            }
+            // The BlueNRG-1 and BlueNRG-2 do not send the event data length.
+            #[cfg(feature = "bluenrg-1-2")]
+            BlueNRGEvent::AttExecuteWriteResponse(conn_handle) => {
+                (0x0C0D, write_conn_handle(params, conn_handle))
+            }
+            BlueNRGEvent::GattIndication(ref event) => (0x0C0E, event.copy_into_slice(params)),
+            BlueNRGEvent::GattNotification(ref event) => (0x0C0F, event.copy_into_slice(params)),
+            BlueNRGEvent::GattProcedureComplete(ref event) => {
+                (0x0C10, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::AttErrorResponse(ref event) => (0x0C11, event.copy_into_slice(params)),
+            BlueNRGEvent::GattDiscoverOrReadCharacteristicByUuidResponse(ref event) => {
+                (0x0C12, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::AttWritePermitRequest(ref event) => {
+                (0x0C13, event.copy_write_permit_request_into_slice(params))
+            }
+            BlueNRGEvent::AttReadPermitRequest(ref event) => {
+                (0x0C14, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::AttReadMultiplePermitRequest(ref event) => {
+                (0x0C15, event.copy_into_slice(params))
+            }
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::GattTxPoolAvailable(ref event) => (0x0C16, event.copy_into_slice(params)),
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::GattServerConfirmation(conn_handle) => {
+                (0x0C17, write_conn_handle(params, conn_handle))
+            }
+            #[cfg(feature = "ms")]
+            BlueNRGEvent::AttPrepareWritePermitRequest(ref event) => {
+                (0x0C18, event.copy_into_slice(params))
+            }
+            BlueNRGEvent::Unknown { code, ref data } => {
+                let data = data.as_slice();
+                params[..data.len()].copy_from_slice(data);
+                (code, data.len())
+            }
+        };
+        LittleEndian::write_u16(&mut bytes[0..2], code);
+
+        2 + len
+    }
+}
+
+fn write_conn_handle(bytes: &mut [u8], conn_handle: ConnectionHandle) -> usize {
+    LittleEndian::write_u16(&mut bytes[0..2], conn_handle.0);
+    2
+}
+
 /// Potential reasons the controller sent the [`HalInitialized`](BlueNRGEvent::HalInitialized)
 /// event.
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ResetReason {
     /// Firmware started properly
     Normal,
-    /// Updater mode entered because of updater_start command
+    /// Updater mode entered because of the [`updater_start`](crate::updater::Commands::updater_start)
+    /// command
     Updater,
     /// Updater mode entered because of a bad BLUE flag
     UpdaterBadFlag,
@@ -789,6 +1363,22 @@ impl TryFrom<u8> for ResetReason {
     }
 }
 
+impl From<ResetReason> for u8 {
+    fn from(reason: ResetReason) -> u8 {
+        match reason {
+            ResetReason::Normal => 1,
+            ResetReason::Updater => 2,
+            ResetReason::UpdaterBadFlag => 3,
+            ResetReason::UpdaterPin => 4,
+            ResetReason::Watchdog => 5,
+            ResetReason::Lockup => 6,
+            ResetReason::Brownout => 7,
+            ResetReason::Crash => 8,
+            ResetReason::EccError => 9,
+        }
+    }
+}
+
 /// Convert a buffer to the `HalInitialized` `BlueNRGEvent`.
 ///
 /// # Errors
@@ -807,6 +1397,7 @@ bitflags! {
     /// Bitfield for the [Events Lost](BlueNRGEvent::EventsLost) event. Each bit indicates a
     /// different type of event that was not handled.
     #[derive(Default)]
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
     pub struct EventFlags: u64 {
         /// HCI Event: [Disconnection complete](hci::event::Event::DisconnectionComplete).
         const DISCONNECTION_COMPLETE = 1 << 0;
@@ -923,6 +1514,9 @@ bitflags! {
     }
 }
 
+#[cfg(all(feature = "ms", feature = "serde"))]
+impl_serde_bitflags!(EventFlags, u64);
+
 /// Convert a buffer to the `EventsLost` `BlueNRGEvent`.
 ///
 /// # Errors
@@ -942,11 +1536,13 @@ fn to_lost_event(buffer: &[u8]) -> Result<EventFlags, hci::event::Error<BlueNRGE
 // The maximum length of [`FaultData::debug_data`]. The maximum length of an event is 255 bytes,
 // and the non-variable data of the event takes up 40 bytes.
 #[cfg(feature = "ms")]
-const MAX_DEBUG_DATA_LEN: usize = 215;
+const MAX_DEBUG_DATA_LEN: usize = payload_limit(215);
 
 /// Specific reason for the fault reported with [`FaultData`].
 #[cfg(feature = "ms")]
 #[derive(Clone, Copy, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum CrashReason {
     /// The controller reset because an assertion failed.
     Assertion,
@@ -976,6 +1572,19 @@ impl TryFrom<u8> for CrashReason {
     }
 }
 
+#[cfg(feature = "ms")]
+impl From<CrashReason> for u8 {
+    fn from(reason: CrashReason) -> u8 {
+        // Follows the CubeExpansion source code, which is also the first value accepted when
+        // parsing.
+        match reason {
+            CrashReason::Assertion => 0,
+            CrashReason::NmiFault => 1,
+            CrashReason::HardFault => 2,
+        }
+    }
+}
+
 /// Fault data reported after a crash.
 #[cfg(feature = "ms")]
 #[derive(Clone, Copy)]
@@ -1029,6 +1638,53 @@ impl Debug for FaultData {
     }
 }
 
+#[cfg(all(feature = "ms", feature = "defmt"))]
+impl defmt::Format for FaultData {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "FaultData {{ reason: {}, sp: {=u32:#x}, r0: {=u32:#x}, r1: {=u32:#x}, r2: {=u32:#x}, \
+             r3: {=u32:#x}, r12: {=u32:#x}, lr: {=u32:#x}, pc: {=u32:#x}, xpsr: {=u32:#x}, \
+             debug_data: {=[u8]:#x} }}",
+            self.reason,
+            self.sp,
+            self.r0,
+            self.r1,
+            self.r2,
+            self.r3,
+            self.r12,
+            self.lr,
+            self.pc,
+            self.xpsr,
+            self.debug_data()
+        )
+    }
+}
+
+#[cfg(all(feature = "ms", feature = "serde"))]
+impl serde::Serialize for FaultData {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("FaultData", 11)?;
+        state.serialize_field("reason", &self.reason)?;
+        state.serialize_field("sp", &self.sp)?;
+        state.serialize_field("r0", &self.r0)?;
+        state.serialize_field("r1", &self.r1)?;
+        state.serialize_field("r2", &self.r2)?;
+        state.serialize_field("r3", &self.r3)?;
+        state.serialize_field("r12", &self.r12)?;
+        state.serialize_field("lr", &self.lr)?;
+        state.serialize_field("pc", &self.pc)?;
+        state.serialize_field("xpsr", &self.xpsr)?;
+        state.serialize_field("debug_data", self.debug_data())?;
+        state.end()
+    }
+}
+
 #[cfg(feature = "ms")]
 impl FaultData {
     /// Returns the valid debug data.
@@ -1043,6 +1699,7 @@ fn to_crash_report(buffer: &[u8]) -> Result<FaultData, hci::event::Error<BlueNRG
 
     let debug_data_len = buffer[39] as usize;
     require_len!(buffer, 40 + debug_data_len);
+    require_payload_fits!(debug_data_len, MAX_DEBUG_DATA_LEN);
 
     let mut fault_data = FaultData {
         reason: buffer[2].try_into().map_err(hci::event::Error::Vendor)?,
@@ -1063,6 +1720,162 @@ fn to_crash_report(buffer: &[u8]) -> Result<FaultData, hci::event::Error<BlueNRG
     Ok(fault_data)
 }
 
+#[cfg(feature = "ms")]
+impl FaultData {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        bytes[0] = self.reason.into();
+        let registers = [
+            self.sp, self.r0, self.r1, self.r2, self.r3, self.r12, self.lr, self.pc, self.xpsr,
+        ];
+        for (i, register) in registers.iter().enumerate() {
+            LittleEndian::write_u32(&mut bytes[1 + 4 * i..5 + 4 * i], *register);
+        }
+        bytes[37] = self.debug_data_len as u8;
+        bytes[38..38 + self.debug_data_len].copy_from_slice(self.debug_data());
+
+        38 + self.debug_data_len
+    }
+}
+
+/// Radio states reported in the [HAL End of Radio
+/// Activity](BlueNRGEvent::HalEndOfRadioActivity) event.
+#[cfg(feature = "bluenrg-1-2")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RadioState {
+    /// The radio is idle.
+    Idle,
+    /// Advertising.
+    Advertising,
+    /// Connection event in the peripheral role.
+    PeripheralConnection,
+    /// Scanning.
+    Scanning,
+    /// Connection request.
+    ConnectionRequest,
+    /// Connection event in the central role.
+    CentralConnection,
+    /// Transmitter test mode.
+    TxTest,
+    /// Receiver test mode.
+    RxTest,
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+impl TryFrom<u8> for RadioState {
+    type Error = BlueNRGError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(RadioState::Idle),
+            1 => Ok(RadioState::Advertising),
+            2 => Ok(RadioState::PeripheralConnection),
+            3 => Ok(RadioState::Scanning),
+            4 => Ok(RadioState::ConnectionRequest),
+            5 => Ok(RadioState::CentralConnection),
+            6 => Ok(RadioState::TxTest),
+            7 => Ok(RadioState::RxTest),
+            _ => Err(BlueNRGError::BadRadioState(value)),
+        }
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+impl From<RadioState> for u8 {
+    fn from(state: RadioState) -> u8 {
+        match state {
+            RadioState::Idle => 0,
+            RadioState::Advertising => 1,
+            RadioState::PeripheralConnection => 2,
+            RadioState::Scanning => 3,
+            RadioState::ConnectionRequest => 4,
+            RadioState::CentralConnection => 5,
+            RadioState::TxTest => 6,
+            RadioState::RxTest => 7,
+        }
+    }
+}
+
+/// Parameters of the [HAL End of Radio Activity](BlueNRGEvent::HalEndOfRadioActivity) event.
+#[cfg(feature = "bluenrg-1-2")]
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HalEndOfRadioActivity {
+    /// The radio state that ended.
+    pub last_state: RadioState,
+
+    /// The next radio state.
+    pub next_state: RadioState,
+
+    /// System time, in units of 625/256 us (about 2.44 us), when the next radio state starts.
+    pub next_state_sys_time: u32,
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+fn to_hal_end_of_radio_activity(
+    buffer: &[u8],
+) -> Result<HalEndOfRadioActivity, hci::event::Error<BlueNRGError>> {
+    require_len!(buffer, 8);
+    Ok(HalEndOfRadioActivity {
+        last_state: buffer[2].try_into().map_err(hci::event::Error::Vendor)?,
+        next_state: buffer[3].try_into().map_err(hci::event::Error::Vendor)?,
+        next_state_sys_time: LittleEndian::read_u32(&buffer[4..]),
+    })
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+impl HalEndOfRadioActivity {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        bytes[0] = self.last_state.into();
+        bytes[1] = self.next_state.into();
+        LittleEndian::write_u32(&mut bytes[2..6], self.next_state_sys_time);
+
+        6
+    }
+}
+
+/// Parameters of the [HAL Scan Request Report](BlueNRGEvent::HalScanRequestReport) event.
+#[cfg(feature = "bluenrg-1-2")]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct HalScanRequestReport {
+    /// Received signal strength of the scan request, in dBm.
+    pub rssi: i8,
+
+    /// Address of the device that sent the scan request.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::BdAddrTypeDef"))]
+    pub peer_addr: BdAddrType,
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+fn to_hal_scan_request_report(
+    buffer: &[u8],
+) -> Result<HalScanRequestReport, hci::event::Error<BlueNRGError>> {
+    require_len!(buffer, 10);
+
+    let mut addr = BdAddr([0; 6]);
+    addr.0.copy_from_slice(&buffer[4..10]);
+    Ok(HalScanRequestReport {
+        rssi: buffer[2] as i8,
+        peer_addr: hci::to_bd_addr_type(buffer[3], addr)
+            .map_err(|e| hci::event::Error::Vendor(BlueNRGError::BadScanRequestAddrType(e.0)))?,
+    })
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+impl HalScanRequestReport {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        bytes[0] = self.rssi as u8;
+        self.peer_addr.copy_into_slice(&mut bytes[1..8]);
+
+        8
+    }
+}
+
 macro_rules! require_l2cap_event_data_len {
     ($left:expr, $right:expr) => {
         let actual = $left[4];
@@ -1090,17 +1903,51 @@ macro_rules! require_l2cap_len {
 /// For more info see connection parameter update response and command reject in Bluetooth Core v4.0
 /// spec.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct L2CapConnectionUpdateResponse {
     /// The connection handle related to the event
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
 
+    /// The identifier of the connection parameter update request this response is for.
+    ///
+    /// The BlueNRG-1 and BlueNRG-2 do not report the identifier.
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    pub identifier: u8,
+
     /// The result of the update request, including details about the result.
     pub result: L2CapConnectionUpdateResult,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for L2CapConnectionUpdateResponse {
+    fn format(&self, f: defmt::Formatter) {
+        #[cfg(not(feature = "bluenrg-1-2"))]
+        defmt::write!(
+            f,
+            "L2CapConnectionUpdateResponse {{ conn_handle: {}, identifier: {}, result: ",
+            Remote(&self.conn_handle),
+            self.identifier
+        );
+        defmt::write!(f, "{} }}", self.result);
+        #[cfg(feature = "bluenrg-1-2")]
+        defmt::write!(
+            f,
+            "L2CapConnectionUpdateResponse {{ conn_handle: {}, result: {} }}",
+            Remote(&self.conn_handle),
+            self.result
+        );
+    }
+}
+
 /// Reasons why an L2CAP command was rejected. see the Bluetooth specification, v4.1, Vol 3, Part A,
 /// Section 4.1.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum L2CapRejectionReason {
     /// The controller sent an unknown command.
     CommandNotUnderstood,
@@ -1124,8 +1971,20 @@ impl TryFrom<u16> for L2CapRejectionReason {
     }
 }
 
+impl From<L2CapRejectionReason> for u16 {
+    fn from(reason: L2CapRejectionReason) -> u16 {
+        match reason {
+            L2CapRejectionReason::CommandNotUnderstood => 0,
+            L2CapRejectionReason::SignalingMtuExceeded => 1,
+            L2CapRejectionReason::InvalidCid => 2,
+        }
+    }
+}
+
 /// Potential results that can be used in the L2CAP connection update response.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum L2CapConnectionUpdateResult {
     /// The update request was rejected. The code indicates the reason for the rejection.
     CommandRejected(L2CapRejectionReason),
@@ -1149,6 +2008,7 @@ fn to_l2cap_connection_update_accepted_result(
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 fn extract_l2cap_connection_update_response_result(
     buffer: &[u8],
 ) -> Result<L2CapConnectionUpdateResult, BlueNRGError> {
@@ -1161,6 +2021,7 @@ fn extract_l2cap_connection_update_response_result(
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 fn to_l2cap_connection_update_response(
     buffer: &[u8],
 ) -> Result<L2CapConnectionUpdateResponse, hci::event::Error<BlueNRGError>> {
@@ -1170,19 +2031,84 @@ fn to_l2cap_connection_update_response(
 
     Ok(L2CapConnectionUpdateResponse {
         conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
+        identifier: buffer[6],
         result: extract_l2cap_connection_update_response_result(buffer)
             .map_err(hci::event::Error::Vendor)?,
     })
 }
 
+// The BlueNRG-1 and BlueNRG-2 only report the result of the L2CAP response.
+#[cfg(feature = "bluenrg-1-2")]
+fn to_l2cap_connection_update_response(
+    buffer: &[u8],
+) -> Result<L2CapConnectionUpdateResponse, hci::event::Error<BlueNRGError>> {
+    require_len!(buffer, 6);
+
+    Ok(L2CapConnectionUpdateResponse {
+        conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
+        result: to_l2cap_connection_update_accepted_result(LittleEndian::read_u16(&buffer[4..]))
+            .map_err(hci::event::Error::Vendor)?,
+    })
+}
+
+impl L2CapConnectionUpdateResponse {
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        let (code, result) = match self.result {
+            L2CapConnectionUpdateResult::CommandRejected(reason) => (0x01, reason.into()),
+            L2CapConnectionUpdateResult::ParametersUpdated => (0x13, 0x0000),
+            L2CapConnectionUpdateResult::ParametersRejected => (0x13, 0x0001),
+        };
+
+        write_conn_handle(bytes, self.conn_handle);
+        bytes[2] = 6; // L2CAP event data length
+        bytes[3] = code;
+        bytes[4] = self.identifier;
+        LittleEndian::write_u16(&mut bytes[5..7], 2); // L2CAP length
+        LittleEndian::write_u16(&mut bytes[7..9], result);
+
+        9
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        let result = match self.result {
+            L2CapConnectionUpdateResult::CommandRejected(reason) => reason.into(),
+            L2CapConnectionUpdateResult::ParametersUpdated => 0x0000,
+            L2CapConnectionUpdateResult::ParametersRejected => 0x0001,
+        };
+
+        write_conn_handle(bytes, self.conn_handle);
+        LittleEndian::write_u16(&mut bytes[2..4], result);
+
+        4
+    }
+}
+
 /// This event is generated when the central device does not respond to the connection update
 /// request within 30 seconds.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct L2CapProcedureTimeout {
     /// The connection handle related to the event.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for L2CapProcedureTimeout {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "L2CapProcedureTimeout {{ conn_handle: {} }}",
+            Remote(&self.conn_handle)
+        )
+    }
+}
+
 fn to_l2cap_procedure_timeout(
     buffer: &[u8],
 ) -> Result<ConnectionHandle, hci::event::Error<BlueNRGError>> {
@@ -1200,11 +2126,16 @@ fn to_l2cap_procedure_timeout(
 ///
 /// Defined in Vol 3, Part A, section 4.20 of the Bluetooth specification.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct L2CapConnectionUpdateRequest {
     /// Handle of the connection for which the connection update request has been received.  The
     /// [same handle](crate::l2cap::ConnectionParameterUpdateResponse::conn_handle) has to be
     /// returned while responding to the event with the command
     /// [`l2cap_connection_parameter_update_response`](crate::l2cap::Commands::connection_parameter_update_response).
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
 
     /// This is the identifier which associates the request to the response. The [same
@@ -1214,42 +2145,126 @@ pub struct L2CapConnectionUpdateRequest {
     pub identifier: u8,
 
     /// Defines the range of the connection interval, the latency, and the supervision timeout.
+    #[cfg_attr(
+        feature = "serde",
+        serde(serialize_with = "crate::serde_remote::ConnectionIntervalDef::serialize")
+    )]
     pub conn_interval: ConnectionInterval,
 }
 
-fn to_l2cap_connection_update_request(
+#[cfg(feature = "defmt")]
+impl defmt::Format for L2CapConnectionUpdateRequest {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "L2CapConnectionUpdateRequest {{ conn_handle: {}, identifier: ",
+            Remote(&self.conn_handle)
+        );
+        defmt::write!(
+            f,
+            "{}, conn_interval: {} }}",
+            self.identifier,
+            defmt::Debug2Format(&self.conn_interval)
+        )
+    }
+}
+
+fn to_l2cap_connection_update_request(
     buffer: &[u8],
 ) -> Result<L2CapConnectionUpdateRequest, hci::event::Error<BlueNRGError>> {
-    require_len!(buffer, 16);
-    require_l2cap_event_data_len!(buffer, 11);
-    require_l2cap_len!(LittleEndian::read_u16(&buffer[6..]), 8);
+    // The BlueNRG-1 and BlueNRG-2 do not send the L2CAP event data length.
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    let params = {
+        require_len!(buffer, 16);
+        require_l2cap_event_data_len!(buffer, 11);
+        &buffer[5..]
+    };
+    #[cfg(feature = "bluenrg-1-2")]
+    let params = {
+        require_len!(buffer, 15);
+        &buffer[4..]
+    };
+    require_l2cap_len!(LittleEndian::read_u16(&params[1..]), 8);
 
-    let interval = ConnectionInterval::from_bytes(&buffer[8..16])
+    let interval = ConnectionInterval::from_bytes(&params[3..11])
         .map_err(BlueNRGError::BadConnectionInterval)
         .map_err(hci::event::Error::Vendor)?;
 
     Ok(L2CapConnectionUpdateRequest {
         conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
-        identifier: buffer[5],
+        identifier: params[0],
         conn_interval: interval,
     })
 }
 
+impl L2CapConnectionUpdateRequest {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        #[cfg(not(feature = "bluenrg-1-2"))]
+        let (params, len) = {
+            bytes[2] = 11; // L2CAP event data length
+            (&mut bytes[3..], 14)
+        };
+        #[cfg(feature = "bluenrg-1-2")]
+        let (params, len) = (&mut bytes[2..], 13);
+        params[0] = self.identifier;
+        LittleEndian::write_u16(&mut params[1..3], 8); // L2CAP length
+        self.conn_interval.copy_into_slice(&mut params[3..11]);
+
+        len
+    }
+}
+
 /// This event is generated when the pairing process has completed successfully or a pairing
 /// procedure timeout has occurred or the pairing has failed. This is to notify the application that
 /// we have paired with a remote device so that it can take further actions or to notify that a
 /// timeout has occurred so that the upper layer can decide to disconnect the link.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GapPairingComplete {
     /// Connection handle on which the pairing procedure completed
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
 
     /// Reason the pairing is complete.
     pub status: GapPairingStatus,
+
+    /// The SMP pairing failed reason if the pairing [failed](GapPairingStatus::Failed), otherwise
+    /// 0. See the Bluetooth specification, v4.2, Vol 3, Part H, Section 3.5.5.
+    ///
+    /// Only the BlueNRG-1 and BlueNRG-2 report the reason.
+    #[cfg(feature = "bluenrg-1-2")]
+    pub reason: u8,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for GapPairingComplete {
+    fn format(&self, f: defmt::Formatter) {
+        #[cfg(not(feature = "bluenrg-1-2"))]
+        defmt::write!(
+            f,
+            "GapPairingComplete {{ conn_handle: {}, status: {} }}",
+            Remote(&self.conn_handle),
+            self.status
+        );
+        #[cfg(feature = "bluenrg-1-2")]
+        defmt::write!(
+            f,
+            "GapPairingComplete {{ conn_handle: {}, status: {}, reason: {} }}",
+            Remote(&self.conn_handle),
+            self.status,
+            self.reason
+        );
+    }
 }
 
 /// Reasons the [GAP Pairing Complete](BlueNRGEvent::GapPairingComplete) event was generated.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GapPairingStatus {
     /// Pairing with a remote device was successful.
     Success,
@@ -1273,16 +2288,50 @@ impl TryFrom<u8> for GapPairingStatus {
     }
 }
 
+impl From<GapPairingStatus> for u8 {
+    fn from(status: GapPairingStatus) -> u8 {
+        match status {
+            GapPairingStatus::Success => 0,
+            GapPairingStatus::Timeout => 1,
+            GapPairingStatus::Failed => 2,
+        }
+    }
+}
+
 fn to_gap_pairing_complete(
     buffer: &[u8],
 ) -> Result<GapPairingComplete, hci::event::Error<BlueNRGError>> {
+    #[cfg(not(feature = "bluenrg-1-2"))]
     require_len!(buffer, 5);
+    #[cfg(feature = "bluenrg-1-2")]
+    require_len!(buffer, 6);
     Ok(GapPairingComplete {
         conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
         status: buffer[4].try_into().map_err(hci::event::Error::Vendor)?,
+        #[cfg(feature = "bluenrg-1-2")]
+        reason: buffer[5],
     })
 }
 
+impl GapPairingComplete {
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        bytes[2] = self.status.into();
+
+        3
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        bytes[2] = self.status.into();
+        bytes[3] = self.reason;
+
+        4
+    }
+}
+
 fn to_conn_handle(buffer: &[u8]) -> Result<ConnectionHandle, hci::event::Error<BlueNRGError>> {
     require_len_at_least!(buffer, 4);
     Ok(ConnectionHandle(LittleEndian::read_u16(&buffer[2..])))
@@ -1291,11 +2340,20 @@ fn to_conn_handle(buffer: &[u8]) -> Result<ConnectionHandle, hci::event::Error<B
 /// The event is given by the GAP layer to the upper layers when a device is discovered during
 /// scanning as a consequence of one of the GAP procedures started by the upper layers.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GapDeviceFound {
     /// Type of event
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::advertisement_event")
+    )]
     pub event: GapDeviceFoundEvent,
 
     /// Address of the peer device found during scanning
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::BdAddrTypeDef"))]
     pub bdaddr: BdAddrType,
 
     // Length of significant data
@@ -1317,9 +2375,10 @@ impl GapDeviceFound {
 
 pub use hci::event::AdvertisementEvent as GapDeviceFoundEvent;
 
-fn to_gap_device_found(buffer: &[u8]) -> Result<GapDeviceFound, hci::event::Error<BlueNRGError>> {
-    const RSSI_UNAVAILABLE: i8 = 127;
+// Value of the RSSI byte in the GAP Device Found event when the RSSI is not available.
+const RSSI_UNAVAILABLE: i8 = 127;
 
+fn to_gap_device_found(buffer: &[u8]) -> Result<GapDeviceFound, hci::event::Error<BlueNRGError>> {
     require_len_at_least!(buffer, 12);
 
     let data_len = buffer[10] as usize;
@@ -1352,9 +2411,29 @@ fn to_gap_device_found(buffer: &[u8]) -> Result<GapDeviceFound, hci::event::Erro
     Ok(event)
 }
 
+impl GapDeviceFound {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        bytes[0] = match self.event {
+            GapDeviceFoundEvent::Advertisement => 0,
+            GapDeviceFoundEvent::DirectAdvertisement => 1,
+            GapDeviceFoundEvent::Scan => 2,
+            GapDeviceFoundEvent::NonConnectableAdvertisement => 3,
+            GapDeviceFoundEvent::ScanResponse => 4,
+        };
+        self.bdaddr.copy_into_slice(&mut bytes[1..8]);
+        bytes[8] = self.data_len as u8;
+        bytes[9..9 + self.data_len].copy_from_slice(self.data());
+        bytes[9 + self.data_len] = self.rssi.unwrap_or(RSSI_UNAVAILABLE) as u8;
+
+        10 + self.data_len
+    }
+}
+
 /// This event is sent by the GAP to the upper layers when a procedure previously started has been
 /// terminated by the upper layer or has completed for any other reason
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct GapProcedureComplete {
     /// Type of procedure that completed
     pub procedure: GapProcedure,
@@ -1364,7 +2443,7 @@ pub struct GapProcedureComplete {
 
 /// Maximum length of the name returned in the [`NameDiscovery`](GapProcedure::NameDiscovery)
 /// procedure.
-pub const MAX_NAME_LEN: usize = 248;
+pub const MAX_NAME_LEN: usize = payload_limit(248);
 
 /// Newtype for the name buffer returned after successful
 /// [`NameDiscovery`](GapProcedure::NameDiscovery).
@@ -1377,6 +2456,13 @@ impl Debug for NameBuffer {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for NameBuffer {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=[u8]:#x}", &self.0[..])
+    }
+}
+
 impl PartialEq<NameBuffer> for NameBuffer {
     fn eq(&self, other: &Self) -> bool {
         if self.0.len() != other.0.len() {
@@ -1415,8 +2501,67 @@ pub enum GapProcedure {
     DirectConnectionEstablishment,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GapProcedure {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            GapProcedure::LimitedDiscovery => defmt::write!(f, "LimitedDiscovery"),
+            GapProcedure::GeneralDiscovery => defmt::write!(f, "GeneralDiscovery"),
+            GapProcedure::NameDiscovery(len, name) => {
+                defmt::write!(f, "NameDiscovery({=[u8]:#x})", &name.0[..*len])
+            }
+            GapProcedure::AutoConnectionEstablishment => {
+                defmt::write!(f, "AutoConnectionEstablishment")
+            }
+            GapProcedure::GeneralConnectionEstablishment(addr) => {
+                defmt::write!(f, "GeneralConnectionEstablishment({=[u8]:#x})", &addr.0[..])
+            }
+            GapProcedure::SelectiveConnectionEstablishment => {
+                defmt::write!(f, "SelectiveConnectionEstablishment")
+            }
+            GapProcedure::DirectConnectionEstablishment => {
+                defmt::write!(f, "DirectConnectionEstablishment")
+            }
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for GapProcedure {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        const NAME: &str = "GapProcedure";
+        match self {
+            GapProcedure::LimitedDiscovery => {
+                serializer.serialize_unit_variant(NAME, 0, "LimitedDiscovery")
+            }
+            GapProcedure::GeneralDiscovery => {
+                serializer.serialize_unit_variant(NAME, 1, "GeneralDiscovery")
+            }
+            GapProcedure::NameDiscovery(len, name) => {
+                serializer.serialize_newtype_variant(NAME, 2, "NameDiscovery", &name.0[..*len])
+            }
+            GapProcedure::AutoConnectionEstablishment => {
+                serializer.serialize_unit_variant(NAME, 3, "AutoConnectionEstablishment")
+            }
+            GapProcedure::GeneralConnectionEstablishment(addr) => serializer
+                .serialize_newtype_variant(NAME, 4, "GeneralConnectionEstablishment", &addr.0),
+            GapProcedure::SelectiveConnectionEstablishment => {
+                serializer.serialize_unit_variant(NAME, 5, "SelectiveConnectionEstablishment")
+            }
+            GapProcedure::DirectConnectionEstablishment => {
+                serializer.serialize_unit_variant(NAME, 6, "DirectConnectionEstablishment")
+            }
+        }
+    }
+}
+
 /// Possible results of a [GAP procedure](BlueNRGEvent::GapProcedureComplete).
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GapProcedureStatus {
     /// BLE Status Success.
     Success,
@@ -1439,6 +2584,16 @@ impl TryFrom<u8> for GapProcedureStatus {
     }
 }
 
+impl From<GapProcedureStatus> for u8 {
+    fn from(status: GapProcedureStatus) -> u8 {
+        match status {
+            GapProcedureStatus::Success => 0x00,
+            GapProcedureStatus::Failed => 0x41,
+            GapProcedureStatus::AuthFailure => 0x05,
+        }
+    }
+}
+
 fn to_gap_procedure_complete(
     buffer: &[u8],
 ) -> Result<GapProcedureComplete, hci::event::Error<BlueNRGError>> {
@@ -1450,6 +2605,7 @@ fn to_gap_procedure_complete(
         0x04 => {
             require_len_at_least!(buffer, 5);
             let name_len = buffer.len() - 4;
+            require_payload_fits!(name_len, MAX_NAME_LEN);
             let mut name = NameBuffer([0; MAX_NAME_LEN]);
             name.0[..name_len].copy_from_slice(&buffer[4..]);
 
@@ -1477,7 +2633,30 @@ fn to_gap_procedure_complete(
     })
 }
 
-#[cfg(not(feature = "ms"))]
+impl GapProcedureComplete {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        let (procedure, len) = match self.procedure {
+            GapProcedure::LimitedDiscovery => (0x01, 0),
+            GapProcedure::GeneralDiscovery => (0x02, 0),
+            GapProcedure::NameDiscovery(name_len, ref name) => {
+                bytes[2..2 + name_len].copy_from_slice(&name.0[..name_len]);
+                (0x04, name_len)
+            }
+            GapProcedure::AutoConnectionEstablishment => (0x08, 0),
+            GapProcedure::GeneralConnectionEstablishment(addr) => {
+                bytes[2..8].copy_from_slice(&addr.0);
+                (0x10, 6)
+            }
+            GapProcedure::SelectiveConnectionEstablishment => (0x20, 0),
+            GapProcedure::DirectConnectionEstablishment => (0x40, 0),
+        };
+        bytes[0] = procedure;
+        bytes[1] = self.status.into();
+
+        2 + len
+    }
+}
+
 fn to_gap_reconnection_address(buffer: &[u8]) -> Result<BdAddr, hci::event::Error<BlueNRGError>> {
     require_len!(buffer, 8);
     let mut addr = BdAddr([0; 6]);
@@ -1485,6 +2664,118 @@ fn to_gap_reconnection_address(buffer: &[u8]) -> Result<BdAddr, hci::event::Erro
     Ok(addr)
 }
 
+/// Parameters of the [GAP Numeric Comparison Value](BlueNRGEvent::GapNumericComparisonValue)
+/// event.
+#[cfg(feature = "bluenrg-1-2")]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GapNumericComparisonValue {
+    /// Connection handle on which pairing is in progress.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
+    pub conn_handle: ConnectionHandle,
+
+    /// The value to show to the user, from 0 to 999999.
+    pub numeric_value: u32,
+}
+
+#[cfg(all(feature = "bluenrg-1-2", feature = "defmt"))]
+impl defmt::Format for GapNumericComparisonValue {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GapNumericComparisonValue {{ conn_handle: {}, numeric_value: {} }}",
+            Remote(&self.conn_handle),
+            self.numeric_value
+        )
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+fn to_gap_numeric_comparison_value(
+    buffer: &[u8],
+) -> Result<GapNumericComparisonValue, hci::event::Error<BlueNRGError>> {
+    require_len!(buffer, 8);
+    Ok(GapNumericComparisonValue {
+        conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
+        numeric_value: LittleEndian::read_u32(&buffer[4..]),
+    })
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+impl GapNumericComparisonValue {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        LittleEndian::write_u32(&mut bytes[2..6], self.numeric_value);
+
+        6
+    }
+}
+
+/// Parameters of the [GAP Keypress Notification](BlueNRGEvent::GapKeypressNotification) event.
+#[cfg(feature = "bluenrg-1-2")]
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GapKeypressNotification {
+    /// Connection handle on which pairing is in progress.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
+    pub conn_handle: ConnectionHandle,
+
+    /// Progress of pass key entry on the peer.
+    pub notification: crate::gap::KeypressNotification,
+}
+
+#[cfg(all(feature = "bluenrg-1-2", feature = "defmt"))]
+impl defmt::Format for GapKeypressNotification {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GapKeypressNotification {{ conn_handle: {}, notification: {} }}",
+            Remote(&self.conn_handle),
+            self.notification
+        )
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+fn to_gap_keypress_notification(
+    buffer: &[u8],
+) -> Result<GapKeypressNotification, hci::event::Error<BlueNRGError>> {
+    use crate::gap::KeypressNotification;
+
+    require_len!(buffer, 5);
+    Ok(GapKeypressNotification {
+        conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
+        notification: match buffer[4] {
+            0x00 => KeypressNotification::EntryStarted,
+            0x01 => KeypressNotification::DigitEntered,
+            0x02 => KeypressNotification::DigitErased,
+            0x03 => KeypressNotification::Cleared,
+            0x04 => KeypressNotification::EntryCompleted,
+            other => {
+                return Err(hci::event::Error::Vendor(
+                    BlueNRGError::BadKeypressNotification(other),
+                ))
+            }
+        },
+    })
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+impl GapKeypressNotification {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        bytes[2] = self.notification as u8;
+
+        3
+    }
+}
+
 /// This event is generated to the application by the ATT server when a client modifies any
 /// attribute on the server, as consequence of one of the following ATT procedures:
 /// - write without response
@@ -1527,11 +2818,13 @@ impl GattAttributeModified {
 /// Newtype for an attribute handle. These handles are IDs, not general integers, and should not be
 /// manipulated as such.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttributeHandle(pub u16);
 
 // Defines the maximum length of a ATT attribute value field. This is determined by the max packet
 // size (255) less the minimum number of bytes used by other fields in any packet.
-const MAX_ATTRIBUTE_LEN: usize = 248;
+const MAX_ATTRIBUTE_LEN: usize = payload_limit(248);
 
 impl Debug for GattAttributeModified {
     #[cfg(feature = "ms")]
@@ -1559,7 +2852,68 @@ impl Debug for GattAttributeModified {
     }
 }
 
-#[cfg(feature = "ms")]
+#[cfg(feature = "defmt")]
+impl defmt::Format for GattAttributeModified {
+    #[cfg(feature = "ms")]
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{conn_handle: {=u16:#x}, attr_handle: {}, offset: {=usize}, continued: {=bool}, \
+             data: {=[u8]:#x}}}",
+            self.conn_handle.0,
+            self.attr_handle,
+            self.offset,
+            self.continued,
+            self.data()
+        )
+    }
+
+    #[cfg(not(feature = "ms"))]
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{conn_handle: {=u16:#x}, attr_handle: {}, data: {=[u8]:#x}}}",
+            self.conn_handle.0,
+            self.attr_handle,
+            self.data()
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for GattAttributeModified {
+    #[cfg(feature = "ms")]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("GattAttributeModified", 5)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field("attr_handle", &self.attr_handle)?;
+        state.serialize_field("offset", &self.offset)?;
+        state.serialize_field("continued", &self.continued)?;
+        state.serialize_field("data", self.data())?;
+        state.end()
+    }
+
+    #[cfg(not(feature = "ms"))]
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("GattAttributeModified", 3)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field("attr_handle", &self.attr_handle)?;
+        state.serialize_field("data", self.data())?;
+        state.end()
+    }
+}
+
+#[cfg(all(feature = "ms", not(feature = "bluenrg-1-2")))]
 fn to_gatt_attribute_modified(
     buffer: &[u8],
 ) -> Result<GattAttributeModified, hci::event::Error<BlueNRGError>> {
@@ -1567,6 +2921,7 @@ fn to_gatt_attribute_modified(
 
     let data_len = buffer[6] as usize;
     require_len!(buffer, 9 + data_len);
+    require_payload_fits!(data_len, MAX_ATTRIBUTE_LEN);
 
     let mut data = [0; MAX_ATTRIBUTE_LEN];
     data[..data_len].copy_from_slice(&buffer[9..]);
@@ -1582,6 +2937,31 @@ fn to_gatt_attribute_modified(
     })
 }
 
+// The BlueNRG-1 and BlueNRG-2 report the offset before a 16-bit data length.
+#[cfg(feature = "bluenrg-1-2")]
+fn to_gatt_attribute_modified(
+    buffer: &[u8],
+) -> Result<GattAttributeModified, hci::event::Error<BlueNRGError>> {
+    require_len_at_least!(buffer, 10);
+
+    let data_len = LittleEndian::read_u16(&buffer[8..]) as usize;
+    require_len!(buffer, 10 + data_len);
+    require_payload_fits!(data_len, MAX_ATTRIBUTE_LEN);
+
+    let mut data = [0; MAX_ATTRIBUTE_LEN];
+    data[..data_len].copy_from_slice(&buffer[10..]);
+
+    let offset_field = LittleEndian::read_u16(&buffer[6..]);
+    Ok(GattAttributeModified {
+        conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
+        attr_handle: AttributeHandle(LittleEndian::read_u16(&buffer[4..])),
+        offset: (offset_field & 0x7FFF) as usize,
+        continued: (offset_field & 0x8000) > 0,
+        data_len,
+        data_buf: data,
+    })
+}
+
 #[cfg(not(feature = "ms"))]
 fn to_gatt_attribute_modified(
     buffer: &[u8],
@@ -1590,6 +2970,7 @@ fn to_gatt_attribute_modified(
 
     let data_len = buffer[6] as usize;
     require_len!(buffer, 7 + data_len);
+    require_payload_fits!(data_len, MAX_ATTRIBUTE_LEN);
 
     let mut data = [0; MAX_ATTRIBUTE_LEN];
     data[..data_len].copy_from_slice(&buffer[7..]);
@@ -1602,16 +2983,69 @@ fn to_gatt_attribute_modified(
     })
 }
 
+impl GattAttributeModified {
+    #[cfg(all(feature = "ms", not(feature = "bluenrg-1-2")))]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        LittleEndian::write_u16(&mut bytes[2..4], self.attr_handle.0);
+        bytes[4] = self.data_len as u8;
+        let continued = if self.continued { 0x8000 } else { 0 };
+        LittleEndian::write_u16(&mut bytes[5..7], self.offset as u16 | continued);
+        bytes[7..7 + self.data_len].copy_from_slice(self.data());
+
+        7 + self.data_len
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        LittleEndian::write_u16(&mut bytes[2..4], self.attr_handle.0);
+        let continued = if self.continued { 0x8000 } else { 0 };
+        LittleEndian::write_u16(&mut bytes[4..6], self.offset as u16 | continued);
+        LittleEndian::write_u16(&mut bytes[6..8], self.data_len as u16);
+        bytes[8..8 + self.data_len].copy_from_slice(self.data());
+
+        8 + self.data_len
+    }
+
+    #[cfg(not(feature = "ms"))]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        LittleEndian::write_u16(&mut bytes[2..4], self.attr_handle.0);
+        bytes[4] = self.data_len as u8;
+        bytes[5..5 + self.data_len].copy_from_slice(self.data());
+
+        5 + self.data_len
+    }
+}
+
 /// This event is generated in response to an Exchange MTU request.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttExchangeMtuResponse {
     ///  The connection handle related to the response.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
 
     /// Attribute server receive MTU size.
     pub server_rx_mtu: usize,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttExchangeMtuResponse {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "AttExchangeMtuResponse {{ conn_handle: {}, server_rx_mtu: {} }}",
+            Remote(&self.conn_handle),
+            self.server_rx_mtu
+        )
+    }
+}
+
 fn to_att_exchange_mtu_resp(
     buffer: &[u8],
 ) -> Result<AttExchangeMtuResponse, hci::event::Error<BlueNRGError>> {
@@ -1622,17 +3056,52 @@ fn to_att_exchange_mtu_resp(
     })
 }
 
+impl AttExchangeMtuResponse {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        bytes[2] = 1; // event data length, as sent by the controller
+        LittleEndian::write_u16(&mut bytes[3..5], self.server_rx_mtu as u16);
+
+        5
+    }
+}
+
 /// This event is generated in response to a Find Information Request. See Find Information Response
 /// in Bluetooth Core v4.0 spec.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AttFindInformationResponse {
     /// The connection handle related to the response
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
     /// The Find Information Response shall have complete handle-UUID pairs. Such pairs shall not be
     /// split across response packets; this also implies that a handleUUID pair shall fit into a
     /// single response packet. The handle-UUID pairs shall be returned in ascending order of
     /// attribute handles.
     handle_uuid_pairs: HandleUuidPairs,
+
+    /// True if the packet ended with a partial pair that was dropped.
+    truncated: bool,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttFindInformationResponse {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "AttFindInformationResponse {{ conn_handle: {}, handle_uuid_pairs: ",
+            Remote(&self.conn_handle)
+        );
+        defmt::write!(
+            f,
+            "{}, truncated: {} }}",
+            self.handle_uuid_pairs,
+            self.truncated
+        )
+    }
 }
 
 impl AttFindInformationResponse {
@@ -1658,6 +3127,12 @@ impl AttFindInformationResponse {
             }
         }
     }
+
+    /// Returns true if the packet ended with a partial handle-UUID pair, which was dropped. This
+    /// only happens with [lenient parsing](set_lenient_att_parsing).
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
 }
 
 // Assuming a maximum HCI packet size of 255, these are the maximum number of handle-UUID pairs for
@@ -1666,41 +3141,108 @@ impl AttFindInformationResponse {
 // Packets have 6 other bytes of data preceding the handle-UUID pairs.
 //
 // max = floor((255 - 6) / pair_length)
-const MAX_FORMAT16_PAIR_COUNT: usize = 62;
-const MAX_FORMAT128_PAIR_COUNT: usize = 13;
+const MAX_FORMAT16_PAIR_COUNT: usize = payload_limit(62 * 4) / 4;
+const MAX_FORMAT128_PAIR_COUNT: usize = payload_limit(13 * 18) / 18;
 
 /// One format of the handle-UUID pairs in the [`AttFindInformationResponse`] event. The UUIDs are
 /// 16 bits.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandleUuid16Pair {
     /// Attribute handle
     pub handle: AttributeHandle,
-    /// Attribute UUID
-    pub uuid: Uuid16,
+    /// Attribute UUID. Always a [16-bit UUID](Uuid::Uuid16).
+    pub uuid: Uuid,
 }
 
 /// One format of the handle-UUID pairs in the [`AttFindInformationResponse`] event. The UUIDs are
 /// 128 bits.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandleUuid128Pair {
     /// Attribute handle
     pub handle: AttributeHandle,
-    /// Attribute UUID
-    pub uuid: Uuid128,
+    /// Attribute UUID. Always a [128-bit UUID](Uuid::Uuid128).
+    pub uuid: Uuid,
 }
 
-/// Newtype for the 16-bit UUID buffer.
+/// Newtype for the 16-bit UUID buffer. Converts to, and compares with,
+/// [`Uuid`].
+#[deprecated(note = "use `crate::uuid::Uuid`")]
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Uuid16(pub u16);
 
-/// Newtype for the 128-bit UUID buffer.
+#[cfg(feature = "defmt")]
+#[allow(deprecated)]
+impl defmt::Format for Uuid16 {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Uuid16({})", self.0)
+    }
+}
+
+/// Newtype for the 128-bit UUID buffer. Converts to, and compares with,
+/// [`Uuid`].
+#[deprecated(note = "use `crate::uuid::Uuid`")]
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Uuid128(pub [u8; 16]);
 
+#[cfg(feature = "defmt")]
+#[allow(deprecated)]
+impl defmt::Format for Uuid128 {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "Uuid128({})", &self.0[..])
+    }
+}
+
+// The pairs are stored in their wire sizes, and converted to [`Uuid`]s as they are read.
 #[derive(Copy, Clone)]
 enum HandleUuidPairs {
-    Format16(usize, [HandleUuid16Pair; MAX_FORMAT16_PAIR_COUNT]),
-    Format128(usize, [HandleUuid128Pair; MAX_FORMAT128_PAIR_COUNT]),
+    Format16(usize, [RawHandleUuid16Pair; MAX_FORMAT16_PAIR_COUNT]),
+    Format128(usize, [RawHandleUuid128Pair; MAX_FORMAT128_PAIR_COUNT]),
+}
+
+trait RawHandleUuidPair: Copy {
+    type Pair;
+
+    fn pair(self) -> Self::Pair;
+}
+
+#[derive(Copy, Clone)]
+struct RawHandleUuid16Pair {
+    handle: AttributeHandle,
+    uuid: u16,
+}
+
+impl RawHandleUuidPair for RawHandleUuid16Pair {
+    type Pair = HandleUuid16Pair;
+
+    fn pair(self) -> HandleUuid16Pair {
+        HandleUuid16Pair {
+            handle: self.handle,
+            uuid: Uuid::Uuid16(self.uuid),
+        }
+    }
+}
+
+#[derive(Copy, Clone)]
+struct RawHandleUuid128Pair {
+    handle: AttributeHandle,
+    uuid: [u8; 16],
+}
+
+impl RawHandleUuidPair for RawHandleUuid128Pair {
+    type Pair = HandleUuid128Pair;
+
+    fn pair(self) -> HandleUuid128Pair {
+        HandleUuid128Pair {
+            handle: self.handle,
+            uuid: Uuid::Uuid128(self.uuid),
+        }
+    }
 }
 
 impl Debug for HandleUuidPairs {
@@ -1708,7 +3250,7 @@ impl Debug for HandleUuidPairs {
         write!(f, "{{")?;
         match *self {
             HandleUuidPairs::Format16(count, pairs) => {
-                for handle_uuid_pair in &pairs[..count] {
+                for handle_uuid_pair in pairs[..count].iter().map(|p| p.pair()) {
                     write!(
                         f,
                         "{{{:?}, {:?}}}",
@@ -1717,7 +3259,7 @@ impl Debug for HandleUuidPairs {
                 }
             }
             HandleUuidPairs::Format128(count, pairs) => {
-                for handle_uuid_pair in &pairs[..count] {
+                for handle_uuid_pair in pairs[..count].iter().map(|p| p.pair()) {
                     write!(
                         f,
                         "{{{:?}, {:?}}}",
@@ -1730,6 +3272,73 @@ impl Debug for HandleUuidPairs {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for HandleUuidPairs {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            HandleUuidPairs::Format16(count, pairs) => format_pairs(f, &pairs[..*count]),
+            HandleUuidPairs::Format128(count, pairs) => format_pairs(f, &pairs[..*count]),
+        }
+    }
+}
+
+#[cfg(feature = "defmt")]
+fn format_pairs<T>(f: defmt::Formatter, pairs: &[T])
+where
+    T: RawHandleUuidPair,
+    T::Pair: defmt::Format,
+{
+    defmt::write!(f, "[");
+    for (i, pair) in pairs.iter().enumerate() {
+        if i > 0 {
+            defmt::write!(f, ", ");
+        }
+        defmt::write!(f, "{}", pair.pair());
+    }
+    defmt::write!(f, "]");
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for HandleUuidPairs {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        const NAME: &str = "HandleUuidPairs";
+        match self {
+            HandleUuidPairs::Format16(count, pairs) => serializer.serialize_newtype_variant(
+                NAME,
+                0,
+                "Format16",
+                &SerializePairs(&pairs[..*count]),
+            ),
+            HandleUuidPairs::Format128(count, pairs) => serializer.serialize_newtype_variant(
+                NAME,
+                1,
+                "Format128",
+                &SerializePairs(&pairs[..*count]),
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+struct SerializePairs<'a, T>(&'a [T]);
+
+#[cfg(feature = "serde")]
+impl<'a, T> serde::Serialize for SerializePairs<'a, T>
+where
+    T: RawHandleUuidPair,
+    T::Pair: serde::Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.0.iter().map(|p| p.pair()))
+    }
+}
+
 /// Possible iterators over handle-UUID pairs that can be returnedby the [ATT find information
 /// response](AttFindInformationResponse). All pairs from the same event have the same format.
 pub enum HandleUuidPairIterator<'a> {
@@ -1741,7 +3350,7 @@ pub enum HandleUuidPairIterator<'a> {
 
 /// Iterator over handle-UUID pairs for 16-bit UUIDs.
 pub struct HandleUuid16PairIterator<'a> {
-    data: &'a [HandleUuid16Pair; MAX_FORMAT16_PAIR_COUNT],
+    data: &'a [RawHandleUuid16Pair; MAX_FORMAT16_PAIR_COUNT],
     count: usize,
     next_index: usize,
 }
@@ -1755,13 +3364,13 @@ impl<'a> Iterator for HandleUuid16PairIterator<'a> {
 
         let index = self.next_index;
         self.next_index += 1;
-        Some(self.data[index])
+        Some(self.data[index].pair())
     }
 }
 
 /// Iterator over handle-UUID pairs for 128-bit UUIDs.
 pub struct HandleUuid128PairIterator<'a> {
-    data: &'a [HandleUuid128Pair; MAX_FORMAT128_PAIR_COUNT],
+    data: &'a [RawHandleUuid128Pair; MAX_FORMAT128_PAIR_COUNT],
     count: usize,
     next_index: usize,
 }
@@ -1775,7 +3384,7 @@ impl<'a> Iterator for HandleUuid128PairIterator<'a> {
 
         let index = self.next_index;
         self.next_index += 1;
-        Some(self.data[index])
+        Some(self.data[index].pair())
     }
 }
 
@@ -1784,62 +3393,126 @@ fn to_att_find_information_response(
 ) -> Result<AttFindInformationResponse, hci::event::Error<BlueNRGError>> {
     require_len_at_least!(buffer, 6);
 
-    let data_len = buffer[4] as usize;
-    require_len!(buffer, 5 + data_len);
+    // The BlueNRG-1 and BlueNRG-2 send the format first, followed by the length of the pairs
+    // alone.
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    let format = {
+        require_len!(buffer, 5 + buffer[4] as usize);
+        buffer[5]
+    };
+    #[cfg(feature = "bluenrg-1-2")]
+    let format = {
+        require_len!(buffer, 6 + buffer[5] as usize);
+        buffer[4]
+    };
+
+    let (handle_uuid_pairs, truncated) = match format {
+        1 => {
+            let (pairs_len, truncated) = complete_records_len(
+                buffer.len() - 6,
+                4,
+                BlueNRGError::AttFindInformationResponsePartialPair16,
+            )
+            .map_err(hci::event::Error::Vendor)?;
+            require_payload_fits!(pairs_len, 4 * MAX_FORMAT16_PAIR_COUNT);
+            (to_handle_uuid16_pairs(&buffer[6..6 + pairs_len]), truncated)
+        }
+        2 => {
+            let (pairs_len, truncated) = complete_records_len(
+                buffer.len() - 6,
+                18,
+                BlueNRGError::AttFindInformationResponsePartialPair128,
+            )
+            .map_err(hci::event::Error::Vendor)?;
+            require_payload_fits!(pairs_len, 18 * MAX_FORMAT128_PAIR_COUNT);
+            (
+                to_handle_uuid128_pairs(&buffer[6..6 + pairs_len]),
+                truncated,
+            )
+        }
+        _ => {
+            return Err(hci::event::Error::Vendor(
+                BlueNRGError::BadAttFindInformationResponseFormat(format),
+            ));
+        }
+    };
 
     Ok(AttFindInformationResponse {
         conn_handle: to_conn_handle(buffer)?,
-        handle_uuid_pairs: match buffer[5] {
-            1 => to_handle_uuid16_pairs(&buffer[6..]).map_err(hci::event::Error::Vendor)?,
-            2 => to_handle_uuid128_pairs(&buffer[6..]).map_err(hci::event::Error::Vendor)?,
-            _ => {
-                return Err(hci::event::Error::Vendor(
-                    BlueNRGError::BadAttFindInformationResponseFormat(buffer[5]),
-                ));
-            }
-        },
+        handle_uuid_pairs,
+        truncated,
     })
 }
 
-fn to_handle_uuid16_pairs(buffer: &[u8]) -> Result<HandleUuidPairs, BlueNRGError> {
+// The buffer must hold a whole number of pairs.
+fn to_handle_uuid16_pairs(buffer: &[u8]) -> HandleUuidPairs {
     const PAIR_LEN: usize = 4;
-    if buffer.len() % PAIR_LEN != 0 {
-        return Err(BlueNRGError::AttFindInformationResponsePartialPair16);
-    }
-
     let count = buffer.len() / PAIR_LEN;
-    let mut pairs = [HandleUuid16Pair {
+    let mut pairs = [RawHandleUuid16Pair {
         handle: AttributeHandle(0),
-        uuid: Uuid16(0),
+        uuid: 0,
     }; MAX_FORMAT16_PAIR_COUNT];
     for (i, pair) in pairs.iter_mut().enumerate().take(count) {
         let index = i * PAIR_LEN;
         pair.handle = AttributeHandle(LittleEndian::read_u16(&buffer[index..]));
-        pair.uuid = Uuid16(LittleEndian::read_u16(&buffer[2 + index..]));
+        pair.uuid = LittleEndian::read_u16(&buffer[2 + index..]);
     }
 
-    Ok(HandleUuidPairs::Format16(count, pairs))
+    HandleUuidPairs::Format16(count, pairs)
 }
 
-fn to_handle_uuid128_pairs(buffer: &[u8]) -> Result<HandleUuidPairs, BlueNRGError> {
+// The buffer must hold a whole number of pairs.
+fn to_handle_uuid128_pairs(buffer: &[u8]) -> HandleUuidPairs {
     const PAIR_LEN: usize = 18;
-    if buffer.len() % PAIR_LEN != 0 {
-        return Err(BlueNRGError::AttFindInformationResponsePartialPair128);
-    }
-
     let count = buffer.len() / PAIR_LEN;
-    let mut pairs = [HandleUuid128Pair {
+    let mut pairs = [RawHandleUuid128Pair {
         handle: AttributeHandle(0),
-        uuid: Uuid128([0; 16]),
+        uuid: [0; 16],
     }; MAX_FORMAT128_PAIR_COUNT];
     for (i, pair) in pairs.iter_mut().enumerate().take(count) {
         let index = i * PAIR_LEN;
         let next_index = (i + 1) * PAIR_LEN;
         pair.handle = AttributeHandle(LittleEndian::read_u16(&buffer[index..]));
-        pair.uuid.0.copy_from_slice(&buffer[2 + index..next_index]);
+        pair.uuid.copy_from_slice(&buffer[2 + index..next_index]);
     }
 
-    Ok(HandleUuidPairs::Format128(count, pairs))
+    HandleUuidPairs::Format128(count, pairs)
+}
+
+impl AttFindInformationResponse {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        let (format, pairs_len) = match self.handle_uuid_pairs {
+            HandleUuidPairs::Format16(count, ref pairs) => {
+                for (i, pair) in pairs[..count].iter().enumerate() {
+                    let index = 4 + 4 * i;
+                    LittleEndian::write_u16(&mut bytes[index..], pair.handle.0);
+                    LittleEndian::write_u16(&mut bytes[2 + index..], pair.uuid);
+                }
+                (1, 4 * count)
+            }
+            HandleUuidPairs::Format128(count, ref pairs) => {
+                for (i, pair) in pairs[..count].iter().enumerate() {
+                    let index = 4 + 18 * i;
+                    LittleEndian::write_u16(&mut bytes[index..], pair.handle.0);
+                    bytes[2 + index..18 + index].copy_from_slice(&pair.uuid);
+                }
+                (2, 18 * count)
+            }
+        };
+        #[cfg(not(feature = "bluenrg-1-2"))]
+        {
+            bytes[2] = 1 + pairs_len as u8;
+            bytes[3] = format;
+        }
+        #[cfg(feature = "bluenrg-1-2")]
+        {
+            bytes[2] = format;
+            bytes[3] = pairs_len as u8;
+        }
+
+        4 + pairs_len
+    }
 }
 
 /// This event is generated in response to a Find By Type Value Request.
@@ -1853,6 +3526,9 @@ pub struct AttFindByTypeValueResponse {
 
     /// Handles Information List as defined in Bluetooth Core v4.1 spec.
     handles: [HandleInfoPair; MAX_HANDLE_INFO_PAIR_COUNT],
+
+    /// True if the packet ended with a partial pair that was dropped.
+    truncated: bool,
 }
 
 impl AttFindByTypeValueResponse {
@@ -1864,6 +3540,12 @@ impl AttFindByTypeValueResponse {
             next_index: 0,
         }
     }
+
+    /// Returns true if the packet ended with a partial handle pair, which was dropped. This only
+    /// happens with [lenient parsing](set_lenient_att_parsing).
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
 }
 
 impl Debug for AttFindByTypeValueResponse {
@@ -1876,16 +3558,46 @@ impl Debug for AttFindByTypeValueResponse {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttFindByTypeValueResponse {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{.conn_handle = {=u16:#x}, .handle_pairs = {}}}",
+            self.conn_handle.0,
+            &self.handles[..self.handle_pair_count]
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AttFindByTypeValueResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("AttFindByTypeValueResponse", 3)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field("handle_pairs", &self.handles[..self.handle_pair_count])?;
+        state.serialize_field("truncated", &self.truncated)?;
+        state.end()
+    }
+}
+
 // Assuming a maximum HCI packet size of 255, these are the maximum number of handle pairs that can
 // be in one packet.
 //
 // Packets have 5 other bytes of data preceding the handle-UUID pairs.
 //
 // max = floor((255 - 5) / 4)
-const MAX_HANDLE_INFO_PAIR_COUNT: usize = 62;
+const MAX_HANDLE_INFO_PAIR_COUNT: usize = payload_limit(62 * 4) / 4;
 
 /// Simple container for the handle information returned in [`AttFindByTypeValueResponse`].
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct HandleInfoPair {
     /// Attribute handle
     pub attribute: AttributeHandle,
@@ -1895,6 +3607,8 @@ pub struct HandleInfoPair {
 
 /// Newtype for Group End handles
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GroupEndHandle(pub u16);
 
 /// Iterator into valid [`HandleInfoPair`] structs returned in the [ATT Find By Type Value
@@ -1925,15 +3639,21 @@ fn to_att_find_by_value_type_response(
 
     require_len_at_least!(buffer, 5);
 
+    // The BlueNRG-1 and BlueNRG-2 send the number of pairs instead of their length.
+    #[cfg(not(feature = "bluenrg-1-2"))]
     let data_len = buffer[4] as usize;
+    #[cfg(feature = "bluenrg-1-2")]
+    let data_len = PAIR_LEN * buffer[4] as usize;
     require_len!(buffer, 5 + data_len);
 
-    let pair_buffer = &buffer[5..];
-    if pair_buffer.len() % PAIR_LEN != 0 {
-        return Err(hci::event::Error::Vendor(
-            BlueNRGError::AttFindByTypeValuePartial,
-        ));
-    }
+    let (pairs_len, truncated) = complete_records_len(
+        buffer.len() - 5,
+        PAIR_LEN,
+        BlueNRGError::AttFindByTypeValuePartial,
+    )
+    .map_err(hci::event::Error::Vendor)?;
+    let pair_buffer = &buffer[5..5 + pairs_len];
+    require_payload_fits!(pair_buffer.len(), PAIR_LEN * MAX_HANDLE_INFO_PAIR_COUNT);
 
     let count = pair_buffer.len() / PAIR_LEN;
     let mut pairs = [HandleInfoPair {
@@ -1949,9 +3669,28 @@ fn to_att_find_by_value_type_response(
         conn_handle: to_conn_handle(buffer)?,
         handle_pair_count: count,
         handles: pairs,
+        truncated,
     })
 }
 
+impl AttFindByTypeValueResponse {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        #[cfg(not(feature = "bluenrg-1-2"))]
+        let pairs_field = 4 * self.handle_pair_count as u8;
+        #[cfg(feature = "bluenrg-1-2")]
+        let pairs_field = self.handle_pair_count as u8;
+        bytes[2] = pairs_field;
+        for (i, pair) in self.handle_pairs_iter().enumerate() {
+            let index = 3 + 4 * i;
+            LittleEndian::write_u16(&mut bytes[index..], pair.attribute.0);
+            LittleEndian::write_u16(&mut bytes[2 + index..], pair.group_end.0);
+        }
+
+        3 + 4 * self.handle_pair_count
+    }
+}
+
 /// This event is generated in response to a Read By Type Request.
 #[derive(Copy, Clone)]
 pub struct AttReadByTypeResponse {
@@ -1965,11 +3704,13 @@ pub struct AttReadByTypeResponse {
     // Raw data of the response. Contains 2 octets for the attribute handle followed by `value_len`
     // octets of value data. These pairs repeat for `data_len` bytes.
     handle_value_pair_buf: [u8; MAX_HANDLE_VALUE_PAIR_BUF_LEN],
+    // True if the packet ended with a partial pair that was dropped.
+    truncated: bool,
 }
 
 // The maximum amount of data in the buffer is the max HCI packet size (255) less the other data in
 // the packet.
-const MAX_HANDLE_VALUE_PAIR_BUF_LEN: usize = 249;
+const MAX_HANDLE_VALUE_PAIR_BUF_LEN: usize = payload_limit(249);
 
 impl Debug for AttReadByTypeResponse {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
@@ -1986,6 +3727,36 @@ impl Debug for AttReadByTypeResponse {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttReadByTypeResponse {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{{.conn_handle = {=u16:#x}, ", self.conn_handle.0);
+        for handle_value_pair in self.handle_value_pair_iter() {
+            defmt::write!(f, "{}", handle_value_pair);
+        }
+        defmt::write!(f, "}}")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AttReadByTypeResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("AttReadByTypeResponse", 3)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field(
+            "handle_value_pairs",
+            &crate::serde_remote::Seq(|| self.handle_value_pair_iter()),
+        )?;
+        state.serialize_field("truncated", &self.truncated)?;
+        state.end()
+    }
+}
+
 impl AttReadByTypeResponse {
     /// Return an iterator over all valid handle-value pairs returned with the response.
     pub fn handle_value_pair_iter(&self) -> HandleValuePairIterator {
@@ -1994,6 +3765,12 @@ impl AttReadByTypeResponse {
             index: 0,
         }
     }
+
+    /// Returns true if the packet ended with a partial handle-value pair, which was dropped. This
+    /// only happens with [lenient parsing](set_lenient_att_parsing).
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
 }
 
 /// Iterator over the valid handle-value pairs returned with the [ATT Read by Type
@@ -2024,6 +3801,8 @@ impl<'a> Iterator for HandleValuePairIterator<'a> {
 }
 
 /// A single handle-value pair returned by the [ATT Read by Type response](AttReadByTypeResponse).
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct HandleValuePair<'a> {
     /// Attribute handle
     pub handle: AttributeHandle,
@@ -2032,21 +3811,49 @@ pub struct HandleValuePair<'a> {
     pub value: &'a [u8],
 }
 
+// Returns the record length of an ATT Read By Type or Read By Group Type response, after checking
+// the length of the records that follow it. The BlueNRG-1 and BlueNRG-2 send the record length
+// first, and the length of the records excludes it.
+fn to_att_record_len(buffer: &[u8]) -> Result<usize, hci::event::Error<BlueNRGError>> {
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    {
+        require_len!(buffer, 5 + buffer[4] as usize);
+        Ok(buffer[5] as usize)
+    }
+    #[cfg(feature = "bluenrg-1-2")]
+    {
+        require_len!(buffer, 6 + buffer[5] as usize);
+        Ok(buffer[4] as usize)
+    }
+}
+
+fn write_att_record_len(bytes: &mut [u8], record_len: usize, data_len: usize) {
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    {
+        bytes[2] = 1 + data_len as u8;
+        bytes[3] = record_len as u8;
+    }
+    #[cfg(feature = "bluenrg-1-2")]
+    {
+        bytes[2] = record_len as u8;
+        bytes[3] = data_len as u8;
+    }
+}
+
 fn to_att_read_by_type_response(
     buffer: &[u8],
 ) -> Result<AttReadByTypeResponse, hci::event::Error<BlueNRGError>> {
     require_len_at_least!(buffer, 6);
 
-    let data_len = buffer[4] as usize;
-    require_len!(buffer, 5 + data_len);
-
-    let handle_value_pair_len = buffer[5] as usize;
-    let handle_value_pair_buf = &buffer[6..];
-    if handle_value_pair_buf.len() % handle_value_pair_len != 0 {
-        return Err(hci::event::Error::Vendor(
-            BlueNRGError::AttReadByTypeResponsePartial,
-        ));
-    }
+    let handle_value_pair_len = to_att_record_len(buffer)?;
+    let (pairs_len, truncated) = complete_records_len(
+        buffer.len() - 6,
+        handle_value_pair_len,
+        BlueNRGError::AttReadByTypeResponsePartial,
+    )
+    .map_err(hci::event::Error::Vendor)?;
+    let handle_value_pair_buf = &buffer[6..6 + pairs_len];
+    require_payload_fits!(handle_value_pair_buf.len(), MAX_HANDLE_VALUE_PAIR_BUF_LEN);
 
     let mut full_handle_value_pair_buf = [0; MAX_HANDLE_VALUE_PAIR_BUF_LEN];
     full_handle_value_pair_buf[..handle_value_pair_buf.len()]
@@ -2057,9 +3864,20 @@ fn to_att_read_by_type_response(
         data_len: handle_value_pair_buf.len(),
         value_len: handle_value_pair_len - 2,
         handle_value_pair_buf: full_handle_value_pair_buf,
+        truncated,
     })
 }
 
+impl AttReadByTypeResponse {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        write_att_record_len(bytes, 2 + self.value_len, self.data_len);
+        bytes[4..4 + self.data_len].copy_from_slice(&self.handle_value_pair_buf[..self.data_len]);
+
+        4 + self.data_len
+    }
+}
+
 /// This event is generated in response to a Read Request.
 #[derive(Copy, Clone)]
 pub struct AttReadResponse {
@@ -2075,7 +3893,7 @@ pub struct AttReadResponse {
 
 // The maximum amount of data in the buffer is the max HCI packet size (255) less the other data in
 // the packet.
-const MAX_READ_RESPONSE_LEN: usize = 250;
+const MAX_READ_RESPONSE_LEN: usize = payload_limit(250);
 
 impl Debug for AttReadResponse {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
@@ -2088,6 +3906,33 @@ impl Debug for AttReadResponse {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttReadResponse {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{.conn_handle = {=u16:#x}, value = {=[u8]:#x}}}",
+            self.conn_handle.0,
+            self.value()
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AttReadResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("AttReadResponse", 2)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field("value", self.value())?;
+        state.end()
+    }
+}
+
 impl AttReadResponse {
     /// Returns the valid part of the value data.
     pub fn value(&self) -> &[u8] {
@@ -2100,6 +3945,7 @@ fn to_att_read_response(buffer: &[u8]) -> Result<AttReadResponse, hci::event::Er
 
     let data_len = buffer[4] as usize;
     require_len!(buffer, 5 + data_len);
+    require_payload_fits!(data_len, MAX_READ_RESPONSE_LEN);
 
     let mut value_buf = [0; MAX_READ_RESPONSE_LEN];
     value_buf[..data_len].copy_from_slice(&buffer[5..]);
@@ -2111,6 +3957,16 @@ fn to_att_read_response(buffer: &[u8]) -> Result<AttReadResponse, hci::event::Er
     })
 }
 
+impl AttReadResponse {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        bytes[2] = self.value_len as u8;
+        bytes[3..3 + self.value_len].copy_from_slice(self.value());
+
+        3 + self.value_len
+    }
+}
+
 /// This event is generated in response to a Read By Group Type Request. See the Bluetooth Core v4.1
 /// spec, Vol 3, section 3.4.4.9 and 3.4.4.10.
 #[derive(Copy, Clone)]
@@ -2130,11 +3986,14 @@ pub struct AttReadByGroupTypeResponse {
     // 2. 2 octets for end group handle.
     // 3. (attribute_group_len - 4) octets for attribute value.
     attribute_data_buf: [u8; MAX_ATTRIBUTE_DATA_BUF_LEN],
+
+    // True if the packet ended with a partial attribute data group that was dropped.
+    truncated: bool,
 }
 
 // The maximum amount of data in the buffer is the max HCI packet size (255) less the other data in
 // the packet.
-const MAX_ATTRIBUTE_DATA_BUF_LEN: usize = 249;
+const MAX_ATTRIBUTE_DATA_BUF_LEN: usize = payload_limit(249);
 
 impl AttReadByGroupTypeResponse {
     /// Create and return an iterator for the attribute data returned with the response.
@@ -2144,6 +4003,12 @@ impl AttReadByGroupTypeResponse {
             next_index: 0,
         }
     }
+
+    /// Returns true if the packet ended with a partial attribute data group, which was dropped.
+    /// This only happens with [lenient parsing](set_lenient_att_parsing).
+    pub fn is_truncated(&self) -> bool {
+        self.truncated
+    }
 }
 
 impl Debug for AttReadByGroupTypeResponse {
@@ -2162,6 +4027,36 @@ impl Debug for AttReadByGroupTypeResponse {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttReadByGroupTypeResponse {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{{.conn_handle = {=u16:#x}, ", self.conn_handle.0);
+        for attribute_data in self.attribute_data_iter() {
+            defmt::write!(f, "{}", attribute_data);
+        }
+        defmt::write!(f, "}}")
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AttReadByGroupTypeResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("AttReadByGroupTypeResponse", 3)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field(
+            "attribute_data",
+            &crate::serde_remote::Seq(|| self.attribute_data_iter()),
+        )?;
+        state.serialize_field("truncated", &self.truncated)?;
+        state.end()
+    }
+}
+
 /// Iterator over the attribute data returned in the [`AttReadByGroupTypeResponse`].
 pub struct AttributeDataIterator<'a> {
     event: &'a AttReadByGroupTypeResponse,
@@ -2192,6 +4087,8 @@ impl<'a> Iterator for AttributeDataIterator<'a> {
 }
 
 /// Attribute data returned in the [`AttReadByGroupTypeResponse`] event.
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct AttributeData<'a> {
     /// Attribute handle
     pub attribute_handle: AttributeHandle,
@@ -2206,27 +4103,36 @@ fn to_att_read_by_group_type_response(
 ) -> Result<AttReadByGroupTypeResponse, hci::event::Error<BlueNRGError>> {
     require_len_at_least!(buffer, 6);
 
-    let data_len = buffer[4] as usize;
-    require_len!(buffer, 5 + data_len);
-
-    let attribute_group_len = buffer[5] as usize;
-
-    if buffer[6..].len() % attribute_group_len != 0 {
-        return Err(hci::event::Error::Vendor(
-            BlueNRGError::AttReadByGroupTypeResponsePartial,
-        ));
-    }
+    let attribute_group_len = to_att_record_len(buffer)?;
+    let (groups_len, truncated) = complete_records_len(
+        buffer.len() - 6,
+        attribute_group_len,
+        BlueNRGError::AttReadByGroupTypeResponsePartial,
+    )
+    .map_err(hci::event::Error::Vendor)?;
 
+    require_payload_fits!(groups_len, MAX_ATTRIBUTE_DATA_BUF_LEN);
     let mut attribute_data_buf = [0; MAX_ATTRIBUTE_DATA_BUF_LEN];
-    attribute_data_buf[..data_len - 1].copy_from_slice(&buffer[6..]);
+    attribute_data_buf[..groups_len].copy_from_slice(&buffer[6..6 + groups_len]);
     Ok(AttReadByGroupTypeResponse {
         conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
-        data_len: data_len - 1, // lose 1 byte to attribute_group_len
+        data_len: groups_len,
         attribute_group_len,
         attribute_data_buf,
+        truncated,
     })
 }
 
+impl AttReadByGroupTypeResponse {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        write_att_record_len(bytes, self.attribute_group_len, self.data_len);
+        bytes[4..4 + self.data_len].copy_from_slice(&self.attribute_data_buf[..self.data_len]);
+
+        4 + self.data_len
+    }
+}
+
 /// This event is generated in response to a Prepare Write Request. See the Bluetooth Core v4.1
 /// spec, Vol 3, Part F, section 3.4.6.1 and 3.4.6.2
 #[derive(Copy, Clone)]
@@ -2245,7 +4151,7 @@ pub struct AttPrepareWriteResponse {
 
 // The maximum amount of data in the buffer is the max HCI packet size (255) less the other data in
 // the packet.
-const MAX_WRITE_RESPONSE_VALUE_LEN: usize = 246;
+const MAX_WRITE_RESPONSE_VALUE_LEN: usize = payload_limit(246);
 
 impl Debug for AttPrepareWriteResponse {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
@@ -2260,6 +4166,38 @@ impl Debug for AttPrepareWriteResponse {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttPrepareWriteResponse {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{.conn_handle = {=u16:#x}, .attribute_handle = {}, .offset = {=usize}, \
+             .value = {=[u8]:#x}}}",
+            self.conn_handle.0,
+            self.attribute_handle,
+            self.offset,
+            self.value()
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AttPrepareWriteResponse {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("AttPrepareWriteResponse", 4)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field("attribute_handle", &self.attribute_handle)?;
+        state.serialize_field("offset", &self.offset)?;
+        state.serialize_field("value", self.value())?;
+        state.end()
+    }
+}
+
 impl AttPrepareWriteResponse {
     /// Returns the partial value of the attribute to be written.
     pub fn value(&self) -> &[u8] {
@@ -2272,21 +4210,53 @@ fn to_att_prepare_write_response(
 ) -> Result<AttPrepareWriteResponse, hci::event::Error<BlueNRGError>> {
     require_len_at_least!(buffer, 9);
 
-    let data_len = buffer[4] as usize;
-    require_len!(buffer, 5 + data_len);
+    // The BlueNRG-1 and BlueNRG-2 send the length of the value alone, after the offset.
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    let (value_len, params) = {
+        let data_len = buffer[4] as usize;
+        require_len!(buffer, 5 + data_len);
+        (data_len - 4, &buffer[5..9])
+    };
+    #[cfg(feature = "bluenrg-1-2")]
+    let (value_len, params) = {
+        let value_len = buffer[8] as usize;
+        require_len!(buffer, 9 + value_len);
+        (value_len, &buffer[4..8])
+    };
 
-    let value_len = data_len - 4;
+    require_payload_fits!(value_len, MAX_WRITE_RESPONSE_VALUE_LEN);
     let mut value_buf = [0; MAX_WRITE_RESPONSE_VALUE_LEN];
     value_buf[..value_len].copy_from_slice(&buffer[9..]);
     Ok(AttPrepareWriteResponse {
         conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
-        attribute_handle: AttributeHandle(LittleEndian::read_u16(&buffer[5..])),
-        offset: LittleEndian::read_u16(&buffer[7..]) as usize,
+        attribute_handle: AttributeHandle(LittleEndian::read_u16(&params[0..])),
+        offset: LittleEndian::read_u16(&params[2..]) as usize,
         value_len,
         value_buf,
     })
 }
 
+impl AttPrepareWriteResponse {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        #[cfg(not(feature = "bluenrg-1-2"))]
+        let params = {
+            bytes[2] = 4 + self.value_len as u8;
+            &mut bytes[3..7]
+        };
+        #[cfg(feature = "bluenrg-1-2")]
+        let params = {
+            bytes[6] = self.value_len as u8;
+            &mut bytes[2..6]
+        };
+        LittleEndian::write_u16(&mut params[0..2], self.attribute_handle.0);
+        LittleEndian::write_u16(&mut params[2..4], self.offset as u16);
+        bytes[7..7 + self.value_len].copy_from_slice(self.value());
+
+        7 + self.value_len
+    }
+}
+
 /// Defines the attribute value returned by a [GATT Indication](BlueNRGEvent::GattIndication) or
 /// [GATT Notification](BlueNRGEvent::GattNotification) event.
 #[derive(Copy, Clone)]
@@ -2304,7 +4274,7 @@ pub struct AttributeValue {
 
 // The maximum amount of data in the buffer is the max HCI packet size (255) less the other data in
 // the packet.
-const MAX_ATTRIBUTE_VALUE_LEN: usize = 248;
+const MAX_ATTRIBUTE_VALUE_LEN: usize = payload_limit(248);
 
 impl Debug for AttributeValue {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
@@ -2318,6 +4288,35 @@ impl Debug for AttributeValue {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttributeValue {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{.conn_handle = {=u16:#x}, .attribute_handle = {}, .value = {=[u8]:#x}}}",
+            self.conn_handle.0,
+            self.attribute_handle,
+            self.value()
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AttributeValue {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("AttributeValue", 3)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field("attribute_handle", &self.attribute_handle)?;
+        state.serialize_field("value", self.value())?;
+        state.end()
+    }
+}
+
 impl AttributeValue {
     /// Returns the current value of the attribute.
     pub fn value(&self) -> &[u8] {
@@ -2325,6 +4324,7 @@ impl AttributeValue {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 fn to_attribute_value(buffer: &[u8]) -> Result<AttributeValue, hci::event::Error<BlueNRGError>> {
     require_len_at_least!(buffer, 7);
 
@@ -2332,6 +4332,7 @@ fn to_attribute_value(buffer: &[u8]) -> Result<AttributeValue, hci::event::Error
     require_len!(buffer, 5 + data_len);
 
     let value_len = data_len - 2;
+    require_payload_fits!(value_len, MAX_ATTRIBUTE_VALUE_LEN);
     let mut value_buf = [0; MAX_ATTRIBUTE_VALUE_LEN];
     value_buf[..value_len].copy_from_slice(&buffer[7..]);
     Ok(AttributeValue {
@@ -2342,6 +4343,12 @@ fn to_attribute_value(buffer: &[u8]) -> Result<AttributeValue, hci::event::Error
     })
 }
 
+// The BlueNRG-1 and BlueNRG-2 use the write permit request layout for attribute values.
+#[cfg(feature = "bluenrg-1-2")]
+fn to_attribute_value(buffer: &[u8]) -> Result<AttributeValue, hci::event::Error<BlueNRGError>> {
+    to_write_permit_request(buffer)
+}
+
 fn to_write_permit_request(
     buffer: &[u8],
 ) -> Result<AttributeValue, hci::event::Error<BlueNRGError>> {
@@ -2351,6 +4358,7 @@ fn to_write_permit_request(
     require_len!(buffer, 7 + data_len);
 
     let value_len = data_len;
+    require_payload_fits!(value_len, MAX_ATTRIBUTE_VALUE_LEN);
     let mut value_buf = [0; MAX_ATTRIBUTE_VALUE_LEN];
     value_buf[..value_len].copy_from_slice(&buffer[7..]);
     Ok(AttributeValue {
@@ -2361,11 +4369,45 @@ fn to_write_permit_request(
     })
 }
 
+impl AttributeValue {
+    // Layout used by the indication, notification and discover or read characteristic by UUID
+    // events.
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        bytes[2] = 2 + self.value_len as u8;
+        LittleEndian::write_u16(&mut bytes[3..5], self.attribute_handle.0);
+        bytes[5..5 + self.value_len].copy_from_slice(self.value());
+
+        5 + self.value_len
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        self.copy_write_permit_request_into_slice(bytes)
+    }
+
+    // Layout used by the write permit request event.
+    fn copy_write_permit_request_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        LittleEndian::write_u16(&mut bytes[2..4], self.attribute_handle.0);
+        bytes[4] = self.value_len as u8;
+        bytes[5..5 + self.value_len].copy_from_slice(self.value());
+
+        5 + self.value_len
+    }
+}
+
 /// This event is generated when a GATT client procedure completes either with error or
 /// successfully.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GattProcedureComplete {
     /// The connection handle for which the GATT procedure has completed.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
 
     /// Indicates whether the procedure completed with [error](GattProcedureStatus::Failed) or was
@@ -2373,9 +4415,23 @@ pub struct GattProcedureComplete {
     pub status: GattProcedureStatus,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for GattProcedureComplete {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GattProcedureComplete {{ conn_handle: {}, status: {} }}",
+            Remote(&self.conn_handle),
+            self.status
+        )
+    }
+}
+
 /// Allowed status codes for the [GATT Procedure Complete](BlueNRGEvent::GattProcedureComplete)
 /// event.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum GattProcedureStatus {
     /// BLE Status Success
     Success,
@@ -2395,22 +4451,65 @@ impl TryFrom<u8> for GattProcedureStatus {
     }
 }
 
+impl From<GattProcedureStatus> for u8 {
+    fn from(status: GattProcedureStatus) -> u8 {
+        match status {
+            GattProcedureStatus::Success => 0x00,
+            GattProcedureStatus::Failed => 0x41,
+        }
+    }
+}
+
 fn to_gatt_procedure_complete(
     buffer: &[u8],
 ) -> Result<GattProcedureComplete, hci::event::Error<BlueNRGError>> {
-    require_len!(buffer, 6);
+    // The BlueNRG-1 and BlueNRG-2 do not send the event data length.
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    let status = {
+        require_len!(buffer, 6);
+        buffer[5]
+    };
+    #[cfg(feature = "bluenrg-1-2")]
+    let status = {
+        require_len!(buffer, 5);
+        buffer[4]
+    };
 
     Ok(GattProcedureComplete {
         conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
-        status: buffer[5].try_into().map_err(hci::event::Error::Vendor)?,
+        status: status.try_into().map_err(hci::event::Error::Vendor)?,
     })
 }
 
+impl GattProcedureComplete {
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        bytes[2] = 1; // event data length
+        bytes[3] = self.status.into();
+
+        4
+    }
+
+    #[cfg(feature = "bluenrg-1-2")]
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        bytes[2] = self.status.into();
+
+        3
+    }
+}
+
 /// The Error Response is used to state that a given request cannot be performed, and to provide the
 /// reason. See the Bluetooth Core Specification, v4.1, Vol 3, Part F, Section 3.4.1.1.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttErrorResponse {
     /// The connection handle related to the event.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
     /// The request that generated this error response.
     pub request: AttRequest,
@@ -2420,11 +4519,26 @@ pub struct AttErrorResponse {
     pub error: AttError,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttErrorResponse {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "AttErrorResponse {{ conn_handle: {}, request: {}, attribute_handle: ",
+            Remote(&self.conn_handle),
+            self.request
+        );
+        defmt::write!(f, "{}, error: {} }}", self.attribute_handle, self.error)
+    }
+}
+
 /// Potential error codes for the [ATT Error Response](BlueNRGEvent::AttErrorResponse). See Table
 /// 3.3 in the Bluetooth Core Specification, v4.1, Vol 3, Part F, Section 3.4.1.1 and The Bluetooth
 /// Core Specification Supplement, Table 1.1.
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttError {
     /// The attribute handle given was not valid on this server.
     InvalidHandle = 0x01,
@@ -2605,6 +4719,8 @@ impl TryFrom<u8> for AttError {
 /// 3.4.8.
 #[repr(u8)]
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum AttRequest {
     /// Section 3.4.1.1
     ErrorResponse = 0x01,
@@ -2705,18 +4821,46 @@ impl TryFrom<u8> for AttRequest {
 fn to_att_error_response(
     buffer: &[u8],
 ) -> Result<AttErrorResponse, hci::event::Error<BlueNRGError>> {
-    require_len!(buffer, 9);
+    // The BlueNRG-1 and BlueNRG-2 do not send the event data length.
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    let params = {
+        require_len!(buffer, 9);
+        &buffer[5..]
+    };
+    #[cfg(feature = "bluenrg-1-2")]
+    let params = {
+        require_len!(buffer, 8);
+        &buffer[4..]
+    };
     Ok(AttErrorResponse {
         conn_handle: ConnectionHandle(LittleEndian::read_u16(&buffer[2..])),
-        request: buffer[5].try_into().map_err(hci::event::Error::Vendor)?,
-        attribute_handle: AttributeHandle(LittleEndian::read_u16(&buffer[6..])),
-        error: buffer[8]
+        request: params[0].try_into().map_err(hci::event::Error::Vendor)?,
+        attribute_handle: AttributeHandle(LittleEndian::read_u16(&params[1..])),
+        error: params[3]
             .try_into()
             .map_err(BlueNRGError::BadAttError)
             .map_err(hci::event::Error::Vendor)?,
     })
 }
 
+impl AttErrorResponse {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        #[cfg(not(feature = "bluenrg-1-2"))]
+        let (params, len) = {
+            bytes[2] = 4; // event data length
+            (&mut bytes[3..], 7)
+        };
+        #[cfg(feature = "bluenrg-1-2")]
+        let (params, len) = (&mut bytes[2..], 6);
+        params[0] = self.request as u8;
+        LittleEndian::write_u16(&mut params[1..3], self.attribute_handle.0);
+        params[3] = self.error as u8;
+
+        len
+    }
+}
+
 /// This event is given to the application when a read request or read blob request is received by
 /// the server from the client. This event will be given to the application only if the event bit
 /// for this event generation is set when the characteristic was added. On receiving this event, the
@@ -2726,8 +4870,13 @@ fn to_att_error_response(
 ///
 /// See the Bluetooth Core v4.1 spec, Vol 3, Part F, section 3.4.4.
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct AttReadPermitRequest {
     /// Handle of the connection on which there was the request to read the attribute
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
 
     /// The handle of the attribute that has been requested by the client to be read.
@@ -2737,6 +4886,19 @@ pub struct AttReadPermitRequest {
     pub offset: usize,
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttReadPermitRequest {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "AttReadPermitRequest {{ conn_handle: {}, attribute_handle: {}, offset: {} }}",
+            Remote(&self.conn_handle),
+            self.attribute_handle,
+            self.offset
+        )
+    }
+}
+
 fn to_att_read_permit_request(
     buffer: &[u8],
 ) -> Result<AttReadPermitRequest, hci::event::Error<BlueNRGError>> {
@@ -2748,6 +4910,17 @@ fn to_att_read_permit_request(
     })
 }
 
+impl AttReadPermitRequest {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        LittleEndian::write_u16(&mut bytes[2..4], self.attribute_handle.0);
+        bytes[4] = 2; // event data length
+        LittleEndian::write_u16(&mut bytes[5..7], self.offset as u16);
+
+        7
+    }
+}
+
 /// This event is given to the application when a read multiple request or read by type request is
 /// received by the server from the client. This event will be given to the application only if the
 /// event bit for this event generation is set when the characteristic was added.  On receiving this
@@ -2770,7 +4943,7 @@ pub struct AttReadMultiplePermitRequest {
 
 // The maximum number of handles in the buffer is the max HCI packet size (255) less the other data in
 // the packet divided by the length of an attribute handle (2).
-const MAX_ATTRIBUTE_HANDLE_BUFFER_LEN: usize = 125;
+const MAX_ATTRIBUTE_HANDLE_BUFFER_LEN: usize = payload_limit(125 * 2) / 2;
 
 impl Debug for AttReadMultiplePermitRequest {
     fn fmt(&self, f: &mut Formatter) -> FmtResult {
@@ -2783,6 +4956,33 @@ impl Debug for AttReadMultiplePermitRequest {
     }
 }
 
+#[cfg(feature = "defmt")]
+impl defmt::Format for AttReadMultiplePermitRequest {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{.conn_handle = {=u16:#x}, .handles = {}}}",
+            self.conn_handle.0,
+            self.handles()
+        )
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for AttReadMultiplePermitRequest {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("AttReadMultiplePermitRequest", 2)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field("handles", self.handles())?;
+        state.end()
+    }
+}
+
 impl AttReadMultiplePermitRequest {
     /// Returns the valid attribute handles returned by the ATT Read Multiple Permit Request event.
     pub fn handles(&self) -> &[AttributeHandle] {
@@ -2795,14 +4995,20 @@ fn to_att_read_multiple_permit_request(
 ) -> Result<AttReadMultiplePermitRequest, hci::event::Error<BlueNRGError>> {
     require_len_at_least!(buffer, 5);
 
+    // The BlueNRG-1 and BlueNRG-2 send the number of handles instead of their length.
+    #[cfg(not(feature = "bluenrg-1-2"))]
     let data_len = buffer[4] as usize;
+    #[cfg(feature = "bluenrg-1-2")]
+    let data_len = 2 * buffer[4] as usize;
     if data_len % 2 != 0 {
         return Err(hci::event::Error::Vendor(
             BlueNRGError::AttReadMultiplePermitRequestPartial,
         ));
     }
+    require_len!(buffer, 5 + data_len);
 
     let handle_len = data_len / 2;
+    require_payload_fits!(handle_len, MAX_ATTRIBUTE_HANDLE_BUFFER_LEN);
     let mut handles = [AttributeHandle(0); MAX_ATTRIBUTE_HANDLE_BUFFER_LEN];
     for (i, handle) in handles.iter_mut().enumerate().take(handle_len) {
         let index = 5 + 2 * i;
@@ -2816,18 +5022,51 @@ fn to_att_read_multiple_permit_request(
     })
 }
 
+impl AttReadMultiplePermitRequest {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        #[cfg(not(feature = "bluenrg-1-2"))]
+        let handles_field = 2 * self.handles_len as u8;
+        #[cfg(feature = "bluenrg-1-2")]
+        let handles_field = self.handles_len as u8;
+        bytes[2] = handles_field;
+        for (i, handle) in self.handles().iter().enumerate() {
+            LittleEndian::write_u16(&mut bytes[3 + 2 * i..5 + 2 * i], handle.0);
+        }
+
+        3 + 2 * self.handles_len
+    }
+}
+
 /// This event is raised when the number of available TX buffers is above a threshold TH (TH = 2).
 /// The event will be given only if a previous ACI command returned with
 /// [`InsufficientResources`](AttError::InsufficientResources).
 #[cfg(feature = "ms")]
 #[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GattTxPoolAvailable {
     /// Connection handle on which the GATT procedure is running.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::ConnectionHandleDef")
+    )]
     pub conn_handle: ConnectionHandle,
     /// Indicates the number of elements available in the attrTxPool List.
     pub available_buffers: usize,
 }
 
+#[cfg(all(feature = "ms", feature = "defmt"))]
+impl defmt::Format for GattTxPoolAvailable {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "GattTxPoolAvailable {{ conn_handle: {}, available_buffers: {} }}",
+            Remote(&self.conn_handle),
+            self.available_buffers
+        )
+    }
+}
+
 #[cfg(feature = "ms")]
 fn to_gatt_tx_pool_available(
     buffer: &[u8],
@@ -2839,6 +5078,16 @@ fn to_gatt_tx_pool_available(
     })
 }
 
+#[cfg(feature = "ms")]
+impl GattTxPoolAvailable {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        LittleEndian::write_u16(&mut bytes[2..4], self.available_buffers as u16);
+
+        4
+    }
+}
+
 /// This event is given to the application when a prepare write request is received by the server
 /// from the client.
 ///
@@ -2868,7 +5117,7 @@ pub struct AttPrepareWritePermitRequest {
 // The maximum number of bytes in the buffer is the max HCI packet size (255) less the other data in
 // the packet.
 #[cfg(feature = "ms")]
-const MAX_PREPARE_WRITE_PERMIT_REQ_VALUE_LEN: usize = 246;
+const MAX_PREPARE_WRITE_PERMIT_REQ_VALUE_LEN: usize = payload_limit(246);
 
 #[cfg(feature = "ms")]
 impl Debug for AttPrepareWritePermitRequest {
@@ -2884,6 +5133,38 @@ impl Debug for AttPrepareWritePermitRequest {
     }
 }
 
+#[cfg(all(feature = "ms", feature = "defmt"))]
+impl defmt::Format for AttPrepareWritePermitRequest {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "{{.conn_handle = {=u16:#x}, .attribute_handle = {}, .offset = {=usize}, \
+             .value = {=[u8]:#x}}}",
+            self.conn_handle.0,
+            self.attribute_handle,
+            self.offset,
+            self.value()
+        )
+    }
+}
+
+#[cfg(all(feature = "ms", feature = "serde"))]
+impl serde::Serialize for AttPrepareWritePermitRequest {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        use serde::ser::SerializeStruct;
+
+        let mut state = serializer.serialize_struct("AttPrepareWritePermitRequest", 4)?;
+        state.serialize_field("conn_handle", &self.conn_handle.0)?;
+        state.serialize_field("attribute_handle", &self.attribute_handle)?;
+        state.serialize_field("offset", &self.offset)?;
+        state.serialize_field("value", self.value())?;
+        state.end()
+    }
+}
+
 #[cfg(feature = "ms")]
 impl AttPrepareWritePermitRequest {
     /// Returns the data to be written.
@@ -2900,6 +5181,7 @@ fn to_att_prepare_write_permit_request(
 
     let data_len = buffer[8] as usize;
     require_len!(buffer, 9 + data_len);
+    require_payload_fits!(data_len, MAX_PREPARE_WRITE_PERMIT_REQ_VALUE_LEN);
 
     let mut value_buf = [0; MAX_PREPARE_WRITE_PERMIT_REQ_VALUE_LEN];
     value_buf[..data_len].copy_from_slice(&buffer[9..]);
@@ -2911,3 +5193,16 @@ fn to_att_prepare_write_permit_request(
         value_buf,
     })
 }
+
+#[cfg(feature = "ms")]
+impl AttPrepareWritePermitRequest {
+    fn copy_into_slice(&self, bytes: &mut [u8]) -> usize {
+        write_conn_handle(bytes, self.conn_handle);
+        LittleEndian::write_u16(&mut bytes[2..4], self.attribute_handle.0);
+        LittleEndian::write_u16(&mut bytes[4..6], self.offset as u16);
+        bytes[6] = self.value_len as u8;
+        bytes[7..7 + self.value_len].copy_from_slice(self.value());
+
+        7 + self.value_len
+    }
+}
@@ -0,0 +1,265 @@
+//! Capabilities of the controller firmware.
+//!
+//! Not every firmware revision implements every command and event in this crate. Firmware that
+//! does not know a command may answer it with [`InvalidOperation`](crate::event::Status::InvalidOperation)
+//! or not answer at all, which leaves the application waiting for a Command Complete event that
+//! never comes. [`Capabilities`] describes what a firmware revision supports. It is derived from
+//! the version in the [local version information](crate::LocalVersionInfoExt::bluenrg_version), or
+//! the application states the firmware version with [`Capabilities::for_firmware`].
+//!
+//! [`Checked`] implements [`CommandWriter`](crate::CommandWriter), so all of the
+//! [`gap`](crate::gap), [`gatt`](crate::gatt), [`hal`](crate::hal), and [`l2cap`](crate::l2cap)
+//! commands can be issued to it. It refuses commands that the firmware does not support before
+//! anything is sent to the controller:
+//!
+//! ```
+//! # extern crate bluetooth_hci as hci;
+//! # extern crate nb;
+//! # use bluenrg::CommandWriter;
+//! # struct Controller;
+//! # impl CommandWriter for Controller {
+//! #     type Error = ();
+//! #     fn write_command(&mut self, _: hci::Opcode, _: &[u8]) -> nb::Result<(), ()> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # let mut controller = Controller;
+//! # #[cfg(all(feature = "ms", not(feature = "bluenrg-1-2")))]
+//! # {
+//! use bluenrg::firmware::{Capabilities, Checked, Error};
+//! use bluenrg::gatt::{CharacteristicHandle, Commands as GattCommands};
+//!
+//! // BlueNRG firmware 6.4 does not implement GATT Read Handle Value Offset.
+//! let capabilities = Capabilities::for_firmware(6, 4, 0);
+//! let mut checked = Checked::new(&mut controller, capabilities);
+//! assert_eq!(
+//!     checked.read_handle_value_offset(CharacteristicHandle(0x0201), 0),
+//!     Err(nb::Error::Other(bluenrg::gatt::Error::Comm(
+//!         Error::Unsupported(hci::Opcode(0xFD2B))
+//!     )))
+//! );
+//! # }
+//! ```
+
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+use crate::event::BlueNRGEvent;
+
+bitflags! {
+    /// Commands and events that only some firmware revisions support. Everything not listed here
+    /// is supported by all firmware revisions.
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct Features: u32 {
+        /// [GAP Set Broadcast Mode](crate::gap::Commands::set_broadcast_mode) and [GAP Start
+        /// Observation Procedure](crate::gap::Commands::start_observation_procedure). BlueNRG-MS
+        /// firmware only.
+        const GAP_BROADCAST_AND_OBSERVATION = 1 << 0;
+        /// [GATT Read Handle Value Offset](crate::gatt::Commands::read_handle_value_offset) and
+        /// [GATT Update Long Characteristic
+        /// Value](crate::gatt::Commands::update_long_characteristic_value). BlueNRG-MS firmware
+        /// only.
+        const GATT_LONG_VALUES = 1 << 1;
+        /// [GATT TX Pool Available](BlueNRGEvent::GattTxPoolAvailable) event. BlueNRG-MS firmware
+        /// only.
+        const GATT_TX_POOL_AVAILABLE = 1 << 2;
+        /// [GATT Server Confirmation](BlueNRGEvent::GattServerConfirmation) event. BlueNRG-MS
+        /// firmware only.
+        const GATT_SERVER_CONFIRMATION = 1 << 3;
+        /// [ATT Prepare Write Permit Request](BlueNRGEvent::AttPrepareWritePermitRequest) event.
+        /// BlueNRG-MS firmware only.
+        const ATT_PREPARE_WRITE_PERMIT_REQUEST = 1 << 4;
+        /// [GATT Add Characteristic](crate::gatt::Commands::add_characteristic) takes a two-byte
+        /// characteristic value length. Firmware 7.2 and later; clear
+        /// [`fw_version_before_v72`](crate::gatt::AddCharacteristicParameters::fw_version_before_v72)
+        /// when this is set.
+        const LONG_CHARACTERISTIC_VALUE_LENGTH = 1 << 5;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(Features, u32);
+
+/// Version of the controller firmware, compared in (major, minor, patch) order.
+type FirmwareVersion = (u8, u8, u8);
+
+/// Firmware version that introduced each feature. BlueNRG-MS firmware has major version 7 (see
+/// [`Version::variant`](crate::Version::variant)), and the commands and events that only the
+/// BlueNRG-MS [variant](crate::Variant) implements are listed from 7.0. The two-byte
+/// characteristic value length matches
+/// [`fw_version_before_v72`](crate::gatt::AddCharacteristicParameters::fw_version_before_v72).
+const FEATURE_TABLE: [(Features, FirmwareVersion); 6] = [
+    (Features::GAP_BROADCAST_AND_OBSERVATION, (7, 0, 0)),
+    (Features::GATT_LONG_VALUES, (7, 0, 0)),
+    (Features::GATT_TX_POOL_AVAILABLE, (7, 0, 0)),
+    (Features::GATT_SERVER_CONFIRMATION, (7, 0, 0)),
+    (Features::ATT_PREPARE_WRITE_PERMIT_REQUEST, (7, 0, 0)),
+    (Features::LONG_CHARACTERISTIC_VALUE_LENGTH, (7, 2, 0)),
+];
+
+/// Commands and events supported by a firmware revision.
+///
+/// The table covers the BlueNRG and BlueNRG-MS firmware. The BlueNRG-1 and BlueNRG-2 firmware
+/// (major versions 2 and 3) implements everything, so with the `bluenrg-1-2` feature every version
+/// has all features. [`Capabilities::default`] assumes that everything is supported, which is how
+/// the crate behaves when the firmware version is not known.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capabilities {
+    /// Commands and events that the firmware supports.
+    pub features: Features,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Capabilities {
+            features: Features::all(),
+        }
+    }
+}
+
+impl Capabilities {
+    /// Returns the capabilities of the given firmware version.
+    pub fn for_firmware(major: u8, minor: u8, patch: u8) -> Capabilities {
+        if cfg!(feature = "bluenrg-1-2") {
+            return Capabilities::default();
+        }
+
+        let version = (major, minor, patch);
+        let mut capabilities = Capabilities {
+            features: Features::empty(),
+        };
+        for &(feature, since) in FEATURE_TABLE.iter() {
+            if version >= since {
+                capabilities.features |= feature;
+            }
+        }
+
+        capabilities
+    }
+
+    /// Returns the capabilities of the firmware that reported the given version. See
+    /// [`LocalVersionInfoExt::bluenrg_version`](crate::LocalVersionInfoExt::bluenrg_version).
+    pub fn from_version(version: &crate::Version) -> Capabilities {
+        Capabilities::for_firmware(version.major, version.minor, version.patch)
+    }
+
+    /// Returns true if the firmware supports the command with the given opcode. Commands that are
+    /// not vendor-specific are always considered supported.
+    pub fn supports_command(&self, opcode: hci::Opcode) -> bool {
+        match command_feature(opcode) {
+            Some(feature) => self.features.contains(feature),
+            None => true,
+        }
+    }
+
+    /// Returns true if the firmware can generate the given event.
+    pub fn supports_event(&self, event: &BlueNRGEvent) -> bool {
+        match event_feature(event) {
+            Some(feature) => self.features.contains(feature),
+            None => true,
+        }
+    }
+}
+
+fn command_feature(opcode: hci::Opcode) -> Option<Features> {
+    match opcode {
+        crate::opcode::GAP_SET_BROADCAST_MODE | crate::opcode::GAP_START_OBSERVATION_PROCEDURE => {
+            Some(Features::GAP_BROADCAST_AND_OBSERVATION)
+        }
+        crate::opcode::GATT_READ_HANDLE_VALUE_OFFSET
+        | crate::opcode::GATT_UPDATE_LONG_CHARACTERISTIC_VALUE => Some(Features::GATT_LONG_VALUES),
+        _ => None,
+    }
+}
+
+fn event_feature(event: &BlueNRGEvent) -> Option<Features> {
+    match event {
+        #[cfg(feature = "ms")]
+        BlueNRGEvent::GattTxPoolAvailable(_) => Some(Features::GATT_TX_POOL_AVAILABLE),
+        #[cfg(feature = "ms")]
+        BlueNRGEvent::GattServerConfirmation(_) => Some(Features::GATT_SERVER_CONFIRMATION),
+        #[cfg(feature = "ms")]
+        BlueNRGEvent::AttPrepareWritePermitRequest(_) => {
+            Some(Features::ATT_PREPARE_WRITE_PERMIT_REQUEST)
+        }
+        _ => None,
+    }
+}
+
+/// Potential errors from commands issued to a [`Checked`] writer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error<E> {
+    /// The firmware does not support the command. Includes the opcode of the command, which was
+    /// not sent.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::OpcodeDef"))]
+    Unsupported(hci::Opcode),
+
+    /// Underlying error of the wrapped writer.
+    Comm(E),
+}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for Error<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::Unsupported(opcode) => {
+                defmt::write!(f, "Unsupported({})", crate::defmt_remote::Remote(opcode))
+            }
+            Error::Comm(e) => defmt::write!(f, "Comm({})", e),
+        }
+    }
+}
+
+/// [`CommandWriter`](crate::CommandWriter) that refuses commands the firmware does not support,
+/// and passes all others through to the wrapped writer.
+///
+/// Commands that validate their parameters report the refusal as the communication error of their
+/// command group, for example `hal::Error::Comm(firmware::Error::Unsupported(opcode))`.
+pub struct Checked<'w, W> {
+    writer: &'w mut W,
+    capabilities: Capabilities,
+}
+
+impl<'w, W> Checked<'w, W> {
+    /// Returns a writer that checks each command against the given capabilities before passing it
+    /// to `writer`.
+    pub fn new(writer: &'w mut W, capabilities: Capabilities) -> Checked<'w, W> {
+        Checked {
+            writer,
+            capabilities,
+        }
+    }
+
+    /// Returns the capabilities that commands are checked against.
+    pub fn capabilities(&self) -> Capabilities {
+        self.capabilities
+    }
+
+    /// Returns the wrapped writer, for commands that should not be checked.
+    pub fn writer(&mut self) -> &mut W {
+        self.writer
+    }
+}
+
+impl<'w, W> crate::CommandWriter for Checked<'w, W>
+where
+    W: crate::CommandWriter,
+{
+    type Error = Error<W::Error>;
+
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), Self::Error> {
+        if !self.capabilities.supports_command(opcode) {
+            return Err(nb::Error::Other(Error::Unsupported(opcode)));
+        }
+
+        self.writer
+            .write_command(opcode, params)
+            .map_err(|e| match e {
+                nb::Error::WouldBlock => nb::Error::WouldBlock,
+                nb::Error::Other(e) => nb::Error::Other(Error::Comm(e)),
+            })
+    }
+}
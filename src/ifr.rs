@@ -0,0 +1,585 @@
+//! Device configuration stored in the information register (IFR).
+//!
+//! The IFR is a flash sector after the Bluetooth stack that holds the radio configuration: the
+//! controller role, the accuracy of the sleep clock, and the startup time of the high-speed crystal,
+//! among calibration data that must be preserved. The controller reads it at reset, so a new
+//! configuration takes effect after the next reboot.
+//!
+//! The IFR can only be accessed in [updater mode](crate::updater::Commands::updater_start). To
+//! change the configuration, read the IFR with [`IfrReader`], change its
+//! [configuration](Ifr::set_config), and write it back with [`IfrWriter`]:
+//!
+//! ```
+//! # use bluenrg::ifr::{Ifr, Error};
+//! # use core::time::Duration;
+//! fn tune(ifr: &mut Ifr) -> Result<(), Error> {
+//!     let mut config = ifr.config()?;
+//!     config.hs_startup_time = Duration::from_micros(1000);
+//!     ifr.set_config(&config)
+//! }
+//! ```
+//!
+//! The layout is that of the BlueNRG-MS.
+
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+use crate::event::command::ReturnParameters;
+use crate::hal::Role;
+use crate::updater::{self, FlashError};
+use byteorder::{ByteOrder, LittleEndian};
+use core::cmp::min;
+use core::convert::TryFrom;
+use core::time::Duration;
+
+/// Address of the IFR in the controller flash.
+pub const BASE_ADDRESS: u32 = 0x1002_0000;
+
+/// Length of the IFR, in bytes.
+pub const IFR_LEN: usize = 192;
+
+// The configuration is in the last block of the IFR; the blocks before it hold calibration data.
+const CONFIG_OFFSET: usize = 128;
+
+// The IFR is read in blocks of this length, so the data fits in the return parameters even if the
+// event payload is limited.
+const READ_LEN: usize = crate::event::payload_limit(64);
+
+/// Contents of the IFR.
+#[derive(Copy, Clone, PartialEq)]
+pub struct Ifr {
+    bytes: [u8; IFR_LEN],
+}
+
+impl core::fmt::Debug for Ifr {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        write!(f, "{:?}", self.as_bytes())
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Ifr {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{=[u8]:#x}", self.as_bytes())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Ifr {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serde::Serialize::serialize(self.as_bytes(), serializer)
+    }
+}
+
+impl Ifr {
+    /// Returns the IFR with the given contents, for example from a file saved by ST's tools.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`BadLength`](Error::BadLength) if `bytes` is not [`IFR_LEN`] bytes long. The
+    /// configuration is not validated until it is [read](Ifr::config).
+    pub fn from_bytes(bytes: &[u8]) -> Result<Ifr, Error> {
+        if bytes.len() != IFR_LEN {
+            return Err(Error::BadLength(bytes.len(), IFR_LEN));
+        }
+
+        let mut ifr = Ifr {
+            bytes: [0; IFR_LEN],
+        };
+        ifr.bytes.copy_from_slice(bytes);
+
+        Ok(ifr)
+    }
+
+    /// Returns the contents of the IFR, as they are stored in flash.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Returns the configuration stored in the IFR.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any field of the configuration is out of range. An erased IFR has no
+    /// valid configuration.
+    pub fn config(&self) -> Result<Config, Error> {
+        let bytes = &self.bytes[CONFIG_OFFSET..];
+        let config = Config {
+            role: to_role(bytes[0])?,
+            peripheral_sleep_clock_accuracy: LittleEndian::read_u16(&bytes[28..30]),
+            central_sleep_clock_accuracy: to_central_clock_accuracy(bytes[30])?,
+            hs_startup_time: to_hs_startup_time(LittleEndian::read_u16(&bytes[32..34])),
+            date: Date {
+                year: from_bcd(bytes[41])?,
+                month: from_bcd(bytes[42])?,
+                day: from_bcd(bytes[43])?,
+            },
+        };
+        config.validate()?;
+
+        Ok(config)
+    }
+
+    /// Replaces the configuration stored in the IFR. The rest of the IFR is unchanged.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any field of the configuration is out of range; the IFR is not changed.
+    pub fn set_config(&mut self, config: &Config) -> Result<(), Error> {
+        config.validate()?;
+
+        let bytes = &mut self.bytes[CONFIG_OFFSET..];
+        bytes[0] = config.role as u8;
+        LittleEndian::write_u16(&mut bytes[28..30], config.peripheral_sleep_clock_accuracy);
+        bytes[30] = from_central_clock_accuracy(config.central_sleep_clock_accuracy);
+        LittleEndian::write_u16(
+            &mut bytes[32..34],
+            from_hs_startup_time(config.hs_startup_time) as u16,
+        );
+        bytes[41] = to_bcd(config.date.year);
+        bytes[42] = to_bcd(config.date.month);
+        bytes[43] = to_bcd(config.date.day);
+
+        Ok(())
+    }
+}
+
+/// Radio configuration stored in the [IFR](Ifr).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Config {
+    /// Role of the controller, which trades the number of connections for the RAM available for
+    /// other uses.
+    pub role: Role,
+
+    /// Accuracy of the sleep clock when the controller is a peripheral, in parts-per-million. Must
+    /// not be more than 500.
+    pub peripheral_sleep_clock_accuracy: u16,
+
+    /// Accuracy of the sleep clock when the controller is a central.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::central_clock_accuracy")
+    )]
+    pub central_sleep_clock_accuracy: hci::event::CentralClockAccuracy,
+
+    /// Time the high-speed crystal needs to start up. If it is too short, the controller reports
+    /// [`RadioState`](crate::HardwareError::RadioState) or
+    /// [`TimerOverrun`](crate::HardwareError::TimerOverrun) hardware errors. The range is from
+    /// 2.44 µs to 160 ms, with a resolution of 2.44 µs (625/256 µs).
+    pub hs_startup_time: Duration,
+
+    /// Date of the configuration.
+    pub date: Date,
+}
+
+impl Config {
+    const MAX_PERIPHERAL_SLEEP_CLOCK_ACCURACY: u16 = 500;
+
+    /// Checks that every field is in range.
+    ///
+    /// # Errors
+    ///
+    /// - [`BadPeripheralSleepClockAccuracy`](Error::BadPeripheralSleepClockAccuracy) if the
+    ///   peripheral sleep clock accuracy is more than 500 ppm.
+    /// - [`BadHsStartupTime`](Error::BadHsStartupTime) if the HS startup time is out of range.
+    /// - [`BadDate`](Error::BadDate) if the date does not exist.
+    pub fn validate(&self) -> Result<(), Error> {
+        if self.peripheral_sleep_clock_accuracy > Self::MAX_PERIPHERAL_SLEEP_CLOCK_ACCURACY {
+            return Err(Error::BadPeripheralSleepClockAccuracy(
+                self.peripheral_sleep_clock_accuracy,
+            ));
+        }
+
+        let hs_startup_time = from_hs_startup_time(self.hs_startup_time);
+        if hs_startup_time == 0 || hs_startup_time > u128::from(u16::MAX) {
+            return Err(Error::BadHsStartupTime(self.hs_startup_time));
+        }
+
+        self.date.validate()
+    }
+}
+
+/// Date stored in the [IFR](Ifr) [configuration](Config).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Date {
+    /// Year, from 0 to 99.
+    pub year: u8,
+
+    /// Month, from 1 to 12.
+    pub month: u8,
+
+    /// Day of the month, from 1 to 31.
+    pub day: u8,
+}
+
+impl Date {
+    fn validate(&self) -> Result<(), Error> {
+        if self.year > 99 || !(1..=12).contains(&self.month) || !(1..=31).contains(&self.day) {
+            return Err(Error::BadDate(*self));
+        }
+
+        Ok(())
+    }
+}
+
+/// Potential errors from parsing or validating the [IFR](Ifr).
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Error {
+    /// The IFR is not the right length. Includes the actual and expected lengths.
+    BadLength(usize, usize),
+
+    /// The [role](Role) was not recognized. Includes the invalid value.
+    BadRole(u8),
+
+    /// The peripheral sleep clock accuracy is more than 500 ppm. Includes the invalid value.
+    BadPeripheralSleepClockAccuracy(u16),
+
+    /// The central sleep clock accuracy was not recognized. Includes the invalid value.
+    BadCentralSleepClockAccuracy(u8),
+
+    /// The HS startup time is out of range. Includes the invalid value.
+    BadHsStartupTime(Duration),
+
+    /// A field of the date was not a valid binary-coded decimal. Includes the invalid byte.
+    BadBcd(u8),
+
+    /// The date does not exist. Includes the invalid date.
+    BadDate(Date),
+}
+
+fn to_role(value: u8) -> Result<Role, Error> {
+    match value {
+        1 => Ok(Role::Peripheral6Kb),
+        2 => Ok(Role::Peripheral12Kb),
+        3 => Ok(Role::Primary12Kb),
+        4 => Ok(Role::SimultaneousAdvertisingScanning),
+        _ => Err(Error::BadRole(value)),
+    }
+}
+
+fn to_central_clock_accuracy(value: u8) -> Result<hci::event::CentralClockAccuracy, Error> {
+    hci::event::CentralClockAccuracy::try_from(value)
+        .map_err(|_| Error::BadCentralSleepClockAccuracy(value))
+}
+
+fn from_central_clock_accuracy(accuracy: hci::event::CentralClockAccuracy) -> u8 {
+    use hci::event::CentralClockAccuracy::*;
+
+    match accuracy {
+        Ppm500 => 0,
+        Ppm250 => 1,
+        Ppm150 => 2,
+        Ppm100 => 3,
+        Ppm75 => 4,
+        Ppm50 => 5,
+        Ppm30 => 6,
+        Ppm20 => 7,
+    }
+}
+
+// The HS startup time is stored in units of 625/256 µs.
+const HS_STARTUP_TIME_UNIT_NUMERATOR: u128 = 625_000;
+const HS_STARTUP_TIME_UNIT_DENOMINATOR: u128 = 256;
+
+fn to_hs_startup_time(value: u16) -> Duration {
+    Duration::from_nanos(
+        (u128::from(value) * HS_STARTUP_TIME_UNIT_NUMERATOR / HS_STARTUP_TIME_UNIT_DENOMINATOR)
+            as u64,
+    )
+}
+
+// Rounds to the nearest unit, so durations returned by to_hs_startup_time convert back exactly.
+fn from_hs_startup_time(time: Duration) -> u128 {
+    (time.as_nanos() * HS_STARTUP_TIME_UNIT_DENOMINATOR + HS_STARTUP_TIME_UNIT_NUMERATOR / 2)
+        / HS_STARTUP_TIME_UNIT_NUMERATOR
+}
+
+fn from_bcd(value: u8) -> Result<u8, Error> {
+    if value >> 4 > 9 || value & 0xF > 9 {
+        return Err(Error::BadBcd(value));
+    }
+
+    Ok((value >> 4) * 10 + (value & 0xF))
+}
+
+fn to_bcd(value: u8) -> u8 {
+    ((value / 10) << 4) | (value % 10)
+}
+
+/// Reads the [IFR](Ifr) from the controller, which must be in updater mode.
+///
+/// Once the last block has been read, [`poll`](IfrReader::poll) returns the IFR instead of `()`;
+/// otherwise the reader is driven like the other
+/// [nonblocking procedures](crate#nonblocking-procedures).
+pub struct IfrReader {
+    bytes: [u8; IFR_LEN],
+    offset: usize,
+    awaiting: bool,
+    failure: Option<FlashError<crate::NoComm>>,
+}
+
+impl IfrReader {
+    /// Returns a reader that starts at the beginning of the IFR.
+    pub fn new() -> IfrReader {
+        IfrReader {
+            bytes: [0; IFR_LEN],
+            offset: 0,
+            awaiting: false,
+            failure: None,
+        }
+    }
+
+    /// Sends the next read command to the controller, unless the reader is waiting for the Command
+    /// Complete event of the previous one.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] while reading is in progress.
+    /// - Returns a [`FlashError`] if reading failed.
+    pub fn poll<C>(&mut self, controller: &mut C) -> nb::Result<Ifr, FlashError<C::Error>>
+    where
+        C: updater::Commands,
+    {
+        if let Some(failure) = self.failure {
+            return Err(nb::Error::Other(FlashError::widen(failure)));
+        }
+        if self.offset == IFR_LEN {
+            return Ok(Ifr { bytes: self.bytes });
+        }
+        if self.awaiting {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        match controller.read_data_block(self.address(), READ_LEN) {
+            Ok(()) => {
+                self.awaiting = true;
+                Err(nb::Error::WouldBlock)
+            }
+            Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+            Err(nb::Error::Other(e)) => Err(nb::Error::Other(FlashError::Command(e))),
+        }
+    }
+
+    /// Stores the data returned by the read command that was sent last. Other events are ignored.
+    pub fn handle_event(&mut self, event: &hci::Event<crate::event::BlueNRGEvent>) {
+        if !self.awaiting {
+            return;
+        }
+        let data = match *event {
+            hci::Event::CommandComplete(ref event) => match event.return_params {
+                hci::event::command::ReturnParameters::Vendor(
+                    ReturnParameters::UpdaterReadDataBlock(ref data),
+                ) => data,
+                _ => return,
+            },
+            _ => return,
+        };
+
+        self.awaiting = false;
+        if data.status != hci::Status::Success {
+            self.failure = Some(FlashError::CommandFailed(
+                crate::opcode::UPDATER_READ_DATA_BLOCK,
+                data.status,
+            ));
+        } else if data.data().len() < READ_LEN {
+            self.failure = Some(FlashError::ShortRead(self.address()));
+        } else {
+            self.bytes[self.offset..self.offset + READ_LEN]
+                .copy_from_slice(&data.data()[..READ_LEN]);
+            self.offset += READ_LEN;
+        }
+    }
+
+    fn address(&self) -> u32 {
+        BASE_ADDRESS + self.offset as u32
+    }
+}
+
+impl Default for IfrReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum WriteStep {
+    GetBufferSize,
+    EraseBlueFlag,
+    EraseSector,
+    Program,
+    Verify,
+    ResetBlueFlag,
+    Done,
+}
+
+/// Writes the [IFR](Ifr) to the controller, which must be in updater mode.
+///
+/// The writer reads the size of the updater buffer, erases the BLUE flag, erases and programs the
+/// IFR in blocks that fit in the buffer, verifies it, and resets the BLUE flag. If writing is
+/// interrupted, the controller restarts in updater mode, and the IFR can be written again. The new
+/// configuration takes effect once the controller is
+/// [rebooted](crate::updater::Commands::updater_reboot).
+///
+/// Drive the writer with [`poll`](IfrWriter::poll) and [`handle_event`](IfrWriter::handle_event),
+/// as described in [nonblocking procedures](crate#nonblocking-procedures).
+pub struct IfrWriter {
+    ifr: Ifr,
+    step: WriteStep,
+    block_len: usize,
+    offset: usize,
+    awaiting: bool,
+    failure: Option<FlashError<crate::NoComm>>,
+}
+
+impl IfrWriter {
+    /// Returns a writer for the given IFR. Write an IFR that was read from the controller, so that
+    /// its calibration data is preserved.
+    pub fn new(ifr: &Ifr) -> IfrWriter {
+        IfrWriter {
+            ifr: *ifr,
+            step: WriteStep::GetBufferSize,
+            block_len: 0,
+            offset: 0,
+            awaiting: false,
+            failure: None,
+        }
+    }
+
+    /// Returns true if the IFR has been written and verified, and the BLUE flag reset.
+    pub fn is_done(&self) -> bool {
+        self.step == WriteStep::Done
+    }
+
+    /// Sends the next command to the controller, unless the writer is waiting for the Command
+    /// Complete event of the previous one.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] while writing is in progress.
+    /// - Returns a [`FlashError`] if writing failed. Writing can be retried with a new `IfrWriter`.
+    pub fn poll<C>(&mut self, controller: &mut C) -> nb::Result<(), FlashError<C::Error>>
+    where
+        C: updater::Commands,
+    {
+        if let Some(failure) = self.failure {
+            return Err(nb::Error::Other(FlashError::widen(failure)));
+        }
+        if self.step == WriteStep::Done {
+            return Ok(());
+        }
+        if self.awaiting {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let result = match self.step {
+            WriteStep::GetBufferSize => controller
+                .get_updater_buffer_size()
+                .map_err(updater::rewrap_error),
+            WriteStep::EraseBlueFlag => controller.erase_blue_flag().map_err(updater::rewrap_error),
+            WriteStep::EraseSector => controller
+                .erase_sector(BASE_ADDRESS)
+                .map_err(updater::rewrap_error),
+            WriteStep::Program => controller.program_data_block(
+                BASE_ADDRESS + self.offset as u32,
+                &self.ifr.bytes[self.offset..min(self.offset + self.block_len, IFR_LEN)],
+            ),
+            WriteStep::Verify => controller
+                .calculate_crc(BASE_ADDRESS, 1)
+                .map_err(updater::rewrap_error),
+            WriteStep::ResetBlueFlag => controller.reset_blue_flag().map_err(updater::rewrap_error),
+            WriteStep::Done => Ok(()),
+        };
+        match result {
+            Ok(()) => {
+                self.awaiting = true;
+                Err(nb::Error::WouldBlock)
+            }
+            Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+            Err(nb::Error::Other(e)) => Err(nb::Error::Other(FlashError::Command(e))),
+        }
+    }
+
+    /// Advances writing with the return parameters of the command that was sent last. Events that
+    /// do not complete that command are ignored.
+    pub fn handle_event(&mut self, event: &hci::Event<crate::event::BlueNRGEvent>) {
+        if !self.awaiting {
+            return;
+        }
+        if let hci::Event::CommandComplete(ref event) = *event {
+            if let hci::event::command::ReturnParameters::Vendor(ref params) = event.return_params {
+                self.handle_return_parameters(params);
+            }
+        }
+    }
+
+    fn handle_return_parameters(&mut self, params: &ReturnParameters) {
+        let (opcode, status) = match (self.step, params) {
+            (WriteStep::GetBufferSize, ReturnParameters::UpdaterGetBufferSize(params)) => {
+                (crate::opcode::UPDATER_GET_BUFFER_SIZE, params.status)
+            }
+            (WriteStep::EraseBlueFlag, ReturnParameters::UpdaterEraseBlueFlag(status)) => {
+                (crate::opcode::UPDATER_ERASE_BLUE_FLAG, *status)
+            }
+            (WriteStep::EraseSector, ReturnParameters::UpdaterEraseSector(status)) => {
+                (crate::opcode::UPDATER_ERASE_SECTOR, *status)
+            }
+            (WriteStep::Program, ReturnParameters::UpdaterProgramDataBlock(status)) => {
+                (crate::opcode::UPDATER_PROGRAM_DATA_BLOCK, *status)
+            }
+            (WriteStep::Verify, ReturnParameters::UpdaterCalculateCrc(params)) => {
+                (crate::opcode::UPDATER_CALC_CRC, params.status)
+            }
+            (WriteStep::ResetBlueFlag, ReturnParameters::UpdaterResetBlueFlag(status)) => {
+                (crate::opcode::UPDATER_RESET_BLUE_FLAG, *status)
+            }
+            _ => return,
+        };
+
+        self.awaiting = false;
+        if status != hci::Status::Success {
+            self.failure = Some(FlashError::CommandFailed(opcode, status));
+            return;
+        }
+
+        self.step = match (self.step, params) {
+            (WriteStep::GetBufferSize, ReturnParameters::UpdaterGetBufferSize(params)) => {
+                // Program whole words, as the flasher does.
+                self.block_len = min(params.buffer_size, updater::MAX_DATA_BLOCK_LEN) & !3;
+                if self.block_len == 0 {
+                    self.failure = Some(FlashError::BufferTooSmall(params.buffer_size));
+                    return;
+                }
+                WriteStep::EraseBlueFlag
+            }
+            (WriteStep::EraseBlueFlag, _) => WriteStep::EraseSector,
+            (WriteStep::EraseSector, _) => WriteStep::Program,
+            (WriteStep::Program, _) => {
+                self.offset = min(self.offset + self.block_len, IFR_LEN);
+                if self.offset == IFR_LEN {
+                    WriteStep::Verify
+                } else {
+                    WriteStep::Program
+                }
+            }
+            (WriteStep::Verify, ReturnParameters::UpdaterCalculateCrc(params)) => {
+                if params.crc != updater::sector_crc(self.ifr.as_bytes()) {
+                    self.failure = Some(FlashError::VerifyFailed(BASE_ADDRESS));
+                    return;
+                }
+                WriteStep::ResetBlueFlag
+            }
+            (WriteStep::ResetBlueFlag, _) => WriteStep::Done,
+            (step, _) => step,
+        };
+    }
+}
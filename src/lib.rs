@@ -1,6 +1,9 @@
 //! Bluetooth HCI for STMicro's BlueNRG-MS Bluetooth controllers.
 //!
-//! *Note*: This crate does not provide support for the BlueNRG-1 or BlueNRG-2 SoCs.
+//! *Note*: The BlueNRG-1 and BlueNRG-2 SoCs are supported only when running the network coprocessor
+//! firmware, and only with the `bluenrg-1-2` feature enabled. That feature adds the commands and
+//! events that are specific to those chips, and changes the layout of
+//! [`AuthenticationRequirements`](gap::AuthenticationRequirements) to match their firmware.
 //!
 //! # Design
 //!
@@ -23,7 +26,9 @@
 //! # Vendor-Specific Commands
 //!
 //! BlueNRG-MS provides several vendor-specific commands that control the behavior of the
-//! controller.
+//! controller. Commands that this crate does not define can be sent through [`raw`].
+//! Serialized commands can be decoded back into their parameters with
+//! [`decode::VendorCommand`].
 //!
 //! # Vendor-Specific Events
 //!
@@ -31,6 +36,15 @@
 //! controller. Many of these events are forwarded from the link layer, and these are documented
 //! with a reference to the appropriate section of the Bluetooth specification.
 //!
+//! # Nonblocking Procedures
+//!
+//! Procedures that take more than one command, such as [flashing](updater::Flasher) firmware or
+//! [provisioning](provision::Provisioner) an identity, are state machines that never wait for the
+//! controller. Their `poll` function sends the next command once the previous one has completed,
+//! and their `handle_event` function takes every event read from the controller, ignoring the
+//! events that do not concern them. Call both from the event loop until `poll` returns `Ok`. A
+//! failure found while handling an event is returned by the next call to `poll`.
+//!
 //! # Example
 //!
 //! TODO
@@ -53,23 +67,50 @@ use byteorder::{ByteOrder, LittleEndian};
 use core::cmp::min;
 use core::convert::TryFrom;
 use core::marker::PhantomData;
+#[cfg(feature = "ms")]
+use core::sync::atomic::{AtomicU8, Ordering};
 use hci::host::HciHeader;
 use hci::Controller;
 
+#[cfg(feature = "serde")]
+#[macro_use]
+mod serde_remote;
+
+#[cfg(feature = "defmt")]
+mod defmt_remote;
+
 mod cb;
 mod command;
+#[cfg(feature = "ms")]
+pub mod crash;
 pub mod event;
+pub mod firmware;
+pub mod ifr;
+pub mod links;
 mod opcode;
-
+pub mod provision;
+pub mod queue;
+#[cfg(feature = "ms")]
+pub mod recovery;
+pub mod rftest;
+pub mod txpower;
+pub mod uuid;
+
+pub use command::decode;
 pub use command::gap;
 pub use command::gatt;
 pub use command::hal;
 pub use command::l2cap;
+pub use command::raw;
+pub use command::updater;
+pub use command::{CommandError, EncodeError, NoComm, ValidationError};
 
 pub use hci::host::{AdvertisingFilterPolicy, AdvertisingType, OwnAddressType};
 
 /// Enumeration of potential errors that may occur when reading from or writing to the chip.
 #[derive(Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Error<SpiError, GpioError> {
     /// SPI errors occur if there is an underlying error during a transfer.
     Spi(SpiError),
@@ -294,12 +335,39 @@ where
 
         Ok(())
     }
+}
 
-    fn write_command(
-        &mut self,
-        opcode: opcode::Opcode,
-        params: &[u8],
-    ) -> nb::Result<(), Error<SpiError, GpioError>> {
+/// Sink for serialized vendor-specific commands.
+///
+/// The [`gap`], [`gatt`], [`hal`], [`l2cap`], [`raw`], and [`updater`] command traits are
+/// implemented for every `CommandWriter`. [`ActiveBlueNRG`] writes each command to the controller immediately;
+/// [`queue::CommandQueue`] holds them until they can be [pumped](queue::CommandQueue::pump) to the
+/// controller.
+pub trait CommandWriter {
+    /// Type of errors that may occur while writing a command.
+    type Error;
+
+    /// Writes the command with the given opcode and serialized parameters.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] if the command cannot be accepted right now.
+    /// - Returns any underlying error of the writer.
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), Self::Error>;
+}
+
+impl<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, SpiError, GpioError> CommandWriter
+    for ActiveBlueNRG<'bnrg, 'spi, 'dbuf, SPI, OutputPin1, OutputPin2, InputPin, GpioError>
+where
+    SPI: emhal::blocking::spi::Transfer<u8, Error = SpiError>
+        + emhal::blocking::spi::Write<u8, Error = SpiError>,
+    OutputPin1: emhal::digital::v2::OutputPin<Error = GpioError>,
+    OutputPin2: emhal::digital::v2::OutputPin<Error = GpioError>,
+    InputPin: emhal::digital::v2::InputPin<Error = GpioError>,
+{
+    type Error = Error<SpiError, GpioError>;
+
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), Self::Error> {
         const HEADER_LEN: usize = 4;
         let mut header = [0; HEADER_LEN];
         hci::host::uart::CommandHeader::new(opcode, params.len()).copy_into_slice(&mut header);
@@ -424,6 +492,7 @@ pub trait UartController<E>:
     + crate::gatt::Commands<Error = E>
     + crate::hal::Commands<Error = E>
     + crate::l2cap::Commands<Error = E>
+    + crate::raw::Commands<Error = E>
     + bluetooth_hci::host::uart::Hci<E, crate::event::BlueNRGEvent, crate::event::BlueNRGError>
 {
 }
@@ -432,6 +501,7 @@ impl<T, E> UartController<E> for T where
         + crate::gatt::Commands<Error = E>
         + crate::hal::Commands<Error = E>
         + crate::l2cap::Commands<Error = E>
+        + crate::raw::Commands<Error = E>
         + bluetooth_hci::host::uart::Hci<E, crate::event::BlueNRGEvent, crate::event::BlueNRGError>
 {
 }
@@ -515,6 +585,100 @@ pub struct Version {
     pub patch: u8,
 }
 
+impl Version {
+    /// Returns the protocol variant that the controller firmware implements. BlueNRG firmware has
+    /// major version 6 or lower; BlueNRG-MS firmware has major version 7 or higher.
+    ///
+    /// With the `bluenrg-1-2` feature, the controller is a BlueNRG-1 or BlueNRG-2, whose firmware
+    /// (major version 2 or 3) always implements the BlueNRG-MS variant.
+    pub fn variant(&self) -> Variant {
+        if cfg!(feature = "bluenrg-1-2") || self.major >= 7 {
+            Variant::BlueNRGMS
+        } else {
+            Variant::BlueNRG
+        }
+    }
+
+    /// Returns the commands and events that the controller firmware supports.
+    pub fn capabilities(&self) -> firmware::Capabilities {
+        firmware::Capabilities::from_version(self)
+    }
+}
+
+/// Protocol variants of the controller firmware.
+///
+/// The BlueNRG and BlueNRG-MS differ in the parameters of a few commands, and in how a few events
+/// and return parameters are encoded. With the `ms` feature, the crate supports both, and the
+/// variant to use is selected at runtime with [`set_variant`]; without it, the crate only supports
+/// the BlueNRG.
+///
+/// The selected variant determines how command parameters are serialized, and how events and
+/// return parameters are deserialized. Commands that only the BlueNRG-MS supports return an
+/// `UnsupportedByVariant` error, without writing anything, while the BlueNRG is selected.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Variant {
+    /// The original BlueNRG.
+    BlueNRG,
+
+    /// The BlueNRG-MS.
+    BlueNRGMS,
+}
+
+#[cfg(feature = "ms")]
+static VARIANT: AtomicU8 = AtomicU8::new(Variant::BlueNRGMS as u8);
+
+/// Selects the protocol variant used to serialize commands and to deserialize events and return
+/// parameters. The BlueNRG-MS is selected until this is called.
+///
+/// The selection is global, because events are deserialized without any access to the controller.
+/// The variant can be detected from the [local version
+/// information](LocalVersionInfoExt::bluenrg_version):
+///
+/// ```
+/// # use bluenrg::{LocalVersionInfoExt, Variant};
+/// # let info = bluetooth_hci::event::command::LocalVersionInfo::<bluenrg::event::Status> {
+/// #     status: bluetooth_hci::Status::Success,
+/// #     hci_version: 6,
+/// #     hci_revision: 0x3106,
+/// #     lmp_version: 6,
+/// #     manufacturer_name: 0x30,
+/// #     lmp_subversion: 0x0041,
+/// # };
+/// // `info` is the return value of the Read Local Version Information command.
+/// bluenrg::set_variant(info.bluenrg_version().variant());
+/// # #[cfg(not(feature = "bluenrg-1-2"))]
+/// assert_eq!(bluenrg::variant(), Variant::BlueNRG);
+/// ```
+#[cfg(feature = "ms")]
+pub fn set_variant(variant: Variant) {
+    VARIANT.store(variant as u8, Ordering::Relaxed);
+}
+
+/// Returns the protocol variant selected with [`set_variant`]. Without the `ms` feature, this is
+/// always [`BlueNRG`](Variant::BlueNRG).
+pub fn variant() -> Variant {
+    #[cfg(feature = "ms")]
+    {
+        if VARIANT.load(Ordering::Relaxed) == Variant::BlueNRG as u8 {
+            Variant::BlueNRG
+        } else {
+            Variant::BlueNRGMS
+        }
+    }
+
+    #[cfg(not(feature = "ms"))]
+    {
+        Variant::BlueNRG
+    }
+}
+
+/// Returns true if the BlueNRG-MS protocol variant is selected.
+pub(crate) fn is_ms() -> bool {
+    variant() == Variant::BlueNRGMS
+}
+
 /// Extension trait to convert [`hci::event::command::LocalVersionInfo`] into the BlueNRG-specific
 /// [`Version`] struct.
 pub trait LocalVersionInfoExt {
@@ -536,24 +700,28 @@ impl<VS> LocalVersionInfoExt for hci::event::command::LocalVersionInfo<VS> {
 
 /// Hardware event codes returned by the `HardwareError` HCI event.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum HardwareError {
     /// Error on the SPI bus has been detected, most likely caused by incorrect SPI configuration on
     /// the external micro-controller.
     SpiFraming,
 
     /// Caused by a slow crystal startup and they are an indication that the HS_STARTUP_TIME in the
-    /// device configuration needs to be tuned. After this event is recommended to hardware reset
-    /// the device.
+    /// device configuration needs to be [tuned](crate::ifr::Config::hs_startup_time). After this
+    /// event is recommended to hardware reset the device.
     RadioState,
 
     /// Caused by a slow crystal startup and they are an indication that the HS_STARTUP_TIME in the
-    /// device configuration needs to be tuned. After this event is recommended to hardware reset
-    /// the device.
+    /// device configuration needs to be [tuned](crate::ifr::Config::hs_startup_time). After this
+    /// event is recommended to hardware reset the device.
     TimerOverrun,
 }
 
 /// Error type for `TryFrom<u8>` to `HardwareError`. Includes the invalid byte.
 #[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct InvalidHardwareError(pub u8);
 
 impl TryFrom<u8> for HardwareError {
@@ -0,0 +1,258 @@
+//! Tracking of the link layer state of the controller.
+//!
+//! [HAL Get Link Status](crate::hal::Commands::get_link_status) returns a snapshot of the 8 link
+//! slots of the controller. [`Tracker`] keeps a model of those slots between snapshots: it merges
+//! in the LE Connection Complete and Disconnection Complete events as they arrive, and compares
+//! each new snapshot with the model. Every difference is reported as a [`Change`].
+
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+use crate::event::command::{ClientStatus, HalLinkStatus, LinkState, ReturnParameters};
+use crate::hal::{self, Role};
+
+/// Number of link slots in the controller.
+pub const SLOTS: usize = 8;
+
+/// Notifications from the [`Tracker`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Change {
+    /// The state or connection handle of a slot changed.
+    Slot {
+        /// Index of the slot.
+        slot: usize,
+
+        /// State of the slot before the change.
+        previous: ClientStatus,
+
+        /// State of the slot after the change.
+        current: ClientStatus,
+    },
+
+    /// Every connection that the [role](Role) allows is in use, so no more connections can be
+    /// made. Includes the number of connections. This is reported once each time the limit is
+    /// reached.
+    ConnectionsExhausted(usize),
+}
+
+/// Changes reported by [`Tracker::handle_event`].
+#[derive(Clone, Debug)]
+pub struct Changes {
+    changes: [Option<Change>; SLOTS + 1],
+    next: usize,
+}
+
+impl Changes {
+    fn new() -> Changes {
+        Changes {
+            changes: [None; SLOTS + 1],
+            next: 0,
+        }
+    }
+
+    fn push(&mut self, change: Change) {
+        if let Some(entry) = self.changes.iter_mut().find(|entry| entry.is_none()) {
+            *entry = Some(change);
+        }
+    }
+}
+
+impl Iterator for Changes {
+    type Item = Change;
+
+    fn next(&mut self) -> Option<Change> {
+        let change = self.changes.get(self.next).copied().flatten();
+        if change.is_some() {
+            self.next += 1;
+        }
+
+        change
+    }
+}
+
+/// Model of the link slots of the controller.
+///
+/// Call [`refresh`](Tracker::refresh) to request a new snapshot, for example from a periodic
+/// timer, and [`rearm`](Tracker::rearm) if its response is lost. Pass every event from the
+/// controller to [`handle_event`](Tracker::handle_event). Connection events update the model
+/// immediately. The controller does not report which slot a new connection uses, so the tracker
+/// assumes the slot that was advertising (for peripherals) or scanning (for centrals), or else the
+/// first idle slot; the next snapshot corrects the guess.
+#[derive(Clone, Debug)]
+pub struct Tracker {
+    role: Role,
+    slots: [ClientStatus; SLOTS],
+    awaiting: bool,
+    exhausted: bool,
+}
+
+impl Tracker {
+    /// Returns a tracker for a controller configured with the given role. Every slot starts idle.
+    pub fn new(role: Role) -> Tracker {
+        Tracker {
+            role,
+            slots: [ClientStatus {
+                state: LinkState::Idle,
+                conn_handle: hci::ConnectionHandle(0),
+            }; SLOTS],
+            awaiting: false,
+            exhausted: false,
+        }
+    }
+
+    /// Returns the current model of the slots.
+    pub fn slots(&self) -> &[ClientStatus; SLOTS] {
+        &self.slots
+    }
+
+    /// Returns the index of the slot with the given connection, if it is connected.
+    pub fn slot(&self, conn_handle: hci::ConnectionHandle) -> Option<usize> {
+        self.slots
+            .iter()
+            .position(|slot| slot.state.is_connected() && slot.conn_handle == conn_handle)
+    }
+
+    /// Returns the number of slots in the given state.
+    pub fn count(&self, state: LinkState) -> usize {
+        self.slots.iter().filter(|slot| slot.state == state).count()
+    }
+
+    /// Returns the number of connected slots, in either role.
+    pub fn connections(&self) -> usize {
+        self.slots
+            .iter()
+            .filter(|slot| slot.state.is_connected())
+            .count()
+    }
+
+    /// Returns the number of connections that can still be made in the configured role.
+    pub fn available_connections(&self) -> usize {
+        self.role
+            .max_connections()
+            .saturating_sub(self.connections())
+    }
+
+    /// Requests a new snapshot of the slots with [HAL Get Link
+    /// Status](crate::hal::Commands::get_link_status), unless a request is already pending.
+    ///
+    /// # Errors
+    ///
+    /// Only underlying communication errors are reported.
+    pub fn refresh<C>(&mut self, controller: &mut C) -> nb::Result<(), C::Error>
+    where
+        C: hal::Commands,
+    {
+        if self.awaiting {
+            return Ok(());
+        }
+
+        controller.get_link_status()?;
+        self.awaiting = true;
+
+        Ok(())
+    }
+
+    /// Forgets the pending snapshot request, so that the next [`refresh`](Tracker::refresh) sends
+    /// a new one. Call this if the response is lost, for example if it does not arrive within a
+    /// timeout or the controller was reset; otherwise the tracker waits for it forever.
+    pub fn rearm(&mut self) {
+        self.awaiting = false;
+    }
+
+    /// Updates the model with an event from the controller, and returns the changes. Only link
+    /// status snapshots, LE Connection Complete, and Disconnection Complete events change the
+    /// model. Failed snapshots are ignored.
+    pub fn handle_event(&mut self, event: &hci::Event<crate::event::BlueNRGEvent>) -> Changes {
+        let mut changes = Changes::new();
+        match *event {
+            hci::Event::CommandComplete(ref event) => {
+                if let hci::event::command::ReturnParameters::Vendor(
+                    ReturnParameters::HalGetLinkStatus(ref status),
+                ) = event.return_params
+                {
+                    self.awaiting = false;
+                    self.update(status, &mut changes);
+                }
+            }
+            hci::Event::LeConnectionComplete(ref event) if event.status == hci::Status::Success => {
+                self.connect(event.conn_handle, event.role, &mut changes);
+            }
+            hci::Event::DisconnectionComplete(ref event)
+                if event.status == hci::Status::Success =>
+            {
+                if let Some(slot) = self.slot(event.conn_handle) {
+                    let current = ClientStatus {
+                        state: LinkState::Idle,
+                        ..self.slots[slot]
+                    };
+                    self.set(slot, current, &mut changes);
+                }
+            }
+            _ => (),
+        }
+
+        self.check_exhausted(&mut changes);
+        changes
+    }
+
+    fn update(&mut self, status: &HalLinkStatus, changes: &mut Changes) {
+        if status.status != hci::Status::Success {
+            return;
+        }
+
+        for (slot, &current) in status.clients.iter().enumerate() {
+            self.set(slot, current, changes);
+        }
+    }
+
+    fn connect(
+        &mut self,
+        conn_handle: hci::ConnectionHandle,
+        role: hci::event::ConnectionRole,
+        changes: &mut Changes,
+    ) {
+        let (before, state) = match role {
+            hci::event::ConnectionRole::Peripheral => {
+                (LinkState::Advertising, LinkState::ConnectedAsPeripheral)
+            }
+            hci::event::ConnectionRole::Central => {
+                (LinkState::Scanning, LinkState::ConnectedAsPrimary)
+            }
+        };
+        let slot = self
+            .slot(conn_handle)
+            .or_else(|| self.slots.iter().position(|slot| slot.state == before))
+            .or_else(|| {
+                self.slots
+                    .iter()
+                    .position(|slot| slot.state == LinkState::Idle)
+            });
+        if let Some(slot) = slot {
+            self.set(slot, ClientStatus { state, conn_handle }, changes);
+        }
+    }
+
+    fn set(&mut self, slot: usize, current: ClientStatus, changes: &mut Changes) {
+        let previous = self.slots[slot];
+        if previous != current {
+            self.slots[slot] = current;
+            changes.push(Change::Slot {
+                slot,
+                previous,
+                current,
+            });
+        }
+    }
+
+    fn check_exhausted(&mut self, changes: &mut Changes) {
+        let connections = self.connections();
+        if connections < self.role.max_connections() {
+            self.exhausted = false;
+        } else if !self.exhausted {
+            self.exhausted = true;
+            changes.push(Change::ConnectionsExhausted(connections));
+        }
+    }
+}
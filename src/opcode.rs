@@ -6,18 +6,38 @@ const fn ocf(cgid: u16, cid: u16) -> u16 {
 
 const VENDOR_OGF: u16 = 0x3F;
 
+/// Returns the vendor-specific opcode with the given OCF.
+pub const fn vendor(ocf: u16) -> Opcode {
+    Opcode::new(VENDOR_OGF, ocf)
+}
+
 macro_rules! opcodes {
     (
         $(
             $_cgid_comment:ident = $cgid:expr;
             {
-                $(pub const $var:ident = $cid:expr;)+
+                $($(#[$attr:meta])* pub const $var:ident = $cid:expr;)+
             }
         )+
     ) => {
         $($(
+            $(#[$attr])*
             pub const $var: Opcode = Opcode::new(VENDOR_OGF, ocf($cgid, $cid));
         )+)+
+
+        /// Returns the name of the vendor-specific command with the given opcode, if it is known.
+        pub fn name(opcode: Opcode) -> Option<&'static str> {
+            $($(
+                $(#[$attr])*
+                {
+                    if opcode == $var {
+                        return Some(stringify!($var));
+                    }
+                }
+            )+)+
+
+            None
+        }
     }
 }
 
@@ -33,10 +53,29 @@ opcodes! {
         pub const HAL_START_TONE = 0x15;
         pub const HAL_STOP_TONE = 0x16;
         pub const HAL_GET_LINK_STATUS = 0x17;
+        #[cfg(feature = "bluenrg-1-2")]
+        pub const HAL_SET_RADIO_ACTIVITY_MASK = 0x18;
 
         // The documentation says the OCF is 0xF8 (0b1111_1000), but that does not fit the OCF
         // length (7 bits). The C source code has 0x19, which is valid.
         pub const HAL_GET_ANCHOR_PERIOD = 0x19;
+        #[cfg(feature = "bluenrg-1-2")]
+        pub const HAL_SET_EVENT_MASK = 0x1A;
+        #[cfg(feature = "bluenrg-1-2")]
+        pub const HAL_STACK_RESET = 0x3B;
+    }
+    Updater = 0x0;
+    {
+        pub const UPDATER_START = 0x20;
+        pub const UPDATER_REBOOT = 0x21;
+        pub const UPDATER_GET_VERSION = 0x22;
+        pub const UPDATER_GET_BUFFER_SIZE = 0x23;
+        pub const UPDATER_ERASE_BLUE_FLAG = 0x24;
+        pub const UPDATER_RESET_BLUE_FLAG = 0x25;
+        pub const UPDATER_ERASE_SECTOR = 0x26;
+        pub const UPDATER_PROGRAM_DATA_BLOCK = 0x27;
+        pub const UPDATER_READ_DATA_BLOCK = 0x28;
+        pub const UPDATER_CALC_CRC = 0x29;
     }
     Gap = 0x1;
     {
@@ -76,6 +115,14 @@ opcodes! {
         pub const GAP_START_OBSERVATION_PROCEDURE = 0x22;
         pub const GAP_GET_BONDED_DEVICES = 0x23;
         pub const GAP_IS_DEVICE_BONDED = 0x24;
+        #[cfg(feature = "bluenrg-1-2")]
+        pub const GAP_NUMERIC_COMPARISON_VALUE_CONFIRM = 0x25;
+        #[cfg(feature = "bluenrg-1-2")]
+        pub const GAP_PASSKEY_INPUT = 0x26;
+        #[cfg(feature = "bluenrg-1-2")]
+        pub const GAP_REMOVE_BONDED_DEVICE = 0x2A;
+        #[cfg(feature = "bluenrg-1-2")]
+        pub const GAP_SET_LE_EVENT_MASK = 0x2F;
     }
     Gatt = 0x2;
     {
@@ -0,0 +1,320 @@
+//! Provisioning of the device identity stored in the controller configuration data.
+//!
+//! [`Provisioner`] writes a complete [`Identity`] with [HAL Write Config
+//! Data](crate::hal::Commands::write_config_data), then reads every field back with [HAL Read
+//! Config Data](crate::hal::Commands::read_config_data) and reports the fields that do not match.
+//! Each field is written with its own command, so an identity can be provisioned without the
+//! ordered [`ConfigData`] builders, and a failure points at the field that
+//! caused it.
+
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+use crate::event::command::{HalConfigParameter, ReturnParameters};
+use crate::hal::{self, ConfigData, ConfigParameter, Role};
+
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
+/// Identity of a device, as stored in the controller configuration data.
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Identity {
+    /// Bluetooth public address.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::BdAddrDef"))]
+    pub public_address: hci::BdAddr,
+
+    /// Diversifier used to derive CSRK (connection signature resolving key).
+    pub diversifier: u16,
+
+    /// Encryption root key used to derive the LTK (long-term key) and CSRK.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::EncryptionKeyDef")
+    )]
+    pub encryption_root: hci::host::EncryptionKey,
+
+    /// Identity root key used to derive the LTK and CSRK.
+    #[cfg_attr(
+        feature = "serde",
+        serde(with = "crate::serde_remote::EncryptionKeyDef")
+    )]
+    pub identity_root: hci::host::EncryptionKey,
+
+    /// Link Layer only mode.
+    pub link_layer_only: bool,
+
+    /// Role and mode configuration.
+    pub role: Role,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Identity {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "Identity {{ public_address: {}, diversifier: {}, encryption_root: ",
+            Remote(&self.public_address),
+            self.diversifier
+        );
+        defmt::write!(
+            f,
+            "{}, identity_root: {}, link_layer_only: {}, role: {} }}",
+            defmt::Debug2Format(&self.encryption_root),
+            defmt::Debug2Format(&self.identity_root),
+            self.link_layer_only,
+            self.role
+        )
+    }
+}
+
+bitflags! {
+    /// Fields of an [`Identity`].
+    #[cfg_attr(feature = "defmt", derive(defmt::Format))]
+    pub struct Fields: u8 {
+        /// [`public_address`](Identity::public_address)
+        const PUBLIC_ADDRESS = 1 << 0;
+        /// [`diversifier`](Identity::diversifier)
+        const DIVERSIFIER = 1 << 1;
+        /// [`encryption_root`](Identity::encryption_root)
+        const ENCRYPTION_ROOT = 1 << 2;
+        /// [`identity_root`](Identity::identity_root)
+        const IDENTITY_ROOT = 1 << 3;
+        /// [`link_layer_only`](Identity::link_layer_only)
+        const LINK_LAYER_ONLY = 1 << 4;
+        /// [`role`](Identity::role)
+        const ROLE = 1 << 5;
+    }
+}
+
+#[cfg(feature = "serde")]
+impl_serde_bitflags!(Fields, u8);
+
+// Fields in the order they are written and read back.
+const FIELDS: [(Fields, ConfigParameter); 6] = [
+    (Fields::PUBLIC_ADDRESS, ConfigParameter::PublicAddress),
+    (Fields::DIVERSIFIER, ConfigParameter::Diversifier),
+    (Fields::ENCRYPTION_ROOT, ConfigParameter::EncryptionRoot),
+    (Fields::IDENTITY_ROOT, ConfigParameter::IdentityRoot),
+    (Fields::LINK_LAYER_ONLY, ConfigParameter::LinkLayerOnly),
+    (Fields::ROLE, ConfigParameter::Role),
+];
+
+impl Identity {
+    fn config_data(&self, param: ConfigParameter) -> ConfigData {
+        match param {
+            ConfigParameter::PublicAddress => {
+                ConfigData::public_address(self.public_address).build()
+            }
+            ConfigParameter::Diversifier => ConfigData::diversifier(self.diversifier).build(),
+            ConfigParameter::EncryptionRoot => {
+                ConfigData::encryption_root(&self.encryption_root).build()
+            }
+            ConfigParameter::IdentityRoot => ConfigData::identity_root(&self.identity_root).build(),
+            ConfigParameter::LinkLayerOnly => {
+                ConfigData::link_layer_only(self.link_layer_only).build()
+            }
+            ConfigParameter::Role => ConfigData::role(self.role).build(),
+        }
+    }
+
+    fn matches(&self, param: ConfigParameter, value: &HalConfigParameter) -> bool {
+        match (param, value) {
+            (ConfigParameter::PublicAddress, HalConfigParameter::PublicAddress(addr)) => {
+                *addr == self.public_address
+            }
+            (ConfigParameter::Diversifier, HalConfigParameter::Diversifier(d)) => {
+                *d == self.diversifier
+            }
+            (ConfigParameter::EncryptionRoot, HalConfigParameter::EncryptionKey(key)) => {
+                *key == self.encryption_root
+            }
+            (ConfigParameter::IdentityRoot, HalConfigParameter::EncryptionKey(key)) => {
+                *key == self.identity_root
+            }
+            (ConfigParameter::LinkLayerOnly, HalConfigParameter::Byte(b)) => {
+                *b == self.link_layer_only as u8
+            }
+            (ConfigParameter::Role, HalConfigParameter::Byte(b)) => *b == self.role as u8,
+            _ => false,
+        }
+    }
+}
+
+/// Potential errors from [provisioning](Provisioner) an identity.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error<E> {
+    /// The controller reported that a command failed. Includes the opcode of the command and the
+    /// status it returned.
+    CommandFailed(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::OpcodeDef"))] hci::Opcode,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// The values read back from the controller did not match the identity. Includes the fields
+    /// that did not match.
+    Mismatch(Fields),
+
+    /// A command could not be sent.
+    Command(hal::Error<E>),
+}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for Error<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::CommandFailed(opcode, status) => {
+                defmt::write!(f, "CommandFailed({}, {})", Remote(opcode), Remote(status))
+            }
+            Error::Mismatch(fields) => defmt::write!(f, "Mismatch({})", fields),
+            Error::Command(e) => defmt::write!(f, "Command({})", e),
+        }
+    }
+}
+
+impl<E> Error<E> {
+    fn widen(failure: Error<crate::NoComm>) -> Error<E> {
+        match failure {
+            Error::CommandFailed(opcode, status) => Error::CommandFailed(opcode, status),
+            Error::Mismatch(fields) => Error::Mismatch(fields),
+            Error::Command(e) => Error::Command(e.widen()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Step {
+    Write,
+    Read,
+    Done,
+}
+
+/// Writes an [`Identity`] to the controller and verifies it.
+///
+/// The provisioner writes every field, then reads every field back, even after a mismatch, so the
+/// [`Mismatch`](Error::Mismatch) error lists all of the fields that did not match. It is a
+/// [nonblocking procedure](crate#nonblocking-procedures).
+pub struct Provisioner {
+    identity: Identity,
+    step: Step,
+    field: usize,
+    mismatches: Fields,
+    awaiting: bool,
+    failure: Option<Error<crate::NoComm>>,
+}
+
+impl Provisioner {
+    /// Returns a provisioner for the given identity.
+    pub fn new(identity: &Identity) -> Provisioner {
+        Provisioner {
+            identity: identity.clone(),
+            step: Step::Write,
+            field: 0,
+            mismatches: Fields::empty(),
+            awaiting: false,
+            failure: None,
+        }
+    }
+
+    /// Returns true if every field has been written and read back.
+    pub fn is_done(&self) -> bool {
+        self.step == Step::Done
+    }
+
+    /// Sends the next command to the controller, unless the provisioner is waiting for the Command
+    /// Complete event of the previous one.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] while provisioning is in progress.
+    /// - Returns [`Mismatch`](Error::Mismatch) once every field has been read back, if any of them
+    ///   did not match.
+    /// - Returns other [errors](Error) if a command failed. Provisioning can be retried with a new
+    ///   `Provisioner`.
+    pub fn poll<C>(&mut self, controller: &mut C) -> nb::Result<(), Error<C::Error>>
+    where
+        C: hal::Commands,
+    {
+        if let Some(failure) = self.failure {
+            return Err(nb::Error::Other(Error::widen(failure)));
+        }
+        if self.step == Step::Done {
+            if !self.mismatches.is_empty() {
+                return Err(nb::Error::Other(Error::Mismatch(self.mismatches)));
+            }
+
+            return Ok(());
+        }
+        if self.awaiting {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let (_, param) = FIELDS[self.field];
+        let result = match self.step {
+            Step::Write => controller.write_config_data(&self.identity.config_data(param)),
+            Step::Read => controller.read_config_data(param).map_err(|e| match e {
+                nb::Error::WouldBlock => nb::Error::WouldBlock,
+                nb::Error::Other(c) => nb::Error::Other(hal::Error::Comm(c)),
+            }),
+            Step::Done => Ok(()),
+        };
+        match result {
+            Ok(()) => {
+                self.awaiting = true;
+                Err(nb::Error::WouldBlock)
+            }
+            Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+            Err(nb::Error::Other(e)) => Err(nb::Error::Other(Error::Command(e))),
+        }
+    }
+
+    /// Advances provisioning with the return parameters of the command that was sent last. Events
+    /// that do not complete that command are ignored.
+    pub fn handle_event(&mut self, event: &hci::Event<crate::event::BlueNRGEvent>) {
+        if !self.awaiting {
+            return;
+        }
+        let params = match *event {
+            hci::Event::CommandComplete(ref event) => match event.return_params {
+                hci::event::command::ReturnParameters::Vendor(ref params) => params,
+                _ => return,
+            },
+            _ => return,
+        };
+
+        let (field, param) = FIELDS[self.field];
+        match (self.step, params) {
+            (Step::Write, ReturnParameters::HalWriteConfigData(status)) => {
+                if *status != hci::Status::Success {
+                    self.failure = Some(Error::CommandFailed(
+                        crate::opcode::HAL_WRITE_CONFIG_DATA,
+                        *status,
+                    ));
+                }
+            }
+            (Step::Read, ReturnParameters::HalReadConfigData(data)) => {
+                if data.status != hci::Status::Success {
+                    self.failure = Some(Error::CommandFailed(
+                        crate::opcode::HAL_READ_CONFIG_DATA,
+                        data.status,
+                    ));
+                } else if !self.identity.matches(param, &data.value) {
+                    self.mismatches |= field;
+                }
+            }
+            _ => return,
+        }
+
+        self.awaiting = false;
+        self.field += 1;
+        if self.field == FIELDS.len() {
+            self.field = 0;
+            self.step = match self.step {
+                Step::Write => Step::Read,
+                _ => Step::Done,
+            };
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! Non-blocking queue for outbound vendor-specific commands.
+//!
+//! Writing a command to the controller returns [`nb::Error::WouldBlock`] whenever the controller
+//! does not have room for it, so every call site would otherwise need to retry. A [`CommandQueue`]
+//! implements [`CommandWriter`](crate::CommandWriter), so all of the [`gap`](crate::gap),
+//! [`gatt`](crate::gatt), [`hal`](crate::hal), and [`l2cap`](crate::l2cap) commands can be issued
+//! to it directly. Each command is serialized into the queue immediately, and
+//! [`pump`](CommandQueue::pump) later sends the queued commands to the controller as buffer space
+//! and command credits allow.
+//!
+//! Commands are sent in [priority](Priority) order, and in the order they were queued within the
+//! same priority.
+
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+/// Maximum length of the parameters of a single queued command. The HCI command header encodes the
+/// parameter length in a single byte.
+pub const MAX_PARAMETER_LEN: usize = 255;
+
+/// Relative urgency of a queued command.
+///
+/// By default, the priority of a command is determined from its opcode (see [`Priority::of`]). It
+/// can be overridden using [`CommandQueue::with_priority`].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Priority {
+    /// Bulk traffic that may wait behind other commands, like characteristic value updates.
+    Bulk,
+    /// Most commands.
+    Normal,
+    /// Commands with tight timing requirements, like responses to ATT permit requests. These are
+    /// sent before any other queued command.
+    Urgent,
+}
+
+impl Priority {
+    /// Returns the default priority of the command with the given opcode.
+    ///
+    /// - [`allow_read`](crate::gatt::Commands::allow_read) and
+    ///   [`write_response`](crate::gatt::Commands::write_response) must be sent before the ATT
+    ///   transaction times out, so they are [`Urgent`](Priority::Urgent).
+    /// - [`update_characteristic_value`](crate::gatt::Commands::update_characteristic_value) (and
+    ///   its long variant) are [`Bulk`](Priority::Bulk).
+    /// - All other commands are [`Normal`](Priority::Normal).
+    pub fn of(opcode: hci::Opcode) -> Priority {
+        match opcode {
+            crate::opcode::GATT_ALLOW_READ | crate::opcode::GATT_WRITE_RESPONSE => Priority::Urgent,
+            crate::opcode::GATT_UPDATE_CHARACTERISTIC_VALUE
+            | crate::opcode::GATT_UPDATE_LONG_CHARACTERISTIC_VALUE => Priority::Bulk,
+            _ => Priority::Normal,
+        }
+    }
+}
+
+/// Storage for a single queued command.
+///
+/// The application provides the storage for the [`CommandQueue`], typically in a static:
+///
+/// ```
+/// # use bluenrg::queue::{CommandQueue, Slot};
+/// static mut SLOTS: [Slot; 4] = [Slot::EMPTY; 4];
+///
+/// let queue = CommandQueue::new(unsafe { &mut SLOTS });
+/// assert_eq!(queue.capacity(), 4);
+/// ```
+#[derive(Copy, Clone)]
+pub struct Slot {
+    opcode: hci::Opcode,
+    priority: Priority,
+    params_len: usize,
+    params_buf: [u8; MAX_PARAMETER_LEN],
+}
+
+impl Slot {
+    /// A slot that does not contain a command.
+    pub const EMPTY: Slot = Slot {
+        opcode: hci::Opcode(0),
+        priority: Priority::Normal,
+        params_len: 0,
+        params_buf: [0; MAX_PARAMETER_LEN],
+    };
+
+    fn params(&self) -> &[u8] {
+        &self.params_buf[..self.params_len]
+    }
+}
+
+/// Potential errors from queueing a command.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Error {
+    /// The command parameters are longer than [`MAX_PARAMETER_LEN`]. Includes the length of the
+    /// parameters.
+    ParametersTooLong(usize),
+}
+
+/// Bounded queue of serialized commands waiting to be sent to the controller.
+///
+/// # Command credits
+///
+/// The controller reports how many commands it is willing to accept in the `num_hci_command_packets`
+/// field of the Command Complete and Command Status events. The queue starts with a single credit,
+/// as required by the Bluetooth specification, and spends one credit for each command it sends.
+/// Pass each received event to [`update_credits`](CommandQueue::update_credits) to replenish them.
+pub struct CommandQueue<'buf> {
+    // Queued commands are stored in slots[..len], in the order they will be sent.
+    slots: &'buf mut [Slot],
+    len: usize,
+    credits: u8,
+    priority_override: Option<Priority>,
+}
+
+impl<'buf> CommandQueue<'buf> {
+    /// Returns a new, empty queue that uses the given slots as its storage. The queue can hold at
+    /// most `slots.len()` commands.
+    pub fn new(slots: &'buf mut [Slot]) -> CommandQueue<'buf> {
+        CommandQueue {
+            slots,
+            len: 0,
+            credits: 1,
+            priority_override: None,
+        }
+    }
+
+    /// Returns the maximum number of commands the queue can hold.
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Returns the number of commands waiting to be sent.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no commands are waiting to be sent.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns true if the queue cannot accept another command.
+    pub fn is_full(&self) -> bool {
+        self.len == self.slots.len()
+    }
+
+    /// Returns the number of commands the controller is currently willing to accept.
+    pub fn credits(&self) -> u8 {
+        self.credits
+    }
+
+    /// Sets the number of commands the controller is currently willing to accept.
+    pub fn set_credits(&mut self, credits: u8) {
+        self.credits = credits;
+    }
+
+    /// Updates the number of available command credits from the Command Complete and Command
+    /// Status events. All other events are ignored.
+    pub fn update_credits<V>(&mut self, event: &hci::Event<V>)
+    where
+        V: hci::event::VendorEvent,
+    {
+        match event {
+            hci::Event::CommandComplete(event) => self.credits = event.num_hci_command_packets,
+            hci::Event::CommandStatus(event) => self.credits = event.num_hci_command_packets,
+            _ => (),
+        }
+    }
+
+    /// Invokes `body` with this queue, and queues all commands issued within it with the given
+    /// priority instead of their default [priority](Priority::of).
+    pub fn with_priority<T, F>(&mut self, priority: Priority, body: F) -> T
+    where
+        F: FnOnce(&mut CommandQueue<'buf>) -> T,
+    {
+        let previous = self.priority_override.replace(priority);
+        let result = body(self);
+        self.priority_override = previous;
+
+        result
+    }
+
+    /// Queues a command with the given priority. The command is placed behind all queued commands
+    /// with the same or higher priority.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] if the queue is full.
+    /// - Returns [`ParametersTooLong`](Error::ParametersTooLong) if the parameters are longer than
+    ///   [`MAX_PARAMETER_LEN`].
+    pub fn push(
+        &mut self,
+        priority: Priority,
+        opcode: hci::Opcode,
+        params: &[u8],
+    ) -> nb::Result<(), Error> {
+        if params.len() > MAX_PARAMETER_LEN {
+            return Err(nb::Error::Other(Error::ParametersTooLong(params.len())));
+        }
+        if self.is_full() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let index = self.slots[..self.len]
+            .iter()
+            .position(|slot| slot.priority < priority)
+            .unwrap_or(self.len);
+        self.slots.copy_within(index..self.len, index + 1);
+        self.len += 1;
+
+        let slot = &mut self.slots[index];
+        slot.opcode = opcode;
+        slot.priority = priority;
+        slot.params_len = params.len();
+        slot.params_buf[..params.len()].copy_from_slice(params);
+
+        Ok(())
+    }
+
+    /// Removes all queued commands without sending them.
+    pub fn clear(&mut self) {
+        self.len = 0;
+    }
+
+    /// Sends queued commands to the controller, highest priority first, until the queue is empty,
+    /// the controller has no room for the next command, or there are no command credits left.
+    ///
+    /// A command is only removed from the queue once it has been written successfully, so `pump`
+    /// may be called again after any error to retry.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] if commands remain in the queue because the controller
+    ///   is not ready or there are no command credits left.
+    /// - Returns any underlying communication error.
+    pub fn pump<C>(&mut self, controller: &mut C) -> nb::Result<(), C::Error>
+    where
+        C: crate::CommandWriter,
+    {
+        while !self.is_empty() {
+            if self.credits == 0 {
+                return Err(nb::Error::WouldBlock);
+            }
+
+            let slot = &self.slots[0];
+            controller.write_command(slot.opcode, slot.params())?;
+
+            self.slots.copy_within(1..self.len, 0);
+            self.len -= 1;
+            self.credits -= 1;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'buf> crate::CommandWriter for CommandQueue<'buf> {
+    type Error = Error;
+
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), Self::Error> {
+        let priority = self
+            .priority_override
+            .unwrap_or_else(|| Priority::of(opcode));
+        self.push(priority, opcode, params)
+    }
+}
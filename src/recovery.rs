@@ -0,0 +1,458 @@
+//! Recovery from events lost by the controller.
+//!
+//! When the controller cannot deliver events, it reports the types it dropped in an
+//! [`EventsLost`](BlueNRGEvent::EventsLost) event, and the application's view of the controller
+//! may no longer be accurate. [`Recovery`] maps each lost type to a corrective action:
+//!
+//! - Lost connection and disconnection events: the link status is re-read with [HAL Get Link
+//!   Status](crate::hal::Commands::get_link_status). The pending procedures on connections that
+//!   are no longer open are failed. Pass the response to a
+//!   [`links::Tracker`](crate::links::Tracker) to bring its model up to date.
+//! - Lost [attribute modifications](BlueNRGEvent::GattAttributeModified): the
+//!   [watched](Recovery::watch) characteristic values are re-read with [GATT Read Handle
+//!   Value](crate::gatt::Commands::read_handle_value).
+//! - Lost responses and completions of GATT procedures: the pending procedures can no longer
+//!   complete correctly, so they are failed.
+//!
+//! Every other lost type has no corrective action, and is reported as unrecovered.
+
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+use crate::event::command::{ClientStatus, ReturnParameters};
+use crate::event::{BlueNRGEvent, EventFlags};
+use crate::gatt::{CharacteristicHandle, Commands as GattCommands};
+use crate::hal::Commands as HalCommands;
+
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
+/// Maximum number of characteristic values that can be [watched](Recovery::watch).
+pub const MAX_WATCHED: usize = 16;
+
+/// Maximum number of GATT procedures that can be [pending](Recovery::procedure_started) at once.
+pub const MAX_PROCEDURES: usize = 8;
+
+/// Lost events that are recovered by re-reading the link status.
+pub const LINK_EVENTS: EventFlags = EventFlags::from_bits_truncate(
+    EventFlags::DISCONNECTION_COMPLETE.bits() | EventFlags::LINK_LAYER_CONNECTION_COMPLETE.bits(),
+);
+
+/// Lost events that are recovered by re-reading the watched characteristic values.
+pub const MODIFICATION_EVENTS: EventFlags = EventFlags::GATT_ATTRIBUTE_MODIFIED;
+
+/// Lost events that fail the pending GATT procedures.
+pub const PROCEDURE_EVENTS: EventFlags = EventFlags::from_bits_truncate(
+    EventFlags::GATT_PROCEDURE_TIMEOUT.bits()
+        | EventFlags::ATT_EXCHANGE_MTU_RESPONSE.bits()
+        | EventFlags::ATT_FIND_INFORMATION_RESPONSE.bits()
+        | EventFlags::ATT_FIND_BY_TYPE_VALUE_RESPONSE.bits()
+        | EventFlags::ATT_READ_BY_TYPE_RESPONSE.bits()
+        | EventFlags::ATT_READ_RESPONSE.bits()
+        | EventFlags::ATT_READ_BLOB_RESPONSE.bits()
+        | EventFlags::ATT_READ_MULTIPLE_RESPONSE.bits()
+        | EventFlags::ATT_READ_BY_GROUP_TYPE_RESPONSE.bits()
+        | EventFlags::ATT_WRITE_RESPONSE.bits()
+        | EventFlags::ATT_PREPARE_WRITE_RESPONSE.bits()
+        | EventFlags::ATT_EXECUTE_WRITE_RESPONSE.bits()
+        | EventFlags::GATT_PROCEDURE_COMPLETE.bits()
+        | EventFlags::GATT_ERROR_RESPONSE.bits()
+        | EventFlags::GATT_DISCOVER_OR_READ_CHARACTERISTIC_BY_UUID_RESPONSE.bits(),
+);
+
+/// Potential errors from [`Recovery::poll`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error<E> {
+    /// The controller reported that a command failed. Includes the opcode of the command and the
+    /// status it returned.
+    CommandFailed(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::OpcodeDef"))] hci::Opcode,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// Underlying communication error.
+    Comm(E),
+}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for Error<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::CommandFailed(opcode, status) => {
+                defmt::write!(f, "CommandFailed({}, {})", Remote(opcode), Remote(status))
+            }
+            Error::Comm(e) => defmt::write!(f, "Comm({})", e),
+        }
+    }
+}
+
+/// Errors from registering a characteristic value or a procedure with [`Recovery`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum RegisterError {
+    /// [`MAX_WATCHED`] characteristic values are already watched.
+    TooManyHandles,
+
+    /// [`MAX_PROCEDURES`] procedures are already pending.
+    TooManyProcedures,
+}
+
+/// Notifications from [`Recovery::handle_event`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Notice {
+    /// The pending GATT procedure on the connection was failed, because its responses, its
+    /// completion, or the disconnection of the connection were lost.
+    ProcedureFailed(
+        #[cfg_attr(
+            feature = "serde",
+            serde(with = "crate::serde_remote::ConnectionHandleDef")
+        )]
+        hci::ConnectionHandle,
+    ),
+
+    /// The characteristic value was re-read. The value is in the
+    /// [return parameters](ReturnParameters::GattReadHandleValue) of the same event.
+    ValueRefreshed(CharacteristicHandle),
+
+    /// Events of these types were lost, and could not be recovered.
+    Unrecovered(EventFlags),
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Notice {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Notice::ProcedureFailed(conn_handle) => {
+                defmt::write!(f, "ProcedureFailed({})", Remote(conn_handle))
+            }
+            Notice::ValueRefreshed(value) => defmt::write!(f, "ValueRefreshed({})", value),
+            Notice::Unrecovered(value) => defmt::write!(f, "Unrecovered({})", value),
+        }
+    }
+}
+
+/// Notices reported by [`Recovery::handle_event`].
+#[derive(Clone, Debug)]
+pub struct Notices {
+    notices: [Option<Notice>; MAX_PROCEDURES + 1],
+    next: usize,
+}
+
+impl Notices {
+    fn new() -> Notices {
+        Notices {
+            notices: [None; MAX_PROCEDURES + 1],
+            next: 0,
+        }
+    }
+
+    fn push(&mut self, notice: Notice) {
+        if let Some(entry) = self.notices.iter_mut().find(|entry| entry.is_none()) {
+            *entry = Some(notice);
+        }
+    }
+}
+
+impl Iterator for Notices {
+    type Item = Notice;
+
+    fn next(&mut self) -> Option<Notice> {
+        let notice = self.notices.get(self.next).copied().flatten();
+        if notice.is_some() {
+            self.next += 1;
+        }
+
+        notice
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Awaiting {
+    LinkStatus,
+    Value(usize),
+}
+
+/// Applies corrective actions for lost events.
+///
+/// [Watch](Recovery::watch) the characteristic values the application keeps a copy of, and report
+/// the GATT procedures it [starts](Recovery::procedure_started). Pass every event from the
+/// controller to [`handle_event`](Recovery::handle_event), and call [`poll`](Recovery::poll) until
+/// it returns `Ok` to send the commands needed to recover.
+#[derive(Clone, Debug)]
+pub struct Recovery {
+    watched: [Option<CharacteristicHandle>; MAX_WATCHED],
+    stale: [bool; MAX_WATCHED],
+    procedures: [Option<hci::ConnectionHandle>; MAX_PROCEDURES],
+    refresh_links: bool,
+    awaiting: Option<Awaiting>,
+    failure: Option<(hci::Opcode, hci::Status<crate::event::Status>)>,
+    unrecovered: EventFlags,
+}
+
+impl Default for Recovery {
+    fn default() -> Self {
+        Recovery::new()
+    }
+}
+
+impl Recovery {
+    /// Returns a recovery layer with no watched values and no pending procedures.
+    pub fn new() -> Recovery {
+        Recovery {
+            watched: [None; MAX_WATCHED],
+            stale: [false; MAX_WATCHED],
+            procedures: [None; MAX_PROCEDURES],
+            refresh_links: false,
+            awaiting: None,
+            failure: None,
+            unrecovered: EventFlags::empty(),
+        }
+    }
+
+    /// Re-reads the given characteristic value whenever attribute modifications are lost.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TooManyHandles`](RegisterError::TooManyHandles) if [`MAX_WATCHED`] values are
+    /// already watched.
+    pub fn watch(&mut self, handle: CharacteristicHandle) -> Result<(), RegisterError> {
+        if self.watched.contains(&Some(handle)) {
+            return Ok(());
+        }
+
+        let entry = self
+            .watched
+            .iter_mut()
+            .find(|entry| entry.is_none())
+            .ok_or(RegisterError::TooManyHandles)?;
+        *entry = Some(handle);
+
+        Ok(())
+    }
+
+    /// Stops watching the given characteristic value.
+    pub fn unwatch(&mut self, handle: CharacteristicHandle) {
+        for (entry, stale) in self.watched.iter_mut().zip(self.stale.iter_mut()) {
+            if *entry == Some(handle) {
+                *entry = None;
+                *stale = false;
+            }
+        }
+    }
+
+    /// Records that a GATT client procedure was started on the connection. The procedure is pending
+    /// until its [GATT Procedure Complete](BlueNRGEvent::GattProcedureComplete) or [GATT Procedure
+    /// Timeout](BlueNRGEvent::GattProcedureTimeout) event, or until the connection is closed.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`TooManyProcedures`](RegisterError::TooManyProcedures) if [`MAX_PROCEDURES`]
+    /// procedures are already pending.
+    pub fn procedure_started(
+        &mut self,
+        conn_handle: hci::ConnectionHandle,
+    ) -> Result<(), RegisterError> {
+        let entry = self
+            .procedures
+            .iter_mut()
+            .find(|entry| entry.is_none())
+            .ok_or(RegisterError::TooManyProcedures)?;
+        *entry = Some(conn_handle);
+
+        Ok(())
+    }
+
+    /// Returns true if a GATT procedure is pending on the connection.
+    pub fn is_procedure_pending(&self, conn_handle: hci::ConnectionHandle) -> bool {
+        self.procedures.contains(&Some(conn_handle))
+    }
+
+    /// Returns true if there are no commands left to send or responses left to wait for.
+    pub fn is_idle(&self) -> bool {
+        !self.refresh_links && self.awaiting.is_none() && !self.stale.contains(&true)
+    }
+
+    /// Returns every type of lost event that could not be recovered since the last call to
+    /// [`clear_unrecovered`](Recovery::clear_unrecovered).
+    pub fn unrecovered(&self) -> EventFlags {
+        self.unrecovered
+    }
+
+    /// Clears the [unrecovered](Recovery::unrecovered) event types, for example after the
+    /// application has re-initialized the affected state.
+    pub fn clear_unrecovered(&mut self) {
+        self.unrecovered = EventFlags::empty();
+    }
+
+    /// Sends the next command needed to recover, unless the recovery layer is waiting for the
+    /// Command Complete event of the previous one.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] while recovery is in progress.
+    /// - Returns [`CommandFailed`](Error::CommandFailed) once if the controller rejected a command.
+    ///   A rejected read is not retried until attribute modifications are lost again.
+    /// - Returns [`Comm`](Error::Comm) if a command could not be sent.
+    pub fn poll<C>(&mut self, controller: &mut C) -> nb::Result<(), Error<C::Error>>
+    where
+        C: crate::CommandWriter,
+    {
+        if let Some((opcode, status)) = self.failure.take() {
+            return Err(nb::Error::Other(Error::CommandFailed(opcode, status)));
+        }
+        if self.awaiting.is_some() {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let (result, awaiting) = if self.refresh_links {
+            (controller.get_link_status(), Awaiting::LinkStatus)
+        } else if let Some((index, handle)) = self.next_stale() {
+            (controller.read_handle_value(handle), Awaiting::Value(index))
+        } else {
+            return Ok(());
+        };
+        result.map_err(|e| e.map(Error::Comm))?;
+        match awaiting {
+            Awaiting::LinkStatus => self.refresh_links = false,
+            Awaiting::Value(index) => self.stale[index] = false,
+        }
+        self.awaiting = Some(awaiting);
+
+        Err(nb::Error::WouldBlock)
+    }
+
+    /// Updates the recovery layer with an event from the controller, and returns the notices for
+    /// the application. Besides [`EventsLost`](BlueNRGEvent::EventsLost) and the Command Complete
+    /// events of its own commands, the recovery layer follows the events that end GATT
+    /// procedures.
+    pub fn handle_event(&mut self, event: &hci::Event<BlueNRGEvent>) -> Notices {
+        use hci::event::command::ReturnParameters as HciParams;
+
+        let mut notices = Notices::new();
+        match *event {
+            hci::Event::Vendor(BlueNRGEvent::EventsLost(flags)) => {
+                self.recover(flags, &mut notices);
+            }
+            hci::Event::Vendor(BlueNRGEvent::GattProcedureComplete(ref event)) => {
+                self.procedure_ended(event.conn_handle);
+            }
+            hci::Event::Vendor(BlueNRGEvent::GattProcedureTimeout(conn_handle)) => {
+                self.procedure_ended(conn_handle);
+            }
+            hci::Event::DisconnectionComplete(ref event)
+                if event.status == hci::Status::Success =>
+            {
+                for entry in self.procedures.iter_mut() {
+                    if *entry == Some(event.conn_handle) {
+                        *entry = None;
+                    }
+                }
+            }
+            hci::Event::CommandComplete(ref event) => {
+                let (opcode, status) = match (self.awaiting, &event.return_params) {
+                    (
+                        Some(Awaiting::LinkStatus),
+                        HciParams::Vendor(ReturnParameters::HalGetLinkStatus(params)),
+                    ) => {
+                        if params.status == hci::Status::Success {
+                            self.release_closed(&params.clients, &mut notices);
+                        }
+                        (crate::opcode::HAL_GET_LINK_STATUS, params.status)
+                    }
+                    (
+                        Some(Awaiting::Value(index)),
+                        HciParams::Vendor(ReturnParameters::GattReadHandleValue(params)),
+                    ) => {
+                        if params.status == hci::Status::Success {
+                            if let Some(handle) = self.watched[index] {
+                                notices.push(Notice::ValueRefreshed(handle));
+                            }
+                        }
+                        (crate::opcode::GATT_READ_HANDLE_VALUE, params.status)
+                    }
+                    _ => return notices,
+                };
+                self.awaiting = None;
+                if status != hci::Status::Success {
+                    self.failure = Some((opcode, status));
+                }
+            }
+            _ => (),
+        }
+
+        notices
+    }
+
+    fn recover(&mut self, flags: EventFlags, notices: &mut Notices) {
+        let mut unrecovered = flags - LINK_EVENTS - MODIFICATION_EVENTS - PROCEDURE_EVENTS;
+
+        if flags.intersects(LINK_EVENTS) {
+            self.refresh_links = true;
+        }
+
+        if flags.intersects(MODIFICATION_EVENTS) {
+            if self.watched.iter().any(Option::is_some) {
+                for (entry, stale) in self.watched.iter().zip(self.stale.iter_mut()) {
+                    *stale |= entry.is_some();
+                }
+            } else {
+                unrecovered |= flags & MODIFICATION_EVENTS;
+            }
+        }
+
+        // A pending procedure missed some of its responses, or will never complete, so its result
+        // can no longer be trusted.
+        if flags.intersects(PROCEDURE_EVENTS) {
+            for entry in self.procedures.iter_mut() {
+                if let Some(conn_handle) = entry.take() {
+                    notices.push(Notice::ProcedureFailed(conn_handle));
+                }
+            }
+        }
+
+        if !unrecovered.is_empty() {
+            self.unrecovered |= unrecovered;
+            notices.push(Notice::Unrecovered(unrecovered));
+        }
+    }
+
+    // The disconnection events of these procedures were lost, so they would stay pending forever.
+    fn release_closed(&mut self, clients: &[ClientStatus], notices: &mut Notices) {
+        for entry in self.procedures.iter_mut() {
+            if let Some(conn_handle) = *entry {
+                let connected = clients
+                    .iter()
+                    .any(|client| client.state.is_connected() && client.conn_handle == conn_handle);
+                if !connected {
+                    *entry = None;
+                    notices.push(Notice::ProcedureFailed(conn_handle));
+                }
+            }
+        }
+    }
+
+    fn next_stale(&self) -> Option<(usize, CharacteristicHandle)> {
+        self.watched
+            .iter()
+            .zip(self.stale.iter())
+            .enumerate()
+            .find_map(|(index, (entry, &stale))| match entry {
+                Some(handle) if stale => Some((index, *handle)),
+                _ => None,
+            })
+    }
+
+    fn procedure_ended(&mut self, conn_handle: hci::ConnectionHandle) {
+        if let Some(entry) = self
+            .procedures
+            .iter_mut()
+            .find(|entry| **entry == Some(conn_handle))
+        {
+            *entry = None;
+        }
+    }
+}
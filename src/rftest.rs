@@ -0,0 +1,580 @@
+//! RF production tests.
+//!
+//! [`Runner`] sequences the test commands of the controller into a complete production test of a
+//! unit:
+//!
+//! - a carrier [tone](crate::hal::Commands::start_tone) on each of the requested channels,
+//! - LE transmitter tests, counting the packets sent with [HAL Get TX Test Packet
+//!   Count](crate::hal::Commands::get_tx_test_packet_count),
+//! - LE receiver tests, counting the packets received, and
+//! - a transmitter test at each of the requested [power levels](PowerLevel).
+//!
+//! The results are collected in a [`Report`]. The runner refuses to start while any connection is
+//! active, since the tests take over the radio.
+
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+use crate::event::command::ReturnParameters;
+use crate::hal::{self, Commands, PowerLevel};
+use core::time::Duration;
+use hci::host::TestPacketPayload;
+
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
+/// Maximum number of tests in a [`Plan`].
+pub const MAX_TESTS: usize = 64;
+
+const MAX_CHANNEL: u8 = 39;
+const MAX_TEST_PACKET_LEN: u8 = 37;
+
+const LE_RECEIVER_TEST: hci::Opcode = hci::Opcode::new(0x08, 0x001D);
+const LE_TRANSMITTER_TEST: hci::Opcode = hci::Opcode::new(0x08, 0x001E);
+const LE_TEST_END: hci::Opcode = hci::Opcode::new(0x08, 0x001F);
+
+/// Tests to run on a unit. The tests run in the order of the fields: tones, transmitter tests,
+/// receiver tests, and finally the power sweep.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct Plan<'a> {
+    /// Channels on which to transmit a carrier tone. Valid channels are 0 to 39.
+    pub tone_channels: &'a [u8],
+
+    /// Channels on which to run an LE transmitter test at the current power level. Valid channels
+    /// are 0 to 39.
+    pub transmitter_channels: &'a [u8],
+
+    /// Channels on which to run an LE receiver test. Valid channels are 0 to 39.
+    pub receiver_channels: &'a [u8],
+
+    /// Power levels at which to run an LE transmitter test on the
+    /// [`sweep_channel`](Plan::sweep_channel). The power level is not restored after the sweep.
+    pub power_levels: &'a [PowerLevel],
+
+    /// Channel used for the power sweep.
+    pub sweep_channel: u8,
+
+    /// Length of the payload of each packet sent by the transmitter tests, from 0 to 37 bytes.
+    pub payload_length: u8,
+
+    /// Payload of each packet sent by the transmitter tests.
+    #[cfg_attr(feature = "defmt", defmt(Debug2Format))]
+    pub payload: TestPacketPayload,
+
+    /// Time each test runs.
+    pub dwell: Duration,
+}
+
+/// Reasons a [`Plan`] cannot be run.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum PlanError {
+    /// A channel is greater than 39. Includes the invalid channel.
+    BadChannel(u8),
+
+    /// The payload length is greater than 37. Includes the invalid length.
+    BadPayloadLength(u8),
+
+    /// The plan has more than [`MAX_TESTS`] tests. Includes the number of tests.
+    TooManyTests(usize),
+}
+
+impl<'a> Plan<'a> {
+    /// Returns the number of tests in the plan.
+    pub fn len(&self) -> usize {
+        self.tone_channels.len()
+            + self.transmitter_channels.len()
+            + self.receiver_channels.len()
+            + self.power_levels.len()
+    }
+
+    /// Returns true if the plan has no tests.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Checks that the plan can be run.
+    ///
+    /// # Errors
+    ///
+    /// - [`BadChannel`](PlanError::BadChannel) if any channel is greater than 39.
+    /// - [`BadPayloadLength`](PlanError::BadPayloadLength) if the payload length is greater than
+    ///   37.
+    /// - [`TooManyTests`](PlanError::TooManyTests) if there are more than [`MAX_TESTS`] tests.
+    pub fn validate(&self) -> Result<(), PlanError> {
+        let sweep_channel: &[u8] = if self.power_levels.is_empty() {
+            &[]
+        } else {
+            core::slice::from_ref(&self.sweep_channel)
+        };
+        if let Some(&channel) = self
+            .tone_channels
+            .iter()
+            .chain(self.transmitter_channels)
+            .chain(self.receiver_channels)
+            .chain(sweep_channel)
+            .find(|&&channel| channel > MAX_CHANNEL)
+        {
+            return Err(PlanError::BadChannel(channel));
+        }
+        if self.payload_length > MAX_TEST_PACKET_LEN {
+            return Err(PlanError::BadPayloadLength(self.payload_length));
+        }
+        if self.len() > MAX_TESTS {
+            return Err(PlanError::TooManyTests(self.len()));
+        }
+
+        Ok(())
+    }
+
+    fn test(&self, index: usize) -> Option<Test> {
+        let mut index = index;
+        if let Some(&channel) = self.tone_channels.get(index) {
+            return Some(Test::Tone { channel });
+        }
+        index -= self.tone_channels.len();
+        if let Some(&channel) = self.transmitter_channels.get(index) {
+            return Some(Test::Transmitter {
+                channel,
+                power_level: None,
+            });
+        }
+        index -= self.transmitter_channels.len();
+        if let Some(&channel) = self.receiver_channels.get(index) {
+            return Some(Test::Receiver { channel });
+        }
+        index -= self.receiver_channels.len();
+        self.power_levels
+            .get(index)
+            .map(|&power_level| Test::Transmitter {
+                channel: self.sweep_channel,
+                power_level: Some(power_level),
+            })
+    }
+}
+
+/// A single test of a [`Plan`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Test {
+    /// Carrier tone.
+    Tone {
+        /// Channel of the tone.
+        channel: u8,
+    },
+
+    /// LE transmitter test.
+    Transmitter {
+        /// Channel of the test.
+        channel: u8,
+
+        /// Power level set for the test, or `None` if the current power level was used.
+        power_level: Option<PowerLevel>,
+    },
+
+    /// LE receiver test.
+    Receiver {
+        /// Channel of the test.
+        channel: u8,
+    },
+}
+
+/// Result of a single [`Test`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TestResult {
+    /// The test that was run.
+    pub test: Test,
+
+    /// Status of the first command of the test that failed, or
+    /// [`Success`](hci::Status::Success) if none did.
+    #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+    pub status: hci::Status<crate::event::Status>,
+
+    /// For transmitter tests, the number of packets sent. For receiver tests, the number of
+    /// packets received. `None` for tones, and for tests that failed before they were counted.
+    pub packets: Option<u32>,
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for TestResult {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(
+            f,
+            "TestResult {{ test: {}, status: {}, packets: {} }}",
+            self.test,
+            Remote(&self.status),
+            self.packets
+        )
+    }
+}
+
+impl TestResult {
+    /// Returns true if every command of the test succeeded.
+    pub fn passed(&self) -> bool {
+        self.status == hci::Status::Success
+    }
+}
+
+/// Results of every test run on a unit, in the order they were run.
+#[derive(Clone)]
+pub struct Report {
+    results: [TestResult; MAX_TESTS],
+    len: usize,
+}
+
+impl Report {
+    const EMPTY: TestResult = TestResult {
+        test: Test::Tone { channel: 0 },
+        status: hci::Status::Success,
+        packets: None,
+    };
+
+    fn new() -> Report {
+        Report {
+            results: [Self::EMPTY; MAX_TESTS],
+            len: 0,
+        }
+    }
+
+    fn push(&mut self, result: TestResult) {
+        self.results[self.len] = result;
+        self.len += 1;
+    }
+
+    /// Returns the results of the tests that have completed.
+    pub fn results(&self) -> &[TestResult] {
+        &self.results[..self.len]
+    }
+
+    /// Returns true if every completed test passed.
+    pub fn passed(&self) -> bool {
+        self.results().iter().all(TestResult::passed)
+    }
+}
+
+impl core::fmt::Debug for Report {
+    fn fmt(&self, f: &mut core::fmt::Formatter) -> core::fmt::Result {
+        f.debug_list().entries(self.results()).finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl defmt::Format for Report {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "{}", self.results())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Report {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.collect_seq(self.results())
+    }
+}
+
+/// Potential errors from [running](Runner) the tests.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Error<E> {
+    /// The controller reported that the [link status](crate::hal::Commands::get_link_status)
+    /// could not be read. Includes the status it returned.
+    LinkStatusFailed(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// At least one connection is active, so no tests were run. Includes the number of active
+    /// connections.
+    ConnectionsActive(usize),
+
+    /// A command could not be sent.
+    Command(hal::Error<E>),
+}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for Error<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::LinkStatusFailed(status) => {
+                defmt::write!(f, "LinkStatusFailed({})", Remote(status))
+            }
+            Error::ConnectionsActive(count) => defmt::write!(f, "ConnectionsActive({})", count),
+            Error::Command(e) => defmt::write!(f, "Command({})", e),
+        }
+    }
+}
+
+impl<E> Error<E> {
+    fn widen(failure: Error<crate::NoComm>) -> Error<E> {
+        match failure {
+            Error::LinkStatusFailed(status) => Error::LinkStatusFailed(status),
+            Error::ConnectionsActive(count) => Error::ConnectionsActive(count),
+            Error::Command(e) => Error::Command(e.widen()),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Step {
+    CheckLinks,
+    SetPowerLevel,
+    Start,
+    Dwell(Option<Duration>),
+    Stop,
+    ReadPacketCount,
+    Done,
+}
+
+/// Runs the tests of a [`Plan`] on a unit.
+///
+/// The runner is a [nonblocking procedure](crate#nonblocking-procedures) that also keeps time:
+/// [`poll`](Runner::poll) takes the current time, and ends each test once the [dwell](Plan::dwell)
+/// time has passed, so keep calling it while a test is running.
+///
+/// A test that fails is recorded in the [`Report`] with the status of the failing command, and the
+/// runner moves on to the next test.
+pub struct Runner<'a> {
+    plan: Plan<'a>,
+    report: Report,
+    step: Step,
+    index: usize,
+    current: Option<TestResult>,
+    awaiting: bool,
+    failure: Option<Error<crate::NoComm>>,
+}
+
+impl<'a> Runner<'a> {
+    /// Returns a runner for the given plan.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`PlanError`] if the plan is not [valid](Plan::validate).
+    pub fn new(plan: &Plan<'a>) -> Result<Runner<'a>, PlanError> {
+        plan.validate()?;
+
+        Ok(Runner {
+            plan: *plan,
+            report: Report::new(),
+            step: Step::CheckLinks,
+            index: 0,
+            current: None,
+            awaiting: false,
+            failure: None,
+        })
+    }
+
+    /// Returns the results of the tests that have completed so far.
+    pub fn report(&self) -> &Report {
+        &self.report
+    }
+
+    /// Returns true if every test has run.
+    pub fn is_done(&self) -> bool {
+        self.step == Step::Done
+    }
+
+    /// Sends the next command to the controller, unless the runner is waiting for the Command
+    /// Complete event of the previous one or for a test to run for the dwell time. `now` is the
+    /// current time, measured from any fixed point by a monotonic clock.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] while the tests are in progress.
+    /// - Returns [`ConnectionsActive`](Error::ConnectionsActive) if any connection is active when
+    ///   the runner starts.
+    /// - Returns other [errors](Error) if a command could not be sent. The tests can be retried
+    ///   with a new `Runner`, after ending any test that is still in progress.
+    pub fn poll<C>(&mut self, controller: &mut C, now: Duration) -> nb::Result<(), Error<C::Error>>
+    where
+        C: crate::CommandWriter,
+    {
+        if let Some(failure) = self.failure {
+            return Err(nb::Error::Other(Error::widen(failure)));
+        }
+        if self.step == Step::Done {
+            return Ok(());
+        }
+        if self.awaiting {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        if let Step::Dwell(deadline) = self.step {
+            let deadline = deadline.unwrap_or_else(|| now.saturating_add(self.plan.dwell));
+            if now < deadline {
+                self.step = Step::Dwell(Some(deadline));
+                return Err(nb::Error::WouldBlock);
+            }
+            self.step = Step::Stop;
+        }
+
+        let test = self.current.map(|current| current.test);
+        let result = match (self.step, test) {
+            (Step::CheckLinks, _) => controller.get_link_status().map_err(comm),
+            (
+                Step::SetPowerLevel,
+                Some(Test::Transmitter {
+                    power_level: Some(level),
+                    ..
+                }),
+            ) => controller.set_tx_power_level(level).map_err(comm),
+            (Step::Start, Some(Test::Tone { channel })) => controller.start_tone(channel),
+            (Step::Start, Some(Test::Transmitter { channel, .. })) => controller
+                .write_command(
+                    LE_TRANSMITTER_TEST,
+                    &[channel, self.plan.payload_length, self.plan.payload as u8],
+                )
+                .map_err(comm),
+            (Step::Start, Some(Test::Receiver { channel })) => controller
+                .write_command(LE_RECEIVER_TEST, &[channel])
+                .map_err(comm),
+            (Step::Stop, Some(Test::Tone { .. })) => controller.stop_tone().map_err(comm),
+            (Step::Stop, _) => controller.write_command(LE_TEST_END, &[]).map_err(comm),
+            (Step::ReadPacketCount, _) => controller.get_tx_test_packet_count().map_err(comm),
+            _ => Ok(()),
+        };
+        match result {
+            Ok(()) => {
+                self.awaiting = true;
+                Err(nb::Error::WouldBlock)
+            }
+            Err(nb::Error::WouldBlock) => Err(nb::Error::WouldBlock),
+            Err(nb::Error::Other(e)) => Err(nb::Error::Other(Error::Command(e))),
+        }
+    }
+
+    /// Advances the tests with the return parameters of the command that was sent last. Events
+    /// that do not complete that command are ignored.
+    pub fn handle_event(&mut self, event: &hci::Event<crate::event::BlueNRGEvent>) {
+        use hci::event::command::ReturnParameters as HciParams;
+
+        if !self.awaiting {
+            return;
+        }
+        let params = match *event {
+            hci::Event::CommandComplete(ref event) => &event.return_params,
+            _ => return,
+        };
+
+        if let HciParams::Vendor(ReturnParameters::HalGetLinkStatus(ref status)) = *params {
+            if self.step == Step::CheckLinks {
+                self.awaiting = false;
+                self.check_links(status);
+            }
+            return;
+        }
+
+        let mut current = match self.current {
+            Some(current) => current,
+            None => return,
+        };
+        let (status, packets) = match (self.step, current.test, params) {
+            (
+                Step::SetPowerLevel,
+                _,
+                HciParams::Vendor(ReturnParameters::HalSetTxPowerLevel(status)),
+            )
+            | (
+                Step::Start,
+                Test::Tone { .. },
+                HciParams::Vendor(ReturnParameters::HalStartTone(status)),
+            )
+            | (Step::Start, Test::Transmitter { .. }, HciParams::LeTransmitterTest(status))
+            | (Step::Start, Test::Receiver { .. }, HciParams::LeReceiverTest(status))
+            | (
+                Step::Stop,
+                Test::Tone { .. },
+                HciParams::Vendor(ReturnParameters::HalStopTone(status)),
+            ) => (*status, None),
+            (Step::Stop, Test::Transmitter { .. }, HciParams::LeTestEnd(end)) => (end.status, None),
+            (Step::Stop, Test::Receiver { .. }, HciParams::LeTestEnd(end)) => {
+                (end.status, Some(end.number_of_packets as u32))
+            }
+            (
+                Step::ReadPacketCount,
+                _,
+                HciParams::Vendor(ReturnParameters::HalGetTxTestPacketCount(count)),
+            ) => (count.status, Some(count.packet_count)),
+            _ => return,
+        };
+
+        self.awaiting = false;
+        if current.passed() {
+            current.status = status;
+        }
+        if packets.is_some() {
+            current.packets = packets;
+        }
+        let next = match (self.step, current.test) {
+            // A test that did not start does not need to be stopped.
+            (Step::SetPowerLevel, _) | (Step::Start, _) if !current.passed() => None,
+            (Step::SetPowerLevel, _) => Some(Step::Start),
+            (Step::Start, _) => Some(Step::Dwell(None)),
+            (Step::Stop, Test::Transmitter { .. }) if current.passed() => {
+                Some(Step::ReadPacketCount)
+            }
+            _ => None,
+        };
+        match next {
+            Some(step) => {
+                self.step = step;
+                self.current = Some(current);
+            }
+            None => self.finish(current),
+        }
+    }
+
+    fn check_links(&mut self, link_status: &crate::event::command::HalLinkStatus) {
+        if link_status.status != hci::Status::Success {
+            self.failure = Some(Error::LinkStatusFailed(link_status.status));
+            return;
+        }
+
+        let connections = link_status
+            .clients
+            .iter()
+            .filter(|client| client.state.is_connected())
+            .count();
+        if connections > 0 {
+            self.failure = Some(Error::ConnectionsActive(connections));
+            return;
+        }
+
+        self.next_test();
+    }
+
+    fn finish(&mut self, result: TestResult) {
+        self.report.push(result);
+        self.index += 1;
+        self.next_test();
+    }
+
+    fn next_test(&mut self) {
+        self.current = self.plan.test(self.index).map(|test| TestResult {
+            test,
+            status: hci::Status::Success,
+            packets: None,
+        });
+        self.step = match self.current.map(|current| current.test) {
+            None => Step::Done,
+            Some(Test::Transmitter {
+                power_level: Some(_),
+                ..
+            }) => Step::SetPowerLevel,
+            Some(_) => Step::Start,
+        };
+    }
+}
+
+fn comm<E>(e: nb::Error<E>) -> nb::Error<hal::Error<E>> {
+    match e {
+        nb::Error::WouldBlock => nb::Error::WouldBlock,
+        nb::Error::Other(c) => nb::Error::Other(hal::Error::Comm(c)),
+    }
+}
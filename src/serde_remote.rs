@@ -0,0 +1,427 @@
+//! Serde support for the types this crate uses from the Bluetooth HCI crate.
+//!
+//! The HCI crate does not implement `Serialize` or `Deserialize`, so fields of its types name one
+//! of the definitions here with `#[serde(with = "...")]` or `#[serde(serialize_with = "...")]`.
+//! Handles, addresses, opcodes, status codes, and other enumerations round-trip as the values the
+//! controller uses for them. Intervals and lengths serialize as their ranges of durations; the
+//! connection interval and scan window serialize through their getters.
+//!
+//! Some of the types that use these definitions can only be serialized, so not every `deserialize`
+//! function is used.
+
+use core::convert::TryFrom;
+use core::time::Duration;
+use serde::de::Error as _;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "hci::ConnectionHandle")]
+pub struct ConnectionHandleDef(pub u16);
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "hci::Opcode")]
+pub struct OpcodeDef(pub u16);
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "hci::BdAddr")]
+pub struct BdAddrDef(pub [u8; 6]);
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "hci::BdAddrType")]
+pub enum BdAddrTypeDef {
+    Public(#[serde(with = "BdAddrDef")] hci::BdAddr),
+    Random(#[serde(with = "BdAddrDef")] hci::BdAddr),
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "hci::host::EncryptionKey")]
+pub struct EncryptionKeyDef(pub [u8; 16]);
+
+#[derive(Serialize)]
+#[serde(remote = "hci::types::ConnectionInterval")]
+pub struct ConnectionIntervalDef {
+    #[serde(getter = "hci::types::ConnectionInterval::interval")]
+    interval: (Duration, Duration),
+    #[serde(getter = "hci::types::ConnectionInterval::conn_latency")]
+    conn_latency: u16,
+    #[serde(getter = "hci::types::ConnectionInterval::supervision_timeout")]
+    supervision_timeout: Duration,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(remote = "hci::types::ConnectionIntervalError")]
+pub enum ConnectionIntervalErrorDef {
+    Incomplete,
+    IntervalTooShort(Duration),
+    IntervalTooLong(Duration),
+    IntervalInverted(Duration, Duration),
+    BadConnectionLatency(u16),
+    SupervisionTimeoutTooShort(Duration, Duration),
+    SupervisionTimeoutTooLong(Duration),
+    ImpossibleSupervisionTimeout(Duration),
+}
+
+#[derive(Serialize)]
+#[serde(remote = "hci::types::ScanWindow")]
+pub struct ScanWindowDef {
+    #[serde(getter = "hci::types::ScanWindow::interval")]
+    interval: Duration,
+    #[serde(getter = "hci::types::ScanWindow::window")]
+    window: Duration,
+}
+
+/// (De)serializes an optional [`BdAddr`](hci::BdAddr) as its raw bytes.
+pub mod option_bd_addr {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S>(addr: &Option<hci::BdAddr>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        addr.map(|addr| addr.0).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<Option<hci::BdAddr>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(Option::<[u8; 6]>::deserialize(deserializer)?.map(hci::BdAddr))
+    }
+}
+
+/// (De)serializes an HCI [`Status`](hci::Status) as the status code sent by the controller.
+pub mod status {
+    use super::*;
+
+    pub fn serialize<V, S>(status: &hci::Status<V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        V: Copy + Into<u8>,
+        S: Serializer,
+    {
+        let code: u8 = (*status).into();
+        code.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, V, D>(deserializer: D) -> Result<hci::Status<V>, D::Error>
+    where
+        V: TryFrom<u8>,
+        D: Deserializer<'de>,
+    {
+        let code = u8::deserialize(deserializer)?;
+        hci::Status::try_from(code)
+            .map_err(|_| D::Error::custom(format_args!("bad status code {:#04x}", code)))
+    }
+}
+
+/// Serializes a borrowed [`BdAddrType`](hci::BdAddrType) with [`BdAddrTypeDef`].
+pub struct BdAddrTypeRef<'a>(pub &'a hci::BdAddrType);
+
+impl Serialize for BdAddrTypeRef<'_> {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        BdAddrTypeDef::serialize(self.0, serializer)
+    }
+}
+
+/// Serializes the items returned by a fresh iterator as a sequence. Used for events that only
+/// expose their contents through an iterator.
+pub struct Seq<F>(pub F);
+
+impl<F, I> Serialize for Seq<F>
+where
+    F: Fn() -> I,
+    I: IntoIterator,
+    I::Item: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq((self.0)())
+    }
+}
+
+/// (De)serializes the result of a command as its status code: 0 for success.
+pub mod status_result {
+    use super::*;
+
+    pub fn serialize<V, S>(
+        result: &Result<(), hci::Status<V>>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        V: Copy + Into<u8>,
+        S: Serializer,
+    {
+        match result {
+            Ok(()) => 0u8.serialize(serializer),
+            Err(status) => status::serialize(status, serializer),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn deserialize<'de, V, D>(deserializer: D) -> Result<Result<(), hci::Status<V>>, D::Error>
+    where
+        V: TryFrom<u8>,
+        D: Deserializer<'de>,
+    {
+        match status::deserialize(deserializer)? {
+            hci::Status::Success => Ok(Ok(())),
+            other => Ok(Err(other)),
+        }
+    }
+}
+
+/// Defines a module that (de)serializes an HCI enumeration as the code the controller uses for
+/// each variant. Variants that are not listed (for example, those added by later Bluetooth
+/// versions) cannot be serialized.
+macro_rules! code_enum {
+    ($module:ident, $type:ty { $($variant:ident = $code:expr),+ $(,)? }) => {
+        pub mod $module {
+            use super::*;
+
+            pub fn serialize<S>(value: &$type, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: Serializer,
+            {
+                #[allow(unreachable_patterns)]
+                let code: u8 = match value {
+                    $(<$type>::$variant => $code,)+
+                    _ => {
+                        return Err(serde::ser::Error::custom(format_args!(
+                            "unsupported {}",
+                            stringify!($type)
+                        )))
+                    }
+                };
+                code.serialize(serializer)
+            }
+
+            #[allow(dead_code)]
+            pub fn deserialize<'de, D>(deserializer: D) -> Result<$type, D::Error>
+            where
+                D: Deserializer<'de>,
+            {
+                match u8::deserialize(deserializer)? {
+                    $($code => Ok(<$type>::$variant),)+
+                    code => Err(D::Error::custom(format_args!(
+                        "bad {} code {:#04x}",
+                        stringify!($type),
+                        code
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+code_enum!(advertisement_event, hci::event::AdvertisementEvent {
+    Advertisement = 0x00,
+    DirectAdvertisement = 0x01,
+    Scan = 0x02,
+    NonConnectableAdvertisement = 0x03,
+    ScanResponse = 0x04,
+});
+
+code_enum!(central_clock_accuracy, hci::event::CentralClockAccuracy {
+    Ppm500 = 0x00,
+    Ppm250 = 0x01,
+    Ppm150 = 0x02,
+    Ppm100 = 0x03,
+    Ppm75 = 0x04,
+    Ppm50 = 0x05,
+    Ppm30 = 0x06,
+    Ppm20 = 0x07,
+});
+
+code_enum!(advertising_type, hci::types::AdvertisingType {
+    ConnectableUndirected = 0x00,
+    ConnectableDirectedHighDutyCycle = 0x01,
+    ScannableUndirected = 0x02,
+    NonConnectableUndirected = 0x03,
+    ConnectableDirectedLowDutyCycle = 0x04,
+});
+
+code_enum!(advertising_filter_policy, hci::host::AdvertisingFilterPolicy {
+    AllowConnectionAndScan = 0x00,
+    AllowConnectionWhiteListScan = 0x01,
+    WhiteListConnectionAllowScan = 0x02,
+    WhiteListConnectionAndScan = 0x03,
+});
+
+code_enum!(own_address_type, hci::host::OwnAddressType {
+    Public = 0x00,
+    Random = 0x01,
+});
+
+code_enum!(scan_type, hci::host::ScanType {
+    Passive = 0x00,
+    Active = 0x01,
+});
+
+/// (De)serializes a [`PeerAddrType`](hci::host::PeerAddrType) like a
+/// [`BdAddrType`](hci::BdAddrType).
+pub mod peer_addr_type {
+    use super::*;
+
+    pub fn serialize<S>(addr: &hci::host::PeerAddrType, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        #[allow(unreachable_patterns)]
+        let addr = match *addr {
+            hci::host::PeerAddrType::PublicDeviceAddress(addr) => hci::BdAddrType::Public(addr),
+            hci::host::PeerAddrType::RandomDeviceAddress(addr) => hci::BdAddrType::Random(addr),
+            _ => return Err(serde::ser::Error::custom("unsupported PeerAddrType")),
+        };
+        BdAddrTypeDef::serialize(&addr, serializer)
+    }
+
+    #[allow(dead_code)]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<hci::host::PeerAddrType, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Ok(match BdAddrTypeDef::deserialize(deserializer)? {
+            hci::BdAddrType::Public(addr) => hci::host::PeerAddrType::PublicDeviceAddress(addr),
+            hci::BdAddrType::Random(addr) => hci::host::PeerAddrType::RandomDeviceAddress(addr),
+        })
+    }
+}
+
+/// Serializes a list of [`PeerAddrType`](hci::host::PeerAddrType) with [`peer_addr_type`].
+pub fn peer_addr_types<S>(
+    addrs: &[hci::host::PeerAddrType],
+    serializer: S,
+) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+{
+    struct Item<'a>(&'a hci::host::PeerAddrType);
+
+    impl Serialize for Item<'_> {
+        fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+        where
+            S: Serializer,
+        {
+            peer_addr_type::serialize(self.0, serializer)
+        }
+    }
+
+    serializer.collect_seq(addrs.iter().map(Item))
+}
+
+// Converts a number of 0.625 ms units, as encoded by the HCI types, back to a duration.
+fn from_units(bytes: &[u8]) -> Duration {
+    Duration::from_micros(625 * u64::from(u16::from_le_bytes([bytes[0], bytes[1]])))
+}
+
+/// (De)serializes an [`ExpectedConnectionLength`](hci::types::ExpectedConnectionLength) as its
+/// range of durations.
+pub mod expected_connection_length {
+    use super::*;
+
+    pub fn serialize<S>(
+        length: &hci::types::ExpectedConnectionLength,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut bytes = [0; 4];
+        length.copy_into_slice(&mut bytes);
+        (from_units(&bytes[0..]), from_units(&bytes[2..])).serialize(serializer)
+    }
+
+    #[allow(dead_code)]
+    pub fn deserialize<'de, D>(
+        deserializer: D,
+    ) -> Result<hci::types::ExpectedConnectionLength, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let (min, max) = <(Duration, Duration)>::deserialize(deserializer)?;
+        hci::types::ExpectedConnectionLength::new(min, max)
+            .map_err(|e| D::Error::custom(format_args!("{:?}", e)))
+    }
+}
+
+/// (De)serializes an [`AdvertisingInterval`](hci::types::AdvertisingInterval) as its advertising
+/// type and range of durations. The range is empty for high duty cycle directed advertising.
+pub mod advertising_interval {
+    use super::*;
+
+    #[derive(Serialize, Deserialize)]
+    struct Interval {
+        #[serde(with = "advertising_type")]
+        advertising_type: hci::types::AdvertisingType,
+        interval: (Duration, Duration),
+    }
+
+    pub fn serialize<S>(
+        interval: &hci::types::AdvertisingInterval,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        let mut bytes = [0; 5];
+        interval.copy_into_slice(&mut bytes);
+        Interval {
+            advertising_type: interval.advertising_type(),
+            interval: (from_units(&bytes[0..]), from_units(&bytes[2..])),
+        }
+        .serialize(serializer)
+    }
+
+    #[allow(dead_code)]
+    pub fn deserialize<'de, D>(deserializer: D) -> Result<hci::types::AdvertisingInterval, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let Interval {
+            advertising_type,
+            interval: (min, max),
+        } = Interval::deserialize(deserializer)?;
+        let builder = hci::types::AdvertisingInterval::for_type(advertising_type);
+        if advertising_type == hci::types::AdvertisingType::ConnectableDirectedHighDutyCycle {
+            builder.build()
+        } else {
+            builder.with_range(min, max)
+        }
+        .map_err(|e| D::Error::custom(format_args!("{:?}", e)))
+    }
+}
+
+/// Implements `Serialize` and `Deserialize` for a bitflags type as its raw bits. Deserializing
+/// fails if any bit is not one of the defined flags.
+macro_rules! impl_serde_bitflags {
+    ($type:ident, $bits:ty) => {
+        impl serde::Serialize for $type {
+            fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+            where
+                S: serde::Serializer,
+            {
+                serde::Serialize::serialize(&self.bits(), serializer)
+            }
+        }
+
+        impl<'de> serde::Deserialize<'de> for $type {
+            fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+            where
+                D: serde::Deserializer<'de>,
+            {
+                let bits = <$bits as serde::Deserialize>::deserialize(deserializer)?;
+                $type::from_bits(bits).ok_or_else(|| {
+                    <D::Error as serde::de::Error>::custom(format_args!(
+                        "invalid bits {:#x} for {}",
+                        bits,
+                        stringify!($type)
+                    ))
+                })
+            }
+        }
+    };
+}
@@ -0,0 +1,342 @@
+//! Transmit power control in dBm.
+//!
+//! [`PowerControl`] applies [power levels](PowerLevel) chosen by output power, rather than by PA
+//! level. It can keep the [`TxPowerLevel`](crate::gap::AdvertisingDataType::TxPowerLevel)
+//! advertising data in sync with the level, and it can adjust the level to the RSSI observed on
+//! the connections.
+
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+use crate::event::command::ReturnParameters;
+use crate::gap::{self, AdvertisingDataType, Commands as GapCommands};
+use crate::hal::{self, Commands as HalCommands, PowerLevel};
+
+#[cfg(feature = "defmt")]
+use crate::defmt_remote::Remote;
+
+// Maximum number of connections of the controller.
+const MAX_LINKS: usize = 8;
+
+// Reported by the controller when the RSSI is not available.
+const RSSI_UNAVAILABLE: i8 = 127;
+
+/// Policy to adjust the power level to the RSSI observed on the connections.
+///
+/// The controller has a single power level for every connection, so the level is chosen for the
+/// connection with the weakest RSSI. Links are assumed to be symmetric: if the weakest connection
+/// is received 6 dB above the target, the level is lowered by about 6 dB. The RSSI observed before
+/// a new level is applied is discarded.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RssiPolicy {
+    /// RSSI, in dBm, at which the weakest connection should be received.
+    pub target_rssi: i8,
+
+    /// The level is only adjusted if the weakest RSSI is more than this many dB away from the
+    /// target.
+    pub hysteresis: u8,
+
+    /// Lowest level the policy may choose.
+    pub min_level: PowerLevel,
+
+    /// Highest level the policy may choose.
+    pub max_level: PowerLevel,
+}
+
+/// Potential errors from [`PowerControl`].
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub enum Error<E> {
+    /// The controller reported that a command failed. Includes the opcode of the command and the
+    /// status it returned.
+    CommandFailed(
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::OpcodeDef"))] hci::Opcode,
+        #[cfg_attr(feature = "serde", serde(with = "crate::serde_remote::status"))]
+        hci::Status<crate::event::Status>,
+    ),
+
+    /// The [power level](crate::hal::Commands::set_tx_power_level) could not be sent.
+    Hal(hal::Error<E>),
+
+    /// The [advertising data](crate::gap::Commands::update_advertising_data) could not be sent.
+    Gap(gap::Error<E>),
+}
+
+#[cfg(feature = "defmt")]
+impl<E: defmt::Format> defmt::Format for Error<E> {
+    fn format(&self, f: defmt::Formatter) {
+        match self {
+            Error::CommandFailed(opcode, status) => {
+                defmt::write!(f, "CommandFailed({}, {})", Remote(opcode), Remote(status))
+            }
+            Error::Hal(e) => defmt::write!(f, "Hal({})", e),
+            Error::Gap(e) => defmt::write!(f, "Gap({})", e),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum Step {
+    Idle,
+    SetLevel(PowerLevel),
+    UpdateAdvertisingData(PowerLevel),
+}
+
+/// Keeps track of the power level of the controller, and applies new levels.
+///
+/// Requesting a level does not send any commands. Call [`poll`](PowerControl::poll) to apply the
+/// requested level, and pass every event from the controller to
+/// [`handle_event`](PowerControl::handle_event), until `poll` returns `Ok`.
+#[derive(Clone, Debug)]
+pub struct PowerControl {
+    level: PowerLevel,
+    requested: Option<PowerLevel>,
+    sync_advertising_data: bool,
+    rssi_policy: Option<RssiPolicy>,
+    links: [Option<(hci::ConnectionHandle, i8)>; MAX_LINKS],
+    step: Step,
+    awaiting: bool,
+    failure: Option<(hci::Opcode, hci::Status<crate::event::Status>)>,
+}
+
+impl PowerControl {
+    /// Returns a power control for a controller that currently uses the given level.
+    pub fn new(level: PowerLevel) -> PowerControl {
+        PowerControl {
+            level,
+            requested: None,
+            sync_advertising_data: false,
+            rssi_policy: None,
+            links: [None; MAX_LINKS],
+            step: Step::Idle,
+            awaiting: false,
+            failure: None,
+        }
+    }
+
+    /// Returns the power level the controller currently uses.
+    pub fn level(&self) -> PowerLevel {
+        self.level
+    }
+
+    /// Returns true if there is no requested level left to apply.
+    pub fn is_idle(&self) -> bool {
+        self.requested.is_none() && self.step == Step::Idle
+    }
+
+    /// Enables or disables updating the
+    /// [`TxPowerLevel`](crate::gap::AdvertisingDataType::TxPowerLevel) advertising data each time
+    /// a level is applied. The advertising data contains the level
+    /// [rounded](PowerLevel::rounded_dbm) to the nearest dBm.
+    pub fn sync_advertising_data(&mut self, enable: bool) {
+        self.sync_advertising_data = enable;
+    }
+
+    /// Sets the policy used to adjust the level to the observed RSSI, or disables the adjustment.
+    pub fn set_rssi_policy(&mut self, policy: Option<RssiPolicy>) {
+        self.rssi_policy = policy;
+        self.adjust();
+    }
+
+    /// Requests the given level.
+    pub fn set_level(&mut self, level: PowerLevel) {
+        self.requested = if level == self.level && self.step == Step::Idle {
+            None
+        } else {
+            Some(level)
+        };
+    }
+
+    /// Requests the level with the output power closest to `dbm`, and returns it.
+    pub fn set_dbm(&mut self, dbm: f32) -> PowerLevel {
+        let level = PowerLevel::nearest(dbm);
+        self.set_level(level);
+
+        level
+    }
+
+    /// Requests the lowest level with an output power from `min` to `max` dBm, and returns it. If
+    /// no level is in that range, the level closest to the range is requested instead.
+    pub fn set_dbm_range(&mut self, min: f32, max: f32) -> PowerLevel {
+        let level = PowerLevel::lowest_within(min, max).unwrap_or_else(|| {
+            let below = PowerLevel::ALL.iter().rev().find(|level| level.dbm() < min);
+            let above = PowerLevel::ALL.iter().find(|level| level.dbm() > max);
+            match (below, above) {
+                (Some(below), Some(above)) if min - below.dbm() <= above.dbm() - max => *below,
+                (_, Some(above)) => *above,
+                (Some(below), None) => *below,
+                (None, None) => PowerLevel::nearest(min),
+            }
+        });
+        self.set_level(level);
+
+        level
+    }
+
+    /// Records the RSSI of a connection, and requests a new level if the [RSSI
+    /// policy](RssiPolicy) calls for one. RSSI values reported by [Read
+    /// RSSI](hci::host::Hci::read_rssi) are recorded by [`handle_event`](PowerControl::handle_event).
+    pub fn observe_rssi(&mut self, conn_handle: hci::ConnectionHandle, rssi: i8) {
+        if rssi == RSSI_UNAVAILABLE {
+            return;
+        }
+
+        let slot = self
+            .links
+            .iter()
+            .position(|link| matches!(link, Some((handle, _)) if *handle == conn_handle))
+            .or_else(|| self.links.iter().position(Option::is_none));
+        if let Some(slot) = slot {
+            self.links[slot] = Some((conn_handle, rssi));
+            self.adjust();
+        }
+    }
+
+    /// Forgets the RSSI of a connection, and requests a new level if the [RSSI policy](RssiPolicy)
+    /// calls for one without it. Disconnections are handled by
+    /// [`handle_event`](PowerControl::handle_event).
+    pub fn forget(&mut self, conn_handle: hci::ConnectionHandle) {
+        for link in self.links.iter_mut() {
+            if matches!(link, Some((handle, _)) if *handle == conn_handle) {
+                *link = None;
+            }
+        }
+        self.adjust();
+    }
+
+    fn adjust(&mut self) {
+        let policy = match self.rssi_policy {
+            Some(policy) => policy,
+            None => return,
+        };
+        let weakest = match self.links.iter().flatten().map(|&(_, rssi)| rssi).min() {
+            Some(rssi) => rssi,
+            None => return,
+        };
+        let margin = i16::from(weakest) - i16::from(policy.target_rssi);
+        if margin.abs() <= i16::from(policy.hysteresis) {
+            return;
+        }
+
+        // The lowest level that reaches the target, within the limits of the policy.
+        let wanted = self.level.dbm() - f32::from(margin);
+        let level = PowerLevel::ALL
+            .iter()
+            .filter(|level| {
+                level.dbm() >= policy.min_level.dbm() && level.dbm() <= policy.max_level.dbm()
+            })
+            .find(|level| level.dbm() >= wanted)
+            .copied()
+            .unwrap_or(policy.max_level);
+        self.set_level(level);
+    }
+
+    /// Sends the next command needed to apply the requested level, unless the power control is
+    /// waiting for the Command Complete event of the previous one.
+    ///
+    /// # Errors
+    ///
+    /// - Returns [`nb::Error::WouldBlock`] while a level is being applied.
+    /// - Returns [`CommandFailed`](Error::CommandFailed) once if the controller rejected a command.
+    ///   A rejected level is not retried until it is requested again.
+    /// - Returns [`Hal`](Error::Hal) or [`Gap`](Error::Gap) if a command could not be sent.
+    pub fn poll<C>(&mut self, controller: &mut C) -> nb::Result<(), Error<C::Error>>
+    where
+        C: crate::CommandWriter,
+    {
+        if let Some((opcode, status)) = self.failure.take() {
+            return Err(nb::Error::Other(Error::CommandFailed(opcode, status)));
+        }
+        if self.awaiting {
+            return Err(nb::Error::WouldBlock);
+        }
+        if self.step == Step::Idle {
+            match self.requested.take() {
+                Some(level) => self.step = Step::SetLevel(level),
+                None => return Ok(()),
+            }
+        }
+
+        let result = match self.step {
+            Step::SetLevel(level) => controller
+                .set_tx_power_level(level)
+                .map_err(|e| e.map(|c| Error::Hal(hal::Error::Comm(c)))),
+            Step::UpdateAdvertisingData(level) => controller
+                .update_advertising_data(&[
+                    2,
+                    AdvertisingDataType::TxPowerLevel as u8,
+                    level.rounded_dbm() as u8,
+                ])
+                .map_err(|e| e.map(Error::Gap)),
+            Step::Idle => Ok(()),
+        };
+        match result {
+            Ok(()) => {
+                self.awaiting = true;
+                Err(nb::Error::WouldBlock)
+            }
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Advances the power control with an event from the controller. Besides the Command Complete
+    /// events of its own commands, the power control records the RSSI from [Read
+    /// RSSI](hci::host::Hci::read_rssi) and forgets connections when they are disconnected.
+    pub fn handle_event(&mut self, event: &hci::Event<crate::event::BlueNRGEvent>) {
+        use hci::event::command::ReturnParameters as HciParams;
+
+        let params = match *event {
+            hci::Event::CommandComplete(ref event) => &event.return_params,
+            hci::Event::DisconnectionComplete(ref event) => {
+                if event.status == hci::Status::Success {
+                    self.forget(event.conn_handle);
+                }
+                return;
+            }
+            _ => return,
+        };
+
+        let (opcode, status) = match (self.step, params) {
+            (_, HciParams::ReadRssi(rssi)) => {
+                if rssi.status == hci::Status::Success {
+                    self.observe_rssi(rssi.conn_handle, rssi.rssi);
+                }
+                return;
+            }
+            (
+                Step::SetLevel(_),
+                HciParams::Vendor(ReturnParameters::HalSetTxPowerLevel(status)),
+            ) => (crate::opcode::HAL_SET_TX_POWER_LEVEL, *status),
+            (
+                Step::UpdateAdvertisingData(_),
+                HciParams::Vendor(ReturnParameters::GapUpdateAdvertisingData(status)),
+            ) => (crate::opcode::GAP_UPDATE_ADVERTISING_DATA, *status),
+            _ => return,
+        };
+        if !self.awaiting {
+            return;
+        }
+
+        self.awaiting = false;
+        if status != hci::Status::Success {
+            self.failure = Some((opcode, status));
+            self.step = Step::Idle;
+            return;
+        }
+        self.step = match self.step {
+            Step::SetLevel(level) => {
+                // The RSSI observed at the old level no longer applies.
+                self.level = level;
+                self.links = [None; MAX_LINKS];
+                if self.sync_advertising_data {
+                    Step::UpdateAdvertisingData(level)
+                } else {
+                    Step::Idle
+                }
+            }
+            _ => Step::Idle,
+        };
+    }
+}
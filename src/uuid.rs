@@ -0,0 +1,385 @@
+//! Bluetooth UUIDs, shared by the GATT commands and the events that report attributes.
+//!
+//! A [`Uuid`] is either a 16-bit UUID assigned by the Bluetooth SIG or a full 128-bit UUID. 16-bit
+//! (and 32-bit) UUIDs are shorthand for 128-bit UUIDs built on the [Bluetooth Base
+//! UUID](BASE_UUID) (Bluetooth Core Specification v4.1, Vol 3, Part B, Section 2.5.1), so UUIDs
+//! compare equal if they expand to the same 128-bit value.
+//!
+//! UUIDs can be parsed from and formatted as strings:
+//!
+//! ```
+//! # use bluenrg::uuid::{self, Uuid};
+//! let uuid: Uuid = "0000180d-0000-1000-8000-00805f9b34fb".parse().unwrap();
+//! assert_eq!(uuid, uuid::service::HEART_RATE);
+//! assert_eq!(uuid.to_u16(), Some(0x180D));
+//! ```
+//!
+//! The [`service`], [`declaration`], [`characteristic`], and [`descriptor`] modules list commonly
+//! used assigned numbers.
+
+use crate::command::check_buffer_len;
+use crate::EncodeError;
+use byteorder::{ByteOrder, LittleEndian};
+use core::fmt;
+use core::hash::{Hash, Hasher};
+use core::str::FromStr;
+
+/// The Bluetooth Base UUID, 00000000-0000-1000-8000-00805F9B34FB. A 16- or 32-bit UUID `x` is
+/// shorthand for `(x << 96) + BASE_UUID`.
+pub const BASE_UUID: u128 = 0x0000_0000_0000_1000_8000_0080_5F9B_34FB;
+
+/// A Bluetooth UUID.
+///
+/// Equality and hashing compare the 128-bit form, so `Uuid::Uuid16(0x180D)` is equal to the
+/// `Uuid::Uuid128` built from `0000180D-0000-1000-8000-00805F9B34FB`.
+#[derive(Copy, Clone, Debug)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Uuid {
+    /// 16-bit UUID
+    Uuid16(u16),
+
+    /// 128-bit UUID, in the little-endian byte order used on the wire.
+    Uuid128([u8; 16]),
+}
+
+impl Uuid {
+    /// Returns the 16-bit UUID.
+    pub const fn from_u16(uuid: u16) -> Uuid {
+        Uuid::Uuid16(uuid)
+    }
+
+    /// Returns the 128-bit UUID that the 32-bit UUID is shorthand for.
+    pub const fn from_u32(uuid: u32) -> Uuid {
+        Uuid::from_u128(((uuid as u128) << 96) + BASE_UUID)
+    }
+
+    /// Returns the 128-bit UUID with the given value. The most significant byte of the value is the
+    /// first byte of the string form.
+    pub const fn from_u128(uuid: u128) -> Uuid {
+        Uuid::Uuid128(uuid.to_le_bytes())
+    }
+
+    /// Returns the 128-bit value of the UUID, expanding a 16-bit UUID with the [`BASE_UUID`].
+    pub const fn to_u128(&self) -> u128 {
+        match *self {
+            Uuid::Uuid16(uuid) => ((uuid as u128) << 96) + BASE_UUID,
+            Uuid::Uuid128(bytes) => u128::from_le_bytes(bytes),
+        }
+    }
+
+    /// Returns the 128-bit form of the UUID, in the little-endian byte order used on the wire.
+    pub const fn to_le_bytes(&self) -> [u8; 16] {
+        self.to_u128().to_le_bytes()
+    }
+
+    /// Returns the 16-bit form of the UUID, if it has one: either it is a 16-bit UUID, or it is a
+    /// 128-bit UUID that is a 16-bit UUID expanded with the [`BASE_UUID`].
+    pub fn to_u16(&self) -> Option<u16> {
+        match *self {
+            Uuid::Uuid16(uuid) => Some(uuid),
+            Uuid::Uuid128(_) => {
+                let value = self.to_u128();
+                if value & !(0xFFFF << 96) == BASE_UUID {
+                    Some((value >> 96) as u16)
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    pub(crate) fn copy_into_slice(&self, bytes: &mut [u8]) -> Result<usize, EncodeError> {
+        match *self {
+            Uuid::Uuid16(uuid) => {
+                check_buffer_len(bytes, 3)?;
+
+                bytes[0] = 0x01;
+                LittleEndian::write_u16(&mut bytes[1..3], uuid);
+
+                Ok(3)
+            }
+            Uuid::Uuid128(uuid) => {
+                check_buffer_len(bytes, 17)?;
+
+                bytes[0] = 0x02;
+                bytes[1..17].copy_from_slice(&uuid);
+
+                Ok(17)
+            }
+        }
+    }
+}
+
+impl PartialEq for Uuid {
+    fn eq(&self, other: &Uuid) -> bool {
+        self.to_u128() == other.to_u128()
+    }
+}
+
+impl Eq for Uuid {}
+
+impl Hash for Uuid {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.to_u128().hash(state);
+    }
+}
+
+impl From<u16> for Uuid {
+    fn from(uuid: u16) -> Self {
+        Uuid::from_u16(uuid)
+    }
+}
+
+impl From<u128> for Uuid {
+    fn from(uuid: u128) -> Self {
+        Uuid::from_u128(uuid)
+    }
+}
+
+#[allow(deprecated)]
+impl From<crate::gatt::Uuid16> for Uuid {
+    fn from(uuid: crate::gatt::Uuid16) -> Self {
+        Uuid::Uuid16(uuid.0)
+    }
+}
+
+#[allow(deprecated)]
+impl From<crate::event::Uuid16> for Uuid {
+    fn from(uuid: crate::event::Uuid16) -> Self {
+        Uuid::Uuid16(uuid.0)
+    }
+}
+
+#[allow(deprecated)]
+impl From<crate::event::Uuid128> for Uuid {
+    fn from(uuid: crate::event::Uuid128) -> Self {
+        Uuid::Uuid128(uuid.0)
+    }
+}
+
+macro_rules! impl_uuid_eq {
+    ($type:ty) => {
+        #[allow(deprecated)]
+        impl PartialEq<Uuid> for $type {
+            fn eq(&self, other: &Uuid) -> bool {
+                Uuid::from(*self) == *other
+            }
+        }
+
+        #[allow(deprecated)]
+        impl PartialEq<$type> for Uuid {
+            fn eq(&self, other: &$type) -> bool {
+                *self == Uuid::from(*other)
+            }
+        }
+    };
+}
+
+impl_uuid_eq!(crate::gatt::Uuid16);
+impl_uuid_eq!(crate::event::Uuid16);
+impl_uuid_eq!(crate::event::Uuid128);
+
+/// Formats 16-bit UUIDs as 4 hex digits (`180d`), and 128-bit UUIDs in the standard hyphenated form
+/// (`0000180d-0000-1000-8000-00805f9b34fb`).
+impl fmt::Display for Uuid {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            Uuid::Uuid16(uuid) => write!(f, "{:04x}", uuid),
+            Uuid::Uuid128(_) => {
+                let value = self.to_u128();
+                write!(
+                    f,
+                    "{:08x}-{:04x}-{:04x}-{:04x}-{:012x}",
+                    value >> 96,
+                    (value >> 80) & 0xFFFF,
+                    (value >> 64) & 0xFFFF,
+                    (value >> 48) & 0xFFFF,
+                    value & 0xFFFF_FFFF_FFFF
+                )
+            }
+        }
+    }
+}
+
+/// Potential errors from parsing a [`Uuid`] from a string.
+#[derive(Copy, Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ParseError {
+    /// The string is not 4 or 8 hex digits (with an optional `0x` prefix), or a 36-character
+    /// hyphenated UUID. Includes the length of the string.
+    BadLength(usize),
+
+    /// The string has a character that is not a hex digit, or is missing a hyphen. Includes the
+    /// byte index of the character.
+    BadCharacter(usize),
+}
+
+/// Parses a 16-bit UUID from 4 hex digits, a 32-bit UUID from 8 hex digits (either with an optional
+/// `0x` prefix), or a 128-bit UUID in the standard hyphenated form. Hex digits may be upper or
+/// lower case.
+impl FromStr for Uuid {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Uuid, ParseError> {
+        const HYPHENS: [usize; 4] = [8, 13, 18, 23];
+
+        let (prefix_len, digits) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+            Some(digits) => (2, digits),
+            None => (0, s),
+        };
+        match digits.len() {
+            4 => Ok(Uuid::from_u16(parse_hex(digits, prefix_len, &[])? as u16)),
+            8 => Ok(Uuid::from_u32(parse_hex(digits, prefix_len, &[])? as u32)),
+            36 if prefix_len == 0 => Ok(Uuid::from_u128(parse_hex(s, 0, &HYPHENS)?)),
+            _ => Err(ParseError::BadLength(s.len())),
+        }
+    }
+}
+
+// Parses the hex digits in `s`, which must have hyphens at (and only at) the given indices.
+// `offset` is the index of `s` in the original string, for errors.
+fn parse_hex(s: &str, offset: usize, hyphens: &[usize]) -> Result<u128, ParseError> {
+    let mut value = 0;
+    for (i, c) in s.bytes().enumerate() {
+        if hyphens.contains(&i) {
+            if c != b'-' {
+                return Err(ParseError::BadCharacter(offset + i));
+            }
+            continue;
+        }
+
+        let digit = (c as char)
+            .to_digit(16)
+            .ok_or(ParseError::BadCharacter(offset + i))?;
+        value = (value << 4) | digit as u128;
+    }
+
+    Ok(value)
+}
+
+/// Assigned numbers of commonly used GATT services.
+pub mod service {
+    use super::Uuid;
+
+    /// Generic Access
+    pub const GENERIC_ACCESS: Uuid = Uuid::from_u16(0x1800);
+    /// Generic Attribute
+    pub const GENERIC_ATTRIBUTE: Uuid = Uuid::from_u16(0x1801);
+    /// Immediate Alert
+    pub const IMMEDIATE_ALERT: Uuid = Uuid::from_u16(0x1802);
+    /// Link Loss
+    pub const LINK_LOSS: Uuid = Uuid::from_u16(0x1803);
+    /// Tx Power
+    pub const TX_POWER: Uuid = Uuid::from_u16(0x1804);
+    /// Current Time
+    pub const CURRENT_TIME: Uuid = Uuid::from_u16(0x1805);
+    /// Health Thermometer
+    pub const HEALTH_THERMOMETER: Uuid = Uuid::from_u16(0x1809);
+    /// Device Information
+    pub const DEVICE_INFORMATION: Uuid = Uuid::from_u16(0x180A);
+    /// Heart Rate
+    pub const HEART_RATE: Uuid = Uuid::from_u16(0x180D);
+    /// Battery
+    pub const BATTERY: Uuid = Uuid::from_u16(0x180F);
+    /// Blood Pressure
+    pub const BLOOD_PRESSURE: Uuid = Uuid::from_u16(0x1810);
+    /// Human Interface Device
+    pub const HUMAN_INTERFACE_DEVICE: Uuid = Uuid::from_u16(0x1812);
+    /// Scan Parameters
+    pub const SCAN_PARAMETERS: Uuid = Uuid::from_u16(0x1813);
+    /// Running Speed and Cadence
+    pub const RUNNING_SPEED_AND_CADENCE: Uuid = Uuid::from_u16(0x1814);
+    /// Cycling Speed and Cadence
+    pub const CYCLING_SPEED_AND_CADENCE: Uuid = Uuid::from_u16(0x1816);
+    /// Environmental Sensing
+    pub const ENVIRONMENTAL_SENSING: Uuid = Uuid::from_u16(0x181A);
+}
+
+/// Assigned numbers of the GATT attribute types that declare services and characteristics.
+pub mod declaration {
+    use super::Uuid;
+
+    /// Primary Service
+    pub const PRIMARY_SERVICE: Uuid = Uuid::from_u16(0x2800);
+    /// Secondary Service
+    pub const SECONDARY_SERVICE: Uuid = Uuid::from_u16(0x2801);
+    /// Include
+    pub const INCLUDE: Uuid = Uuid::from_u16(0x2802);
+    /// Characteristic
+    pub const CHARACTERISTIC: Uuid = Uuid::from_u16(0x2803);
+}
+
+/// Assigned numbers of commonly used GATT characteristics.
+pub mod characteristic {
+    use super::Uuid;
+
+    /// Device Name
+    pub const DEVICE_NAME: Uuid = Uuid::from_u16(0x2A00);
+    /// Appearance
+    pub const APPEARANCE: Uuid = Uuid::from_u16(0x2A01);
+    /// Peripheral Privacy Flag
+    pub const PERIPHERAL_PRIVACY_FLAG: Uuid = Uuid::from_u16(0x2A02);
+    /// Reconnection Address
+    pub const RECONNECTION_ADDRESS: Uuid = Uuid::from_u16(0x2A03);
+    /// Peripheral Preferred Connection Parameters
+    pub const PERIPHERAL_PREFERRED_CONNECTION_PARAMETERS: Uuid = Uuid::from_u16(0x2A04);
+    /// Service Changed
+    pub const SERVICE_CHANGED: Uuid = Uuid::from_u16(0x2A05);
+    /// Alert Level
+    pub const ALERT_LEVEL: Uuid = Uuid::from_u16(0x2A06);
+    /// Tx Power Level
+    pub const TX_POWER_LEVEL: Uuid = Uuid::from_u16(0x2A07);
+    /// Battery Level
+    pub const BATTERY_LEVEL: Uuid = Uuid::from_u16(0x2A19);
+    /// Temperature Measurement
+    pub const TEMPERATURE_MEASUREMENT: Uuid = Uuid::from_u16(0x2A1C);
+    /// System ID
+    pub const SYSTEM_ID: Uuid = Uuid::from_u16(0x2A23);
+    /// Model Number String
+    pub const MODEL_NUMBER_STRING: Uuid = Uuid::from_u16(0x2A24);
+    /// Serial Number String
+    pub const SERIAL_NUMBER_STRING: Uuid = Uuid::from_u16(0x2A25);
+    /// Firmware Revision String
+    pub const FIRMWARE_REVISION_STRING: Uuid = Uuid::from_u16(0x2A26);
+    /// Hardware Revision String
+    pub const HARDWARE_REVISION_STRING: Uuid = Uuid::from_u16(0x2A27);
+    /// Software Revision String
+    pub const SOFTWARE_REVISION_STRING: Uuid = Uuid::from_u16(0x2A28);
+    /// Manufacturer Name String
+    pub const MANUFACTURER_NAME_STRING: Uuid = Uuid::from_u16(0x2A29);
+    /// Current Time
+    pub const CURRENT_TIME: Uuid = Uuid::from_u16(0x2A2B);
+    /// Heart Rate Measurement
+    pub const HEART_RATE_MEASUREMENT: Uuid = Uuid::from_u16(0x2A37);
+    /// Body Sensor Location
+    pub const BODY_SENSOR_LOCATION: Uuid = Uuid::from_u16(0x2A38);
+    /// Heart Rate Control Point
+    pub const HEART_RATE_CONTROL_POINT: Uuid = Uuid::from_u16(0x2A39);
+    /// PnP ID
+    pub const PNP_ID: Uuid = Uuid::from_u16(0x2A50);
+}
+
+/// Assigned numbers of the GATT characteristic descriptors. See also
+/// [`KnownDescriptor`](crate::gatt::KnownDescriptor).
+pub mod descriptor {
+    use super::Uuid;
+
+    /// Characteristic Extended Properties
+    pub const CHARACTERISTIC_EXTENDED_PROPERTIES: Uuid = Uuid::from_u16(0x2900);
+    /// Characteristic User Description
+    pub const CHARACTERISTIC_USER_DESCRIPTION: Uuid = Uuid::from_u16(0x2901);
+    /// Client Characteristic Configuration
+    pub const CLIENT_CHARACTERISTIC_CONFIGURATION: Uuid = Uuid::from_u16(0x2902);
+    /// Server Characteristic Configuration
+    pub const SERVER_CHARACTERISTIC_CONFIGURATION: Uuid = Uuid::from_u16(0x2903);
+    /// Characteristic Presentation Format
+    pub const CHARACTERISTIC_PRESENTATION_FORMAT: Uuid = Uuid::from_u16(0x2904);
+    /// Characteristic Aggregate Format
+    pub const CHARACTERISTIC_AGGREGATE_FORMAT: Uuid = Uuid::from_u16(0x2905);
+    /// Valid Range
+    pub const VALID_RANGE: Uuid = Uuid::from_u16(0x2906);
+    /// Report Reference
+    pub const REPORT_REFERENCE: Uuid = Uuid::from_u16(0x2908);
+}
@@ -0,0 +1,411 @@
+#![cfg(feature = "bluenrg-1-2")]
+
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::decode::{opcode_name, VendorCommand};
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::*;
+use bluenrg::gap::Commands as GapCommands;
+use bluenrg::gap::{
+    AuthenticationRequirements, IdentityAddressType, KeypressNotification, Pin,
+    SecureConnectionsSupport,
+};
+use bluenrg::hal::Commands as HalCommands;
+use bluenrg::hal::{EventFlags as HalEventFlags, RadioActivityFlags};
+use bluenrg::uuid::Uuid;
+use fixture::record;
+use hci::event::command::ReturnParameters as HciParams;
+use hci::event::{Event as HciEvent, Packet, VendorEvent};
+
+#[test]
+fn hal_commands() {
+    let written = record(|writer| {
+        writer
+            .set_radio_activity_mask(RadioActivityFlags::ADVERTISING | RadioActivityFlags::RX_TEST)
+            .unwrap();
+        writer
+            .set_hal_event_mask(HalEventFlags::SCAN_REQUEST_REPORT)
+            .unwrap();
+        writer.stack_reset().unwrap();
+    });
+    assert_eq!(
+        written,
+        [
+            (0xFC18, vec![0x82, 0x00]),
+            (0xFC1A, vec![0x01, 0x00, 0x00, 0x00]),
+            (0xFC3B, vec![]),
+        ]
+    );
+}
+
+#[test]
+fn gap_commands() {
+    let written = record(|writer| {
+        writer
+            .numeric_comparison_value_confirm(hci::ConnectionHandle(0x0201), true)
+            .unwrap();
+        writer
+            .passkey_input(
+                hci::ConnectionHandle(0x0201),
+                KeypressNotification::DigitErased,
+            )
+            .unwrap();
+        writer
+            .remove_bonded_device(hci::host::PeerAddrType::RandomDeviceAddress(hci::BdAddr([
+                1, 2, 3, 4, 5, 6,
+            ])))
+            .unwrap();
+        writer
+            .set_le_event_mask(
+                hci::host::LeEventFlags::CONNECTION_COMPLETE
+                    | hci::host::LeEventFlags::ADVERTISING_REPORT,
+            )
+            .unwrap();
+    });
+    assert_eq!(
+        written,
+        [
+            (0xFCA5, vec![0x01, 0x02, 0x01]),
+            (0xFCA6, vec![0x01, 0x02, 0x02]),
+            (0xFCAA, vec![0x01, 1, 2, 3, 4, 5, 6]),
+            (0xFCAF, vec![0x03, 0, 0, 0, 0, 0, 0, 0]),
+        ]
+    );
+}
+
+#[test]
+fn authentication_requirement() {
+    let requirements = AuthenticationRequirements {
+        mitm_protection_required: true,
+        secure_connections: SecureConnectionsSupport::Optional,
+        keypress_notification_support: true,
+        encryption_key_size_range: (8, 16),
+        fixed_pin: Pin::Fixed(123456),
+        bonding_required: true,
+        identity_address_type: IdentityAddressType::Random,
+    };
+    let written = record(|writer| {
+        writer
+            .set_authentication_requirement(&requirements)
+            .unwrap();
+    });
+    let expected = vec![1, 1, 1, 1, 8, 16, 0, 0x40, 0xE2, 0x01, 0x00, 1];
+    assert_eq!(written, [(0xFC86, expected.clone())]);
+
+    match VendorCommand::new(hci::Opcode(0xFC86), &expected) {
+        Ok(VendorCommand::GapSetAuthenticationRequirement(decoded)) => {
+            assert_eq!(
+                decoded.secure_connections,
+                SecureConnectionsSupport::Optional
+            );
+            assert!(decoded.keypress_notification_support);
+            assert_eq!(decoded.fixed_pin, Pin::Fixed(123456));
+            assert_eq!(decoded.identity_address_type, IdentityAddressType::Random);
+        }
+        other => panic!("Did not get authentication requirement: {:?}", other),
+    }
+}
+
+#[test]
+fn authentication_requirement_is_validated() {
+    let written = record(|writer| {
+        let err = writer
+            .set_authentication_requirement(&AuthenticationRequirements {
+                mitm_protection_required: false,
+                secure_connections: SecureConnectionsSupport::NotSupported,
+                keypress_notification_support: false,
+                encryption_key_size_range: (7, 16),
+                fixed_pin: Pin::Fixed(1_000_000),
+                bonding_required: false,
+                identity_address_type: IdentityAddressType::Public,
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(bluenrg::gap::Error::BadFixedPin(1_000_000))
+        );
+    });
+    assert!(written.is_empty());
+}
+
+#[test]
+fn decode_commands() {
+    assert_eq!(
+        opcode_name(hci::Opcode(0xFC18)),
+        Some("HAL_SET_RADIO_ACTIVITY_MASK")
+    );
+    assert_eq!(
+        opcode_name(hci::Opcode(0xFCAF)),
+        Some("GAP_SET_LE_EVENT_MASK")
+    );
+
+    match VendorCommand::new(hci::Opcode(0xFCA6), &[0x01, 0x02, 0x04]) {
+        Ok(VendorCommand::GapPasskeyInput { conn_handle, input }) => {
+            assert_eq!(conn_handle, hci::ConnectionHandle(0x0201));
+            assert_eq!(input, KeypressNotification::EntryCompleted);
+        }
+        other => panic!("Did not get passkey input: {:?}", other),
+    }
+    assert_eq!(
+        VendorCommand::new(hci::Opcode(0xFC18), &[0x00, 0x01]).err(),
+        Some(bluenrg::decode::Error::BadRadioActivityMask(0x0100))
+    );
+}
+
+fn assert_round_trip(buffer: &[u8]) -> BlueNRGEvent {
+    let event = BlueNRGEvent::new(buffer).unwrap();
+    let mut bytes = [0; 255];
+    let len = event.copy_into_slice(&mut bytes).unwrap();
+    assert_eq!(&bytes[..len], buffer);
+
+    event
+}
+
+#[test]
+fn hal_end_of_radio_activity() {
+    let buffer = [0x04, 0x00, 0x01, 0x02, 0x78, 0x56, 0x34, 0x12];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::HalEndOfRadioActivity(event) => {
+            assert_eq!(event.last_state, RadioState::Advertising);
+            assert_eq!(event.next_state, RadioState::PeripheralConnection);
+            assert_eq!(event.next_state_sys_time, 0x1234_5678);
+        }
+        other => panic!("Did not get end of radio activity: {:?}", other),
+    }
+}
+
+#[test]
+fn hal_end_of_radio_activity_bad_state() {
+    let buffer = [0x04, 0x00, 0x08, 0x02, 0x78, 0x56, 0x34, 0x12];
+    match BlueNRGEvent::new(&buffer) {
+        Err(hci::event::Error::Vendor(BlueNRGError::BadRadioState(8))) => (),
+        other => panic!("Did not get bad radio state: {:?}", other),
+    }
+}
+
+#[test]
+fn hal_scan_request_report() {
+    let buffer = [0x05, 0x00, 0xC4, 0x01, 1, 2, 3, 4, 5, 6];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::HalScanRequestReport(event) => {
+            assert_eq!(event.rssi, -60);
+            assert_eq!(
+                event.peer_addr,
+                hci::BdAddrType::Random(hci::BdAddr([1, 2, 3, 4, 5, 6]))
+            );
+        }
+        other => panic!("Did not get scan request report: {:?}", other),
+    }
+}
+
+#[test]
+fn gap_numeric_comparison_value() {
+    let buffer = [0x09, 0x04, 0x01, 0x02, 0x3F, 0x42, 0x0F, 0x00];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::GapNumericComparisonValue(event) => {
+            assert_eq!(event.conn_handle, hci::ConnectionHandle(0x0201));
+            assert_eq!(event.numeric_value, 999_999);
+        }
+        other => panic!("Did not get numeric comparison value: {:?}", other),
+    }
+}
+
+#[test]
+fn gap_keypress_notification() {
+    let buffer = [0x0A, 0x04, 0x01, 0x02, 0x01];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::GapKeypressNotification(event) => {
+            assert_eq!(event.conn_handle, hci::ConnectionHandle(0x0201));
+            assert_eq!(event.notification, KeypressNotification::DigitEntered);
+        }
+        other => panic!("Did not get keypress notification: {:?}", other),
+    }
+
+    match BlueNRGEvent::new(&[0x0A, 0x04, 0x01, 0x02, 0x05]) {
+        Err(hci::event::Error::Vendor(BlueNRGError::BadKeypressNotification(5))) => (),
+        other => panic!("Did not get bad keypress notification: {:?}", other),
+    }
+}
+
+#[test]
+fn gatt_attribute_modified() {
+    // A client enabling notifications, as sent by BlueNRG-2 firmware 2.1.
+    let packet = [
+        0xFF, 0x0C, 0x01, 0x0C, 0x01, 0x08, 0x0E, 0x00, 0x00, 0x00, 0x02, 0x00, 0x01, 0x00,
+    ];
+    match HciEvent::<BlueNRGEvent>::new(Packet(&packet)) {
+        Ok(HciEvent::Vendor(BlueNRGEvent::GattAttributeModified(event))) => {
+            assert_eq!(event.conn_handle, hci::ConnectionHandle(0x0801));
+            assert_eq!(event.attr_handle, AttributeHandle(0x000E));
+            assert_eq!(event.offset, 0);
+            assert!(!event.continued);
+            assert_eq!(event.data(), [0x01, 0x00]);
+        }
+        other => panic!("Did not get attribute modified: {:?}", other),
+    }
+    assert_round_trip(&packet[2..]);
+
+    let buffer = [
+        0x01, 0x0C, 0x01, 0x08, 0x0E, 0x00, 0x14, 0x80, 0x02, 0x00, 0x01, 0x02,
+    ];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::GattAttributeModified(event) => {
+            assert_eq!(event.offset, 20);
+            assert!(event.continued);
+            assert_eq!(event.data(), [0x01, 0x02]);
+        }
+        other => panic!("Did not get attribute modified: {:?}", other),
+    }
+
+    match BlueNRGEvent::new(&buffer[..11]) {
+        Err(hci::event::Error::BadLength(11, 12)) => (),
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
+#[test]
+fn gap_pairing_complete() {
+    let buffer = [0x01, 0x04, 0x01, 0x02, 0x02, 0x05];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::GapPairingComplete(event) => {
+            assert_eq!(event.conn_handle, hci::ConnectionHandle(0x0201));
+            assert_eq!(event.status, GapPairingStatus::Failed);
+            assert_eq!(event.reason, 0x05);
+        }
+        other => panic!("Did not get pairing complete: {:?}", other),
+    }
+}
+
+#[test]
+fn l2cap_connection_update_request() {
+    let buffer = [
+        0x02, 0x08, 0x01, 0x02, 0x04, 0x08, 0x00, 0x28, 0x00, 0x50, 0x00, 0x0A, 0x00, 0x58, 0x02,
+    ];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::L2CapConnectionUpdateRequest(event) => {
+            assert_eq!(event.conn_handle, hci::ConnectionHandle(0x0201));
+            assert_eq!(event.identifier, 0x04);
+            assert_eq!(event.conn_interval.conn_latency(), 10);
+        }
+        other => panic!("Did not get L2CAP connection update request: {:?}", other),
+    }
+}
+
+#[test]
+fn l2cap_connection_update_response() {
+    let buffer = [0x00, 0x08, 0x01, 0x02, 0x01, 0x00];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::L2CapConnectionUpdateResponse(event) => {
+            assert_eq!(event.conn_handle, hci::ConnectionHandle(0x0201));
+            assert_eq!(
+                event.result,
+                L2CapConnectionUpdateResult::ParametersRejected
+            );
+        }
+        other => panic!("Did not get L2CAP connection update response: {:?}", other),
+    }
+}
+
+#[test]
+fn att_find_information_response() {
+    let buffer = [
+        0x04, 0x0C, 0x01, 0x02, 1, 8, 0x03, 0x00, 0x00, 0x28, 0x04, 0x00, 0x03, 0x28,
+    ];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::AttFindInformationResponse(event) => match event.handle_uuid_pair_iter() {
+            HandleUuidPairIterator::Format16(iter) => {
+                let pairs: Vec<_> = iter.map(|pair| (pair.handle, pair.uuid)).collect();
+                assert_eq!(
+                    pairs,
+                    [
+                        (AttributeHandle(3), Uuid::Uuid16(0x2800)),
+                        (AttributeHandle(4), Uuid::Uuid16(0x2803)),
+                    ]
+                );
+            }
+            HandleUuidPairIterator::Format128(_) => panic!("Got 128-bit UUIDs"),
+        },
+        other => panic!("Did not get find information response: {:?}", other),
+    }
+}
+
+#[test]
+fn att_prepare_write_response() {
+    let buffer = [
+        0x0C, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 2, 0x07, 0x08,
+    ];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::AttPrepareWriteResponse(event) => {
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.offset, 0x0605);
+            assert_eq!(event.value(), [0x07, 0x08]);
+        }
+        other => panic!("Did not get prepare write response: {:?}", other),
+    }
+}
+
+#[test]
+fn gatt_notification() {
+    let buffer = [0x0F, 0x0C, 0x01, 0x02, 0x03, 0x04, 2, 0x05, 0x06];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::GattNotification(event) => {
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.value(), [0x05, 0x06]);
+        }
+        other => panic!("Did not get notification: {:?}", other),
+    }
+}
+
+#[test]
+fn gatt_procedure_complete() {
+    let buffer = [0x10, 0x0C, 0x01, 0x02, 0x41];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::GattProcedureComplete(event) => {
+            assert_eq!(event.status, GattProcedureStatus::Failed);
+        }
+        other => panic!("Did not get procedure complete: {:?}", other),
+    }
+}
+
+#[test]
+fn att_error_response() {
+    let buffer = [0x11, 0x0C, 0x01, 0x02, 0x0A, 0x04, 0x05, 0x0A];
+    match assert_round_trip(&buffer) {
+        BlueNRGEvent::AttErrorResponse(event) => {
+            assert_eq!(event.request, AttRequest::ReadRequest);
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0504));
+            assert_eq!(event.error, AttError::AttributeNotFound);
+        }
+        other => panic!("Did not get error response: {:?}", other),
+    }
+}
+
+#[test]
+fn version_variant() {
+    let version = bluenrg::Version {
+        hw_version: 0x31,
+        major: 2,
+        minor: 1,
+        patch: 0,
+    };
+    assert_eq!(version.variant(), bluenrg::Variant::BlueNRGMS);
+}
+
+#[test]
+fn stack_reset_return_parameters() {
+    let buffer = [0x0E, 4, 1, 0x3B, 0xFC, 0];
+    match HciEvent::<BlueNRGEvent>::new(Packet(&buffer)) {
+        Ok(HciEvent::CommandComplete(event)) => match event.return_params {
+            HciParams::Vendor(BNRGParams::HalStackReset(status)) => {
+                assert_eq!(status, hci::Status::Success);
+            }
+            other => panic!("Wrong return parameters: {:?}", other),
+        },
+        other => panic!("Did not get command complete event: {:?}", other),
+    }
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "cli")]
+
+use std::process::Command;
+
+fn decode(args: &[&str]) -> String {
+    let output = Command::new(env!("CARGO_BIN_EXE_bluenrg-decode"))
+        .args(args)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[test]
+fn names_and_decodes_vendor_commands() {
+    let output = decode(&["01 00 fc 00", "0x01 0x0d 0xfc 0x01 0x00"]);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "CMD 0xfc00 HAL_GET_FIRMWARE_REVISION: HalGetFirmwareRevision",
+            "CMD 0xfc0d HAL_READ_CONFIG_DATA: HalReadConfigData(PublicAddress)",
+        ]
+    );
+}
+
+#[test]
+fn decodes_return_parameters_and_vendor_events() {
+    let output = decode(&["04 0e 06 01 00 fc 00 13 07", "04 ff 03 01 00 01"]);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(
+        lines,
+        [
+            "EVT CommandComplete(CommandComplete { num_hci_command_packets: 1, return_params: \
+             Vendor(HalGetFirmwareRevision(HalFirmwareRevision { status: Success, \
+             revision: 1811 })) })",
+            "EVT Vendor(HalInitialized(Normal))",
+        ]
+    );
+}
+
+#[test]
+#[cfg(not(feature = "bluenrg-1-2"))]
+fn prints_event_payloads_in_full() {
+    let value = "00 01 02 03 04 05 06 07 08 09 0a 0b 0c 0d 0e 0f 10 11 12 13";
+    let output = decode(&[
+        &format!("04 ff 1b 0f 0c 01 02 16 03 04 {}", value),
+        &format!("04 ff 16 ff 0f {}", value),
+    ]);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 4);
+    assert!(lines[0].starts_with("EVT Vendor(GattNotification("));
+    assert_eq!(lines[1], format!("  value (20 bytes): {}", value));
+    assert!(lines[2].starts_with("EVT Vendor(Unknown { code: 4095"));
+    assert_eq!(lines[3], format!("  data (20 bytes): {}", value));
+}
+
+#[test]
+#[cfg(feature = "ms")]
+fn analyzes_crash_records() {
+    // Hard fault after a crash reset, with the Thumb bit clear and PC in SRAM.
+    let record = "01 02 08 01000000 \
+                  000f0020 00000000 00000000 00000000 00000000 00000000 \
+                  f9ffffff 10000020 03000000 \
+                  02 aa bb";
+    let output = decode(&["--crash", record]);
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines[0], "CRASH #1 HardFault after Crash reset");
+    assert!(lines.contains(&"  pc  0x20000010 (Sram)"));
+    assert!(lines.contains(&"  lr  0xfffffff9 (EXC_RETURN: return to thread mode, main stack)"));
+    assert!(lines.contains(&"  xpsr 0x00000003 (flags ----, HardFault, Thumb bit clear)"));
+    assert_eq!(lines.last(), Some(&"  debug data (2 bytes): aa bb"));
+    assert_eq!(
+        lines.iter().filter(|line| line.contains("warning")).count(),
+        2
+    );
+}
+
+#[test]
+fn rejects_odd_length_hex_tokens() {
+    let output = Command::new(env!("CARGO_BIN_EXE_bluenrg-decode"))
+        .arg("04 0e 04 01 abc def")
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert_eq!(
+        String::from_utf8(output.stderr).unwrap(),
+        "bluenrg-decode: odd number of hex digits in \"abc\"\n"
+    );
+}
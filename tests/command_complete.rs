@@ -702,3 +702,37 @@ fn gatt_read_handle_value_offset() {
         other => panic!("Did not get command complete event: {:?}", other),
     }
 }
+
+#[test]
+fn unknown_vendor_command() {
+    let buffer = [0x0E, 6, 1, 0x7F, 0xFC, 0x00, 0x01, 0x02];
+    match Event::new(Packet(&buffer)) {
+        Ok(HciEvent::CommandComplete(event)) => {
+            assert_eq!(event.num_hci_command_packets, 1);
+            match event.return_params {
+                HciParams::Vendor(BNRGParams::Unknown { opcode, bytes }) => {
+                    assert_eq!(opcode, hci::Opcode(0xFC7F));
+                    assert_eq!(bytes.as_slice(), &[0x00, 0x01, 0x02]);
+                }
+                other => panic!("Wrong return parameters: {:?}", other),
+            }
+        }
+        other => panic!("Did not get command complete event: {:?}", other),
+    }
+}
+
+#[cfg(not(feature = "ms"))]
+#[test]
+fn gatt_read_handle_value_offset_unknown() {
+    let buffer = [0x0E, 9, 1, 0x2B, 0xFD, 0x00, 0x03, 0x00, 1, 2, 3];
+    match Event::new(Packet(&buffer)) {
+        Ok(HciEvent::CommandComplete(event)) => match event.return_params {
+            HciParams::Vendor(BNRGParams::Unknown { opcode, bytes }) => {
+                assert_eq!(opcode, hci::Opcode(0xFD2B));
+                assert_eq!(bytes.as_slice(), &[0x00, 0x03, 0x00, 1, 2, 3]);
+            }
+            other => panic!("Wrong return parameters: {:?}", other),
+        },
+        other => panic!("Did not get command complete event: {:?}", other),
+    }
+}
@@ -0,0 +1,93 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+use bluenrg::gap::Commands as GapCommands;
+use bluenrg::gatt::Commands as GattCommands;
+use bluenrg::hal::Commands as HalCommands;
+use bluenrg::{CommandError, CommandWriter, ValidationError};
+
+type Result = nb::Result<(), CommandError<&'static str>>;
+
+struct FailingWriter {
+    result: nb::Result<(), &'static str>,
+}
+
+impl CommandWriter for FailingWriter {
+    type Error = &'static str;
+
+    fn write_command(
+        &mut self,
+        _opcode: hci::Opcode,
+        _params: &[u8],
+    ) -> nb::Result<(), Self::Error> {
+        self.result
+    }
+}
+
+#[test]
+fn validation_errors_keep_the_command_group() {
+    let mut writer = FailingWriter { result: Ok(()) };
+
+    let err: Result = writer
+        .update_advertising_data(&[0; 32])
+        .map_err(CommandError::rewrap);
+    assert_eq!(
+        err,
+        Err(nb::Error::Other(CommandError::Validation(
+            ValidationError::Gap(bluenrg::gap::Error::BadAdvertisingDataLength(32))
+        )))
+    );
+
+    let err: Result = writer.start_tone(40).map_err(CommandError::rewrap);
+    assert_eq!(
+        err,
+        Err(nb::Error::Other(CommandError::Validation(
+            ValidationError::Hal(bluenrg::hal::Error::InvalidChannel(40))
+        )))
+    );
+}
+
+#[test]
+fn communication_errors_from_every_command_group() {
+    let mut writer = FailingWriter {
+        result: Err(nb::Error::Other("spi")),
+    };
+
+    let err: Result = writer.set_nondiscoverable().map_err(CommandError::rewrap);
+    assert_eq!(err, Err(nb::Error::Other(CommandError::Comm("spi"))));
+
+    let err: Result = writer
+        .update_advertising_data(&[0x02, 0x01, 0x06])
+        .map_err(CommandError::rewrap);
+    assert_eq!(err, Err(nb::Error::Other(CommandError::Comm("spi"))));
+
+    let err: Result = GattCommands::init(&mut writer).map_err(CommandError::rewrap);
+    assert_eq!(err, Err(nb::Error::Other(CommandError::Comm("spi"))));
+}
+
+#[test]
+fn would_block_is_preserved() {
+    let mut writer = FailingWriter {
+        result: Err(nb::Error::WouldBlock),
+    };
+
+    let err: Result = writer
+        .update_advertising_data(&[0x02, 0x01, 0x06])
+        .map_err(CommandError::rewrap);
+    assert_eq!(err, Err(nb::Error::WouldBlock));
+}
+
+#[test]
+fn check_status() {
+    assert_eq!(
+        CommandError::<()>::check_status(hci::Status::Success),
+        Ok(())
+    );
+    assert_eq!(
+        CommandError::<()>::check_status(hci::Status::Vendor(bluenrg::event::Status::Failed)),
+        Err(CommandError::Status(hci::Status::Vendor(
+            bluenrg::event::Status::Failed
+        )))
+    );
+}
@@ -0,0 +1,214 @@
+#![cfg(feature = "ms")]
+
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+
+use bluenrg::crash::*;
+use bluenrg::event::{BlueNRGEvent, CrashReason, ResetReason};
+use hci::event::VendorEvent;
+
+#[derive(Default)]
+struct MemoryStorage {
+    counts: Option<[u8; COUNTS_LEN]>,
+    records: Vec<Vec<u8>>,
+}
+
+impl Storage for MemoryStorage {
+    type Error = ();
+
+    fn load_counts(&mut self, counts: &mut [u8; COUNTS_LEN]) -> Result<bool, ()> {
+        match self.counts {
+            Some(saved) => {
+                *counts = saved;
+                Ok(true)
+            }
+            None => Ok(false),
+        }
+    }
+
+    fn save_counts(&mut self, counts: &[u8; COUNTS_LEN]) -> Result<(), ()> {
+        self.counts = Some(*counts);
+        Ok(())
+    }
+
+    fn save_record(&mut self, record: &[u8]) -> Result<(), ()> {
+        self.records.push(record.to_vec());
+        Ok(())
+    }
+}
+
+fn vendor_event(buffer: &[u8]) -> hci::Event<BlueNRGEvent> {
+    hci::Event::Vendor(BlueNRGEvent::new(buffer).unwrap())
+}
+
+fn hal_initialized(reason: ResetReason) -> hci::Event<BlueNRGEvent> {
+    vendor_event(&[0x01, 0x00, reason.into()])
+}
+
+// A hard fault at PC 0x0000_1234 with 3 bytes of debug data.
+fn crash_report() -> hci::Event<BlueNRGEvent> {
+    let mut buffer = vec![0x03, 0x00, 0x02];
+    let registers: [u32; 9] = [
+        0x2000_0F00,
+        1,
+        2,
+        3,
+        4,
+        12,
+        0xFFFF_FFF9,
+        0x0000_1234,
+        0x0100_0003,
+    ];
+    for register in registers.iter() {
+        buffer.extend_from_slice(&register.to_le_bytes());
+    }
+    buffer.extend_from_slice(&[3, 0xAA, 0xBB, 0xCC]);
+    vendor_event(&buffer)
+}
+
+#[test]
+fn monitor_counts_resets_and_saves_reports() {
+    let mut monitor = Monitor::new(MemoryStorage::default()).unwrap();
+    assert_eq!(monitor.counts(), CrashCounts::default());
+
+    monitor
+        .handle_event(&hal_initialized(ResetReason::Normal))
+        .unwrap();
+    monitor
+        .handle_event(&hal_initialized(ResetReason::Watchdog))
+        .unwrap();
+    monitor
+        .handle_event(&hal_initialized(ResetReason::Crash))
+        .unwrap();
+    monitor.handle_event(&crash_report()).unwrap();
+    assert_eq!(
+        monitor.counts(),
+        CrashCounts {
+            crashes: 1,
+            watchdog_resets: 1,
+            lockups: 0,
+            reports: 1,
+        }
+    );
+    assert_eq!(monitor.counts().resets(), 2);
+
+    let storage = monitor.storage();
+    assert_eq!(storage.records.len(), 1);
+    let record = Record::from_bytes(&storage.records[0]).unwrap();
+    assert_eq!(record.crash_reason, CrashReason::HardFault);
+    assert_eq!(record.reset_reason, Some(ResetReason::Crash));
+    assert_eq!(record.sequence, 2);
+    assert_eq!(record.registers.pc, 0x0000_1234);
+    assert_eq!(record.debug_data, [0xAA, 0xBB, 0xCC]);
+
+    // The counts survive a reset of the host.
+    let storage = MemoryStorage {
+        counts: storage.counts,
+        records: Vec::new(),
+    };
+    let mut monitor = Monitor::new(storage).unwrap();
+    assert_eq!(monitor.counts().reports, 1);
+    monitor
+        .handle_event(&hal_initialized(ResetReason::Lockup))
+        .unwrap();
+    assert_eq!(monitor.counts().lockups, 1);
+
+    // A report without a preceding reset is saved without a reset reason.
+    monitor.handle_event(&crash_report()).unwrap();
+    monitor.handle_event(&crash_report()).unwrap();
+    let records = &monitor.storage().records;
+    assert_eq!(
+        Record::from_bytes(&records[0]).unwrap().reset_reason,
+        Some(ResetReason::Lockup)
+    );
+    assert_eq!(Record::from_bytes(&records[1]).unwrap().reset_reason, None);
+}
+
+#[test]
+fn record_format() {
+    let record = Record {
+        crash_reason: CrashReason::NmiFault,
+        reset_reason: None,
+        sequence: 0x0403_0201,
+        registers: Registers {
+            sp: 0x2000_0000,
+            r0: 0,
+            r1: 0,
+            r2: 0,
+            r3: 0,
+            r12: 0,
+            lr: 0x0000_0101,
+            pc: 0x0000_0200,
+            xpsr: 0x6100_0002,
+        },
+        debug_data: &[0x55],
+    };
+    assert_eq!(record.len(), 45);
+
+    let mut short = [0; 44];
+    assert_eq!(
+        record.copy_into_slice(&mut short),
+        Err(FormatError::BufferTooSmall(45))
+    );
+
+    let mut bytes = [0; MAX_RECORD_LEN];
+    assert_eq!(record.copy_into_slice(&mut bytes), Ok(45));
+    assert_eq!(bytes[..7], [1, 1, 0, 0x01, 0x02, 0x03, 0x04]);
+    assert_eq!(bytes[43..45], [1, 0x55]);
+    assert_eq!(Record::from_bytes(&bytes), Ok(record));
+
+    assert_eq!(
+        Record::from_bytes(&bytes[..44]),
+        Err(FormatError::Truncated(44))
+    );
+    bytes[2] = 0x20;
+    assert_eq!(
+        Record::from_bytes(&bytes),
+        Err(FormatError::BadResetReason(0x20))
+    );
+    bytes[0] = 2;
+    assert_eq!(Record::from_bytes(&bytes), Err(FormatError::BadVersion(2)));
+
+    let mut counts = CrashCounts::default().to_bytes();
+    counts[0] = 0xFF;
+    assert_eq!(
+        CrashCounts::from_bytes(&counts),
+        Err(FormatError::BadVersion(0xFF))
+    );
+}
+
+#[test]
+fn register_analysis() {
+    let registers = Registers {
+        sp: 0x2000_0F00,
+        r0: 0,
+        r1: 0,
+        r2: 0,
+        r3: 0,
+        r12: 0,
+        lr: 0xFFFF_FFF9,
+        pc: 0x1000_0000,
+        xpsr: 0x0100_0003,
+    };
+    assert_eq!(registers.exception_number(), 3);
+    assert!(registers.is_thumb());
+    assert_eq!(
+        registers.exception_return(),
+        Some(ExceptionReturn::ThreadMainStack)
+    );
+
+    let registers = Registers {
+        lr: 0x0000_0101,
+        xpsr: 0x0000_0010,
+        ..registers
+    };
+    assert_eq!(registers.exception_number(), 16);
+    assert!(!registers.is_thumb());
+    assert_eq!(registers.exception_return(), None);
+
+    assert_eq!(Region::of(0x0000_1234), Region::Code);
+    assert_eq!(Region::of(0x2000_0F00), Region::Sram);
+    assert_eq!(Region::of(0x4000_0000), Region::Peripheral);
+    assert_eq!(Region::of(0xA000_0000), Region::External);
+    assert_eq!(Region::of(0xE000_ED00), Region::System);
+}
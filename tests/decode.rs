@@ -0,0 +1,347 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::decode::*;
+use bluenrg::gap::Commands as GapCommands;
+use bluenrg::gatt::Commands as GattCommands;
+use bluenrg::hal::Commands as HalCommands;
+use bluenrg::l2cap::Commands as L2CapCommands;
+use fixture::RecordingWriter;
+use std::time::Duration;
+
+// Decodes the command, sends it again through the command traits, and checks that the same bytes
+// were written.
+fn round_trip<F>(opcode: u16, params: &[u8], resend: F)
+where
+    F: FnOnce(VendorCommand, &mut RecordingWriter),
+{
+    let cmd = VendorCommand::new(hci::Opcode(opcode), params).unwrap();
+    let mut writer = RecordingWriter::new();
+    resend(cmd, &mut writer);
+    assert_eq!(writer.written, [(opcode, params.to_vec())]);
+}
+
+#[test]
+fn hal_write_config_data() {
+    round_trip(0xFC0C, &[0x06, 2, 0x01, 0x02], |cmd, writer| match cmd {
+        VendorCommand::HalWriteConfigData(config) => {
+            assert_eq!(config.offset(), 6);
+            assert_eq!(config.value(), [0x01, 0x02]);
+            writer.write_config_data(&config).unwrap();
+        }
+        other => panic!("Did not get HAL Write Config Data: {:?}", other),
+    });
+}
+
+#[test]
+fn hal_write_config_data_not_on_field_boundaries() {
+    let cmd = VendorCommand::new(hci::Opcode(0xFC0C), &[0x07, 2, 0x01, 0x02]).unwrap();
+    let mut writer = RecordingWriter::new();
+    match cmd {
+        VendorCommand::HalWriteConfigData(config) => {
+            assert_eq!(
+                writer.write_config_data(&config),
+                Err(nb::Error::Other(bluenrg::hal::Error::BadConfigData(7, 2)))
+            );
+        }
+        other => panic!("Did not get HAL Write Config Data: {:?}", other),
+    }
+    assert!(writer.written.is_empty());
+}
+
+#[test]
+fn hal_set_tx_power_level() {
+    round_trip(0xFC0F, &[0x01, 0x07], |cmd, writer| match cmd {
+        VendorCommand::HalSetTxPowerLevel(level) => {
+            assert_eq!(level, bluenrg::hal::PowerLevel::Dbm8_0);
+            writer.set_tx_power_level(level).unwrap();
+        }
+        other => panic!("Did not get HAL Set Tx Power Level: {:?}", other),
+    });
+}
+
+#[test]
+fn gap_set_discoverable() {
+    round_trip(
+        0xFC83,
+        &[
+            0x00, 0x00, 0x08, 0x00, 0x10, 0x00, 0x00, 8, 0x08, 0x74, 0x65, 0x73, 0x74, 0x64, 0x65,
+            0x76, 4, 0x01, 0x02, 0x03, 0x04, 0x80, 0x0C, 0xFF, 0xFF,
+        ],
+        |cmd, writer| match cmd {
+            VendorCommand::GapSetDiscoverable(params) => {
+                assert_eq!(
+                    params.advertising_interval,
+                    Some((Duration::from_millis(1280), Duration::from_millis(2560)))
+                );
+                assert_eq!(
+                    params.local_name,
+                    Some(bluenrg::gap::LocalName::Shortened(b"testdev"))
+                );
+                assert_eq!(params.advertising_data, [0x01, 0x02, 0x03, 0x04]);
+                assert_eq!(
+                    params.conn_interval,
+                    (Some(Duration::from_millis(4000)), None)
+                );
+                writer.set_discoverable(&params).unwrap();
+            }
+            other => panic!("Did not get GAP Set Discoverable: {:?}", other),
+        },
+    );
+}
+
+#[cfg(not(feature = "ms"))]
+#[test]
+fn gap_start_auto_connection_establishment() {
+    round_trip(
+        0xFC99,
+        &[
+            0x04, 0x00, 0x04, 0x00, 0x01, 0x28, 0x00, 0xc8, 0x00, 10, 0, 0x58, 0x02, 0xF0, 0x00,
+            0x60, 0x09, 1, 10, 20, 30, 40, 50, 60, 2, 0, 1, 2, 3, 4, 5, 6, 1, 6, 5, 4, 3, 2, 1,
+        ],
+        |cmd, writer| match cmd {
+            VendorCommand::GapStartAutoConnectionEstablishment(params) => {
+                assert_eq!(
+                    params.reconnection_address,
+                    Some(hci::BdAddr([10, 20, 30, 40, 50, 60]))
+                );
+                assert_eq!(params.white_list.as_slice().len(), 2);
+                writer
+                    .start_auto_connection_establishment(&params.parameters())
+                    .unwrap();
+            }
+            other => panic!(
+                "Did not get GAP Start Auto Connection Establishment: {:?}",
+                other
+            ),
+        },
+    );
+}
+
+#[cfg(feature = "ms")]
+#[test]
+fn gap_start_auto_connection_establishment() {
+    round_trip(
+        0xFC99,
+        &[
+            0x04, 0x00, 0x04, 0x00, 0x01, 0x28, 0x00, 0xc8, 0x00, 10, 0, 0x58, 0x02, 0xF0, 0x00,
+            0x60, 0x09, 2, 0, 1, 2, 3, 4, 5, 6, 1, 6, 5, 4, 3, 2, 1,
+        ],
+        |cmd, writer| match cmd {
+            VendorCommand::GapStartAutoConnectionEstablishment(params) => {
+                assert_eq!(params.white_list.as_slice().len(), 2);
+                writer
+                    .start_auto_connection_establishment(&params.parameters())
+                    .unwrap();
+            }
+            other => panic!(
+                "Did not get GAP Start Auto Connection Establishment: {:?}",
+                other
+            ),
+        },
+    );
+}
+
+#[cfg(feature = "ms")]
+#[test]
+fn gap_set_broadcast_mode() {
+    round_trip(
+        0xFCA1,
+        &[
+            0x00, 0x01, 0x00, 0x02, 0x02, 0x01, 3, 0x01, 0x02, 0x03, 1, 0x00, 1, 2, 3, 4, 5, 6,
+        ],
+        |cmd, writer| match cmd {
+            VendorCommand::GapSetBroadcastMode(params) => {
+                assert_eq!(params.advertising_data, [0x01, 0x02, 0x03]);
+                writer.set_broadcast_mode(&params.parameters()).unwrap();
+            }
+            other => panic!("Did not get GAP Set Broadcast Mode: {:?}", other),
+        },
+    );
+}
+
+#[test]
+fn gap_terminate() {
+    round_trip(0xFC93, &[0x01, 0x02, 0x13], |cmd, writer| match cmd {
+        VendorCommand::GapTerminate {
+            conn_handle,
+            reason,
+        } => {
+            assert_eq!(conn_handle, hci::ConnectionHandle(0x0201));
+            assert_eq!(reason, hci::Status::RemoteTerminationByUser);
+            writer.terminate(conn_handle, reason).unwrap();
+        }
+        other => panic!("Did not get GAP Terminate: {:?}", other),
+    });
+}
+
+#[test]
+fn gatt_add_characteristic() {
+    round_trip(
+        0xFD04,
+        &[
+            0x01, 0x02, 0x01, 0x03, 0x04, 0x05, 0x01, 0x02, 0x01, 0x04, 0x07, 0x01,
+        ],
+        |cmd, writer| match cmd {
+            VendorCommand::GattAddCharacteristic(params) => {
+                assert_eq!(params.characteristic_value_len, 0x0105);
+                assert!(!params.fw_version_before_v72);
+                writer.add_characteristic(&params).unwrap();
+            }
+            other => panic!("Did not get GATT Add Characteristic: {:?}", other),
+        },
+    );
+}
+
+#[test]
+fn gatt_add_characteristic_before_v72() {
+    round_trip(
+        0xFD04,
+        &[
+            0x01, 0x02, 0x01, 0x03, 0x04, 0x05, 0x02, 0x01, 0x04, 0x07, 0x01,
+        ],
+        |cmd, writer| match cmd {
+            VendorCommand::GattAddCharacteristic(params) => {
+                assert_eq!(params.characteristic_value_len, 0x05);
+                assert!(params.fw_version_before_v72);
+                writer.add_characteristic(&params).unwrap();
+            }
+            other => panic!("Did not get GATT Add Characteristic: {:?}", other),
+        },
+    );
+}
+
+#[test]
+fn gatt_execute_and_cancel_write_request() {
+    round_trip(0xFD11, &[0x01, 0x02, 1], |cmd, writer| match cmd {
+        VendorCommand::GattExecuteWriteRequest(conn_handle) => {
+            writer.execute_write_request(conn_handle).unwrap();
+        }
+        other => panic!("Did not get GATT Execute Write Request: {:?}", other),
+    });
+    round_trip(0xFD11, &[0x01, 0x02, 0], |cmd, writer| match cmd {
+        VendorCommand::GattCancelWriteRequest(conn_handle) => {
+            writer.cancel_write_request(conn_handle).unwrap();
+        }
+        other => panic!("Did not get GATT Cancel Write Request: {:?}", other),
+    });
+}
+
+#[test]
+fn gatt_read_multiple_characteristic_values() {
+    round_trip(
+        0xFD1B,
+        &[0x01, 0x02, 3, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08],
+        |cmd, writer| match cmd {
+            VendorCommand::GattReadMultipleCharacteristicValues(params) => {
+                assert_eq!(
+                    params.handles(),
+                    [
+                        bluenrg::gatt::CharacteristicHandle(0x0403),
+                        bluenrg::gatt::CharacteristicHandle(0x0605),
+                        bluenrg::gatt::CharacteristicHandle(0x0807),
+                    ]
+                );
+                writer
+                    .read_multiple_characteristic_values(&params.parameters())
+                    .unwrap();
+            }
+            other => panic!(
+                "Did not get GATT Read Multiple Characteristic Values: {:?}",
+                other
+            ),
+        },
+    );
+}
+
+#[test]
+fn gatt_write_response() {
+    round_trip(
+        0xFD26,
+        &[0x01, 0x02, 0x03, 0x04, 1, 0x12, 3, 1, 2, 3],
+        |cmd, writer| match cmd {
+            VendorCommand::GattWriteResponse(params) => {
+                assert_eq!(params.status, Err(hci::Status::InvalidParameters));
+                assert_eq!(params.value, [1, 2, 3]);
+                writer.write_response(&params).unwrap();
+            }
+            other => panic!("Did not get GATT Write Response: {:?}", other),
+        },
+    );
+}
+
+#[test]
+fn l2cap_connection_parameter_update_response() {
+    round_trip(
+        0xFD82,
+        &[
+            0x01, 0x02, 0x28, 0x00, 0x50, 0x00, 0x0A, 0x00, 0x58, 0x02, 0x08, 0x00, 0x10, 0x00,
+            0x0F, 1,
+        ],
+        |cmd, writer| match cmd {
+            VendorCommand::L2CapConnectionParameterUpdateResponse(params) => {
+                assert_eq!(params.identifier, 0x0F);
+                assert!(params.accepted);
+                writer
+                    .connection_parameter_update_response(&params)
+                    .unwrap();
+            }
+            other => panic!(
+                "Did not get L2CAP Connection Parameter Update Response: {:?}",
+                other
+            ),
+        },
+    );
+}
+
+#[test]
+fn unknown_opcode() {
+    let err = VendorCommand::new(hci::Opcode(0xFC3F), &[]).err().unwrap();
+    assert_eq!(err, Error::UnknownOpcode(hci::Opcode(0xFC3F)));
+}
+
+#[test]
+fn bad_length() {
+    let err = VendorCommand::new(hci::Opcode(0xFC00), &[0]).err().unwrap();
+    assert_eq!(err, Error::BadLength(1, 0));
+
+    let err = VendorCommand::new(hci::Opcode(0xFD1C), &[0x01, 0x02, 0x03, 0x04, 3, 1, 2])
+        .err()
+        .unwrap();
+    assert_eq!(err, Error::BadLength(7, 8));
+}
+
+#[test]
+fn bad_values() {
+    let err = VendorCommand::new(hci::Opcode(0xFD11), &[0x01, 0x02, 2])
+        .err()
+        .unwrap();
+    assert_eq!(err, Error::BadBooleanValue(2));
+
+    let err = VendorCommand::new(hci::Opcode(0xFD02), &[0x03, 0x01, 0x02, 0x01, 3])
+        .err()
+        .unwrap();
+    assert_eq!(err, Error::BadUuidType(0x03));
+
+    let err = VendorCommand::new(hci::Opcode(0xFD0C), &[0x01, 0x02, 0x04, 0x00, 0x03, 0x00])
+        .err()
+        .unwrap();
+    assert_eq!(err, Error::InvertedRange(0x0004, 0x0003));
+}
+
+#[test]
+fn opcode_names() {
+    assert_eq!(
+        opcode_name(hci::Opcode(0xFC00)),
+        Some("HAL_GET_FIRMWARE_REVISION")
+    );
+    assert_eq!(
+        opcode_name(hci::Opcode(0xFC83)),
+        Some("GAP_SET_DISCOVERABLE")
+    );
+    assert_eq!(opcode_name(hci::Opcode(0xFC3F)), None);
+    assert_eq!(opcode_name(hci::Opcode(0x0C03)), None);
+}
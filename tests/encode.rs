@@ -0,0 +1,296 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+
+use bluenrg::event::command::ReturnParameters;
+use bluenrg::event::*;
+use hci::event::{VendorEvent, VendorReturnParameters};
+
+// Parses each event, serializes it again, and checks that the same bytes come back.
+fn check_events(buffers: &[&[u8]]) {
+    for buffer in buffers {
+        let event = BlueNRGEvent::new(buffer)
+            .unwrap_or_else(|e| panic!("Could not parse {:?}: {:?}", buffer, e));
+        let mut bytes = [0; 255];
+        let len = event.copy_into_slice(&mut bytes).unwrap();
+        assert_eq!(&bytes[..len], *buffer, "Encoded {:?}", event);
+    }
+}
+
+// Same as `check_events`, for return parameters. The buffers do not include the number of HCI
+// command packets, which is part of the Command Complete event.
+fn check_return_parameters(buffers: &[&[u8]]) {
+    for buffer in buffers {
+        let mut packet = vec![1];
+        packet.extend_from_slice(buffer);
+        let params = ReturnParameters::new(&packet)
+            .unwrap_or_else(|e| panic!("Could not parse {:?}: {:?}", buffer, e));
+        let mut bytes = [0; 255];
+        let len = params.copy_into_slice(&mut bytes).unwrap();
+        assert_eq!(&bytes[..len], *buffer, "Encoded {:?}", params);
+    }
+}
+
+#[test]
+fn events() {
+    check_events(&[
+        &[0x01, 0x00, 0x01],
+        &[0x01, 0x00, 0x09],
+        &[0x00, 0x04],
+        &[0x02, 0x04, 0x01, 0x02],
+        &[0x03, 0x04, 0x01, 0x02],
+        &[0x04, 0x04],
+        &[0x05, 0x04],
+        &[
+            0x06, 0x04, 0x00, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 3, 0x01, 0x02, 0x03, 0xF0,
+        ],
+        &[
+            0x06, 0x04, 0x04, 0x01, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0, 127,
+        ],
+        &[0x07, 0x04, 0x01, 0x00],
+        &[0x07, 0x04, 0x04, 0x41, 0x41, 0x42, 0x43],
+        &[0x07, 0x04, 0x10, 0x05, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06],
+        &[0x07, 0x04, 0x40, 0x00],
+        &[0x01, 0x08, 0x01, 0x02, 0],
+        &[0x02, 0x0C, 0x01, 0x02],
+        &[0x03, 0x0C, 0x01, 0x02, 1, 0x03, 0x04],
+        &[0x07, 0x0C, 0x01, 0x02, 4, 0x01, 0x02, 0x03, 0x04],
+        &[0x07, 0x0C, 0x01, 0x02, 0],
+        &[0x08, 0x0C, 0x01, 0x02, 2, 0x01, 0x02],
+        &[0x09, 0x0C, 0x01, 0x02, 1, 0x01],
+        &[
+            0x13, 0x0C, 0x01, 0x02, 0x03, 0x04, 4, 0x05, 0x06, 0x07, 0x08,
+        ],
+        &[0x14, 0x0C, 0x01, 0x02, 0x03, 0x04, 2, 0x05, 0x06],
+        &[0x00, 0x0F, 0x01, 0x02, 0x03],
+    ]);
+}
+
+// Events whose layout differs on the BlueNRG-1 and BlueNRG-2.
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn original_layout_events() {
+    check_events(&[
+        &[0x01, 0x04, 0x01, 0x02, 0x02],
+        &[
+            0x00, 0x08, 0x01, 0x02, 6, 0x01, 0x03, 0x02, 0x00, 0x02, 0x00,
+        ],
+        &[
+            0x00, 0x08, 0x01, 0x02, 6, 0x13, 0x07, 0x02, 0x00, 0x01, 0x00,
+        ],
+        &[
+            0x02, 0x08, 0x01, 0x02, 11, 0x04, 0x08, 0x00, 0x28, 0x00, 0x50, 0x00, 0x0A, 0x00, 0x58,
+            0x02,
+        ],
+        &[
+            0x04, 0x0C, 0x01, 0x02, 9, 1, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A,
+        ],
+        &[
+            0x04, 0x0C, 0x01, 0x02, 19, 2, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B,
+            0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14,
+        ],
+        &[
+            0x05, 0x0C, 0x01, 0x02, 8, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A,
+        ],
+        &[
+            0x06, 0x0C, 0x01, 0x02, 9, 4, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A,
+        ],
+        &[
+            0x0A, 0x0C, 0x01, 0x02, 13, 6, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+            0x0A, 0x0B, 0x0C,
+        ],
+        &[
+            0x0C, 0x0C, 0x01, 0x02, 6, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+        ],
+        &[0x0D, 0x0C, 0x01, 0x02, 0],
+        &[0x0E, 0x0C, 0x01, 0x02, 4, 0x03, 0x04, 0x05, 0x06],
+        &[0x0F, 0x0C, 0x01, 0x02, 2, 0x03, 0x04],
+        &[0x10, 0x0C, 0x01, 0x02, 1, 0x41],
+        &[0x11, 0x0C, 0x01, 0x02, 4, 0xD2, 0x04, 0x05, 0x9F],
+        &[
+            0x12, 0x0C, 0x01, 0x02, 6, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+        ],
+        &[0x15, 0x0C, 0x01, 0x02, 4, 0x03, 0x04, 0x05, 0x06],
+    ]);
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn bluenrg_1_2_layout_events() {
+    check_events(&[
+        &[0x01, 0x04, 0x01, 0x02, 0x02, 0x05],
+        &[0x00, 0x08, 0x01, 0x02, 0x00, 0x00],
+        &[0x00, 0x08, 0x01, 0x02, 0x01, 0x00],
+        &[
+            0x02, 0x08, 0x01, 0x02, 0x04, 0x08, 0x00, 0x28, 0x00, 0x50, 0x00, 0x0A, 0x00, 0x58,
+            0x02,
+        ],
+        &[
+            0x01, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x86, 2, 0, 0x07, 0x08,
+        ],
+        &[
+            0x04, 0x0C, 0x01, 0x02, 1, 8, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A,
+        ],
+        &[
+            0x04, 0x0C, 0x01, 0x02, 2, 18, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B,
+            0x0C, 0x0D, 0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14,
+        ],
+        &[
+            0x05, 0x0C, 0x01, 0x02, 2, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A,
+        ],
+        &[
+            0x06, 0x0C, 0x01, 0x02, 4, 8, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A,
+        ],
+        &[
+            0x0A, 0x0C, 0x01, 0x02, 6, 12, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09,
+            0x0A, 0x0B, 0x0C,
+        ],
+        &[
+            0x0C, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 2, 0x07, 0x08,
+        ],
+        &[0x0D, 0x0C, 0x01, 0x02],
+        &[0x0E, 0x0C, 0x01, 0x02, 0x03, 0x04, 2, 0x05, 0x06],
+        &[0x0F, 0x0C, 0x01, 0x02, 0x03, 0x04, 0],
+        &[0x10, 0x0C, 0x01, 0x02, 0x41],
+        &[0x11, 0x0C, 0x01, 0x02, 0xD2, 0x04, 0x05, 0x9F],
+        &[
+            0x12, 0x0C, 0x01, 0x02, 0x03, 0x04, 4, 0x05, 0x06, 0x07, 0x08,
+        ],
+        &[0x15, 0x0C, 0x01, 0x02, 2, 0x03, 0x04, 0x05, 0x06],
+    ]);
+}
+
+#[cfg(feature = "ms")]
+#[test]
+fn ms_events() {
+    let mut crash_report = vec![0x03, 0x00, 0x02];
+    crash_report.extend(1..=36);
+    crash_report.extend_from_slice(&[3, 0x25, 0x26, 0x27]);
+
+    check_events(&[
+        &[
+            0x02, 0x00, 0b10101010, 0b11001100, 0b11110000, 0b00001111, 0b00110011, 0b01010101,
+            0b00000001, 0b00000000,
+        ],
+        &crash_report,
+        &[0x08, 0x04, 0x01, 0x02],
+        &[0x16, 0x0C, 0x01, 0x02, 0x03, 0x04],
+        &[0x17, 0x0C, 0x01, 0x02],
+        &[
+            0x18, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 4, 0x07, 0x08, 0x09, 0x0a,
+        ],
+    ]);
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    check_events(&[&[
+        0x01, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x02, 0x05, 0x86, 0x07, 0x08,
+    ]]);
+}
+
+#[cfg(not(feature = "ms"))]
+#[test]
+fn non_ms_events() {
+    check_events(&[
+        &[0x08, 0x04, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06],
+        &[0x01, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x02, 0x07, 0x08],
+        &[0x02, 0x00, 0x01, 0x02],
+        &[0x16, 0x0C, 0x01, 0x02, 0x03, 0x04],
+    ]);
+}
+
+#[test]
+fn return_parameters() {
+    check_return_parameters(&[
+        &[0x00, 0xFC, 0x00, 0x01, 0x02],
+        &[0x0C, 0xFC, 0x00],
+        &[0x0D, 0xFC, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06],
+        &[0x0D, 0xFC, 0x00, 0x01, 0x02],
+        &[
+            0x0D, 0xFC, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B,
+            0x0C, 0x0D, 0x0E, 0x0F, 0x10,
+        ],
+        &[0x0D, 0xFC, 0x00, 0x01],
+        &[0x0F, 0xFC, 0x12],
+        &[0x14, 0xFC, 0x00, 0x01, 0x02, 0x03, 0x04],
+        &[
+            0x17, 0xFC, 0x00, 0, 1, 2, 3, 4, 5, 6, 7, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+            0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+        ],
+        &[
+            0x19, 0xFC, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+        ],
+        &[0x8A, 0xFC, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06],
+        &[0x90, 0xFC, 0x00, 0x00, 0x01, 0x00, 0x02],
+        &[
+            0xA3, 0xFC, 0x00, 2, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x01, 0x06, 0x05, 0x04,
+            0x03, 0x02, 0x01,
+        ],
+        &[0xA3, 0xFC, 0x12],
+        &[0x01, 0xFD, 0x00],
+        &[0x02, 0xFD, 0x00, 0x01, 0x02],
+        &[0x03, 0xFD, 0x00, 0x01, 0x02],
+        &[0x04, 0xFD, 0x00, 0x01, 0x02],
+        &[0x05, 0xFD, 0x00, 0x01, 0x02],
+        &[0x26, 0xFD, 0x12],
+        &[0x2A, 0xFD, 0x00, 0x03, 0x00, 0x01, 0x02, 0x03],
+        &[0x82, 0xFD, 0x00],
+        &[0x7F, 0xFC, 0x00, 0x01, 0x02],
+    ]);
+}
+
+#[cfg(feature = "ms")]
+#[test]
+fn ms_return_parameters() {
+    check_return_parameters(&[
+        &[0xA0, 0xFC, 0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06],
+        &[0xA0, 0xFC, 0x12],
+        &[0xA1, 0xFC, 0x00],
+        &[0x2B, 0xFD, 0x00, 0x03, 0x00, 0x01, 0x02, 0x03],
+        &[0x2C, 0xFD, 0x00],
+    ]);
+}
+
+#[cfg(not(feature = "ms"))]
+#[test]
+fn non_ms_return_parameters() {
+    check_return_parameters(&[
+        &[0xA0, 0xFC, 0x00],
+        &[0xA1, 0xFC, 0x00],
+        &[0x2B, 0xFD, 0x00, 0x03, 0x00, 0x01, 0x02, 0x03],
+    ]);
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn l2cap_connection_update_response_identifier() {
+    let buffer = [
+        0x00, 0x08, 0x01, 0x02, 6, 0x13, 0x2A, 0x02, 0x00, 0x00, 0x00,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::L2CapConnectionUpdateResponse(event)) => {
+            assert_eq!(event.identifier, 0x2A);
+        }
+        other => panic!("Did not get L2CAP connection update response: {:?}", other),
+    }
+}
+
+#[test]
+fn buffer_too_small() {
+    let event = BlueNRGEvent::new(&[0x02, 0x04, 0x01, 0x02]).unwrap();
+    let mut bytes = [0; 3];
+    assert_eq!(
+        event.copy_into_slice(&mut bytes),
+        Err(bluenrg::EncodeError::BufferTooSmall {
+            required: 4,
+            available: 3
+        })
+    );
+
+    let params = ReturnParameters::new(&[1, 0x00, 0xFC, 0x00, 0x01, 0x02]).unwrap();
+    let mut bytes = [0; 4];
+    assert_eq!(
+        params.copy_into_slice(&mut bytes),
+        Err(bluenrg::EncodeError::BufferTooSmall {
+            required: 5,
+            available: 4
+        })
+    );
+}
@@ -3,6 +3,8 @@ extern crate bluetooth_hci as hci;
 extern crate byteorder;
 
 use bluenrg::event::*;
+use bluenrg::uuid::Uuid;
+#[cfg(not(feature = "bluenrg-1-2"))]
 use byteorder::{ByteOrder, LittleEndian};
 use hci::event::{Error as HciError, VendorEvent};
 use std::time::Duration;
@@ -88,7 +90,10 @@ fn hal_events_lost_unknown() {
         0b00000000, 0b00000000,
     ];
     match BlueNRGEvent::new(&buffer) {
-        Err(HciError::Vendor(BlueNRGError::UnknownEvent(0x0002))) => (),
+        Ok(BlueNRGEvent::Unknown { code, data }) => {
+            assert_eq!(code, 0x0002);
+            assert_eq!(data.as_slice(), &buffer[2..]);
+        }
         other => panic!("Did not get unknown event: {:?}", other),
     }
 }
@@ -200,11 +205,15 @@ fn hal_crash_info_unknown() {
     buffer[0] = 0x03; // event code
     buffer[1] = 0x00;
     match BlueNRGEvent::new(&buffer) {
-        Err(HciError::Vendor(BlueNRGError::UnknownEvent(0x0003))) => (),
+        Ok(BlueNRGEvent::Unknown { code, data }) => {
+            assert_eq!(code, 0x0003);
+            assert_eq!(data.as_slice(), &buffer[2..]);
+        }
         other => panic!("Did not get unknown event: {:?}", other),
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 fn l2cap_connection_update_response_buffer(
     event_data_len: u8,
     response_code: u8,
@@ -222,8 +231,11 @@ fn l2cap_connection_update_response_buffer(
     buffer
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 const CONNECTION_UPDATE_RESP_EVENT_DATA_LEN: u8 = 6;
+#[cfg(not(feature = "bluenrg-1-2"))]
 const CONNECTION_UPDATE_RESP_L2CAP_LEN: u16 = 2;
+#[cfg(not(feature = "bluenrg-1-2"))]
 fn l2cap_connection_update_response_command_rejected_buffer() -> [u8; 11] {
     l2cap_connection_update_response_buffer(
         CONNECTION_UPDATE_RESP_EVENT_DATA_LEN,
@@ -233,6 +245,7 @@ fn l2cap_connection_update_response_command_rejected_buffer() -> [u8; 11] {
     )
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_response_cmd_rejected() {
     let buffer = l2cap_connection_update_response_command_rejected_buffer();
@@ -250,6 +263,7 @@ fn l2cap_connection_update_response_cmd_rejected() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_response_updated_accepted() {
     let buffer = l2cap_connection_update_response_buffer(
@@ -267,6 +281,7 @@ fn l2cap_connection_update_response_updated_accepted() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_response_updated_param_rejected() {
     let buffer = l2cap_connection_update_response_buffer(
@@ -285,6 +300,7 @@ fn l2cap_connection_update_response_updated_param_rejected() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_response_failed_code() {
     let buffer = l2cap_connection_update_response_buffer(
@@ -301,6 +317,7 @@ fn l2cap_connection_update_response_failed_code() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_response_failed_data_length() {
     let buffer = l2cap_connection_update_response_buffer(
@@ -317,6 +334,7 @@ fn l2cap_connection_update_response_failed_data_length() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_response_failed_l2cap_length() {
     let buffer = l2cap_connection_update_response_buffer(
@@ -333,6 +351,7 @@ fn l2cap_connection_update_response_failed_l2cap_length() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_response_failed_unknown_result() {
     let buffer = l2cap_connection_update_response_buffer(
@@ -349,6 +368,7 @@ fn l2cap_connection_update_response_failed_unknown_result() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_response_failed_unknown_rejection_reason() {
     let buffer = l2cap_connection_update_response_buffer(
@@ -365,6 +385,56 @@ fn l2cap_connection_update_response_failed_unknown_rejection_reason() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn l2cap_connection_update_response_updated_accepted() {
+    let buffer = [0x00, 0x08, 0x01, 0x02, 0x00, 0x00];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::L2CapConnectionUpdateResponse(resp)) => {
+            assert_eq!(resp.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(resp.result, L2CapConnectionUpdateResult::ParametersUpdated);
+        }
+        other => panic!("Did not get L2CAP connection update response: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn l2cap_connection_update_response_updated_param_rejected() {
+    let buffer = [0x00, 0x08, 0x01, 0x02, 0x01, 0x00];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::L2CapConnectionUpdateResponse(resp)) => {
+            assert_eq!(resp.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(resp.result, L2CapConnectionUpdateResult::ParametersRejected);
+        }
+        other => panic!("Did not get L2CAP connection update response: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn l2cap_connection_update_response_failed_length() {
+    let buffer = [
+        0x00, 0x08, 0x01, 0x02, 0x01, 0x03, 0x00, 0x02, 0x00, 0x01, 0x00,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::BadLength(11, 6)) => (),
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn l2cap_connection_update_response_failed_unknown_result() {
+    let buffer = [0x00, 0x08, 0x01, 0x02, 0x02, 0x00];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::BadL2CapConnectionResponseResult(result))) => {
+            assert_eq!(result, 0x0002)
+        }
+        other => panic!("Did not get bad result: {:?}", other),
+    }
+}
+
 #[test]
 fn l2cap_procedure_timeout() {
     let buffer = [0x01, 0x08, 0x01, 0x02, 0x00];
@@ -385,8 +455,11 @@ fn l2cap_procedure_timeout_failed() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 const L2CAP_CONN_UPDATE_REQ_EVENT_DATA_LEN: u8 = 11;
+#[cfg(not(feature = "bluenrg-1-2"))]
 const L2CAP_CONN_UPDATE_REQ_L2CAP_LEN: u16 = 8;
+#[cfg(not(feature = "bluenrg-1-2"))]
 fn l2cap_connection_update_request_buffer(
     event_data_len: u8,
     l2cap_len: u16,
@@ -409,6 +482,7 @@ fn l2cap_connection_update_request_buffer(
     buffer
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_request() {
     let buffer = l2cap_connection_update_request_buffer(
@@ -437,6 +511,7 @@ fn l2cap_connection_update_request() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_request_failed_event_data_len() {
     let buffer = l2cap_connection_update_request_buffer(
@@ -456,6 +531,7 @@ fn l2cap_connection_update_request_failed_event_data_len() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn l2cap_connection_update_request_failed_l2cap_len() {
     let buffer = l2cap_connection_update_request_buffer(
@@ -475,6 +551,57 @@ fn l2cap_connection_update_request_failed_l2cap_len() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn l2cap_connection_update_request() {
+    let buffer = [
+        0x02, 0x08, 0x01, 0x00, 0x02, 0x08, 0x00, 0x06, 0x00, 0x0A, 0x00, 0x0A, 0x00, 0x80, 0x0C,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::L2CapConnectionUpdateRequest(req)) => {
+            assert_eq!(req.conn_handle, ConnectionHandle(1));
+            assert_eq!(req.identifier, 2);
+            assert_eq!(
+                req.conn_interval.interval(),
+                (Duration::from_micros(7500), Duration::from_micros(12500))
+            );
+            assert_eq!(req.conn_interval.conn_latency(), 10);
+            assert_eq!(
+                req.conn_interval.supervision_timeout(),
+                Duration::from_millis(32000)
+            );
+        }
+        other => panic!("Did not get L2CAP connection update request: {:?}", other),
+    }
+}
+
+// The BlueNRG-1 and BlueNRG-2 do not send the L2CAP event data length, so the MS layout is too
+// long.
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn l2cap_connection_update_request_failed_length() {
+    let buffer = [
+        0x02, 0x08, 0x01, 0x00, 0x0B, 0x02, 0x08, 0x00, 0x06, 0x00, 0x0A, 0x00, 0x0A, 0x00, 0x80,
+        0x0C,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::BadLength(16, 15)) => (),
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn l2cap_connection_update_request_failed_l2cap_len() {
+    let buffer = [
+        0x02, 0x08, 0x01, 0x00, 0x02, 0x07, 0x00, 0x06, 0x00, 0x0A, 0x00, 0x0A, 0x00, 0x80, 0x0C,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::BadL2CapLength(7, 8))) => (),
+        other => panic!("Did not get L2CAP length: {:?}", other),
+    }
+}
+
 #[test]
 fn gap_limited_discoverable() {
     let buffer = [0x00, 0x04];
@@ -484,6 +611,7 @@ fn gap_limited_discoverable() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gap_pairing_complete() {
     let buffer = [0x01, 0x04, 0x01, 0x02, 0x00];
@@ -496,6 +624,7 @@ fn gap_pairing_complete() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gap_pairing_complete_failed() {
     let buffer = [0x01, 0x04, 0x01, 0x02, 0x03];
@@ -505,6 +634,41 @@ fn gap_pairing_complete_failed() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gap_pairing_complete() {
+    let buffer = [0x01, 0x04, 0x01, 0x02, 0x00, 0x00];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GapPairingComplete(evt)) => {
+            assert_eq!(evt.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(evt.status, GapPairingStatus::Success);
+            assert_eq!(evt.reason, 0);
+        }
+        other => panic!("Did not get GAP Pairing complete: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gap_pairing_complete_failed() {
+    let buffer = [0x01, 0x04, 0x01, 0x02, 0x03, 0x00];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::BadGapPairingStatus(value))) => assert_eq!(value, 3),
+        other => panic!("Did not get bad pairing status: {:?}", other),
+    }
+}
+
+// The MS layout, without the reason.
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gap_pairing_complete_failed_length() {
+    let buffer = [0x01, 0x04, 0x01, 0x02, 0x00];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::BadLength(5, 6)) => (),
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
 #[test]
 fn gap_pass_key_request() {
     let buffer = [0x02, 0x04, 0x01, 0x02];
@@ -714,7 +878,7 @@ fn gap_addr_not_resolved() {
     }
 }
 
-#[cfg(feature = "ms")]
+#[cfg(all(feature = "ms", not(feature = "bluenrg-1-2")))]
 #[test]
 fn gatt_attribute_modified() {
     let buffer = [
@@ -732,7 +896,7 @@ fn gatt_attribute_modified() {
     }
 }
 
-#[cfg(feature = "ms")]
+#[cfg(all(feature = "ms", not(feature = "bluenrg-1-2")))]
 #[test]
 fn gatt_attribute_modified_failed_bad_data_len() {
     let buffer = [
@@ -747,6 +911,39 @@ fn gatt_attribute_modified_failed_bad_data_len() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_attribute_modified() {
+    let buffer = [
+        0x01, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x86, 0x02, 0x00, 0x07, 0x08,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattAttributeModified(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.attr_handle, AttributeHandle(0x0403));
+            assert_eq!(event.offset, 0x0605);
+            assert!(event.continued);
+            assert_eq!(event.data(), [0x07, 0x08]);
+        }
+        other => panic!("Did not get Gatt attribute modified: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_attribute_modified_failed_bad_data_len() {
+    let buffer = [
+        0x01, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x03, 0x00, 0x07, 0x08,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::BadLength(actual, expected)) => {
+            assert_eq!(actual, buffer.len());
+            assert_eq!(expected, buffer.len() + 1);
+        }
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
 #[cfg(not(feature = "ms"))]
 #[test]
 fn gatt_attribute_modified() {
@@ -797,6 +994,7 @@ fn att_exchange_mtu_response() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_find_information_response_16bit_uuids() {
     let buffer = [
@@ -809,15 +1007,15 @@ fn att_find_information_response_16bit_uuids() {
             if let HandleUuidPairIterator::Format16(mut iter) = event.handle_uuid_pair_iter() {
                 let actual = iter.next().unwrap();
                 assert_eq!(actual.handle, AttributeHandle(0x0403));
-                assert_eq!(actual.uuid, Uuid16(0x0605));
+                assert_eq!(actual.uuid, Uuid::Uuid16(0x0605));
 
                 let actual = iter.next().unwrap();
                 assert_eq!(actual.handle, AttributeHandle(0x0807));
-                assert_eq!(actual.uuid, Uuid16(0x0a09));
+                assert_eq!(actual.uuid, Uuid::Uuid16(0x0a09));
 
                 let actual = iter.next().unwrap();
                 assert_eq!(actual.handle, AttributeHandle(0x0c0b));
-                assert_eq!(actual.uuid, Uuid16(0x0e0d));
+                assert_eq!(actual.uuid, Uuid::Uuid16(0x0e0d));
 
                 match iter.next() {
                     Some(actual) => panic!("Found extra HandleUuidPair: {:?}", actual),
@@ -831,6 +1029,13 @@ fn att_find_information_response_16bit_uuids() {
     }
 }
 
+// The response carries 36 bytes of handle/UUID pairs, more than the 32-byte payload limit.
+#[cfg(any(
+    not(feature = "event-payload-32"),
+    feature = "event-payload-64",
+    feature = "event-payload-128"
+))]
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_find_information_response_128bit_uuids() {
     let buffer = [
@@ -846,7 +1051,7 @@ fn att_find_information_response_128bit_uuids() {
                 assert_eq!(actual.handle, AttributeHandle(0x0403));
                 assert_eq!(
                     actual.uuid,
-                    Uuid128([
+                    Uuid::Uuid128([
                         0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e, 0x0f, 0x10,
                         0x11, 0x12, 0x13, 0x14,
                     ])
@@ -856,7 +1061,7 @@ fn att_find_information_response_128bit_uuids() {
                 assert_eq!(actual.handle, AttributeHandle(0x1615));
                 assert_eq!(
                     actual.uuid,
-                    Uuid128([
+                    Uuid::Uuid128([
                         0x17, 0x18, 0x19, 0x1a, 0x1b, 0x1c, 0x1d, 0x1e, 0x1f, 0x20, 0x21, 0x22,
                         0x23, 0x24, 0x25, 0x26,
                     ])
@@ -874,6 +1079,7 @@ fn att_find_information_response_128bit_uuids() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_find_information_response_failed_format() {
     let buffer = [0x04, 0x0C, 0x01, 0x02, 1, 3];
@@ -883,6 +1089,7 @@ fn att_find_information_response_failed_format() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_find_information_response_failed_partial_uuid() {
     let buffer = [
@@ -897,6 +1104,88 @@ fn att_find_information_response_failed_partial_uuid() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_find_information_response_16bit_uuids() {
+    let buffer = [
+        0x04, 0x0C, 0x01, 0x02, 1, 12, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+        0x0d, 0x0e,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::AttFindInformationResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            if let HandleUuidPairIterator::Format16(iter) = event.handle_uuid_pair_iter() {
+                let pairs: Vec<_> = iter.map(|pair| (pair.handle, pair.uuid)).collect();
+                assert_eq!(
+                    pairs,
+                    [
+                        (AttributeHandle(0x0403), Uuid::Uuid16(0x0605)),
+                        (AttributeHandle(0x0807), Uuid::Uuid16(0x0a09)),
+                        (AttributeHandle(0x0c0b), Uuid::Uuid16(0x0e0d)),
+                    ]
+                );
+            } else {
+                panic!("Did not get HandleUuidPair::Format16")
+            }
+        }
+        other => panic!("Did not get ATT find info response: {:?}", other),
+    }
+}
+
+// The response carries 36 bytes of handle/UUID pairs, more than the 32-byte payload limit.
+#[cfg(any(
+    not(feature = "event-payload-32"),
+    feature = "event-payload-64",
+    feature = "event-payload-128"
+))]
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_find_information_response_128bit_uuids() {
+    let buffer = [
+        0x04, 0x0C, 0x01, 0x02, 2, 36, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+        0x0d, 0x0e, 0x0f, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1a, 0x1b,
+        0x1c, 0x1d, 0x1e, 0x1f, 0x20, 0x21, 0x22, 0x23, 0x24, 0x25, 0x26,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::AttFindInformationResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            if let HandleUuidPairIterator::Format128(iter) = event.handle_uuid_pair_iter() {
+                let handles: Vec<_> = iter.map(|pair| pair.handle).collect();
+                assert_eq!(handles, [AttributeHandle(0x0403), AttributeHandle(0x1615)]);
+            } else {
+                panic!("Did not get HandleUuidPair::Format128")
+            }
+        }
+        other => panic!("Did not get ATT find info response: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_find_information_response_failed_format() {
+    let buffer = [0x04, 0x0C, 0x01, 0x02, 3, 0];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::BadAttFindInformationResponseFormat(3))) => (),
+        other => panic!("Did not get bad ATT Find info response format: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_find_information_response_failed_partial_uuid() {
+    let buffer = [
+        0x04, 0x0C, 0x01, 0x02, 1, 10, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::AttFindInformationResponsePartialPair16)) => (),
+        other => panic!(
+            "Did not get bad ATT Find info response partial pair: {:?}",
+            other
+        ),
+    }
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_find_by_type_value_response() {
     let buffer = [
@@ -925,6 +1214,7 @@ fn att_find_by_type_value_response() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_find_by_type_value_response_failed_partial_pair() {
     let buffer = [
@@ -939,6 +1229,45 @@ fn att_find_by_type_value_response_failed_partial_pair() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_find_by_type_value_response() {
+    let buffer = [
+        0x05, 0x0C, 0x01, 0x02, 2, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::AttFindByTypeValueResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            let pairs: Vec<_> = event
+                .handle_pairs_iter()
+                .map(|pair| (pair.attribute, pair.group_end))
+                .collect();
+            assert_eq!(
+                pairs,
+                [
+                    (AttributeHandle(0x0201), GroupEndHandle(0x0403)),
+                    (AttributeHandle(0x0605), GroupEndHandle(0x0807)),
+                ]
+            );
+        }
+        other => panic!("Did not get find-by-type-value response: {:?}", other),
+    }
+}
+
+// The BlueNRG-1 and BlueNRG-2 send the number of pairs, so a partial pair is a bad length.
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_find_by_type_value_response_failed_partial_pair() {
+    let buffer = [
+        0x05, 0x0C, 0x01, 0x02, 2, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::BadLength(12, 13)) => (),
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_read_by_type_response() {
     let buffer = [
@@ -967,6 +1296,7 @@ fn att_read_by_type_response() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_read_by_type_response_failed_partial_pair() {
     let buffer = [
@@ -979,6 +1309,45 @@ fn att_read_by_type_response_failed_partial_pair() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_read_by_type_response() {
+    let buffer = [
+        0x06, 0x0C, 0x01, 0x02, 6, 12, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x11, 0x12, 0x13, 0x14,
+        0x15, 0x16,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::AttReadByTypeResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+
+            let mut iter = event.handle_value_pair_iter();
+            let actual = iter.next().unwrap();
+            assert_eq!(actual.handle, AttributeHandle(0x0201));
+            assert_eq!(actual.value, [0x03, 0x04, 0x05, 0x06]);
+
+            let actual = iter.next().unwrap();
+            assert_eq!(actual.handle, AttributeHandle(0x1211));
+            assert_eq!(actual.value, [0x13, 0x14, 0x15, 0x16]);
+
+            assert!(iter.next().is_none());
+        }
+        other => panic!("Did not get read-by-type response: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_read_by_type_response_failed_partial_pair() {
+    let buffer = [
+        0x06, 0x0C, 0x01, 0x02, 6, 11, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x11, 0x12, 0x13, 0x14,
+        0x15,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::AttReadByTypeResponsePartial)) => (),
+        other => panic!("Did not get partial read-by-type response: {:?}", other),
+    }
+}
+
 #[test]
 fn att_read_response() {
     let buffer = [0x07, 0x0C, 0x01, 0x02, 4, 0x01, 0x02, 0x03, 0x04];
@@ -1087,6 +1456,7 @@ fn att_read_multiple_response_failed() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_read_by_group_type_response() {
     let buffer = [
@@ -1117,6 +1487,7 @@ fn att_read_by_group_type_response() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_read_by_group_type_response_failed() {
     let buffer = [
@@ -1132,13 +1503,58 @@ fn att_read_by_group_type_response_failed() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
 #[test]
-fn att_prepare_write_response() {
+fn att_read_by_group_type_response() {
     let buffer = [
-        0x0C, 0x0C, 0x01, 0x02, 8, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a,
+        0x0A, 0x0C, 0x01, 0x02, 8, 16, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x11, 0x12,
+        0x13, 0x14, 0x15, 0x16, 0x17, 0x18,
     ];
     match BlueNRGEvent::new(&buffer) {
-        Ok(BlueNRGEvent::AttPrepareWriteResponse(event)) => {
+        Ok(BlueNRGEvent::AttReadByGroupTypeResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+
+            let mut iter = event.attribute_data_iter();
+            let actual = iter.next().unwrap();
+            assert_eq!(actual.attribute_handle, AttributeHandle(0x0201));
+            assert_eq!(actual.group_end_handle, GroupEndHandle(0x0403));
+            assert_eq!(actual.value, [0x05, 0x06, 0x07, 0x08]);
+
+            let actual = iter.next().unwrap();
+            assert_eq!(actual.attribute_handle, AttributeHandle(0x1211));
+            assert_eq!(actual.group_end_handle, GroupEndHandle(0x1413));
+            assert_eq!(actual.value, [0x15, 0x16, 0x17, 0x18]);
+
+            assert!(iter.next().is_none());
+        }
+        other => panic!("Did not get Read by Group Type Response: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_read_by_group_type_response_failed() {
+    let buffer = [
+        0x0A, 0x0C, 0x01, 0x02, 8, 15, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x11, 0x12,
+        0x13, 0x14, 0x15, 0x16, 0x17,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::AttReadByGroupTypeResponsePartial)) => (),
+        other => panic!(
+            "Did not get partial Read by Group Type Response: {:?}",
+            other
+        ),
+    }
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn att_prepare_write_response() {
+    let buffer = [
+        0x0C, 0x0C, 0x01, 0x02, 8, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::AttPrepareWriteResponse(event)) => {
             assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
             assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
             assert_eq!(event.offset, 0x0605);
@@ -1148,6 +1564,7 @@ fn att_prepare_write_response() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_prepare_write_response_empty() {
     let buffer = [0x0C, 0x0C, 0x01, 0x02, 4, 0x03, 0x04, 0x05, 0x06];
@@ -1162,6 +1579,38 @@ fn att_prepare_write_response_empty() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_prepare_write_response() {
+    let buffer = [
+        0x0C, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 4, 0x07, 0x08, 0x09, 0x0a,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::AttPrepareWriteResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.offset, 0x0605);
+            assert_eq!(event.value(), [0x07, 0x08, 0x09, 0x0a]);
+        }
+        other => panic!("Did not get ATT prepare write response: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_prepare_write_response_empty() {
+    let buffer = [0x0C, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::AttPrepareWriteResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.offset, 0x0605);
+            assert_eq!(event.value(), []);
+        }
+        other => panic!("Did not get ATT prepare write response: {:?}", other),
+    }
+}
+
 #[test]
 fn att_execute_write_response() {
     let buffer = [0x0D, 0x0C, 0x01, 0x02, 0];
@@ -1173,6 +1622,7 @@ fn att_execute_write_response() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gatt_indication() {
     let buffer = [
@@ -1188,6 +1638,7 @@ fn gatt_indication() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gatt_indication_empty() {
     let buffer = [0x0E, 0x0C, 0x01, 0x02, 2, 0x03, 0x04];
@@ -1201,6 +1652,37 @@ fn gatt_indication_empty() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_indication() {
+    let buffer = [
+        0x0E, 0x0C, 0x01, 0x02, 0x03, 0x04, 4, 0x05, 0x06, 0x07, 0x08,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattIndication(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.value(), [0x05, 0x06, 0x07, 0x08]);
+        }
+        other => panic!("Did not get GATT Indication: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_indication_empty() {
+    let buffer = [0x0E, 0x0C, 0x01, 0x02, 0x03, 0x04, 0];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattIndication(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.value(), []);
+        }
+        other => panic!("Did not get GATT Indication: {:?}", other),
+    }
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gatt_notification() {
     let buffer = [
@@ -1216,6 +1698,7 @@ fn gatt_notification() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gatt_notification_empty() {
     let buffer = [0x0F, 0x0C, 0x01, 0x02, 2, 0x03, 0x04];
@@ -1229,6 +1712,37 @@ fn gatt_notification_empty() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_notification() {
+    let buffer = [
+        0x0F, 0x0C, 0x01, 0x02, 0x03, 0x04, 4, 0x05, 0x06, 0x07, 0x08,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattNotification(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.value(), [0x05, 0x06, 0x07, 0x08]);
+        }
+        other => panic!("Did not get GATT Notification: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_notification_empty() {
+    let buffer = [0x0F, 0x0C, 0x01, 0x02, 0x03, 0x04, 0];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattNotification(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.value(), []);
+        }
+        other => panic!("Did not get GATT Notification: {:?}", other),
+    }
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gatt_procedure_complete_success() {
     let buffer = [0x10, 0x0C, 0x01, 0x02, 1, 0];
@@ -1241,6 +1755,7 @@ fn gatt_procedure_complete_success() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gatt_procedure_complete_failed() {
     let buffer = [0x10, 0x0C, 0x01, 0x02, 1, 0x41];
@@ -1253,6 +1768,7 @@ fn gatt_procedure_complete_failed() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gatt_procedure_complete_error_unknown_code() {
     let buffer = [0x10, 0x0C, 0x01, 0x02, 1, 0x40];
@@ -1264,6 +1780,56 @@ fn gatt_procedure_complete_error_unknown_code() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_procedure_complete_success() {
+    let buffer = [0x10, 0x0C, 0x01, 0x02, 0];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattProcedureComplete(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.status, GattProcedureStatus::Success);
+        }
+        other => panic!("Did not get GATT Procedure Complete: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_procedure_complete_failed() {
+    let buffer = [0x10, 0x0C, 0x01, 0x02, 0x41];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattProcedureComplete(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.status, GattProcedureStatus::Failed);
+        }
+        other => panic!("Did not get GATT Procedure Complete: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_procedure_complete_error_unknown_code() {
+    let buffer = [0x10, 0x0C, 0x01, 0x02, 0x40];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::BadGattProcedureStatus(code))) => {
+            assert_eq!(code, 0x40);
+        }
+        other => panic!("Did not get Bad GATT Procedure Status: {:?}", other),
+    }
+}
+
+// The MS layout, with the event data length.
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_procedure_complete_failed_length() {
+    let buffer = [0x10, 0x0C, 0x01, 0x02, 1, 0];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::BadLength(6, 5)) => (),
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_error_response() {
     let buffer = [0x11, 0x0C, 0x01, 0x02, 4, 0x03, 0x04, 0x05, 0x07];
@@ -1278,6 +1844,7 @@ fn att_error_response() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_error_response_failed_bad_request_opcode() {
     let buffer = [0x11, 0x0C, 0x01, 0x02, 4, 0x48, 0x04, 0x05, 0x07];
@@ -1289,6 +1856,7 @@ fn att_error_response_failed_bad_request_opcode() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_error_response_failed_bad_error_code() {
     let buffer = [0x11, 0x0C, 0x01, 0x02, 4, 0x03, 0x04, 0x05, 0x12];
@@ -1300,6 +1868,46 @@ fn att_error_response_failed_bad_error_code() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_error_response() {
+    let buffer = [0x11, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x07];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::AttErrorResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.request, AttRequest::ExchangeMtuResponse);
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0504));
+            assert_eq!(event.error, AttError::InvalidOffset);
+        }
+        other => panic!("Did not get ATT error response: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_error_response_failed_bad_request_opcode() {
+    let buffer = [0x11, 0x0C, 0x01, 0x02, 0x48, 0x04, 0x05, 0x07];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::BadAttRequestOpcode(code))) => {
+            assert_eq!(code, 0x48);
+        }
+        other => panic!("Did not get bad ATT request opcode: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_error_response_failed_bad_error_code() {
+    let buffer = [0x11, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x12];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::Vendor(BlueNRGError::BadAttError(code))) => {
+            assert_eq!(code, 0x12);
+        }
+        other => panic!("Did not get bad ATT error code: {:?}", other),
+    }
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gatt_discover_or_read_characteristic_by_uuid_response() {
     let buffer = [
@@ -1315,6 +1923,7 @@ fn gatt_discover_or_read_characteristic_by_uuid_response() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn gatt_discover_or_read_characteristic_by_uuid_response_empty() {
     let buffer = [0x12, 0x0C, 0x01, 0x02, 2, 0x03, 0x04];
@@ -1328,6 +1937,42 @@ fn gatt_discover_or_read_characteristic_by_uuid_response_empty() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_discover_or_read_characteristic_by_uuid_response() {
+    let buffer = [
+        0x12, 0x0C, 0x01, 0x02, 0x03, 0x04, 4, 0x05, 0x06, 0x07, 0x08,
+    ];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattDiscoverOrReadCharacteristicByUuidResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.value(), [0x05, 0x06, 0x07, 0x08]);
+        }
+        other => panic!(
+            "Did not get GATT Discover or Read Characteristic by UUID Response: {:?}",
+            other
+        ),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn gatt_discover_or_read_characteristic_by_uuid_response_empty() {
+    let buffer = [0x12, 0x0C, 0x01, 0x02, 0x03, 0x04, 0];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattDiscoverOrReadCharacteristicByUuidResponse(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(event.attribute_handle, AttributeHandle(0x0403));
+            assert_eq!(event.value(), []);
+        }
+        other => panic!(
+            "Did not get GATT Discover or Read Characteristic by UUID Response: {:?}",
+            other
+        ),
+    }
+}
+
 #[test]
 fn att_write_permit_request() {
     let buffer = [
@@ -1369,6 +2014,7 @@ fn att_read_permit_request() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_read_multiple_permit_request() {
     let buffer = [0x15, 0x0C, 0x01, 0x02, 4, 0x03, 0x04, 0x05, 0x06];
@@ -1384,6 +2030,7 @@ fn att_read_multiple_permit_request() {
     }
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn att_read_multiple_permit_request_failed() {
     let buffer = [0x15, 0x0C, 0x01, 0x02, 3, 0x03, 0x04, 0x05];
@@ -1396,6 +2043,33 @@ fn att_read_multiple_permit_request_failed() {
     }
 }
 
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_read_multiple_permit_request() {
+    let buffer = [0x15, 0x0C, 0x01, 0x02, 2, 0x03, 0x04, 0x05, 0x06];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::AttReadMultiplePermitRequest(event)) => {
+            assert_eq!(event.conn_handle, ConnectionHandle(0x0201));
+            assert_eq!(
+                event.handles(),
+                [AttributeHandle(0x0403), AttributeHandle(0x0605)]
+            );
+        }
+        other => panic!("Did not get ATT Read Multiple Permit Request: {:?}", other),
+    }
+}
+
+// The BlueNRG-1 and BlueNRG-2 send the number of handles, so a partial handle is a bad length.
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_read_multiple_permit_request_failed() {
+    let buffer = [0x15, 0x0C, 0x01, 0x02, 2, 0x03, 0x04, 0x05];
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::BadLength(8, 9)) => (),
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
 #[cfg(feature = "ms")]
 #[test]
 fn gatt_tx_pool_available() {
@@ -1414,7 +2088,10 @@ fn gatt_tx_pool_available() {
 fn gatt_tx_pool_available_unknown() {
     let buffer = [0x16, 0x0C, 0x01, 0x02, 0x03, 0x04];
     match BlueNRGEvent::new(&buffer) {
-        Err(HciError::Vendor(BlueNRGError::UnknownEvent(0x0C16))) => (),
+        Ok(BlueNRGEvent::Unknown { code, data }) => {
+            assert_eq!(code, 0x0C16);
+            assert_eq!(data.as_slice(), &buffer[2..]);
+        }
         other => panic!("Did not get unknown event: {:?}", other),
     }
 }
@@ -1436,7 +2113,10 @@ fn gatt_server_confirmation() {
 fn gatt_server_confirmation_unknown() {
     let buffer = [0x17, 0x0C, 0x01, 0x02];
     match BlueNRGEvent::new(&buffer) {
-        Err(HciError::Vendor(BlueNRGError::UnknownEvent(0x0C17))) => (),
+        Ok(BlueNRGEvent::Unknown { code, data }) => {
+            assert_eq!(code, 0x0C17);
+            assert_eq!(data.as_slice(), &buffer[2..]);
+        }
         other => panic!("Did not get unknown event: {:?}", other),
     }
 }
@@ -1480,7 +2160,50 @@ fn att_prepare_write_permit_request_unknown() {
         0x18, 0x0C, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 4, 0x07, 0x08, 0x09, 0x0a,
     ];
     match BlueNRGEvent::new(&buffer) {
-        Err(HciError::Vendor(BlueNRGError::UnknownEvent(0x0C18))) => (),
+        Ok(BlueNRGEvent::Unknown { code, data }) => {
+            assert_eq!(code, 0x0C18);
+            assert_eq!(data.as_slice(), &buffer[2..]);
+        }
+        other => panic!("Did not get unknown event: {:?}", other),
+    }
+}
+
+#[test]
+fn unknown_event() {
+    let buffer = [0x00, 0x0F, 0x01, 0x02, 0x03];
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::Unknown { code, data }) => {
+            assert_eq!(code, 0x0F00);
+            assert_eq!(data.as_slice(), [0x01, 0x02, 0x03]);
+        }
         other => panic!("Did not get unknown event: {:?}", other),
     }
 }
+
+#[cfg(all(
+    feature = "event-payload-32",
+    not(any(feature = "event-payload-64", feature = "event-payload-128"))
+))]
+#[test]
+fn gatt_notification_exceeds_payload_limit() {
+    let mut buffer = [0; 40];
+    buffer[0] = 0x0F;
+    buffer[1] = 0x0C;
+    buffer[4] = 35;
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::BadLength(actual, max)) => {
+            assert_eq!(actual, 33);
+            assert_eq!(max, MAX_PAYLOAD_LEN);
+        }
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
+#[cfg(all(
+    feature = "event-payload-32",
+    not(any(feature = "event-payload-64", feature = "event-payload-128"))
+))]
+#[test]
+fn payload_limit_bounds_event_size() {
+    assert!(std::mem::size_of::<BlueNRGEvent>() < 128);
+}
@@ -0,0 +1,113 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::firmware::*;
+use bluenrg::gap::Commands as GapCommands;
+#[cfg(feature = "ms")]
+use bluenrg::gatt::{CharacteristicHandle, Commands as GattCommands};
+use bluenrg::hal::Commands as HalCommands;
+use bluenrg::LocalVersionInfoExt;
+use fixture::RecordingWriter;
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn bluenrg_firmware() {
+    let capabilities = Capabilities::for_firmware(6, 4, 0);
+    assert_eq!(capabilities.features, Features::empty());
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn features_accumulate_with_version() {
+    let v70 = Capabilities::for_firmware(7, 0, 3);
+    assert!(v70.features.contains(Features::GATT_TX_POOL_AVAILABLE));
+    assert!(v70.features.contains(Features::GATT_LONG_VALUES));
+    assert!(!v70
+        .features
+        .contains(Features::LONG_CHARACTERISTIC_VALUE_LENGTH));
+
+    let v72 = Capabilities::for_firmware(7, 2, 1);
+    assert_eq!(v72.features, Features::all());
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn bluenrg_1_2_firmware() {
+    assert_eq!(Capabilities::for_firmware(2, 1, 0), Capabilities::default());
+    assert_eq!(Capabilities::for_firmware(3, 2, 3), Capabilities::default());
+}
+
+#[test]
+fn from_reported_versions() {
+    let info = hci::event::command::LocalVersionInfo::<bluenrg::event::Status> {
+        status: hci::Status::Success,
+        hci_version: 6,
+        hci_revision: 0x3107,
+        lmp_version: 6,
+        manufacturer_name: 0x30,
+        lmp_subversion: 0x0012,
+    };
+    assert_eq!(
+        info.bluenrg_version().capabilities(),
+        Capabilities::for_firmware(7, 1, 2)
+    );
+}
+
+#[cfg(feature = "ms")]
+#[test]
+fn supports_event() {
+    use bluenrg::event::{BlueNRGEvent, GattTxPoolAvailable};
+
+    let event = BlueNRGEvent::GattTxPoolAvailable(GattTxPoolAvailable {
+        conn_handle: hci::ConnectionHandle(0x0201),
+        available_buffers: 4,
+    });
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    assert!(!Capabilities::for_firmware(6, 4, 0).supports_event(&event));
+    assert!(Capabilities::for_firmware(7, 0, 0).supports_event(&event));
+}
+
+#[cfg(all(feature = "ms", not(feature = "bluenrg-1-2")))]
+#[test]
+fn checked_refuses_unsupported_commands() {
+    let mut writer = RecordingWriter::new();
+    {
+        let mut checked = Checked::new(&mut writer, Capabilities::for_firmware(6, 4, 0));
+        checked.get_link_status().unwrap();
+        assert_eq!(
+            checked.read_handle_value_offset(CharacteristicHandle(0x0201), 0),
+            Err(nb::Error::Other(bluenrg::gatt::Error::Comm(
+                Error::Unsupported(hci::Opcode(0xFD2B))
+            )))
+        );
+        checked.set_nondiscoverable().unwrap();
+    }
+    assert_eq!(writer.opcodes(), [0xFC17, 0xFC81]);
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn checked_allows_bluenrg_1_2_commands() {
+    let mut writer = RecordingWriter::new();
+    {
+        let mut checked = Checked::new(&mut writer, Capabilities::for_firmware(2, 1, 0));
+        checked
+            .read_handle_value_offset(CharacteristicHandle(0x0201), 0)
+            .unwrap();
+    }
+    assert_eq!(writer.opcodes(), [0xFD2B]);
+}
+
+#[test]
+fn default_capabilities_allow_everything() {
+    let mut writer = RecordingWriter::new();
+    {
+        let mut checked = Checked::new(&mut writer, Capabilities::default());
+        checked.get_anchor_period().unwrap();
+        checked.get_bonded_devices().unwrap();
+    }
+    assert_eq!(writer.opcodes(), [0xFC19, 0xFCA3]);
+}
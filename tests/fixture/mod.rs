@@ -1,13 +1,17 @@
 #![allow(dead_code)]
 
 extern crate bluenrg;
+extern crate bluetooth_hci as hci;
 extern crate embedded_hal as hal;
 extern crate nb;
 
-use bluenrg::{ActiveBlueNRG, BlueNRG};
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::BlueNRGEvent;
+use bluenrg::{ActiveBlueNRG, BlueNRG, CommandWriter};
+use hci::event::{Event as HciEvent, Packet};
 use std::cmp;
 
-static mut DUMMY_RX_BUFFER: [u8; 8] = [0; 8];
+pub mod updater;
 
 pub struct Fixture<'sink, 'buf> {
     pub sink: &'sink mut RecordingSink,
@@ -18,7 +22,7 @@ impl<'sink, 'buf> Fixture<'sink, 'buf> {
     pub fn new(sink: &'sink mut RecordingSink) -> Fixture<'sink, 'buf> {
         Fixture {
             sink,
-            bnrg: unsafe { BlueNRG::new(&mut DUMMY_RX_BUFFER, DummyPin, DummyPin, DummyPin) },
+            bnrg: BlueNRG::new(Box::leak(Box::new([0; 8])), DummyPin, DummyPin, DummyPin),
         }
     }
 
@@ -26,7 +30,7 @@ impl<'sink, 'buf> Fixture<'sink, 'buf> {
     where
         F: FnOnce(&mut ActiveBlueNRG<RecordingSink, DummyPin, DummyPin, DummyPin, NeverError>) -> T,
     {
-        self.bnrg.with_spi(&mut self.sink, body)
+        self.bnrg.with_spi(self.sink, body)
     }
 
     pub fn wrote_header(&self) -> bool {
@@ -151,3 +155,105 @@ impl hal::digital::v2::InputPin for DummyPin {
 }
 
 pub struct DummySpi;
+
+// Records the opcode and parameters of each command, without talking to a controller.
+pub struct RecordingWriter {
+    pub written: Vec<(u16, Vec<u8>)>,
+    pub accept: usize,
+}
+
+impl RecordingWriter {
+    pub fn new() -> RecordingWriter {
+        RecordingWriter::accepting(usize::MAX)
+    }
+
+    // The writer blocks once it has accepted the given number of commands.
+    pub fn accepting(accept: usize) -> RecordingWriter {
+        RecordingWriter {
+            written: Vec::new(),
+            accept,
+        }
+    }
+
+    pub fn opcodes(&self) -> Vec<u16> {
+        self.written.iter().map(|(opcode, _)| *opcode).collect()
+    }
+}
+
+impl CommandWriter for RecordingWriter {
+    type Error = ();
+
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), ()> {
+        if self.accept == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        self.accept -= 1;
+        self.written.push((opcode.0, params.to_vec()));
+        Ok(())
+    }
+}
+
+pub fn record<F>(f: F) -> Vec<(u16, Vec<u8>)>
+where
+    F: FnOnce(&mut RecordingWriter),
+{
+    let mut writer = RecordingWriter::new();
+    f(&mut writer);
+    writer.written
+}
+
+// Returns a Command Complete packet that returns one command credit. The return parameters start
+// with the opcode of the command.
+pub fn command_complete(params: &[u8]) -> Vec<u8> {
+    let mut packet = vec![0x0E, 1 + params.len() as u8, 1];
+    packet.extend_from_slice(params);
+    packet
+}
+
+// Returns a Command Complete packet with the given vendor-specific return parameters.
+pub fn encode_command_complete(params: &BNRGParams) -> Vec<u8> {
+    let mut bytes = [0; 254];
+    let len = params.copy_into_slice(&mut bytes).unwrap();
+    command_complete(&bytes[..len])
+}
+
+pub fn event(packet: &[u8]) -> HciEvent<BlueNRGEvent> {
+    HciEvent::new(Packet(packet)).unwrap()
+}
+
+// Fake controller that answers each command with one event.
+pub trait Responder {
+    // Returns the packet answering the last command, if one was sent since the last call.
+    fn response(&mut self) -> Option<Vec<u8>>;
+
+    // Called instead of handling an event when no command was sent.
+    fn idle(&mut self) {
+        panic!("No command sent");
+    }
+}
+
+// Polls `machine` until it finishes, and passes it every response of the controller.
+pub fn drive<M, C, T, E, P, H>(
+    machine: &mut M,
+    controller: &mut C,
+    mut poll: P,
+    mut handle: H,
+) -> Result<T, E>
+where
+    C: Responder,
+    P: FnMut(&mut M, &mut C) -> nb::Result<T, E>,
+    H: FnMut(&mut M, &HciEvent<BlueNRGEvent>),
+{
+    loop {
+        match poll(machine, controller) {
+            Ok(value) => return Ok(value),
+            Err(nb::Error::Other(e)) => return Err(e),
+            Err(nb::Error::WouldBlock) => (),
+        }
+        match controller.response() {
+            Some(packet) => handle(machine, &event(&packet)),
+            None => controller.idle(),
+        }
+    }
+}
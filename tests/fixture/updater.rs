@@ -0,0 +1,108 @@
+use super::{command_complete, encode_command_complete, hci, Responder};
+use bluenrg::decode::VendorCommand;
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::command::{UpdaterBufferSize, UpdaterCrc};
+use bluenrg::updater::{crc, BASE_ADDRESS, MAX_IMAGE_LEN, SECTOR_SIZE};
+use bluenrg::CommandWriter;
+
+// Emulates the updater: executes each command against a flash array and queues its return
+// parameters. The flash holds the firmware image area followed by the IFR sector.
+pub struct FakeUpdater {
+    pub flash: Vec<u8>,
+    pub buffer_size: usize,
+    pub blue_flag: bool,
+    pub erased_sectors: Vec<u32>,
+    // A byte that keeps its value when it is programmed.
+    pub stuck_address: Option<u32>,
+    // Return one byte less than requested when reading.
+    pub short_reads: bool,
+    response: Option<Vec<u8>>,
+}
+
+impl FakeUpdater {
+    pub fn new() -> FakeUpdater {
+        FakeUpdater {
+            flash: vec![0xFF; MAX_IMAGE_LEN + SECTOR_SIZE],
+            buffer_size: 250,
+            blue_flag: true,
+            erased_sectors: Vec::new(),
+            stuck_address: None,
+            short_reads: false,
+            response: None,
+        }
+    }
+
+    pub fn offset(address: u32) -> usize {
+        (address - BASE_ADDRESS) as usize
+    }
+}
+
+impl CommandWriter for FakeUpdater {
+    type Error = ();
+
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), ()> {
+        assert!(self.response.is_none());
+        let success = hci::Status::Success;
+        let response = match VendorCommand::new(opcode, params).unwrap() {
+            VendorCommand::UpdaterGetBufferSize => {
+                BNRGParams::UpdaterGetBufferSize(UpdaterBufferSize {
+                    status: success,
+                    buffer_size: self.buffer_size,
+                })
+            }
+            VendorCommand::UpdaterEraseBlueFlag => {
+                self.blue_flag = false;
+                BNRGParams::UpdaterEraseBlueFlag(success)
+            }
+            VendorCommand::UpdaterResetBlueFlag => {
+                self.blue_flag = true;
+                BNRGParams::UpdaterResetBlueFlag(success)
+            }
+            VendorCommand::UpdaterEraseSector(address) => {
+                let start = Self::offset(address);
+                for byte in &mut self.flash[start..start + SECTOR_SIZE] {
+                    *byte = 0xFF;
+                }
+                self.erased_sectors.push(address);
+                BNRGParams::UpdaterEraseSector(success)
+            }
+            VendorCommand::UpdaterProgramDataBlock { address, data } => {
+                assert!(data.len() <= self.buffer_size);
+                let start = Self::offset(address);
+                for (i, byte) in data.iter().enumerate() {
+                    if self.stuck_address != Some(address + i as u32) {
+                        self.flash[start + i] &= byte;
+                    }
+                }
+                BNRGParams::UpdaterProgramDataBlock(success)
+            }
+            // The data of the return parameters can only be set by parsing them, so the packet is
+            // built by hand.
+            VendorCommand::UpdaterReadDataBlock { address, len } => {
+                let len = if self.short_reads { len - 1 } else { len };
+                let start = Self::offset(address);
+                let mut params = vec![0x28, 0xFC, 0];
+                params.extend_from_slice(&self.flash[start..start + len]);
+                self.response = Some(command_complete(&params));
+                return Ok(());
+            }
+            VendorCommand::UpdaterCalculateCrc { address, sectors } => {
+                let start = Self::offset(address);
+                BNRGParams::UpdaterCalculateCrc(UpdaterCrc {
+                    status: success,
+                    crc: crc(&self.flash[start..start + sectors as usize * SECTOR_SIZE]),
+                })
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        };
+        self.response = Some(encode_command_complete(&response));
+
+        Ok(())
+    }
+}
+
+impl Responder for FakeUpdater {
+    fn response(&mut self) -> Option<Vec<u8>> {
+        self.response.take()
+    }
+}
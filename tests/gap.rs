@@ -40,7 +40,7 @@ fn set_limited_discoverable() {
                     filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
                     local_name: Some(LocalName::Shortened(b"testdev")),
                     advertising_data: &[0x01, 0x02, 0x03, 0x04],
-                    conn_interval: (Some(Duration::from_millis(5000)), None),
+                    conn_interval: (Some(Duration::from_millis(4000)), None),
                 })
             })
             .unwrap();
@@ -50,7 +50,7 @@ fn set_limited_discoverable() {
         sink.written_data,
         [
             1, 0x82, 0xFC, 25, 0x00, 0x00, 0x08, 0x00, 0x10, 0x00, 0x00, 8, 0x08, 0x74, 0x65, 0x73,
-            0x74, 0x64, 0x65, 0x76, 4, 0x01, 0x02, 0x03, 0x04, 0xA0, 0x0F, 0xFF, 0xFF
+            0x74, 0x64, 0x65, 0x76, 4, 0x01, 0x02, 0x03, 0x04, 0x80, 0x0C, 0xFF, 0xFF
         ]
     );
 }
@@ -72,7 +72,7 @@ fn set_limited_discoverable_bad_adv_type() {
                     filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
                     local_name: Some(LocalName::Shortened(b"testdev")),
                     advertising_data: &[0x01, 0x02, 0x03, 0x04],
-                    conn_interval: (Some(Duration::from_millis(5000)), None),
+                    conn_interval: (Some(Duration::from_millis(4000)), None),
                 })
             })
             .err()
@@ -105,7 +105,7 @@ fn set_limited_discoverable_bad_adv_interval() {
                     filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
                     local_name: Some(LocalName::Shortened(b"testdev")),
                     advertising_data: &[0x01, 0x02, 0x03, 0x04],
-                    conn_interval: (Some(Duration::from_millis(5000)), None),
+                    conn_interval: (Some(Duration::from_millis(4000)), None),
                 })
             })
             .err()
@@ -140,8 +140,8 @@ fn set_limited_discoverable_bad_conn_interval() {
                     local_name: Some(LocalName::Shortened(b"testdev")),
                     advertising_data: &[0x01, 0x02, 0x03, 0x04],
                     conn_interval: (
-                        Some(Duration::from_millis(5000)),
-                        Some(Duration::from_millis(4999)),
+                        Some(Duration::from_millis(4000)),
+                        Some(Duration::from_millis(3999)),
                     ),
                 })
             })
@@ -150,8 +150,8 @@ fn set_limited_discoverable_bad_conn_interval() {
         assert_eq!(
             err,
             nb::Error::Other(Error::BadConnectionInterval(
-                Duration::from_millis(5000),
-                Duration::from_millis(4999)
+                Duration::from_millis(4000),
+                Duration::from_millis(3999)
             ))
         );
     }
@@ -176,7 +176,7 @@ fn set_discoverable() {
                     filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
                     local_name: Some(LocalName::Shortened(b"testdev")),
                     advertising_data: &[0x01, 0x02, 0x03, 0x04],
-                    conn_interval: (Some(Duration::from_millis(5000)), None),
+                    conn_interval: (Some(Duration::from_millis(4000)), None),
                 })
             })
             .unwrap();
@@ -184,7 +184,7 @@ fn set_discoverable() {
     assert!(sink.wrote_header());
     assert!(sink.wrote(&[
         1, 0x83, 0xFC, 25, 0x00, 0x00, 0x08, 0x00, 0x10, 0x00, 0x00, 8, 0x08, 0x74, 0x65, 0x73,
-        0x74, 0x64, 0x65, 0x76, 4, 0x01, 0x02, 0x03, 0x04, 0xA0, 0x0F, 0xFF, 0xFF
+        0x74, 0x64, 0x65, 0x76, 4, 0x01, 0x02, 0x03, 0x04, 0x80, 0x0C, 0xFF, 0xFF
     ]));
 }
 
@@ -205,7 +205,7 @@ fn set_discoverable_bad_adv_type() {
                     filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
                     local_name: Some(LocalName::Shortened(b"testdev")),
                     advertising_data: &[0x01, 0x02, 0x03, 0x04],
-                    conn_interval: (Some(Duration::from_millis(5000)), None),
+                    conn_interval: (Some(Duration::from_millis(4000)), None),
                 })
             })
             .err()
@@ -238,7 +238,7 @@ fn set_discoverable_bad_adv_interval() {
                     filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
                     local_name: Some(LocalName::Shortened(b"testdev")),
                     advertising_data: &[0x01, 0x02, 0x03, 0x04],
-                    conn_interval: (Some(Duration::from_millis(5000)), None),
+                    conn_interval: (Some(Duration::from_millis(4000)), None),
                 })
             })
             .err()
@@ -273,8 +273,8 @@ fn set_discoverable_bad_conn_interval() {
                     local_name: Some(LocalName::Shortened(b"testdev")),
                     advertising_data: &[0x01, 0x02, 0x03, 0x04],
                     conn_interval: (
-                        Some(Duration::from_millis(5000)),
-                        Some(Duration::from_millis(4999)),
+                        Some(Duration::from_millis(4000)),
+                        Some(Duration::from_millis(3999)),
                     ),
                 })
             })
@@ -283,8 +283,8 @@ fn set_discoverable_bad_conn_interval() {
         assert_eq!(
             err,
             nb::Error::Other(Error::BadConnectionInterval(
-                Duration::from_millis(5000),
-                Duration::from_millis(4999)
+                Duration::from_millis(4000),
+                Duration::from_millis(3999)
             ))
         );
     }
@@ -292,6 +292,123 @@ fn set_discoverable_bad_conn_interval() {
     assert!(!sink.wrote_header());
 }
 
+#[test]
+fn set_discoverable_conn_interval_out_of_range() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.set_discoverable(&DiscoverableParameters {
+                    advertising_type: AdvertisingType::ConnectableUndirected,
+                    advertising_interval: Some((
+                        Duration::from_millis(1280),
+                        Duration::from_millis(1280),
+                    )),
+                    address_type: OwnAddressType::Public,
+                    filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
+                    local_name: Some(LocalName::Shortened(b"testdev")),
+                    advertising_data: &[0x01, 0x02, 0x03, 0x04],
+                    conn_interval: (Some(Duration::from_millis(7)), None),
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::ConnectionIntervalOutOfRange(Duration::from_millis(
+                7
+            )))
+        );
+    }
+
+    assert!(!sink.wrote_header());
+}
+
+#[test]
+fn set_discoverable_adv_interval_out_of_range() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.set_discoverable(&DiscoverableParameters {
+                    advertising_type: AdvertisingType::ConnectableUndirected,
+                    advertising_interval: Some((
+                        Duration::from_millis(19),
+                        Duration::from_millis(1280),
+                    )),
+                    address_type: OwnAddressType::Public,
+                    filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
+                    local_name: None,
+                    advertising_data: &[],
+                    conn_interval: (None, None),
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::BadAdvertisingInterval(
+                Duration::from_millis(19),
+                Duration::from_millis(1280)
+            ))
+        );
+    }
+
+    assert!(!sink.wrote_header());
+}
+
+#[test]
+fn set_discoverable_bad_local_name_length() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.set_discoverable(&DiscoverableParameters {
+                    advertising_type: AdvertisingType::ConnectableUndirected,
+                    advertising_interval: None,
+                    address_type: OwnAddressType::Public,
+                    filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
+                    local_name: Some(LocalName::Complete(&[0x41; 30])),
+                    advertising_data: &[],
+                    conn_interval: (None, None),
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err, nb::Error::Other(Error::BadLocalNameLength(30)));
+    }
+
+    assert!(!sink.wrote_header());
+}
+
+#[test]
+fn set_discoverable_bad_advertising_data_length() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.set_discoverable(&DiscoverableParameters {
+                    advertising_type: AdvertisingType::ConnectableUndirected,
+                    advertising_interval: None,
+                    address_type: OwnAddressType::Public,
+                    filter_policy: AdvertisingFilterPolicy::AllowConnectionAndScan,
+                    local_name: None,
+                    advertising_data: &[0; 32],
+                    conn_interval: (None, None),
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err, nb::Error::Other(Error::BadAdvertisingDataLength(32)));
+    }
+
+    assert!(!sink.wrote_header());
+}
+
 #[cfg(not(feature = "ms"))]
 #[test]
 fn set_direct_connectable() {
@@ -408,6 +525,7 @@ fn set_io_capability() {
     assert!(sink.wrote(&[1, 0x85, 0xFC, 1, 0x03]));
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn set_authentication_requirement() {
     let mut sink = RecordingSink::new();
@@ -421,7 +539,7 @@ fn set_authentication_requirement() {
                         0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0xA, 0xB, 0xC, 0xD, 0xE,
                         0xF,
                     ]),
-                    encryption_key_size_range: (8, 64),
+                    encryption_key_size_range: (8, 16),
                     fixed_pin: Pin::Fixed(123456),
                     bonding_required: true,
                 })
@@ -431,10 +549,11 @@ fn set_authentication_requirement() {
     assert!(sink.wrote_header());
     assert!(sink.wrote(&[
         1, 0x86, 0xFC, 26, 0x01, 0x01, 0x0, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0xA, 0xB,
-        0xC, 0xD, 0xE, 0xF, 8, 64, 0, 0x40, 0xe2, 0x01, 0x00, 0x1
+        0xC, 0xD, 0xE, 0xF, 8, 16, 0, 0x40, 0xe2, 0x01, 0x00, 0x1
     ]));
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn set_authentication_requirement_2() {
     let mut sink = RecordingSink::new();
@@ -445,7 +564,7 @@ fn set_authentication_requirement_2() {
                 controller.set_authentication_requirement(&AuthenticationRequirements {
                     mitm_protection_required: false,
                     out_of_band_auth: OutOfBandAuthentication::Disabled,
-                    encryption_key_size_range: (1, 255),
+                    encryption_key_size_range: (7, 16),
                     fixed_pin: Pin::Requested,
                     bonding_required: false,
                 })
@@ -455,10 +574,11 @@ fn set_authentication_requirement_2() {
     assert!(sink.wrote_header());
     assert!(sink.wrote(&[
         1, 0x86, 0xFC, 26, 0x00, 0x00, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0, 0x0,
-        0x0, 0x0, 0x0, 0x0, 1, 255, 1, 0x00, 0x00, 0x00, 0x00, 0x0
+        0x0, 0x0, 0x0, 0x0, 7, 16, 1, 0x00, 0x00, 0x00, 0x00, 0x0
     ]));
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
 fn set_authentication_requirement_bad_key_size_range() {
     let mut sink = RecordingSink::new();
@@ -484,8 +604,9 @@ fn set_authentication_requirement_bad_key_size_range() {
     assert!(!sink.wrote_header());
 }
 
+#[cfg(not(feature = "bluenrg-1-2"))]
 #[test]
-fn set_authentication_requirement_bad_pin() {
+fn set_authentication_requirement_key_size_out_of_range() {
     let mut sink = RecordingSink::new();
     {
         let mut fixture = Fixture::new(&mut sink);
@@ -495,6 +616,32 @@ fn set_authentication_requirement_bad_pin() {
                     mitm_protection_required: false,
                     out_of_band_auth: OutOfBandAuthentication::Disabled,
                     encryption_key_size_range: (1, 255),
+                    fixed_pin: Pin::Requested,
+                    bonding_required: false,
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::BadEncryptionKeySizeRange(1, 255))
+        );
+    }
+    assert!(!sink.wrote_header());
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn set_authentication_requirement_bad_pin() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.set_authentication_requirement(&AuthenticationRequirements {
+                    mitm_protection_required: false,
+                    out_of_band_auth: OutOfBandAuthentication::Disabled,
+                    encryption_key_size_range: (7, 16),
                     fixed_pin: Pin::Fixed(1000000),
                     bonding_required: false,
                 })
@@ -735,6 +882,29 @@ fn peripheral_security_request() {
     assert!(sink.wrote(&[1, 0x8D, 0xFC, 4, 0x01, 0x02, 0x01, 0x00]));
 }
 
+#[test]
+fn peripheral_security_request_bad_conn_handle() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.peripheral_security_request(&SecurityRequestParameters {
+                    conn_handle: hci::ConnectionHandle(0x0F00),
+                    bonding: true,
+                    mitm_protection: false,
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::BadConnectionHandle(hci::ConnectionHandle(0x0F00)))
+        );
+    }
+    assert!(!sink.wrote_header());
+}
+
 #[test]
 fn update_advertising_data() {
     let mut sink = RecordingSink::new();
@@ -1053,6 +1223,7 @@ fn start_auto_connection_establishment() {
                             Duration::from_millis(1500),
                         )
                         .unwrap(),
+                        reconnection_address: None,
                         white_list: &[
                             hci::host::PeerAddrType::PublicDeviceAddress(hci::BdAddr([
                                 1, 2, 3, 4, 5, 6,
@@ -1142,6 +1313,7 @@ fn start_auto_connection_establishment_white_list_too_long() {
                             Duration::from_millis(1500),
                         )
                         .unwrap(),
+                        reconnection_address: None,
                         white_list: &[hci::host::PeerAddrType::PublicDeviceAddress(hci::BdAddr([
                             1, 2, 3, 4, 5, 6,
                         ])); 34],
@@ -1199,6 +1371,7 @@ fn start_general_connection_establishment() {
                             .unwrap(),
                         own_address_type: hci::host::OwnAddressType::Random,
                         filter_duplicates: true,
+                        reconnection_address: None,
                     },
                 )
             })
@@ -1311,6 +1484,48 @@ fn create_connection() {
     ]));
 }
 
+#[test]
+fn create_connection_rounded_conn_interval() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.create_connection(&ConnectionParameters {
+                    scan_window: ScanWindow::start_every(Duration::from_micros(2500))
+                        .unwrap()
+                        .open_for(Duration::from_micros(2500))
+                        .unwrap(),
+                    peer_address: hci::host::PeerAddrType::PublicDeviceAddress(hci::BdAddr([
+                        1, 2, 3, 4, 5, 6,
+                    ])),
+                    own_address_type: hci::host::OwnAddressType::Random,
+                    // Sent as 6.25 ms, which is below the minimum.
+                    conn_interval: ConnectionIntervalBuilder::new()
+                        .with_range(Duration::from_micros(7500), Duration::from_millis(250))
+                        .with_latency(10)
+                        .with_supervision_timeout(Duration::from_millis(6000))
+                        .build()
+                        .unwrap(),
+                    expected_connection_length: ExpectedConnectionLength::new(
+                        Duration::from_millis(150),
+                        Duration::from_millis(1500),
+                    )
+                    .unwrap(),
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::ConnectionIntervalOutOfRange(Duration::from_micros(
+                7500
+            )))
+        );
+    }
+    assert!(!sink.wrote_header());
+}
+
 #[test]
 fn terminate_procedure() {
     let mut sink = RecordingSink::new();
@@ -1404,6 +1619,29 @@ fn send_pairing_request() {
     assert!(sink.wrote(&[1, 0x9F, 0xFC, 3, 0x01, 0x02, 0x03]));
 }
 
+#[test]
+fn send_pairing_request_bad_conn_handle() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.send_pairing_request(&PairingRequest {
+                    conn_handle: hci::ConnectionHandle(0xFFFF),
+                    force_rebond: true,
+                    force_reencrypt: true,
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::BadConnectionHandle(hci::ConnectionHandle(0xFFFF)))
+        );
+    }
+    assert!(!sink.wrote_header());
+}
+
 #[test]
 fn resolve_private_address() {
     let mut sink = RecordingSink::new();
@@ -58,6 +58,26 @@ fn add_service_128() {
     ]));
 }
 
+#[test]
+fn add_service_bad_max_attribute_records() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.add_service(&AddServiceParameters {
+                    uuid: Uuid::Uuid16(0x0201),
+                    service_type: ServiceType::Primary,
+                    max_attribute_records: 256,
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err, nb::Error::Other(Error::BadMaxAttributeRecords(256)));
+    }
+    assert!(!sink.wrote_header());
+}
+
 #[test]
 fn include_service_16() {
     let mut sink = RecordingSink::new();
@@ -104,6 +124,43 @@ fn include_service_128() {
     ]));
 }
 
+#[test]
+fn include_service_bad_handles() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.include_service(&IncludeServiceParameters {
+                    service_handle: ServiceHandle(0),
+                    include_handle_range: Range::new(ServiceHandle(0x0403), ServiceHandle(0x0605))
+                        .unwrap(),
+                    include_uuid: Uuid::Uuid16(0x0807),
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::BadServiceHandle(ServiceHandle(0)))
+        );
+
+        let range = Range::new(ServiceHandle(0), ServiceHandle(0x0605)).unwrap();
+        let err = fixture
+            .act(|controller| {
+                controller.include_service(&IncludeServiceParameters {
+                    service_handle: ServiceHandle(0x0201),
+                    include_handle_range: range,
+                    include_uuid: Uuid::Uuid16(0x0807),
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err, nb::Error::Other(Error::BadIncludeHandleRange(range)));
+    }
+    assert!(!sink.wrote_header());
+}
+
 #[test]
 fn bad_range() {
     let err = Range::new(ServiceHandle(0x0201), ServiceHandle(0x0102))
@@ -125,7 +182,7 @@ fn add_characteristic_16() {
                 controller.add_characteristic(&AddCharacteristicParameters {
                     service_handle: ServiceHandle(0x0201),
                     characteristic_uuid: Uuid::Uuid16(0x0403),
-                    characteristic_value_len: 0x0605,
+                    characteristic_value_len: 0x0105,
                     characteristic_properties: CharacteristicProperty::BROADCAST
                         | CharacteristicProperty::READ
                         | CharacteristicProperty::NOTIFY,
@@ -143,7 +200,7 @@ fn add_characteristic_16() {
     }
     assert!(sink.wrote_header());
     assert!(sink.wrote(&[
-        1, 0x04, 0xFD, 12, 0x01, 0x02, 0x01, 0x03, 0x04, 0x05, 0x06, 0x13, 0x09, 0x07, 8, 1
+        1, 0x04, 0xFD, 12, 0x01, 0x02, 0x01, 0x03, 0x04, 0x05, 0x01, 0x13, 0x09, 0x07, 8, 1
     ]));
 }
 
@@ -160,7 +217,7 @@ fn add_characteristic_128() {
                         0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18, 0x19, 0x1A, 0x1B,
                         0x1C, 0x1D, 0x1E, 0x1F,
                     ]),
-                    characteristic_value_len: 0x0605,
+                    characteristic_value_len: 0x0105,
                     characteristic_properties: CharacteristicProperty::BROADCAST
                         | CharacteristicProperty::READ
                         | CharacteristicProperty::NOTIFY,
@@ -179,7 +236,7 @@ fn add_characteristic_128() {
     assert!(sink.wrote_header());
     assert!(sink.wrote(&[
         1, 0x04, 0xFD, 26, 0x01, 0x02, 0x02, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17, 0x18,
-        0x19, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E, 0x1F, 0x05, 0x06, 0x13, 0x09, 0x07, 8, 1
+        0x19, 0x1A, 0x1B, 0x1C, 0x1D, 0x1E, 0x1F, 0x05, 0x01, 0x13, 0x09, 0x07, 8, 1
     ]));
 }
 
@@ -250,6 +307,35 @@ fn add_characteristic_pre_v72_128() {
     ]));
 }
 
+#[test]
+fn add_characteristic_value_too_long() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.add_characteristic(&AddCharacteristicParameters {
+                    service_handle: ServiceHandle(0x0201),
+                    characteristic_uuid: Uuid::Uuid16(0x0403),
+                    characteristic_value_len: 256,
+                    characteristic_properties: CharacteristicProperty::READ,
+                    security_permissions: CharacteristicPermission::empty(),
+                    gatt_event_mask: CharacteristicEvent::empty(),
+                    encryption_key_size: EncryptionKeySize::with_value(8).unwrap(),
+                    is_variable: true,
+                    fw_version_before_v72: true,
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::CharacteristicValueTooLong(256))
+        );
+    }
+    assert!(!sink.wrote_header());
+}
+
 #[test]
 fn encryption_key_size_range() {
     assert_eq!(
@@ -531,7 +617,7 @@ fn find_by_type_value_request() {
                         CharacteristicHandle(0x0605),
                     )
                     .unwrap(),
-                    uuid: Uuid16(0x0807),
+                    uuid: Uuid::Uuid16(0x0807),
                     value: &[9, 10, 11, 12],
                 })
             })
@@ -557,7 +643,7 @@ fn find_by_type_value_request_value_too_long() {
                         CharacteristicHandle(0x0605),
                     )
                     .unwrap(),
-                    uuid: Uuid16(0x0807),
+                    uuid: Uuid::Uuid16(0x0807),
                     value: &[0; 247],
                 })
             })
@@ -568,6 +654,40 @@ fn find_by_type_value_request_value_too_long() {
     assert!(!sink.wrote_header());
 }
 
+#[test]
+fn find_by_type_value_request_expanded_uuid() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let range = Range::new(CharacteristicHandle(0x0403), CharacteristicHandle(0x0605)).unwrap();
+        fixture
+            .act(|controller| {
+                controller.find_by_type_value_request(&FindByTypeValueParameters {
+                    conn_handle: hci::ConnectionHandle(0x0201),
+                    attribute_handle_range: range,
+                    uuid: Uuid::from_u128(0x0000_0807_0000_1000_8000_0080_5F9B_34FB),
+                    value: &[9],
+                })
+            })
+            .unwrap();
+
+        let uuid = Uuid::Uuid128([1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16]);
+        let err = fixture
+            .act(|controller| {
+                controller.find_by_type_value_request(&FindByTypeValueParameters {
+                    conn_handle: hci::ConnectionHandle(0x0201),
+                    attribute_handle_range: range,
+                    uuid,
+                    value: &[9],
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err, nb::Error::Other(Error::BadAttributeType(uuid)));
+    }
+    assert!(sink.wrote(&[1, 0x0D, 0xFD, 10, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 1, 9,]));
+}
+
 #[test]
 fn read_by_type_request_16() {
     let mut sink = RecordingSink::new();
@@ -682,7 +802,7 @@ fn prepare_write_request() {
                 controller.prepare_write_request(&WriteRequest {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     attribute_handle: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                     value: &[8, 9, 10, 11, 12, 13, 14],
                 })
             })
@@ -690,7 +810,7 @@ fn prepare_write_request() {
     }
     assert!(sink.wrote_header());
     assert!(sink.wrote(&[
-        1, 0x10, 0xFD, 14, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 0x7, 0x8, 0x9, 0xA, 0xB, 0xC, 0xD, 0xE,
+        1, 0x10, 0xFD, 14, 0x1, 0x2, 0x3, 0x4, 0x5, 0x1, 0x7, 0x8, 0x9, 0xA, 0xB, 0xC, 0xD, 0xE,
     ]));
 }
 
@@ -704,7 +824,7 @@ fn prepare_write_request_too_long() {
                 controller.prepare_write_request(&WriteRequest {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     attribute_handle: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                     value: &[0; 248],
                 })
             })
@@ -968,13 +1088,33 @@ fn read_long_characteristic_value() {
                 controller.read_long_characteristic_value(&LongCharacteristicReadParameters {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     attribute: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                 })
             })
             .unwrap();
     }
     assert!(sink.wrote_header());
-    assert!(sink.wrote(&[1, 0x1A, 0xFD, 6, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6]));
+    assert!(sink.wrote(&[1, 0x1A, 0xFD, 6, 0x1, 0x2, 0x3, 0x4, 0x5, 0x1]));
+}
+
+#[test]
+fn read_long_characteristic_value_bad_offset() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.read_long_characteristic_value(&LongCharacteristicReadParameters {
+                    conn_handle: hci::ConnectionHandle(0x0201),
+                    attribute: CharacteristicHandle(0x0403),
+                    offset: 513,
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err, nb::Error::Other(Error::BadOffset(513)));
+    }
+    assert!(!sink.wrote_header());
 }
 
 #[test]
@@ -1023,6 +1163,27 @@ fn read_multiple_characteristic_values_too_many_handles() {
     assert!(!sink.wrote_header());
 }
 
+#[test]
+fn read_multiple_characteristic_values_too_few_handles() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.read_multiple_characteristic_values(
+                    &MultipleCharacteristicReadParameters {
+                        conn_handle: hci::ConnectionHandle(0x0201),
+                        handles: &[CharacteristicHandle(0x0403)],
+                    },
+                )
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err, nb::Error::Other(Error::TooFewHandlesToRead));
+    }
+    assert!(!sink.wrote_header());
+}
+
 #[test]
 fn write_characteristic_value() {
     let mut sink = RecordingSink::new();
@@ -1062,6 +1223,29 @@ fn write_characteristic_value_too_long() {
     assert!(!sink.wrote_header());
 }
 
+#[test]
+fn write_characteristic_value_bad_conn_handle() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.write_characteristic_value(&CharacteristicValue {
+                    conn_handle: hci::ConnectionHandle(0x0F00),
+                    characteristic_handle: CharacteristicHandle(0x0403),
+                    value: &[1, 2, 3, 4],
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::BadConnectionHandle(hci::ConnectionHandle(0x0F00)))
+        );
+    }
+    assert!(!sink.wrote_header());
+}
+
 #[test]
 fn write_long_characteristic_value() {
     let mut sink = RecordingSink::new();
@@ -1072,14 +1256,14 @@ fn write_long_characteristic_value() {
                 controller.write_long_characteristic_value(&LongCharacteristicValue {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     characteristic_handle: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                     value: &[1, 2, 3, 4],
                 })
             })
             .unwrap();
     }
     assert!(sink.wrote_header());
-    assert!(sink.wrote(&[1, 0x1D, 0xFD, 11, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 4, 1, 2, 3, 4]));
+    assert!(sink.wrote(&[1, 0x1D, 0xFD, 11, 0x1, 0x2, 0x3, 0x4, 0x5, 0x1, 4, 1, 2, 3, 4]));
 }
 
 #[test]
@@ -1092,7 +1276,7 @@ fn write_long_characteristic_value_too_long() {
                 controller.write_long_characteristic_value(&LongCharacteristicValue {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     characteristic_handle: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                     value: &[0; 249],
                 })
             })
@@ -1113,14 +1297,14 @@ fn write_characteristic_value_reliably() {
                 controller.write_characteristic_value_reliably(&LongCharacteristicValue {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     characteristic_handle: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                     value: &[1, 2, 3, 4],
                 })
             })
             .unwrap();
     }
     assert!(sink.wrote_header());
-    assert!(sink.wrote(&[1, 0x1E, 0xFD, 11, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 4, 1, 2, 3, 4]));
+    assert!(sink.wrote(&[1, 0x1E, 0xFD, 11, 0x1, 0x2, 0x3, 0x4, 0x5, 0x1, 4, 1, 2, 3, 4]));
 }
 
 #[test]
@@ -1133,7 +1317,7 @@ fn write_characteristic_value_reliably_too_long() {
                 controller.write_characteristic_value_reliably(&LongCharacteristicValue {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     characteristic_handle: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                     value: &[0; 249],
                 })
             })
@@ -1154,14 +1338,14 @@ fn write_long_characteristic_descriptor() {
                 controller.write_long_characteristic_descriptor(&LongCharacteristicValue {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     characteristic_handle: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                     value: &[1, 2, 3, 4],
                 })
             })
             .unwrap();
     }
     assert!(sink.wrote_header());
-    assert!(sink.wrote(&[1, 0x1F, 0xFD, 11, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6, 4, 1, 2, 3, 4]));
+    assert!(sink.wrote(&[1, 0x1F, 0xFD, 11, 0x1, 0x2, 0x3, 0x4, 0x5, 0x1, 4, 1, 2, 3, 4]));
 }
 
 #[test]
@@ -1174,7 +1358,7 @@ fn write_long_characteristic_descriptor_too_long() {
                 controller.write_long_characteristic_descriptor(&LongCharacteristicValue {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     characteristic_handle: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                     value: &[0; 249],
                 })
             })
@@ -1195,13 +1379,13 @@ fn read_long_characteristic_descriptor() {
                 controller.read_long_characteristic_descriptor(&LongCharacteristicReadParameters {
                     conn_handle: hci::ConnectionHandle(0x0201),
                     attribute: CharacteristicHandle(0x0403),
-                    offset: 0x0605,
+                    offset: 0x0105,
                 })
             })
             .unwrap();
     }
     assert!(sink.wrote_header());
-    assert!(sink.wrote(&[1, 0x20, 0xFD, 6, 0x1, 0x2, 0x3, 0x4, 0x5, 0x6]));
+    assert!(sink.wrote(&[1, 0x20, 0xFD, 6, 0x1, 0x2, 0x3, 0x4, 0x5, 0x1]));
 }
 
 #[test]
@@ -1513,8 +1697,8 @@ fn update_long_characteristic_value() {
                         service_handle: ServiceHandle(0x0201),
                         characteristic_handle: CharacteristicHandle(0x0403),
                         update_type: UpdateType::INDICATION | UpdateType::NOTIFICATION,
-                        total_len: 0x0605,
-                        offset: 0x0807,
+                        total_len: 0x0105,
+                        offset: 0x0107,
                         value: &[0x9, 0xA, 0xB, 0xC],
                     },
                 )
@@ -1523,7 +1707,7 @@ fn update_long_characteristic_value() {
     }
     assert!(sink.wrote_header());
     assert!(sink.wrote(&[
-        1, 0x2C, 0xFD, 14, 0x1, 0x2, 0x3, 0x4, 0x3, 0x5, 0x6, 0x7, 0x8, 4, 0x9, 0xA, 0xB, 0xC,
+        1, 0x2C, 0xFD, 14, 0x1, 0x2, 0x3, 0x4, 0x3, 0x5, 0x1, 0x7, 0x1, 4, 0x9, 0xA, 0xB, 0xC,
     ]));
 }
 
@@ -1540,8 +1724,8 @@ fn update_long_characteristic_value_too_long() {
                         service_handle: ServiceHandle(0x0201),
                         characteristic_handle: CharacteristicHandle(0x0403),
                         update_type: UpdateType::INDICATION | UpdateType::NOTIFICATION,
-                        total_len: 0x0605,
-                        offset: 0x0807,
+                        total_len: 0x0105,
+                        offset: 0x0107,
                         value: &[0; 246],
                     },
                 )
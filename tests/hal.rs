@@ -22,7 +22,7 @@ fn get_firmware_revision() {
 
 fn becomes_bytes(data: ConfigData, expected: &[u8]) -> bool {
     let mut actual = [0; ConfigData::MAX_LENGTH];
-    let len = data.copy_into_slice(&mut actual);
+    let len = data.copy_into_slice(&mut actual).unwrap();
     assert_eq!(&actual[..len], expected);
 
     true
@@ -81,6 +81,19 @@ fn config_data() {
     ))
 }
 
+#[test]
+fn config_data_buffer_too_small() {
+    let data = ConfigData::public_address(hci::BdAddr([1, 2, 3, 4, 5, 6])).build();
+    let mut bytes = [0; 7];
+    assert_eq!(
+        data.copy_into_slice(&mut bytes),
+        Err(bluenrg::EncodeError::BufferTooSmall {
+            required: 8,
+            available: 7
+        })
+    );
+}
+
 #[test]
 fn write_config_data() {
     let mut sink = RecordingSink::new();
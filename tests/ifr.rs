@@ -0,0 +1,199 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::hal::Role;
+use bluenrg::ifr::*;
+use bluenrg::updater::FlashError;
+use core::time::Duration;
+use fixture::drive;
+use fixture::updater::FakeUpdater;
+use hci::event::CentralClockAccuracy;
+
+fn sample_ifr() -> Vec<u8> {
+    let mut bytes: Vec<u8> = (0..IFR_LEN).map(|i| i as u8).collect();
+    let config = &mut bytes[128..];
+    config[0] = 3;
+    config[28..30].copy_from_slice(&[0x64, 0x00]);
+    config[30] = 5;
+    config[32..34].copy_from_slice(&[0x48, 0x01]);
+    config[41..44].copy_from_slice(&[0x19, 0x07, 0x31]);
+
+    bytes
+}
+
+#[test]
+fn parse_config() {
+    let ifr = Ifr::from_bytes(&sample_ifr()).unwrap();
+    let config = ifr.config().unwrap();
+    assert_eq!(config.role, Role::Primary12Kb);
+    assert_eq!(config.role.max_connections(), 8);
+    assert_eq!(config.peripheral_sleep_clock_accuracy, 100);
+    assert_eq!(
+        config.central_sleep_clock_accuracy,
+        CentralClockAccuracy::Ppm50
+    );
+    assert_eq!(config.hs_startup_time, Duration::from_nanos(800_781));
+    assert_eq!(
+        config.date,
+        Date {
+            year: 19,
+            month: 7,
+            day: 31
+        }
+    );
+}
+
+#[test]
+fn set_config_preserves_other_bytes() {
+    let bytes = sample_ifr();
+    let mut ifr = Ifr::from_bytes(&bytes).unwrap();
+    let mut config = ifr.config().unwrap();
+
+    // Unchanged configurations serialize to the same bytes.
+    ifr.set_config(&config).unwrap();
+    assert_eq!(ifr.as_bytes(), &bytes[..]);
+
+    config.role = Role::SimultaneousAdvertisingScanning;
+    config.hs_startup_time = Duration::from_micros(1000);
+    config.date.year = 24;
+    ifr.set_config(&config).unwrap();
+
+    let mut expected = bytes.clone();
+    expected[128] = 4;
+    expected[160..162].copy_from_slice(&[0x9A, 0x01]);
+    expected[169] = 0x24;
+    assert_eq!(ifr.as_bytes(), &expected[..]);
+}
+
+#[test]
+fn invalid_config() {
+    let mut bytes = sample_ifr();
+    bytes[128] = 5;
+    assert_eq!(
+        Ifr::from_bytes(&bytes).unwrap().config(),
+        Err(Error::BadRole(5))
+    );
+
+    let mut bytes = sample_ifr();
+    bytes[170] = 0x1A;
+    assert_eq!(
+        Ifr::from_bytes(&bytes).unwrap().config(),
+        Err(Error::BadBcd(0x1A))
+    );
+
+    assert_eq!(
+        Ifr::from_bytes(&[0; IFR_LEN]).unwrap().config(),
+        Err(Error::BadRole(0))
+    );
+    assert_eq!(
+        Ifr::from_bytes(&[0; 64]).err(),
+        Some(Error::BadLength(64, IFR_LEN))
+    );
+}
+
+#[test]
+fn invalid_config_is_not_written() {
+    let bytes = sample_ifr();
+    let mut ifr = Ifr::from_bytes(&bytes).unwrap();
+    let config = ifr.config().unwrap();
+
+    let mut bad = config;
+    bad.peripheral_sleep_clock_accuracy = 501;
+    assert_eq!(
+        ifr.set_config(&bad),
+        Err(Error::BadPeripheralSleepClockAccuracy(501))
+    );
+
+    let mut bad = config;
+    bad.hs_startup_time = Duration::from_millis(200);
+    assert_eq!(
+        ifr.set_config(&bad),
+        Err(Error::BadHsStartupTime(Duration::from_millis(200)))
+    );
+
+    let mut bad = config;
+    bad.date.month = 13;
+    assert_eq!(ifr.set_config(&bad), Err(Error::BadDate(bad.date)));
+
+    assert_eq!(ifr.as_bytes(), &bytes[..]);
+}
+
+// Returns an updater whose IFR sector starts with the given bytes.
+fn updater_with_ifr(ifr: &[u8]) -> FakeUpdater {
+    let mut updater = FakeUpdater::new();
+    let start = FakeUpdater::offset(BASE_ADDRESS);
+    updater.flash[start..start + ifr.len()].copy_from_slice(ifr);
+    updater
+}
+
+fn ifr_sector(updater: &FakeUpdater) -> &[u8] {
+    &updater.flash[FakeUpdater::offset(BASE_ADDRESS)..]
+}
+
+fn read(updater: &mut FakeUpdater) -> Result<Ifr, FlashError<()>> {
+    drive(
+        &mut IfrReader::new(),
+        updater,
+        |reader, updater| reader.poll(updater),
+        |reader, event| reader.handle_event(event),
+    )
+}
+
+fn write(updater: &mut FakeUpdater, ifr: &Ifr) -> Result<(), FlashError<()>> {
+    drive(
+        &mut IfrWriter::new(ifr),
+        updater,
+        |writer, updater| writer.poll(updater),
+        |writer, event| writer.handle_event(event),
+    )
+}
+
+#[test]
+fn read_modify_write() {
+    let mut updater = updater_with_ifr(&sample_ifr());
+    let mut ifr = read(&mut updater).unwrap();
+    assert_eq!(ifr.as_bytes(), &sample_ifr()[..]);
+
+    let mut config = ifr.config().unwrap();
+    config.role = Role::Peripheral6Kb;
+    ifr.set_config(&config).unwrap();
+    write(&mut updater, &ifr).unwrap();
+
+    assert!(updater.blue_flag);
+    assert_eq!(&ifr_sector(&updater)[..IFR_LEN], ifr.as_bytes());
+    assert_eq!(&ifr_sector(&updater)[..128], &sample_ifr()[..128]);
+    assert_eq!(read(&mut updater).unwrap().config(), Ok(config));
+}
+
+#[test]
+fn short_read() {
+    let mut updater = updater_with_ifr(&sample_ifr());
+    updater.short_reads = true;
+    assert_eq!(read(&mut updater), Err(FlashError::ShortRead(BASE_ADDRESS)));
+}
+
+#[test]
+fn write_in_small_blocks() {
+    let mut updater = updater_with_ifr(&[0; IFR_LEN]);
+    updater.buffer_size = 62;
+    let ifr = Ifr::from_bytes(&sample_ifr()).unwrap();
+    write(&mut updater, &ifr).unwrap();
+
+    assert!(updater.blue_flag);
+    assert_eq!(&ifr_sector(&updater)[..IFR_LEN], ifr.as_bytes());
+}
+
+#[test]
+fn buffer_too_small() {
+    let mut updater = updater_with_ifr(&sample_ifr());
+    updater.buffer_size = 3;
+    let ifr = Ifr::from_bytes(&sample_ifr()).unwrap();
+    assert_eq!(
+        write(&mut updater, &ifr),
+        Err(FlashError::BufferTooSmall(3))
+    );
+    assert!(updater.blue_flag);
+}
@@ -66,3 +66,63 @@ fn connection_parameter_update_response() {
         0xD0, 0x07, 0x0F, 0x01
     ]));
 }
+
+#[test]
+fn connection_parameter_update_request_bad_conn_handle() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.connection_parameter_update_request(&ConnectionParameterUpdateRequest {
+                    conn_handle: hci::ConnectionHandle(0x0F00),
+                    conn_interval: ConnectionIntervalBuilder::new()
+                        .with_range(Duration::from_millis(30), Duration::from_millis(300))
+                        .with_latency(10)
+                        .with_supervision_timeout(Duration::from_millis(6610))
+                        .build()
+                        .unwrap(),
+                })
+            })
+            .err()
+            .unwrap();
+        assert_eq!(
+            err,
+            nb::Error::Other(Error::BadConnectionHandle(hci::ConnectionHandle(0x0F00)))
+        );
+    }
+    assert!(!sink.wrote_header());
+}
+
+#[test]
+fn connection_parameter_update_response_bad_identifier() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        let err = fixture
+            .act(|controller| {
+                controller.connection_parameter_update_response(
+                    &ConnectionParameterUpdateResponse {
+                        conn_handle: hci::ConnectionHandle(0x0201),
+                        conn_interval: ConnectionIntervalBuilder::new()
+                            .with_range(Duration::from_millis(30), Duration::from_millis(300))
+                            .with_latency(10)
+                            .with_supervision_timeout(Duration::from_millis(6610))
+                            .build()
+                            .unwrap(),
+                        expected_connection_length_range: ExpectedConnectionLength::new(
+                            Duration::from_millis(500),
+                            Duration::from_millis(1250),
+                        )
+                        .unwrap(),
+                        identifier: 0,
+                        accepted: true,
+                    },
+                )
+            })
+            .err()
+            .unwrap();
+        assert_eq!(err, nb::Error::Other(Error::BadIdentifier(0)));
+    }
+    assert!(!sink.wrote_header());
+}
@@ -0,0 +1,250 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+
+use bluenrg::event::*;
+use bluenrg::uuid::Uuid;
+#[cfg(feature = "bluenrg-1-2")]
+use hci::event::Error as HciError;
+use hci::event::VendorEvent;
+
+// Lenient parsing is global, so every test in this file enables it. The strict behavior is tested
+// in event.rs, which runs in a separate process.
+fn lenient_event(buffer: &[u8]) -> BlueNRGEvent {
+    set_lenient_att_parsing(true);
+    BlueNRGEvent::new(buffer).unwrap()
+}
+
+fn assert_encodes_complete_records(event: &BlueNRGEvent, complete: &[u8]) {
+    let mut bytes = [0; 255];
+    let len = event.copy_into_slice(&mut bytes).unwrap();
+    assert_eq!(&bytes[..len], complete);
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn att_find_information_response_partial_uuid() {
+    let buffer = [
+        0x04, 0x0C, 0x01, 0x02, 11, 1, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+    ];
+    let event = lenient_event(&buffer);
+    match event {
+        BlueNRGEvent::AttFindInformationResponse(ref event) => {
+            assert!(event.is_truncated());
+            match event.handle_uuid_pair_iter() {
+                HandleUuidPairIterator::Format16(iter) => {
+                    let pairs: Vec<_> = iter.map(|p| (p.handle, p.uuid)).collect();
+                    assert_eq!(
+                        pairs,
+                        [
+                            (AttributeHandle(0x0403), Uuid::Uuid16(0x0605)),
+                            (AttributeHandle(0x0807), Uuid::Uuid16(0x0a09)),
+                        ]
+                    );
+                }
+                HandleUuidPairIterator::Format128(_) => panic!("Got 128-bit UUIDs"),
+            }
+        }
+        ref other => panic!("Did not get ATT Find Information Response: {:?}", other),
+    }
+    assert_encodes_complete_records(
+        &event,
+        &[
+            0x04, 0x0C, 0x01, 0x02, 9, 1, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a,
+        ],
+    );
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_find_information_response_partial_uuid() {
+    let buffer = [
+        0x04, 0x0C, 0x01, 0x02, 1, 10, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c,
+    ];
+    let event = lenient_event(&buffer);
+    match event {
+        BlueNRGEvent::AttFindInformationResponse(ref event) => {
+            assert!(event.is_truncated());
+            match event.handle_uuid_pair_iter() {
+                HandleUuidPairIterator::Format16(iter) => {
+                    let pairs: Vec<_> = iter.map(|p| (p.handle, p.uuid)).collect();
+                    assert_eq!(
+                        pairs,
+                        [
+                            (AttributeHandle(0x0403), Uuid::Uuid16(0x0605)),
+                            (AttributeHandle(0x0807), Uuid::Uuid16(0x0a09)),
+                        ]
+                    );
+                }
+                HandleUuidPairIterator::Format128(_) => panic!("Got 128-bit UUIDs"),
+            }
+        }
+        ref other => panic!("Did not get ATT Find Information Response: {:?}", other),
+    }
+    assert_encodes_complete_records(
+        &event,
+        &[
+            0x04, 0x0C, 0x01, 0x02, 1, 8, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a,
+        ],
+    );
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn att_find_by_type_value_response_partial_pair() {
+    let buffer = [
+        0x05, 0x0C, 0x01, 0x02, 7, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+    ];
+    match lenient_event(&buffer) {
+        BlueNRGEvent::AttFindByTypeValueResponse(event) => {
+            assert!(event.is_truncated());
+            let pairs: Vec<_> = event
+                .handle_pairs_iter()
+                .map(|p| (p.attribute, p.group_end))
+                .collect();
+            assert_eq!(pairs, [(AttributeHandle(0x0201), GroupEndHandle(0x0403))]);
+        }
+        other => panic!("Did not get find-by-type-value response: {:?}", other),
+    }
+}
+
+// The BlueNRG-1 and BlueNRG-2 send the number of pairs, so a response that ends in a partial pair
+// has the wrong length, which lenient parsing does not accept.
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_find_by_type_value_response_partial_pair() {
+    let buffer = [
+        0x05, 0x0C, 0x01, 0x02, 2, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07,
+    ];
+    set_lenient_att_parsing(true);
+    match BlueNRGEvent::new(&buffer) {
+        Err(HciError::BadLength(12, 13)) => (),
+        other => panic!("Did not get bad length: {:?}", other),
+    }
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn att_read_by_type_response_partial_pair() {
+    let buffer = [
+        0x06, 0x0C, 0x01, 0x02, 12, 6, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x11, 0x12, 0x13, 0x14,
+        0x15,
+    ];
+    let event = lenient_event(&buffer);
+    match event {
+        BlueNRGEvent::AttReadByTypeResponse(ref event) => {
+            assert!(event.is_truncated());
+            let mut iter = event.handle_value_pair_iter();
+            let pair = iter.next().unwrap();
+            assert_eq!(pair.handle, AttributeHandle(0x0201));
+            assert_eq!(pair.value, [0x03, 0x04, 0x05, 0x06]);
+            assert!(iter.next().is_none());
+        }
+        ref other => panic!("Did not get read-by-type response: {:?}", other),
+    }
+    assert_encodes_complete_records(
+        &event,
+        &[
+            0x06, 0x0C, 0x01, 0x02, 7, 6, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+        ],
+    );
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_read_by_type_response_partial_pair() {
+    let buffer = [
+        0x06, 0x0C, 0x01, 0x02, 6, 11, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x11, 0x12, 0x13, 0x14,
+        0x15,
+    ];
+    let event = lenient_event(&buffer);
+    match event {
+        BlueNRGEvent::AttReadByTypeResponse(ref event) => {
+            assert!(event.is_truncated());
+            let mut iter = event.handle_value_pair_iter();
+            let pair = iter.next().unwrap();
+            assert_eq!(pair.handle, AttributeHandle(0x0201));
+            assert_eq!(pair.value, [0x03, 0x04, 0x05, 0x06]);
+            assert!(iter.next().is_none());
+        }
+        ref other => panic!("Did not get read-by-type response: {:?}", other),
+    }
+    assert_encodes_complete_records(
+        &event,
+        &[
+            0x06, 0x0C, 0x01, 0x02, 6, 6, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+        ],
+    );
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn att_read_by_group_type_response_partial_group() {
+    let buffer = [
+        0x0A, 0x0C, 0x01, 0x02, 16, 8, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x11, 0x12,
+        0x13, 0x14, 0x15, 0x16, 0x17,
+    ];
+    match lenient_event(&buffer) {
+        BlueNRGEvent::AttReadByGroupTypeResponse(event) => {
+            assert!(event.is_truncated());
+            let mut iter = event.attribute_data_iter();
+            let data = iter.next().unwrap();
+            assert_eq!(data.attribute_handle, AttributeHandle(0x0201));
+            assert_eq!(data.group_end_handle, GroupEndHandle(0x0403));
+            assert_eq!(data.value, [0x05, 0x06, 0x07, 0x08]);
+            assert!(iter.next().is_none());
+        }
+        other => panic!("Did not get Read by Group Type Response: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn att_read_by_group_type_response_partial_group() {
+    let buffer = [
+        0x0A, 0x0C, 0x01, 0x02, 8, 15, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x11, 0x12,
+        0x13, 0x14, 0x15, 0x16, 0x17,
+    ];
+    let event = lenient_event(&buffer);
+    match event {
+        BlueNRGEvent::AttReadByGroupTypeResponse(ref event) => {
+            assert!(event.is_truncated());
+            let mut iter = event.attribute_data_iter();
+            let data = iter.next().unwrap();
+            assert_eq!(data.attribute_handle, AttributeHandle(0x0201));
+            assert_eq!(data.group_end_handle, GroupEndHandle(0x0403));
+            assert_eq!(data.value, [0x05, 0x06, 0x07, 0x08]);
+            assert!(iter.next().is_none());
+        }
+        ref other => panic!("Did not get Read by Group Type Response: {:?}", other),
+    }
+    assert_encodes_complete_records(
+        &event,
+        &[
+            0x0A, 0x0C, 0x01, 0x02, 8, 8, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08,
+        ],
+    );
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn complete_responses_are_not_truncated() {
+    let buffer = [
+        0x06, 0x0C, 0x01, 0x02, 7, 6, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+    ];
+    match lenient_event(&buffer) {
+        BlueNRGEvent::AttReadByTypeResponse(event) => assert!(!event.is_truncated()),
+        other => panic!("Did not get read-by-type response: {:?}", other),
+    }
+}
+
+#[cfg(feature = "bluenrg-1-2")]
+#[test]
+fn complete_responses_are_not_truncated() {
+    let buffer = [
+        0x06, 0x0C, 0x01, 0x02, 6, 6, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06,
+    ];
+    match lenient_event(&buffer) {
+        BlueNRGEvent::AttReadByTypeResponse(event) => assert!(!event.is_truncated()),
+        other => panic!("Did not get read-by-type response: {:?}", other),
+    }
+}
@@ -0,0 +1,190 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::command::{ClientStatus, HalLinkStatus, LinkState};
+use bluenrg::event::BlueNRGEvent;
+use bluenrg::hal::Role;
+use bluenrg::links::*;
+use bluenrg::CommandWriter;
+use hci::event::{Event as HciEvent, Packet};
+use hci::ConnectionHandle;
+
+struct RecordingSink {
+    opcodes: Vec<u16>,
+}
+
+impl CommandWriter for RecordingSink {
+    type Error = ();
+
+    fn write_command(&mut self, opcode: hci::Opcode, _params: &[u8]) -> nb::Result<(), ()> {
+        self.opcodes.push(opcode.0);
+        Ok(())
+    }
+}
+
+fn client(state: LinkState, handle: u16) -> ClientStatus {
+    ClientStatus {
+        state,
+        conn_handle: ConnectionHandle(handle),
+    }
+}
+
+fn link_status(clients: [ClientStatus; 8]) -> Vec<u8> {
+    let params = BNRGParams::HalGetLinkStatus(HalLinkStatus {
+        status: hci::Status::Success,
+        clients,
+    });
+    let mut buffer = vec![0; 257];
+    let len = params.copy_into_slice(&mut buffer[3..]).unwrap();
+    buffer[0] = 0x0E;
+    buffer[1] = 1 + len as u8;
+    buffer[2] = 1;
+    buffer.truncate(3 + len);
+    buffer
+}
+
+fn connection_complete(handle: u16, role: u8) -> Vec<u8> {
+    let mut packet = vec![0x3E, 19, 0x01, 0x00];
+    packet.extend_from_slice(&handle.to_le_bytes());
+    packet.extend_from_slice(&[role, 0x00, 1, 2, 3, 4, 5, 6]);
+    // 50 ms interval, no latency, 1 s supervision timeout
+    packet.extend_from_slice(&[0x28, 0x00, 0x00, 0x00, 0x64, 0x00, 0x00]);
+    packet
+}
+
+fn disconnection_complete(handle: u16) -> Vec<u8> {
+    vec![0x05, 4, 0x00, handle as u8, (handle >> 8) as u8, 0x13]
+}
+
+fn handle(tracker: &mut Tracker, packet: &[u8]) -> Vec<Change> {
+    let event: HciEvent<BlueNRGEvent> = HciEvent::new(Packet(packet)).unwrap();
+    tracker.handle_event(&event).collect()
+}
+
+#[test]
+fn snapshots_are_diffed() {
+    let mut tracker = Tracker::new(Role::Primary12Kb);
+    let mut sink = RecordingSink {
+        opcodes: Vec::new(),
+    };
+    tracker.refresh(&mut sink).unwrap();
+    tracker.refresh(&mut sink).unwrap();
+    assert_eq!(sink.opcodes, [0xFC17]);
+
+    let idle = client(LinkState::Idle, 0);
+    let mut clients = [idle; 8];
+    clients[0] = client(LinkState::Advertising, 0);
+    clients[3] = client(LinkState::Scanning, 0);
+    assert_eq!(
+        handle(&mut tracker, &link_status(clients)),
+        [
+            Change::Slot {
+                slot: 0,
+                previous: idle,
+                current: clients[0],
+            },
+            Change::Slot {
+                slot: 3,
+                previous: idle,
+                current: clients[3],
+            },
+        ]
+    );
+    assert_eq!(tracker.count(LinkState::Advertising), 1);
+    assert_eq!(tracker.count(LinkState::Scanning), 1);
+
+    // An unchanged snapshot has no changes, and the response allows another request.
+    assert_eq!(handle(&mut tracker, &link_status(clients)), []);
+    tracker.refresh(&mut sink).unwrap();
+    assert_eq!(sink.opcodes, [0xFC17, 0xFC17]);
+}
+
+#[test]
+fn lost_snapshot_is_requested_again() {
+    let mut tracker = Tracker::new(Role::Primary12Kb);
+    let mut sink = RecordingSink {
+        opcodes: Vec::new(),
+    };
+    tracker.refresh(&mut sink).unwrap();
+
+    // The response never arrives, so the tracker keeps waiting until it is rearmed.
+    tracker.refresh(&mut sink).unwrap();
+    assert_eq!(sink.opcodes, [0xFC17]);
+    tracker.rearm();
+    tracker.refresh(&mut sink).unwrap();
+    assert_eq!(sink.opcodes, [0xFC17, 0xFC17]);
+
+    // The response to the new request is merged as usual.
+    let mut clients = [client(LinkState::Idle, 0); 8];
+    clients[1] = client(LinkState::Advertising, 0);
+    assert_eq!(handle(&mut tracker, &link_status(clients)).len(), 1);
+    assert_eq!(tracker.count(LinkState::Advertising), 1);
+    tracker.refresh(&mut sink).unwrap();
+    assert_eq!(sink.opcodes, [0xFC17, 0xFC17, 0xFC17]);
+}
+
+#[test]
+fn connection_events_are_merged() {
+    let mut tracker = Tracker::new(Role::Primary12Kb);
+    let mut clients = [client(LinkState::Idle, 0); 8];
+    clients[2] = client(LinkState::Advertising, 0);
+    clients[5] = client(LinkState::Scanning, 0);
+    handle(&mut tracker, &link_status(clients));
+
+    // The peripheral connection takes the advertising slot, and the central connection the
+    // scanning slot.
+    assert_eq!(
+        handle(&mut tracker, &connection_complete(0x0801, 1)),
+        [Change::Slot {
+            slot: 2,
+            previous: clients[2],
+            current: client(LinkState::ConnectedAsPeripheral, 0x0801),
+        }]
+    );
+    assert_eq!(
+        handle(&mut tracker, &connection_complete(0x0802, 0)),
+        [Change::Slot {
+            slot: 5,
+            previous: clients[5],
+            current: client(LinkState::ConnectedAsPrimary, 0x0802),
+        }]
+    );
+    assert_eq!(tracker.slot(ConnectionHandle(0x0802)), Some(5));
+    assert_eq!(tracker.connections(), 2);
+    assert_eq!(tracker.available_connections(), 6);
+
+    assert_eq!(
+        handle(&mut tracker, &disconnection_complete(0x0801)),
+        [Change::Slot {
+            slot: 2,
+            previous: client(LinkState::ConnectedAsPeripheral, 0x0801),
+            current: client(LinkState::Idle, 0x0801),
+        }]
+    );
+    assert_eq!(tracker.slot(ConnectionHandle(0x0801)), None);
+    assert_eq!(tracker.connections(), 1);
+}
+
+#[test]
+fn exhausted_connections_are_reported_once() {
+    let mut tracker = Tracker::new(Role::Peripheral6Kb);
+    let changes = handle(&mut tracker, &connection_complete(0x0801, 1));
+    assert_eq!(changes.len(), 2);
+    assert_eq!(changes[1], Change::ConnectionsExhausted(1));
+    assert_eq!(
+        tracker.slots()[0],
+        client(LinkState::ConnectedAsPeripheral, 0x0801)
+    );
+    assert_eq!(tracker.available_connections(), 0);
+
+    // A snapshot that agrees with the model does not repeat the warning.
+    let mut clients = *tracker.slots();
+    assert_eq!(handle(&mut tracker, &link_status(clients)), []);
+
+    clients[0].state = LinkState::Idle;
+    assert_eq!(handle(&mut tracker, &link_status(clients)).len(), 1);
+    let changes = handle(&mut tracker, &connection_complete(0x0803, 1));
+    assert_eq!(changes[1], Change::ConnectionsExhausted(1));
+}
@@ -0,0 +1,154 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::decode::VendorCommand;
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::command::{HalConfigData, HalConfigParameter};
+use bluenrg::hal::{ConfigParameter, Role};
+use bluenrg::provision::*;
+use bluenrg::CommandWriter;
+use fixture::{drive, encode_command_complete, event, Responder};
+use hci::host::EncryptionKey;
+
+// Emulates the configuration data structure of the controller.
+struct FakeController {
+    config: [u8; 42],
+    read_only: Vec<usize>,
+    written: Vec<usize>,
+    response: Option<BNRGParams>,
+}
+
+impl FakeController {
+    fn new() -> FakeController {
+        FakeController {
+            config: [0; 42],
+            read_only: Vec::new(),
+            written: Vec::new(),
+            response: None,
+        }
+    }
+}
+
+impl CommandWriter for FakeController {
+    type Error = ();
+
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), ()> {
+        assert!(self.response.is_none());
+        let response = match VendorCommand::new(opcode, params).unwrap() {
+            VendorCommand::HalWriteConfigData(data) => {
+                self.written.push(data.offset());
+                if !self.read_only.contains(&data.offset()) {
+                    let value = data.value();
+                    self.config[data.offset()..data.offset() + value.len()].copy_from_slice(value);
+                }
+                BNRGParams::HalWriteConfigData(hci::Status::Success)
+            }
+            VendorCommand::HalReadConfigData(param) => {
+                let offset = param as usize;
+                let bytes = &self.config[offset..];
+                let value = match param {
+                    ConfigParameter::PublicAddress => {
+                        let mut addr = [0; 6];
+                        addr.copy_from_slice(&bytes[..6]);
+                        HalConfigParameter::PublicAddress(hci::BdAddr(addr))
+                    }
+                    ConfigParameter::Diversifier => HalConfigParameter::Diversifier(
+                        u16::from(bytes[0]) | u16::from(bytes[1]) << 8,
+                    ),
+                    ConfigParameter::EncryptionRoot | ConfigParameter::IdentityRoot => {
+                        let mut key = [0; 16];
+                        key.copy_from_slice(&bytes[..16]);
+                        HalConfigParameter::EncryptionKey(EncryptionKey(key))
+                    }
+                    ConfigParameter::LinkLayerOnly | ConfigParameter::Role => {
+                        HalConfigParameter::Byte(bytes[0])
+                    }
+                };
+                BNRGParams::HalReadConfigData(HalConfigData {
+                    status: hci::Status::Success,
+                    value,
+                })
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        };
+        self.response = Some(response);
+
+        Ok(())
+    }
+}
+
+impl Responder for FakeController {
+    fn response(&mut self) -> Option<Vec<u8>> {
+        self.response
+            .take()
+            .map(|params| encode_command_complete(&params))
+    }
+}
+
+fn identity() -> Identity {
+    Identity {
+        public_address: hci::BdAddr([1, 2, 3, 4, 5, 6]),
+        diversifier: 0x1234,
+        encryption_root: EncryptionKey([0x11; 16]),
+        identity_root: EncryptionKey([0x22; 16]),
+        link_layer_only: true,
+        role: Role::Primary12Kb,
+    }
+}
+
+fn provision(controller: &mut FakeController, identity: &Identity) -> Result<(), Error<()>> {
+    drive(
+        &mut Provisioner::new(identity),
+        controller,
+        |provisioner, controller| provisioner.poll(controller),
+        |provisioner, event| provisioner.handle_event(event),
+    )
+}
+
+#[test]
+fn provision_identity() {
+    let mut controller = FakeController::new();
+    provision(&mut controller, &identity()).unwrap();
+
+    assert_eq!(controller.written, [0, 6, 8, 24, 40, 41]);
+    assert_eq!(&controller.config[0..6], &[1, 2, 3, 4, 5, 6]);
+    assert_eq!(&controller.config[6..8], &[0x34, 0x12]);
+    assert_eq!(&controller.config[8..24], &[0x11; 16]);
+    assert_eq!(&controller.config[24..40], &[0x22; 16]);
+    assert_eq!(&controller.config[40..42], &[1, 3]);
+}
+
+#[test]
+fn mismatch_reports_every_field() {
+    let mut controller = FakeController::new();
+    controller.read_only = vec![24, 41];
+    assert_eq!(
+        provision(&mut controller, &identity()),
+        Err(Error::Mismatch(Fields::IDENTITY_ROOT | Fields::ROLE))
+    );
+    assert_eq!(&controller.config[8..24], &[0x11; 16]);
+}
+
+#[test]
+fn command_failure() {
+    let mut controller = FakeController::new();
+    let mut provisioner = Provisioner::new(&identity());
+    assert_eq!(
+        provisioner.poll(&mut controller),
+        Err(nb::Error::WouldBlock)
+    );
+    controller.response.take().unwrap();
+    provisioner.handle_event(&event(&encode_command_complete(
+        &BNRGParams::HalWriteConfigData(hci::Status::UnspecifiedError),
+    )));
+    assert_eq!(
+        provisioner.poll(&mut controller),
+        Err(nb::Error::Other(Error::CommandFailed(
+            hci::Opcode(0xFC0C),
+            hci::Status::UnspecifiedError
+        )))
+    );
+}
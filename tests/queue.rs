@@ -0,0 +1,103 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::event::BlueNRGEvent;
+use bluenrg::gatt::Commands as GattCommands;
+use bluenrg::hal::Commands as HalCommands;
+use bluenrg::queue::*;
+use fixture::RecordingWriter;
+use hci::event::{Event as HciEvent, Packet};
+
+#[test]
+fn queues_commands_until_pumped() {
+    let mut slots = [Slot::EMPTY; 4];
+    let mut queue = CommandQueue::new(&mut slots);
+    queue.get_firmware_revision().unwrap();
+    queue.device_standby().unwrap();
+    assert_eq!(queue.len(), 2);
+
+    let mut writer = RecordingWriter::accepting(8);
+    queue.set_credits(2);
+    queue.pump(&mut writer).unwrap();
+    assert!(queue.is_empty());
+    assert_eq!(writer.opcodes(), [0xFC00, 0xFC13]);
+    assert_eq!(queue.credits(), 0);
+}
+
+#[test]
+fn full_queue_would_block() {
+    let mut slots = [Slot::EMPTY; 1];
+    let mut queue = CommandQueue::new(&mut slots);
+    queue.get_firmware_revision().unwrap();
+    assert!(queue.is_full());
+    assert_eq!(queue.device_standby(), Err(nb::Error::WouldBlock));
+}
+
+#[test]
+fn parameters_too_long() {
+    let mut slots = [Slot::EMPTY; 1];
+    let mut queue = CommandQueue::new(&mut slots);
+    assert_eq!(
+        queue.push(Priority::Normal, hci::Opcode(0xFC00), &[0; 256]),
+        Err(nb::Error::Other(Error::ParametersTooLong(256)))
+    );
+    assert!(queue.is_empty());
+}
+
+#[test]
+fn urgent_commands_jump_ahead() {
+    let mut slots = [Slot::EMPTY; 4];
+    let mut queue = CommandQueue::new(&mut slots);
+    queue
+        .push(Priority::of(hci::Opcode(0xFD06)), hci::Opcode(0xFD06), &[1])
+        .unwrap();
+    queue.get_firmware_revision().unwrap();
+    queue.allow_read(hci::ConnectionHandle(0x0201)).unwrap();
+    queue
+        .with_priority(Priority::Bulk, |q| q.device_standby())
+        .unwrap();
+
+    let mut writer = RecordingWriter::accepting(8);
+    queue.set_credits(4);
+    queue.pump(&mut writer).unwrap();
+    assert_eq!(writer.opcodes(), [0xFD27, 0xFC00, 0xFD06, 0xFC13]);
+    assert_eq!(writer.written[0].1, [0x01, 0x02]);
+}
+
+#[test]
+fn pump_stops_without_credits() {
+    let mut slots = [Slot::EMPTY; 4];
+    let mut queue = CommandQueue::new(&mut slots);
+    queue.get_firmware_revision().unwrap();
+    queue.device_standby().unwrap();
+
+    let mut writer = RecordingWriter::accepting(8);
+    assert_eq!(queue.pump(&mut writer), Err(nb::Error::WouldBlock));
+    assert_eq!(writer.opcodes(), [0xFC00]);
+    assert_eq!(queue.len(), 1);
+
+    let event: HciEvent<BlueNRGEvent> = HciEvent::new(Packet(&[0x0E, 3, 1, 0x00, 0x00])).unwrap();
+    queue.update_credits(&event);
+    assert_eq!(queue.credits(), 1);
+    queue.pump(&mut writer).unwrap();
+    assert_eq!(writer.opcodes(), [0xFC00, 0xFC13]);
+}
+
+#[test]
+fn pump_keeps_command_when_controller_is_busy() {
+    let mut slots = [Slot::EMPTY; 4];
+    let mut queue = CommandQueue::new(&mut slots);
+    queue.get_firmware_revision().unwrap();
+
+    let mut writer = RecordingWriter::accepting(0);
+    assert_eq!(queue.pump(&mut writer), Err(nb::Error::WouldBlock));
+    assert_eq!(queue.len(), 1);
+    assert_eq!(queue.credits(), 1);
+
+    writer.accept = 1;
+    queue.pump(&mut writer).unwrap();
+    assert!(queue.is_empty());
+}
@@ -0,0 +1,38 @@
+extern crate bluenrg;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::raw::*;
+use fixture::{Fixture, RecordingSink};
+
+#[test]
+fn write_vendor_command() {
+    let mut sink = RecordingSink::new();
+    {
+        let mut fixture = Fixture::new(&mut sink);
+        fixture
+            .act(|controller| controller.write_vendor_command(0x07F, &[0x01, 0x02]))
+            .unwrap();
+    }
+    assert!(sink.wrote_header());
+    assert!(sink.wrote(&[1, 0x7F, 0xFC, 2, 0x01, 0x02]));
+}
+
+#[test]
+fn write_vendor_command_bad_ocf() {
+    let mut sink = RecordingSink::new();
+    let mut fixture = Fixture::new(&mut sink);
+    let err = fixture.act(|controller| controller.write_vendor_command(0x400, &[]));
+    assert_eq!(err, Err(nb::Error::Other(Error::BadOcf(0x400))));
+    assert!(!fixture.wrote_header());
+}
+
+#[test]
+fn write_vendor_command_too_long() {
+    let mut sink = RecordingSink::new();
+    let mut fixture = Fixture::new(&mut sink);
+    let err = fixture.act(|controller| controller.write_vendor_command(0x07F, &[0; 256]));
+    assert_eq!(err, Err(nb::Error::Other(Error::ParametersTooLong(256))));
+    assert!(!fixture.wrote_header());
+}
@@ -0,0 +1,250 @@
+#![cfg(feature = "ms")]
+
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::decode::VendorCommand;
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::command::{ClientStatus, HalLinkStatus, LinkState};
+use bluenrg::event::{BlueNRGEvent, EventFlags, GattProcedureComplete, GattProcedureStatus};
+use bluenrg::gatt::CharacteristicHandle;
+use bluenrg::recovery::*;
+use bluenrg::CommandWriter;
+use fixture::{drive, encode_command_complete, Responder};
+use hci::event::{Event as HciEvent, Packet};
+use hci::ConnectionHandle;
+
+// Answers each command with a Command Complete packet.
+struct FakeController {
+    response: Option<Vec<u8>>,
+    reads: Vec<u16>,
+    read_status: u8,
+    clients: [ClientStatus; 8],
+}
+
+impl FakeController {
+    fn new() -> FakeController {
+        FakeController {
+            response: None,
+            reads: Vec::new(),
+            read_status: 0x00,
+            clients: [ClientStatus {
+                state: LinkState::Idle,
+                conn_handle: ConnectionHandle(0),
+            }; 8],
+        }
+    }
+}
+
+impl CommandWriter for FakeController {
+    type Error = ();
+
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), ()> {
+        assert!(self.response.is_none());
+        let response = match VendorCommand::new(opcode, params).unwrap() {
+            VendorCommand::HalGetLinkStatus => {
+                let params = BNRGParams::HalGetLinkStatus(HalLinkStatus {
+                    status: hci::Status::Success,
+                    clients: self.clients,
+                });
+                encode_command_complete(&params)
+            }
+            VendorCommand::GattReadHandleValue(handle) => {
+                self.reads.push(handle.0);
+                // A one-byte value with the low byte of the handle.
+                vec![
+                    0x0E,
+                    7,
+                    1,
+                    0x2A,
+                    0xFD,
+                    self.read_status,
+                    1,
+                    0,
+                    handle.0 as u8,
+                ]
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        };
+        self.response = Some(response);
+
+        Ok(())
+    }
+}
+
+impl Responder for FakeController {
+    fn response(&mut self) -> Option<Vec<u8>> {
+        self.response.take()
+    }
+}
+
+fn events_lost(flags: EventFlags) -> HciEvent<BlueNRGEvent> {
+    HciEvent::Vendor(BlueNRGEvent::EventsLost(flags))
+}
+
+// Runs the recovery to completion, and returns the notices and the first error.
+fn recover(
+    recovery: &mut Recovery,
+    controller: &mut FakeController,
+) -> (Vec<Notice>, Result<(), Error<()>>) {
+    let mut notices = Vec::new();
+    let result = drive(
+        recovery,
+        controller,
+        |recovery, controller| recovery.poll(controller),
+        |recovery, event| notices.extend(recovery.handle_event(event)),
+    );
+    (notices, result)
+}
+
+#[test]
+fn lost_events_are_mapped_to_actions() {
+    let mut controller = FakeController::new();
+    let mut recovery = Recovery::new();
+    recovery.watch(CharacteristicHandle(0x0011)).unwrap();
+    recovery.watch(CharacteristicHandle(0x0022)).unwrap();
+    recovery.watch(CharacteristicHandle(0x0022)).unwrap();
+    assert!(recovery.is_idle());
+
+    let notices: Vec<Notice> = recovery
+        .handle_event(&events_lost(
+            EventFlags::LINK_LAYER_CONNECTION_COMPLETE
+                | EventFlags::GATT_ATTRIBUTE_MODIFIED
+                | EventFlags::GATT_READ_PERMIT_REQUEST,
+        ))
+        .collect();
+    assert_eq!(
+        notices,
+        [Notice::Unrecovered(EventFlags::GATT_READ_PERMIT_REQUEST)]
+    );
+    assert!(!recovery.is_idle());
+
+    let (notices, result) = recover(&mut recovery, &mut controller);
+    assert_eq!(result, Ok(()));
+    assert_eq!(
+        notices,
+        [
+            Notice::ValueRefreshed(CharacteristicHandle(0x0011)),
+            Notice::ValueRefreshed(CharacteristicHandle(0x0022)),
+        ]
+    );
+    assert_eq!(controller.reads, [0x0011, 0x0022]);
+    assert!(recovery.is_idle());
+    assert_eq!(recovery.unrecovered(), EventFlags::GATT_READ_PERMIT_REQUEST);
+
+    recovery.clear_unrecovered();
+    assert_eq!(recovery.unrecovered(), EventFlags::empty());
+}
+
+#[test]
+fn lost_modifications_without_watched_values_are_unrecovered() {
+    let mut recovery = Recovery::new();
+    recovery.watch(CharacteristicHandle(0x0011)).unwrap();
+    recovery.unwatch(CharacteristicHandle(0x0011));
+
+    let notices: Vec<Notice> = recovery
+        .handle_event(&events_lost(EventFlags::GATT_ATTRIBUTE_MODIFIED))
+        .collect();
+    assert_eq!(
+        notices,
+        [Notice::Unrecovered(EventFlags::GATT_ATTRIBUTE_MODIFIED)]
+    );
+    assert!(recovery.is_idle());
+}
+
+#[test]
+fn lost_responses_fail_pending_procedures() {
+    let mut recovery = Recovery::new();
+    recovery
+        .procedure_started(ConnectionHandle(0x0801))
+        .unwrap();
+    recovery
+        .procedure_started(ConnectionHandle(0x0802))
+        .unwrap();
+    recovery
+        .procedure_started(ConnectionHandle(0x0803))
+        .unwrap();
+
+    // One procedure completes, and another connection is closed.
+    recovery.handle_event(&HciEvent::Vendor(BlueNRGEvent::GattProcedureComplete(
+        GattProcedureComplete {
+            conn_handle: ConnectionHandle(0x0801),
+            status: GattProcedureStatus::Success,
+        },
+    )));
+    recovery.handle_event(&HciEvent::new(Packet(&[0x05, 4, 0x00, 0x03, 0x08, 0x13])).unwrap());
+    assert!(!recovery.is_procedure_pending(ConnectionHandle(0x0801)));
+    assert!(recovery.is_procedure_pending(ConnectionHandle(0x0802)));
+    assert!(!recovery.is_procedure_pending(ConnectionHandle(0x0803)));
+
+    let notices: Vec<Notice> = recovery
+        .handle_event(&events_lost(EventFlags::ATT_READ_RESPONSE))
+        .collect();
+    assert_eq!(notices, [Notice::ProcedureFailed(ConnectionHandle(0x0802))]);
+    assert!(!recovery.is_procedure_pending(ConnectionHandle(0x0802)));
+    assert!(recovery.is_idle());
+
+    for i in 0..MAX_PROCEDURES {
+        recovery
+            .procedure_started(ConnectionHandle(i as u16))
+            .unwrap();
+    }
+    assert_eq!(
+        recovery.procedure_started(ConnectionHandle(0x0900)),
+        Err(RegisterError::TooManyProcedures)
+    );
+}
+
+#[test]
+fn lost_disconnection_releases_procedures() {
+    let mut controller = FakeController::new();
+    controller.clients[4] = ClientStatus {
+        state: LinkState::ConnectedAsPrimary,
+        conn_handle: ConnectionHandle(0x0801),
+    };
+    let mut recovery = Recovery::new();
+    recovery
+        .procedure_started(ConnectionHandle(0x0801))
+        .unwrap();
+    recovery
+        .procedure_started(ConnectionHandle(0x0802))
+        .unwrap();
+
+    // The disconnection of 0x0802 is dropped, so only the link status shows that it is closed.
+    let notices: Vec<Notice> = recovery
+        .handle_event(&events_lost(EventFlags::DISCONNECTION_COMPLETE))
+        .collect();
+    assert_eq!(notices, []);
+    assert!(recovery.is_procedure_pending(ConnectionHandle(0x0802)));
+
+    let (notices, result) = recover(&mut recovery, &mut controller);
+    assert_eq!(result, Ok(()));
+    assert_eq!(notices, [Notice::ProcedureFailed(ConnectionHandle(0x0802))]);
+    assert!(recovery.is_procedure_pending(ConnectionHandle(0x0801)));
+    assert!(!recovery.is_procedure_pending(ConnectionHandle(0x0802)));
+    assert!(recovery.is_idle());
+}
+
+#[test]
+fn failed_read_is_reported() {
+    let mut controller = FakeController::new();
+    controller.read_status = 0x0C;
+    let mut recovery = Recovery::new();
+    recovery.watch(CharacteristicHandle(0x0011)).unwrap();
+    recovery.handle_event(&events_lost(EventFlags::GATT_ATTRIBUTE_MODIFIED));
+
+    let (notices, result) = recover(&mut recovery, &mut controller);
+    assert_eq!(notices, []);
+    assert_eq!(
+        result,
+        Err(Error::CommandFailed(
+            hci::Opcode(0xFD2A),
+            hci::Status::CommandDisallowed
+        ))
+    );
+    assert!(recovery.is_idle());
+    assert_eq!(recover(&mut recovery, &mut controller).1, Ok(()));
+}
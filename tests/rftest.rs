@@ -0,0 +1,284 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::decode::VendorCommand;
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::command::{ClientStatus, HalLinkStatus, HalTxTestPacketCount, LinkState};
+use bluenrg::hal::PowerLevel;
+use bluenrg::rftest::*;
+use bluenrg::CommandWriter;
+use core::time::Duration;
+use fixture::{command_complete, drive, encode_command_complete, Responder};
+use hci::host::TestPacketPayload;
+
+const LE_RECEIVER_TEST: u16 = 0x201D;
+const LE_TRANSMITTER_TEST: u16 = 0x201E;
+const LE_TEST_END: u16 = 0x201F;
+
+// Emulates the test modes of the controller. Each millisecond of a test sends or receives one
+// packet.
+struct FakeController {
+    now: Duration,
+    links: [LinkState; 8],
+    started: Option<Duration>,
+    tx_packets: u32,
+    failing: Option<u16>,
+    commands: Vec<String>,
+    response: Option<Vec<u8>>,
+}
+
+impl FakeController {
+    fn new() -> FakeController {
+        FakeController {
+            now: Duration::from_secs(0),
+            links: [LinkState::Idle; 8],
+            started: None,
+            tx_packets: 0,
+            failing: None,
+            commands: Vec::new(),
+            response: None,
+        }
+    }
+
+    fn elapsed_packets(&mut self) -> u32 {
+        let started = self.started.take().expect("No test running");
+        (self.now - started).as_millis() as u32
+    }
+}
+
+impl CommandWriter for FakeController {
+    type Error = ();
+
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), ()> {
+        assert!(self.response.is_none());
+        let (status, status_byte) = if self.failing == Some(opcode.0) {
+            (hci::Status::HardwareFailure, 0x03)
+        } else {
+            (hci::Status::Success, 0x00)
+        };
+        let [lo, hi] = opcode.0.to_le_bytes();
+        let response = match opcode.0 {
+            LE_TRANSMITTER_TEST => {
+                self.commands
+                    .push(format!("tx {} {}", params[0], params[1]));
+                self.started = Some(self.now);
+                command_complete(&[lo, hi, status_byte])
+            }
+            LE_RECEIVER_TEST => {
+                self.commands.push(format!("rx {}", params[0]));
+                self.started = Some(self.now);
+                command_complete(&[lo, hi, status_byte])
+            }
+            LE_TEST_END => {
+                self.commands.push("end".to_string());
+                let packets = self.elapsed_packets();
+                self.tx_packets = packets;
+                command_complete(&[lo, hi, status_byte, packets as u8, 0])
+            }
+            _ => match VendorCommand::new(opcode, params).unwrap() {
+                VendorCommand::HalGetLinkStatus => {
+                    let mut clients = [ClientStatus {
+                        state: LinkState::Idle,
+                        conn_handle: hci::ConnectionHandle(0),
+                    }; 8];
+                    for (client, &state) in clients.iter_mut().zip(&self.links) {
+                        client.state = state;
+                    }
+                    encode_command_complete(&BNRGParams::HalGetLinkStatus(HalLinkStatus {
+                        status,
+                        clients,
+                    }))
+                }
+                VendorCommand::HalSetTxPowerLevel(level) => {
+                    self.commands.push(format!("power {:?}", level));
+                    encode_command_complete(&BNRGParams::HalSetTxPowerLevel(status))
+                }
+                VendorCommand::HalStartTone(channel) => {
+                    self.commands.push(format!("tone {}", channel));
+                    encode_command_complete(&BNRGParams::HalStartTone(status))
+                }
+                VendorCommand::HalStopTone => {
+                    self.commands.push("stop".to_string());
+                    encode_command_complete(&BNRGParams::HalStopTone(status))
+                }
+                VendorCommand::HalGetTxTestPacketCount => encode_command_complete(
+                    &BNRGParams::HalGetTxTestPacketCount(HalTxTestPacketCount {
+                        status,
+                        packet_count: self.tx_packets,
+                    }),
+                ),
+                other => panic!("Unexpected command: {:?}", other),
+            },
+        };
+        self.response = Some(response);
+
+        Ok(())
+    }
+}
+
+impl Responder for FakeController {
+    fn response(&mut self) -> Option<Vec<u8>> {
+        self.response.take()
+    }
+
+    // Time passes while the runner waits for a test to finish.
+    fn idle(&mut self) {
+        self.now += Duration::from_millis(1);
+    }
+}
+
+fn plan() -> Plan<'static> {
+    Plan {
+        tone_channels: &[0, 39],
+        transmitter_channels: &[19],
+        receiver_channels: &[12],
+        power_levels: &[PowerLevel::DbmNeg18, PowerLevel::Dbm8_0],
+        sweep_channel: 20,
+        payload_length: 37,
+        payload: TestPacketPayload::PrbS9,
+        dwell: Duration::from_millis(10),
+    }
+}
+
+fn run(controller: &mut FakeController, plan: &Plan) -> Result<Report, Error<()>> {
+    let mut runner = Runner::new(plan).unwrap();
+    drive(
+        &mut runner,
+        controller,
+        |runner, controller| {
+            let now = controller.now;
+            runner.poll(controller, now)
+        },
+        |runner, event| runner.handle_event(event),
+    )?;
+
+    Ok(runner.report().clone())
+}
+
+#[test]
+fn run_plan() {
+    let mut controller = FakeController::new();
+    let report = run(&mut controller, &plan()).unwrap();
+
+    assert_eq!(
+        controller.commands,
+        [
+            "tone 0",
+            "stop",
+            "tone 39",
+            "stop",
+            "tx 19 37",
+            "end",
+            "rx 12",
+            "end",
+            "power DbmNeg18",
+            "tx 20 37",
+            "end",
+            "power Dbm8_0",
+            "tx 20 37",
+            "end",
+        ]
+    );
+    assert!(report.passed());
+    let result = |test, packets| TestResult {
+        test,
+        status: hci::Status::Success,
+        packets,
+    };
+    assert_eq!(
+        report.results(),
+        &[
+            result(Test::Tone { channel: 0 }, None),
+            result(Test::Tone { channel: 39 }, None),
+            result(
+                Test::Transmitter {
+                    channel: 19,
+                    power_level: None
+                },
+                Some(10)
+            ),
+            result(Test::Receiver { channel: 12 }, Some(10)),
+            result(
+                Test::Transmitter {
+                    channel: 20,
+                    power_level: Some(PowerLevel::DbmNeg18)
+                },
+                Some(10)
+            ),
+            result(
+                Test::Transmitter {
+                    channel: 20,
+                    power_level: Some(PowerLevel::Dbm8_0)
+                },
+                Some(10)
+            ),
+        ]
+    );
+}
+
+#[test]
+fn failed_test_is_reported() {
+    let mut controller = FakeController::new();
+    controller.failing = Some(LE_RECEIVER_TEST);
+    let report = run(&mut controller, &plan()).unwrap();
+
+    assert!(!report.passed());
+    let failed: Vec<_> = report
+        .results()
+        .iter()
+        .filter(|result| !result.passed())
+        .collect();
+    assert_eq!(
+        failed,
+        [&TestResult {
+            test: Test::Receiver { channel: 12 },
+            status: hci::Status::HardwareFailure,
+            packets: None,
+        }]
+    );
+    assert_eq!(report.results().len(), 6);
+
+    // The receiver test never started, so it was not ended.
+    assert_eq!(controller.commands[6..8], ["rx 12", "power DbmNeg18"]);
+}
+
+#[test]
+fn refuses_to_run_while_connected() {
+    let mut controller = FakeController::new();
+    controller.links[2] = LinkState::ConnectedAsPeripheral;
+    controller.links[5] = LinkState::ConnectedAsPrimary;
+    assert_eq!(
+        run(&mut controller, &plan()).err(),
+        Some(Error::ConnectionsActive(2))
+    );
+    assert!(controller.commands.is_empty());
+}
+
+#[test]
+fn invalid_plans() {
+    let mut plan = plan();
+    plan.receiver_channels = &[40];
+    assert_eq!(Runner::new(&plan).err(), Some(PlanError::BadChannel(40)));
+
+    let mut plan = self::plan();
+    plan.sweep_channel = 40;
+    assert_eq!(Runner::new(&plan).err(), Some(PlanError::BadChannel(40)));
+    plan.power_levels = &[];
+    assert!(Runner::new(&plan).is_ok());
+
+    let mut plan = self::plan();
+    plan.payload_length = 38;
+    assert_eq!(
+        Runner::new(&plan).err(),
+        Some(PlanError::BadPayloadLength(38))
+    );
+
+    let channels = [0; 33];
+    let mut plan = self::plan();
+    plan.tone_channels = &channels;
+    plan.receiver_channels = &channels;
+    assert_eq!(Runner::new(&plan).err(), Some(PlanError::TooManyTests(69)));
+}
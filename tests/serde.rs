@@ -0,0 +1,154 @@
+#![cfg(feature = "serde")]
+
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate serde_json;
+
+use bluenrg::event::command::*;
+use bluenrg::event::*;
+use hci::event::VendorEvent;
+use serde_json::json;
+
+#[test]
+fn gatt_notification_serializes_full_value() {
+    // The BlueNRG-1 and BlueNRG-2 send the value length after the attribute handle.
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    let header = [0x0F, 0x0C, 0x01, 0x02, 22, 0x03, 0x04];
+    #[cfg(feature = "bluenrg-1-2")]
+    let header = [0x0F, 0x0C, 0x01, 0x02, 0x03, 0x04, 20];
+    let mut buffer = [0; 27];
+    buffer[..7].copy_from_slice(&header);
+    for (i, byte) in buffer[7..].iter_mut().enumerate() {
+        *byte = i as u8;
+    }
+    let event = BlueNRGEvent::new(&buffer).unwrap();
+    let value: Vec<u8> = (0..20).collect();
+    assert_eq!(
+        serde_json::to_value(event).unwrap(),
+        json!({
+            "GattNotification": {
+                "conn_handle": 0x0201,
+                "attribute_handle": 0x0403,
+                "value": value,
+            }
+        })
+    );
+}
+
+#[test]
+fn gap_procedure_complete_serializes_name() {
+    let buffer = [0x07, 0x04, 0x04, 0x00, b'a', b'b', b'c'];
+    let event = BlueNRGEvent::new(&buffer).unwrap();
+    assert_eq!(
+        serde_json::to_value(event).unwrap(),
+        json!({
+            "GapProcedureComplete": {
+                "procedure": { "NameDiscovery": [b'a', b'b', b'c'] },
+                "status": "Success",
+            }
+        })
+    );
+}
+
+#[test]
+fn gatt_procedure_complete_round_trips() {
+    #[cfg(not(feature = "bluenrg-1-2"))]
+    let buffer = [0x10, 0x0C, 0x01, 0x02, 1, 0x41];
+    #[cfg(feature = "bluenrg-1-2")]
+    let buffer = [0x10, 0x0C, 0x01, 0x02, 0x41];
+    let event = match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GattProcedureComplete(event)) => event,
+        other => panic!("Did not get GATT Procedure Complete: {:?}", other),
+    };
+    let json = serde_json::to_string(&event).unwrap();
+    let decoded: GattProcedureComplete = serde_json::from_str(&json).unwrap();
+    assert_eq!(decoded.conn_handle, event.conn_handle);
+    assert_eq!(decoded.status, event.status);
+}
+
+#[test]
+fn hal_config_data_round_trips() {
+    let params = HalConfigData {
+        status: hci::Status::Vendor(Status::Failed),
+        value: HalConfigParameter::PublicAddress(hci::BdAddr([1, 2, 3, 4, 5, 6])),
+    };
+    let json = serde_json::to_value(&params).unwrap();
+    assert_eq!(
+        json,
+        json!({ "status": 0x41, "value": { "PublicAddress": [1, 2, 3, 4, 5, 6] } })
+    );
+
+    let decoded: HalConfigData = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.status, params.status);
+    assert_eq!(decoded.value, params.value);
+}
+
+#[test]
+fn status_rejects_unknown_code() {
+    let json = json!({ "status": 0xEE, "value": { "Byte": 1 } });
+    assert!(serde_json::from_value::<HalConfigData>(json).is_err());
+}
+
+#[test]
+fn role_rejects_unknown_bits() {
+    assert_eq!(
+        serde_json::from_value::<bluenrg::gap::Role>(json!(0x05)).unwrap(),
+        bluenrg::gap::Role::PERIPHERAL | bluenrg::gap::Role::CENTRAL
+    );
+    assert!(serde_json::from_value::<bluenrg::gap::Role>(json!(0x80)).is_err());
+}
+
+#[test]
+#[cfg(feature = "ms")]
+fn hci_enumerations_round_trip_as_codes() {
+    use bluenrg::gap::DirectConnectableParameters;
+    use std::time::Duration;
+
+    let params = DirectConnectableParameters {
+        own_address_type: hci::host::OwnAddressType::Random,
+        advertising_type: bluenrg::AdvertisingType::ConnectableDirectedLowDutyCycle,
+        initiator_address: hci::BdAddrType::Public(hci::BdAddr([1, 2, 3, 4, 5, 6])),
+        advertising_interval: (Duration::from_millis(20), Duration::from_millis(30)),
+    };
+    let json = serde_json::to_value(&params).unwrap();
+    assert_eq!(json["own_address_type"], json!(0x01));
+    assert_eq!(json["advertising_type"], json!(0x04));
+
+    let decoded: DirectConnectableParameters = serde_json::from_value(json).unwrap();
+    assert_eq!(decoded.own_address_type, params.own_address_type);
+    assert_eq!(decoded.advertising_type, params.advertising_type);
+    assert_eq!(decoded.initiator_address, params.initiator_address);
+
+    let unknown = json!({
+        "own_address_type": 0x07,
+        "advertising_type": 0x04,
+        "initiator_address": { "Public": [1, 2, 3, 4, 5, 6] },
+        "advertising_interval": [
+            { "secs": 0, "nanos": 20_000_000 },
+            { "secs": 0, "nanos": 30_000_000 }
+        ],
+    });
+    assert!(serde_json::from_value::<DirectConnectableParameters>(unknown).is_err());
+}
+
+#[test]
+fn procedure_errors_round_trip_as_codes() {
+    let error = bluenrg::updater::FlashError::<()>::CommandFailed(
+        hci::Opcode(0xFC21),
+        hci::Status::Vendor(Status::Failed),
+    );
+    let json = serde_json::to_value(error).unwrap();
+    assert_eq!(json, json!({ "CommandFailed": [0xFC21, 0x41] }));
+    assert_eq!(
+        serde_json::from_value::<bluenrg::updater::FlashError<()>>(json).unwrap(),
+        error
+    );
+
+    let error = bluenrg::rftest::Error::<()>::LinkStatusFailed(hci::Status::Vendor(Status::Failed));
+    let json = serde_json::to_value(error).unwrap();
+    assert_eq!(json, json!({ "LinkStatusFailed": 0x41 }));
+    assert_eq!(
+        serde_json::from_value::<bluenrg::rftest::Error<()>>(json).unwrap(),
+        error
+    );
+}
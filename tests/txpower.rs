@@ -0,0 +1,211 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::decode::VendorCommand;
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::BlueNRGEvent;
+use bluenrg::hal::PowerLevel;
+use bluenrg::txpower::*;
+use bluenrg::CommandWriter;
+use fixture::{drive, encode_command_complete, event, Responder};
+use hci::event::Event as HciEvent;
+use hci::ConnectionHandle;
+
+#[test]
+fn power_level_dbm() {
+    assert_eq!(PowerLevel::DbmNeg14_7.dbm(), -14.7);
+    assert_eq!(PowerLevel::DbmNeg14_7.rounded_dbm(), -15);
+    assert_eq!(PowerLevel::Dbm1_4.rounded_dbm(), 1);
+    assert_eq!(PowerLevel::DbmNeg14_7.pa_level(), 1);
+    assert!(!PowerLevel::DbmNeg14_7.is_high_power());
+    assert!(PowerLevel::Dbm8_0.is_high_power());
+    assert!(PowerLevel::ALL
+        .windows(2)
+        .all(|pair| pair[0].dbm() < pair[1].dbm()));
+
+    assert_eq!(PowerLevel::nearest(-10.0), PowerLevel::DbmNeg11_4);
+    assert_eq!(PowerLevel::nearest(0.0), PowerLevel::Dbm1_4);
+    assert_eq!(PowerLevel::nearest(-40.0), PowerLevel::DbmNeg18);
+    assert_eq!(PowerLevel::nearest(20.0), PowerLevel::Dbm8_0);
+
+    assert_eq!(
+        PowerLevel::lowest_within(-6.0, 0.0),
+        Some(PowerLevel::DbmNeg5_1)
+    );
+    assert_eq!(PowerLevel::lowest_within(6.0, 7.0), None);
+}
+
+// Records the power level and advertising data of the controller.
+struct FakeController {
+    level: Option<PowerLevel>,
+    advertising_data: Vec<u8>,
+    reject: bool,
+    disconnected: bool,
+    response: Option<BNRGParams>,
+}
+
+impl FakeController {
+    fn new() -> FakeController {
+        FakeController {
+            level: None,
+            advertising_data: Vec::new(),
+            reject: false,
+            disconnected: false,
+            response: None,
+        }
+    }
+}
+
+impl CommandWriter for FakeController {
+    type Error = ();
+
+    fn write_command(&mut self, opcode: hci::Opcode, params: &[u8]) -> nb::Result<(), ()> {
+        assert!(self.response.is_none());
+        if self.disconnected {
+            return Err(nb::Error::Other(()));
+        }
+        let status = if self.reject {
+            hci::Status::CommandDisallowed
+        } else {
+            hci::Status::Success
+        };
+        let response = match VendorCommand::new(opcode, params).unwrap() {
+            VendorCommand::HalSetTxPowerLevel(level) => {
+                if !self.reject {
+                    self.level = Some(level);
+                }
+                BNRGParams::HalSetTxPowerLevel(status)
+            }
+            VendorCommand::GapUpdateAdvertisingData(data) => {
+                self.advertising_data = data.to_vec();
+                BNRGParams::GapUpdateAdvertisingData(status)
+            }
+            other => panic!("Unexpected command: {:?}", other),
+        };
+        self.response = Some(response);
+
+        Ok(())
+    }
+}
+
+impl Responder for FakeController {
+    fn response(&mut self) -> Option<Vec<u8>> {
+        self.response
+            .take()
+            .map(|params| encode_command_complete(&params))
+    }
+}
+
+fn apply(control: &mut PowerControl, controller: &mut FakeController) -> Result<(), Error<()>> {
+    drive(
+        control,
+        controller,
+        |control, controller| control.poll(controller),
+        |control, event| control.handle_event(event),
+    )
+}
+
+fn read_rssi(handle: u16, rssi: i8) -> HciEvent<BlueNRGEvent> {
+    let [lo, hi] = handle.to_le_bytes();
+    event(&[0x0E, 7, 1, 0x05, 0x14, 0x00, lo, hi, rssi as u8])
+}
+
+#[test]
+fn set_dbm_updates_advertising_data() {
+    let mut controller = FakeController::new();
+    let mut control = PowerControl::new(PowerLevel::DbmNeg2_1);
+    control.sync_advertising_data(true);
+
+    assert_eq!(control.set_dbm(-15.0), PowerLevel::DbmNeg15);
+    assert!(!control.is_idle());
+    apply(&mut control, &mut controller).unwrap();
+
+    assert!(control.is_idle());
+    assert_eq!(control.level(), PowerLevel::DbmNeg15);
+    assert_eq!(controller.level, Some(PowerLevel::DbmNeg15));
+    assert_eq!(controller.advertising_data, [2, 0x0A, -15i8 as u8]);
+
+    // The current level is not applied again.
+    controller.level = None;
+    control.set_level(PowerLevel::DbmNeg15);
+    apply(&mut control, &mut controller).unwrap();
+    assert_eq!(controller.level, None);
+}
+
+#[test]
+fn set_dbm_range() {
+    let mut control = PowerControl::new(PowerLevel::Dbm8_0);
+    assert_eq!(control.set_dbm_range(-10.0, -5.0), PowerLevel::DbmNeg8_4);
+    assert_eq!(control.set_dbm_range(2.0, 4.0), PowerLevel::Dbm1_7);
+    assert_eq!(control.set_dbm_range(2.0, 4.5), PowerLevel::Dbm4_7);
+    assert_eq!(control.set_dbm_range(10.0, 20.0), PowerLevel::Dbm8_0);
+    assert_eq!(control.set_dbm_range(-30.0, -20.0), PowerLevel::DbmNeg18);
+}
+
+#[test]
+fn rssi_policy_follows_weakest_connection() {
+    let mut controller = FakeController::new();
+    let mut control = PowerControl::new(PowerLevel::Dbm8_0);
+    control.set_rssi_policy(Some(RssiPolicy {
+        target_rssi: -70,
+        hysteresis: 3,
+        min_level: PowerLevel::DbmNeg15,
+        max_level: PowerLevel::Dbm8_0,
+    }));
+
+    // Both connections are strong, but the weaker one is only 10 dB above the target, so the
+    // level is lowered from 8 dBm to just above -2 dBm.
+    control.handle_event(&read_rssi(0x0801, -40));
+    control.handle_event(&read_rssi(0x0802, -60));
+    apply(&mut control, &mut controller).unwrap();
+    assert_eq!(control.level(), PowerLevel::DbmNeg1_6);
+
+    // Within the hysteresis, the level does not change.
+    control.handle_event(&read_rssi(0x0802, -72));
+    assert!(control.is_idle());
+
+    // The weak connection goes away, and the level is limited by the policy.
+    control.handle_event(&read_rssi(0x0801, -30));
+    control.handle_event(&event(&[0x05, 4, 0x00, 0x02, 0x08, 0x13]));
+    apply(&mut control, &mut controller).unwrap();
+    assert_eq!(control.level(), PowerLevel::DbmNeg15);
+
+    // Unavailable RSSI is ignored.
+    control.observe_rssi(ConnectionHandle(0x0801), 127);
+    assert!(control.is_idle());
+    control.observe_rssi(ConnectionHandle(0x0801), -95);
+    apply(&mut control, &mut controller).unwrap();
+    assert_eq!(control.level(), PowerLevel::Dbm8_0);
+}
+
+#[test]
+fn rejected_level() {
+    let mut controller = FakeController::new();
+    controller.reject = true;
+    let mut control = PowerControl::new(PowerLevel::Dbm8_0);
+    control.set_level(PowerLevel::DbmNeg18);
+    assert_eq!(
+        apply(&mut control, &mut controller),
+        Err(Error::CommandFailed(
+            hci::Opcode(0xFC0F),
+            hci::Status::CommandDisallowed
+        ))
+    );
+    assert_eq!(control.level(), PowerLevel::Dbm8_0);
+    assert_eq!(apply(&mut control, &mut controller), Ok(()));
+}
+
+#[test]
+fn communication_failure() {
+    let mut controller = FakeController::new();
+    controller.disconnected = true;
+    let mut control = PowerControl::new(PowerLevel::Dbm8_0);
+    control.set_level(PowerLevel::DbmNeg18);
+    assert_eq!(
+        apply(&mut control, &mut controller),
+        Err(Error::Hal(bluenrg::hal::Error::Comm(())))
+    );
+}
@@ -0,0 +1,228 @@
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::decode::VendorCommand;
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::command::UpdaterBufferSize;
+use bluenrg::event::BlueNRGEvent;
+use bluenrg::updater::*;
+use fixture::updater::FakeUpdater;
+use fixture::{drive, encode_command_complete, event, record, Responder};
+use hci::event::command::ReturnParameters as HciParams;
+use hci::event::Event as HciEvent;
+
+#[test]
+fn commands() {
+    let written = record(|writer| {
+        writer.updater_start().unwrap();
+        writer.get_updater_buffer_size().unwrap();
+        writer.erase_sector(0x1001_0800).unwrap();
+        writer.program_data_block(0x1001_0800, &[1, 2, 3]).unwrap();
+        writer.read_data_block(0x1001_0800, 16).unwrap();
+        writer.calculate_crc(0x1001_0800, 2).unwrap();
+        writer.reset_blue_flag().unwrap();
+    });
+    assert_eq!(
+        written,
+        [
+            (0xFC20, vec![]),
+            (0xFC23, vec![]),
+            (0xFC26, vec![0x00, 0x08, 0x01, 0x10]),
+            (0xFC27, vec![0x00, 0x08, 0x01, 0x10, 3, 0, 1, 2, 3]),
+            (0xFC28, vec![0x00, 0x08, 0x01, 0x10, 16, 0]),
+            (0xFC29, vec![0x00, 0x08, 0x01, 0x10, 2]),
+            (0xFC25, vec![]),
+        ]
+    );
+}
+
+#[test]
+fn data_too_long() {
+    let written = record(|writer| {
+        assert_eq!(
+            writer.program_data_block(0x1001_0800, &[0; 250]),
+            Err(nb::Error::Other(Error::DataTooLong(250)))
+        );
+        assert_eq!(
+            writer.read_data_block(0x1001_0800, 250),
+            Err(nb::Error::Other(Error::DataTooLong(250)))
+        );
+    });
+    assert!(written.is_empty());
+}
+
+#[test]
+fn decode_commands() {
+    match VendorCommand::new(hci::Opcode(0xFC27), &[0x00, 0x08, 0x01, 0x10, 2, 0, 5, 6]) {
+        Ok(VendorCommand::UpdaterProgramDataBlock { address, data }) => {
+            assert_eq!(address, 0x1001_0800);
+            assert_eq!(data, [5, 6]);
+        }
+        other => panic!("Did not get program data block: {:?}", other),
+    }
+    match VendorCommand::new(hci::Opcode(0xFC29), &[0x00, 0x08, 0x01, 0x10, 3]) {
+        Ok(VendorCommand::UpdaterCalculateCrc { address, sectors }) => {
+            assert_eq!(address, 0x1001_0800);
+            assert_eq!(sectors, 3);
+        }
+        other => panic!("Did not get calculate CRC: {:?}", other),
+    }
+    assert_eq!(
+        VendorCommand::new(hci::Opcode(0xFC27), &[0x00, 0x08, 0x01, 0x10, 3, 0, 5, 6]).err(),
+        Some(bluenrg::decode::Error::BadLength(8, 9))
+    );
+}
+
+fn vendor_params(event: HciEvent<BlueNRGEvent>) -> BNRGParams {
+    match event {
+        HciEvent::CommandComplete(event) => match event.return_params {
+            HciParams::Vendor(params) => params,
+            other => panic!("Wrong return parameters: {:?}", other),
+        },
+        other => panic!("Did not get command complete event: {:?}", other),
+    }
+}
+
+#[test]
+fn return_parameters() {
+    let buffer = [0x0E, 8, 1, 0x29, 0xFC, 0, 0x78, 0x56, 0x34, 0x12];
+    match vendor_params(event(&buffer)) {
+        BNRGParams::UpdaterCalculateCrc(params) => {
+            assert_eq!(params.status, hci::Status::Success);
+            assert_eq!(params.crc, 0x1234_5678);
+        }
+        other => panic!("Wrong return parameters: {:?}", other),
+    }
+
+    let buffer = [0x0E, 7, 1, 0x28, 0xFC, 0, 1, 2, 3];
+    match vendor_params(event(&buffer)) {
+        BNRGParams::UpdaterReadDataBlock(params) => {
+            assert_eq!(params.status, hci::Status::Success);
+            assert_eq!(params.data(), [1, 2, 3]);
+        }
+        other => panic!("Wrong return parameters: {:?}", other),
+    }
+
+    let buffer = [0x0E, 5, 1, 0x23, 0xFC, 0, 0xFA];
+    match vendor_params(event(&buffer)) {
+        BNRGParams::UpdaterGetBufferSize(params) => assert_eq!(params.buffer_size, 250),
+        other => panic!("Wrong return parameters: {:?}", other),
+    }
+}
+
+#[test]
+fn crc_matches_updater() {
+    assert_eq!(crc(&[1, 2, 3, 4, 5, 6, 7, 8]), 0xCA10_A083);
+    assert_eq!(crc(&[1, 2, 3]), 0x293A_D64A);
+    assert_eq!(crc(&[1, 2, 3]), crc(&[1, 2, 3, 0xFF]));
+}
+
+fn flash(updater: &mut FakeUpdater, image: &[u8]) -> Result<(), FlashError<()>> {
+    let mut flasher = Flasher::new(image).unwrap();
+    drive(
+        &mut flasher,
+        updater,
+        |flasher, updater| flasher.poll(updater),
+        |flasher, event| flasher.handle_event(event),
+    )
+}
+
+fn image(len: usize) -> Vec<u8> {
+    (0..len).map(|i| (i * 7 + i / 256) as u8).collect()
+}
+
+#[test]
+fn flash_image() {
+    let image = image(5 * SECTOR_SIZE + 100);
+    let mut updater = FakeUpdater::new();
+    updater.buffer_size = 100;
+    flash(&mut updater, &image).unwrap();
+
+    assert!(updater.blue_flag);
+    assert_eq!(&updater.flash[..SECTOR_SIZE], &[0xFF; SECTOR_SIZE][..]);
+    assert_eq!(
+        &updater.flash[SECTOR_SIZE..image.len()],
+        &image[SECTOR_SIZE..]
+    );
+    assert!(updater.flash[image.len()..].iter().all(|&b| b == 0xFF));
+    assert_eq!(
+        updater.erased_sectors,
+        [
+            0x1001_0800,
+            0x1001_1000,
+            0x1001_1800,
+            0x1001_2000,
+            0x1001_2800
+        ]
+    );
+}
+
+#[test]
+fn resume_skips_programmed_sectors() {
+    let old_image = image(4 * SECTOR_SIZE);
+    let mut new_image = old_image.clone();
+    new_image[3 * SECTOR_SIZE + 10] ^= 0xFF;
+
+    let mut updater = FakeUpdater::new();
+    flash(&mut updater, &old_image).unwrap();
+    updater.erased_sectors.clear();
+
+    // Stale data after the end of the image is erased too.
+    updater.flash[10 * SECTOR_SIZE] = 0;
+    flash(&mut updater, &new_image).unwrap();
+    assert_eq!(updater.erased_sectors, [0x1001_1800, 0x1001_5000]);
+    assert_eq!(
+        &updater.flash[SECTOR_SIZE..new_image.len()],
+        &new_image[SECTOR_SIZE..]
+    );
+    assert!(updater.flash[new_image.len()..].iter().all(|&b| b == 0xFF));
+}
+
+#[test]
+fn verify_failure_leaves_blue_flag_erased() {
+    let image = image(3 * SECTOR_SIZE);
+    let mut updater = FakeUpdater::new();
+    updater.stuck_address = Some(0x1001_1004);
+    assert_eq!(
+        flash(&mut updater, &image),
+        Err(FlashError::VerifyFailed(0x1001_1000))
+    );
+    assert!(!updater.blue_flag);
+}
+
+#[test]
+fn command_failure() {
+    let mut updater = FakeUpdater::new();
+    let image = image(2 * SECTOR_SIZE);
+    let mut flasher = Flasher::new(&image).unwrap();
+    assert_eq!(flasher.poll(&mut updater), Err(nb::Error::WouldBlock));
+    updater.response().unwrap();
+    flasher.handle_event(&event(&encode_command_complete(
+        &BNRGParams::UpdaterGetBufferSize(UpdaterBufferSize {
+            status: hci::Status::HardwareFailure,
+            buffer_size: 0,
+        }),
+    )));
+    assert_eq!(
+        flasher.poll(&mut updater),
+        Err(nb::Error::Other(FlashError::CommandFailed(
+            hci::Opcode(0xFC23),
+            hci::Status::HardwareFailure
+        )))
+    );
+}
+
+#[test]
+fn invalid_image_length() {
+    assert_eq!(
+        Flasher::new(&[0; SECTOR_SIZE]).err(),
+        Some(InvalidImageLength(SECTOR_SIZE))
+    );
+    assert_eq!(
+        Flasher::new(&[0; SECTOR_SIZE + 2]).err(),
+        Some(InvalidImageLength(SECTOR_SIZE + 2))
+    );
+}
@@ -0,0 +1,96 @@
+extern crate bluenrg;
+
+use bluenrg::uuid::*;
+
+const HEART_RATE_128: [u8; 16] = [
+    0xfb, 0x34, 0x9b, 0x5f, 0x80, 0x00, 0x00, 0x80, 0x00, 0x10, 0x00, 0x00, 0x0d, 0x18, 0x00, 0x00,
+];
+
+#[test]
+fn short_uuids_expand_with_base_uuid() {
+    assert_eq!(Uuid::Uuid16(0x180D), Uuid::Uuid128(HEART_RATE_128));
+    assert_eq!(Uuid::Uuid16(0x180D).to_le_bytes(), HEART_RATE_128);
+    assert_eq!(
+        Uuid::from_u32(0x0000_180D).to_u128(),
+        0x0000_180D_0000_1000_8000_0080_5F9B_34FB
+    );
+    assert_eq!(Uuid::Uuid128(HEART_RATE_128).to_u16(), Some(0x180D));
+    assert_ne!(Uuid::Uuid16(0x180D), Uuid::Uuid16(0x180F));
+}
+
+#[test]
+fn custom_uuids_have_no_short_form() {
+    let uuid = Uuid::from_u128(0x6E40_0001_B5A3_F393_E0A9_E50E_24DC_CA9E);
+    assert_eq!(uuid.to_u16(), None);
+    assert_eq!(uuid.to_le_bytes()[15], 0x6E);
+    assert_eq!(uuid.to_le_bytes()[0], 0x9E);
+}
+
+#[test]
+fn parse() {
+    assert_eq!(
+        "0000180d-0000-1000-8000-00805f9b34fb".parse(),
+        Ok(service::HEART_RATE)
+    );
+    assert_eq!(
+        "6E400001-B5A3-F393-E0A9-E50E24DCCA9E".parse(),
+        Ok(Uuid::from_u128(0x6E40_0001_B5A3_F393_E0A9_E50E_24DC_CA9E))
+    );
+    assert_eq!("2a37".parse(), Ok(characteristic::HEART_RATE_MEASUREMENT));
+    assert_eq!(
+        "0x2902".parse(),
+        Ok(descriptor::CLIENT_CHARACTERISTIC_CONFIGURATION)
+    );
+    assert_eq!("0000180F".parse(), Ok(service::BATTERY));
+}
+
+#[test]
+fn parse_errors() {
+    assert_eq!("180".parse::<Uuid>(), Err(ParseError::BadLength(3)));
+    assert_eq!(
+        "0x0000180d-0000-1000-8000-00805f9b34fb".parse::<Uuid>(),
+        Err(ParseError::BadLength(38))
+    );
+    assert_eq!("18g0".parse::<Uuid>(), Err(ParseError::BadCharacter(2)));
+    assert_eq!("0x18g0".parse::<Uuid>(), Err(ParseError::BadCharacter(4)));
+    assert_eq!(
+        "0000180d-0000-1000-8000+00805f9b34fb".parse::<Uuid>(),
+        Err(ParseError::BadCharacter(23))
+    );
+}
+
+#[test]
+fn display_round_trips() {
+    let uuid = Uuid::from_u128(0x6E40_0001_B5A3_F393_E0A9_E50E_24DC_CA9E);
+    assert_eq!(uuid.to_string(), "6e400001-b5a3-f393-e0a9-e50e24dcca9e");
+    assert_eq!(uuid.to_string().parse(), Ok(uuid));
+
+    assert_eq!(service::HEART_RATE.to_string(), "180d");
+    assert_eq!(
+        Uuid::Uuid128(HEART_RATE_128).to_string(),
+        "0000180d-0000-1000-8000-00805f9b34fb"
+    );
+}
+
+// The newtypes are deprecated, but still convert to and compare with `Uuid`.
+#[test]
+#[allow(deprecated)]
+fn compares_with_event_and_command_uuids() {
+    assert_eq!(bluenrg::event::Uuid16(0x180D), service::HEART_RATE);
+    assert_eq!(service::HEART_RATE, bluenrg::event::Uuid128(HEART_RATE_128));
+    assert_eq!(bluenrg::gatt::Uuid16(0x2803), declaration::CHARACTERISTIC);
+    assert_eq!(
+        Uuid::from(bluenrg::event::Uuid128(HEART_RATE_128)),
+        Uuid::from(0x180Du16)
+    );
+    assert_eq!(bluenrg::gatt::Uuid::Uuid16(0x180D), service::HEART_RATE);
+}
+
+#[test]
+fn hash_matches_equality() {
+    use std::collections::HashSet;
+
+    let mut set = HashSet::new();
+    set.insert(service::HEART_RATE);
+    assert!(set.contains(&Uuid::Uuid128(HEART_RATE_128)));
+}
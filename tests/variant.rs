@@ -0,0 +1,282 @@
+#![cfg(feature = "ms")]
+
+extern crate bluenrg;
+extern crate bluetooth_hci as hci;
+extern crate nb;
+
+mod fixture;
+
+use bluenrg::decode::VendorCommand;
+use bluenrg::event::command::ReturnParameters as BNRGParams;
+use bluenrg::event::BlueNRGEvent;
+use bluenrg::gap::Commands as GapCommands;
+use bluenrg::Variant;
+use fixture::record;
+use hci::event::command::ReturnParameters as HciParams;
+use hci::event::{Event as HciEvent, Packet, VendorEvent};
+use std::sync::{Mutex, MutexGuard};
+use std::time::Duration;
+
+// The variant is global, so tests that select it must not run at the same time.
+static VARIANT_LOCK: Mutex<()> = Mutex::new(());
+
+struct SelectedVariant(#[allow(dead_code)] MutexGuard<'static, ()>);
+
+impl SelectedVariant {
+    fn new(variant: Variant) -> SelectedVariant {
+        let guard = VARIANT_LOCK.lock().unwrap_or_else(|e| e.into_inner());
+        bluenrg::set_variant(variant);
+        SelectedVariant(guard)
+    }
+}
+
+impl Drop for SelectedVariant {
+    fn drop(&mut self) {
+        bluenrg::set_variant(Variant::BlueNRGMS);
+    }
+}
+
+#[cfg(not(feature = "bluenrg-1-2"))]
+#[test]
+fn variant_from_version() {
+    let version = |major| bluenrg::Version {
+        hw_version: 0x31,
+        major,
+        minor: 1,
+        patch: 0,
+    };
+    assert_eq!(version(6).variant(), Variant::BlueNRG);
+    assert_eq!(version(7).variant(), Variant::BlueNRGMS);
+}
+
+#[test]
+fn bluenrg_commands_omit_ms_parameters() {
+    let _variant = SelectedVariant::new(Variant::BlueNRG);
+    let written = record(|writer| {
+        writer
+            .init(bluenrg::gap::Role::PERIPHERAL, true, 20)
+            .unwrap();
+        writer
+            .set_nonconnectable(
+                hci::types::AdvertisingType::ScannableUndirected,
+                bluenrg::gap::AddressType::Public,
+            )
+            .unwrap();
+        writer.allow_rebond(hci::ConnectionHandle(0x0201)).unwrap();
+    });
+    assert_eq!(
+        written,
+        [(0xFC8A, vec![0x01]), (0xFC8B, vec![0x02]), (0xFC95, vec![])]
+    );
+}
+
+#[test]
+fn ms_commands_include_ms_parameters() {
+    let _variant = SelectedVariant::new(Variant::BlueNRGMS);
+    let written = record(|writer| {
+        writer
+            .init(bluenrg::gap::Role::PERIPHERAL, true, 20)
+            .unwrap();
+        writer.allow_rebond(hci::ConnectionHandle(0x0201)).unwrap();
+    });
+    assert_eq!(
+        written,
+        [(0xFC8A, vec![0x01, 0x01, 20]), (0xFC95, vec![0x01, 0x02])]
+    );
+}
+
+#[test]
+fn bluenrg_connection_parameters() {
+    let _variant = SelectedVariant::new(Variant::BlueNRG);
+    let written = record(|writer| {
+        writer
+            .set_direct_connectable(&bluenrg::gap::DirectConnectableParameters {
+                own_address_type: hci::host::OwnAddressType::Public,
+                advertising_type: hci::types::AdvertisingType::ConnectableDirectedHighDutyCycle,
+                initiator_address: hci::BdAddrType::Public(hci::BdAddr([1, 2, 3, 4, 5, 6])),
+                advertising_interval: (Duration::from_millis(20), Duration::from_millis(30)),
+            })
+            .unwrap();
+        writer
+            .start_general_connection_establishment(
+                &bluenrg::gap::GeneralConnectionEstablishmentParameters {
+                    scan_window: hci::types::ScanWindow::start_every(Duration::from_millis(10))
+                        .unwrap()
+                        .open_for(Duration::from_millis(5))
+                        .unwrap(),
+                    own_address_type: hci::host::OwnAddressType::Public,
+                    filter_duplicates: true,
+                    reconnection_address: Some(hci::BdAddr([7, 8, 9, 10, 11, 12])),
+                },
+            )
+            .unwrap();
+    });
+    assert_eq!(
+        written,
+        [
+            (0xFC84, vec![0x00, 0x00, 1, 2, 3, 4, 5, 6]),
+            (
+                0xFC9A,
+                vec![0x10, 0x00, 0x08, 0x00, 0x00, 0x01, 1, 7, 8, 9, 10, 11, 12]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn bluenrg_refuses_ms_only_commands() {
+    let _variant = SelectedVariant::new(Variant::BlueNRG);
+    let written = record(|writer| {
+        assert_eq!(
+            writer.start_observation_procedure(&bluenrg::gap::ObservationProcedureParameters {
+                scan_window: hci::types::ScanWindow::start_every(Duration::from_millis(10))
+                    .unwrap()
+                    .open_for(Duration::from_millis(5))
+                    .unwrap(),
+                scan_type: hci::host::ScanType::Passive,
+                own_address_type: bluenrg::gap::AddressType::Public,
+                filter_duplicates: false,
+            }),
+            Err(nb::Error::Other(bluenrg::gap::Error::UnsupportedByVariant))
+        );
+        assert_eq!(
+            bluenrg::gatt::Commands::read_handle_value_offset(
+                writer,
+                bluenrg::gatt::CharacteristicHandle(0x0201),
+                0
+            ),
+            Err(nb::Error::Other(bluenrg::gatt::Error::UnsupportedByVariant))
+        );
+    });
+    assert_eq!(written, []);
+
+    let error: bluenrg::CommandError<()> = bluenrg::gatt::Error::UnsupportedByVariant.into();
+    assert_eq!(
+        error,
+        bluenrg::CommandError::Validation(bluenrg::ValidationError::UnsupportedByVariant)
+    );
+}
+
+#[test]
+fn bluenrg_commands_round_trip() {
+    let _variant = SelectedVariant::new(Variant::BlueNRG);
+    let written = record(|writer| {
+        match VendorCommand::new(hci::Opcode(0xFC8A), &[0x01]) {
+            Ok(VendorCommand::GapInit {
+                role,
+                privacy_enabled,
+                dev_name_characteristic_len,
+            }) => {
+                assert_eq!(role, bluenrg::gap::Role::PERIPHERAL);
+                assert!(!privacy_enabled);
+                assert_eq!(dev_name_characteristic_len, 0);
+                writer
+                    .init(role, privacy_enabled, dev_name_characteristic_len)
+                    .unwrap();
+            }
+            other => panic!("Did not get GAP Init: {:?}", other),
+        }
+        match VendorCommand::new(hci::Opcode(0xFC8B), &[0x02]) {
+            Ok(VendorCommand::GapSetNonConnectable {
+                advertising_type,
+                address_type,
+            }) => {
+                assert_eq!(
+                    advertising_type,
+                    hci::types::AdvertisingType::ScannableUndirected
+                );
+                assert_eq!(address_type, bluenrg::gap::AddressType::Public);
+                writer
+                    .set_nonconnectable(advertising_type, address_type)
+                    .unwrap();
+            }
+            other => panic!("Did not get GAP Set Nonconnectable: {:?}", other),
+        }
+        match VendorCommand::new(hci::Opcode(0xFC95), &[]) {
+            Ok(VendorCommand::GapAllowRebond(conn_handle)) => {
+                writer.allow_rebond(conn_handle).unwrap();
+            }
+            other => panic!("Did not get GAP Allow Rebond: {:?}", other),
+        }
+        match VendorCommand::new(
+            hci::Opcode(0xFC9A),
+            &[0x10, 0x00, 0x08, 0x00, 0x00, 0x01, 1, 7, 8, 9, 10, 11, 12],
+        ) {
+            Ok(VendorCommand::GapStartGeneralConnectionEstablishment(params)) => {
+                assert_eq!(
+                    params.reconnection_address,
+                    Some(hci::BdAddr([7, 8, 9, 10, 11, 12]))
+                );
+                writer
+                    .start_general_connection_establishment(&params)
+                    .unwrap();
+            }
+            other => panic!(
+                "Did not get GAP Start General Connection Establishment: {:?}",
+                other
+            ),
+        }
+    });
+    assert_eq!(
+        written,
+        [
+            (0xFC8A, vec![0x01]),
+            (0xFC8B, vec![0x02]),
+            (0xFC95, vec![]),
+            (
+                0xFC9A,
+                vec![0x10, 0x00, 0x08, 0x00, 0x00, 0x01, 1, 7, 8, 9, 10, 11, 12]
+            ),
+        ]
+    );
+}
+
+#[test]
+fn bluenrg_does_not_decode_ms_only_commands() {
+    let _variant = SelectedVariant::new(Variant::BlueNRG);
+    assert_eq!(
+        VendorCommand::new(hci::Opcode(0xFD2B), &[0x01, 0x02, 0x00]).err(),
+        Some(bluenrg::decode::Error::UnknownOpcode(hci::Opcode(0xFD2B)))
+    );
+    assert_eq!(
+        VendorCommand::new(hci::Opcode(0xFC8A), &[0x01, 0x01, 20]).err(),
+        Some(bluenrg::decode::Error::BadLength(3, 1))
+    );
+}
+
+#[test]
+fn event_0x0408_depends_on_variant() {
+    let buffer = [0x08, 0x04, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06];
+
+    let _variant = SelectedVariant::new(Variant::BlueNRG);
+    match BlueNRGEvent::new(&buffer) {
+        Ok(BlueNRGEvent::GapReconnectionAddress(addr)) => {
+            assert_eq!(addr, hci::BdAddr([1, 2, 3, 4, 5, 6]))
+        }
+        other => panic!("Did not get reconnection address event: {:?}", other),
+    }
+
+    bluenrg::set_variant(Variant::BlueNRGMS);
+    match BlueNRGEvent::new(&buffer[..4]) {
+        Ok(BlueNRGEvent::GapAddressNotResolved(conn_handle)) => {
+            assert_eq!(conn_handle, hci::ConnectionHandle(0x0201))
+        }
+        other => panic!("Did not get address not resolved event: {:?}", other),
+    }
+}
+
+#[test]
+fn bluenrg_resolve_private_address_returns_status_only() {
+    let _variant = SelectedVariant::new(Variant::BlueNRG);
+    let buffer = [0x0E, 4, 1, 0xA0, 0xFC, 0];
+    match HciEvent::<BlueNRGEvent>::new(Packet(&buffer)) {
+        Ok(HciEvent::CommandComplete(event)) => match event.return_params {
+            HciParams::Vendor(BNRGParams::GapResolvePrivateAddress(params)) => {
+                assert_eq!(params.status, hci::Status::Success);
+                assert_eq!(params.bd_addr, None);
+            }
+            other => panic!("Wrong return parameters: {:?}", other),
+        },
+        other => panic!("Did not get command complete event: {:?}", other),
+    }
+}